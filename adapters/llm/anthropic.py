@@ -5,11 +5,15 @@ from typing import Any, AsyncIterator, Dict, List, Optional
 import httpx
 
 from reliapi.adapters.llm.base import LLMAdapter
+from reliapi.core.llm_normalize import normalize_finish_reason
 
 
 class AnthropicAdapter(LLMAdapter):
     """Anthropic Claude API adapter."""
     
+    # Messages API returns content blocks, not choices
+    response_field = "content"
+    
     # Pricing per 1M tokens (as of 2024)
     PRICING = {
         "claude-3-opus-20240229": {"prompt": 15.0, "completion": 75.0},
@@ -18,6 +22,16 @@ class AnthropicAdapter(LLMAdapter):
         "claude-3-5-sonnet-20241022": {"prompt": 3.0, "completion": 15.0},
     }
     
+    # Prompt caching: reads billed at 10% of input price, writes at 125%
+    CACHED_PROMPT_PRICE_RATIO = 0.1
+    CACHE_WRITE_PRICE_RATIO = 1.25
+    
+    # Marker for the end of the cacheable prefix
+    CACHE_CONTROL = {"type": "ephemeral"}
+    
+    def api_path(self, model: str) -> str:
+        return "/messages"
+    
     def prepare_request(
         self,
         messages: List[Dict[str, str]],
@@ -29,12 +43,19 @@ class AnthropicAdapter(LLMAdapter):
         stream: bool = False,
         **kwargs,
     ) -> Dict[str, Any]:
-        """Prepare Anthropic request payload."""
+        """Prepare Anthropic request payload.
+        
+        System messages are not allowed in Anthropic `messages`; they are
+        joined, in order, into the top-level `system` param.
+        """
+        system = [m["content"] for m in messages if m.get("role") == "system"]
         payload = {
             "model": model,
-            "messages": messages,
+            "messages": [m for m in messages if m.get("role") != "system"],
             "max_tokens": max_tokens or 1024,
         }
+        if system:
+            payload["system"] = "\n\n".join(system)
         
         if temperature is not None:
             payload["temperature"] = temperature
@@ -68,6 +89,10 @@ class AnthropicAdapter(LLMAdapter):
         - content_block_stop: End of content block
         - message_delta: Message-level deltas (usage, etc.)
         - message_stop: End of message
+        
+        They are translated to OpenAI chunks: a role delta, text deltas,
+        a finish_reason chunk, then one usage chunk with input and output
+        tokens combined.
         """
         url = f"{base_url.rstrip('/')}{api_path}"
         
@@ -88,6 +113,9 @@ class AnthropicAdapter(LLMAdapter):
                 )
             
             current_event_type = None
+            message_id = None
+            model = payload.get("model")
+            usage: Dict[str, int] = {}
             
             async for line in response.aiter_lines():
                 if not line.strip():
@@ -100,45 +128,38 @@ class AnthropicAdapter(LLMAdapter):
                     data_str = line[6:]  # Remove "data: " prefix
                     try:
                         chunk_data = json.loads(data_str)
-                        
-                        # Yield content_block_delta events (text chunks)
-                        if current_event_type == "content_block_delta":
-                            delta = chunk_data.get("delta", {})
-                            text = delta.get("text", "")
-                            if text:
-                                # Yield in OpenAI-like format for compatibility
-                                yield {
-                                    "choices": [{
-                                        "delta": {"content": text},
-                                        "finish_reason": None,
-                                    }]
-                                }
-                        
-                        # Yield message_delta for usage information
-                        elif current_event_type == "message_delta":
-                            usage = chunk_data.get("usage", {})
-                            if usage:
-                                # Yield usage in OpenAI-like format
-                                yield {
-                                    "_usage_only": True,
-                                    "usage": {
-                                        "prompt_tokens": usage.get("input_tokens", 0),
-                                        "completion_tokens": usage.get("output_tokens", 0),
-                                    }
-                                }
-                        
-                        # Yield message_stop for finish reason
-                        elif current_event_type == "message_stop":
-                            stop_reason = chunk_data.get("stop_reason", "stop")
-                            # Yield final chunk with finish reason
-                            yield {
-                                "choices": [{
-                                    "delta": {},
-                                    "finish_reason": stop_reason,
-                                }]
-                            }
                     except json.JSONDecodeError:
                         continue
+                    
+                    # message_start: id, model and input usage (including prompt cache reads/writes)
+                    if current_event_type == "message_start":
+                        message = chunk_data.get("message", {})
+                        message_id = message.get("id")
+                        model = message.get("model") or model
+                        if message.get("usage"):
+                            usage = self._normalize_usage(message["usage"])
+                        yield self.stream_chunk(message_id, model, {"role": "assistant", "content": ""})
+                    
+                    # content_block_delta: text chunks
+                    elif current_event_type == "content_block_delta":
+                        text = chunk_data.get("delta", {}).get("text", "")
+                        if text:
+                            yield self.stream_chunk(message_id, model, {"content": text})
+                    
+                    # message_delta: stop reason and output usage
+                    elif current_event_type == "message_delta":
+                        if chunk_data.get("usage"):
+                            usage["completion_tokens"] = chunk_data["usage"].get("output_tokens", 0)
+                        stop_reason = chunk_data.get("delta", {}).get("stop_reason")
+                        if stop_reason:
+                            yield self.stream_chunk(
+                                message_id, model, {}, normalize_finish_reason(stop_reason),
+                            )
+                    
+                    # message_stop: end of message, usage sent as one terminal chunk
+                    elif current_event_type == "message_stop":
+                        if usage:
+                            yield self.usage_chunk(message_id, model, usage)
     
     def parse_response(self, response: Dict[str, Any]) -> Dict[str, Any]:
         """Parse Anthropic response to normalized format."""
@@ -161,18 +182,68 @@ class AnthropicAdapter(LLMAdapter):
             "finish_reason": response.get("stop_reason", "stop"),
         }
     
-    def get_cost_usd(
+    def error_codes(self, response: Dict[str, Any]) -> List[str]:
+        """Anthropic errors are {"type": "error", "error": {"type": "overloaded_error", ...}}."""
+        error = response.get("error")
+        if response.get("type") != "error" or not isinstance(error, dict) or not error.get("type"):
+            return []
+        return [str(error["type"])]
+    
+    def parse_usage(self, response: Dict[str, Any]) -> Dict[str, int]:
+        """Extract normalized usage (Anthropic reports cache reads/writes separately)."""
+        return self._normalize_usage(response.get("usage") or {})
+    
+    @staticmethod
+    def _normalize_usage(usage: Dict[str, Any]) -> Dict[str, int]:
+        cached_prompt_tokens = usage.get("cache_read_input_tokens") or 0
+        cache_write_tokens = usage.get("cache_creation_input_tokens") or 0
+        return {
+            "prompt_tokens": (usage.get("input_tokens") or 0) + cached_prompt_tokens + cache_write_tokens,
+            "completion_tokens": usage.get("output_tokens") or 0,
+            "cached_prompt_tokens": cached_prompt_tokens,
+            "cache_write_tokens": cache_write_tokens,
+        }
+    
+    def apply_prompt_caching(self, payload: Dict[str, Any]) -> Dict[str, Any]:
+        """Mark everything before the latest message as the cacheable prefix.
+        
+        The marker goes on the second-to-last message, so each turn reads the
+        previous turns from cache and only the new message is billed in full.
+        """
+        messages = payload.get("messages") or []
+        if len(messages) < 2:
+            return payload
+        
+        prefix_end = dict(messages[-2])
+        content = prefix_end.get("content", "")
+        if isinstance(content, str):
+            content = [{"type": "text", "text": content}]
+        else:
+            content = [dict(block) for block in content]
+        content[-1]["cache_control"] = self.CACHE_CONTROL
+        prefix_end["content"] = content
+        
+        return {**payload, "messages": [*messages[:-2], prefix_end, messages[-1]]}
+    
+    def get_cost_breakdown(
         self,
         model: str,
         prompt_tokens: int,
         completion_tokens: int,
-    ) -> Optional[float]:
-        """Calculate cost in USD."""
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
+    ) -> Optional[Dict[str, float]]:
+        """Calculate input and output cost in USD."""
         pricing = self.PRICING.get(model)
         if not pricing:
             return None
         
-        prompt_cost = (prompt_tokens / 1_000_000) * pricing["prompt"]
+        uncached_tokens = prompt_tokens - cached_prompt_tokens - cache_write_tokens
+        prompt_cost = (
+            uncached_tokens
+            + cached_prompt_tokens * self.CACHED_PROMPT_PRICE_RATIO
+            + cache_write_tokens * self.CACHE_WRITE_PRICE_RATIO
+        ) / 1_000_000 * pricing["prompt"]
         completion_cost = (completion_tokens / 1_000_000) * pricing["completion"]
-        return prompt_cost + completion_cost
+        return {"input_usd": prompt_cost, "output_usd": completion_cost}
 
@@ -1,4 +1,5 @@
 """Base LLM adapter interface."""
+import time
 from abc import ABC, abstractmethod
 from typing import Any, AsyncIterator, Dict, List, Optional
 
@@ -6,6 +7,23 @@ from typing import Any, AsyncIterator, Dict, List, Optional
 class LLMAdapter(ABC):
     """Base class for LLM provider adapters."""
     
+    # Top-level field every successful provider response contains
+    response_field = "choices"
+    
+    # Optional request parameters the provider accepts; others are rejected, not dropped
+    optional_params: frozenset = frozenset()
+    
+    def api_path(self, model: str) -> str:
+        """Chat endpoint path under the target base_url."""
+        return "/chat/completions"
+    
+    def unsupported_params(self, **params: Any) -> List[str]:
+        """Names of the given (non-None) parameters this provider does not support."""
+        return sorted(
+            name for name, value in params.items()
+            if value is not None and name not in self.optional_params
+        )
+    
     @abstractmethod
     def prepare_request(
         self,
@@ -30,21 +48,104 @@ class LLMAdapter(ABC):
             "content": str,           # Text content (required)
             "role": str,              # "assistant" (required, default "assistant")
             "finish_reason": str,     # "stop", "length", "error", etc. (required)
+            "choices": list,          # Every returned choice, same keys plus "index" (optional, for n > 1)
         }
         
         All adapters must return the same structure for consistency.
         """
         pass
     
+    @staticmethod
+    def parse_choices(response: Dict[str, Any]) -> List[Dict[str, Any]]:
+        """Normalize OpenAI-format `choices`, dropping ones that failed.
+        
+        A choice failed if it has no message or finished with "error";
+        with `n > 1` the remaining choices are still served. `logprobs`
+        is kept as returned when the request asked for it.
+        """
+        choices = []
+        for position, choice in enumerate(response.get("choices", [])):
+            if not choice.get("message") or choice.get("finish_reason") == "error":
+                continue
+            parsed = {
+                "index": choice.get("index", position),
+                "content": choice["message"].get("content") or "",
+                "role": choice["message"].get("role", "assistant"),
+                "finish_reason": choice.get("finish_reason", "stop"),
+            }
+            if choice.get("logprobs") is not None:
+                parsed["logprobs"] = choice["logprobs"]
+            choices.append(parsed)
+        return choices
+    
     @abstractmethod
+    def get_cost_breakdown(
+        self,
+        model: str,
+        prompt_tokens: int,
+        completion_tokens: int,
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
+    ) -> Optional[Dict[str, float]]:
+        """Calculate cost in USD split into {"input_usd", "output_usd"} (if available).
+        
+        prompt_tokens includes cached_prompt_tokens and cache_write_tokens,
+        which providers bill at a discount or premium respectively.
+        """
+        pass
+    
     def get_cost_usd(
         self,
         model: str,
         prompt_tokens: int,
         completion_tokens: int,
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
     ) -> Optional[float]:
-        """Calculate cost in USD (if available)."""
-        pass
+        """Calculate total cost in USD (if available)."""
+        breakdown = self.get_cost_breakdown(
+            model, prompt_tokens, completion_tokens, cached_prompt_tokens, cache_write_tokens,
+        )
+        return breakdown["input_usd"] + breakdown["output_usd"] if breakdown else None
+    
+    def parse_usage(self, response: Dict[str, Any]) -> Dict[str, int]:
+        """Extract normalized token usage from a provider response.
+        
+        Returns:
+        {
+            "prompt_tokens": int,         # All input tokens (cached included)
+            "completion_tokens": int,
+            "cached_prompt_tokens": int,  # Input tokens read from provider prompt cache
+            "cache_write_tokens": int,    # Input tokens written to provider prompt cache
+        }
+        
+        Default reads the OpenAI usage format.
+        """
+        usage = response.get("usage") or {}
+        details = usage.get("prompt_tokens_details") or {}
+        return {
+            "prompt_tokens": usage.get("prompt_tokens", 0),
+            "completion_tokens": usage.get("completion_tokens", 0),
+            "cached_prompt_tokens": details.get("cached_tokens", 0),
+            "cache_write_tokens": 0,
+        }
+    
+    def error_codes(self, response: Dict[str, Any]) -> List[str]:
+        """Error codes in a provider error body, matched against retry_on_error_codes.
+        
+        Default reads the OpenAI error format (`error.code`, `error.type`).
+        """
+        error = response.get("error")
+        if not isinstance(error, dict):
+            return []
+        return [str(error[field]) for field in ("code", "type") if error.get(field)]
+    
+    def apply_prompt_caching(self, payload: Dict[str, Any]) -> Dict[str, Any]:
+        """Mark the stable conversation prefix for provider prompt caching.
+        
+        Default: no markers (provider caches automatically or not at all).
+        """
+        return payload
     
     def supports_streaming(self) -> bool:
         """Check if adapter supports streaming.
@@ -63,11 +164,45 @@ class LLMAdapter(ABC):
     ) -> AsyncIterator[Dict[str, Any]]:
         """Stream chat completion from provider.
         
-        Must yield provider-specific chunk dictionaries.
-        Override in subclasses that support streaming.
+        Must yield OpenAI `chat.completion.chunk` dictionaries (see
+        stream_chunk), whatever the provider's own event format, ending
+        with one usage chunk (see usage_chunk) when the provider reports
+        usage. Override in subclasses that support streaming.
         
         Raises:
             NotImplementedError: If streaming is not supported
         """
         raise NotImplementedError("Streaming not supported for this provider")
 
+    
+    @staticmethod
+    def stream_chunk(
+        chunk_id: Optional[str],
+        model: Optional[str],
+        delta: Dict[str, Any],
+        finish_reason: Optional[str] = None,
+        index: int = 0,
+    ) -> Dict[str, Any]:
+        """One OpenAI-style `chat.completion.chunk` with a single choice delta."""
+        return {
+            "id": chunk_id,
+            "object": "chat.completion.chunk",
+            "created": int(time.time()),
+            "model": model,
+            "choices": [{"index": index, "delta": delta, "finish_reason": finish_reason}],
+        }
+    
+    @staticmethod
+    def usage_chunk(chunk_id: Optional[str], model: Optional[str], usage: Dict[str, int]) -> Dict[str, Any]:
+        """Terminal usage chunk (OpenAI `include_usage` shape: no choices), marked `_usage_only`."""
+        prompt_tokens = usage.get("prompt_tokens", 0)
+        completion_tokens = usage.get("completion_tokens", 0)
+        return {
+            "_usage_only": True,
+            "id": chunk_id,
+            "object": "chat.completion.chunk",
+            "created": int(time.time()),
+            "model": model,
+            "choices": [],
+            "usage": {**usage, "total_tokens": prompt_tokens + completion_tokens},
+        }
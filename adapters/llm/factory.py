@@ -3,6 +3,7 @@ from typing import Optional
 
 from reliapi.adapters.llm.anthropic import AnthropicAdapter
 from reliapi.adapters.llm.base import LLMAdapter
+from reliapi.adapters.llm.gemini import GeminiAdapter
 from reliapi.adapters.llm.mistral import MistralAdapter
 from reliapi.adapters.llm.openai import OpenAIAdapter
 
@@ -13,6 +14,7 @@ def get_adapter(provider: str) -> Optional[LLMAdapter]:
         "openai": OpenAIAdapter(),
         "anthropic": AnthropicAdapter(),
         "mistral": MistralAdapter(),
+        "gemini": GeminiAdapter(),
     }
     return adapters.get(provider.lower())
 
@@ -26,5 +28,7 @@ def detect_provider(base_url: str) -> Optional[str]:
         return "anthropic"
     elif "mistral.ai" in base_url_lower:
         return "mistral"
+    elif "generativelanguage.googleapis.com" in base_url_lower:
+        return "gemini"
     return None
 
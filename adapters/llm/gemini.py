@@ -0,0 +1,223 @@
+"""Google Gemini LLM adapter."""
+import json
+from typing import Any, AsyncIterator, Dict, List, Optional
+
+import httpx
+
+from reliapi.adapters.llm.base import LLMAdapter
+from reliapi.core.llm_normalize import normalize_finish_reason
+
+
+class GeminiAdapter(LLMAdapter):
+    """Google Gemini API (generateContent) adapter."""
+
+    # generateContent returns candidates, not choices
+    response_field = "candidates"
+
+    # Pricing per 1M tokens (as of 2024, prompts up to 128k tokens)
+    PRICING = {
+        "gemini-1.5-pro": {"prompt": 1.25, "completion": 5.0},
+        "gemini-1.5-flash": {"prompt": 0.075, "completion": 0.3},
+        "gemini-2.0-flash": {"prompt": 0.1, "completion": 0.4},
+    }
+
+    # Context cache reads are billed at 25% of input price
+    CACHED_PROMPT_PRICE_RATIO = 0.25
+
+    # No logit_bias or logprobs; the seed is sent in generationConfig
+    optional_params = frozenset({"seed"})
+
+    def api_path(self, model: str) -> str:
+        return f"/models/{model}:generateContent"
+
+    def prepare_request(
+        self,
+        messages: List[Dict[str, str]],
+        model: str,
+        max_tokens: Optional[int] = None,
+        temperature: Optional[float] = None,
+        top_p: Optional[float] = None,
+        stop: Optional[List[str]] = None,
+        stream: bool = False,
+        **kwargs,
+    ) -> Dict[str, Any]:
+        """Prepare Gemini request payload.
+
+        The model is part of the URL (see api_path), and streaming uses a
+        separate endpoint, so neither is in the payload. System messages
+        go to `systemInstruction`; assistant turns use the "model" role.
+        """
+        system = [m["content"] for m in messages if m.get("role") == "system"]
+        payload: Dict[str, Any] = {
+            "contents": [
+                {
+                    "role": "model" if m.get("role") == "assistant" else "user",
+                    "parts": [{"text": m.get("content") or ""}],
+                }
+                for m in messages if m.get("role") != "system"
+            ],
+        }
+        if system:
+            payload["systemInstruction"] = {"parts": [{"text": "\n\n".join(system)}]}
+
+        generation_config: Dict[str, Any] = {}
+        if max_tokens is not None:
+            generation_config["maxOutputTokens"] = max_tokens
+        if temperature is not None:
+            generation_config["temperature"] = temperature
+        if top_p is not None:
+            generation_config["topP"] = top_p
+        if stop is not None:
+            generation_config["stopSequences"] = stop
+        if kwargs.get("n") and kwargs["n"] > 1:
+            generation_config["candidateCount"] = kwargs["n"]
+        if kwargs.get("seed") is not None:
+            generation_config["seed"] = kwargs["seed"]
+        if (kwargs.get("response_format") or {}).get("type") == "json_object":
+            generation_config["responseMimeType"] = "application/json"
+        if generation_config:
+            payload["generationConfig"] = generation_config
+
+        return payload
+
+    def supports_streaming(self) -> bool:
+        """Gemini supports streaming."""
+        return True
+
+    async def stream_chat(
+        self,
+        client: httpx.AsyncClient,
+        base_url: str,
+        api_path: str,
+        payload: Dict[str, Any],
+        headers: Dict[str, str],
+    ) -> AsyncIterator[Dict[str, Any]]:
+        """Stream chat completion from Gemini.
+
+        streamGenerateContent with `alt=sse` sends "data: {...}" events,
+        each a partial GenerateContentResponse: `candidates[].content.parts`
+        hold the new text, `finishReason` is set on the last one, and
+        `usageMetadata` is cumulative. They are translated to OpenAI
+        chunks: a role delta, text deltas per candidate, finish_reason
+        chunks, then one usage chunk from the last usageMetadata.
+        """
+        stream_path = api_path.replace(":generateContent", ":streamGenerateContent")
+        url = f"{base_url.rstrip('/')}{stream_path}?alt=sse"
+
+        async with client.stream(
+            "POST",
+            url,
+            json=payload,
+            headers=headers,
+            timeout=60.0,
+        ) as response:
+            if response.status_code >= 400:
+                await response.aread()
+                raise httpx.HTTPStatusError(
+                    f"Gemini API error: {response.status_code}",
+                    request=response.request,
+                    response=response,
+                )
+
+            response_id = None
+            model = None
+            usage_metadata: Dict[str, Any] = {}
+
+            async for line in response.aiter_lines():
+                if not line.startswith("data: "):
+                    continue
+                try:
+                    chunk_data = json.loads(line[6:])
+                except json.JSONDecodeError:
+                    continue
+
+                if response_id is None:
+                    response_id = chunk_data.get("responseId")
+                    model = chunk_data.get("modelVersion")
+                    yield self.stream_chunk(response_id, model, {"role": "assistant", "content": ""})
+
+                for position, candidate in enumerate(chunk_data.get("candidates") or []):
+                    index = candidate.get("index", position)
+                    text = self._parts_text(candidate)
+                    if text:
+                        yield self.stream_chunk(response_id, model, {"content": text}, index=index)
+                    if candidate.get("finishReason"):
+                        yield self.stream_chunk(
+                            response_id, model, {},
+                            normalize_finish_reason(candidate["finishReason"]), index=index,
+                        )
+
+                if chunk_data.get("usageMetadata"):
+                    usage_metadata = chunk_data["usageMetadata"]
+
+            if usage_metadata:
+                yield self.usage_chunk(response_id, model, self._normalize_usage(usage_metadata))
+
+    @staticmethod
+    def _parts_text(candidate: Dict[str, Any]) -> str:
+        parts = (candidate.get("content") or {}).get("parts") or []
+        return "".join(part.get("text", "") for part in parts)
+
+    def parse_response(self, response: Dict[str, Any]) -> Dict[str, Any]:
+        """Parse Gemini response to normalized format."""
+        choices = [
+            {
+                "index": candidate.get("index", position),
+                "content": self._parts_text(candidate),
+                "role": (candidate.get("content") or {}).get("role", "model"),
+                "finish_reason": candidate.get("finishReason", "STOP"),
+            }
+            for position, candidate in enumerate(response.get("candidates") or [])
+        ]
+        if not choices:
+            return {
+                "content": "",
+                "finish_reason": "error",
+            }
+
+        return {
+            "content": choices[0]["content"],
+            "role": choices[0]["role"],
+            "finish_reason": choices[0]["finish_reason"],
+            "choices": choices,
+        }
+
+    def error_codes(self, response: Dict[str, Any]) -> List[str]:
+        """Gemini errors are {"error": {"code": 429, "status": "RESOURCE_EXHAUSTED", ...}}."""
+        error = response.get("error")
+        if not isinstance(error, dict) or not error.get("status"):
+            return []
+        return [str(error["status"])]
+
+    def parse_usage(self, response: Dict[str, Any]) -> Dict[str, int]:
+        """Extract normalized usage from usageMetadata."""
+        return self._normalize_usage(response.get("usageMetadata") or {})
+
+    @staticmethod
+    def _normalize_usage(usage: Dict[str, Any]) -> Dict[str, int]:
+        return {
+            "prompt_tokens": usage.get("promptTokenCount") or 0,
+            "completion_tokens": usage.get("candidatesTokenCount") or 0,
+            "cached_prompt_tokens": usage.get("cachedContentTokenCount") or 0,
+            "cache_write_tokens": 0,
+        }
+
+    def get_cost_breakdown(
+        self,
+        model: str,
+        prompt_tokens: int,
+        completion_tokens: int,
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
+    ) -> Optional[Dict[str, float]]:
+        """Calculate input and output cost in USD."""
+        pricing = self.PRICING.get(model)
+        if not pricing:
+            return None
+
+        prompt_cost = (
+            prompt_tokens - cached_prompt_tokens
+            + cached_prompt_tokens * self.CACHED_PROMPT_PRICE_RATIO
+        ) / 1_000_000 * pricing["prompt"]
+        completion_cost = (completion_tokens / 1_000_000) * pricing["completion"]
+        return {"input_usd": prompt_cost, "output_usd": completion_cost}
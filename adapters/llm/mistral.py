@@ -17,6 +17,9 @@ class MistralAdapter(LLMAdapter):
         "mistral-small-latest": {"prompt": 0.2, "completion": 0.6},
     }
     
+    # No logit_bias; the seed is sent as random_seed
+    optional_params = frozenset({"seed"})
+    
     def prepare_request(
         self,
         messages: List[Dict[str, str]],
@@ -44,6 +47,12 @@ class MistralAdapter(LLMAdapter):
             payload["stop"] = stop
         if stream:
             payload["stream"] = True
+        if kwargs.get("response_format"):
+            payload["response_format"] = kwargs["response_format"]
+        if kwargs.get("n") and kwargs["n"] > 1:
+            payload["n"] = kwargs["n"]
+        if kwargs.get("seed") is not None:
+            payload["random_seed"] = kwargs["seed"]
         
         return payload
     
@@ -114,34 +123,41 @@ class MistralAdapter(LLMAdapter):
     
     def parse_response(self, response: Dict[str, Any]) -> Dict[str, Any]:
         """Parse Mistral response to normalized format."""
-        choices = response.get("choices", [])
+        choices = self.parse_choices(response)
         if not choices:
             return {
                 "content": "",
                 "finish_reason": "error",
+                "choices": [],
             }
         
-        choice = choices[0]
-        message = choice.get("message", {})
-        
         return {
-            "content": message.get("content", ""),
-            "role": message.get("role", "assistant"),
-            "finish_reason": choice.get("finish_reason", "stop"),
+            "content": choices[0]["content"],
+            "role": choices[0]["role"],
+            "finish_reason": choices[0]["finish_reason"],
+            "choices": choices,
         }
     
-    def get_cost_usd(
+    def error_codes(self, response: Dict[str, Any]) -> List[str]:
+        """Mistral errors carry `type`/`code` at the top level ({"object": "error", ...})."""
+        if response.get("object") != "error":
+            return super().error_codes(response)
+        return [str(response[field]) for field in ("code", "type") if response.get(field)]
+    
+    def get_cost_breakdown(
         self,
         model: str,
         prompt_tokens: int,
         completion_tokens: int,
-    ) -> Optional[float]:
-        """Calculate cost in USD."""
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
+    ) -> Optional[Dict[str, float]]:
+        """Calculate input and output cost in USD (no prompt caching discount)."""
         pricing = self.PRICING.get(model)
         if not pricing:
             return None
         
         prompt_cost = (prompt_tokens / 1_000_000) * pricing["prompt"]
         completion_cost = (completion_tokens / 1_000_000) * pricing["completion"]
-        return prompt_cost + completion_cost
+        return {"input_usd": prompt_cost, "output_usd": completion_cost}
 
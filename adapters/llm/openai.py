@@ -19,6 +19,11 @@ class OpenAIAdapter(LLMAdapter):
         "gpt-3.5-turbo": {"prompt": 0.5, "completion": 1.5},
     }
     
+    # Cached prompt tokens are billed at 50% (prompt caching is automatic)
+    CACHED_PROMPT_PRICE_RATIO = 0.5
+    
+    optional_params = frozenset({"logit_bias", "seed", "logprobs", "top_logprobs"})
+    
     def prepare_request(
         self,
         messages: List[Dict[str, str]],
@@ -46,6 +51,21 @@ class OpenAIAdapter(LLMAdapter):
             payload["stop"] = stop
         if stream:
             payload["stream"] = True
+            if kwargs.get("stream_usage", True):
+                # Usage arrives in a final chunk with empty choices
+                payload["stream_options"] = {"include_usage": True}
+        if kwargs.get("response_format"):
+            payload["response_format"] = kwargs["response_format"]
+        if kwargs.get("n") and kwargs["n"] > 1:
+            payload["n"] = kwargs["n"]
+        if kwargs.get("logit_bias"):
+            payload["logit_bias"] = kwargs["logit_bias"]
+        if kwargs.get("seed") is not None:
+            payload["seed"] = kwargs["seed"]
+        if kwargs.get("logprobs"):
+            payload["logprobs"] = True
+            if kwargs.get("top_logprobs") is not None:
+                payload["top_logprobs"] = kwargs["top_logprobs"]
         
         return payload
     
@@ -114,34 +134,38 @@ class OpenAIAdapter(LLMAdapter):
     
     def parse_response(self, response: Dict[str, Any]) -> Dict[str, Any]:
         """Parse OpenAI response to normalized format."""
-        choices = response.get("choices", [])
+        choices = self.parse_choices(response)
         if not choices:
             return {
                 "content": "",
                 "finish_reason": "error",
+                "choices": [],
             }
         
-        choice = choices[0]
-        message = choice.get("message", {})
-        
         return {
-            "content": message.get("content", ""),
-            "role": message.get("role", "assistant"),
-            "finish_reason": choice.get("finish_reason", "stop"),
+            "content": choices[0]["content"],
+            "role": choices[0]["role"],
+            "finish_reason": choices[0]["finish_reason"],
+            "choices": choices,
         }
     
-    def get_cost_usd(
+    def get_cost_breakdown(
         self,
         model: str,
         prompt_tokens: int,
         completion_tokens: int,
-    ) -> Optional[float]:
-        """Calculate cost in USD."""
+        cached_prompt_tokens: int = 0,
+        cache_write_tokens: int = 0,
+    ) -> Optional[Dict[str, float]]:
+        """Calculate input and output cost in USD."""
         pricing = self.PRICING.get(model)
         if not pricing:
             return None
         
-        prompt_cost = (prompt_tokens / 1_000_000) * pricing["prompt"]
+        uncached_tokens = prompt_tokens - cached_prompt_tokens
+        prompt_cost = (
+            uncached_tokens + cached_prompt_tokens * self.CACHED_PROMPT_PRICE_RATIO
+        ) / 1_000_000 * pricing["prompt"]
         completion_cost = (completion_tokens / 1_000_000) * pricing["completion"]
-        return prompt_cost + completion_cost
+        return {"input_usd": prompt_cost, "output_usd": completion_cost}
 
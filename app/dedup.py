@@ -0,0 +1,143 @@
+"""Request deduplication by content hash for proxy routes.
+
+Targets with `dedup.enabled` treat identical requests (same kind, target and
+request fields) arriving within `window_ms` as accidental duplicates. With
+`action: coalesce` duplicates wait for the first request and return its
+result with `meta.deduplicated: true`; with `action: reject` they get 409.
+Requests carrying an idempotency key are left to idempotency handling, unless
+`coalesce_keyed` is set: keyed duplicates then wait for the identical
+in-flight request and run afterwards, so the cache entry it stored answers
+them instead of a second upstream call.
+"""
+import asyncio
+import logging
+import time
+from typing import Any, Awaitable, Callable, Dict, Optional, Union
+
+from reliapi.app.schemas import ErrorDetail, ErrorResponse, MetaResponse, SuccessResponse
+from reliapi.core.dedup import RequestDeduplicator
+from reliapi.core.errors import ErrorCode
+from reliapi.metrics.prometheus import dedup_requests_total
+
+logger = logging.getLogger(__name__)
+
+ProxyResult = Union[SuccessResponse, ErrorResponse]
+
+# Maximum time a duplicate waits for the first request's result
+MAX_COALESCE_WAIT_S = 30
+
+
+def _coalesced_result(data: Dict[str, Any], request_id: str, start_time: float) -> ProxyResult:
+    """Rebuild the first request's result for a coalesced duplicate."""
+    result = SuccessResponse(**data) if data.get("success") else ErrorResponse(**data)
+    result.meta.deduplicated = True
+    result.meta.request_id = request_id
+    result.meta.duration_ms = int((time.time() - start_time) * 1000)
+    return result
+
+
+async def _wait_for_first(
+    deduplicator: RequestDeduplicator, content_hash: str, tenant: Optional[str]
+) -> Optional[Dict[str, Any]]:
+    """Poll (with backoff) for the first request's result; None once it finished without one, or after the wait."""
+    waited = 0.0
+    poll_interval = 0.05
+    while waited < MAX_COALESCE_WAIT_S:
+        existing = deduplicator.get_result(content_hash, tenant=tenant)
+        if existing:
+            return existing
+        if not deduplicator.is_in_flight(content_hash, tenant=tenant):
+            return None
+        await asyncio.sleep(poll_interval)
+        waited += poll_interval
+        poll_interval = min(poll_interval * 1.5, 0.5)
+    return None
+
+
+async def run_with_dedup(
+    deduplicator: Optional[RequestDeduplicator],
+    target_config: Dict[str, Any],
+    kind: str,
+    target_name: str,
+    payload: Dict[str, Any],
+    request_id: str,
+    tenant: Optional[str],
+    idempotency_key: Optional[str],
+    call: Callable[[], Awaitable[ProxyResult]],
+) -> ProxyResult:
+    """Run a proxy call, deduplicating identical in-window requests.
+
+    Args:
+        deduplicator: Request deduplicator
+        target_config: Target configuration (reads `dedup`)
+        kind: "http" or "llm"
+        target_name: Target name
+        payload: Request fields that identify the request content
+        request_id: Request ID
+        tenant: Tenant name
+        idempotency_key: Client idempotency key (dedup is skipped if set, unless coalesce_keyed)
+        call: Runs the actual proxy request
+
+    Returns:
+        Result of the call, the coalesced result, or a 409 error
+    """
+    dedup_config = target_config.get("dedup") or {}
+    if not dedup_config.get("enabled") or not deduplicator or not deduplicator.enabled:
+        return await call()
+    coalesce = dedup_config.get("action", "coalesce") == "coalesce"
+    if idempotency_key and not (coalesce and dedup_config.get("coalesce_keyed")):
+        return await call()
+
+    start_time = time.time()
+    window_ms = dedup_config.get("window_ms", 5000)
+    content_hash = deduplicator.make_content_hash(kind, target_name, payload)
+    is_first, first_request_id = deduplicator.claim(content_hash, request_id, window_ms, tenant=tenant)
+
+    if is_first:
+        try:
+            result = await call()
+            # Streamed responses cannot be replayed; duplicates then run on their own
+            if isinstance(result, (SuccessResponse, ErrorResponse)):
+                deduplicator.store_result(content_hash, result.model_dump(), window_ms, tenant=tenant)
+            return result
+        finally:
+            deduplicator.finish(content_hash, tenant=tenant)
+
+    if idempotency_key:
+        # Keyed duplicates go through their own idempotency checks: they run once the first
+        # request is done, normally as a hit on the cache entry it stored
+        await _wait_for_first(deduplicator, content_hash, tenant)
+        dedup_requests_total.labels(target=target_name, kind=kind, action="awaited").inc()
+        return await call()
+
+    if not coalesce:
+        dedup_requests_total.labels(target=target_name, kind=kind, action="rejected").inc()
+        return ErrorResponse(
+            success=False,
+            error=ErrorDetail(
+                type="client_error",
+                code=ErrorCode.DUPLICATE_REQUEST.value,
+                message=f"Identical request received within {window_ms}ms",
+                retryable=False,
+                source="reliapi",
+                target=target_name,
+                status_code=409,
+                hint="Send an idempotency_key to safely retry the same request",
+                details={"first_request_id": first_request_id},
+            ),
+            meta=MetaResponse(
+                target=target_name,
+                duration_ms=int((time.time() - start_time) * 1000),
+                request_id=request_id,
+            ),
+        )
+
+    # Coalesce: wait for the first request's result (polling with backoff)
+    existing = await _wait_for_first(deduplicator, content_hash, tenant)
+    if existing:
+        dedup_requests_total.labels(target=target_name, kind=kind, action="coalesced").inc()
+        return _coalesced_result(existing, request_id, start_time)
+
+    # First request finished without a result (or timed out): run independently
+    logger.info(f"Duplicate request {request_id} of {first_request_id} not coalesced, running independently")
+    return await call()
@@ -7,27 +7,46 @@ This module contains:
 - Configuration initialization
 """
 import hashlib
+import hmac
 import logging
 import os
+import re
+import uuid
 from dataclasses import dataclass, field
 from typing import Any, Dict, List, Optional, Tuple
 
 from fastapi import HTTPException, Request
 
 from reliapi.config.loader import ConfigLoader
+from reliapi.core.audit_log import AuditLog
 from reliapi.core.cache import Cache
 from reliapi.core.client_profile import ClientProfile, ClientProfileManager
+from reliapi.core.debug_trace import DebugTraceBuffer
+from reliapi.core.dedup import RequestDeduplicator
 from reliapi.core.errors import ErrorCode
 from reliapi.core.idempotency import IdempotencyManager
 from reliapi.core.key_pool import KeyPoolManager, ProviderKey
+from reliapi.core.model_limits import ModelRateLimiter
+from reliapi.core.pinned_responses import PinnedResponses
 from reliapi.core.rate_limiter import RateLimiter
 from reliapi.core.rate_scheduler import RateScheduler
+from reliapi.core.request_overrides import InvalidRequestOverride, parse_request_overrides
+from reliapi.core.request_log import RequestLog
+from reliapi.core.stream_tee import StreamTee
+from reliapi.core.tenant_limits import TenantLimits
+from reliapi.core.usage import UsageStore
 from reliapi.integrations.rapidapi import RapidAPIClient
 from reliapi.integrations.rapidapi_tenant import RapidAPITenantManager
 from reliapi.metrics.prometheus import rapidapi_tier_cache_total
 
 logger = logging.getLogger(__name__)
 
+# Client-supplied X-Request-Id values: safe for logs, headers and Redis keys
+REQUEST_ID_PATTERN = re.compile(r"^[A-Za-z0-9._:-]{1,128}$")
+
+# idempotency_keys.require_uuid: canonical 8-4-4-4-12 hex form
+UUID_PATTERN = re.compile(r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}")
+
 
 class ConfigValidationError(Exception):
     """Raised when configuration validation fails."""
@@ -51,6 +70,16 @@ class AppState:
     client_profile_manager: Optional[ClientProfileManager] = None
     rapidapi_client: Optional[RapidAPIClient] = None
     rapidapi_tenant_manager: Optional[RapidAPITenantManager] = None
+    usage_store: Optional[UsageStore] = None
+    tenant_limits: Optional[TenantLimits] = None
+    model_rate_limiter: Optional[ModelRateLimiter] = None
+    deduplicator: Optional[RequestDeduplicator] = None
+    request_log: Optional[RequestLog] = None
+    audit_log: Optional[AuditLog] = None
+    debug_trace: Optional[DebugTraceBuffer] = None
+    stream_tee: Optional[StreamTee] = None
+    pinned_responses: Optional[PinnedResponses] = None
+    warmup: Optional[Dict[str, Dict[str, Any]]] = None
 
 
 # Global application state instance
@@ -66,6 +95,14 @@ def get_app_state() -> AppState:
     return app_state
 
 
+def _bearer_token(request: Request) -> Optional[str]:
+    """API key from `Authorization: Bearer <key>` (as sent by the OpenAI SDK)."""
+    scheme, _, token = request.headers.get("Authorization", "").partition(" ")
+    if scheme.lower() != "bearer":
+        return None
+    return token.strip() or None
+
+
 def verify_api_key(request: Request) -> Tuple[Optional[str], Optional[str], str]:
     """Verify API key from header and resolve tenant and tier.
 
@@ -88,7 +125,7 @@ def verify_api_key(request: Request) -> Tuple[Optional[str], Optional[str], str]
         HTTPException: If API key is missing or invalid.
     """
     state = get_app_state()
-    api_key = request.headers.get("X-API-Key")
+    api_key = request.headers.get("X-API-Key") or _bearer_token(request)
     headers_dict = dict(request.headers)
 
     def get_tier(api_key: str, headers: Dict[str, str]) -> str:
@@ -111,14 +148,11 @@ def verify_api_key(request: Request) -> Tuple[Optional[str], Optional[str], str]
         return "free"
 
     # Multi-tenant mode: check tenants config
-    if (state.config_loader and
-        hasattr(state.config_loader, 'config') and
-        state.config_loader.config.tenants):
-        tenants = state.config_loader.config.tenants
-
+    tenants = state.config_loader.get_tenants() if state.config_loader else None
+    if tenants:
         # Find tenant by API key
         for tenant_name, tenant_config in tenants.items():
-            if tenant_config.api_key == api_key:
+            if tenant_config.get("api_key") == api_key:
                 request.state.tenant = tenant_name
                 tier = get_tier(api_key, headers_dict)
                 request.state.tier = tier
@@ -167,7 +201,7 @@ def verify_api_key(request: Request) -> Tuple[Optional[str], Optional[str], str]
                 "error": {
                     "type": "client_error",
                     "code": ErrorCode.UNAUTHORIZED.value,
-                    "message": "Missing X-API-Key header",
+                    "message": "Missing X-API-Key header (or Authorization: Bearer <key>)",
                     "retryable": False,
                     "target": None,
                     "status_code": 401,
@@ -215,6 +249,37 @@ def verify_api_key(request: Request) -> Tuple[Optional[str], Optional[str], str]
     return api_key, None, tier
 
 
+def verify_admin_key(request: Request) -> None:
+    """Require the admin key (RELIAPI_ADMIN_KEY) in the X-Admin-Key header.
+
+    Admin endpoints are disabled (403) when RELIAPI_ADMIN_KEY is not set.
+
+    Raises:
+        HTTPException: If admin endpoints are disabled or the key is wrong.
+    """
+    admin_key = os.getenv("RELIAPI_ADMIN_KEY")
+    if not admin_key:
+        raise HTTPException(
+            status_code=403,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.UNAUTHORIZED.value,
+                "message": "Admin endpoints are disabled (RELIAPI_ADMIN_KEY is not set)",
+            },
+        )
+
+    provided = request.headers.get("X-Admin-Key", "")
+    if not hmac.compare_digest(provided.encode(), admin_key.encode()):
+        raise HTTPException(
+            status_code=401,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.UNAUTHORIZED.value,
+                "message": "Missing or invalid X-Admin-Key header",
+            },
+        )
+
+
 def detect_client_profile(
     request: Request,
     tenant: Optional[str] = None
@@ -264,6 +329,178 @@ def get_account_id(api_key: Optional[str]) -> str:
     return hashlib.sha256(api_key.encode()).hexdigest()[:16]
 
 
+def idempotency_key_error(idempotency_key: str, rules: Dict[str, Any]) -> Optional[str]:
+    """Why a key fails the `idempotency_keys` rules, or None if it is valid."""
+    min_length = rules.get("min_length", 1)
+    max_length = rules.get("max_length", 256)
+    if not min_length <= len(idempotency_key) <= max_length:
+        return f"Idempotency key must be {min_length}-{max_length} characters"
+    if rules.get("require_uuid") and not UUID_PATTERN.fullmatch(idempotency_key):
+        return "Idempotency key must be a UUID (e.g. a client-generated UUIDv4)"
+    if rules.get("pattern") and not re.fullmatch(rules["pattern"], idempotency_key):
+        return f"Idempotency key must match {rules['pattern']}"
+    return None
+
+
+def resolve_idempotency_key(
+    body_key: Optional[str], request: Request, api_key: Optional[str] = None
+) -> Optional[str]:
+    """Resolve the idempotency key from the body field or `Idempotency-Key` header.
+
+    The body field takes precedence if both are present. With the default
+    `idempotency_scope: per_key`, the key is prefixed with the caller's account
+    ID so two API keys sending the same value never replay each other's results.
+
+    Args:
+        body_key: `idempotency_key` from the request body
+        request: FastAPI request object
+        api_key: API key of the caller (None leaves the key unscoped)
+
+    Returns:
+        Idempotency key or None
+
+    Raises:
+        HTTPException: If the key fails the `idempotency_keys` rules (400 INVALID_IDEMPOTENCY_KEY)
+    """
+    idempotency_key = body_key or request.headers.get("Idempotency-Key") or None
+    if not idempotency_key:
+        return None
+    state = get_app_state()
+    rules = state.config_loader.get_idempotency_keys() if state.config_loader else {}
+    error = idempotency_key_error(idempotency_key, rules)
+    if error:
+        raise HTTPException(
+            status_code=400,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.INVALID_IDEMPOTENCY_KEY.value,
+                "message": error,
+            },
+        )
+    if not api_key:
+        return idempotency_key
+    scope = state.config_loader.get_idempotency_scope() if state.config_loader else "per_key"
+    if scope == "global":
+        return idempotency_key
+    return f"{get_account_id(api_key)}:{idempotency_key}"
+
+
+def resolve_request_id(request: Request) -> str:
+    """Use the client's `X-Request-Id` as the request ID, or generate one.
+
+    The ID is returned in meta.request_id, logged and sent upstream as
+    X-Request-Id, so clients can correlate with their own IDs.
+
+    Raises:
+        HTTPException: If the header is not 1-128 of A-Z a-z 0-9 . _ : -
+    """
+    request_id = request.headers.get("X-Request-Id")
+    if request_id is None:
+        return f"req_{uuid.uuid4().hex[:16]}"
+    if not REQUEST_ID_PATTERN.match(request_id):
+        raise HTTPException(
+            status_code=400,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.BAD_REQUEST.value,
+                "message": "X-Request-Id must be 1-128 characters of A-Z, a-z, 0-9, '.', '_', ':' or '-'",
+            },
+        )
+    return request_id
+
+
+def resolve_request_overrides(request: Request) -> Optional[Dict[str, int]]:
+    """Resolve per-request timeout/retry overrides from X-ReliAPI-* headers.
+
+    Values are clamped to the `request_overrides` ceilings in config.yaml.
+
+    Args:
+        request: FastAPI request object
+
+    Returns:
+        Overrides for the service handlers, or None if no override headers are set
+
+    Raises:
+        HTTPException: If an override header is malformed
+    """
+    state = get_app_state()
+    limits = state.config_loader.get_request_overrides() if state.config_loader else None
+    try:
+        return parse_request_overrides(request.headers, limits)
+    except InvalidRequestOverride as e:
+        raise HTTPException(
+            status_code=400,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.BAD_REQUEST.value,
+                "message": str(e),
+            },
+        )
+
+
+def resolve_request_tags(
+    tenant: Optional[str],
+    request_tags: Optional[Dict[str, str]],
+) -> Dict[str, str]:
+    """Merge per-tenant default tags with request tags.
+
+    Request tags take precedence over tenant defaults.
+
+    Args:
+        tenant: Tenant name or None
+        request_tags: Tags from the request body
+
+    Returns:
+        Merged tags (empty dict if none)
+    """
+    state = get_app_state()
+    tags: Dict[str, str] = {}
+    if tenant and state.config_loader:
+        tenant_config = state.config_loader.get_tenant(tenant) or {}
+        tags.update(tenant_config.get("tags") or {})
+    if request_tags:
+        tags.update(request_tags)
+    return tags
+
+
+# Request fields that tenant defaults can fill in
+DEFAULTABLE_PARAMS = ("model", "max_tokens", "cache", "post_process")
+
+
+def apply_tenant_defaults(tenant: Optional[str], target: str, request: Any) -> Optional[Dict[str, Any]]:
+    """Fill parameters the request omitted from the tenant's `defaults`.
+
+    Defaults for the target override those for '*'; values sent in the
+    request always win. The request is updated in place.
+
+    Args:
+        tenant: Tenant name or None
+        target: Target name
+        request: Parsed request body (LLM or HTTP proxy request)
+
+    Returns:
+        Effective parameters plus `inherited` (names filled from defaults),
+        or None if the tenant has no defaults for the target
+    """
+    state = get_app_state()
+    if not tenant or not state.config_loader:
+        return None
+    tenant_defaults = (state.config_loader.get_tenant(tenant) or {}).get("defaults") or {}
+    if "*" not in tenant_defaults and target not in tenant_defaults:
+        return None
+    defaults = {**tenant_defaults.get("*", {}), **tenant_defaults.get(target, {})}
+
+    resolved: Dict[str, Any] = {"inherited": []}
+    for name in DEFAULTABLE_PARAMS:
+        if not hasattr(request, name):
+            continue
+        if getattr(request, name) is None and defaults.get(name) is not None:
+            setattr(request, name, defaults[name])
+            resolved["inherited"].append(name)
+        resolved[name] = getattr(request, name)
+    return resolved
+
+
 def validate_startup_config(
     config_loader: ConfigLoader,
     strict: bool = True
@@ -373,6 +610,20 @@ def validate_startup_config(
                     f"max_qps_per_provider_key: {max_qps_key} (must be > 0)"
                 )
 
+    # 4. Validate shadow targets
+    targets_config = config_loader.get_targets()
+    for target_name, target_config in targets_config.items():
+        shadow_config = target_config.get("shadow")
+        if not shadow_config:
+            continue
+        shadow_target = shadow_config.get("target")
+        if shadow_target not in targets_config:
+            errors.append(
+                f"Target '{target_name}' has unknown shadow target '{shadow_target}'"
+            )
+        elif shadow_target == target_name:
+            errors.append(f"Target '{target_name}' cannot shadow itself")
+
     # Log warnings
     for warning in warnings:
         logger.warning(f"Configuration warning: {warning}")
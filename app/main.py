@@ -10,7 +10,7 @@ import logging
 import os
 import traceback
 from contextlib import asynccontextmanager
-from typing import List
+from typing import List, Optional
 
 from fastapi import FastAPI, Request, status
 from fastapi.middleware.cors import CORSMiddleware
@@ -23,11 +23,25 @@ from reliapi.app.dependencies import (
     init_key_pool_manager,
     validate_startup_config,
 )
+from reliapi.app.warmup import run_warmup
 from reliapi.config.loader import ConfigLoader
+from reliapi.core.audit_log import DEFAULT_RETENTION_S as AUDIT_RETENTION_S, AuditLog
 from reliapi.core.cache import Cache
+from reliapi.core.debug_trace import DebugTraceBuffer
+from reliapi.core.dedup import RequestDeduplicator
 from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.kill_switch import kill_switch
+from reliapi.core.maintenance import maintenance_mode
+from reliapi.core.model_limits import ModelRateLimiter
+from reliapi.core.pinned_responses import PinnedResponses
 from reliapi.core.rate_limiter import RateLimiter
 from reliapi.core.rate_scheduler import RateScheduler
+from reliapi.core.request_log import DEFAULT_MAX_ENTRIES, DEFAULT_RETENTION_S, RequestLog
+from reliapi.core.retry_budget import retry_budget
+from reliapi.core.stream_tee import StreamTee
+from reliapi.core.tenant_limits import TenantLimits
+from reliapi.core.usage import UsageStore
+from reliapi.core.usage_export import usage_exporter
 from reliapi.integrations.rapidapi import RapidAPIClient
 from reliapi.integrations.rapidapi_tenant import RapidAPITenantManager
 
@@ -79,9 +93,75 @@ async def lifespan(app: FastAPI):
         raise
 
     logger.info(f"Initializing Redis connection: {redis_url}")
-    state.cache = Cache(redis_url, key_prefix="reliapi")
+    state.cache = Cache(
+        redis_url,
+        key_prefix="reliapi",
+        compression=state.config_loader.get_cache_compression(),
+        memory=state.config_loader.get_cache_memory(),
+        max_value_bytes=state.config_loader.get_max_cache_value_bytes(),
+        write_retry=state.config_loader.get_cache_write_retry(),
+        key_version=state.config_loader.get_cache_key_version(),
+    )
     state.idempotency = IdempotencyManager(redis_url, key_prefix="reliapi")
     state.rate_limiter = RateLimiter(redis_url, key_prefix="reliapi")
+    state.usage_store = UsageStore(redis_url, key_prefix="reliapi")
+    state.tenant_limits = TenantLimits(state.usage_store)
+    state.deduplicator = RequestDeduplicator(redis_url, key_prefix="reliapi")
+    maintenance_mode.connect(redis_url, key_prefix="reliapi")
+    kill_switch.connect(redis_url, key_prefix="reliapi")
+
+    # Opt-in request log for replaying calls during incident analysis
+    if os.getenv("REQUEST_LOG_ENABLED", "false").lower() == "true":
+        state.request_log = RequestLog(
+            redis_url,
+            key_prefix="reliapi",
+            retention_s=int(os.getenv("REQUEST_LOG_RETENTION_S", str(DEFAULT_RETENTION_S))),
+            max_entries=int(os.getenv("REQUEST_LOG_MAX_ENTRIES", str(DEFAULT_MAX_ENTRIES))),
+        )
+        logger.info("Request log enabled")
+
+    # Audit log of budget/rate-limit/policy rejections (on unless disabled)
+    if os.getenv("AUDIT_LOG_ENABLED", "true").lower() == "true":
+        state.audit_log = AuditLog(
+            redis_url,
+            key_prefix="reliapi",
+            retention_s=int(os.getenv("AUDIT_LOG_RETENTION_S", str(AUDIT_RETENTION_S))),
+        )
+
+    # Recent request traces for GET /debug/recent (opt-in, per process)
+    debug_trace_config = state.config_loader.get_debug_trace() or {}
+    if debug_trace_config.get("enabled"):
+        state.debug_trace = DebugTraceBuffer(
+            size=debug_trace_config.get("size", 200),
+            sample_rate=debug_trace_config.get("sample_rate", 1.0),
+            redact=debug_trace_config.get("redact"),
+        )
+        logger.info("Debug trace buffer enabled")
+
+    # Completed LLM streams copied to an analytics sink (opt-in)
+    stream_tee_config = state.config_loader.get_stream_tee() or {}
+    if stream_tee_config.get("enabled"):
+        state.stream_tee = StreamTee(
+            sink=stream_tee_config.get("sink", "file"),
+            path=stream_tee_config.get("path"),
+            url=stream_tee_config.get("url"),
+            headers=stream_tee_config.get("headers"),
+            include_messages=stream_tee_config.get("include_messages", False),
+            redact_patterns=stream_tee_config.get("redact_patterns"),
+            max_queue=stream_tee_config.get("max_queue", 1000),
+            timeout_s=stream_tee_config.get("timeout_s", 5.0),
+            usage_store=state.usage_store,
+        )
+        logger.info(f"Stream tee enabled ({state.stream_tee.sink} sink)")
+
+    # Usage records also sent in batches to an analytics webhook (opt-in)
+    usage_export_config = state.config_loader.get_usage_export() or {}
+    if usage_export_config.get("enabled"):
+        state.usage_store.exporter = usage_exporter(usage_export_config)
+        logger.info("Usage export enabled")
+
+    # Pinned (never-expiring) LLM responses managed via /cache/pin
+    state.pinned_responses = PinnedResponses(redis_url, key_prefix="reliapi")
 
     # Initialize RapidAPI client
     state.rapidapi_client = RapidAPIClient(
@@ -105,6 +185,17 @@ async def lifespan(app: FastAPI):
     else:
         logger.info("No key pools configured, using targets.auth")
 
+    # Opt-in preflight call per target so bad credentials surface at deploy time
+    warmup_config = state.config_loader.get_warmup() or {}
+    if warmup_config.get("enabled"):
+        state.warmup = await run_warmup(
+            state.targets, state.key_pool_manager, timeout_ms=warmup_config.get("timeout_ms", 5000),
+        )
+        failed = sorted(name for name, result in state.warmup.items() if result["status"] == "failed")
+        if failed and warmup_config.get("fail_fast"):
+            raise ConfigValidationError(f"Warmup failed for targets: {', '.join(failed)}")
+        logger.info(f"Warmup checked {len(state.warmup)} targets ({len(failed)} failed)")
+
     # Initialize rate scheduler with memory management
     state.rate_scheduler = RateScheduler(
         max_buckets=1000,
@@ -114,6 +205,12 @@ async def lifespan(app: FastAPI):
     await state.rate_scheduler.start_cleanup_task()
     logger.info("Rate scheduler initialized with memory management")
 
+    # Global retry budget (per-target budgets are read from target config)
+    retry_budget.configure(state.config_loader.get_retry_budget())
+
+    # Initialize per-model RPM/TPM limiter
+    state.model_rate_limiter = ModelRateLimiter()
+
     # Initialize client profile manager
     state.client_profile_manager = init_client_profile_manager(state.config_loader)
     logger.info("Client profile manager initialized")
@@ -128,6 +225,10 @@ async def lifespan(app: FastAPI):
         await state.rate_scheduler.stop_cleanup_task()
     if state.rapidapi_client:
         await state.rapidapi_client.close()
+    if state.stream_tee:
+        await state.stream_tee.close()
+    if state.usage_store and state.usage_store.exporter:
+        await state.usage_store.exporter.close()
 
 
 def create_app() -> FastAPI:
@@ -160,9 +261,34 @@ def create_app() -> FastAPI:
     return app
 
 
+# Defaults when CORS is enabled (CORS_ALLOW_METHODS / CORS_ALLOW_HEADERS override)
+CORS_DEFAULT_METHODS = ["GET", "POST", "OPTIONS"]
+CORS_DEFAULT_HEADERS = ["Content-Type", "Authorization", "X-API-Key", "X-Client", "X-Request-Id"]
+CORS_EXPOSE_HEADERS = [
+    "X-Request-ID",
+    "X-RateLimit-Remaining",
+    "X-RateLimit-Reset",
+    "X-Budget-Remaining-Usd",
+    "X-Budget-Reset",
+    "X-ReliAPI-Estimated-Cost",
+    "X-ReliAPI-Cache",
+    "X-ReliAPI-Request-Log-Id",
+    "Age",
+]
+
+
 def _configure_cors(app: FastAPI) -> None:
-    """Configure CORS middleware with production security."""
-    cors_origins_env = os.getenv("CORS_ORIGINS", "*")
+    """Configure CORS middleware for browser clients.
+
+    Disabled unless CORS_ORIGINS is set, so the proxy is not opened to
+    arbitrary web pages by accident. Starlette answers preflight OPTIONS
+    requests for allowed origins.
+    """
+    cors_origins_env = os.getenv("CORS_ORIGINS", "").strip()
+    if not cors_origins_env:
+        logger.info("CORS disabled (set CORS_ORIGINS to allow browser clients)")
+        return
+
     is_production = os.getenv("ENVIRONMENT", "").lower() == "production"
 
     if cors_origins_env == "*":
@@ -175,6 +301,18 @@ def _configure_cors(app: FastAPI) -> None:
         cors_origins = ["*"]
     else:
         cors_origins = _validate_cors_origins(cors_origins_env, is_production)
+        if not cors_origins:
+            logger.warning("CORS disabled: no valid origins in CORS_ORIGINS")
+            return
+
+    allow_credentials = os.getenv("CORS_ALLOW_CREDENTIALS", "false").lower() == "true"
+    if allow_credentials and "*" in cors_origins:
+        # Credentials with a wildcard origin would let any site call the proxy as the user
+        logger.warning(
+            "CORS_ALLOW_CREDENTIALS ignored with wildcard origin; "
+            "list explicit origins in CORS_ORIGINS to allow credentials"
+        )
+        allow_credentials = False
 
     if is_production:
         logger.info(
@@ -189,13 +327,21 @@ def _configure_cors(app: FastAPI) -> None:
     app.add_middleware(
         CORSMiddleware,
         allow_origins=cors_origins,
-        allow_credentials=True,
-        allow_methods=["*"],
-        allow_headers=["*"],
-        expose_headers=["X-Request-ID", "X-RateLimit-Remaining", "X-RateLimit-Reset"],
+        allow_credentials=allow_credentials,
+        allow_methods=_parse_cors_list(os.getenv("CORS_ALLOW_METHODS"), CORS_DEFAULT_METHODS),
+        allow_headers=_parse_cors_list(os.getenv("CORS_ALLOW_HEADERS"), CORS_DEFAULT_HEADERS),
+        expose_headers=CORS_EXPOSE_HEADERS,
+        max_age=int(os.getenv("CORS_MAX_AGE", "600")),
     )
 
 
+def _parse_cors_list(value: Optional[str], default: List[str]) -> List[str]:
+    """Parse a comma-separated CORS env var, falling back to default if unset."""
+    if not value:
+        return default
+    return [item.strip() for item in value.split(",") if item.strip()]
+
+
 def _validate_cors_origins(cors_origins_env: str, is_production: bool) -> List[str]:
     """Validate and filter CORS origins.
 
@@ -265,13 +411,24 @@ def _register_exception_handlers(app: FastAPI) -> None:
 def _register_routes(app: FastAPI) -> None:
     """Register all route handlers."""
     # Import and register core routes
-    from reliapi.app.routes import health, proxy, rapidapi
+    from reliapi.app.routes import admin, audit, cache, check, debug, health, models, openai_compat, proxy, rapidapi, replay, targets, usage
 
     app.include_router(health.router)
+    app.include_router(admin.router)
+    app.include_router(audit.router)
+    app.include_router(debug.router)
+    app.include_router(cache.router)
+    app.include_router(usage.router)
+    app.include_router(check.router)
+    app.include_router(models.router)
+    app.include_router(targets.router)
+    app.include_router(replay.router)
     
     # v1 API routes (canonical)
     app.include_router(proxy.router, prefix="/v1")
     app.include_router(rapidapi.router, prefix="/v1")
+    # OpenAI SDK base_url points at /v1, so /v1/chat/completions only
+    app.include_router(openai_compat.router, prefix="/v1")
     
     # Legacy routes (deprecated - will be removed in 6 months)
     app.include_router(proxy.router, deprecated=True, tags=["Legacy"])
@@ -0,0 +1,191 @@
+"""OpenAI-compatible request/response translation for POST /v1/chat/completions.
+
+Lets the OpenAI SDK use ReliAPI as its base URL. The OpenAI body is turned
+into an LLMProxyRequest, the normal LLM proxy pipeline runs (cache, retries,
+idempotency, budget caps), and the ReliAPI envelope is translated back into
+the OpenAI response, chunk or error shape. ReliAPI metadata moves to
+`X-ReliAPI-*` response headers.
+"""
+import json
+import time
+from typing import Any, AsyncIterator, Dict, List, Optional, Tuple
+
+from reliapi.app.schemas import LLMProxyRequest, OpenAIChatCompletionRequest
+from reliapi.core.llm_normalize import normalize_finish_reason
+from reliapi.core.sse_heartbeat import is_heartbeat
+
+# Target used when the client does not send X-ReliAPI-Target
+DEFAULT_TARGET = "openai"
+
+# meta field -> response header
+META_HEADERS = {
+    "target": "X-ReliAPI-Target",
+    "provider": "X-ReliAPI-Provider",
+    "cache_hit": "X-ReliAPI-Cache-Hit",
+    "idempotent_hit": "X-ReliAPI-Idempotent-Hit",
+    "retries": "X-ReliAPI-Retries",
+    "duration_ms": "X-ReliAPI-Duration-MS",
+    "cost_usd": "X-ReliAPI-Cost-USD",
+    "cost_estimate_usd": "X-ReliAPI-Estimated-Cost",
+    "cost_policy_applied": "X-ReliAPI-Cost-Policy",
+    "requested_n": "X-ReliAPI-Requested-N",
+    "returned_n": "X-ReliAPI-Returned-N",
+    "request_log_id": "X-ReliAPI-Request-Log-Id",
+}
+
+
+def _message_content(content: Any) -> str:
+    """Flatten OpenAI content (string or list of parts) to text."""
+    if content is None:
+        return ""
+    if isinstance(content, str):
+        return content
+    return "".join(
+        part.get("text", "") for part in content
+        if isinstance(part, dict) and part.get("type") == "text"
+    )
+
+
+def to_llm_proxy_request(
+    body: OpenAIChatCompletionRequest,
+    target: str,
+    idempotency_key: Optional[str] = None,
+    cache_ttl: Optional[int] = None,
+) -> LLMProxyRequest:
+    """Build the ReliAPI LLM request from an OpenAI Chat Completions body."""
+    stop = [body.stop] if isinstance(body.stop, str) else body.stop
+    return LLMProxyRequest(
+        target=target,
+        messages=[
+            {"role": message.get("role", "user"), "content": _message_content(message.get("content"))}
+            for message in body.messages
+        ],
+        model=body.model,
+        max_tokens=body.max_tokens or body.max_completion_tokens,
+        temperature=body.temperature,
+        top_p=body.top_p,
+        stop=stop,
+        response_format=body.response_format,
+        n=body.n,
+        logit_bias=body.logit_bias,
+        seed=body.seed,
+        logprobs=body.logprobs,
+        top_logprobs=body.top_logprobs,
+        stream=body.stream,
+        idempotency_key=idempotency_key,
+        cache=cache_ttl,
+    )
+
+
+def finish_reason(reason: Optional[str]) -> Optional[str]:
+    """Map a provider finish reason to the OpenAI value."""
+    return normalize_finish_reason(reason)
+
+
+def meta_headers(meta: Dict[str, Any]) -> Dict[str, str]:
+    """ReliAPI meta as X-ReliAPI-* headers (unset fields omitted)."""
+    headers = {"X-Request-ID": meta.get("request_id", "")}
+    for field, header in META_HEADERS.items():
+        value = meta.get(field)
+        if value is None:
+            continue
+        if isinstance(value, bool):
+            headers[header] = str(value).lower()
+        elif isinstance(value, float):
+            headers[header] = f"{value:.6f}"
+        else:
+            headers[header] = str(value)
+    return headers
+
+
+def openai_error(
+    message: str,
+    error_type: str,
+    code: Optional[str],
+) -> Dict[str, Any]:
+    """OpenAI error body."""
+    return {"error": {"message": message, "type": error_type, "code": code, "param": None}}
+
+
+def to_openai_response(result: Dict[str, Any]) -> Tuple[int, Dict[str, Any]]:
+    """Translate a ReliAPI response envelope to (status_code, OpenAI body)."""
+    meta = result.get("meta") or {}
+    if not result.get("success"):
+        error = result.get("error") or {}
+        return error.get("status_code") or 500, openai_error(
+            error.get("message", "Request failed"), error.get("type", "api_error"), error.get("code"),
+        )
+
+    data = result.get("data") or {}
+    return 200, {
+        "id": f"chatcmpl-{meta.get('request_id', '')}",
+        "object": "chat.completion",
+        "created": int(time.time()),
+        "model": meta.get("model"),
+        "choices": data.get("choices") or [{
+            "index": 0,
+            "message": {"role": data.get("role", "assistant"), "content": data.get("content", "")},
+            "finish_reason": finish_reason(data.get("finish_reason", "stop")),
+        }],
+        "usage": data.get("usage"),
+    }
+
+
+def parse_sse_event(event: str) -> Tuple[str, Dict[str, Any]]:
+    """Split a ReliAPI SSE event into (event name, data)."""
+    name = "message"
+    data_lines: List[str] = []
+    for line in event.strip().split("\n"):
+        if line.startswith("event: "):
+            name = line[len("event: "):]
+        elif line.startswith("data: "):
+            data_lines.append(line[len("data: "):])
+    return name, json.loads("\n".join(data_lines)) if data_lines else {}
+
+
+def stream_error(data: Dict[str, Any]) -> Tuple[int, Dict[str, Any]]:
+    """Translate a ReliAPI stream `error` event to (status_code, OpenAI error body)."""
+    return data.get("upstream_status") or 500, openai_error(
+        data.get("message", "Stream failed"), "api_error", data.get("code"),
+    )
+
+
+def _sse(data: Any) -> str:
+    return f"data: {json.dumps(data)}\n\n"
+
+
+async def to_openai_stream(
+    events: AsyncIterator[str],
+    request_id: str,
+    model: Optional[str],
+) -> AsyncIterator[str]:
+    """Translate ReliAPI stream events to OpenAI chat.completion.chunk events (heartbeats pass through)."""
+    created = int(time.time())
+
+    def chunk(delta: Dict[str, Any], reason: Optional[str] = None) -> str:
+        return _sse({
+            "id": f"chatcmpl-{request_id}",
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{"index": 0, "delta": delta, "finish_reason": reason}],
+        })
+
+    async for event in events:
+        if is_heartbeat(event):
+            yield event
+            continue
+        name, data = parse_sse_event(event)
+        if name == "meta":
+            model = data.get("model") or model
+            yield chunk({"role": "assistant", "content": ""})
+        elif name == "chunk":
+            if data.get("delta"):
+                yield chunk({"content": data["delta"]})
+        elif name == "done":
+            yield chunk({}, finish_reason(data.get("finish_reason") or "stop"))
+            yield "data: [DONE]\n\n"
+            return
+        elif name == "error":
+            yield _sse(stream_error(data)[1])
+            return
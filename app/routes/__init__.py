@@ -4,8 +4,11 @@ This package contains all route handlers organized by domain:
 
 Core routes:
 - health: Health check and monitoring endpoints
+- openai_compat: OpenAI-compatible /v1/chat/completions
 - proxy: HTTP and LLM proxy endpoints
 - rapidapi: RapidAPI integration endpoints
+- replay: Replay logged requests
+- usage: Usage and cost breakdown endpoint
 
 Business routes:
 - paddle: Paddle payment integration
@@ -14,6 +17,6 @@ Business routes:
 - calculators: ROI/pricing calculators
 - dashboard: Admin dashboard
 """
-from reliapi.app.routes import health, proxy, rapidapi
+from reliapi.app.routes import health, openai_compat, proxy, rapidapi, replay, usage
 
-__all__ = ["health", "proxy", "rapidapi"]
+__all__ = ["health", "openai_compat", "proxy", "rapidapi", "replay", "usage"]
@@ -0,0 +1,89 @@
+"""Admin endpoints (require X-Admin-Key = RELIAPI_ADMIN_KEY).
+
+This module provides:
+- GET /admin/maintenance - Current maintenance mode state
+- POST /admin/maintenance - Turn cache-only maintenance mode on/off
+- POST /admin/targets/{name}/disable - Stop all traffic to a target (kill switch)
+- POST /admin/targets/{name}/enable - Resume traffic to a disabled target
+"""
+import logging
+from typing import Any, Dict, Optional
+
+from fastapi import APIRouter, HTTPException, Request
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.app.schemas import MaintenanceRequest, TargetDisableRequest
+from reliapi.core.errors import ErrorCode
+from reliapi.core.kill_switch import kill_switch
+from reliapi.core.maintenance import maintenance_mode
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/admin", tags=["Admin"])
+
+
+def _require_target(name: str) -> None:
+    """Raise 404 unless the target is configured."""
+    if name not in get_app_state().targets:
+        raise HTTPException(
+            status_code=404,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.NOT_FOUND.value,
+                "message": f"Target '{name}' not found",
+            },
+        )
+
+
+@router.get("/maintenance", summary="Get maintenance mode state")
+async def get_maintenance(http_request: Request) -> Dict[str, Any]:
+    """Global and per-target maintenance flags."""
+    verify_admin_key(http_request)
+    return maintenance_mode.status()
+
+
+@router.post(
+    "/maintenance",
+    summary="Set maintenance mode",
+    description=(
+        "In maintenance mode requests are served from cache only; cache misses "
+        "are rejected with 503 MAINTENANCE_CACHE_ONLY instead of calling upstream."
+    ),
+)
+async def set_maintenance(body: MaintenanceRequest, http_request: Request) -> Dict[str, Any]:
+    """Turn maintenance mode on or off globally or for one target."""
+    verify_admin_key(http_request)
+    if body.target:
+        _require_target(body.target)
+
+    maintenance_mode.set(body.enabled, target=body.target, reason=body.reason)
+    return maintenance_mode.status()
+
+
+@router.post(
+    "/targets/{name}/disable",
+    summary="Disable a target",
+    description=(
+        "Kill switch: every request to the target is rejected with 503 TARGET_DISABLED "
+        "without calling upstream (cache hits are still served if the target sets "
+        "serve_cache_when_disabled). Shared across instances and kept across restarts "
+        "when Redis is available."
+    ),
+)
+async def disable_target(
+    name: str, http_request: Request, body: Optional[TargetDisableRequest] = None,
+) -> Dict[str, Any]:
+    """Stop all traffic to a target."""
+    verify_admin_key(http_request)
+    _require_target(name)
+    kill_switch.set(name, True, reason=body.reason if body else None)
+    return {"target": name, "disabled": True, "disabled_targets": kill_switch.status()}
+
+
+@router.post("/targets/{name}/enable", summary="Enable a disabled target")
+async def enable_target(name: str, http_request: Request) -> Dict[str, Any]:
+    """Resume traffic to a target stopped with the kill switch."""
+    verify_admin_key(http_request)
+    _require_target(name)
+    kill_switch.set(name, False)
+    return {"target": name, "disabled": False, "disabled_targets": kill_switch.status()}
@@ -0,0 +1,49 @@
+"""Audit log endpoint (requires X-Admin-Key = RELIAPI_ADMIN_KEY).
+
+This module provides:
+- GET /audit - Requests rejected for budget, rate-limit or policy reasons
+"""
+import logging
+from typing import Any, Dict, Optional
+
+from fastapi import APIRouter, HTTPException, Query, Request
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.core.audit_log import DEFAULT_QUERY_LIMIT
+from reliapi.core.errors import ErrorCode
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Admin"])
+
+
+@router.get(
+    "/audit",
+    summary="Audit log of rejected requests",
+    description=(
+        "Requests rejected for budget (BUDGET_EXCEEDED, UNKNOWN_MODEL, OUTPUT_CAP_EXCEEDED), rate-limit or "
+        "policy reasons, with key identity, reason code, timestamp and attempted cost. "
+        "Oldest first."
+    ),
+)
+async def get_audit(
+    request: Request,
+    since: Optional[float] = Query(None, alias="from", description="Start time (unix seconds)"),
+    until: Optional[float] = Query(None, alias="to", description="End time (unix seconds)"),
+    limit: int = Query(DEFAULT_QUERY_LIMIT, ge=1, le=10000, description="Maximum records returned"),
+) -> Dict[str, Any]:
+    """Return audit records in the requested time range."""
+    verify_admin_key(request)
+    state = get_app_state()
+    if not state.audit_log or not state.audit_log.enabled:
+        raise HTTPException(
+            status_code=503,
+            detail={
+                "type": "internal_error",
+                "code": ErrorCode.INTERNAL_ERROR.value,
+                "message": "Audit log is unavailable (disabled or Redis not connected)",
+            },
+        )
+
+    records = state.audit_log.query(since=since, until=until, limit=limit)
+    return {"count": len(records), "records": records}
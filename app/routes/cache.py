@@ -0,0 +1,108 @@
+"""Pinned response endpoints (require X-Admin-Key = RELIAPI_ADMIN_KEY).
+
+This module provides:
+- POST /cache/pin - Pin a response for an LLM request (no TTL)
+- DELETE /cache/pin - Remove a pin
+- GET /cache/pins - List pins for audit
+"""
+import logging
+from typing import Any, Dict
+
+from fastapi import APIRouter, HTTPException, Query, Request
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.app.schemas import PinRequest
+from reliapi.core.errors import ErrorCode
+from reliapi.core.pinned_responses import PinnedResponses
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/cache", tags=["Admin"])
+
+
+def _pins() -> PinnedResponses:
+    """Pinned response store, or 503 if it is unavailable."""
+    state = get_app_state()
+    if not state.pinned_responses or not state.pinned_responses.enabled:
+        raise HTTPException(
+            status_code=503,
+            detail={
+                "type": "internal_error",
+                "code": ErrorCode.INTERNAL_ERROR.value,
+                "message": "Pinned responses are unavailable (Redis not connected)",
+            },
+        )
+    return state.pinned_responses
+
+
+@router.post(
+    "/pin",
+    summary="Pin a response",
+    description=(
+        "Store a response for an LLM request with no TTL. Matching non-streaming "
+        "requests are served from the pin (cache_hit: true, pinned: true) regardless "
+        "of cache settings, until the pin is removed."
+    ),
+)
+async def pin_response(body: PinRequest, http_request: Request) -> Dict[str, Any]:
+    """Pin a vetted response; re-pinning the same request replaces it."""
+    verify_admin_key(http_request)
+    pins = _pins()
+    state = get_app_state()
+    target_config = state.targets.get(body.target)
+    if not target_config or not target_config.get("llm"):
+        raise HTTPException(
+            status_code=404,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.NOT_FOUND.value,
+                "message": f"LLM target '{body.target}' not found",
+            },
+        )
+
+    entry = pins.pin(
+        body.target,
+        body.model_dump(exclude={"target", "tenant", "content", "note"}),
+        body.content,
+        tenant=body.tenant,
+        note=body.note,
+    )
+    if entry is None:
+        raise HTTPException(
+            status_code=503,
+            detail={
+                "type": "internal_error",
+                "code": ErrorCode.INTERNAL_ERROR.value,
+                "message": "Failed to store pin",
+            },
+        )
+    logger.info(f"Pinned response {entry['id']} for target '{body.target}'")
+    return entry
+
+
+@router.delete("/pin", summary="Unpin a response")
+async def unpin_response(
+    http_request: Request,
+    pin_id: str = Query(..., alias="id", description="Pin ID returned by POST /cache/pin"),
+) -> Dict[str, Any]:
+    """Remove a pin; its request goes back to normal caching."""
+    verify_admin_key(http_request)
+    if not _pins().unpin(pin_id):
+        raise HTTPException(
+            status_code=404,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.NOT_FOUND.value,
+                "message": f"Pin '{pin_id}' not found",
+            },
+        )
+    logger.info(f"Unpinned response {pin_id}")
+    return {"id": pin_id, "unpinned": True}
+
+
+@router.get("/pins", summary="List pinned responses")
+async def list_pins(http_request: Request) -> Dict[str, Any]:
+    """All pins, oldest first."""
+    verify_admin_key(http_request)
+    entries = _pins().list()
+    return {"count": len(entries), "pins": entries}
@@ -0,0 +1,194 @@
+"""Admission check endpoint for client-side UX.
+
+This module provides:
+- POST /check - Would an LLM request pass auth, budget, rate-limit and policy gates?
+
+The request is evaluated against the same gates as /proxy/llm, in the same
+order, but never executed: no provider call is made, and no rate-limit
+window, budget or idempotency key is consumed.
+"""
+import logging
+import time
+from typing import Any, Dict, Optional
+
+from fastapi import APIRouter, Request
+from fastapi.responses import JSONResponse
+
+from reliapi.app.dependencies import (
+    apply_tenant_defaults,
+    get_account_id,
+    get_app_state,
+    verify_api_key,
+)
+from reliapi.app.routes.proxy import (
+    FREE_TIER_IP_RPM,
+    rate_limit_headers,
+    tenant_cost_multiplier,
+    turn_limit_rejection,
+)
+from reliapi.app.schemas import LLMProxyRequest
+from reliapi.app.services import check_llm_request
+from reliapi.core.auto_model import AUTO_MODEL, select_auto_model
+from reliapi.core.errors import ErrorCode
+from reliapi.core.free_tier_restrictions import FreeTierRestrictions
+from reliapi.core.security import SecurityManager
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Proxy"])
+
+
+def _api_key_format_rejection(api_key: Optional[str]) -> Optional[Dict[str, Any]]:
+    """Reason a malformed API key would be rejected (None if it would not)."""
+    if not api_key:
+        return None
+    is_valid, error_msg = SecurityManager.validate_api_key_format(api_key)
+    if is_valid:
+        return None
+    return {
+        "gate": "auth",
+        "code": "INVALID_API_KEY_FORMAT",
+        "message": error_msg or "Invalid API key format",
+        "status_code": 400,
+        "details": None,
+    }
+
+
+def _free_tier_rejection(
+    http_request: Request, api_key: Optional[str], tier: str
+) -> Optional[Dict[str, Any]]:
+    """Reason the free tier's auto-ban, per-IP or burst limits would reject a request.
+
+    The counters are only read, so checking never uses up the caller's limits.
+    """
+    state = get_app_state()
+    if not state.rate_limiter or tier != "free":
+        return None
+    client_ip = http_request.client.host if http_request.client else "unknown"
+    account_id = get_account_id(api_key)
+
+    should_ban, ban_reason = state.rate_limiter.check_auto_ban(
+        account_id, client_ip, max_attempts=5
+    )
+    if should_ban:
+        return {
+            "gate": "auth",
+            "code": "ACCOUNT_BANNED",
+            "message": f"Account/IP banned: {ban_reason}",
+            "status_code": 403,
+            "details": None,
+        }
+
+    limits = [
+        (state.rate_limiter.peek_ip_rate_limit(client_ip, limit_per_minute=FREE_TIER_IP_RPM),
+         "RATE_LIMIT_EXCEEDED", "Rate limit exceeded. Free tier: 20 requests/minute per IP."),
+        (state.rate_limiter.peek_burst_protection(account_id, limit_per_10min=300),
+         "BURST_LIMIT_EXCEEDED",
+         "Burst limit exceeded. Free tier: maximum 300 requests per 10 minutes."),
+        (state.rate_limiter.peek_account_burst_limit(account_id, limit_per_minute=500),
+         "FREE_TIER_ABUSE", "Burst limit exceeded. Free tier abuse detected."),
+    ]
+    for peeked, code, message in limits:
+        if peeked is not None and peeked[0] == 0:
+            return {
+                "gate": "rate_limit",
+                "code": code,
+                "message": message,
+                "status_code": 429,
+                "details": {"retry_after_s": max(peeked[1] - time.time(), 0)},
+            }
+    return None
+
+
+def _tenant_rejection(tenant: Optional[str]) -> Optional[Dict[str, Any]]:
+    """Reason the tenant's rate_limit_rpm or budget would reject a request (None if it would not)."""
+    state = get_app_state()
+    if not tenant or not state.tenant_limits or not state.config_loader:
+        return None
+    tenant_config = state.config_loader.get_tenant(tenant) or {}
+    rejected = state.tenant_limits.check(tenant, tenant_config)
+    if rejected is None:
+        return None
+    limit, retry_after_s = rejected
+    if limit == "budget":
+        budget = tenant_config["budget"]
+        return {
+            "gate": "budget",
+            "code": ErrorCode.BUDGET_EXCEEDED.value,
+            "message": f"Budget of ${budget['limit_usd']} per {budget.get('period', 'month')} used up",
+            "status_code": 402,
+            "details": {"retry_after_s": retry_after_s},
+        }
+    return {
+        "gate": "rate_limit",
+        "code": ErrorCode.RATE_LIMIT_RELIAPI.value,
+        "message": f"Rate limit exceeded: {tenant_config['rate_limit_rpm']} requests per minute",
+        "status_code": 429,
+        "details": {"retry_after_s": retry_after_s},
+    }
+
+
+@router.post(
+    "/check",
+    summary="Check whether an LLM request would be allowed",
+    description=(
+        "Evaluate an /proxy/llm request body against budget, rate-limit and policy gates without "
+        "executing it. Returns allowed, the first failing gate's reason, and the estimated cost. "
+        "Nothing is consumed."
+    ),
+)
+async def check_request(
+    request: LLMProxyRequest,
+    http_request: Request,
+) -> JSONResponse:
+    """Report whether the request would pass, and why not."""
+    state = get_app_state()
+    api_key, tenant, tier = verify_api_key(http_request)
+    apply_tenant_defaults(tenant, request.target, request)
+
+    target, model = request.target, request.model
+    if model == AUTO_MODEL:
+        target, model, _ = select_auto_model(
+            target, state.targets, request.messages, request.max_tokens,
+            request.constraints.model_dump() if request.constraints else None,
+        )
+
+    result = check_llm_request(
+        target_name=target,
+        messages=request.messages,
+        model=model,
+        max_tokens=request.max_tokens,
+        targets=state.targets,
+        n=request.n,
+        model_rate_limiter=state.model_rate_limiter,
+        cost_multiplier=tenant_cost_multiplier(tenant),
+    )
+
+    # Gates checked before the target's, in /proxy/llm order
+    reason = _api_key_format_rejection(api_key)
+    turn_limit = turn_limit_rejection(tenant, target, request.messages)
+    if not reason and turn_limit:
+        reason = {
+            "gate": "policy",
+            "code": turn_limit["code"],
+            "message": turn_limit["message"],
+            "status_code": 422,
+            "details": turn_limit["details"],
+        }
+    if not reason and tier == "free" and request.stream:
+        allowed, error = FreeTierRestrictions.is_feature_allowed("streaming", tier)
+        if not allowed:
+            reason = {
+                "gate": "policy",
+                "code": error,
+                "message": "SSE streaming not available for Free tier.",
+                "status_code": 403,
+                "details": None,
+            }
+    reason = (
+        reason or _free_tier_rejection(http_request, api_key, tier) or _tenant_rejection(tenant)
+    )
+    if reason:
+        result = {**result, "allowed": False, "reason": reason}
+
+    return JSONResponse(content=result, headers=rate_limit_headers(tenant, http_request, tier))
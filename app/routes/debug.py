@@ -0,0 +1,46 @@
+"""Debug trace endpoint (requires X-Admin-Key = RELIAPI_ADMIN_KEY).
+
+This module provides:
+- GET /debug/recent - Traces of the most recent requests (debug_trace)
+"""
+import logging
+from typing import Any, Dict, Optional
+
+from fastapi import APIRouter, HTTPException, Query, Request
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.core.errors import ErrorCode
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Admin"])
+
+
+@router.get(
+    "/debug/recent",
+    summary="Recent request traces",
+    description=(
+        "Redacted traces of the most recent requests handled by this process: target, model, status, "
+        "cache/idempotent hits, timing breakdown and retries. Newest first."
+    ),
+)
+async def get_recent_traces(
+    request: Request,
+    target: Optional[str] = Query(None, description="Only traces for this target"),
+    limit: Optional[int] = Query(None, ge=1, le=10000, description="Maximum traces returned"),
+) -> Dict[str, Any]:
+    """Return the buffered request traces."""
+    verify_admin_key(request)
+    state = get_app_state()
+    if not state.debug_trace:
+        raise HTTPException(
+            status_code=503,
+            detail={
+                "type": "internal_error",
+                "code": ErrorCode.INTERNAL_ERROR.value,
+                "message": "Debug trace buffer is disabled (set debug_trace.enabled)",
+            },
+        )
+
+    traces = state.debug_trace.recent(limit=limit, target=target)
+    return {"count": len(traces), "traces": traces}
@@ -6,15 +6,22 @@ This module provides:
 - GET /readyz - Readiness check
 - GET /livez - Liveness check
 - GET /metrics - Prometheus metrics
+- GET /circuit - Circuit breaker state per target
 """
 import logging
+from typing import Any, Dict, Optional
 
 from fastapi import APIRouter, HTTPException, Request
 from fastapi.responses import Response
 from prometheus_client import CONTENT_TYPE_LATEST, generate_latest
 from pydantic import BaseModel
 
-from reliapi.app.dependencies import get_app_state
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.core.circuit_breaker import circuit_breakers
+from reliapi.core.health_ttl import health_ttl_status
+from reliapi.core.kill_switch import kill_switch
+from reliapi.core.maintenance import maintenance_mode
+from reliapi.core.regions import region_router, region_url
 
 logger = logging.getLogger(__name__)
 
@@ -26,6 +33,11 @@ class HealthResponse(BaseModel):
 
     status: str
     version: str = "1.0.7"
+    maintenance: Optional[Dict[str, Any]] = None
+    disabled_targets: Optional[Dict[str, Dict[str, Any]]] = None
+    warmup: Optional[Dict[str, Dict[str, Any]]] = None
+    cache_key_version: int = 0
+    cache_ttl_extended: Optional[Dict[str, Dict[str, Any]]] = None
 
 
 class StatusResponse(BaseModel):
@@ -34,6 +46,12 @@ class StatusResponse(BaseModel):
     status: str
 
 
+class CircuitResponse(BaseModel):
+    """Circuit breaker state per target."""
+
+    targets: Dict[str, Dict[str, Any]]
+
+
 def _check_health_rate_limit(request: Request, prefix: str) -> None:
     """Check rate limit for health endpoints.
 
@@ -70,10 +88,52 @@ def _check_health_rate_limit(request: Request, prefix: str) -> None:
         )
 
 
+def _is_admin(request: Request) -> bool:
+    """Whether the request carries a valid X-Admin-Key."""
+    try:
+        verify_admin_key(request)
+    except HTTPException:
+        return False
+    return True
+
+
+def _without_reason(entry: Optional[Dict[str, Any]]) -> Optional[Dict[str, Any]]:
+    """Maintenance or kill switch entry without its operator-written reason."""
+    if entry is None:
+        return None
+    return {name: value for name, value in entry.items() if name != "reason"}
+
+
 @router.get("/health", response_model=HealthResponse)
-async def health_check() -> HealthResponse:
-    """Basic health check endpoint for load balancers and monitoring."""
-    return HealthResponse(status="ok")
+async def health_check(request: Request) -> HealthResponse:
+    """Basic health check endpoint for load balancers and monitoring.
+
+    `maintenance` lists targets in cache-only maintenance mode, if any
+    (with each `reason` only for requests with a valid X-Admin-Key);
+    `disabled_targets` lists targets stopped with the kill switch, if any
+    (reasons likewise only for the admin key);
+    `warmup` has the startup credential check per target (if enabled);
+    `cache_key_version` is the version mixed into cache keys;
+    `cache_ttl_extended` lists targets whose cache TTL health_ttl currently extends.
+    """
+    state = get_app_state()
+    maintenance = maintenance_mode.status()
+    active = maintenance["global"] is not None or bool(maintenance["targets"])
+    disabled = kill_switch.status()
+    if (active or disabled) and not _is_admin(request):
+        maintenance = {
+            "global": _without_reason(maintenance["global"]),
+            "targets": {name: _without_reason(entry) for name, entry in maintenance["targets"].items()},
+        }
+        disabled = {name: _without_reason(entry) for name, entry in disabled.items()}
+    return HealthResponse(
+        status="ok",
+        maintenance=maintenance if active else None,
+        disabled_targets=disabled or None,
+        warmup=state.warmup,
+        cache_key_version=state.cache.key_version if state.cache else 0,
+        cache_ttl_extended=health_ttl_status(state.targets) or None,
+    )
 
 
 @router.get("/healthz", response_model=StatusResponse)
@@ -102,3 +162,28 @@ async def metrics(request: Request) -> Response:
     """Prometheus metrics endpoint with rate limiting."""
     _check_health_rate_limit(request, "metrics")
     return Response(content=generate_latest(), media_type=CONTENT_TYPE_LATEST)
+
+
+@router.get("/circuit", response_model=CircuitResponse)
+async def circuit(request: Request) -> CircuitResponse:
+    """Circuit breaker state per target, including half-open probe counts.
+
+    Targets that have not served a request yet are reported closed with
+    their configured thresholds. Targets with `regions` also report each
+    region's circuit and latency EWMA.
+    """
+    _check_health_rate_limit(request, "circuit")
+    state = get_app_state()
+    targets = {}
+    for name, target_config in state.targets.items():
+        breaker = circuit_breakers.get(name, target_config.get("circuit"))
+        targets[name] = breaker.snapshot(target_config.get("base_url", "").rstrip("/"))
+        if target_config.get("regions"):
+            targets[name]["regions"] = {
+                region["name"]: {
+                    **breaker.snapshot(region_url(region)),
+                    "latency_ewma_ms": region_router.latency_ms(name, region_url(region)),
+                }
+                for region in target_config["regions"]
+            }
+    return CircuitResponse(targets=targets)
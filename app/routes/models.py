@@ -0,0 +1,100 @@
+"""Model discovery endpoint.
+
+This module provides:
+- GET /models - Models available through each LLM target, with context window,
+  pricing and capability flags
+"""
+import logging
+from typing import Any, Dict, List, Optional
+
+from fastapi import APIRouter, Query, Request
+from pydantic import BaseModel
+
+from reliapi.adapters.llm.factory import detect_provider
+from reliapi.app.dependencies import AppState, get_app_state, verify_api_key
+from reliapi.app.services import create_http_client
+from reliapi.core.free_tier_restrictions import FreeTierRestrictions
+from reliapi.core.model_catalog import live_model_cache, target_models
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Models"])
+
+
+class ModelPricing(BaseModel):
+    """Approximate pricing in USD per 1K tokens."""
+
+    prompt_per_1k_usd: float
+    completion_per_1k_usd: float
+
+
+class ModelInfo(BaseModel):
+    """One model available through a target."""
+
+    id: str
+    target: str
+    provider: Optional[str]
+    context_window: Optional[int]
+    pricing: Optional[ModelPricing]
+    capabilities: Dict[str, bool]
+    source: str
+
+
+class ModelsResponse(BaseModel):
+    """Model discovery response model."""
+
+    models: List[ModelInfo]
+
+
+async def _live_models(state: AppState, target_name: str, target_config: Dict[str, Any], provider: Optional[str]) -> List[str]:
+    """Model IDs from the provider's GET /models (cached; empty if the call fails)."""
+    cached = live_model_cache.get(target_name)
+    if cached is not None:
+        return cached
+
+    client, _, _ = create_http_client(target_config, target_name, state.key_pool_manager, provider)
+    try:
+        response = await client.request("GET", "/models")
+        response.raise_for_status()
+        models = [item["id"] for item in response.json().get("data", []) if item.get("id")]
+    except Exception as e:
+        logger.warning(f"Live model listing failed for target '{target_name}': {e}")
+        return []
+    finally:
+        await client.close()
+
+    live_model_cache.set(target_name, models)
+    return models
+
+
+@router.get(
+    "/models",
+    response_model=ModelsResponse,
+    summary="Available models",
+    description=(
+        "Models available through each configured LLM target, with context window, "
+        "pricing and capability flags (vision, tools, json_mode). Only models the "
+        "calling API key may use are listed."
+    ),
+)
+async def list_models(
+    request: Request,
+    target: Optional[str] = Query(None, description="Only list models of this target"),
+) -> Dict[str, Any]:
+    """Return the model catalog visible to the caller's tier."""
+    state = get_app_state()
+    _, _, tier = verify_api_key(request)
+
+    models = []
+    for target_name, target_config in state.targets.items():
+        llm_config = target_config.get("llm")
+        if llm_config is None or (target and target_name != target):
+            continue
+        provider = llm_config.get("provider") or detect_provider(target_config.get("base_url", ""))
+        live = await _live_models(state, target_name, target_config, provider) if llm_config.get("live_models") else None
+        for entry in target_models(target_name, target_config, provider, live):
+            allowed, _ = FreeTierRestrictions.is_model_allowed(provider or "", entry["id"], tier)
+            if allowed:
+                models.append(entry)
+
+    return {"models": models}
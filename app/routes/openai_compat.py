@@ -0,0 +1,139 @@
+"""OpenAI-compatible endpoint for drop-in migration.
+
+This module provides:
+- POST /v1/chat/completions - OpenAI Chat Completions API shape over the LLM proxy
+
+Point the OpenAI SDK's base URL at `<reliapi>/v1` and pass the ReliAPI key as
+the SDK API key. ReliAPI options travel in headers:
+- X-ReliAPI-Target: target name from config.yaml (default: "openai")
+- Idempotency-Key: idempotency key (read by the LLM proxy route)
+- X-ReliAPI-Cache: cache TTL in seconds
+"""
+import json
+import logging
+from typing import Any, Dict, Optional
+
+from fastapi import APIRouter, HTTPException, Request
+from fastapi.responses import JSONResponse, StreamingResponse
+
+from reliapi.app.openai_compat import (
+    DEFAULT_TARGET,
+    meta_headers,
+    openai_error,
+    parse_sse_event,
+    stream_error,
+    to_llm_proxy_request,
+    to_openai_response,
+    to_openai_stream,
+)
+from reliapi.app.routes.proxy import proxy_llm, retry_after_headers
+from reliapi.app.schemas import OpenAIChatCompletionRequest
+from reliapi.app.services import EMPTY_STREAM_ERROR
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["OpenAI Compatible"])
+
+
+def _cache_ttl(http_request: Request) -> Optional[int]:
+    """Cache TTL from X-ReliAPI-Cache (seconds)."""
+    value = http_request.headers.get("X-ReliAPI-Cache")
+    if value is None:
+        return None
+    try:
+        return max(0, int(value))
+    except ValueError:
+        raise HTTPException(
+            status_code=400,
+            detail={
+                "type": "client_error",
+                "code": "BAD_REQUEST",
+                "message": "X-ReliAPI-Cache must be an integer number of seconds",
+            },
+        )
+
+
+def _http_exception_response(e: HTTPException) -> JSONResponse:
+    """OpenAI error response for auth, free-tier and validation rejections."""
+    detail: Any = e.detail
+    if isinstance(detail, dict):
+        detail = detail.get("error", detail)
+        body = openai_error(
+            detail.get("message", "Request rejected"),
+            detail.get("type", "api_error"),
+            detail.get("code"),
+        )
+    else:
+        body = openai_error(str(detail), "api_error", None)
+    return JSONResponse(content=body, status_code=e.status_code, headers=e.headers)
+
+
+@router.post(
+    "/chat/completions",
+    summary="OpenAI-compatible chat completions",
+    description=(
+        "Accepts the OpenAI Chat Completions request body and returns the OpenAI "
+        "response body, with ReliAPI caching, retries, idempotency and budget caps "
+        "applied. ReliAPI metadata is returned in X-ReliAPI-* headers."
+    ),
+)
+async def chat_completions(
+    body: OpenAIChatCompletionRequest,
+    http_request: Request,
+):
+    """OpenAI Chat Completions API over the LLM proxy."""
+    try:
+        llm_request = to_llm_proxy_request(
+            body,
+            target=http_request.headers.get("X-ReliAPI-Target", DEFAULT_TARGET),
+            cache_ttl=_cache_ttl(http_request),
+        )
+        response = await proxy_llm(llm_request, http_request)
+    except HTTPException as e:
+        return _http_exception_response(e)
+
+    if isinstance(response, StreamingResponse):
+        headers: Dict[str, str] = {
+            name: value for name, value in response.headers.items()
+            if name.lower().startswith("x-")
+        }
+        request_id = response.headers.get("x-request-id", "")
+        events = response.body_iterator
+
+        # Errors before the first token become a regular HTTP error response
+        try:
+            first_event = await events.__anext__()
+        except StopAsyncIteration:
+            status_code, error_body = stream_error(EMPTY_STREAM_ERROR)
+            return JSONResponse(content=error_body, status_code=status_code, headers=headers)
+        name, data = parse_sse_event(first_event)
+        if name == "error":
+            status_code, error_body = stream_error(data)
+            headers.update(retry_after_headers(data.get("retry_after_s")))
+            return JSONResponse(content=error_body, status_code=status_code, headers=headers)
+
+        async def _events():
+            yield first_event
+            async for event in events:
+                yield event
+
+        return StreamingResponse(
+            to_openai_stream(_events(), request_id, body.model),
+            media_type="text/event-stream",
+            headers={**headers, "Cache-Control": "no-cache", "Connection": "keep-alive"},
+        )
+
+    result = json.loads(response.body)
+    status_code, openai_body = to_openai_response(result)
+    return JSONResponse(
+        content=openai_body,
+        status_code=status_code,
+        headers={
+            **meta_headers(result.get("meta") or {}),
+            **retry_after_headers((result.get("error") or {}).get("retry_after_s")),
+            **{
+                name: value for name, value in response.headers.items()
+                if name.lower().startswith(("x-ratelimit-", "x-budget-"))
+            },
+        },
+    )
@@ -4,27 +4,56 @@ This module provides:
 - POST /proxy/http - Universal HTTP proxy with reliability features
 - POST /proxy/llm - LLM proxy with idempotency and budget control
 """
+import json
 import logging
-import uuid
-from typing import Dict, Optional
+import math
+import time
+from email.utils import formatdate
+from typing import Any, AsyncIterator, Dict, List, Optional, Union
 
 from fastapi import APIRouter, HTTPException, Request
 from fastapi.responses import JSONResponse, StreamingResponse
 
 from reliapi.app.dependencies import (
+    apply_tenant_defaults,
     detect_client_profile,
     get_account_id,
     get_app_state,
+    resolve_idempotency_key,
+    resolve_request_id,
+    resolve_request_overrides,
+    resolve_request_tags,
     verify_api_key,
 )
+from reliapi.app.dedup import ProxyResult, run_with_dedup
+from reliapi.app.openai_compat import parse_sse_event
 from reliapi.app.schemas import HTTPProxyRequest, LLMProxyRequest
 from reliapi.app.services import (
+    EMPTY_STREAM_ERROR,
+    HTTPStreamResult,
+    collapse_llm_stream,
     handle_http_proxy,
     handle_llm_proxy,
     handle_llm_stream_generator,
+    prime_llm_stream,
+    raw_cost,
 )
+from reliapi.app.shadow import (
+    SHADOW_HTTP_METHODS,
+    run_http_shadow,
+    run_llm_shadow,
+    select_shadow_target,
+    spawn_shadow,
+)
+from reliapi.core.audit_log import rejection_category
+from reliapi.core.auto_model import AUTO_MODEL, select_auto_model
+from reliapi.core.errors import ErrorCode
 from reliapi.core.free_tier_restrictions import FreeTierRestrictions
+from reliapi.core.language_routing import select_language_route
+from reliapi.core.post_process import post_process_text
+from reliapi.core.response_preview import truncate_body
 from reliapi.core.security import SecurityManager
+from reliapi.core.sse_heartbeat import with_heartbeats
 from reliapi.integrations.routellm import (
     apply_routellm_overrides,
     extract_routellm_decision,
@@ -32,6 +61,7 @@ from reliapi.integrations.routellm import (
 )
 from reliapi.metrics.prometheus import (
     free_tier_abuse_attempts_total,
+    language_routes_total,
     rapidapi_tier_distribution,
     routellm_decisions_total,
     routellm_overrides_total,
@@ -41,6 +71,8 @@ logger = logging.getLogger(__name__)
 
 router = APIRouter(tags=["Proxy"])
 
+FREE_TIER_IP_RPM = 20  # Free tier requests per minute per IP
+
 
 def _check_api_key_format(api_key: Optional[str]) -> None:
     """Validate API key format and raise HTTPException if invalid."""
@@ -86,7 +118,7 @@ def _check_free_tier_rate_limits(
 
     # Check IP rate limit (20 req/min)
     allowed, error = state.rate_limiter.check_ip_rate_limit(
-        client_ip, limit_per_minute=20
+        client_ip, limit_per_minute=FREE_TIER_IP_RPM
     )
     if not allowed:
         free_tier_abuse_attempts_total.labels(
@@ -222,7 +254,7 @@ def _check_llm_free_tier_restrictions(
 
     # Check IP rate limit (20 req/min)
     allowed, error = state.rate_limiter.check_ip_rate_limit(
-        client_ip, limit_per_minute=20
+        client_ip, limit_per_minute=FREE_TIER_IP_RPM
     )
     if not allowed:
         state.rate_limiter.abuse_detector.record_limit_bypass_attempt(
@@ -342,6 +374,469 @@ def _check_llm_free_tier_restrictions(
             )
 
 
+def record_rejection(
+    kind: str,
+    target: Optional[str],
+    api_key: Optional[str],
+    tenant: Optional[str],
+    code: Optional[str],
+    status_code: Optional[int],
+    message: Optional[str] = None,
+    category: Optional[str] = None,
+    request_id: Optional[str] = None,
+    model: Optional[str] = None,
+    cost_estimate_usd: Optional[float] = None,
+) -> None:
+    """Record a budget, rate-limit or policy rejection in the audit log (if enabled).
+
+    `category` defaults to the audit category of `code`; other codes are not audited.
+    """
+    state = get_app_state()
+    category = category or rejection_category(code)
+    if not state.audit_log or not category:
+        return
+    state.audit_log.record(
+        category=category,
+        reason_code=code or "UNKNOWN",
+        kind=kind,
+        target=target,
+        key_id=get_account_id(api_key),
+        tenant=tenant,
+        request_id=request_id,
+        model=model,
+        cost_estimate_usd=cost_estimate_usd,
+        status_code=status_code,
+        message=message,
+    )
+
+
+def record_result_rejection(
+    kind: str,
+    target: str,
+    api_key: Optional[str],
+    tenant: Optional[str],
+    result: ProxyResult,
+) -> None:
+    """Audit a proxy result that was rejected by ReliAPI (budget caps, rate limits)."""
+    if result.success:
+        return
+    details = result.error.details or {}
+    record_rejection(
+        kind=kind,
+        target=target,
+        api_key=api_key,
+        tenant=tenant,
+        code=result.error.code,
+        status_code=result.error.status_code,
+        message=result.error.message,
+        request_id=result.meta.request_id,
+        model=result.meta.model,
+        cost_estimate_usd=details.get("cost_estimate_usd", result.meta.cost_estimate_usd),
+    )
+
+
+def record_check_rejection(
+    error: HTTPException,
+    kind: str,
+    target: str,
+    api_key: Optional[str],
+    tenant: Optional[str],
+) -> None:
+    """Audit a request rejected by the free tier or tenant limit checks (429 rate limit, 402 budget, 403 policy)."""
+    detail = error.detail if isinstance(error.detail, dict) else {}
+    record_rejection(
+        kind=kind,
+        target=target,
+        api_key=api_key,
+        tenant=tenant,
+        code=detail.get("code"),
+        status_code=error.status_code,
+        message=detail.get("message"),
+        category=rejection_category(detail.get("code")) or ("rate_limit" if error.status_code == 429 else "policy"),
+    )
+
+
+async def _audit_stream_rejection(
+    events: AsyncIterator[str],
+    target: str,
+    api_key: Optional[str],
+    tenant: Optional[str],
+    request_id: str,
+    model: Optional[str],
+) -> AsyncIterator[str]:
+    """Pass a stream through, auditing it if it was rejected before the first chunk."""
+    try:
+        first_event = await events.__anext__()
+    except StopAsyncIteration:
+        first_event = f"event: error\ndata: {json.dumps(EMPTY_STREAM_ERROR)}\n\n"
+    name, data = parse_sse_event(first_event)
+    if name == "error":
+        record_rejection(
+            kind="llm",
+            target=target,
+            api_key=api_key,
+            tenant=tenant,
+            code=data.get("code"),
+            status_code=data.get("upstream_status"),
+            message=data.get("message"),
+            request_id=request_id,
+            model=model,
+            cost_estimate_usd=(data.get("details") or {}).get("cost_estimate_usd"),
+        )
+    yield first_event
+    async for event in events:
+        yield event
+
+
+def retry_after_headers(retry_after_s: Optional[float]) -> Dict[str, str]:
+    """Retry-After header (whole seconds, rounded up) for a rejection that sets retry_after_s."""
+    if retry_after_s is None:
+        return {}
+    return {"Retry-After": str(max(math.ceil(retry_after_s), 0))}
+
+
+def tenant_limit_headers(tenant: Optional[str]) -> Dict[str, str]:
+    """X-RateLimit-* and X-Budget-* headers for the calling tenant (none without limits)."""
+    state = get_app_state()
+    if not tenant or not state.tenant_limits or not state.config_loader:
+        return {}
+    return state.tenant_limits.headers(tenant, state.config_loader.get_tenant(tenant))
+
+
+def rate_limit_headers(tenant: Optional[str], http_request: Request, tier: str) -> Dict[str, str]:
+    """tenant_limit_headers, plus the free tier's per-IP limit when the tenant sets no rate_limit_rpm.
+
+    The free-tier counter is only read here; the request was counted by its check.
+    """
+    headers = tenant_limit_headers(tenant)
+    state = get_app_state()
+    if "X-RateLimit-Remaining" in headers or tier != "free" or not state.rate_limiter:
+        return headers
+    client_ip = http_request.client.host if http_request.client else "unknown"
+    peeked = state.rate_limiter.peek_ip_rate_limit(client_ip, limit_per_minute=FREE_TIER_IP_RPM)
+    if peeked is not None:
+        remaining, reset_at = peeked
+        headers.update({
+            "X-RateLimit-Limit": str(FREE_TIER_IP_RPM),
+            "X-RateLimit-Remaining": str(remaining),
+            "X-RateLimit-Reset": str(reset_at),
+        })
+    return headers
+
+
+def with_rate_limit_headers(
+    e: HTTPException, tenant: Optional[str], http_request: Request, tier: str
+) -> HTTPException:
+    """Add the caller's rate-limit headers to a rejection, keeping any it already sets."""
+    e.headers = {**rate_limit_headers(tenant, http_request, tier), **(e.headers or {})}
+    return e
+
+
+def tenant_cost_multiplier(tenant: Optional[str]) -> float:
+    """The tenant's cost_multiplier (resale markup on reported cost), 1.0 without one."""
+    state = get_app_state()
+    if not tenant or not state.config_loader:
+        return 1.0
+    return (state.config_loader.get_tenant(tenant) or {}).get("cost_multiplier", 1.0)
+
+
+def streaming_config() -> Dict[str, Any]:
+    """`streaming` settings (proxy buffering header, SSE heartbeats)."""
+    state = get_app_state()
+    return state.config_loader.get_streaming() if state.config_loader else {}
+
+
+def collapse_stream_requested(request_value: Optional[bool], http_request: Request) -> bool:
+    """Whether to buffer a stream=true request into one response.
+
+    The request field wins, then the X-ReliAPI-Collapse-Stream header, then streaming.collapse.
+    """
+    if request_value is not None:
+        return request_value
+    header = http_request.headers.get("X-ReliAPI-Collapse-Stream")
+    if header is not None:
+        return header.strip().lower() in ("1", "true", "yes")
+    return bool(streaming_config().get("collapse"))
+
+
+def post_process_result(result: Any, mode: Optional[str], include_raw: bool = False) -> None:
+    """Apply post_process to a successful LLM result's content and choices.
+
+    The data is replaced, not edited in place, so the cached and idempotent
+    copies keep the raw completion.
+    """
+    if not result.success or mode in (None, "none") or not isinstance(result.data, dict):
+        return
+    data = result.data
+    processed = {**data, "content": post_process_text(data.get("content"), mode)}
+    if data.get("choices"):
+        processed["choices"] = [
+            {**choice, "message": {
+                **choice["message"], "content": post_process_text(choice["message"].get("content"), mode),
+            }} if isinstance(choice.get("message"), dict) else choice
+            for choice in data["choices"]
+        ]
+    if include_raw:
+        processed["raw_content"] = data.get("content")
+    if processed.get("content") != data.get("content") or processed.get("choices") != data.get("choices"):
+        result.meta.post_processed = True
+    result.data = processed
+
+
+def stream_buffering_headers() -> Dict[str, str]:
+    """X-Accel-Buffering: no when streaming.disable_proxy_buffering is set."""
+    return {"X-Accel-Buffering": "no"} if streaming_config().get("disable_proxy_buffering") else {}
+
+
+def check_tenant_limits(tenant: Optional[str]) -> None:
+    """Count a request against the tenant's rate_limit_rpm and budget.
+
+    Raises:
+        HTTPException: 429 RATE_LIMIT_RELIAPI or 402 BUDGET_EXCEEDED, with Retry-After
+            until the minute or budget period resets
+    """
+    state = get_app_state()
+    if not tenant or not state.tenant_limits or not state.config_loader:
+        return
+    tenant_config = state.config_loader.get_tenant(tenant) or {}
+    rejected = state.tenant_limits.acquire(tenant, tenant_config)
+    if rejected is None:
+        return
+    limit, retry_after_s = rejected
+    headers = {**tenant_limit_headers(tenant), **retry_after_headers(retry_after_s)}
+    if limit == "budget":
+        budget = tenant_config["budget"]
+        raise HTTPException(
+            status_code=402,
+            detail={
+                "type": "budget_error",
+                "code": ErrorCode.BUDGET_EXCEEDED.value,
+                "message": f"Budget of ${budget['limit_usd']} per {budget.get('period', 'month')} used up",
+            },
+            headers=headers,
+        )
+    raise HTTPException(
+        status_code=429,
+        detail={
+            "type": "rate_limit_error",
+            "code": ErrorCode.RATE_LIMIT_RELIAPI.value,
+            "message": f"Rate limit exceeded: {tenant_config['rate_limit_rpm']} requests per minute",
+        },
+        headers=headers,
+    )
+
+
+def max_messages(tenant: Optional[str], target_name: str) -> int:
+    """Messages allowed in one request: the target's llm.max_messages, lowered by the tenant's max_messages."""
+    state = get_app_state()
+    limit = ((state.targets.get(target_name) or {}).get("llm") or {}).get("max_messages", 500)
+    if tenant and state.config_loader:
+        tenant_limit = (state.config_loader.get_tenant(tenant) or {}).get("max_messages")
+        if tenant_limit:
+            limit = min(limit, tenant_limit)
+    return limit
+
+
+def turn_limit_rejection(tenant: Optional[str], target_name: str, messages: List[Any]) -> Optional[Dict[str, Any]]:
+    """422 TOO_MANY_TURNS detail if the conversation is longer than max_messages (None if it is not)."""
+    limit = max_messages(tenant, target_name)
+    if len(messages) <= limit:
+        return None
+    return {
+        "type": "client_error",
+        "code": ErrorCode.TOO_MANY_TURNS.value,
+        "message": f"Conversation has {len(messages)} messages; at most {limit} are allowed",
+        "details": {"messages": len(messages), "max_messages": limit},
+    }
+
+
+def http_cache_headers(result: ProxyResult) -> Dict[str, str]:
+    """Age, Date and X-ReliAPI-Cache (HIT/MISS/STALE) for a /proxy/http response.
+
+    Hits carry the stored upstream Date and the entry's age; misses carry
+    the live upstream Date and Age (0 if the upstream sent none). Hits
+    past the upstream max-age or the entry's TTL (cache.health_ttl) are STALE.
+    """
+    meta = result.meta
+    if not meta.cache_hit:
+        status = "MISS"
+    else:
+        status = "STALE" if meta.cache_stale or meta.cache_ttl_extended else "HIT"
+    headers = {"X-ReliAPI-Cache": status}
+    upstream_headers = {
+        name.lower(): value
+        for name, value in ((getattr(result, "data", None) or {}).get("headers") or {}).items()
+    }
+    if meta.cache_hit and meta.cache_age_s is not None:
+        headers["Age"] = str(meta.cache_age_s)
+        headers["Date"] = upstream_headers.get("date") or formatdate(time.time() - meta.cache_age_s, usegmt=True)
+    elif not meta.cache_hit and result.success:
+        headers["Age"] = upstream_headers.get("age", "0")
+        if upstream_headers.get("date"):
+            headers["Date"] = upstream_headers["date"]
+    return headers
+
+
+def record_request_log(
+    request_id: str,
+    kind: str,
+    target: str,
+    payload: Dict[str, Any],
+    result: ProxyResult,
+    tenant: Optional[str],
+) -> Dict[str, str]:
+    """Append a completed request to the request log (if enabled).
+
+    The entry ID is set as meta.request_log_id, and the returned
+    X-ReliAPI-Request-Log-Id header carries it, so callers can pass it to /replay.
+    """
+    state = get_app_state()
+    if not state.request_log:
+        return {}
+    entry_id = state.request_log.record(
+        request_id=request_id,
+        kind=kind,
+        target=target,
+        payload=payload,
+        status="success" if result.success else "error",
+        latency_ms=result.meta.duration_ms,
+        status_code=200 if result.success else (result.error.status_code or 500),
+        model=result.meta.model,
+        cost_usd=result.meta.cost_usd,
+        retries=result.meta.retries,
+        cache_hit=result.meta.cache_hit,
+        tenant=tenant,
+    )
+    if not entry_id:
+        return {}
+    result.meta.request_log_id = entry_id
+    return {"X-ReliAPI-Request-Log-Id": entry_id}
+
+
+def record_debug_trace(
+    kind: str,
+    target: str,
+    api_key: Optional[str],
+    tenant: Optional[str],
+    result: Union[ProxyResult, HTTPStreamResult],
+    tags: Optional[Dict[str, str]],
+    metadata: Optional[Any],
+) -> None:
+    """Add a completed request to the debug trace buffer (if enabled)."""
+    state = get_app_state()
+    if not state.debug_trace:
+        return
+    meta = result.meta
+    error = None if result.success else result.error
+    state.debug_trace.record(
+        request_id=meta.request_id,
+        kind=kind,
+        target=target,
+        model=meta.model,
+        provider=meta.provider,
+        status="success" if result.success else "error",
+        status_code=getattr(result, "status_code", None) or (error.status_code if error else 200),
+        error_code=error.code if error else None,
+        error_message=error.message if error else None,
+        cache_hit=meta.cache_hit,
+        idempotent_hit=meta.idempotent_hit,
+        deduplicated=bool(meta.deduplicated),
+        streamed=isinstance(result, HTTPStreamResult),
+        retries=meta.retries,
+        duration_ms=meta.duration_ms,
+        upstream_ms=meta.upstream_ms,
+        queue_ms=meta.queue_ms,
+        overhead_ms=meta.overhead_ms,
+        cost_usd=meta.cost_usd,
+        cache_ttl_s=meta.cache_ttl_s,
+        tenant=tenant,
+        key_id=get_account_id(api_key),
+        tags=tags or None,
+        metadata=metadata,
+    )
+
+
+async def _tee_stream(
+    events: AsyncIterator[str],
+    target: str,
+    tenant: Optional[str],
+    request_id: str,
+    messages: List[Dict[str, Any]],
+    tags: Optional[Dict[str, str]],
+) -> AsyncIterator[str]:
+    """Pass an LLM stream through unchanged, queueing it for the stream_tee sink once it is done."""
+    start_time = time.time()
+    record: Dict[str, Any] = {}
+    parts: List[str] = []
+    async for event in events:
+        try:
+            name, data = parse_sse_event(event)
+            if name == "meta":
+                record.update(provider=data.get("provider"), model=data.get("model"))
+                record.update(cache_hit=bool(data.get("cache_hit")), idempotent_hit=bool(data.get("idempotent_hit")))
+            elif name == "chunk":
+                parts.append(data.get("delta") or "")
+            elif name == "done":
+                get_app_state().stream_tee.submit(
+                    "".join(parts),
+                    messages=messages,
+                    request_id=request_id,
+                    target=target,
+                    tenant=tenant,
+                    tags=tags or None,
+                    finish_reason=data.get("finish_reason"),
+                    usage=data.get("usage"),
+                    cost_usd=data.get("cost_usd"),
+                    duration_ms=int((time.time() - start_time) * 1000),
+                    **record,
+                )
+        except Exception as e:
+            # The tee never breaks the client stream
+            logger.warning(f"Stream tee skipped request {request_id}: {e}")
+        yield event
+
+
+async def _trace_stream(
+    events: AsyncIterator[str],
+    target: str,
+    model: Optional[str],
+    api_key: Optional[str],
+    tenant: Optional[str],
+    request_id: str,
+    tags: Optional[Dict[str, str]],
+    metadata: Optional[Any],
+) -> AsyncIterator[str]:
+    """Pass an LLM stream through, adding its debug trace when it ends (or the client leaves)."""
+    start_time = time.time()
+    trace: Dict[str, Any] = {"model": model, "status": "cancelled", "retries": 0}
+    try:
+        async for event in events:
+            name, data = parse_sse_event(event)
+            if name == "meta":
+                trace.update(model=data.get("model", model), provider=data.get("provider"))
+                trace.update(cache_hit=bool(data.get("cache_hit")), idempotent_hit=bool(data.get("idempotent_hit")))
+            elif name == "done":
+                trace.update(status="success", status_code=200, retries=data.get("retries") or 0)
+                trace["cost_usd"] = data.get("cost_usd")
+            elif name == "error":
+                trace.update(status="error", status_code=data.get("upstream_status"), error_code=data.get("code"))
+                trace["error_message"] = data.get("message")
+            yield event
+    finally:
+        get_app_state().debug_trace.record(
+            request_id=request_id,
+            kind="llm",
+            target=target,
+            streamed=True,
+            duration_ms=int((time.time() - start_time) * 1000),
+            tenant=tenant,
+            key_id=get_account_id(api_key),
+            tags=tags or None,
+            metadata=metadata,
+            **trace,
+        )
+
+
 @router.post(
     "/proxy/http",
     summary="Proxy HTTP request",
@@ -354,7 +849,7 @@ def _check_llm_free_tier_restrictions(
 async def proxy_http(
     request: HTTPProxyRequest,
     http_request: Request,
-) -> JSONResponse:
+) -> Union[JSONResponse, StreamingResponse]:
     """Universal HTTP proxy endpoint for any HTTP API."""
     state = get_app_state()
 
@@ -364,36 +859,141 @@ async def proxy_http(
     # Validate API key format
     _check_api_key_format(api_key)
 
+    # Idempotency key may come as Idempotency-Key header (body field wins), scoped per idempotency_scope
+    request.idempotency_key = resolve_idempotency_key(request.idempotency_key, http_request, api_key)
+
+    # Timeout/retry overrides from X-ReliAPI-* headers, clamped to server ceilings
+    overrides = resolve_request_overrides(http_request)
+
+    # Tenant defaults for parameters the request omitted
+    resolved_params = apply_tenant_defaults(tenant, request.target, request)
+
     # Check rate limits for free tier
-    _check_free_tier_rate_limits(http_request, api_key, tier, endpoint="http")
+    try:
+        _check_free_tier_rate_limits(http_request, api_key, tier, endpoint="http")
+        check_tenant_limits(tenant)
+    except HTTPException as e:
+        record_check_rejection(e, "http", request.target, api_key, tenant)
+        raise with_rate_limit_headers(e, tenant, http_request, tier)
 
-    # Generate request ID
-    request_id = f"req_{uuid.uuid4().hex[:16]}"
+    # Client-supplied X-Request-Id, or a generated one
+    request_id = resolve_request_id(http_request)
 
     # Detect client profile
     client_profile_name = detect_client_profile(http_request, tenant=tenant)
 
-    result = await handle_http_proxy(
+    target_config = state.targets.get(request.target, {})
+    payload = {
+        "method": request.method,
+        "path": request.path,
+        "headers": request.headers,
+        "query": request.query,
+        "body": request.body,
+    }
+    result = await run_with_dedup(
+        deduplicator=state.deduplicator,
+        target_config=target_config,
+        kind="http",
         target_name=request.target,
-        method=request.method,
-        path=request.path,
-        headers=request.headers,
-        query=request.query,
-        body=request.body,
-        idempotency_key=request.idempotency_key,
-        cache_ttl=request.cache,
-        targets=state.targets,
-        cache=state.cache,
-        idempotency=state.idempotency,
-        key_pool_manager=state.key_pool_manager,
-        rate_scheduler=state.rate_scheduler,
-        client_profile_name=client_profile_name,
-        client_profile_manager=state.client_profile_manager,
+        payload=payload,
         request_id=request_id,
         tenant=tenant,
-        tier=tier,
+        idempotency_key=request.idempotency_key,
+        call=lambda: handle_http_proxy(
+            target_name=request.target,
+            method=request.method,
+            path=request.path,
+            headers=request.headers,
+            query=request.query,
+            body=request.body,
+            idempotency_key=request.idempotency_key,
+            cache_ttl=request.cache,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            key_pool_manager=state.key_pool_manager,
+            rate_scheduler=state.rate_scheduler,
+            client_profile_name=client_profile_name,
+            client_profile_manager=state.client_profile_manager,
+            request_id=request_id,
+            tenant=tenant,
+            tier=tier,
+            overrides=overrides,
+            client_ip=http_request.client.host if http_request.client else None,
+            hedge=request.hedge,
+        ),
     )
 
+    # content_types stream rule: the upstream body is relayed with its own status and headers
+    if isinstance(result, HTTPStreamResult):
+        if state.usage_store:
+            state.usage_store.record(
+                request_id=request_id,
+                kind="http",
+                target=request.target,
+                status="success",
+                latency_ms=result.meta.duration_ms,
+                tags=resolve_request_tags(tenant, request.tags),
+                tenant=tenant,
+            )
+        record_debug_trace(
+            "http", request.target, api_key, tenant, result, resolve_request_tags(tenant, request.tags), request.metadata,
+        )
+        return StreamingResponse(
+            result.chunks,
+            status_code=result.status_code,
+            headers={
+                **result.headers,
+                "X-Request-ID": request_id,
+                **rate_limit_headers(tenant, http_request, tier),
+                **stream_buffering_headers(),
+            },
+        )
+
+    if resolved_params:
+        result.meta.resolved_params = resolved_params
+    result.meta.metadata = request.metadata
+
+    # Mirror live traffic to shadow target (response is not affected)
+    shadow_config = select_shadow_target(target_config)
+    if (
+        shadow_config
+        and request.method in SHADOW_HTTP_METHODS
+        and not (result.meta.cache_hit or result.meta.deduplicated)
+    ):
+        spawn_shadow(run_http_shadow(
+            primary=result,
+            shadow_config=shadow_config,
+            target_name=request.target,
+            method=request.method,
+            path=request.path,
+            headers=request.headers,
+            query=request.query,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            request_id=request_id,
+            tenant=tenant,
+        ))
+
+    # Record usage for cost attribution
+    if state.usage_store:
+        state.usage_store.record(
+            request_id=request_id,
+            kind="http",
+            target=request.target,
+            status="success" if result.success else "error",
+            latency_ms=result.meta.duration_ms,
+            tags=resolve_request_tags(tenant, request.tags),
+            tenant=tenant,
+        )
+
+    request_log_headers = record_request_log(request_id, "http", request.target, payload, result, tenant)
+    record_debug_trace(
+        "http", request.target, api_key, tenant, result, resolve_request_tags(tenant, request.tags), request.metadata,
+    )
+    record_result_rejection("http", request.target, api_key, tenant, result)
+
     # Record usage for RapidAPI tracking
     if state.rapidapi_client and api_key:
         await state.rapidapi_client.record_usage(
@@ -404,6 +1004,13 @@ async def proxy_http(
         )
         rapidapi_tier_distribution.labels(tier=tier).inc()
 
+    if request.max_response_chars and result.success and isinstance(result.data, dict):
+        # Only the returned copy is shortened; cache and idempotency entries keep the full body
+        body, truncated = truncate_body(result.data.get("body"), request.max_response_chars)
+        if truncated:
+            result.data = {**result.data, "body": body}
+            result.meta.truncated = True
+
     status_code = 200 if result.success else (result.error.status_code or 500)
     return JSONResponse(
         content=result.model_dump(),
@@ -413,6 +1020,10 @@ async def proxy_http(
             "X-Cache-Hit": str(result.meta.cache_hit).lower(),
             "X-Retries": str(result.meta.retries),
             "X-Duration-MS": str(result.meta.duration_ms),
+            **http_cache_headers(result),
+            **retry_after_headers(None if result.success else result.error.retry_after_s),
+            **rate_limit_headers(tenant, http_request, tier),
+            **request_log_headers,
         },
     )
 
@@ -440,11 +1051,17 @@ async def proxy_llm(
     # Validate API key format
     _check_api_key_format(api_key)
 
-    # Check LLM-specific free tier restrictions
-    _check_llm_free_tier_restrictions(http_request, request, api_key, tier)
+    # Idempotency key may come as Idempotency-Key header (body field wins), scoped per idempotency_scope
+    request.idempotency_key = resolve_idempotency_key(request.idempotency_key, http_request, api_key)
+
+    # Timeout/retry overrides from X-ReliAPI-* headers, clamped to server ceilings
+    overrides = resolve_request_overrides(http_request)
 
-    # Generate request ID
-    request_id = f"req_{uuid.uuid4().hex[:16]}"
+    # Tenant defaults for parameters the request omitted
+    resolved_params = apply_tenant_defaults(tenant, request.target, request)
+
+    # Client-supplied X-Request-Id, or a generated one
+    request_id = resolve_request_id(http_request)
 
     # Extract RouteLLM routing decision from headers
     routellm_decision = extract_routellm_decision(dict(http_request.headers))
@@ -476,6 +1093,55 @@ async def proxy_llm(
 
         routellm_metrics.record_decision(routellm_decision)
 
+    # Cost-based selection for model "auto" (cheapest candidate meeting constraints)
+    auto_selected_model = None
+    if resolved_model == AUTO_MODEL:
+        resolved_target, resolved_model, _ = select_auto_model(
+            resolved_target,
+            state.targets,
+            request.messages,
+            request.max_tokens,
+            request.constraints.model_dump() if request.constraints else None,
+        )
+        auto_selected_model = resolved_model or (
+            state.targets.get(resolved_target, {}).get("llm") or {}
+        ).get("default_model")
+
+    # Language-based routing, only for requests that left the model to the proxy
+    detected_language = language_selected_model = None
+    if request.model is None and resolved_model is None:
+        routing_target = resolved_target
+        resolved_target, resolved_model, detected_language = select_language_route(
+            resolved_target, state.targets, request.messages,
+        )
+        if detected_language:
+            routed = resolved_model is not None or resolved_target != routing_target
+            if routed:
+                language_selected_model = resolved_model or (
+                    state.targets.get(resolved_target, {}).get("llm") or {}
+                ).get("default_model")
+            language_routes_total.labels(
+                target=routing_target, language=detected_language, routed=str(routed).lower(),
+            ).inc()
+
+    # Structural guard on conversation length, checked against the target the request goes to,
+    # before the rate limits so a rejected conversation does not use up the caller's window
+    turn_limit = turn_limit_rejection(tenant, resolved_target, request.messages)
+    if turn_limit:
+        error = HTTPException(status_code=422, detail=turn_limit)
+        record_check_rejection(error, "llm", resolved_target, api_key, tenant)
+        raise with_rate_limit_headers(error, tenant, http_request, tier)
+
+    # Check LLM-specific free tier restrictions
+    try:
+        _check_llm_free_tier_restrictions(http_request, request, api_key, tier)
+        check_tenant_limits(tenant)
+    except HTTPException as e:
+        record_check_rejection(e, "llm", request.target, api_key, tenant)
+        raise with_rate_limit_headers(e, tenant, http_request, tier)
+
+    cost_multiplier = tenant_cost_multiplier(tenant)
+
     # Handle streaming requests
     if request.stream:
         generator = handle_llm_stream_generator(
@@ -494,19 +1160,85 @@ async def proxy_llm(
             request_id=request_id,
             tenant=tenant,
             tier=tier,
+            usage_store=state.usage_store,
+            tags=resolve_request_tags(tenant, request.tags),
+            model_rate_limiter=state.model_rate_limiter,
+            response_format=request.response_format,
+            overrides=overrides,
+            logit_bias=request.logit_bias,
+            seed=request.seed,
+            client_ip=http_request.client.host if http_request.client else None,
+            metadata=request.metadata,
+            cost_multiplier=cost_multiplier,
         )
 
+        if state.stream_tee:
+            generator = _tee_stream(
+                generator, resolved_target, tenant, request_id, request.messages,
+                resolve_request_tags(tenant, request.tags),
+            )
+
+        if collapse_stream_requested(request.collapse_stream, http_request):
+            # Streamed from the provider, returned as one response (usage is recorded by the stream)
+            result = await collapse_llm_stream(generator, resolved_target, request_id)
+            result.meta.auto_selected_model = auto_selected_model
+            result.meta.detected_language = detected_language
+            result.meta.language_selected_model = language_selected_model
+            post_process_result(result, request.post_process, request.include_raw_content)
+            record_debug_trace(
+                "llm", resolved_target, api_key, tenant, result,
+                resolve_request_tags(tenant, request.tags), request.metadata,
+            )
+            record_result_rejection("llm", resolved_target, api_key, tenant, result)
+            response_headers = {
+                "X-Request-ID": request_id,
+                "X-Cache-Hit": str(result.meta.cache_hit).lower(),
+                "X-Retries": str(result.meta.retries),
+                "X-Duration-MS": str(result.meta.duration_ms),
+            }
+            if routellm_decision:
+                response_headers.update(routellm_decision.to_response_headers())
+            if not result.success:
+                response_headers.update(retry_after_headers(result.error.retry_after_s))
+            response_headers.update(rate_limit_headers(tenant, http_request, tier))
+            return JSONResponse(
+                content=result.model_dump(),
+                status_code=200 if result.success else (result.error.status_code or 500),
+                headers=response_headers,
+            )
+
+        # Read the meta event so the cost estimate can be sent as a header
+        stream_meta, generator = await prime_llm_stream(generator)
+        if stream_meta is None:
+            # Stream was rejected before it started (budget cap, rate limit, ...)
+            generator = _audit_stream_rejection(
+                generator, resolved_target, api_key, tenant, request_id, resolved_model,
+            )
+        if state.debug_trace:
+            generator = _trace_stream(
+                generator, resolved_target, resolved_model, api_key, tenant, request_id,
+                resolve_request_tags(tenant, request.tags), request.metadata,
+            )
+
         # Build response headers including RouteLLM correlation
         response_headers: Dict[str, str] = {
             "X-Request-ID": request_id,
             "Cache-Control": "no-cache",
             "Connection": "keep-alive",
         }
+        if stream_meta and stream_meta.get("cost_estimate_usd") is not None:
+            # Upper bound (prompt + max_tokens); actual cost is in the final `done` event
+            response_headers["X-ReliAPI-Estimated-Cost"] = f"{stream_meta['cost_estimate_usd']:.6f}"
+        if detected_language:
+            response_headers["X-ReliAPI-Detected-Language"] = detected_language
         if routellm_decision:
             response_headers.update(routellm_decision.to_response_headers())
+        # Budget is as of the stream start; its cost is recorded when it ends
+        response_headers.update(rate_limit_headers(tenant, http_request, tier))
+        response_headers.update(stream_buffering_headers())
 
         return StreamingResponse(
-            generator,
+            with_heartbeats(generator, streaming_config().get("heartbeat_interval_s")),
             media_type="text/event-stream",
             headers=response_headers,
         )
@@ -515,36 +1247,119 @@ async def proxy_llm(
     client_profile_name = detect_client_profile(http_request, tenant=tenant)
 
     # Handle non-streaming requests
-    result = await handle_llm_proxy(
+    payload = {
+        "messages": request.messages,
+        "model": resolved_model,
+        "max_tokens": request.max_tokens,
+        "temperature": request.temperature,
+        "top_p": request.top_p,
+        "stop": request.stop,
+        "response_format": request.response_format,
+        "n": request.n,
+        "logit_bias": request.logit_bias,
+        "seed": request.seed,
+        "logprobs": request.logprobs,
+        "top_logprobs": request.top_logprobs,
+        "json_schema": request.json_schema,
+    }
+    result = await run_with_dedup(
+        deduplicator=state.deduplicator,
+        target_config=state.targets.get(resolved_target, {}),
+        kind="llm",
         target_name=resolved_target,
-        messages=request.messages,
-        model=resolved_model,
-        max_tokens=request.max_tokens,
-        temperature=request.temperature,
-        top_p=request.top_p,
-        stop=request.stop,
-        stream=False,
-        idempotency_key=request.idempotency_key,
-        cache_ttl=request.cache,
-        targets=state.targets,
-        cache=state.cache,
-        idempotency=state.idempotency,
+        payload=payload,
         request_id=request_id,
         tenant=tenant,
-        tier=tier,
-        key_pool_manager=state.key_pool_manager,
-        rate_scheduler=state.rate_scheduler,
-        client_profile_name=client_profile_name,
-        client_profile_manager=state.client_profile_manager,
+        idempotency_key=request.idempotency_key,
+        call=lambda: handle_llm_proxy(
+            target_name=resolved_target,
+            messages=request.messages,
+            model=resolved_model,
+            max_tokens=request.max_tokens,
+            temperature=request.temperature,
+            top_p=request.top_p,
+            stop=request.stop,
+            stream=False,
+            idempotency_key=request.idempotency_key,
+            cache_ttl=request.cache,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            request_id=request_id,
+            tenant=tenant,
+            tier=tier,
+            key_pool_manager=state.key_pool_manager,
+            rate_scheduler=state.rate_scheduler,
+            client_profile_name=client_profile_name,
+            client_profile_manager=state.client_profile_manager,
+            model_rate_limiter=state.model_rate_limiter,
+            response_format=request.response_format,
+            overrides=overrides,
+            n=request.n,
+            logit_bias=request.logit_bias,
+            seed=request.seed,
+            logprobs=request.logprobs,
+            top_logprobs=request.top_logprobs,
+            pins=state.pinned_responses,
+            client_ip=http_request.client.host if http_request.client else None,
+            cost_multiplier=cost_multiplier,
+            json_schema=request.json_schema,
+        ),
+    )
+
+    if resolved_params:
+        result.meta.resolved_params = resolved_params
+    result.meta.metadata = request.metadata
+
+    # Mirror live traffic to shadow target (response is not affected)
+    shadow_config = select_shadow_target(state.targets.get(resolved_target, {}))
+    if shadow_config and not (result.meta.cache_hit or result.meta.idempotent_hit or result.meta.deduplicated):
+        spawn_shadow(run_llm_shadow(
+            primary=result,
+            shadow_config=shadow_config,
+            target_name=resolved_target,
+            messages=request.messages,
+            max_tokens=request.max_tokens,
+            temperature=request.temperature,
+            top_p=request.top_p,
+            stop=request.stop,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            request_id=request_id,
+            tenant=tenant,
+            key_pool_manager=state.key_pool_manager,
+            cost_multiplier=cost_multiplier,
+        ))
+
+    cost_usd = result.meta.cost_usd or 0.0
+
+    # Record usage for cost attribution
+    if state.usage_store:
+        usage = (result.data.get("usage") if result.success and isinstance(result.data, dict) else None) or {}
+        state.usage_store.record(
+            request_id=request_id,
+            kind="llm",
+            target=resolved_target,
+            status="success" if result.success else "error",
+            latency_ms=result.meta.duration_ms,
+            model=result.meta.model,
+            cost_usd=cost_usd,
+            raw_cost_usd=raw_cost(cost_usd, cost_multiplier),
+            tags=resolve_request_tags(tenant, request.tags),
+            tenant=tenant,
+            prompt_tokens=usage.get("prompt_tokens"),
+            completion_tokens=usage.get("completion_tokens"),
+        )
+
+    request_log_headers = record_request_log(request_id, "llm", resolved_target, payload, result, tenant)
+    record_debug_trace(
+        "llm", resolved_target, api_key, tenant, result, resolve_request_tags(tenant, request.tags), request.metadata,
     )
+    record_result_rejection("llm", resolved_target, api_key, tenant, result)
 
     # Record usage for RapidAPI tracking
     if state.rapidapi_client and api_key:
-        cost_usd = (
-            result.data.usage.estimated_cost_usd
-            if result.success and result.data and result.data.usage
-            else 0.0
-        )
         await state.rapidapi_client.record_usage(
             api_key=api_key,
             endpoint="/proxy/llm",
@@ -554,6 +1369,11 @@ async def proxy_llm(
         )
         rapidapi_tier_distribution.labels(tier=tier).inc()
 
+    result.meta.auto_selected_model = auto_selected_model
+    result.meta.detected_language = detected_language
+    result.meta.language_selected_model = language_selected_model
+    post_process_result(result, request.post_process, request.include_raw_content)
+
     # Add RouteLLM correlation to response meta
     if routellm_decision:
         result.meta.routellm_decision_id = routellm_decision.decision_id
@@ -570,6 +1390,10 @@ async def proxy_llm(
     }
     if routellm_decision:
         response_headers.update(routellm_decision.to_response_headers())
+    if not result.success:
+        response_headers.update(retry_after_headers(result.error.retry_after_s))
+    response_headers.update(rate_limit_headers(tenant, http_request, tier))
+    response_headers.update(request_log_headers)
 
     status_code = 200 if result.success else (result.error.status_code or 500)
     return JSONResponse(
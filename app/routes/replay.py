@@ -0,0 +1,146 @@
+"""Replay endpoint for reproducing logged requests.
+
+This module provides:
+- POST /replay - Re-send a request recorded in the request log
+
+Requests are logged when REQUEST_LOG_ENABLED=true. Replays run through the
+normal proxy pipeline without the original idempotency key, are marked with
+`meta.replay_of`, and are not recorded in usage/billing unless
+`count_budget` is set.
+"""
+import logging
+import uuid
+from typing import Union
+
+from fastapi import APIRouter, HTTPException, Request
+from fastapi.responses import JSONResponse, StreamingResponse
+
+from reliapi.app.dependencies import get_app_state, verify_api_key
+from reliapi.app.schemas import ReplayRequest
+from reliapi.app.routes.proxy import tenant_cost_multiplier
+from reliapi.app.services import HTTPStreamResult, handle_http_proxy, handle_llm_proxy, raw_cost
+from reliapi.core.errors import ErrorCode
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Replay"])
+
+
+@router.post(
+    "/replay",
+    summary="Replay a logged request",
+    description=(
+        "Re-send a request from the request log by entry ID. "
+        "Replays do not count towards usage or billing unless count_budget is true."
+    ),
+)
+async def replay(
+    body: ReplayRequest,
+    http_request: Request,
+) -> Union[JSONResponse, StreamingResponse]:
+    """Replay a request log entry for the caller's tenant."""
+    state = get_app_state()
+    api_key, tenant, tier = verify_api_key(http_request)
+
+    entry = state.request_log.get(body.entry_id, tenant=tenant) if state.request_log else None
+    if not entry:
+        raise HTTPException(
+            status_code=404,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.NOT_FOUND.value,
+                "message": f"Request log entry '{body.entry_id}' not found (disabled, expired or trimmed)",
+            },
+        )
+
+    request_id = f"req_{uuid.uuid4().hex[:16]}"
+    payload = entry["request"]
+    logger.info(f"Replaying request log entry {body.entry_id} as {request_id} (count_budget={body.count_budget})")
+
+    cost_multiplier = tenant_cost_multiplier(tenant)
+    if entry["kind"] == "llm":
+        result = await handle_llm_proxy(
+            target_name=entry["target"],
+            messages=payload["messages"],
+            model=payload.get("model"),
+            max_tokens=payload.get("max_tokens"),
+            temperature=payload.get("temperature"),
+            top_p=payload.get("top_p"),
+            stop=payload.get("stop"),
+            stream=False,
+            idempotency_key=None,
+            cache_ttl=None,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            request_id=request_id,
+            tenant=tenant,
+            tier=tier,
+            key_pool_manager=state.key_pool_manager,
+            rate_scheduler=state.rate_scheduler,
+            model_rate_limiter=state.model_rate_limiter,
+            response_format=payload.get("response_format"),
+            n=payload.get("n"),
+            logit_bias=payload.get("logit_bias"),
+            seed=payload.get("seed"),
+            logprobs=payload.get("logprobs"),
+            top_logprobs=payload.get("top_logprobs"),
+            cost_multiplier=cost_multiplier,
+        )
+    else:
+        result = await handle_http_proxy(
+            target_name=entry["target"],
+            method=payload["method"],
+            path=payload["path"],
+            headers=payload.get("headers"),
+            query=payload.get("query"),
+            body=payload.get("body"),
+            idempotency_key=None,
+            cache_ttl=None,
+            targets=state.targets,
+            cache=state.cache,
+            idempotency=state.idempotency,
+            key_pool_manager=state.key_pool_manager,
+            rate_scheduler=state.rate_scheduler,
+            request_id=request_id,
+            tenant=tenant,
+            tier=tier,
+        )
+
+    result.meta.replay_of = body.entry_id
+
+    if body.count_budget:
+        if state.usage_store:
+            state.usage_store.record(
+                request_id=request_id,
+                kind=entry["kind"],
+                target=entry["target"],
+                status="success" if result.success else "error",
+                latency_ms=result.meta.duration_ms,
+                model=result.meta.model,
+                cost_usd=result.meta.cost_usd,
+                raw_cost_usd=raw_cost(result.meta.cost_usd, cost_multiplier),
+                tenant=tenant,
+            )
+        if state.rapidapi_client and api_key:
+            await state.rapidapi_client.record_usage(
+                api_key=api_key,
+                endpoint=f"/proxy/{entry['kind']}",
+                latency_ms=result.meta.duration_ms,
+                status="success" if result.success else "error",
+                cost_usd=result.meta.cost_usd or 0.0,
+            )
+
+    if isinstance(result, HTTPStreamResult):
+        return StreamingResponse(
+            result.chunks,
+            status_code=result.status_code,
+            headers={**result.headers, "X-Request-ID": request_id},
+        )
+
+    status_code = 200 if result.success else (result.error.status_code or 500)
+    return JSONResponse(
+        content=result.model_dump(),
+        status_code=status_code,
+        headers={"X-Request-ID": request_id},
+    )
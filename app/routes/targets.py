@@ -0,0 +1,49 @@
+"""Target check endpoint (requires X-Admin-Key = RELIAPI_ADMIN_KEY).
+
+This module provides:
+- POST /targets/{name}/ping - Verify a target is reachable and its credentials work
+"""
+import logging
+from typing import Any, Dict
+
+from fastapi import APIRouter, HTTPException, Request
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key
+from reliapi.app.warmup import check_target, warmup_path
+from reliapi.core.errors import ErrorCode
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/targets", tags=["Admin"])
+
+
+@router.post(
+    "/{name}/ping",
+    summary="Ping a target",
+    description=(
+        "Make one unbilled request to the target (GET /models for LLM targets, "
+        "warmup_path or / for others) and report reachability, status and latency. "
+        "No completion is generated, and budgets, usage and the circuit breaker "
+        "are not affected."
+    ),
+)
+async def ping_target(name: str, http_request: Request) -> Dict[str, Any]:
+    """Validate a target before routing traffic to it."""
+    verify_admin_key(http_request)
+    state = get_app_state()
+    target_config = state.targets.get(name)
+    if target_config is None:
+        raise HTTPException(
+            status_code=404,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.NOT_FOUND.value,
+                "message": f"Target '{name}' not found",
+            },
+        )
+
+    timeout_s = min(target_config.get("timeout_ms", 20000), 10000) / 1000.0
+    result = await check_target(
+        name, target_config, state.key_pool_manager, timeout_s, path=warmup_path(target_config) or "/",
+    )
+    return {"target": name, **result}
@@ -0,0 +1,137 @@
+"""Usage breakdown endpoint for cost attribution.
+
+This module provides:
+- GET /usage - Request count and cost, optionally grouped by target, model or tag
+- GET /admin/usage - Usage export for any tenant with billed and raw cost (admin key)
+"""
+import logging
+from typing import Any, Dict, List, Optional
+
+from fastapi import APIRouter, HTTPException, Query, Request
+from pydantic import BaseModel
+
+from reliapi.app.dependencies import get_app_state, verify_admin_key, verify_api_key
+from reliapi.core.errors import ErrorCode
+from reliapi.core.usage import UsageStore
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["Usage"])
+
+
+class UsageGroup(BaseModel):
+    """Aggregated usage for one group."""
+
+    group: str
+    requests: int
+    errors: int
+    cost_usd: float
+
+
+class UsageResponse(BaseModel):
+    """Usage breakdown response model."""
+
+    group_by: Optional[str]
+    total_requests: int
+    total_cost_usd: float
+    groups: List[UsageGroup]
+
+
+class UsageExportGroup(UsageGroup):
+    """Aggregated usage for one group, with the actual provider cost."""
+
+    raw_cost_usd: float
+
+
+class UsageExportResponse(BaseModel):
+    """Usage export for reconciling billed (cost_usd) and actual (raw_cost_usd) cost."""
+
+    tenant: Optional[str]
+    group_by: Optional[str]
+    total_requests: int
+    total_cost_usd: float
+    total_raw_cost_usd: float
+    groups: List[UsageExportGroup]
+
+
+def _aggregate(records: List[Dict[str, Any]], group_by: Optional[str]) -> List[Dict[str, Any]]:
+    """UsageStore.aggregate with unsupported group_by reported as 400."""
+    try:
+        return UsageStore.aggregate(records, group_by)
+    except ValueError as e:
+        raise HTTPException(
+            status_code=400,
+            detail={
+                "type": "client_error",
+                "code": ErrorCode.BAD_REQUEST.value,
+                "message": str(e),
+            },
+        )
+
+
+@router.get(
+    "/usage",
+    response_model=UsageResponse,
+    summary="Usage breakdown",
+    description=(
+        "Request count and cost for the calling API key. "
+        "Use group_by=target, model, kind, status or tag:<name> "
+        "(e.g., tag:team) to break down spend."
+    ),
+)
+async def get_usage(
+    request: Request,
+    group_by: Optional[str] = Query(None, description="target, model, kind, status or tag:<name>"),
+    since: Optional[float] = Query(None, alias="from", description="Start time (unix seconds)"),
+    until: Optional[float] = Query(None, alias="to", description="End time (unix seconds)"),
+) -> Dict[str, Any]:
+    """Return usage aggregated for the caller's tenant."""
+    state = get_app_state()
+    _, tenant, _ = verify_api_key(request)
+
+    records = state.usage_store.query(tenant=tenant, since=since, until=until) if state.usage_store else []
+    groups = _aggregate(records, group_by)
+
+    return {
+        "group_by": group_by,
+        "total_requests": len(records),
+        "total_cost_usd": round(sum(r.get("cost_usd") or 0.0 for r in records), 6),
+        "groups": [{**g, "cost_usd": round(g["cost_usd"], 6)} for g in groups],
+    }
+
+
+@router.get(
+    "/admin/usage",
+    response_model=UsageExportResponse,
+    tags=["Admin"],
+    summary="Usage export with billed and raw cost",
+    description=(
+        "Usage of any tenant (requires X-Admin-Key). cost_usd is the cost as billed, "
+        "after the tenant's cost_multiplier; raw_cost_usd is the actual provider cost."
+    ),
+)
+async def export_usage(
+    request: Request,
+    tenant: Optional[str] = Query(None, description="Tenant name (omit for requests without a tenant)"),
+    group_by: Optional[str] = Query(None, description="target, model, kind, status or tag:<name>"),
+    since: Optional[float] = Query(None, alias="from", description="Start time (unix seconds)"),
+    until: Optional[float] = Query(None, alias="to", description="End time (unix seconds)"),
+) -> Dict[str, Any]:
+    """Return usage for a tenant with billed and actual cost side by side."""
+    verify_admin_key(request)
+    state = get_app_state()
+
+    records = state.usage_store.query(tenant=tenant, since=since, until=until) if state.usage_store else []
+    groups = _aggregate(records, group_by)
+
+    return {
+        "tenant": tenant,
+        "group_by": group_by,
+        "total_requests": len(records),
+        "total_cost_usd": round(sum(r.get("cost_usd") or 0.0 for r in records), 6),
+        "total_raw_cost_usd": round(sum(g["raw_cost_usd"] for g in groups), 6),
+        "groups": [
+            {**g, "cost_usd": round(g["cost_usd"], 6), "raw_cost_usd": round(g["raw_cost_usd"], 6)}
+            for g in groups
+        ],
+    }
@@ -5,10 +5,11 @@ This module provides Pydantic models for:
 - LLM proxy requests and responses
 - Error and metadata structures
 """
+import json
 from enum import Enum
 from typing import Any, Dict, List, Literal, Optional, Union
 
-from pydantic import BaseModel, Field, field_validator
+from pydantic import BaseModel, ConfigDict, Field, field_validator, model_validator
 
 
 class HTTPMethod(str, Enum):
@@ -46,6 +47,70 @@ class ErrorSource(str, Enum):
     UPSTREAM = "upstream"
 
 
+# Limits for request tags (cost attribution)
+MAX_TAGS = 10
+MAX_TAG_KEY_LENGTH = 64
+MAX_TAG_VALUE_LENGTH = 128
+
+
+def _validate_tags(v: Optional[Dict[str, str]]) -> Optional[Dict[str, str]]:
+    """Validate tag count and key/value lengths."""
+    if v is None:
+        return v
+    if len(v) > MAX_TAGS:
+        raise ValueError(f"Too many tags: {len(v)} (max {MAX_TAGS})")
+    for key, value in v.items():
+        if not key or len(key) > MAX_TAG_KEY_LENGTH:
+            raise ValueError(f"Tag key must be 1-{MAX_TAG_KEY_LENGTH} characters: {key!r}")
+        if len(value) > MAX_TAG_VALUE_LENGTH:
+            raise ValueError(f"Tag value for {key!r} exceeds {MAX_TAG_VALUE_LENGTH} characters")
+    return v
+
+
+# Limit for request metadata echoed back in meta (serialized JSON size)
+MAX_METADATA_BYTES = 4096
+
+
+def _validate_metadata(v: Any) -> Any:
+    """Validate the serialized size of request metadata."""
+    if v is None:
+        return v
+    size = len(json.dumps(v, separators=(",", ":"), ensure_ascii=False).encode())
+    if size > MAX_METADATA_BYTES:
+        raise ValueError(f"metadata is {size} bytes serialized (max {MAX_METADATA_BYTES})")
+    return v
+
+
+# Limits for logit_bias (OpenAI accepts -100..100 per token)
+MAX_LOGIT_BIAS = 100
+MAX_LOGIT_BIAS_ENTRIES = 300
+
+
+def _validate_logit_bias(v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+    """Validate logit_bias keys (token IDs) and bias range."""
+    if v is None:
+        return v
+    if len(v) > MAX_LOGIT_BIAS_ENTRIES:
+        raise ValueError(f"Too many logit_bias entries: {len(v)} (max {MAX_LOGIT_BIAS_ENTRIES})")
+    for token_id, bias in v.items():
+        if not token_id.isdigit():
+            raise ValueError(f"logit_bias keys must be token IDs, got {token_id!r}")
+        if not -MAX_LOGIT_BIAS <= bias <= MAX_LOGIT_BIAS:
+            raise ValueError(
+                f"logit_bias for token {token_id} must be between "
+                f"-{MAX_LOGIT_BIAS} and {MAX_LOGIT_BIAS}, got {bias}"
+            )
+    return v
+
+
+def _validate_logprobs(logprobs: Optional[bool], top_logprobs: Optional[int], stream: bool) -> None:
+    """Reject top_logprobs without logprobs, and logprobs on streams."""
+    if top_logprobs is not None and not logprobs:
+        raise ValueError("top_logprobs requires logprobs=true")
+    if logprobs and stream:
+        raise ValueError("logprobs is not supported with stream=true")
+
+
 class ChatMessage(BaseModel):
     """LLM chat message structure."""
 
@@ -53,6 +118,17 @@ class ChatMessage(BaseModel):
     content: str = Field(..., description="Message content")
 
 
+class ModelConstraints(BaseModel):
+    """Capability requirements for `model: "auto"` selection."""
+
+    min_context_tokens: Optional[int] = Field(
+        None, ge=1, description="Minimum context window in tokens"
+    )
+    capabilities: Optional[List[str]] = Field(
+        None, description="Required capabilities (e.g., ['vision']), matched against auto_models"
+    )
+
+
 class HTTPProxyRequest(BaseModel):
     """Request schema for POST /proxy/http.
 
@@ -94,7 +170,36 @@ class HTTPProxyRequest(BaseModel):
         ge=0,
         description=(
             "Cache TTL in seconds (overrides config default). "
-            "Only applies to GET/HEAD requests."
+            "Applies to GET/HEAD, and to POST on targets listing it in cache.methods (required there)."
+        ),
+    )
+    hedge: Optional[bool] = Field(
+        None,
+        description=(
+            "Hedge this request: send a second identical call if the first is slow (GET/HEAD or with "
+            "idempotency_key only). Default: the target's hedging config; false opts out."
+        ),
+    )
+    max_response_chars: Optional[int] = Field(
+        None,
+        gt=0,
+        description=(
+            "Return at most this many characters of the response body (compact JSON for JSON bodies, "
+            "kept valid). The full response is still cached. Sets meta.truncated when shortened."
+        ),
+    )
+    tags: Optional[Dict[str, str]] = Field(
+        None,
+        description=(
+            "Tags for cost attribution (e.g., {'team': 'search', 'feature': 'rerank'}). "
+            "Merged over per-key default tags. Not part of the cache key."
+        ),
+    )
+    metadata: Optional[Any] = Field(
+        None,
+        description=(
+            "Arbitrary JSON echoed back verbatim in meta.metadata (max 4 KB serialized). "
+            "Not interpreted and not part of the cache key."
         ),
     )
 
@@ -108,6 +213,18 @@ class HTTPProxyRequest(BaseModel):
             raise ValueError(f"Invalid HTTP method: {v}. Must be one of {valid_methods}")
         return v
 
+    @field_validator("tags")
+    @classmethod
+    def validate_tags(cls, v: Optional[Dict[str, str]]) -> Optional[Dict[str, str]]:
+        """Validate tag limits."""
+        return _validate_tags(v)
+
+    @field_validator("metadata")
+    @classmethod
+    def validate_metadata(cls, v: Any) -> Any:
+        """Validate metadata size."""
+        return _validate_metadata(v)
+
 
 class LLMProxyRequest(BaseModel):
     """Request schema for POST /proxy/llm.
@@ -141,6 +258,13 @@ class LLMProxyRequest(BaseModel):
             "Uses default from config if not specified."
         ),
     )
+    system: Optional[str] = Field(
+        None,
+        description=(
+            "System prompt, prepended to messages as a system message "
+            "(ahead of any system messages already in messages)"
+        ),
+    )
     max_tokens: Optional[int] = Field(
         None,
         ge=1,
@@ -163,6 +287,65 @@ class LLMProxyRequest(BaseModel):
     stop: Optional[List[str]] = Field(
         None, description="Stop sequences (e.g., ['\\n', 'END'])"
     )
+    n: Optional[int] = Field(
+        None,
+        ge=1,
+        le=16,
+        description=(
+            "Number of completions to generate (OpenAI and Mistral, non-streaming). "
+            "If the provider returns fewer, the ones returned are served"
+        ),
+    )
+    constraints: Optional[ModelConstraints] = Field(
+        None,
+        description=(
+            "Requirements for model='auto': the cheapest configured candidate "
+            "that satisfies them is used"
+        ),
+    )
+    response_format: Optional[Dict[str, Any]] = Field(
+        None,
+        description=(
+            "OpenAI-style response format, e.g. {'type': 'json_object'} for JSON mode "
+            "(forwarded to OpenAI and Mistral)"
+        ),
+    )
+    json_schema: Optional[Dict[str, Any]] = Field(
+        None,
+        description=(
+            "JSON Schema (draft 2020-12) the completion text must match (non-streaming). Invalid outputs "
+            "are retried with a corrective instruction or rejected with 422 SCHEMA_VIOLATION "
+            "(llm.output_schema); only valid outputs are cached"
+        ),
+    )
+    logit_bias: Optional[Dict[str, float]] = Field(
+        None,
+        description=(
+            "Token ID -> bias (-100..100), e.g. {'50256': -100}. OpenAI only; "
+            "part of the cache key"
+        ),
+    )
+    seed: Optional[int] = Field(
+        None,
+        ge=0,
+        description=(
+            "Sampling seed (OpenAI and Mistral). Seeded requests are cached "
+            "even with temperature > 0"
+        ),
+    )
+    logprobs: Optional[bool] = Field(
+        None,
+        description=(
+            "Return token log probabilities in each choice (OpenAI only, non-streaming). "
+            "Part of the cache key; replayed from cache unchanged"
+        ),
+    )
+    top_logprobs: Optional[int] = Field(
+        None,
+        ge=0,
+        le=20,
+        description="Most likely alternatives returned per token (0-20, requires logprobs=true)",
+    )
     stream: bool = Field(
         False,
         description=(
@@ -170,6 +353,23 @@ class LLMProxyRequest(BaseModel):
             "If false or omitted, returns standard JSON response."
         ),
     )
+    collapse_stream: Optional[bool] = Field(
+        None,
+        description=(
+            "With stream=true: stream from the provider but return one buffered JSON response. "
+            "Overrides the X-ReliAPI-Collapse-Stream header and streaming.collapse."
+        ),
+    )
+    post_process: Optional[Literal["none", "trim", "trim_fences"]] = Field(
+        None,
+        description=(
+            "Clean up the returned content: trim whitespace, or also unwrap a response that is one "
+            "markdown code fence. Not applied to streamed chunks. Default: the tenant's defaults, else none."
+        ),
+    )
+    include_raw_content: bool = Field(
+        False, description="With post_process: also return the unprocessed text as data.raw_content"
+    )
     idempotency_key: Optional[str] = Field(
         None,
         description=(
@@ -185,6 +385,162 @@ class LLMProxyRequest(BaseModel):
             "Cached responses return instantly without LLM call."
         ),
     )
+    tags: Optional[Dict[str, str]] = Field(
+        None,
+        description=(
+            "Tags for cost attribution (e.g., {'team': 'search', 'feature': 'rerank'}). "
+            "Merged over per-key default tags. Not part of the cache key."
+        ),
+    )
+    metadata: Optional[Any] = Field(
+        None,
+        description=(
+            "Arbitrary JSON echoed back verbatim in meta.metadata (max 4 KB serialized). "
+            "Not interpreted and not part of the cache key."
+        ),
+    )
+
+    @field_validator("tags")
+    @classmethod
+    def validate_tags(cls, v: Optional[Dict[str, str]]) -> Optional[Dict[str, str]]:
+        """Validate tag limits."""
+        return _validate_tags(v)
+
+    @field_validator("metadata")
+    @classmethod
+    def validate_metadata(cls, v: Any) -> Any:
+        """Validate metadata size."""
+        return _validate_metadata(v)
+
+    @field_validator("logit_bias")
+    @classmethod
+    def validate_logit_bias(cls, v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+        """Validate logit_bias shape."""
+        return _validate_logit_bias(v)
+
+    @model_validator(mode="after")
+    def validate_logprobs(self) -> "LLMProxyRequest":
+        """top_logprobs needs logprobs; logprobs are not streamed."""
+        _validate_logprobs(self.logprobs, self.top_logprobs, self.stream)
+        return self
+
+    @model_validator(mode="after")
+    def validate_json_schema(self) -> "LLMProxyRequest":
+        """json_schema must be a valid schema, and is checked on whole (non-streamed) outputs only."""
+        if self.json_schema is None:
+            return self
+        if self.stream:
+            raise ValueError("json_schema is not supported with stream=true")
+        from jsonschema import Draft202012Validator
+        from jsonschema.exceptions import SchemaError
+
+        try:
+            Draft202012Validator.check_schema(self.json_schema)
+        except SchemaError as e:
+            raise ValueError(f"Invalid json_schema: {e.message}")
+        return self
+
+    @model_validator(mode="after")
+    def prepend_system(self) -> "LLMProxyRequest":
+        """Move the system field into messages, so it is part of the cache key like any message."""
+        if self.system:
+            self.messages = [{"role": "system", "content": self.system}, *self.messages]
+            self.system = None
+        return self
+
+
+class OpenAIChatCompletionRequest(BaseModel):
+    """Request schema for POST /v1/chat/completions (OpenAI-compatible).
+
+    Accepts the OpenAI Chat Completions body as sent by the OpenAI SDK.
+    Unsupported OpenAI parameters are accepted and ignored.
+    """
+
+    model_config = ConfigDict(extra="allow")
+
+    model: Optional[str] = Field(None, description="Model name (uses target default if omitted)")
+    messages: List[Dict[str, Any]] = Field(
+        ...,
+        min_length=1,
+        description="OpenAI messages; content may be a string or a list of text parts",
+    )
+    max_tokens: Optional[int] = Field(None, ge=1, description="Maximum tokens in response")
+    max_completion_tokens: Optional[int] = Field(
+        None, ge=1, description="Alias of max_tokens used by newer OpenAI clients"
+    )
+    temperature: Optional[float] = Field(None, ge=0.0, le=2.0, description="Temperature (0.0-2.0)")
+    top_p: Optional[float] = Field(None, ge=0.0, le=1.0, description="Top-p sampling parameter")
+    stop: Optional[Union[str, List[str]]] = Field(None, description="Stop sequence(s)")
+    n: Optional[int] = Field(None, ge=1, le=16, description="Number of completions to generate")
+    response_format: Optional[Dict[str, Any]] = Field(None, description="Response format (JSON mode)")
+    logit_bias: Optional[Dict[str, float]] = Field(None, description="Token ID -> bias (-100..100)")
+    seed: Optional[int] = Field(None, ge=0, description="Sampling seed")
+    logprobs: Optional[bool] = Field(None, description="Return token log probabilities (non-streaming)")
+    top_logprobs: Optional[int] = Field(None, ge=0, le=20, description="Alternatives per token (requires logprobs)")
+    stream: bool = Field(False, description="Stream OpenAI chat.completion.chunk events")
+
+    @field_validator("logit_bias")
+    @classmethod
+    def validate_logit_bias(cls, v: Optional[Dict[str, float]]) -> Optional[Dict[str, float]]:
+        """Validate logit_bias shape."""
+        return _validate_logit_bias(v)
+
+    @model_validator(mode="after")
+    def validate_logprobs(self) -> "OpenAIChatCompletionRequest":
+        """top_logprobs needs logprobs; logprobs are not streamed."""
+        _validate_logprobs(self.logprobs, self.top_logprobs, self.stream)
+        return self
+
+
+class MaintenanceRequest(BaseModel):
+    """Request schema for POST /admin/maintenance."""
+
+    enabled: bool = Field(..., description="Turn cache-only maintenance mode on or off")
+    target: Optional[str] = Field(
+        None, description="Target to put in maintenance (all targets if omitted)"
+    )
+    reason: Optional[str] = Field(None, description="Free-form note shown in /health")
+
+
+class TargetDisableRequest(BaseModel):
+    """Request schema for POST /admin/targets/{name}/disable."""
+
+    reason: Optional[str] = Field(None, description="Free-form note shown in /health")
+
+
+class PinRequest(BaseModel):
+    """Request schema for POST /cache/pin.
+
+    Identifies an LLM request by the fields a client sends to /proxy/llm;
+    the pinned content is served for exactly that request.
+    """
+
+    target: str = Field(..., description="Target name from config")
+    tenant: Optional[str] = Field(None, description="Tenant the pin applies to (requests without a tenant if omitted)")
+    messages: List[Dict[str, str]] = Field(..., min_length=1, description="Chat messages of the pinned request")
+    model: Optional[str] = Field(None, description="Model of the pinned request (omit to match requests without one)")
+    max_tokens: Optional[int] = Field(None, ge=1)
+    temperature: Optional[float] = Field(None, ge=0.0, le=2.0)
+    top_p: Optional[float] = Field(None, ge=0.0, le=1.0)
+    stop: Optional[List[str]] = None
+    response_format: Optional[Dict[str, Any]] = None
+    logit_bias: Optional[Dict[str, float]] = None
+    seed: Optional[int] = Field(None, ge=0)
+    content: str = Field(..., description="Response content served for the request")
+    note: Optional[str] = Field(None, description="Free-form note shown when listing pins")
+
+
+class ReplayRequest(BaseModel):
+    """Request schema for POST /replay.
+
+    Re-sends a request recorded in the request log (REQUEST_LOG_ENABLED=true).
+    """
+
+    entry_id: str = Field(..., description="Request log entry ID (e.g., 'rlog_...')")
+    count_budget: bool = Field(
+        False,
+        description="Record the replay's cost in usage/billing like a live request",
+    )
 
 
 class TokenUsage(BaseModel):
@@ -200,6 +556,21 @@ class TokenUsage(BaseModel):
     )
 
 
+class LLMChoiceMessage(BaseModel):
+    """Message of a normalized choice."""
+
+    role: str = Field("assistant", description="Message role (normalized to system/user/assistant)")
+    content: str = Field(..., description="Generated text content")
+
+
+class LLMChoice(BaseModel):
+    """One completion, in the same shape for every provider."""
+
+    index: int = Field(..., description="Choice index assigned by the provider")
+    message: LLMChoiceMessage = Field(..., description="Generated message")
+    finish_reason: Optional[str] = Field(None, description="Normalized finish reason (stop, length, ...)")
+
+
 class LLMResponseData(BaseModel):
     """LLM response data structure."""
 
@@ -209,6 +580,12 @@ class LLMResponseData(BaseModel):
     finish_reason: Optional[str] = Field(
         None, description="Reason for completion (stop, length, etc.)"
     )
+    choices: Optional[List[LLMChoice]] = Field(
+        None, description="Returned completions (content/role/finish_reason mirror the first one)"
+    )
+    raw: Optional[Dict[str, Any]] = Field(
+        None, description="Raw provider response (llm.normalization.include_raw)"
+    )
 
 
 class ErrorDetail(BaseModel):
@@ -235,6 +612,13 @@ class ErrorDetail(BaseModel):
     )
 
 
+class CostBreakdown(BaseModel):
+    """Split of cost_usd by token type (token counts x model pricing)."""
+
+    input_usd: float = Field(..., ge=0, description="Cost of prompt tokens (prompt-cache discounts applied)")
+    output_usd: float = Field(..., ge=0, description="Cost of completion tokens")
+
+
 class MetaResponse(BaseModel):
     """Metadata in response."""
 
@@ -242,19 +626,133 @@ class MetaResponse(BaseModel):
     provider: Optional[str] = Field(None, description="Provider name (for LLM)")
     model: Optional[str] = Field(None, description="Model name (for LLM)")
     cache_hit: bool = Field(False, description="Whether response was from cache")
+    pinned: Optional[bool] = Field(
+        None, description="Whether response was served from a pinned entry (POST /cache/pin)"
+    )
+    cached_error: Optional[bool] = Field(
+        None, description="Whether the cached response is an upstream error (negative caching)"
+    )
     idempotent_hit: bool = Field(
         False, description="Whether response was from idempotency cache"
     )
+    deduplicated: Optional[bool] = Field(
+        None, description="Whether response was coalesced onto an identical in-window request"
+    )
+    auto_selected_model: Optional[str] = Field(
+        None, description="Model chosen for model='auto' (cheapest candidate, or default if none matched)"
+    )
+    cost_ceiling_reached: Optional[bool] = Field(
+        None, description="Stream aborted mid-generation at llm.stream_cost_ceiling_usd (output is cut off)"
+    )
+    post_processed: Optional[bool] = Field(
+        None, description="Whether post_process changed the returned content (caches keep the raw text)"
+    )
+    truncated: Optional[bool] = Field(
+        None, description="Whether data.body was shortened to max_response_chars (the cached copy is complete)"
+    )
+    detected_language: Optional[str] = Field(
+        None, description="Input language detected for llm.language_routing (ISO 639-1), if any"
+    )
+    language_selected_model: Optional[str] = Field(
+        None, description="Model chosen by llm.language_routing for the detected language"
+    )
+    replay_of: Optional[str] = Field(
+        None, description="Request log entry ID this response replays (POST /replay)"
+    )
+    request_log_id: Optional[str] = Field(
+        None, description="Request log entry ID of this request, for POST /replay (REQUEST_LOG_ENABLED=true)"
+    )
     retries: int = Field(0, ge=0, description="Number of retries")
     duration_ms: int = Field(..., ge=0, description="Request duration in milliseconds")
+    upstream_ms: Optional[int] = Field(None, ge=0, description="Time spent in provider calls (0 on cache hits)")
+    queue_ms: Optional[int] = Field(None, ge=0, description="Time waiting on rate and concurrency limits")
+    overhead_ms: Optional[int] = Field(None, ge=0, description="Proxy processing time (cache, tokenization, serialization)")
     request_id: str = Field(..., description="Request ID")
+    upstream_request_id: Optional[str] = Field(
+        None, description="Provider's request ID (e.g. OpenAI x-request-id), replayed on cache/idempotent hits"
+    )
+    region: Optional[str] = Field(
+        None, description="Regional endpoint that served the request (targets with regions)"
+    )
+    hedged: Optional[bool] = Field(None, description="A hedge (second identical upstream call) was sent")
+    hedge_winner: Optional[int] = Field(None, description="Attempt whose response was used when hedged: 1 original, 2 hedge")
+    history_summarized: Optional[bool] = Field(
+        None, description="Older turns were replaced with a summary (llm.summarization)"
+    )
+    history_tokens_saved: Optional[int] = Field(
+        None, ge=0, description="Estimated prompt tokens saved by the summary (summarization cost is in cost_usd)"
+    )
+    json_repaired: Optional[bool] = Field(
+        None, description="Malformed JSON-mode output was repaired (llm.json_repair)"
+    )
+    empty_retries: Optional[int] = Field(
+        None, ge=0, description="Retries after empty completions (llm.empty_response); the last answer is returned"
+    )
+    schema_valid: Optional[bool] = Field(
+        None, description="Requests with json_schema: the returned output matches the schema"
+    )
+    schema_retries: Optional[int] = Field(
+        None, ge=0, description="Corrective retries needed for a json_schema-valid output (llm.output_schema)"
+    )
+    timeout_ms: Optional[int] = Field(
+        None, ge=0, description="Provider timeout computed from max_tokens (llm.timeout_scaling)"
+    )
+    cache_ttl_s: Optional[int] = Field(
+        None, ge=0, description="TTL of the cache entry written for this response, after cache.ttl_jitter"
+    )
+    cache_expires_in_s: Optional[int] = Field(
+        None, ge=0, description="Cache hits with ttl_on_hit extend: seconds until the entry expires, after this hit's extension"
+    )
+    cache_age_s: Optional[int] = Field(
+        None, ge=0, description="HTTP cache hits: seconds since the response was generated upstream (Age header)"
+    )
+    cache_stale: Optional[bool] = Field(
+        None, description="HTTP cache hit older than the upstream Cache-Control max-age"
+    )
+    cache_ttl_extended: Optional[bool] = Field(
+        None, description="Cache hit past the entry's TTL, served because the target is unhealthy (cache.health_ttl)"
+    )
+    cache_skipped_too_large: Optional[bool] = Field(
+        None, description="Response was served but not cached: it exceeds max_cache_value_bytes"
+    )
+    cache_hits: Optional[int] = Field(
+        None, ge=0, description="Batch embeddings: inputs served from the per-input cache (cache.embeddings)"
+    )
+    cache_misses: Optional[int] = Field(
+        None, ge=0, description="Batch embeddings: inputs sent upstream (usage and cost cover only these)"
+    )
+    requested_n: Optional[int] = Field(None, description="Completions requested (n > 1 only)")
+    returned_n: Optional[int] = Field(
+        None, description="Completions returned; lower than requested_n if the provider returned fewer"
+    )
     trace_id: Optional[str] = Field(None, description="Trace ID")
     cost_usd: Optional[float] = Field(
         None, ge=0, description="Actual cost in USD (for LLM)"
     )
+    cost_breakdown: Optional[CostBreakdown] = Field(
+        None, description="cost_usd split into input and output cost (replayed on cache hits)"
+    )
+    usage_source: Optional[str] = Field(
+        None, description="Where token counts come from: 'provider' (reported usage) or 'estimated' (local chars/4)"
+    )
+    resolved_params: Optional[Dict[str, Any]] = Field(
+        None,
+        description="Effective model/max_tokens/cache after tenant defaults, with the inherited names in 'inherited'",
+    )
+    metadata: Optional[Any] = Field(None, description="The request's metadata, echoed back verbatim")
     cost_estimate_usd: Optional[float] = Field(
         None, ge=0, description="Estimated cost before request (for LLM)"
     )
+    cache_skipped_nondeterministic: Optional[bool] = Field(
+        None,
+        description="Response not cached because temperature > 0 (set cache.cache_nondeterministic to cache it)",
+    )
+    cost_approximate: Optional[bool] = Field(
+        None, description="Cost is a chars/4 estimate at fallback pricing (model has no pricing)"
+    )
+    cached_prompt_tokens: Optional[int] = Field(
+        None, ge=0, description="Prompt tokens served from the provider's prompt cache (for LLM)"
+    )
     cost_policy_applied: Optional[str] = Field(
         None,
         description="Cost policy applied: none, soft_cap_throttled, hard_cap_rejected",
@@ -268,12 +766,19 @@ class MetaResponse(BaseModel):
     original_max_tokens: Optional[int] = Field(
         None, description="Original max_tokens before reduction (for LLM)"
     )
+    output_cap_tokens: Optional[int] = Field(
+        None, description="Per-model output cap max_tokens was clamped to (llm.output_caps, for LLM)"
+    )
     fallback_used: Optional[bool] = Field(
         None, description="Whether fallback was used"
     )
     fallback_target: Optional[str] = Field(
         None, description="Fallback target name if used"
     )
+    content_filter_original_model: Optional[str] = Field(
+        None,
+        description="Model whose response was blocked by the content filter; `model` is the retry (llm.on_content_filter_model)",
+    )
     # RouteLLM correlation fields
     routellm_decision_id: Optional[str] = Field(
         None, description="RouteLLM routing decision ID for correlation"
@@ -1,31 +1,76 @@
 """Service layer for ReliAPI endpoints."""
+import asyncio
+import base64
+import functools
 import hashlib
 import json
 import time
 from dataclasses import dataclass, field
-from typing import Any, AsyncIterator, Dict, List, Optional, Set, Union, Tuple
+from typing import Any, AsyncIterator, Awaitable, Callable, Dict, List, Mapping, Optional, Set, Union, Tuple
 
 import httpx
 
 from reliapi.adapters.llm.factory import detect_provider, get_adapter
-from reliapi.app.schemas import ErrorDetail, ErrorResponse, MetaResponse, SuccessResponse
-from reliapi.core.cache import Cache
-from reliapi.core.circuit_breaker import CircuitBreaker
+from reliapi.app.openai_compat import parse_sse_event
+from reliapi.app.schemas import CostBreakdown, ErrorDetail, ErrorResponse, MetaResponse, SuccessResponse
+from reliapi.core.cache import DEFAULT_TTL_JITTER, FRESH_UNTIL_FIELD, Cache, CacheValueTooLarge
+from reliapi.core.canonical_json import canonical_body, canonical_json
+from reliapi.core.content_types import extract_field, has_stream_rule, select_content_rule
+from reliapi.core.circuit_breaker import circuit_breakers
+from reliapi.core.concurrency import Overloaded, QueueTimeout, concurrency_limiter
 from reliapi.core.cost_estimator import CostEstimator
+from reliapi.core.embeddings_cache import EmbeddingsBatch, EmbeddingsMismatch
 from reliapi.core.errors import ErrorCode, UpstreamStatus
-from reliapi.core.http_client import UpstreamHTTPClient
+from reliapi.core.grpc_transcoding import GrpcTransport
+from reliapi.core.health_ttl import health_ttl_reason, stale_ttl_s
+from reliapi.core.hedging import hedge_tracker, hedged_call
+from reliapi.core.http_client import UpstreamHTTPClient, outbound_headers
 from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.json_repair import is_json_mode, repair_json
+from reliapi.core.llm_normalize import normalize_choices, normalize_finish_reason
 from reliapi.core.client_profile import ClientProfileManager
 from reliapi.core.key_pool import KeyPoolManager, ProviderKey, MAX_KEY_SWITCHES
 from reliapi.core.logging import structured_logger
+from reliapi.core.kill_switch import kill_switch
+from reliapi.core.maintenance import maintenance_mode
+from reliapi.core.model_limits import (
+    DEFAULT_MODEL_KEY,
+    ModelRateLimiter,
+    ModelRateReservation,
+    estimate_request_tokens,
+    resolve_model_limits,
+)
+from reliapi.core.pinned_responses import PinnedResponses
+from reliapi.core.plugins import PluginError, load_plugins, transform_request, transform_response
 from reliapi.core.rate_scheduler import RateScheduler
+from reliapi.core.regions import region_name, region_router, region_url
+from reliapi.core.request_overrides import apply_request_overrides
+from reliapi.core.request_signing import request_signer
+from reliapi.core.provider_errors import RetryableErrorCode, error_code_validator, error_codes
+from reliapi.core.response_schema import (
+    SchemaViolation,
+    corrective_instruction,
+    response_validator,
+    validate_llm_output,
+    validate_response_body,
+)
+from reliapi.core.response_transform import apply_response_transform
 from reliapi.core.retry import RetryMatrix
+from reliapi.core.retry_budget import retry_budget
+from reliapi.core.stream_retry import replay_safe_stream
+from reliapi.core.summarization import replace_history, split_history, summarizer_messages, tokens_saved
+from reliapi.core.timing import RequestTimer
+from reliapi.core.request_params import DEFAULT_ON_UNKNOWN_PARAM, filter_params
+from reliapi.core.usage import UsageStore
 from reliapi.metrics.prometheus import (
     budget_events_total,
     cache_hits_total,
     cache_misses_total,
+    cache_ttl_extended_hits_total,
+    content_filter_retries_total,
     errors_total,
     idempotent_hits_total,
+    json_repairs_total,
     key_pool_errors_total,
     key_pool_exhausted_total,
     key_pool_qps,
@@ -33,9 +78,22 @@ from reliapi.metrics.prometheus import (
     key_pool_status,
     key_switches_exhausted_total,
     key_switches_total,
+    llm_completion_tokens,
+    llm_params_filtered_total,
     llm_cost_usd_total,
+    llm_prompt_tokens,
+    llm_empty_retries_total,
+    llm_request_cost_usd,
+    llm_schema_retries_total,
+    model_rate_limit_events_total,
+    model_rate_limit_remaining,
+    rate_scheduler_429_total,
     request_latency_ms,
+    request_size_bytes,
     requests_total,
+    response_size_bytes,
+    stream_cost_ceiling_total,
+    stream_idle_timeouts_total,
     # Legacy metrics (kept for backward compatibility)
     http_requests_total,
     latency_ms,
@@ -265,23 +323,867 @@ def _get_auth_from_key_pool_or_fallback(
     return auth, None, "targets.auth"
 
 
+def _is_range_request(headers: Optional[Dict[str, str]]) -> bool:
+    """Whether the client asked for part of the resource (Range header)."""
+    return any(name.lower() == "range" for name in headers or {})
+
+
+def _upstream_cache_ttl(response_headers: Optional[Dict[str, str]]) -> Optional[int]:
+    """TTL from the upstream Cache-Control header (cache.respect_upstream_cache_control).
+
+    Returns 0 for no-store, no-cache, private or max-age=0 (do not cache),
+    s-maxage or max-age in seconds if present, None if the header sets no TTL.
+    """
+    value = next(
+        (v for name, v in (response_headers or {}).items() if name.lower() == "cache-control"), None
+    )
+    if not value:
+        return None
+    directives = {}
+    for part in value.split(","):
+        name, _, arg = part.strip().partition("=")
+        directives[name.strip().lower()] = arg.strip().strip('"')
+    if directives.keys() & {"no-store", "no-cache", "private"}:
+        return 0
+    for name in ("s-maxage", "max-age"):
+        if directives.get(name, "").isdigit():
+            return int(directives[name])
+    return None
+
+
+def _cached_response_age(cached: Dict[str, Any]) -> Dict[str, Any]:
+    """`cache_age_s`/`cache_stale` meta fields for a cached HTTP response.
+
+    The age is the upstream Age header plus the time spent in the cache
+    (RFC 9111 section 4.2.3); the entry is stale once the age exceeds the
+    upstream's Cache-Control max-age. Entries written without cached_at
+    report neither.
+    """
+    if cached.get("cached_at") is None:
+        return {}
+    response_headers = cached.get("headers") or {}
+    upstream_age = next((v for name, v in response_headers.items() if name.lower() == "age"), "0")
+    age = int(time.time() - cached["cached_at"]) + (int(upstream_age) if str(upstream_age).isdigit() else 0)
+    freshness = _upstream_cache_ttl(response_headers)
+    return {
+        "cache_age_s": max(age, 0),
+        "cache_stale": True if freshness is not None and age > freshness else None,
+    }
+
+
+def _ttl_extended(
+    cached: Dict[str, Any], target_name: str, kind: str, health_reason: Optional[str]
+) -> Dict[str, Any]:
+    """`cache_ttl_extended` meta field for a hit served past its TTL under cache.health_ttl."""
+    fresh_until = cached.get(FRESH_UNTIL_FIELD)
+    if not health_reason or fresh_until is None or time.time() < fresh_until:
+        return {}
+    cache_ttl_extended_hits_total.labels(target=target_name, kind=kind, reason=health_reason).inc()
+    return {"cache_ttl_extended": True}
+
+
+def _upstream_validator(
+    target_config: Dict[str, Any],
+    extract: Callable[[Dict[str, Any]], List[str]] = error_codes,
+    schema_validator: Optional[Callable[[httpx.Response], None]] = None,
+) -> Optional[Callable[[httpx.Response], None]]:
+    """Response hook retrying retry_on_error_codes matches, then schema violations (None if neither)."""
+    patterns = target_config.get("retry_on_error_codes")
+    code_validator = error_code_validator(patterns, extract) if patterns else None
+    if not code_validator or not schema_validator:
+        return code_validator or schema_validator
+
+    def _validate(response: httpx.Response) -> None:
+        code_validator(response)
+        schema_validator(response)
+
+    return _validate
+
+
+def _parse_http_body(response_body: bytes, mode: str = "auto") -> Any:
+    """JSON body, else {"raw": text}, else {"raw_base64": ...} for binary content.
+
+    A content_types rule can force `text` ({"raw": ...}) or `base64` ({"raw_base64": ...}).
+    """
+    if not response_body:
+        return {}
+    if mode == "base64":
+        return {"raw_base64": base64.b64encode(response_body).decode("ascii")}
+    if mode == "text":
+        return {"raw": response_body.decode(errors="replace")}
+    try:
+        return json.loads(response_body.decode())
+    except UnicodeDecodeError:
+        return {"raw_base64": base64.b64encode(response_body).decode("ascii")}
+    except ValueError:
+        return {"raw": response_body.decode()}
+
+
+def _shape_http_body(body: Any, content_rule: Dict[str, Any], target_config: Dict[str, Any]) -> Any:
+    """response_transform (unless the content_types rule turns it off), then the rule's extract."""
+    if content_rule.get("transform", True):
+        body = apply_response_transform(body, target_config.get("response_transform"))
+    if content_rule.get("extract"):
+        body = extract_field(body, content_rule["extract"])
+    return body
+
+
+# Connection-level or re-encoded headers; the relayed body is decoded and chunked anew
+_HOP_BY_HOP_HEADERS = {
+    "connection", "keep-alive", "transfer-encoding", "content-length", "content-encoding", "te", "trailer", "upgrade",
+}
+
+
+@dataclass
+class HTTPStreamResult:
+    """A /proxy/http response relayed as it arrives (content_types rule with stream: true)."""
+    
+    status_code: int
+    headers: Dict[str, str]
+    chunks: AsyncIterator[bytes]
+    meta: MetaResponse
+    close: Callable[[], Awaitable[None]]
+
+    @property
+    def success(self) -> bool:
+        # Only successful upstream responses are streamed
+        return True
+
+
+async def _relay_http_stream(response: httpx.Response, close: Callable[[], Awaitable[None]]) -> AsyncIterator[bytes]:
+    """Upstream body chunks; close() runs when the relay ends."""
+    try:
+        async for chunk in response.aiter_bytes():
+            yield chunk
+    finally:
+        await close()
+
+
+def _http_stream_result(
+    response: httpx.Response,
+    client: UpstreamHTTPClient,
+    concurrency_slot: Optional[Any],
+    target_config: Dict[str, Any],
+    target_name: str,
+    path: str,
+    idempotency: IdempotencyManager,
+    idempotency_key: Optional[str],
+    request_id: str,
+    tenant: Optional[str],
+    start_time: float,
+    timer: RequestTimer,
+    retries: int,
+    hedge_meta: Dict[str, Any],
+) -> HTTPStreamResult:
+    """Relay a response matching a stream rule; it is not cached or stored for idempotency."""
+    if idempotency_key:
+        idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+    duration_ms = int((time.time() - start_time) * 1000)
+    _log_and_metric_http_request(
+        request_id=request_id,
+        target_name=target_name,
+        path=path,
+        outcome="success",
+        latency_ms=duration_ms,
+        cache_hit=False,
+        idempotent_hit=False,
+        tenant=tenant,
+    )
+    http_requests_total.labels(target=target_name, status="success").inc()
+    latency_ms.labels(target=target_name, status="success").observe(duration_ms)
+    closed = False
+
+    async def close() -> None:
+        # Release the response, client and concurrency slot once (also for streams never relayed)
+        nonlocal closed
+        if closed:
+            return
+        closed = True
+        await response.aclose()
+        if concurrency_slot:
+            concurrency_slot.release()
+        await client.close()
+
+    return HTTPStreamResult(
+        status_code=response.status_code,
+        headers={
+            name: value for name, value in response.headers.items() if name.lower() not in _HOP_BY_HOP_HEADERS
+        },
+        chunks=_relay_http_stream(response, close),
+        meta=MetaResponse(
+            target=target_name,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=retries,
+            duration_ms=duration_ms,
+            **timer.breakdown(duration_ms),
+            upstream_request_id=_upstream_request_id(target_config, response.headers),
+            region=_served_region(target_config, client),
+            **hedge_meta,
+            request_id=request_id,
+            trace_id=None,
+        ),
+        close=close,
+    )
+
+
+# Methods cached by default: no side effects upstream, so a cached answer is always safe
+CACHE_SAFE_METHODS = ("GET", "HEAD")
+
+# Upstream errors after which the provider may still complete (and bill) the request:
+# keyed requests are not retried, sent to another region or key, or failed over on them
+AMBIGUOUS_FAILOVER_STATUSES = (504,)
+
+
+def _http_cacheable(method: str, cache_config: Dict[str, Any], cache_ttl: Optional[int]) -> bool:
+    """Whether a /proxy/http request may be served from and stored in the cache.
+
+    GET/HEAD are cached by default. POST (read-only search or GraphQL
+    endpoints) only when the target lists it in cache.methods and the
+    request sets its own `cache` TTL, so a mutation is never cached by
+    accident. Other methods are never cached.
+    """
+    method = method.upper()
+    if not cache_config.get("enabled", True) or method not in cache_config.get("methods", CACHE_SAFE_METHODS):
+        return False
+    return method in CACHE_SAFE_METHODS or bool(cache_ttl)
+
+
+def _store_http_cache(
+    cache: Cache,
+    cache_config: Dict[str, Any],
+    method: str,
+    full_url: str,
+    headers: Optional[Dict[str, str]],
+    body_bytes: Optional[bytes],
+    query: Optional[Dict[str, Any]],
+    result_data: Dict[str, Any],
+    cache_ttl: Optional[int],
+    tenant: Optional[str],
+) -> Dict[str, Any]:
+    """Cache an upstream HTTP response (see _http_cacheable for which methods are).
+    
+    Successful responses use the regular TTL. Error statuses listed in
+    cache.negative_statuses are cached with the (shorter) negative_ttl_s
+    and flagged with cached_error so hits can be reported as such; errors
+    whose JSON-encoded body exceeds negative_max_body_bytes are not cached.
+    Range requests and partial (206) responses are never cached, so a
+    cached entry is always the full resource. With
+    cache.respect_upstream_cache_control, the upstream Cache-Control header
+    sets the TTL (or prevents caching) unless the request set `cache`.
+
+    Returns:
+        Meta fields for the response (see _cache_store_meta)
+    """
+    if not _http_cacheable(method, cache_config, cache_ttl):
+        return {}
+    if _is_range_request(headers) or result_data["status_code"] == 206:
+        return {}
+    
+    status_code = result_data["status_code"]
+    ttl_jitter = cache_config.get("ttl_jitter", DEFAULT_TTL_JITTER)
+    if status_code < 400:
+        ttl = cache_ttl
+        if not ttl and cache_config.get("respect_upstream_cache_control"):
+            ttl = _upstream_cache_ttl(result_data.get("headers"))
+            if ttl == 0:
+                return {}
+        ttl = ttl or cache_config.get("ttl_s", 3600)
+        return _cache_store_meta(lambda: cache.set(
+            method, full_url, headers, body_bytes, {**result_data, "cached_at": time.time()},
+            ttl_s=ttl, query=query, allow_post=True, tenant=tenant, ttl_jitter=ttl_jitter,
+            max_ttl_s=_max_ttl(cache_config), vary_headers=cache_config.get("vary_headers"),
+            stale_ttl_s=stale_ttl_s(cache_config, ttl),
+        ))
+    if status_code in cache_config.get("negative_statuses", []):
+        body_size = len(json.dumps(result_data.get("body"), default=str).encode("utf-8"))
+        if body_size > cache_config.get("negative_max_body_bytes", 65536):
+            return {}
+        return _cache_store_meta(lambda: cache.set(
+            method, full_url, headers, body_bytes,
+            {**result_data, "cached_error": True, "cached_at": time.time()},
+            ttl_s=cache_config.get("negative_ttl_s", 30),
+            query=query,
+            allow_post=True,
+            tenant=tenant,
+            ttl_jitter=ttl_jitter,
+            vary_headers=cache_config.get("vary_headers"),
+        ))
+    return {}
+
+
+def _merge_embeddings(
+    embeddings: Optional[EmbeddingsBatch],
+    body_json: Any,
+    response_status: int,
+    cache: Cache,
+    cache_config: Dict[str, Any],
+    full_url: str,
+    cache_ttl: Optional[int],
+    tenant: Optional[str],
+) -> Any:
+    """Merge cached embeddings into an upstream response for the misses, and cache the new ones."""
+    if not embeddings or response_status >= 400:
+        return body_json
+    merged = embeddings.merge(body_json)
+    embeddings.store(
+        cache, full_url, merged, cache_ttl or cache_config.get("ttl_s", 3600), tenant,
+        ttl_jitter=cache_config.get("ttl_jitter", DEFAULT_TTL_JITTER),
+    )
+    return merged
+
+
+def _total_cost(cost_breakdown: Optional[Dict[str, float]]) -> Optional[float]:
+    """cost_usd from an {"input_usd", "output_usd"} breakdown."""
+    if cost_breakdown is None:
+        return None
+    return cost_breakdown["input_usd"] + cost_breakdown["output_usd"]
+
+
+def _billed(cost_usd: Optional[float], cost_multiplier: float) -> Optional[float]:
+    """Cost as billed to the tenant (tenant cost_multiplier markup)."""
+    if cost_usd is None or cost_multiplier == 1.0:
+        return cost_usd
+    return cost_usd * cost_multiplier
+
+
+def _running_stream_cost(
+    adapter: Any,
+    provider: str,
+    model: str,
+    prompt_tokens: int,
+    content: str,
+    usage: Dict[str, int],
+    approximate: bool,
+    cost_multiplier: float,
+) -> Optional[float]:
+    """Billed cost of a stream so far: provider usage where reported, else chars/4 estimates."""
+    prompt_tokens = usage.get("prompt_tokens", prompt_tokens)
+    completion_tokens = usage.get("completion_tokens") or CostEstimator.estimate_tokens(content)
+    breakdown = adapter.get_cost_breakdown(model, prompt_tokens, completion_tokens)
+    if breakdown is None and approximate:
+        breakdown = CostEstimator.approximate_cost_breakdown_from_usage(provider, model, prompt_tokens, completion_tokens)
+    return _billed(_total_cost(breakdown), cost_multiplier)
+
+
+def _billed_breakdown(
+    cost_breakdown: Optional[Dict[str, float]], cost_multiplier: float
+) -> Optional[Dict[str, float]]:
+    if cost_breakdown is None or cost_multiplier == 1.0:
+        return cost_breakdown
+    return {name: value * cost_multiplier for name, value in cost_breakdown.items()}
+
+
+def raw_cost(cost_usd: Optional[float], cost_multiplier: float) -> Optional[float]:
+    """Actual provider cost behind a billed cost_usd."""
+    if cost_usd is None or cost_multiplier == 1.0:
+        return cost_usd
+    return cost_usd / cost_multiplier
+
+
+def _hedging_config(
+    target_config: Dict[str, Any],
+    hedge: Optional[bool],
+    method: str,
+    idempotency_key: Optional[str],
+) -> Optional[Dict[str, Any]]:
+    """Hedging settings for an HTTP request, or None if it is not hedged.
+
+    Only GET/HEAD and keyed requests are safe to send twice. The request's
+    `hedge` wins over the target's `hedging.enabled` (defaults apply to targets
+    without a hedging config).
+    """
+    if hedge is False or not (method.upper() in ["GET", "HEAD"] or idempotency_key):
+        return None
+    config = target_config.get("hedging")
+    if hedge:
+        return config or {}
+    return config if config and config.get("enabled", True) else None
+
+
+def _scaled_timeout_ms(
+    target_config: Dict[str, Any],
+    max_tokens: Optional[int],
+    overrides: Optional[Dict[str, int]],
+) -> Optional[int]:
+    """Provider timeout for an LLM request under llm.timeout_scaling.
+
+    base_ms + per_token_ms * max_tokens, capped at max_ms. None (the fixed
+    timeout_ms applies) without scaling config or max_tokens, or when the
+    request set its own timeout.
+    """
+    scaling = (target_config.get("llm") or {}).get("timeout_scaling")
+    if not scaling or not max_tokens or (overrides and "timeout_ms" in overrides):
+        return None
+    timeout_ms = scaling.get("base_ms", 5000) + scaling.get("per_token_ms", 20) * max_tokens
+    return int(min(timeout_ms, scaling.get("max_ms", 300000)))
+
+
+def _apply_output_cap(
+    llm_config: Dict[str, Any],
+    model: str,
+    max_tokens: Optional[int],
+) -> Tuple[Optional[int], Optional[int], Optional[int]]:
+    """(max_tokens, cap it was clamped to, cap it exceeds) under llm.output_caps.
+
+    A max_tokens above the model's cap ('*' for models without their own) is
+    clamped, or reported as exceeding it with on_exceed: reject. Without a
+    max_tokens the cap becomes the limit.
+    """
+    caps = llm_config.get("output_caps") or {}
+    cap = caps.get(model) or caps.get(DEFAULT_MODEL_KEY)
+    if not cap or (max_tokens is not None and max_tokens <= cap["max_tokens"]):
+        return max_tokens, None, None
+    if max_tokens is not None and cap.get("on_exceed", "clamp") == "reject":
+        return max_tokens, None, cap["max_tokens"]
+    return cap["max_tokens"], cap["max_tokens"], None
+
+
+def _output_cap_message(model: str, max_tokens: int, cap: int) -> str:
+    return f"max_tokens {max_tokens} exceeds the output cap of {cap} tokens for model '{model}'"
+
+
+def _max_ttl(cache_config: Dict[str, Any]) -> Optional[int]:
+    """max_ttl_s to record on cache writes when hits extend the TTL (ttl_on_hit: extend)."""
+    return cache_config.get("max_ttl_s") if cache_config.get("ttl_on_hit") == "extend" else None
+
+
+def _cost_weighted_ttl(cache_config: Dict[str, Any], provider: str, model: str) -> int:
+    """ttl_s, scaled by the model's price under ttl_cost_policy (unpriced models keep ttl_s)."""
+    ttl_s = cache_config.get("ttl_s", 3600)
+    policy = cache_config.get("ttl_cost_policy")
+    pricing = CostEstimator.PRICING_PER_1K.get(provider, {}).get(model) if policy else None
+    if not pricing:
+        return ttl_s
+    price = pricing["prompt"] + pricing["completion"]
+    ttl = ttl_s * (price / policy.get("reference_usd_per_1k", 0.01)) ** policy.get("exponent", 0.5)
+    ttl = max(ttl, policy.get("min_ttl_s", 60))
+    if policy.get("max_ttl_s"):
+        ttl = min(ttl, policy["max_ttl_s"])
+    return int(ttl)
+
+
+def _extend_on_hit(
+    cache: Cache,
+    cache_config: Dict[str, Any],
+    entry: Dict[str, Any],
+    method: str,
+    url: str,
+    headers: Optional[Dict[str, str]],
+    body: Optional[bytes],
+    query: Optional[Dict[str, Any]],
+    allow_post: bool = False,
+    tenant: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Extend a hit entry's TTL (ttl_on_hit: extend) and return its cache_expires_in_s meta."""
+    if cache_config.get("ttl_on_hit") != "extend":
+        return {}
+    expires_in_s = cache.extend_ttl(
+        method, url, headers, body, query, entry, allow_post=allow_post, tenant=tenant,
+        vary_headers=cache_config.get("vary_headers"),
+    )
+    return {"cache_expires_in_s": expires_in_s} if expires_in_s is not None else {}
+
+
+def _cache_store_meta(store: Callable[[], Optional[int]]) -> Dict[str, Any]:
+    """Run a cache write and return the meta fields describing it.
+
+    {"cache_ttl_s": applied TTL} if stored, {"cache_skipped_too_large": True}
+    if the value exceeded max_cache_value_bytes (the response is still served),
+    {} otherwise.
+    """
+    try:
+        ttl_s = store()
+    except CacheValueTooLarge:
+        return {"cache_skipped_too_large": True}
+    return {"cache_ttl_s": ttl_s} if ttl_s is not None else {}
+
+
+def _schema_violation_response(
+    violation: SchemaViolation,
+    target_name: str,
+    path: str,
+    request_id: str,
+    start_time: float,
+    retries: int,
+    tenant: Optional[str],
+) -> ErrorResponse:
+    """502 SCHEMA_VIOLATION for an upstream response that failed response_schema."""
+    duration_ms = int((time.time() - start_time) * 1000)
+    logger.warning(f"Target '{target_name}' response failed schema validation: {violation}")
+    _log_and_metric_http_request(
+        request_id=request_id,
+        target_name=target_name,
+        path=path,
+        outcome="error",
+        latency_ms=duration_ms,
+        cache_hit=False,
+        idempotent_hit=False,
+        error_code=ErrorCode.SCHEMA_VIOLATION.value,
+        upstream_status=502,
+        tenant=tenant,
+    )
+    http_requests_total.labels(target=target_name, status="error").inc()
+    latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="upstream_error",
+            code=ErrorCode.SCHEMA_VIOLATION.value,
+            message=f"Upstream response does not match response_schema at {violation.path}: {violation.message}",
+            retryable=violation.retryable,
+            source="upstream",
+            target=target_name,
+            status_code=502,
+            details={"path": violation.path},
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=retries,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _embeddings_mismatch_response(
+    mismatch: EmbeddingsMismatch,
+    target_name: str,
+    path: str,
+    request_id: str,
+    start_time: float,
+    retries: int,
+    tenant: Optional[str],
+) -> ErrorResponse:
+    """502 EMBEDDINGS_MISMATCH for a paid embeddings response that could not be merged.
+
+    Not retryable: the upstream call was billed, so `details` carries its
+    usage and cost_usd instead of inviting a second paid call.
+    """
+    duration_ms = int((time.time() - start_time) * 1000)
+    usage = mismatch.usage or {}
+    prompt_tokens = usage.get("prompt_tokens") or usage.get("total_tokens")
+    cost_usd = CostEstimator.embedding_cost(mismatch.model, prompt_tokens) if prompt_tokens else None
+    logger.warning(f"Target '{target_name}' embeddings response could not be merged: {mismatch}")
+    _log_and_metric_http_request(
+        request_id=request_id,
+        target_name=target_name,
+        path=path,
+        outcome="error",
+        latency_ms=duration_ms,
+        cache_hit=False,
+        idempotent_hit=False,
+        error_code=ErrorCode.EMBEDDINGS_MISMATCH.value,
+        upstream_status=502,
+        tenant=tenant,
+    )
+    http_requests_total.labels(target=target_name, status="error").inc()
+    latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="upstream_error",
+            code=ErrorCode.EMBEDDINGS_MISMATCH.value,
+            message=f"Upstream embeddings response does not match the inputs sent: {mismatch}",
+            retryable=False,
+            source="upstream",
+            target=target_name,
+            status_code=502,
+            details={"usage": mismatch.usage, "model": mismatch.model, "cost_usd": cost_usd},
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=retries,
+            duration_ms=duration_ms,
+            cost_usd=cost_usd,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+# Provider response header carrying its request ID, unless the target sets upstream_request_id_header
+DEFAULT_UPSTREAM_REQUEST_ID_HEADER = "x-request-id"
+PROVIDER_REQUEST_ID_HEADERS = {"anthropic": "request-id"}
+
+
+def _served_region(target_config: Dict[str, Any], client: UpstreamHTTPClient) -> Optional[str]:
+    """Region that served the last response of a target with `regions` (meta.region)."""
+    regions = target_config.get("regions")
+    return region_name(regions, client.served_base_url) if regions else None
+
+
+def _upstream_request_id(
+    target_config: Dict[str, Any],
+    headers: Optional[Mapping[str, str]],
+    provider: Optional[str] = None,
+) -> Optional[str]:
+    """Provider request ID from upstream response headers (for support tickets)."""
+    if not headers:
+        return None
+    header = (
+        target_config.get("upstream_request_id_header")
+        or PROVIDER_REQUEST_ID_HEADERS.get(provider, DEFAULT_UPSTREAM_REQUEST_ID_HEADER)
+    ).lower()
+    for name, value in headers.items():
+        if name.lower() == header:
+            return value
+    return None
+
+
+def _maintenance_error(
+    target_name: str,
+    request_id: str,
+    duration_ms: int,
+    provider: Optional[str] = None,
+    model: Optional[str] = None,
+) -> ErrorResponse:
+    """503 MAINTENANCE_CACHE_ONLY for a cache miss while the target is in maintenance mode."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="maintenance",
+            code=ErrorCode.MAINTENANCE_CACHE_ONLY.value,
+            message=f"Target '{target_name}' is in maintenance mode: only cached responses are served",
+            retryable=True,
+            source="reliapi",
+            target=target_name,
+            status_code=503,
+            hint="Retry after the maintenance window or send a request that is already cached",
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _plugin_error(e: PluginError, target_name: str, request_id: str, duration_ms: int) -> ErrorResponse:
+    """500 PLUGIN_ERROR for a target plugin that failed, timed out or returned invalid output."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="plugin_error",
+            code=ErrorCode.PLUGIN_ERROR.value,
+            message=str(e),
+            retryable=False,
+            source="reliapi",
+            target=target_name,
+            status_code=500,
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _target_disabled_error(
+    target_name: str,
+    request_id: str,
+    duration_ms: int,
+    provider: Optional[str] = None,
+    model: Optional[str] = None,
+) -> ErrorResponse:
+    """503 TARGET_DISABLED for a target turned off via the kill switch."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="maintenance",
+            code=ErrorCode.TARGET_DISABLED.value,
+            message=f"Target '{target_name}' is disabled",
+            retryable=True,
+            source="reliapi",
+            target=target_name,
+            status_code=503,
+            hint="Traffic to this target was stopped by an operator; retry after it is re-enabled",
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _saturation_code(error: QueueTimeout) -> str:
+    return (ErrorCode.OVERLOADED if isinstance(error, Overloaded) else ErrorCode.QUEUE_TIMEOUT).value
+
+
+def _queue_timeout_error(
+    target_name: str,
+    request_id: str,
+    timer: RequestTimer,
+    error: QueueTimeout,
+    provider: Optional[str] = None,
+    model: Optional[str] = None,
+) -> ErrorResponse:
+    """503 QUEUE_TIMEOUT (or OVERLOADED when load shedding) for a saturated target."""
+    duration_ms = int((time.time() - timer.start_time) * 1000)
+    shed = isinstance(error, Overloaded)
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="overloaded" if shed else "queue_timeout",
+            code=_saturation_code(error),
+            message=str(error),
+            retryable=True,
+            source="reliapi",
+            target=target_name,
+            status_code=503,
+            retry_after_s=error.retry_after_s,
+            hint="Target is at its concurrency limit; retry after retry_after_s",
+            details=None if shed else {"max_queue_wait_ms": error.max_queue_wait_ms},
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            **timer.breakdown(duration_ms),
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _unsupported_param_error(
+    target_name: str,
+    request_id: str,
+    duration_ms: int,
+    provider: str,
+    model: Optional[str],
+    params: List[str],
+) -> ErrorResponse:
+    """400 UNSUPPORTED_PARAM for request parameters the target's provider cannot honor."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="client_error",
+            code=ErrorCode.UNSUPPORTED_PARAM.value,
+            message=f"Provider '{provider}' does not support: {', '.join(params)}",
+            retryable=False,
+            source="reliapi",
+            target=target_name,
+            status_code=400,
+            details={"params": params, "provider": provider},
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _allowed_params(
+    llm_config: Dict[str, Any], target_name: str, params: Dict[str, Any]
+) -> Tuple[Dict[str, Any], List[str]]:
+    """(params to forward, params to reject) under llm.allowed_params / on_unknown_param."""
+    on_unknown = llm_config.get("on_unknown_param", DEFAULT_ON_UNKNOWN_PARAM)
+    params, disallowed = filter_params(params, llm_config.get("allowed_params"), on_unknown)
+    for name in disallowed:
+        llm_params_filtered_total.labels(target=target_name, param=name, action=on_unknown).inc()
+    if disallowed and on_unknown == "forward":
+        logger.info(f"Forwarding params outside allowed_params for target '{target_name}': {disallowed}")
+    return params, disallowed if on_unknown == "reject" else []
+
+
+def _param_not_allowed_error(
+    target_name: str,
+    request_id: str,
+    duration_ms: int,
+    provider: Optional[str],
+    model: Optional[str],
+    params: List[str],
+) -> ErrorResponse:
+    """400 PARAM_NOT_ALLOWED for request parameters outside the target's llm.allowed_params."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="client_error",
+            code=ErrorCode.PARAM_NOT_ALLOWED.value,
+            message=f"Target '{target_name}' does not accept: {', '.join(params)}",
+            retryable=False,
+            source="reliapi",
+            target=target_name,
+            status_code=400,
+            details={"params": params},
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
 def create_http_client(
     target_config: Dict[str, Any],
     target_name: str,
     key_pool_manager: Optional[KeyPoolManager] = None,
     provider: Optional[str] = None,
+    client_ip: Optional[str] = None,
+    request_id: Optional[str] = None,
 ) -> Tuple[UpstreamHTTPClient, Optional[ProviderKey], str]:
-    """Create HTTP client for target."""
+    """Create HTTP client for target (client_ip is forwarded if the target allows it, request_id always).
+
+    Targets with `regions` get the fastest healthy region as base URL and the others as failovers.
+    """
     base_url = target_config["base_url"]
     timeout_ms = target_config.get("timeout_ms", 20000)
     timeout_s = timeout_ms / 1000.0
     
-    # Circuit breaker
-    circuit_config = target_config.get("circuit", {})
-    circuit_breaker = CircuitBreaker(
-        failures_to_open=circuit_config.get("error_threshold", 5),
-        open_ttl_s=circuit_config.get("cooldown_s", 60),
-    )
+    # Circuit breaker (shared across requests to the target)
+    circuit_breaker = circuit_breakers.get(target_name, target_config.get("circuit"))
+    
+    # Regional endpoints: lowest-latency healthy region first, the rest for failover
+    failover_base_urls: List[str] = []
+    latency_observer = None
+    if target_config.get("regions"):
+        ranked = [region_url(region) for region in region_router.rank(
+            target_name, target_config["regions"], circuit_breaker
+        )]
+        base_url, failover_base_urls = ranked[0], ranked[1:]
+        latency_observer = functools.partial(region_router.observe, target_name)
     
     # Retry matrix
     retry_config = target_config.get("retry_matrix", {})
@@ -312,15 +1214,551 @@ def create_http_client(
         retry_matrix=retry_matrix,
         circuit_breaker=circuit_breaker,
         auth=auth,
+        retry_budget=retry_budget.scope(target_name, target_config.get("retry_budget")),
+        default_headers=outbound_headers(target_config, client_ip, request_id),
+        failover_base_urls=failover_base_urls,
+        latency_observer=latency_observer,
+        # gRPC targets: requests are transcoded to the configured methods
+        transport=GrpcTransport(target_name, target_config["grpc"]) if target_config.get("grpc") else None,
+        signer=request_signer(target_config),
     )
     
     return client, selected_key, auth_source
 
 
-async def handle_http_proxy(
+async def _acquire_model_rate_limit(
+    model_rate_limiter: Optional[ModelRateLimiter],
     target_name: str,
-    method: str,
-    path: str,
+    model: str,
+    llm_config: Dict[str, Any],
+    messages: List[Dict[str, str]],
+    max_tokens: Optional[int],
+) -> Tuple[bool, Optional[ModelRateReservation], Optional[float], Optional[str]]:
+    """Apply per-model RPM/TPM caps before calling the provider.
+    
+    Returns:
+        Tuple of (allowed, reservation, retry_after_s, limiting)
+    """
+    limits = resolve_model_limits(llm_config, model)
+    if not model_rate_limiter or not limits:
+        return True, None, None, None
+    
+    reservation, retry_after_s, limiting = await model_rate_limiter.acquire(
+        target_name, model, limits, estimate_request_tokens(messages, max_tokens)
+    )
+    if reservation is None:
+        model_rate_limit_events_total.labels(target=target_name, model=model, limit=limiting, action="rejected").inc()
+    elif reservation.delayed_by:
+        model_rate_limit_events_total.labels(
+            target=target_name, model=model, limit=reservation.delayed_by, action="delayed"
+        ).inc()
+    _update_model_rate_metrics(model_rate_limiter, target_name, model, limits)
+    return reservation is not None, reservation, retry_after_s, limiting
+
+
+async def _admit_cache_hit(
+    model_rate_limiter: Optional[ModelRateLimiter],
+    target_config: Dict[str, Any],
+    target_name: str,
+    model: str,
+    llm_config: Dict[str, Any],
+) -> Tuple[bool, Optional[float], Optional[str]]:
+    """Count an LLM cache hit against the per-model RPM cap (count_cache_hits_against_rate_limit).
+
+    Hits are free by default: no upstream work means no rate-limit or concurrency cost.
+    Counted hits take a request slot but no tokens.
+
+    Returns:
+        Tuple of (allowed, retry_after_s, limiting)
+    """
+    if not target_config.get("count_cache_hits_against_rate_limit"):
+        return True, None, None
+    allowed, _, retry_after_s, limiting = await _acquire_model_rate_limit(
+        model_rate_limiter, target_name, model, llm_config, [], None
+    )
+    return allowed, retry_after_s, limiting
+
+
+def _model_rate_limit_error(
+    target_name: str,
+    request_id: str,
+    provider: str,
+    model: str,
+    retry_after_s: Optional[float],
+    limiting: str,
+    duration_ms: int,
+) -> ErrorResponse:
+    """429 RATE_LIMIT_RELIAPI for a request over the per-model RPM/TPM cap."""
+    rate_scheduler_429_total.labels(source="reliapi").inc()
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(
+            type="rate_limit",
+            code=ErrorCode.RATE_LIMIT_RELIAPI.value,
+            message=f"Model {limiting.upper()} limit exceeded for '{model}'",
+            retryable=retry_after_s is not None,
+            source="reliapi",
+            retry_after_s=retry_after_s,
+            target=target_name,
+            status_code=429,
+            hint="Request exceeds the configured per-model limit" if retry_after_s is None else "Upstream provider tier limit is being protected",
+        ),
+        meta=MetaResponse(
+            target=target_name,
+            provider=provider,
+            model=model,
+            cache_hit=False,
+            idempotent_hit=False,
+            retries=0,
+            duration_ms=duration_ms,
+            request_id=request_id,
+            trace_id=None,
+        ),
+    )
+
+
+def _model_rate_limit_event(model: str, retry_after_s: Optional[float], limiting: str) -> str:
+    """Stream `error` event for a request over the per-model RPM/TPM cap."""
+    rate_scheduler_429_total.labels(source="reliapi").inc()
+    error_data = {
+        "code": ErrorCode.RATE_LIMIT_RELIAPI.value,
+        "message": f"Model {limiting.upper()} limit exceeded for '{model}'",
+        "upstream_status": 429,
+        "retry_after_s": retry_after_s,
+    }
+    return f"event: error\ndata: {json.dumps(error_data)}\n\n"
+
+
+async def _wait_for_idempotent_result(
+    idempotency: IdempotencyManager, idempotency_key: str, tenant: Optional[str], max_wait_s: float = 30
+) -> Optional[Dict[str, Any]]:
+    """Poll (with backoff) until the in-progress request stores its result, or None after max_wait_s."""
+    waited = 0.0
+    poll_interval = 0.05
+    while idempotency.is_in_progress(idempotency_key, tenant=tenant) and waited < max_wait_s:
+        await asyncio.sleep(poll_interval)
+        waited += poll_interval
+        poll_interval = min(poll_interval * 1.5, 0.5)
+        existing_result = idempotency.get_result(idempotency_key, tenant=tenant)
+        if existing_result:
+            return existing_result
+    return idempotency.get_result(idempotency_key, tenant=tenant)
+
+
+def _store_failover_result(
+    idempotency: IdempotencyManager,
+    idempotency_key: str,
+    result: SuccessResponse,
+    fallback_target: str,
+    ttl_s: int,
+    tenant: Optional[str],
+) -> None:
+    """Store a fallback target's response under the primary request's idempotency key."""
+    idempotency.store_result(
+        idempotency_key,
+        {
+            "data": result.data,
+            "cost_usd": result.meta.cost_usd,
+            "cost_breakdown": result.meta.cost_breakdown,
+            "upstream_request_id": result.meta.upstream_request_id,
+            "provider": result.meta.provider,
+            "model": result.meta.model,
+            "fallback_target": fallback_target,
+        },
+        ttl_s=ttl_s,
+        tenant=tenant,
+    )
+    idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+
+
+def _idempotent_origin(existing_result: Dict[str, Any], provider: Optional[str], model: str) -> Dict[str, Any]:
+    """Provider/model meta of a replayed result: failovers replay as served by their fallback target."""
+    if not existing_result.get("fallback_target"):
+        return {"provider": provider, "model": model}
+    return {
+        "provider": existing_result.get("provider"),
+        "model": existing_result.get("model"),
+        "fallback_used": True,
+        "fallback_target": existing_result["fallback_target"],
+    }
+
+
+def _idempotent_stream_replay(
+    existing_result: Dict[str, Any],
+    target_name: str,
+    provider: str,
+    model: str,
+    request_id: str,
+    metadata: Optional[Any],
+) -> List[str]:
+    """SSE events replaying a completed stream stored under an idempotency key (one chunk)."""
+    data = existing_result.get("data", {})
+    meta_data = {
+        "target": target_name,
+        "provider": provider,
+        "model": model,
+        "request_id": request_id,
+        "idempotent_hit": True,
+        "metadata": metadata,
+    }
+    done_data = {
+        "finish_reason": data.get("finish_reason", "stop"),
+        "usage": data.get("usage"),
+        "cost_usd": existing_result.get("cost_usd"),
+        "cost_breakdown": existing_result.get("cost_breakdown"),
+        "idempotent_hit": True,
+        "metadata": metadata,
+    }
+    return [
+        f"event: meta\ndata: {json.dumps(meta_data)}\n\n",
+        f"event: chunk\ndata: {json.dumps({'delta': data.get('content', ''), 'finish_reason': None})}\n\n",
+        f"event: done\ndata: {json.dumps(done_data)}\n\n",
+    ]
+
+
+async def collapse_llm_stream(
+    events: AsyncIterator[str], target_name: str, request_id: str
+) -> Union[SuccessResponse, ErrorResponse]:
+    """Consume a handle_llm_stream_generator stream into one buffered response (`collapse_stream`).
+
+    Content is assembled from the chunks; cost, usage and meta come from the
+    meta and done events, so they match what a streaming client would see.
+    """
+    start_time = time.time()
+    meta_fields = set(MetaResponse.model_fields)
+    meta: Dict[str, Any] = {"target": target_name, "request_id": request_id}
+    parts: List[str] = []
+    done: Optional[Dict[str, Any]] = None
+    error: Optional[Dict[str, Any]] = None
+    async for event in events:
+        name, data = parse_sse_event(event)
+        if name == "chunk":
+            parts.append(data.get("delta") or "")
+        elif name in ("meta", "done"):
+            meta.update({k: v for k, v in data.items() if k in meta_fields and v is not None})
+            if name == "done":
+                done = data
+        elif name == "error":
+            error = data
+            meta.update({k: v for k, v in data.items() if k in meta_fields and v is not None})
+    meta["duration_ms"] = int((time.time() - start_time) * 1000)
+    meta.setdefault("cache_hit", False)
+    meta.setdefault("retries", 0)
+
+    if error is not None or done is None:
+        error = error or {"code": ErrorCode.INTERNAL_ERROR.value, "message": "Stream ended without a done event"}
+        status_code = error.get("upstream_status") or 500
+        return ErrorResponse(
+            success=False,
+            error=ErrorDetail(
+                type="rate_limit" if status_code == 429 else "upstream_error",
+                code=error.get("code") or ErrorCode.INTERNAL_ERROR.value,
+                message=error.get("message") or "Stream failed",
+                retryable=status_code == 429 or status_code >= 500,
+                source=error.get("source") or "upstream",
+                retry_after_s=error.get("retry_after_s"),
+                target=target_name,
+                status_code=status_code,
+            ),
+            meta=MetaResponse(**meta),
+        )
+
+    content = "".join(parts)
+    finish_reason = done.get("finish_reason") or "stop"
+    return SuccessResponse(
+        success=True,
+        data={
+            "content": content,
+            "role": "assistant",
+            "finish_reason": finish_reason,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": content},
+                "finish_reason": finish_reason,
+            }],
+            "usage": done.get("usage"),
+        },
+        meta=MetaResponse(**meta),
+    )
+
+
+def _reconcile_model_rate_limit(
+    model_rate_limiter: Optional[ModelRateLimiter],
+    reservation: Optional[ModelRateReservation],
+    target_name: str,
+    model: str,
+    llm_config: Dict[str, Any],
+    total_tokens: int,
+) -> None:
+    """Replace the TPM reservation with actual token usage from the provider."""
+    if not model_rate_limiter or not reservation or not total_tokens:
+        return
+    model_rate_limiter.reconcile(reservation, total_tokens)
+    limits = resolve_model_limits(llm_config, model)
+    if limits:
+        _update_model_rate_metrics(model_rate_limiter, target_name, model, limits)
+
+
+def _observe_payload_sizes(
+    target_name: str,
+    kind: str,
+    model: str,
+    request_body: Optional[bytes],
+    response_body: Optional[bytes],
+) -> None:
+    """Record upstream request/response body sizes."""
+    request_size_bytes.labels(target=target_name, kind=kind, model=model).observe(len(request_body or b""))
+    response_size_bytes.labels(target=target_name, kind=kind, model=model).observe(len(response_body or b""))
+
+
+def _observe_llm_usage(
+    target_name: str,
+    model: str,
+    prompt_tokens: int,
+    completion_tokens: int,
+    cost_usd: Optional[float],
+) -> None:
+    """Record per-model token and cost distributions for an upstream LLM call."""
+    llm_prompt_tokens.labels(target=target_name, model=model).observe(prompt_tokens)
+    llm_completion_tokens.labels(target=target_name, model=model).observe(completion_tokens)
+    if cost_usd is not None:
+        llm_request_cost_usd.labels(target=target_name, model=model).observe(cost_usd)
+
+
+class StreamIdleTimeout(Exception):
+    """Upstream stream sent no chunk within the idle timeout (or no first chunk in time)."""
+
+    def __init__(self, first_chunk: bool = False):
+        super().__init__("first chunk timeout" if first_chunk else "idle timeout")
+        self.first_chunk = first_chunk
+
+
+async def _iter_with_idle_timeout(
+    stream: AsyncIterator[Dict[str, Any]],
+    idle_timeout_s: float,
+    first_chunk_timeout_s: Optional[float] = None,
+) -> AsyncIterator[Dict[str, Any]]:
+    """Yield chunks from stream, aborting it if the gap between chunks exceeds idle_timeout_s.
+
+    The wait for the first chunk uses first_chunk_timeout_s instead (idle_timeout_s if
+    None), since providers may take much longer to start a completion than between
+    tokens. The upstream stream is closed when the wait times out or the consumer stops.
+    """
+    iterator = stream.__aiter__()
+    first_chunk = True
+    try:
+        while True:
+            timeout_s = first_chunk_timeout_s if first_chunk and first_chunk_timeout_s is not None else idle_timeout_s
+            try:
+                chunk = await asyncio.wait_for(iterator.__anext__(), timeout_s)
+            except StopAsyncIteration:
+                return
+            except asyncio.TimeoutError:
+                raise StreamIdleTimeout(first_chunk=first_chunk) from None
+            first_chunk = False
+            yield chunk
+    finally:
+        aclose = getattr(iterator, "aclose", None)
+        if aclose:
+            await aclose()
+
+
+def _stream_retryable(error: BaseException) -> bool:
+    """Stream failures llm.stream_retry may retry: network errors, idle timeouts, 429 and 5xx.
+
+    Not 504: streams are only retried under an idempotency key, and the provider may still be
+    generating (and billing) the first attempt.
+    """
+    if isinstance(error, httpx.HTTPStatusError):
+        status = error.response.status_code
+        return status not in AMBIGUOUS_FAILOVER_STATUSES and (status == 429 or status >= 500)
+    return isinstance(error, (httpx.RequestError, StreamIdleTimeout))
+
+
+def _stream_retry_meta(stats: Dict[str, Any]) -> Dict[str, Any]:
+    """`retries`/`stream_restarted` fields for stream done and error events."""
+    if not stats.get("retries"):
+        return {}
+    return {"retries": stats["retries"], "stream_restarted": stats.get("restarted")}
+
+
+def _skip_nondeterministic_cache(cache_config: Dict[str, Any], temperature: Optional[float]) -> bool:
+    """Whether a sampled (temperature > 0) response must bypass the cache.
+    
+    Sampled outputs are meant to vary, so they are only cached with
+    `cache.cache_nondeterministic: true`. A seed does not change this:
+    providers only make seeded sampling best-effort reproducible.
+    """
+    return bool(temperature and temperature > 0) and not cache_config.get("cache_nondeterministic", False)
+
+
+def _unknown_model_policy(llm_config: Dict[str, Any], provider: str, model: str) -> Optional[str]:
+    """on_unknown_model policy to apply, or None if the model is priced."""
+    if CostEstimator.is_priced(provider, model):
+        return None
+    return llm_config.get("on_unknown_model", "allow")
+
+
+def _unknown_model_message(provider: str, model: str) -> str:
+    return f"No pricing for model '{model}' (provider '{provider}'); target rejects unpriced models (on_unknown_model: reject)"
+
+
+def _merge_stream_usage(current: Dict[str, int], usage: Dict[str, Any]) -> Dict[str, int]:
+    """Merge a stream usage chunk; providers may split usage across chunks."""
+    merged = dict(current)
+    for key in ("prompt_tokens", "completion_tokens", "cached_prompt_tokens", "cache_write_tokens"):
+        if key in usage:
+            merged[key] = usage[key]
+    cached_tokens = (usage.get("prompt_tokens_details") or {}).get("cached_tokens")
+    if cached_tokens is not None:
+        merged["cached_prompt_tokens"] = cached_tokens
+    return merged
+
+
+def _estimate_usage(messages: List[Dict[str, Any]], completions: List[str]) -> Dict[str, int]:
+    """Local token counts (chars/4) for responses without provider usage, in parse_usage shape."""
+    return {
+        "prompt_tokens": CostEstimator.estimate_prompt_tokens(messages),
+        "completion_tokens": sum(CostEstimator.estimate_tokens(text) for text in completions),
+        "cached_prompt_tokens": 0,
+        "cache_write_tokens": 0,
+    }
+
+
+async def _summarize_history(
+    messages: List[Dict[str, Any]],
+    target_config: Dict[str, Any],
+    target_name: str,
+    adapter: Any,
+    provider: str,
+    model: str,
+    cache: Cache,
+    tenant: Optional[str] = None,
+    key_pool_manager: Optional[KeyPoolManager] = None,
+    request_id: Optional[str] = None,
+) -> Tuple[List[Dict[str, Any]], Dict[str, Any], Optional[float]]:
+    """Replace older turns with a summary if the history exceeds llm.summarization.threshold_tokens.
+
+    Returns:
+        (messages, meta fields, summarization cost in USD); the messages are
+        unchanged and the meta empty when nothing was summarized or the
+        summarization call failed
+    """
+    config = target_config["llm"]["summarization"]
+    split = split_history(messages, config)
+    if not split:
+        return messages, {}, None
+    older, recent = split
+    
+    summary_model = config.get("model") or model
+    api_path = adapter.api_path(summary_model)
+    body = canonical_json(adapter.prepare_request(
+        messages=summarizer_messages(older, config),
+        model=summary_model,
+        max_tokens=config.get("max_summary_tokens", 256),
+        temperature=0.0,
+    )).encode()
+    # Own cache URL, so a chat request never reads a stored summary as a completion
+    cache_url = f"{target_config['base_url']}{api_path}#summary"
+    cache_config = target_config.get("cache", {})
+    cost_usd = None
+    cached = None
+    if cache_config.get("enabled", True):
+        cached = cache.get("POST", cache_url, None, body, None, allow_post=True, tenant=tenant)
+    if cached:
+        summary = cached["summary"]
+    else:
+        client, _, _ = create_http_client(target_config, target_name, key_pool_manager, provider, request_id=request_id)
+        try:
+            response = await client.request(
+                method="POST",
+                path=api_path,
+                headers={"Content-Type": "application/json"},
+                body=body,
+                params=None,
+            )
+            response_body = await response.aread()
+            # Status first: a 4xx/5xx body is often HTML or plain text
+            if response.status_code >= 400:
+                raise ValueError(f"status {response.status_code}")
+            response_json = json.loads(response_body)
+            summary = adapter.parse_response(response_json).get("content", "").strip()
+            if not summary:
+                raise ValueError("empty summary")
+            usage = adapter.parse_usage(response_json)
+            cost_usd = _total_cost(adapter.get_cost_breakdown(
+                summary_model, usage["prompt_tokens"], usage["completion_tokens"],
+            ))
+        except Exception as e:
+            # Summarization is an optimization: the request goes ahead with the full history
+            logger.warning(f"Target '{target_name}' history summarization failed, sending full history: {e}")
+            return messages, {}, None
+        finally:
+            await client.close()
+        if cache_config.get("enabled", True):
+            try:
+                cache.set(
+                    "POST", cache_url, None, body, {"summary": summary},
+                    ttl_s=cache_config.get("ttl_s", 3600), query=None, allow_post=True, tenant=tenant,
+                )
+            except CacheValueTooLarge:
+                pass
+    
+    summarized = replace_history(messages, recent, summary)
+    return summarized, {
+        "history_summarized": True,
+        "history_tokens_saved": tokens_saved(messages, summarized),
+    }, cost_usd
+
+
+def _summary_cost_estimate(
+    llm_config: Dict[str, Any],
+    provider: str,
+    model: str,
+    messages: List[Dict[str, Any]],
+    approximate: bool = False,
+) -> Optional[float]:
+    """Estimated cost of the llm.summarization call a request would trigger (None if it triggers none)."""
+    config = llm_config.get("summarization")
+    split = split_history(messages, config) if config else None
+    if not split:
+        return None
+    return CostEstimator.estimate_from_messages(
+        provider, config.get("model") or model, summarizer_messages(split[0], config),
+        config.get("max_summary_tokens", 256), approximate=approximate,
+    )
+
+
+def _with_summary_estimate(cost_estimate_usd: Optional[float], summary_estimate_usd: Optional[float]) -> Optional[float]:
+    """Pre-call estimate including the summarization call (the full history is still the worst case)."""
+    if summary_estimate_usd is None:
+        return cost_estimate_usd
+    return (cost_estimate_usd or 0.0) + summary_estimate_usd
+
+
+def _choice_counts(n: Optional[int], data: Dict[str, Any]) -> Dict[str, int]:
+    """`requested_n`/`returned_n` meta fields for n > 1 requests (empty otherwise)."""
+    if not n or n <= 1:
+        return {}
+    return {"requested_n": n, "returned_n": len(data.get("choices") or [])}
+
+
+def _update_model_rate_metrics(
+    model_rate_limiter: ModelRateLimiter,
+    target_name: str,
+    model: str,
+    limits: Dict[str, Any],
+) -> None:
+    """Export remaining RPM/TPM for a target+model."""
+    for limit, remaining in model_rate_limiter.get_remaining(target_name, model, limits).items():
+        if remaining is not None:
+            model_rate_limit_remaining.labels(target=target_name, model=model, limit=limit).set(remaining)
+
+
+async def handle_http_proxy(
+    target_name: str,
+    method: str,
+    path: str,
     headers: Optional[Dict[str, str]],
     query: Optional[Dict[str, Any]],
     body: Optional[str],
@@ -331,13 +1769,18 @@ async def handle_http_proxy(
     idempotency: IdempotencyManager,
     request_id: str,
     tenant: Optional[str] = None,
+    tier: str = "free",
     key_pool_manager: Optional[KeyPoolManager] = None,
     rate_scheduler: Optional[RateScheduler] = None,
     client_profile_name: Optional[str] = None,
     client_profile_manager: Optional[ClientProfileManager] = None,
-) -> Union[SuccessResponse, ErrorResponse]:
-    """Handle HTTP proxy request."""
+    overrides: Optional[Dict[str, int]] = None,
+    client_ip: Optional[str] = None,
+    hedge: Optional[bool] = None,
+) -> Union[SuccessResponse, ErrorResponse, HTTPStreamResult]:
+    """Handle HTTP proxy request (HTTPStreamResult when a content_types stream rule matches)."""
     start_time = time.time()
+    timer = RequestTimer(start_time)
     retries = 0
     # Use KeySwitchState for proper tracking across request lifecycle
     key_switch_state = KeySwitchState()
@@ -365,6 +1808,26 @@ async def handle_http_proxy(
             ),
         )
     
+    # Per-request timeout/retry overrides (X-ReliAPI-* headers, already clamped)
+    target_config = apply_request_overrides(target_config, overrides)
+    
+    # Kill switch: rejected before the cache unless the target serves cache hits while disabled
+    if kill_switch.is_disabled(target_name) and not target_config.get("serve_cache_when_disabled"):
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
+            path=path,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.TARGET_DISABLED.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _target_disabled_error(target_name, request_id, duration_ms)
+    
     # Build full URL
     base_url = target_config["base_url"].rstrip("/")
     full_url = f"{base_url}{path}"
@@ -372,31 +1835,71 @@ async def handle_http_proxy(
     # Prepare body
     body_bytes = body.encode() if body else None
     
-    # Check cache for GET/HEAD
+    # Check cache (GET/HEAD, or POST opted in with cache.methods)
     cache_hit = False
-    if method.upper() in ["GET", "HEAD"]:
-        cache_config = target_config.get("cache", {})
-        if cache_config.get("enabled", True):
+    cache_config = target_config.get("cache", {})
+    # Body identity for cache keys and idempotency hashes; the upstream still gets body_bytes
+    key_body = canonical_body(body_bytes) if cache_config.get("canonical_json_body") else body_bytes
+    content_rules = target_config.get("content_types")
+    request_rule = select_content_rule(content_rules, headers)
+    # Range requests bypass the cache (a cached full body is not sliced locally)
+    if _http_cacheable(method, cache_config, cache_ttl) and not _is_range_request(headers):
+        if request_rule.get("cache", True):
             ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-            cached = cache.get(method, full_url, headers, body_bytes, query, tenant=tenant)
+            health_reason = health_ttl_reason(target_name, target_config)
+            cached = cache.get(
+                method, full_url, headers, key_body, query, allow_post=True, tenant=tenant,
+                vary_headers=cache_config.get("vary_headers"), serve_stale=health_reason is not None,
+                legacy_body=body_bytes,
+            )
             if cached:
                 cache_hit = True
+                cached_error = cached.get("cached_error", False)
+                cached_status = cached.get("status_code", 200)
                 duration_ms = int((time.time() - start_time) * 1000)
                 # Update metrics and log
                 _log_and_metric_http_request(
                     request_id=request_id,
                     target_name=target_name,
                     path=path,
-                    outcome="success",
+                    outcome="error" if cached_status >= 500 else "success",
                     latency_ms=duration_ms,
                     cache_hit=True,
                     idempotent_hit=False,
+                    error_code=ErrorCode.from_http_status(cached_status).value if cached_status >= 500 else None,
+                    upstream_status=cached_status if cached_error else None,
                     tenant=tenant,
                 )
                 # Legacy metrics
                 cache_hits_total.labels(target=target_name, kind="http", tenant=tenant or "default").inc()
-                http_requests_total.labels(target=target_name, status="success").inc()
-                latency_ms.labels(target=target_name, status="success").observe(duration_ms)
+                legacy_status = "error" if cached_status >= 500 else "success"
+                http_requests_total.labels(target=target_name, status=legacy_status).inc()
+                latency_ms.labels(target=target_name, status=legacy_status).observe(duration_ms)
+                if cached_status >= 500:
+                    # Negatively cached upstream server error
+                    return ErrorResponse(
+                        success=False,
+                        error=ErrorDetail(
+                            type="upstream_error",
+                            code=ErrorCode.from_http_status(cached_status).value,
+                            message=f"Upstream returned {cached_status} (cached)",
+                            retryable=True,
+                            target=target_name,
+                            status_code=cached_status,
+                        ),
+                        meta=MetaResponse(
+                            target=target_name,
+                            cache_hit=True,
+                            cached_error=True,
+                            idempotent_hit=False,
+                            retries=0,
+                            duration_ms=duration_ms,
+                            upstream_request_id=_upstream_request_id(target_config, cached.get("headers")),
+                            **_cached_response_age(cached),
+                            request_id=request_id,
+                            trace_id=None,
+                        ),
+                    )
                 return SuccessResponse(
                     success=True,
                     data={
@@ -407,24 +1910,72 @@ async def handle_http_proxy(
                     meta=MetaResponse(
                         target=target_name,
                         cache_hit=True,
+                        cached_error=True if cached_error else None,
                         idempotent_hit=False,
                         retries=0,
                         duration_ms=duration_ms,
+                        **timer.breakdown(duration_ms),
+                        upstream_request_id=_upstream_request_id(target_config, cached.get("headers")),
+                        **_cached_response_age(cached),
+                        **_ttl_extended(cached, target_name, "http", health_reason),
+                        **_extend_on_hit(
+                            cache, cache_config, cached, method, full_url, headers, key_body, query,
+                            allow_post=True, tenant=tenant,
+                        ),
                         request_id=request_id,
                         trace_id=None,
                     ),
                 )
     
+    # Batch embeddings: inputs are cached one by one, only the misses go upstream
+    embeddings = None
+    upstream_body = body_bytes
+    if cache_config.get("embeddings") and cache_config.get("enabled", True):
+        embeddings = EmbeddingsBatch.parse(method, path, body_bytes)
+    if embeddings:
+        embeddings.lookup(cache, full_url, tenant)
+        if not embeddings.misses:
+            duration_ms = int((time.time() - start_time) * 1000)
+            _log_and_metric_http_request(
+                request_id=request_id,
+                target_name=target_name,
+                path=path,
+                outcome="success",
+                latency_ms=duration_ms,
+                cache_hit=True,
+                idempotent_hit=False,
+                tenant=tenant,
+            )
+            cache_hits_total.labels(target=target_name, kind="http", tenant=tenant or "default").inc()
+            return SuccessResponse(
+                success=True,
+                data={"status_code": 200, "headers": {}, "body": embeddings.cached_body()},
+                meta=MetaResponse(
+                    target=target_name,
+                    cache_hit=True,
+                    idempotent_hit=False,
+                    retries=0,
+                    duration_ms=duration_ms,
+                    **timer.breakdown(duration_ms),
+                    **embeddings.meta(),
+                    request_id=request_id,
+                    trace_id=None,
+                ),
+            )
+        upstream_body = embeddings.upstream_body()
+    
     # Handle idempotency for POST/PUT/PATCH
     if idempotency_key and method.upper() in ["POST", "PUT", "PATCH"]:
         is_new, existing_id, existing_hash = idempotency.register_request(
-            idempotency_key, method, full_url, headers, body_bytes, request_id, tenant=tenant
+            idempotency_key, method, full_url, headers, key_body, request_id, tenant=tenant
         )
         
         if not is_new:
             # Check if request body differs
-            current_hash = idempotency.make_request_hash(method, full_url, headers, body_bytes)
-            if existing_hash != current_hash:
+            current_hash = idempotency.make_request_hash(method, full_url, headers, key_body)
+            # Also accept the previous version's hash (raw body), for one release
+            legacy_hash = idempotency.make_legacy_request_hash(method, full_url, headers, body_bytes)
+            if existing_hash not in (current_hash, legacy_hash):
                 return ErrorResponse(
                     success=False,
                     error=ErrorDetail(
@@ -469,6 +2020,8 @@ async def handle_http_proxy(
                         idempotent_hit=True,
                         retries=0,
                         duration_ms=duration_ms,
+                        **timer.breakdown(duration_ms),
+                        upstream_request_id=_upstream_request_id(target_config, existing_result.get("headers")),
                         request_id=request_id,
                         trace_id=None,
                     ),
@@ -509,6 +2062,8 @@ async def handle_http_proxy(
                             idempotent_hit=True,
                             retries=0,
                             duration_ms=duration_ms,
+                            **timer.breakdown(duration_ms),
+                            upstream_request_id=_upstream_request_id(target_config, existing_result.get("headers")),
                             request_id=request_id,
                             trace_id=None,
                         ),
@@ -516,9 +2071,47 @@ async def handle_http_proxy(
         
         idempotency.mark_in_progress(idempotency_key, tenant=tenant)
     
+    # Maintenance mode: cache misses are rejected instead of calling upstream
+    if maintenance_mode.is_active(target_name):
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
+            path=path,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.MAINTENANCE_CACHE_ONLY.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _maintenance_error(target_name, request_id, duration_ms)
+    
+    # Kill switch with serve_cache_when_disabled: the cache missed
+    if kill_switch.is_disabled(target_name):
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
+            path=path,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.TARGET_DISABLED.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _target_disabled_error(target_name, request_id, duration_ms)
+    
     # Create HTTP client (with key pool support)
     client, selected_key, auth_source = create_http_client(
-        target_config, target_name, key_pool_manager=key_pool_manager
+        target_config, target_name, key_pool_manager=key_pool_manager, client_ip=client_ip, request_id=request_id
     )
     
     # Check rate limits before request
@@ -528,11 +2121,12 @@ async def handle_http_proxy(
         if selected_key.qps_limit:
             provider_key_qps = float(selected_key.qps_limit)
         
-        allowed, retry_after_s, limiting_bucket = await rate_scheduler.check_rate_limit(
-            provider_key_id=selected_key.id,
-            tenant=tenant,
-            provider_key_qps=provider_key_qps,
-        )
+        with timer.queue():
+            allowed, retry_after_s, limiting_bucket = await rate_scheduler.check_rate_limit(
+                provider_key_id=selected_key.id,
+                tenant=tenant,
+                provider_key_qps=provider_key_qps,
+            )
         
         if not allowed:
             rate_scheduler_429_total.labels(source="reliapi").inc()
@@ -563,32 +2157,116 @@ async def handle_http_proxy(
                 ),
             )
     
+    # Optional response contract: with on_violation "retry" mismatches are retried like a 5xx
+    schema_config = target_config.get("response_schema")
+    validator = None
+    if schema_config and schema_config.get("on_violation") == "retry":
+        validator = response_validator(schema_config["json_schema"])
+    upstream_validator = _upstream_validator(target_config, schema_validator=validator)
+    
+    # Concurrency cap: queue for a slot, up to max_queue_wait_ms
     try:
-        # Make request
-        response = await client.request(
-            method=method,
+        with timer.queue():
+            concurrency_slot = await concurrency_limiter.acquire(target_name, target_config.get("concurrency"))
+    except QueueTimeout as e:
+        await client.close()
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
             path=path,
-            headers=headers,
-            body=body_bytes,
-            params=query,
+            outcome="error",
+            latency_ms=int((time.time() - start_time) * 1000),
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=_saturation_code(e),
+            upstream_status=503,
+            tenant=tenant,
         )
+        return _queue_timeout_error(target_name, request_id, timer, e)
+    
+    plugins = load_plugins(target_config.get("plugins"))
+    # Set once a streamed response owns the client and concurrency slot
+    streaming = False
+    try:
+        # Plugins rewrite what is sent upstream, not the cache/idempotency identity
+        outgoing = {"method": method, "path": path, "headers": headers, "query": query, "body": upstream_body}
+        if plugins:
+            outgoing = await transform_request(plugins, method, path, headers, query, upstream_body)
         
-        # Read response
-        response_body = await response.aread()
+        # Bodies of responses matching a stream rule are left unread and relayed
+        def _streams(response: httpx.Response) -> bool:
+            return bool(select_content_rule(content_rules, headers, response.headers).get("stream"))
+        stream_when = _streams if has_stream_rule(content_rules) else None
+        
+        # Make request (hedged: a second identical call once the first is slower than usual)
+        def _upstream_call():
+            return client.request(
+                method=outgoing["method"],
+                path=outgoing["path"],
+                headers=outgoing["headers"],
+                body=outgoing["body"],
+                params=outgoing["query"],
+                response_validator=upstream_validator,
+                stream_when=stream_when,
+                no_retry_statuses=AMBIGUOUS_FAILOVER_STATUSES if idempotency_key else (),
+            )
+        
+        hedging_config = _hedging_config(target_config, hedge, method, idempotency_key)
+        hedge_meta: Dict[str, Any] = {}
+        with timer.upstream():
+            try:
+                upstream_started = time.monotonic()
+                if hedging_config is not None:
+                    hedge_tracker.record_call(target_name)
+                    response, hedge_meta = await hedged_call(
+                        _upstream_call,
+                        hedge_tracker.delay_s(target_name, hedging_config),
+                        lambda: hedge_tracker.try_hedge(target_name, hedging_config),
+                    )
+                else:
+                    response = await _upstream_call()
+                hedge_tracker.observe(target_name, time.monotonic() - upstream_started)
+            except RetryableErrorCode as e:
+                # Retries exhausted: the last response is handled like any other
+                response = e.response
+            content_rule = select_content_rule(content_rules, headers, response.headers)
+            if content_rule.get("stream") and response.is_success:
+                streaming = True
+            else:
+                response_body = await response.aread()
+        if streaming:
+            return _http_stream_result(
+                response, client, concurrency_slot, target_config, target_name, path,
+                idempotency, idempotency_key, request_id, tenant, start_time, timer, retries, hedge_meta,
+            )
+        _observe_payload_sizes(target_name, "http", "n/a", outgoing["body"], response_body)
         response_status = response.status_code
         response_headers = dict(response.headers)
         
+        # Validate before returning or caching
+        if schema_config and response_status < 400:
+            violation = validate_response_body(
+                schema_config["json_schema"], response_body, retryable=validator is not None
+            )
+            if violation:
+                raise violation
+        
         # Parse body
-        try:
-            body_json = json.loads(response_body.decode()) if response_body else {}
-        except:
-            body_json = {"raw": response_body.decode() if response_body else ""}
+        body_json = _parse_http_body(response_body, content_rule.get("body", "auto"))
+        body_json = _merge_embeddings(
+            embeddings, body_json, response_status, cache, cache_config, full_url, cache_ttl, tenant
+        )
+        body_json = _shape_http_body(body_json, content_rule, target_config)
         
         result_data = {
             "status_code": response_status,
             "headers": response_headers,
             "body": body_json,
         }
+        if plugins:
+            result_data = await transform_response(plugins, result_data)
         
         # Update key pool health on success
         if selected_key and key_pool_manager:
@@ -603,21 +2281,10 @@ async def handle_http_proxy(
             key_pool_status.labels(provider_key_id=selected_key.id, status=selected_key.status).observe(status_value)
         
         # Store in cache
-        if method.upper() in ["GET", "HEAD"] and response_status < 400:
-            cache_config = target_config.get("cache", {})
-            if cache_config.get("enabled", True):
-                ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-                cache.set(
-                    method, full_url, headers, body_bytes,
-                    {
-                        "status_code": response_status,
-                        "headers": response_headers,
-                        "body": body_json,
-                    },
-                    ttl_s=ttl,
-                    query=query,
-                    tenant=tenant,
-                )
+        cache_store_meta = _store_http_cache(
+            cache, cache_config, method, full_url, headers, key_body, query,
+            result_data, cache_ttl, tenant,
+        ) if content_rule.get("cache", True) else {}
         
         # Store idempotency result (use same TTL as cache for consistency)
         if idempotency_key:
@@ -648,6 +2315,12 @@ async def handle_http_proxy(
                 idempotent_hit=False,
                 retries=retries,
                 duration_ms=duration_ms,
+                **timer.breakdown(duration_ms),
+                upstream_request_id=_upstream_request_id(target_config, response_headers),
+                region=_served_region(target_config, client),
+                **hedge_meta,
+                **cache_store_meta,
+                **(embeddings.meta() if embeddings else {}),
                 request_id=request_id,
                 trace_id=None,
             ),
@@ -657,7 +2330,6 @@ async def handle_http_proxy(
         # HTTP error
         error_type = "upstream_error"
         error_code = ErrorCode.from_http_status(e.response.status_code)
-        upstream_status_norm = UpstreamStatus.normalize(e.response.status_code)
         retryable = e.response.status_code >= 500 or e.response.status_code == 429
         
         # Update key pool health on error
@@ -679,7 +2351,10 @@ async def handle_http_proxy(
             key_switch_state.provider = selected_key.provider
             key_switch_state.used_keys.add(selected_key.id)
             
-            if retryable and key_pool_manager.has_pool(selected_key.provider) and key_switch_state.can_switch():
+            switchable = retryable and not (
+                idempotency_key and e.response.status_code in AMBIGUOUS_FAILOVER_STATUSES
+            )
+            if switchable and key_pool_manager.has_pool(selected_key.provider) and key_switch_state.can_switch():
                     # Select new key, excluding recently used keys
                     new_key = key_pool_manager.select_key(
                         selected_key.provider, 
@@ -703,29 +2378,35 @@ async def handle_http_proxy(
                         
                         # Retry request (this is a simple retry, not full retry logic)
                         try:
-                            response = await client.request(
-                                method=method,
-                                path=path,
-                                headers=headers,
-                                body=body_bytes,
-                                params=query,
-                            )
-                            # If successful, continue with normal flow
-                            response_body = await response.aread()
+                            with timer.upstream():
+                                response = await client.request(
+                                    method=outgoing["method"],
+                                    path=outgoing["path"],
+                                    headers=outgoing["headers"],
+                                    body=outgoing["body"],
+                                    params=outgoing["query"],
+                                    no_retry_statuses=AMBIGUOUS_FAILOVER_STATUSES if idempotency_key else (),
+                                )
+                                response_body = await response.aread()
+                            _observe_payload_sizes(target_name, "http", "n/a", outgoing["body"], response_body)
                             response_status = response.status_code
                             response_headers = dict(response.headers)
                             
                             # Parse body
-                            try:
-                                body_json = json.loads(response_body.decode()) if response_body else {}
-                            except:
-                                body_json = {"raw": response_body.decode() if response_body else ""}
+                            content_rule = select_content_rule(content_rules, headers, response_headers)
+                            body_json = _parse_http_body(response_body, content_rule.get("body", "auto"))
+                            body_json = _merge_embeddings(
+                                embeddings, body_json, response_status, cache, cache_config, full_url, cache_ttl, tenant
+                            )
+                            body_json = _shape_http_body(body_json, content_rule, target_config)
                             
                             result_data = {
                                 "status_code": response_status,
                                 "headers": response_headers,
                                 "body": body_json,
                             }
+                            if plugins:
+                                result_data = await transform_response(plugins, result_data)
                             
                             # Update key pool health on success
                             if selected_key and key_pool_manager:
@@ -737,21 +2418,10 @@ async def handle_http_proxy(
                                 ).inc()
                             
                             # Store in cache
-                            if method.upper() in ["GET", "HEAD"] and response_status < 400:
-                                cache_config = target_config.get("cache", {})
-                                if cache_config.get("enabled", True):
-                                    ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-                                    cache.set(
-                                        method, full_url, headers, body_bytes,
-                                        {
-                                            "status_code": response_status,
-                                            "headers": response_headers,
-                                            "body": body_json,
-                                        },
-                                        ttl_s=ttl,
-                                        query=query,
-                                        tenant=tenant,
-                                    )
+                            cache_store_meta = _store_http_cache(
+                                cache, cache_config, method, full_url, headers, key_body, query,
+                                result_data, cache_ttl, tenant,
+                            ) if content_rule.get("cache", True) else {}
                             
                             # Store idempotency result
                             if idempotency_key:
@@ -782,10 +2452,21 @@ async def handle_http_proxy(
                                     idempotent_hit=False,
                                     retries=retries,
                                     duration_ms=duration_ms,
+                                    **timer.breakdown(duration_ms),
+                                    upstream_request_id=_upstream_request_id(target_config, response_headers),
+                                    region=_served_region(target_config, client),
+                                    **cache_store_meta,
+                                    **(embeddings.meta() if embeddings else {}),
                                     request_id=request_id,
                                     trace_id=None,
                                 ),
                             )
+                        except EmbeddingsMismatch as mismatch:
+                            if idempotency_key:
+                                idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+                            return _embeddings_mismatch_response(
+                                mismatch, target_name, path, request_id, start_time, retries, tenant
+                            )
                         except Exception:
                             # Fall through to error handling
                             pass
@@ -793,6 +2474,19 @@ async def handle_http_proxy(
         if idempotency_key:
             idempotency.clear_in_progress(idempotency_key, tenant=tenant)
         
+        # Negative caching for configured error statuses (e.g. 503)
+        error_rule = select_content_rule(content_rules, headers, e.response.headers)
+        if error_rule.get("cache", True):
+            _store_http_cache(
+                cache, cache_config, method, full_url, headers, key_body, query,
+                {
+                    "status_code": e.response.status_code,
+                    "headers": dict(e.response.headers),
+                    "body": _parse_http_body(e.response.content, error_rule.get("body", "auto")),
+                },
+                cache_ttl, tenant,
+            )
+        
         duration_ms = int((time.time() - start_time) * 1000)
         _log_and_metric_http_request(
             request_id=request_id,
@@ -808,12 +2502,6 @@ async def handle_http_proxy(
         )
         # Legacy metrics
         http_requests_total.labels(target=target_name, status="error").inc()
-        errors_total.labels(
-            target=target_name,
-            kind="http",
-            error_code=error_code.value,
-            upstream_status=upstream_status_norm,
-        ).inc()
         latency_ms.labels(target=target_name, status="error").observe(duration_ms)
         return ErrorResponse(
             success=False,
@@ -831,11 +2519,41 @@ async def handle_http_proxy(
                 idempotent_hit=False,
                 retries=retries,
                 duration_ms=duration_ms,
+                upstream_request_id=_upstream_request_id(target_config, e.response.headers),
+                region=_served_region(target_config, client),
                 request_id=request_id,
                 trace_id=None,
             ),
         )
         
+    except SchemaViolation as e:
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        return _schema_violation_response(e, target_name, path, request_id, start_time, retries, tenant)
+        
+    except EmbeddingsMismatch as e:
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        return _embeddings_mismatch_response(e, target_name, path, request_id, start_time, retries, tenant)
+        
+    except PluginError as e:
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
+            path=path,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.PLUGIN_ERROR.value,
+            upstream_status=500,
+            tenant=tenant,
+        )
+        return _plugin_error(e, target_name, request_id, duration_ms)
+        
     except httpx.RequestError as e:
         # Network/timeout error
         if idempotency_key:
@@ -856,7 +2574,6 @@ async def handle_http_proxy(
         
         duration_ms = int((time.time() - start_time) * 1000)
         error_code = ErrorCode.NETWORK_ERROR
-        upstream_status_norm = UpstreamStatus.BAD_GATEWAY.value
         _log_and_metric_http_request(
             request_id=request_id,
             target_name=target_name,
@@ -869,89 +2586,532 @@ async def handle_http_proxy(
             upstream_status=502,  # Actual status for logs
             tenant=tenant,
         )
-        # Legacy metrics
-        http_requests_total.labels(target=target_name, status="error").inc()
-        errors_total.labels(
+        # Legacy metrics
+        http_requests_total.labels(target=target_name, status="error").inc()
+        latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+        return ErrorResponse(
+            success=False,
+            error=ErrorDetail(
+                type="upstream_error",
+                code=error_code.value,
+                message=f"Network error: {str(e)}",
+                retryable=True,
+                target=target_name,
+                status_code=502,
+            ),
+            meta=MetaResponse(
+                target=target_name,
+                cache_hit=False,
+                retries=retries,
+                duration_ms=duration_ms,
+                request_id=request_id,
+                trace_id=None,
+            ),
+        )
+        
+    except Exception as e:
+        # Other errors
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        
+        duration_ms = int((time.time() - start_time) * 1000)
+        error_code = ErrorCode.INTERNAL_ERROR
+        _log_and_metric_http_request(
+            request_id=request_id,
+            target_name=target_name,
+            path=path,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=error_code.value,
+            upstream_status=500,  # Actual status for logs
+            tenant=tenant,
+        )
+        # Legacy metrics
+        http_requests_total.labels(target=target_name, status="error").inc()
+        latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+        return ErrorResponse(
+            success=False,
+            error=ErrorDetail(
+                type="internal_error",
+                code=error_code.value,
+                message=f"Internal error: {str(e)}",
+                retryable=True,
+                target=target_name,
+                status_code=500,
+            ),
+            meta=MetaResponse(
+                target=target_name,
+                cache_hit=False,
+                retries=retries,
+                duration_ms=duration_ms,
+                request_id=request_id,
+                trace_id=None,
+            ),
+        )
+        
+    finally:
+        if not streaming:
+            if concurrency_slot:
+                concurrency_slot.release()
+            await client.close()
+
+
+def _check_rejection(gate: str, code: ErrorCode, message: str, status_code: int, **details: Any) -> Dict[str, Any]:
+    return {"gate": gate, "code": code.value, "message": message, "status_code": status_code, "details": details or None}
+
+
+def check_llm_request(
+    target_name: str,
+    messages: List[Dict[str, str]],
+    model: Optional[str],
+    max_tokens: Optional[int],
+    targets: Dict[str, Dict],
+    n: Optional[int] = None,
+    model_rate_limiter: Optional[ModelRateLimiter] = None,
+    cost_multiplier: float = 1.0,
+) -> Dict[str, Any]:
+    """Evaluate the target's gates for an LLM request without executing it (POST /check).
+
+    Mirrors handle_llm_proxy up to the provider call: kill switch and
+    maintenance mode, output caps, unpriced models, the soft and hard cost
+    caps and per-model RPM/TPM (unless it delays instead of rejecting).
+    Nothing is counted: no rate-limit window or budget is consumed.
+
+    Returns:
+        {"allowed", "reason" (None, or gate/code/message/status_code/details),
+         "target", "provider", "model", "max_tokens", "cost_estimate_usd",
+         "cost_approximate", "cost_policy_applied"}
+    """
+    result: Dict[str, Any] = {
+        "allowed": True, "reason": None, "target": target_name, "provider": None, "model": model,
+        "max_tokens": max_tokens, "cost_estimate_usd": None, "cost_approximate": None,
+        "cost_policy_applied": "none",
+    }
+
+    def reject(reason: Dict[str, Any]) -> Dict[str, Any]:
+        return {**result, "allowed": False, "reason": reason}
+
+    target_config = targets.get(target_name)
+    if not target_config:
+        return reject(_check_rejection(
+            "policy", ErrorCode.NOT_FOUND, f"Target '{target_name}' not found", 404,
+        ))
+    llm_config = target_config.get("llm", {})
+    if not llm_config:
+        return reject(_check_rejection(
+            "policy", ErrorCode.INVALID_TARGET, f"Target '{target_name}' is not configured for LLM", 400,
+        ))
+    if kill_switch.is_disabled(target_name):
+        return reject(_check_rejection(
+            "policy", ErrorCode.TARGET_DISABLED, f"Target '{target_name}' is disabled", 503,
+        ))
+    if maintenance_mode.is_active(target_name):
+        return reject(_check_rejection(
+            "policy", ErrorCode.MAINTENANCE_CACHE_ONLY,
+            f"Target '{target_name}' is in maintenance mode: only cached responses are served", 503,
+        ))
+
+    final_model = model or llm_config.get("default_model", "gpt-4")
+    final_max_tokens = max_tokens
+    if final_max_tokens is None:
+        final_max_tokens = llm_config.get("max_tokens")
+    elif llm_config.get("max_tokens"):
+        final_max_tokens = min(final_max_tokens, llm_config["max_tokens"])
+    provider = llm_config.get("provider") or detect_provider(target_config["base_url"])
+    result.update(provider=provider, model=final_model, max_tokens=final_max_tokens)
+
+    final_max_tokens, _, exceeded_cap = _apply_output_cap(llm_config, final_model, final_max_tokens)
+    if exceeded_cap:
+        return reject(_check_rejection(
+            "budget", ErrorCode.OUTPUT_CAP_EXCEEDED, _output_cap_message(final_model, final_max_tokens, exceeded_cap),
+            422, output_cap_tokens=exceeded_cap,
+        ))
+    result["max_tokens"] = final_max_tokens
+
+    if provider:
+        unknown_model_policy = _unknown_model_policy(llm_config, provider, final_model)
+        if unknown_model_policy == "reject":
+            return reject(_check_rejection(
+                "budget", ErrorCode.UNKNOWN_MODEL, _unknown_model_message(provider, final_model), 422,
+            ))
+        cost_approximate = unknown_model_policy == "estimate_chars"
+        raw_estimate_usd = CostEstimator.estimate_from_messages(
+            provider, final_model, messages,
+            final_max_tokens * n if final_max_tokens and n else final_max_tokens,
+            approximate=cost_approximate,
+        )
+        cost_estimate_usd = _billed(raw_estimate_usd, cost_multiplier)
+        result.update(cost_estimate_usd=cost_estimate_usd, cost_approximate=cost_approximate or None)
+
+        # Target caps limit provider cost; messages show it billed, like every other cost
+        hard_cost_cap = llm_config.get("hard_cost_cap_usd")
+        if hard_cost_cap and raw_estimate_usd and raw_estimate_usd > hard_cost_cap:
+            billed_cap = _billed(hard_cost_cap, cost_multiplier)
+            return reject(_check_rejection(
+                "budget", ErrorCode.BUDGET_EXCEEDED,
+                f"Estimated cost ${cost_estimate_usd:.6f} exceeds hard cap ${billed_cap:.6f}", 400,
+                hard_cost_cap_usd=billed_cap,
+            ))
+        soft_cost_cap = llm_config.get("soft_cost_cap_usd")
+        if soft_cost_cap and raw_estimate_usd and raw_estimate_usd > soft_cost_cap and final_max_tokens:
+            # Allowed, with max_tokens reduced as the request itself would be
+            final_max_tokens = int(final_max_tokens * (soft_cost_cap / raw_estimate_usd) * 0.9)
+            result.update(
+                max_tokens=final_max_tokens,
+                cost_policy_applied="soft_cap_throttled",
+                cost_estimate_usd=_billed(CostEstimator.estimate_from_messages(
+                    provider, final_model, messages, final_max_tokens, approximate=cost_approximate
+                ), cost_multiplier),
+            )
+
+    limits = resolve_model_limits(llm_config, final_model)
+    if limits and model_rate_limiter and limits.get("on_limit") != "delay":
+        remaining = model_rate_limiter.get_remaining(target_name, final_model, limits)
+        tokens = estimate_request_tokens(messages, final_max_tokens)
+        limiting = "rpm" if remaining["rpm"] == 0 else (
+            "tpm" if remaining["tpm"] is not None and remaining["tpm"] < tokens else None
+        )
+        if limiting:
+            return reject(_check_rejection(
+                "rate_limit", ErrorCode.RATE_LIMIT_RELIAPI,
+                f"Model {limiting.upper()} limit exceeded for '{final_model}'", 429, remaining=remaining,
+            ))
+
+    return result
+
+
+async def handle_llm_proxy(
+    target_name: str,
+    messages: List[Dict[str, str]],
+    model: Optional[str],
+    max_tokens: Optional[int],
+    temperature: Optional[float],
+    top_p: Optional[float],
+    stop: Optional[List[str]],
+    stream: Optional[bool],
+    idempotency_key: Optional[str],
+    cache_ttl: Optional[int],
+    targets: Dict[str, Dict],
+    cache: Cache,
+    idempotency: IdempotencyManager,
+    request_id: str,
+    tenant: Optional[str] = None,
+    tier: str = "free",
+    key_pool_manager: Optional[KeyPoolManager] = None,
+    rate_scheduler: Optional[RateScheduler] = None,
+    client_profile_name: Optional[str] = None,
+    client_profile_manager: Optional[ClientProfileManager] = None,
+    model_rate_limiter: Optional[ModelRateLimiter] = None,
+    response_format: Optional[Dict[str, Any]] = None,
+    overrides: Optional[Dict[str, int]] = None,
+    n: Optional[int] = None,
+    logit_bias: Optional[Dict[str, float]] = None,
+    seed: Optional[int] = None,
+    logprobs: Optional[bool] = None,
+    top_logprobs: Optional[int] = None,
+    pins: Optional[PinnedResponses] = None,
+    client_ip: Optional[str] = None,
+    cost_multiplier: float = 1.0,
+    json_schema: Optional[Dict[str, Any]] = None,
+) -> Union[SuccessResponse, ErrorResponse]:
+    """Handle LLM proxy request (see _handle_llm_proxy).
+
+    With llm.on_content_filter_model, a response blocked by the provider's
+    content filter (finish_reason content_filter) is retried once on that
+    model; meta.content_filter_original_model records the substitution and
+    cost_usd includes both calls.
+
+    With llm.empty_response, a completion without content is retried (with
+    the optional nudge message) up to max_retries times; meta.empty_retries
+    counts the retries.
+
+    With json_schema, an output that does not match it is retried with a
+    corrective instruction up to llm.output_schema.max_retries times, then
+    rejected with 422 SCHEMA_VIOLATION; cost_usd includes every attempt.
+    """
+    kwargs: Dict[str, Any] = dict(
+        target_name=target_name,
+        messages=messages,
+        model=model,
+        max_tokens=max_tokens,
+        temperature=temperature,
+        top_p=top_p,
+        stop=stop,
+        stream=stream,
+        idempotency_key=idempotency_key,
+        cache_ttl=cache_ttl,
+        targets=targets,
+        cache=cache,
+        idempotency=idempotency,
+        request_id=request_id,
+        tenant=tenant,
+        tier=tier,
+        key_pool_manager=key_pool_manager,
+        rate_scheduler=rate_scheduler,
+        client_profile_name=client_profile_name,
+        client_profile_manager=client_profile_manager,
+        model_rate_limiter=model_rate_limiter,
+        response_format=response_format,
+        overrides=overrides,
+        n=n,
+        logit_bias=logit_bias,
+        seed=seed,
+        logprobs=logprobs,
+        top_logprobs=top_logprobs,
+        pins=pins,
+        client_ip=client_ip,
+        cost_multiplier=cost_multiplier,
+        json_schema=json_schema,
+    )
+    result = await _handle_llm_proxy(**kwargs)
+    target_config = targets.get(target_name) or {}
+    # A retried output is stored under the idempotency key once the outcome is final
+    deferred = (
+        idempotency_key is not None
+        and isinstance(result, SuccessResponse)
+        and not result.meta.idempotent_hit
+        and _retried_output(result.data, target_config.get("llm") or {}, json_schema)
+    )
+    result = await _retry_content_filter(result, target_config, kwargs)
+    result = await _retry_empty_completion(result, target_config, kwargs)
+    if json_schema and isinstance(result, SuccessResponse):
+        result = await _enforce_output_schema(result, target_config, kwargs)
+    if deferred:
+        _store_final_result(kwargs, target_config, result)
+    return result
+
+
+def _add_prior_attempt(retry: Union[SuccessResponse, ErrorResponse], prior: SuccessResponse) -> None:
+    """Fold an earlier attempt's cost, duration and retries into the response that replaces it."""
+    if not (prior.meta.cache_hit or prior.meta.idempotent_hit):
+        retry.meta.cost_usd = (retry.meta.cost_usd or 0.0) + (prior.meta.cost_usd or 0.0)
+        if retry.meta.cost_breakdown and prior.meta.cost_breakdown:
+            retry.meta.cost_breakdown = CostBreakdown(
+                input_usd=retry.meta.cost_breakdown.input_usd + prior.meta.cost_breakdown.input_usd,
+                output_usd=retry.meta.cost_breakdown.output_usd + prior.meta.cost_breakdown.output_usd,
+            )
+    retry.meta.duration_ms += prior.meta.duration_ms
+    retry.meta.retries += prior.meta.retries
+
+
+def _retried_output(
+    data: Dict[str, Any], llm_config: Dict[str, Any], json_schema: Optional[Dict[str, Any]]
+) -> bool:
+    """Whether handle_llm_proxy retries an output: it fails json_schema, is empty under
+    llm.empty_response, or is content-filtered with llm.on_content_filter_model set."""
+    return bool(
+        (json_schema is not None and validate_llm_output(json_schema, data.get("content")))
+        or _empty_completion(data, llm_config.get("empty_response"))
+        or (data.get("finish_reason") == "content_filter" and llm_config.get("on_content_filter_model"))
+    )
+
+
+def _store_final_result(
+    kwargs: Dict[str, Any],
+    target_config: Dict[str, Any],
+    result: Union[SuccessResponse, ErrorResponse],
+) -> None:
+    """Store the final outcome of a keyed request whose first output was retried, and release the key.
+    
+    Duplicates get the response that replaced the first one, or the 422 once json_schema retries
+    are exhausted. An empty completion under llm.empty_response and a retryable error are not
+    stored, so a duplicate asks the provider again.
+    """
+    idempotency_key = kwargs["idempotency_key"]
+    idempotency = kwargs["idempotency"]
+    tenant = kwargs.get("tenant")
+    cache_config = target_config.get("cache") or {}
+    ttl_s = kwargs.get("cache_ttl") or cache_config.get("ttl_s", 3600)
+    cost_breakdown = result.meta.cost_breakdown.model_dump() if result.meta.cost_breakdown else None
+    if isinstance(result, SuccessResponse):
+        if not _empty_completion(result.data, (target_config.get("llm") or {}).get("empty_response")):
+            idempotency.store_result(
+                idempotency_key,
+                {
+                    "data": result.data,
+                    "cost_usd": result.meta.cost_usd,
+                    "cost_breakdown": cost_breakdown,
+                    "upstream_request_id": result.meta.upstream_request_id,
+                },
+                ttl_s=ttl_s,
+                tenant=tenant,
+            )
+    elif not result.error.retryable:
+        idempotency.store_result(
+            idempotency_key,
+            {
+                "error": result.error.model_dump(),
+                "cost_usd": result.meta.cost_usd,
+                "cost_breakdown": cost_breakdown,
+                "upstream_request_id": result.meta.upstream_request_id,
+            },
+            ttl_s=ttl_s,
+            tenant=tenant,
+        )
+    idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+
+
+def _idempotent_error(
+    existing_result: Dict[str, Any],
+    target_name: str,
+    provider: Optional[str],
+    model: str,
+    request_id: str,
+    duration_ms: int,
+) -> ErrorResponse:
+    """Replay of a keyed request whose stored outcome is an error (the 422 after json_schema retries)."""
+    return ErrorResponse(
+        success=False,
+        error=ErrorDetail(**existing_result["error"]),
+        meta=MetaResponse(
             target=target_name,
-            kind="http",
-            error_code=error_code.value,
-            upstream_status=upstream_status_norm,
-        ).inc()
-        latency_ms.labels(target=target_name, status="error").observe(duration_ms)
-        return ErrorResponse(
-            success=False,
-            error=ErrorDetail(
-                type="upstream_error",
-                code=error_code.value,
-                message=f"Network error: {str(e)}",
-                retryable=True,
-                target=target_name,
-                status_code=502,
-            ),
-            meta=MetaResponse(
-                target=target_name,
-                cache_hit=False,
-                retries=retries,
-                duration_ms=duration_ms,
-                request_id=request_id,
-                trace_id=None,
-            ),
-        )
-        
-    except Exception as e:
-        # Other errors
-        if idempotency_key:
-            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
-        
-        duration_ms = int((time.time() - start_time) * 1000)
-        error_code = ErrorCode.INTERNAL_ERROR
-        upstream_status_norm = UpstreamStatus.INTERNAL_SERVER_ERROR.value
-        _log_and_metric_http_request(
-            request_id=request_id,
-            target_name=target_name,
-            path=path,
-            outcome="error",
-            latency_ms=duration_ms,
+            provider=provider,
+            model=model,
             cache_hit=False,
-            idempotent_hit=False,
-            error_code=error_code.value,
-            upstream_status=500,  # Actual status for logs
-            tenant=tenant,
+            idempotent_hit=True,
+            retries=0,
+            duration_ms=duration_ms,
+            upstream_request_id=existing_result.get("upstream_request_id"),
+            request_id=request_id,
+            trace_id=None,
+            cost_usd=existing_result.get("cost_usd"),
+            cost_breakdown=existing_result.get("cost_breakdown"),
+        ),
+    )
+
+
+async def _retry_content_filter(
+    result: Union[SuccessResponse, ErrorResponse],
+    target_config: Dict[str, Any],
+    kwargs: Dict[str, Any],
+) -> Union[SuccessResponse, ErrorResponse]:
+    """Retry a content-filtered response once on llm.on_content_filter_model."""
+    fallback_model = (target_config.get("llm") or {}).get("on_content_filter_model")
+    if (
+        not fallback_model
+        or not isinstance(result, SuccessResponse)
+        or result.data.get("finish_reason") != "content_filter"
+        or result.meta.model == fallback_model
+    ):
+        return result
+    
+    content_filter_retries_total.labels(
+        target=kwargs["target_name"], model=result.meta.model, fallback_model=fallback_model
+    ).inc()
+    # Own idempotency key would replay the blocked response
+    retry = await _handle_llm_proxy(**{**kwargs, "model": fallback_model, "idempotency_key": None})
+    if not isinstance(retry, SuccessResponse):
+        logger.warning(f"Content-filter retry on '{fallback_model}' failed: {retry.error.code}")
+        return result
+    
+    _add_prior_attempt(retry, result)
+    retry.meta.content_filter_original_model = result.meta.model
+    return retry
+
+
+def _empty_completion(data: Dict[str, Any], empty_config: Optional[Dict[str, Any]]) -> bool:
+    """Whether a completion counts as empty under llm.empty_response (tool calls and filtered output do not)."""
+    if not empty_config or data.get("finish_reason") == "content_filter":
+        return False
+    choices = data.get("choices") or []
+    if choices and (choices[0].get("message") or {}).get("tool_calls"):
+        return False
+    content = data.get("content")
+    if not isinstance(content, str):
+        return content is None
+    return not (content.strip() if empty_config.get("detect", "whitespace") == "whitespace" else content)
+
+
+async def _retry_empty_completion(
+    result: Union[SuccessResponse, ErrorResponse],
+    target_config: Dict[str, Any],
+    kwargs: Dict[str, Any],
+) -> Union[SuccessResponse, ErrorResponse]:
+    """Retry an empty completion under llm.empty_response."""
+    empty_config = (target_config.get("llm") or {}).get("empty_response")
+    if not isinstance(result, SuccessResponse) or not _empty_completion(result.data, empty_config):
+        return result
+    if empty_config.get("keyed_only", True) and not kwargs.get("idempotency_key"):
+        return result
+    
+    messages = kwargs["messages"]
+    if empty_config.get("nudge"):
+        messages = [*messages, {"role": "user", "content": empty_config["nudge"]}]
+    attempts = 0
+    while _empty_completion(result.data, empty_config) and attempts < empty_config.get("max_retries", 1):
+        attempts += 1
+        llm_empty_retries_total.labels(target=kwargs["target_name"], model=result.meta.model or "unknown").inc()
+        retry = await _handle_llm_proxy(**{**kwargs, "messages": messages, "idempotency_key": None})
+        if not isinstance(retry, SuccessResponse):
+            logger.warning(f"Retry of an empty completion from '{kwargs['target_name']}' failed: {retry.error.code}")
+            break
+        _add_prior_attempt(retry, result)
+        result = retry
+    result.meta.empty_retries = attempts
+    return result
+
+
+async def _enforce_output_schema(
+    result: SuccessResponse,
+    target_config: Dict[str, Any],
+    kwargs: Dict[str, Any],
+) -> Union[SuccessResponse, ErrorResponse]:
+    """Validate the output against json_schema, retrying with corrective instructions (llm.output_schema)."""
+    json_schema = kwargs["json_schema"]
+    config = (target_config.get("llm") or {}).get("output_schema") or {}
+    max_retries = config.get("max_retries", 2) if config.get("on_failure", "retry") == "retry" else 0
+    messages = list(kwargs["messages"])
+    attempts = 0
+    violation = validate_llm_output(json_schema, result.data.get("content"))
+    while violation and attempts < max_retries:
+        attempts += 1
+        llm_schema_retries_total.labels(target=kwargs["target_name"], model=result.meta.model or "unknown").inc()
+        # The model sees its own answer and what is wrong with it
+        messages += [
+            {"role": "assistant", "content": result.data.get("content") or ""},
+            {"role": "user", "content": corrective_instruction(violation)},
+        ]
+        # A valid answer is cached as the answer to the original request
+        retry = await _handle_llm_proxy(**{
+            **kwargs, "messages": messages, "idempotency_key": None, "cache_as_messages": kwargs["messages"],
+        })
+        if not isinstance(retry, SuccessResponse):
+            # The failed retry's error still reports what the earlier attempts cost
+            _add_prior_attempt(retry, result)
+            retry.meta.schema_retries = attempts
+            return retry
+        _add_prior_attempt(retry, result)
+        result = retry
+        violation = validate_llm_output(json_schema, result.data.get("content"))
+    
+    if violation:
+        logger.warning(
+            f"Target '{kwargs['target_name']}' output failed json_schema after {attempts} retries: {violation}"
         )
-        # Legacy metrics
-        http_requests_total.labels(target=target_name, status="error").inc()
-        errors_total.labels(
-            target=target_name,
-            kind="http",
-            error_code=error_code.value,
-            upstream_status=upstream_status_norm,
-        ).inc()
-        latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+        result.meta.schema_valid = False
+        result.meta.schema_retries = attempts or None
         return ErrorResponse(
             success=False,
             error=ErrorDetail(
-                type="internal_error",
-                code=error_code.value,
-                message=f"Internal error: {str(e)}",
-                retryable=True,
-                target=target_name,
-                status_code=500,
-            ),
-            meta=MetaResponse(
-                target=target_name,
-                cache_hit=False,
-                retries=retries,
-                duration_ms=duration_ms,
-                request_id=request_id,
-                trace_id=None,
+                type="schema_violation",
+                code=ErrorCode.SCHEMA_VIOLATION.value,
+                message=f"Model output does not match json_schema at {violation.path}: {violation.message}",
+                retryable=False,
+                source="upstream",
+                target=kwargs["target_name"],
+                status_code=422,
+                details={"path": violation.path, "schema_retries": attempts},
             ),
+            meta=result.meta,
         )
-        
-    finally:
-        await client.close()
+    result.meta.schema_valid = True
+    result.meta.schema_retries = attempts or None
+    return result
 
 
-async def handle_llm_proxy(
+async def _handle_llm_proxy(
     target_name: str,
     messages: List[Dict[str, str]],
     model: Optional[str],
@@ -972,9 +3132,35 @@ async def handle_llm_proxy(
     rate_scheduler: Optional[RateScheduler] = None,
     client_profile_name: Optional[str] = None,
     client_profile_manager: Optional[ClientProfileManager] = None,
+    model_rate_limiter: Optional[ModelRateLimiter] = None,
+    response_format: Optional[Dict[str, Any]] = None,
+    overrides: Optional[Dict[str, int]] = None,
+    n: Optional[int] = None,
+    logit_bias: Optional[Dict[str, float]] = None,
+    seed: Optional[int] = None,
+    logprobs: Optional[bool] = None,
+    top_logprobs: Optional[int] = None,
+    pins: Optional[PinnedResponses] = None,
+    client_ip: Optional[str] = None,
+    cost_multiplier: float = 1.0,
+    json_schema: Optional[Dict[str, Any]] = None,
+    cache_as_messages: Optional[List[Dict[str, Any]]] = None,
 ) -> Union[SuccessResponse, ErrorResponse]:
-    """Handle LLM proxy request."""
+    """Handle LLM proxy request.
+
+    cost_multiplier (the tenant's markup) scales the cost estimate, the cost caps
+    it is checked against and the reported cost_usd. With json_schema, cached
+    outputs that fail it are skipped and failing outputs are not cached
+    (handle_llm_proxy does the retries); nor are empty outputs under
+    empty_response, or content-filtered ones with on_content_filter_model.
+    Those outputs are not stored under the idempotency key either: the key stays
+    in progress until handle_llm_proxy stores the final outcome.
+    
+    cache_as_messages caches (and looks up) the response under the request with
+    these messages instead; a json_schema retry answers the original request.
+    """
     start_time = time.time()
+    timer = RequestTimer(start_time)
     retries = 0
     # Use KeySwitchState for proper tracking across request lifecycle
     key_switch_state = KeySwitchState()
@@ -1009,6 +3195,9 @@ async def handle_llm_proxy(
             ),
         )
     
+    # Per-request timeout/retry overrides (X-ReliAPI-* headers, already clamped)
+    target_config = apply_request_overrides(target_config, overrides)
+    
     # Check if target has LLM config
     llm_config = target_config.get("llm", {})
     if not llm_config:
@@ -1035,6 +3224,41 @@ async def handle_llm_proxy(
             ),
         )
     
+    # Only whitelisted optional params reach the provider (and the cache key)
+    params, rejected_params = _allowed_params(llm_config, target_name, {
+        "temperature": temperature, "top_p": top_p, "stop": stop, "response_format": response_format,
+        "n": n, "logit_bias": logit_bias, "seed": seed, "logprobs": logprobs, "top_logprobs": top_logprobs,
+    })
+    if rejected_params:
+        return _param_not_allowed_error(
+            target_name, request_id, int((time.time() - start_time) * 1000),
+            llm_config.get("provider"), model, rejected_params,
+        )
+    temperature, top_p, stop, response_format = (
+        params["temperature"], params["top_p"], params["stop"], params["response_format"]
+    )
+    n, logit_bias, seed = params["n"], params["logit_bias"], params["seed"]
+    logprobs, top_logprobs = params["logprobs"], params["top_logprobs"]
+    
+    # Kill switch: rejected before the cache unless the target serves cache hits while disabled
+    if kill_switch.is_disabled(target_name) and not target_config.get("serve_cache_when_disabled"):
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=llm_config.get("provider"),
+            model=model,
+            stream=False,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.TARGET_DISABLED.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _target_disabled_error(target_name, request_id, duration_ms, model=model)
+    
     # Apply config limits
     final_model = model or llm_config.get("default_model", "gpt-4")
     final_max_tokens = max_tokens
@@ -1053,21 +3277,164 @@ async def handle_llm_proxy(
     base_url = target_config["base_url"]
     provider = llm_config.get("provider") or detect_provider(base_url)
     
+    # Per-model output cap (llm.output_caps), after llm.max_tokens
+    final_max_tokens, output_cap_tokens, exceeded_cap = _apply_output_cap(llm_config, final_model, final_max_tokens)
+    if exceeded_cap:
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=provider or "unknown",
+            model=final_model,
+            stream=False,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.OUTPUT_CAP_EXCEEDED.value,
+            upstream_status=422,
+            tenant=tenant,
+        )
+        return ErrorResponse(
+            success=False,
+            error=ErrorDetail(
+                type="budget_error",
+                code=ErrorCode.OUTPUT_CAP_EXCEEDED.value,
+                message=_output_cap_message(final_model, final_max_tokens, exceeded_cap),
+                retryable=False,
+                target=target_name,
+                status_code=422,
+                details={"model": final_model, "max_tokens": final_max_tokens, "output_cap_tokens": exceeded_cap},
+            ),
+            meta=MetaResponse(
+                target=target_name,
+                provider=provider,
+                model=final_model,
+                cache_hit=False,
+                idempotent_hit=False,
+                retries=0,
+                duration_ms=duration_ms,
+                request_id=request_id,
+                trace_id=None,
+            ),
+        )
+    
+    # Pinned responses are served regardless of cache settings (matched on the request as sent);
+    # pins carry no logprobs, so logprobs requests always go upstream or to the cache
+    if pins and (n or 1) == 1 and not logprobs:
+        pinned = pins.get(target_name, {
+            "messages": messages, "model": model, "max_tokens": max_tokens,
+            "temperature": temperature, "top_p": top_p, "stop": stop,
+            "response_format": response_format, "logit_bias": logit_bias, "seed": seed,
+        }, tenant=tenant)
+        if pinned:
+            duration_ms = int((time.time() - start_time) * 1000)
+            _log_and_metric_llm_request(
+                request_id=request_id,
+                target_name=target_name,
+                provider=provider or "unknown",
+                model=final_model,
+                stream=False,
+                outcome="success",
+                latency_ms=duration_ms,
+                cache_hit=True,
+                idempotent_hit=False,
+                cost_usd=0.0,
+                tenant=tenant,
+            )
+            cache_hits_total.labels(target=target_name, kind="llm", tenant=tenant or "default").inc()
+            return SuccessResponse(
+                success=True,
+                data={
+                    "content": pinned["content"],
+                    "model": final_model,
+                    "finish_reason": "stop",
+                    "choices": normalize_choices([{"content": pinned["content"]}]),
+                    "usage": {"prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0},
+                },
+                meta=MetaResponse(
+                    target=target_name,
+                    provider=provider,
+                    model=final_model,
+                    cache_hit=True,
+                    pinned=True,
+                    retries=0,
+                    duration_ms=duration_ms,
+                    **timer.breakdown(duration_ms),
+                    request_id=request_id,
+                    trace_id=None,
+                    cost_usd=0.0,
+                ),
+            )
+    
     # Budget control: estimate cost and check caps
     cost_estimate_usd = None
     cost_policy_applied = "none"
     max_tokens_reduced = False
     original_max_tokens = None
+    cost_approximate = False
     
     if provider:
-        # Estimate cost before making request
-        cost_estimate_usd = CostEstimator.estimate_from_messages(
-            provider, final_model, messages, final_max_tokens
+        # Models without pricing: allow (no estimate), reject, or estimate from chars
+        unknown_model_policy = _unknown_model_policy(llm_config, provider, final_model)
+        if unknown_model_policy == "reject":
+            duration_ms = int((time.time() - start_time) * 1000)
+            _log_and_metric_llm_request(
+                request_id=request_id,
+                target_name=target_name,
+                provider=provider,
+                model=final_model,
+                stream=False,
+                outcome="error",
+                latency_ms=duration_ms,
+                cache_hit=False,
+                idempotent_hit=False,
+                error_code=ErrorCode.UNKNOWN_MODEL.value,
+                upstream_status=422,
+                tenant=tenant,
+            )
+            return ErrorResponse(
+                success=False,
+                error=ErrorDetail(
+                    type="budget_error",
+                    code=ErrorCode.UNKNOWN_MODEL.value,
+                    message=_unknown_model_message(provider, final_model),
+                    retryable=False,
+                    target=target_name,
+                    status_code=422,
+                    details={"model": final_model, "provider": provider},
+                ),
+                meta=MetaResponse(
+                    target=target_name,
+                    provider=provider,
+                    model=final_model,
+                    cache_hit=False,
+                    idempotent_hit=False,
+                    retries=0,
+                    duration_ms=duration_ms,
+                    request_id=request_id,
+                    trace_id=None,
+                ),
+            )
+        cost_approximate = unknown_model_policy == "estimate_chars"
+        
+        # Estimate cost before making request (each of n completions may use max_tokens),
+        # plus the summarization call a long history triggers
+        summary_estimate_usd = _summary_cost_estimate(
+            llm_config, provider, final_model, messages, approximate=cost_approximate
         )
+        raw_estimate_usd = _with_summary_estimate(CostEstimator.estimate_from_messages(
+            provider, final_model, messages,
+            final_max_tokens * n if final_max_tokens and n else final_max_tokens,
+            approximate=cost_approximate,
+        ), summary_estimate_usd)
+        cost_estimate_usd = _billed(raw_estimate_usd, cost_multiplier)
         
-        # Check hard cost cap (reject if exceeded)
+        # Check hard cost cap (reject if exceeded); target caps limit provider cost,
+        # and the error reports the cap billed like every other cost
         hard_cost_cap = llm_config.get("hard_cost_cap_usd")
-        if hard_cost_cap and cost_estimate_usd and cost_estimate_usd > hard_cost_cap:
+        if hard_cost_cap and raw_estimate_usd and raw_estimate_usd > hard_cost_cap:
+            hard_cost_cap = _billed(hard_cost_cap, cost_multiplier)
             duration_ms = int((time.time() - start_time) * 1000)
             budget_events_total.labels(target=target_name, event="hard_cap", tenant=tenant or "default").inc()
             error_code = ErrorCode.BUDGET_EXCEEDED
@@ -1113,15 +3480,16 @@ async def handle_llm_proxy(
                     request_id=request_id,
                     trace_id=None,
                     cost_estimate_usd=cost_estimate_usd,
+                    cost_approximate=cost_approximate or None,
                     cost_policy_applied="hard_cap_rejected",
                 ),
             )
         
         # Check soft cost cap (throttle by reducing max_tokens)
         soft_cost_cap = llm_config.get("soft_cost_cap_usd")
-        if soft_cost_cap and cost_estimate_usd and cost_estimate_usd > soft_cost_cap:
+        if soft_cost_cap and raw_estimate_usd and raw_estimate_usd > soft_cost_cap:
             # Auto-reduce max_tokens to fit soft cap
-            reduction_factor = soft_cost_cap / cost_estimate_usd
+            reduction_factor = soft_cost_cap / raw_estimate_usd
             original_max_tokens = final_max_tokens
             final_max_tokens = int(final_max_tokens * reduction_factor * 0.9)  # 0.9 for safety margin
             max_tokens_reduced = True
@@ -1129,9 +3497,11 @@ async def handle_llm_proxy(
             budget_events_total.labels(target=target_name, event="soft_cap", tenant=tenant or "default").inc()
             
             # Re-estimate with reduced tokens
-            cost_estimate_usd = CostEstimator.estimate_from_messages(
-                provider, final_model, messages, final_max_tokens
-            )
+            cost_estimate_usd = _billed(_with_summary_estimate(CostEstimator.estimate_from_messages(
+                provider, final_model, messages,
+                final_max_tokens * n if n else final_max_tokens,
+                approximate=cost_approximate,
+            ), summary_estimate_usd), cost_multiplier)
     
     if not provider:
         return ErrorResponse(
@@ -1181,39 +3551,74 @@ async def handle_llm_proxy(
             ),
         )
     
-    # Prepare request payload
-    payload = adapter.prepare_request(
-        messages=messages,
-        model=final_model,
-        max_tokens=final_max_tokens,
-        temperature=final_temperature,
-        top_p=top_p,
-        stop=stop,
-        stream=False,  # Non-streaming path
+    unsupported = adapter.unsupported_params(
+        logit_bias=logit_bias, seed=seed, logprobs=logprobs or None, top_logprobs=top_logprobs,
     )
+    if unsupported:
+        return _unsupported_param_error(
+            target_name, request_id, int((time.time() - start_time) * 1000),
+            provider, final_model, unsupported,
+        )
+    
+    def _request_payload(request_messages: List[Dict[str, Any]]) -> Dict[str, Any]:
+        payload = adapter.prepare_request(
+            messages=request_messages,
+            model=final_model,
+            max_tokens=final_max_tokens,
+            temperature=final_temperature,
+            top_p=top_p,
+            stop=stop,
+            stream=False,  # Non-streaming path
+            response_format=response_format,
+            n=n,
+            logit_bias=logit_bias,
+            seed=seed,
+            logprobs=logprobs,
+            top_logprobs=top_logprobs,
+        )
+        if llm_config.get("prompt_caching"):
+            payload = adapter.apply_prompt_caching(payload)
+        return payload
+    
+    # Prepare request payload
+    payload = _request_payload(messages)
     
     # Determine API endpoint based on provider
-    if provider == "openai":
-        api_path = "/chat/completions"
-    elif provider == "anthropic":
-        api_path = "/messages"
-    elif provider == "mistral":
-        api_path = "/chat/completions"
-    else:
-        api_path = "/chat/completions"  # Default
+    api_path = adapter.api_path(final_model)
     
-    # Build cache key
-    cache_key_body = json.dumps(payload, sort_keys=True)
+    # Build cache key (the request as received, also when its history is summarized below)
+    cache_key_payload = payload if cache_as_messages is None else _request_payload(cache_as_messages)
+    cache_key_body = canonical_json(cache_key_payload)
     cache_key_bytes = cache_key_body.encode()
+    request_body = canonical_json(payload).encode()
+    # The previous version keyed on json.dumps(sort_keys=True); accepted for one release
+    legacy_key_bytes = json.dumps(cache_key_payload, sort_keys=True).encode()
     
     # Check cache
     cache_hit = False
     cache_config = target_config.get("cache", {})
-    if cache_config.get("enabled", True):
+    cache_skipped_nondeterministic = _skip_nondeterministic_cache(cache_config, final_temperature)
+    cache_enabled = cache_config.get("enabled", True) and not cache_skipped_nondeterministic
+    if cache_enabled:
         ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-        cached = cache.get("POST", base_url + api_path, None, cache_key_bytes, None, allow_post=True, tenant=tenant)
+        health_reason = health_ttl_reason(target_name, target_config)
+        cached = cache.get(
+            "POST", base_url + api_path, None, cache_key_bytes, None, allow_post=True, tenant=tenant,
+            serve_stale=health_reason is not None, legacy_body=legacy_key_bytes,
+        )
+        if cached and json_schema and validate_llm_output(json_schema, cached.get("body", {}).get("content")):
+            # Cached for a request without this schema; ask the provider instead
+            cached = None
         if cached:
             cache_hit = True
+            allowed, retry_after_s, limiting = await _admit_cache_hit(
+                model_rate_limiter, target_config, target_name, final_model, llm_config
+            )
+            if not allowed:
+                return _model_rate_limit_error(
+                    target_name, request_id, provider, final_model, retry_after_s, limiting,
+                    int((time.time() - start_time) * 1000),
+                )
             duration_ms = int((time.time() - start_time) * 1000)
             cost_usd = cached.get("cost_usd")
             _log_and_metric_llm_request(
@@ -1244,9 +3649,19 @@ async def handle_llm_proxy(
                         cache_hit=True,
                         retries=0,
                         duration_ms=duration_ms,
+                        **timer.breakdown(duration_ms),
+                        upstream_request_id=cached.get("upstream_request_id"),
+                        json_repaired=cached.get("json_repaired"),
+                        **_choice_counts(n, cached.get("body", {})),
+                        **_ttl_extended(cached, target_name, "llm", health_reason),
+                        **_extend_on_hit(
+                            cache, cache_config, cached, "POST", base_url + api_path, None, cache_key_bytes, None,
+                            allow_post=True, tenant=tenant,
+                        ),
                         request_id=request_id,
                         trace_id=None,
                         cost_usd=cached.get("cost_usd"),
+                        cost_breakdown=cached.get("cost_breakdown"),
                     ),
                 )
     
@@ -1259,7 +3674,8 @@ async def handle_llm_proxy(
         
         if not is_new:
             current_hash = idempotency.make_request_hash("POST", full_url, None, cache_key_bytes)
-            if existing_hash != current_hash:
+            legacy_hash = idempotency.make_legacy_request_hash("POST", full_url, None, legacy_key_bytes)
+            if existing_hash not in (current_hash, legacy_hash):
                 return ErrorResponse(
                     success=False,
                     error=ErrorDetail(
@@ -1285,6 +3701,11 @@ async def handle_llm_proxy(
                 )
             
             existing_result = idempotency.get_result(idempotency_key, tenant=tenant)
+            if existing_result and "error" in existing_result:
+                return _idempotent_error(
+                    existing_result, target_name, provider, final_model, request_id,
+                    int((time.time() - start_time) * 1000),
+                )
             if existing_result:
                 duration_ms = int((time.time() - start_time) * 1000)
                 cost_usd = existing_result.get("cost_usd")
@@ -1306,15 +3727,18 @@ async def handle_llm_proxy(
                     data=existing_result.get("data", {}),
                     meta=MetaResponse(
                         target=target_name,
-                        provider=provider,
-                        model=final_model,
+                        **_idempotent_origin(existing_result, provider, final_model),
                         cache_hit=False,
                         idempotent_hit=True,
                         retries=0,
                         duration_ms=duration_ms,
+                        **timer.breakdown(duration_ms),
+                        upstream_request_id=existing_result.get("upstream_request_id"),
+                        **_choice_counts(n, existing_result.get("data", {})),
                         request_id=request_id,
                         trace_id=None,
                         cost_usd=cost_usd,
+                        cost_breakdown=existing_result.get("cost_breakdown"),
                     ),
                 )
             
@@ -1332,6 +3756,11 @@ async def handle_llm_proxy(
                 poll_interval = min(poll_interval * 1.5, 0.5)
                 
                 existing_result = idempotency.get_result(idempotency_key, tenant=tenant)
+                if existing_result and "error" in existing_result:
+                    return _idempotent_error(
+                        existing_result, target_name, provider, final_model, request_id,
+                        int((time.time() - start_time) * 1000),
+                    )
                 if existing_result:
                     duration_ms = int((time.time() - start_time) * 1000)
                     cost_usd = existing_result.get("cost_usd")
@@ -1353,27 +3782,78 @@ async def handle_llm_proxy(
                         data=existing_result.get("data", {}),
                         meta=MetaResponse(
                             target=target_name,
-                            provider=provider,
-                            model=final_model,
+                            **_idempotent_origin(existing_result, provider, final_model),
                             cache_hit=False,
                             idempotent_hit=True,
                             retries=0,
                             duration_ms=duration_ms,
+                            **timer.breakdown(duration_ms),
+                            upstream_request_id=existing_result.get("upstream_request_id"),
+                            **_choice_counts(n, existing_result.get("data", {})),
                             request_id=request_id,
                             trace_id=None,
                             cost_usd=cost_usd,
+                            cost_breakdown=existing_result.get("cost_breakdown"),
                         ),
                     )
         
         idempotency.mark_in_progress(idempotency_key, tenant=tenant)
     
+    # Maintenance mode: cache misses are rejected instead of calling the provider
+    if maintenance_mode.is_active(target_name):
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=provider,
+            model=final_model,
+            stream=False,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.MAINTENANCE_CACHE_ONLY.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _maintenance_error(target_name, request_id, duration_ms, provider=provider, model=final_model)
+    
+    # Kill switch with serve_cache_when_disabled: the cache missed
+    if kill_switch.is_disabled(target_name):
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=provider,
+            model=final_model,
+            stream=False,
+            outcome="error",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=ErrorCode.TARGET_DISABLED.value,
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _target_disabled_error(target_name, request_id, duration_ms, provider=provider, model=final_model)
+    
     # Create HTTP client
     # Get provider for key pool selection
     provider = llm_config.get("provider") or detect_provider(target_config.get("base_url", ""))
     
+    # Timeout scaled to the requested generation length
+    scaled_timeout_ms = _scaled_timeout_ms(target_config, final_max_tokens, overrides)
+    if scaled_timeout_ms:
+        target_config = {**target_config, "timeout_ms": scaled_timeout_ms}
+    
     # Create HTTP client (with key pool support)
     client, selected_key, auth_source = create_http_client(
-        target_config, target_name, key_pool_manager=key_pool_manager, provider=provider
+        target_config, target_name, key_pool_manager=key_pool_manager, provider=provider,
+        client_ip=client_ip, request_id=request_id,
     )
     
     # Get client profile and apply limits
@@ -1400,14 +3880,15 @@ async def handle_llm_proxy(
         if profile and profile.max_qps_per_provider_key:
             profile_qps = profile.max_qps_per_provider_key
         
-        allowed, retry_after_s, limiting_bucket = await rate_scheduler.check_rate_limit(
-            provider_key_id=selected_key.id,
-            tenant=tenant,
-            client_profile=client_profile_name,
-            provider_key_qps=provider_key_qps,
-            tenant_qps=tenant_qps,
-            profile_qps=profile_qps,
-        )
+        with timer.queue():
+            allowed, retry_after_s, limiting_bucket = await rate_scheduler.check_rate_limit(
+                provider_key_id=selected_key.id,
+                tenant=tenant,
+                client_profile=client_profile_name,
+                provider_key_qps=provider_key_qps,
+                tenant_qps=tenant_qps,
+                profile_qps=profile_qps,
+            )
         
         if not allowed:
             rate_scheduler_429_total.labels(source="reliapi").inc()
@@ -1440,24 +3921,273 @@ async def handle_llm_proxy(
                 ),
             )
     
-    try:
-        # Make request
-        response = await client.request(
-            method="POST",
-            path=api_path,
-            headers={"Content-Type": "application/json"},
-            body=cache_key_bytes,
-            params=None,
+    # Per-model RPM/TPM caps (enforced proactively to avoid upstream 429s)
+    with timer.queue():
+        allowed, model_rate_reservation, retry_after_s, limiting = await _acquire_model_rate_limit(
+            model_rate_limiter, target_name, final_model, llm_config, messages, final_max_tokens
+        )
+    if not allowed:
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        return _model_rate_limit_error(
+            target_name, request_id, provider, final_model, retry_after_s, limiting,
+            int((time.time() - start_time) * 1000),
+        )
+    
+    # Long histories: older turns replaced with a summary (its cost is added to cost_usd).
+    # Only now, past every gate, so a rejected or replayed request never pays for one.
+    history_meta: Dict[str, Any] = {}
+    summary_cost_usd = None
+    # Fallback targets get the conversation as received (and apply their own summarization)
+    client_messages = messages
+    if llm_config.get("summarization"):
+        messages, history_meta, summary_cost_usd = await _summarize_history(
+            messages, target_config, target_name, adapter, provider, final_model, cache,
+            tenant=tenant, key_pool_manager=key_pool_manager, request_id=request_id,
+        )
+        if history_meta:
+            request_body = canonical_json(_request_payload(messages)).encode()
+    
+    def _llm_success_response(
+        response_json: Dict[str, Any],
+        normalized_response: Dict[str, Any],
+        upstream_request_id: Optional[str] = None,
+    ) -> SuccessResponse:
+        """Record success and build the response (shared by the main path and key-switch retry)."""
+        # Update key pool health on success
+        if selected_key and key_pool_manager:
+            key_pool_manager.record_success(selected_key.id)
+            key_pool_requests_total.labels(
+                provider_key_id=selected_key.id,
+                provider=selected_key.provider,
+                status="success",
+            ).inc()
+            key_pool_qps.labels(provider_key_id=selected_key.id).observe(selected_key.current_qps)
+            status_value = {"active": 0, "degraded": 1, "exhausted": 2, "banned": 3}.get(selected_key.status, 0)
+            key_pool_status.labels(provider_key_id=selected_key.id, status=selected_key.status).observe(status_value)
+        
+        # Calculate cost (from provider usage; local estimate if the provider sent none)
+        usage_source = "provider"
+        usage = adapter.parse_usage(response_json)
+        if not response_json.get("usage"):
+            usage_source = "estimated"
+            usage = _estimate_usage(messages, [
+                choice.get("content") or ""
+                for choice in normalized_response.get("choices") or [normalized_response]
+            ])
+        prompt_tokens = usage["prompt_tokens"]
+        completion_tokens = usage["completion_tokens"]
+        cost_breakdown = adapter.get_cost_breakdown(
+            final_model, prompt_tokens, completion_tokens,
+            cached_prompt_tokens=usage["cached_prompt_tokens"],
+            cache_write_tokens=usage["cache_write_tokens"],
+        )
+        if cost_breakdown is None and cost_approximate:
+            cost_breakdown = CostEstimator.approximate_cost_breakdown_from_usage(
+                provider, final_model, prompt_tokens, completion_tokens
+            )
+        cost_usd = _total_cost(cost_breakdown)
+        if summary_cost_usd:
+            cost_usd = (cost_usd or 0.0) + summary_cost_usd
+        _observe_llm_usage(target_name, final_model, prompt_tokens, completion_tokens, cost_usd)
+        # Reported, cached and metered at the billed cost; metrics above keep the actual cost
+        cost_usd = _billed(cost_usd, cost_multiplier)
+        cost_breakdown = _billed_breakdown(cost_breakdown, cost_multiplier)
+        _reconcile_model_rate_limit(
+            model_rate_limiter, model_rate_reservation, target_name, final_model, llm_config,
+            prompt_tokens + completion_tokens,
         )
         
-        # Read response
-        response_body = await response.aread()
+        # Adapters without multi-choice support (Anthropic) return a single completion
+        choices = normalized_response.get("choices")
+        if choices is None:
+            choices = [{
+                "index": 0,
+                "content": normalized_response.get("content", ""),
+                "role": normalized_response.get("role", "assistant"),
+                "finish_reason": normalized_response.get("finish_reason", "stop"),
+            }]
+        
+        # Opt-in repair of almost-valid JSON (code fences, trailing commas) in JSON mode
+        json_repaired = False
+        if llm_config.get("json_repair") and (is_json_mode(response_format) or json_schema):
+            for choice in choices:
+                repaired = repair_json(choice["content"]) if choice["content"] else None
+                if repaired is not None:
+                    choice["content"] = repaired
+                    json_repaired = True
+            if json_repaired:
+                json_repairs_total.labels(target=target_name, model=final_model).inc()
+        
+        # Same shape for every provider: OpenAI-style choices, roles and finish reasons
+        normalization = llm_config.get("normalization") or {}
+        normalized_choices = normalize_choices(choices, normalization)
+        first_choice = normalized_choices[0] if normalized_choices else None
+        result_data = {
+            "content": first_choice["message"]["content"] if first_choice else normalized_response.get("content", ""),
+            "role": first_choice["message"]["role"] if first_choice else "assistant",
+            "finish_reason": (
+                first_choice["finish_reason"] if first_choice
+                else normalize_finish_reason(normalized_response.get("finish_reason", "stop"), normalization)
+            ),
+            "choices": normalized_choices,
+            "usage": {
+                "prompt_tokens": prompt_tokens,
+                "completion_tokens": completion_tokens,
+                "total_tokens": prompt_tokens + completion_tokens,
+            },
+        }
+        if first_choice and first_choice.get("logprobs") is not None:
+            result_data["logprobs"] = first_choice["logprobs"]
+        if normalization.get("include_raw"):
+            result_data["raw"] = response_json
+        # With n > 1, serve whatever the provider returned; cost comes from actual usage
+        if n and n > 1 and len(choices) < n:
+            logger.warning(f"Target '{target_name}' returned {len(choices)} of n={n} choices")
+        
+        # Store in cache (outputs failing the request's json_schema, empty under empty_response,
+        # or blocked by the content filter when on_content_filter_model retries them, are not)
+        cache_store_meta: Dict[str, Any] = {}
+        cacheable_output = not _retried_output(result_data, llm_config, json_schema)
+        if cache_enabled and cacheable_output:
+            ttl = cache_ttl or _cost_weighted_ttl(cache_config, provider, final_model)
+            cache_store_meta = _cache_store_meta(lambda: cache.set(
+                "POST", base_url + api_path, None, cache_key_bytes,
+                {
+                    "body": result_data,
+                    "cost_usd": cost_usd,
+                    "cost_breakdown": cost_breakdown,
+                    "upstream_request_id": upstream_request_id,
+                    "json_repaired": json_repaired or None,
+                },
+                ttl_s=ttl,
+                query=None,
+                allow_post=True,
+                tenant=tenant,
+                ttl_jitter=cache_config.get("ttl_jitter", DEFAULT_TTL_JITTER),
+                max_ttl_s=_max_ttl(cache_config),
+                stale_ttl_s=stale_ttl_s(cache_config, ttl),
+            ))
+        
+        # Store idempotency result (use same TTL as cache for consistency);
+        # handle_llm_proxy stores the outcome of outputs it retries
+        if idempotency_key and cacheable_output:
+            idempotency_ttl = cache_ttl or cache_config.get("ttl_s", 3600) if cache_config.get("enabled", True) else 3600
+            idempotency.store_result(
+                idempotency_key,
+                {
+                    "data": result_data,
+                    "cost_usd": cost_usd,
+                    "cost_breakdown": cost_breakdown,
+                    "upstream_request_id": upstream_request_id,
+                },
+                ttl_s=idempotency_ttl,
+                tenant=tenant,
+            )
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        
+        duration_ms = int((time.time() - start_time) * 1000)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=provider,
+            model=final_model,
+            stream=False,
+            outcome="success",
+            latency_ms=duration_ms,
+            cache_hit=False,
+            idempotent_hit=False,
+            cost_usd=cost_usd,
+            tenant=tenant,
+        )
+        # Legacy metrics
+        llm_requests_total.labels(target=target_name, provider=provider, status="success").inc()
+        latency_ms.labels(target=target_name, status="success").observe(duration_ms)
+        # Note: llm_cost_usd legacy metric removed, using llm_cost_usd_total instead
+        return SuccessResponse(
+            success=True,
+            data=result_data,
+            meta=MetaResponse(
+                target=target_name,
+                provider=provider,
+                model=final_model,
+                cache_hit=False,
+                idempotent_hit=False,
+                retries=retries,
+                duration_ms=duration_ms,
+                **timer.breakdown(duration_ms),
+                upstream_request_id=upstream_request_id,
+                region=_served_region(target_config, client),
+                **_choice_counts(n, result_data),
+                request_id=request_id,
+                trace_id=None,
+                cost_usd=cost_usd,
+                cost_breakdown=cost_breakdown,
+                cost_estimate_usd=cost_estimate_usd,
+                cost_policy_applied=cost_policy_applied,
+                max_tokens_reduced=max_tokens_reduced if max_tokens_reduced else None,
+                original_max_tokens=original_max_tokens if max_tokens_reduced else None,
+                output_cap_tokens=output_cap_tokens,
+                cost_approximate=cost_approximate or None,
+                cached_prompt_tokens=usage["cached_prompt_tokens"],
+                usage_source=usage_source,
+                cache_skipped_nondeterministic=cache_skipped_nondeterministic or None,
+                json_repaired=json_repaired or None,
+                timeout_ms=scaled_timeout_ms,
+                **history_meta,
+                **cache_store_meta,
+            ),
+        )
+    
+    # Concurrency cap: queue for a slot, up to max_queue_wait_ms
+    try:
+        with timer.queue():
+            concurrency_slot = await concurrency_limiter.acquire(target_name, target_config.get("concurrency"))
+    except QueueTimeout as e:
+        await client.close()
+        if idempotency_key:
+            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+        _log_and_metric_llm_request(
+            request_id=request_id,
+            target_name=target_name,
+            provider=provider,
+            model=final_model,
+            stream=False,
+            outcome="error",
+            latency_ms=int((time.time() - start_time) * 1000),
+            cache_hit=False,
+            idempotent_hit=False,
+            error_code=_saturation_code(e),
+            upstream_status=503,
+            tenant=tenant,
+        )
+        return _queue_timeout_error(target_name, request_id, timer, e, provider=provider, model=final_model)
+    
+    try:
+        # Make request
+        with timer.upstream():
+            try:
+                response = await client.request(
+                    method="POST",
+                    path=api_path,
+                    headers={"Content-Type": "application/json"},
+                    body=request_body,
+                    params=None,
+                    response_validator=_upstream_validator(target_config, adapter.error_codes),
+                    no_retry_statuses=AMBIGUOUS_FAILOVER_STATUSES if idempotency_key else (),
+                )
+            except RetryableErrorCode as e:
+                # Retries exhausted: the last response is handled like any other
+                response = e.response
+            response_body = await response.aread()
+        _observe_payload_sizes(target_name, "llm", final_model, request_body, response_body)
         response_status = response.status_code
         
         if response_status >= 400:
             # Check if we should try fallback
             fallback_targets = target_config.get("fallback_targets", [])
-            
+            should_fallback = False
+
             # Free tier: No chaining fallbacks (>1 provider)
             if tier == "free" and len(fallback_targets) > 0:
                 # Free tier cannot use fallback chains
@@ -1467,6 +4197,8 @@ async def handle_llm_proxy(
                     fallback_targets
                     and (response_status >= 500 or response_status == 429)
                     and response_status < 600  # Only retryable errors
+                    # A keyed request is never sent twice if the first provider may have done the work
+                    and not (idempotency_key and response_status in AMBIGUOUS_FAILOVER_STATUSES)
                 )
             
             if should_fallback:
@@ -1485,7 +4217,7 @@ async def handle_llm_proxy(
                     try:
                         fallback_result = await handle_llm_proxy(
                             target_name=fallback_target_name,
-                            messages=messages,
+                            messages=client_messages,
                             model=model,
                             max_tokens=max_tokens,
                             temperature=temperature,
@@ -1500,6 +4232,17 @@ async def handle_llm_proxy(
                             request_id=request_id,
                             tenant=tenant,
                             tier=tier,  # Pass tier to fallback handler
+                            model_rate_limiter=model_rate_limiter,
+                            response_format=response_format,
+                            overrides=overrides,
+                            n=n,
+                            logit_bias=logit_bias,
+                            seed=seed,
+                            logprobs=logprobs,
+                            top_logprobs=top_logprobs,
+                            client_ip=client_ip,
+                            cost_multiplier=cost_multiplier,
+                            json_schema=json_schema,
                         )
                         
                         if fallback_result.success:
@@ -1507,6 +4250,20 @@ async def handle_llm_proxy(
                             if isinstance(fallback_result, SuccessResponse):
                                 fallback_result.meta.fallback_used = True
                                 fallback_result.meta.fallback_target = fallback_target_name
+                                # The summary made for this target was paid for too
+                                if summary_cost_usd:
+                                    fallback_result.meta.cost_usd = (
+                                        (fallback_result.meta.cost_usd or 0.0)
+                                        + _billed(summary_cost_usd, cost_multiplier)
+                                    )
+                            if idempotency_key:
+                                # The key now stands for the fallback's response: replays return it
+                                # instead of calling either provider again
+                                _store_failover_result(
+                                    idempotency, idempotency_key, fallback_result, fallback_target_name,
+                                    cache_ttl or cache_config.get("ttl_s", 3600) if cache_config.get("enabled", True) else 3600,
+                                    tenant,
+                                )
                             return fallback_result
                     except Exception as e:
                         # Continue to next fallback
@@ -1531,7 +4288,9 @@ async def handle_llm_proxy(
                 key_switch_state.provider = selected_key.provider
                 key_switch_state.used_keys.add(selected_key.id)
                 
-                retryable_error = response_status >= 500 or response_status == 429
+                retryable_error = (response_status >= 500 or response_status == 429) and not (
+                    idempotency_key and response_status in AMBIGUOUS_FAILOVER_STATUSES
+                )
                 if retryable_error and key_pool_manager.has_pool(selected_key.provider) and key_switch_state.can_switch():
                     # Select new key, excluding recently used keys
                     new_key = key_pool_manager.select_key(
@@ -1555,120 +4314,33 @@ async def handle_llm_proxy(
                         
                         # Retry request
                         try:
-                            response = await client.request(
-                                method="POST",
-                                path=api_path,
-                                headers={"Content-Type": "application/json"},
-                                body=cache_key_bytes,
-                                params=None,
-                            )
-                            
-                            response_body = await response.aread()
+                            with timer.upstream():
+                                response = await client.request(
+                                    method="POST",
+                                    path=api_path,
+                                    headers={"Content-Type": "application/json"},
+                                    body=request_body,
+                                    params=None,
+                                )
+                                response_body = await response.aread()
+                            _observe_payload_sizes(target_name, "llm", final_model, request_body, response_body)
                             response_status = response.status_code
                             
                             # If successful, continue with normal flow
                             if response_status < 400:
                                 response_json = json.loads(response_body.decode()) if response_body else {}
                                 
-                                if not response_json or "choices" not in response_json:
-                                    raise ValueError(f"Invalid response format from {provider}: missing 'choices' field")
+                                if not response_json or adapter.response_field not in response_json:
+                                    raise ValueError(f"Invalid response format from {provider}: missing '{adapter.response_field}' field")
                                 
                                 normalized_response = adapter.parse_response(response_json)
                                 
                                 if not normalized_response or "content" not in normalized_response:
                                     raise ValueError(f"Adapter parse_response returned invalid format: {normalized_response}")
                                 
-                                # Update key pool health on success
-                                if selected_key and key_pool_manager:
-                                    key_pool_manager.record_success(selected_key.id)
-                                    key_pool_requests_total.labels(
-                                        provider_key_id=selected_key.id,
-                                        provider=selected_key.provider,
-                                        status="success",
-                                    ).inc()
-                                
-                                # Calculate cost
-                                usage = response_json.get("usage", {})
-                                prompt_tokens = usage.get("prompt_tokens", 0)
-                                completion_tokens = usage.get("completion_tokens", 0)
-                                cost_usd = adapter.get_cost_usd(final_model, prompt_tokens, completion_tokens)
-                                
-                                result_data = {
-                                    "content": normalized_response.get("content", ""),
-                                    "role": normalized_response.get("role", "assistant"),
-                                    "finish_reason": normalized_response.get("finish_reason", "stop"),
-                                    "usage": {
-                                        "prompt_tokens": prompt_tokens,
-                                        "completion_tokens": completion_tokens,
-                                        "total_tokens": prompt_tokens + completion_tokens,
-                                    },
-                                }
-                                
-                                # Store in cache
-                                if cache_config.get("enabled", True):
-                                    ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-                                    cache.set(
-                                        "POST", base_url + api_path, None, cache_key_bytes,
-                                        {
-                                            "body": result_data,
-                                            "cost_usd": cost_usd,
-                                        },
-                                        ttl_s=ttl,
-                                        query=None,
-                                        allow_post=True,
-                                        tenant=tenant,
-                                    )
-                                
-                                # Store idempotency result
-                                if idempotency_key:
-                                    idempotency_ttl = cache_ttl or cache_config.get("ttl_s", 3600) if cache_config.get("enabled", True) else 3600
-                                    idempotency.store_result(
-                                        idempotency_key,
-                                        {
-                                            "data": result_data,
-                                            "cost_usd": cost_usd,
-                                        },
-                                        ttl_s=idempotency_ttl,
-                                        tenant=tenant,
-                                    )
-                                    idempotency.clear_in_progress(idempotency_key, tenant=tenant)
-                                
-                                duration_ms = int((time.time() - start_time) * 1000)
-                                _log_and_metric_llm_request(
-                                    request_id=request_id,
-                                    target_name=target_name,
-                                    provider=provider,
-                                    model=final_model,
-                                    stream=False,
-                                    outcome="success",
-                                    latency_ms=duration_ms,
-                                    cache_hit=False,
-                                    idempotent_hit=False,
-                                    cost_usd=cost_usd,
-                                    tenant=tenant,
-                                )
-                                llm_requests_total.labels(target=target_name, provider=provider, status="success").inc()
-                                latency_ms.labels(target=target_name, status="success").observe(duration_ms)
-                                
-                                return SuccessResponse(
-                                    success=True,
-                                    data=result_data,
-                                    meta=MetaResponse(
-                                        target=target_name,
-                                        provider=provider,
-                                        model=final_model,
-                                        cache_hit=False,
-                                        idempotent_hit=False,
-                                        retries=retries,
-                                        duration_ms=duration_ms,
-                                        request_id=request_id,
-                                        trace_id=None,
-                                        cost_usd=cost_usd,
-                                        cost_estimate_usd=cost_estimate_usd,
-                                        cost_policy_applied=cost_policy_applied,
-                                        max_tokens_reduced=max_tokens_reduced if max_tokens_reduced else None,
-                                        original_max_tokens=original_max_tokens if max_tokens_reduced else None,
-                                    ),
+                                return _llm_success_response(
+                                    response_json, normalized_response,
+                                    _upstream_request_id(target_config, response.headers, provider),
                                 )
                         except Exception:
                             # Fall through to error handling
@@ -1680,7 +4352,6 @@ async def handle_llm_proxy(
             
             duration_ms = int((time.time() - start_time) * 1000)
             error_code = ErrorCode.from_http_status(response_status)
-            upstream_status_norm = UpstreamStatus.normalize(response_status)
             _log_and_metric_llm_request(
                 request_id=request_id,
                 target_name=target_name,
@@ -1697,12 +4368,6 @@ async def handle_llm_proxy(
             )
             # Legacy metrics
             llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-            errors_total.labels(
-                target=target_name,
-                kind="llm",
-                error_code=error_code.value,
-                upstream_status=upstream_status_norm,
-            ).inc()
             latency_ms.labels(target=target_name, status="error").observe(duration_ms)
             return ErrorResponse(
                 success=False,
@@ -1721,6 +4386,8 @@ async def handle_llm_proxy(
                     cache_hit=False,
                     retries=retries,
                     duration_ms=duration_ms,
+                    upstream_request_id=_upstream_request_id(target_config, response.headers, provider),
+                    region=_served_region(target_config, client),
                     request_id=request_id,
                     trace_id=None,
                 ),
@@ -1730,8 +4397,8 @@ async def handle_llm_proxy(
         response_json = json.loads(response_body.decode()) if response_body else {}
         
         # Validate response has required fields
-        if not response_json or "choices" not in response_json:
-            raise ValueError(f"Invalid response format from {provider}: missing 'choices' field")
+        if not response_json or adapter.response_field not in response_json:
+            raise ValueError(f"Invalid response format from {provider}: missing '{adapter.response_field}' field")
         
         normalized_response = adapter.parse_response(response_json)
         
@@ -1739,101 +4406,9 @@ async def handle_llm_proxy(
         if not normalized_response or "content" not in normalized_response:
             raise ValueError(f"Adapter parse_response returned invalid format: {normalized_response}")
         
-        # Update key pool health on success
-        if selected_key and key_pool_manager:
-            key_pool_manager.record_success(selected_key.id)
-            key_pool_requests_total.labels(
-                provider_key_id=selected_key.id,
-                provider=selected_key.provider,
-                status="success",
-            ).inc()
-            key_pool_qps.labels(provider_key_id=selected_key.id).observe(selected_key.current_qps)
-            status_value = {"active": 0, "degraded": 1, "exhausted": 2, "banned": 3}.get(selected_key.status, 0)
-            key_pool_status.labels(provider_key_id=selected_key.id, status=selected_key.status).observe(status_value)
-        
-        # Calculate cost
-        usage = response_json.get("usage", {})
-        prompt_tokens = usage.get("prompt_tokens", 0)
-        completion_tokens = usage.get("completion_tokens", 0)
-        cost_usd = adapter.get_cost_usd(final_model, prompt_tokens, completion_tokens)
-        
-        result_data = {
-            "content": normalized_response.get("content", ""),
-            "role": normalized_response.get("role", "assistant"),
-            "finish_reason": normalized_response.get("finish_reason", "stop"),
-            "usage": {
-                "prompt_tokens": prompt_tokens,
-                "completion_tokens": completion_tokens,
-                "total_tokens": prompt_tokens + completion_tokens,
-            },
-        }
-        
-        # Store in cache
-        if cache_config.get("enabled", True):
-            ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-            cache.set(
-                "POST", base_url + api_path, None, cache_key_bytes,
-                {
-                    "body": result_data,
-                    "cost_usd": cost_usd,
-                },
-                ttl_s=ttl,
-                query=None,
-                allow_post=True,
-                tenant=tenant,
-            )
-        
-        # Store idempotency result (use same TTL as cache for consistency)
-        if idempotency_key:
-            idempotency_ttl = cache_ttl or cache_config.get("ttl_s", 3600) if cache_config.get("enabled", True) else 3600
-            idempotency.store_result(
-                idempotency_key,
-                {
-                    "data": result_data,
-                    "cost_usd": cost_usd,
-                },
-                ttl_s=idempotency_ttl,
-                tenant=tenant,
-            )
-            idempotency.clear_in_progress(idempotency_key, tenant=tenant)
-        
-        duration_ms = int((time.time() - start_time) * 1000)
-        _log_and_metric_llm_request(
-            request_id=request_id,
-            target_name=target_name,
-            provider=provider,
-            model=final_model,
-            stream=False,
-            outcome="success",
-            latency_ms=duration_ms,
-            cache_hit=False,
-            idempotent_hit=False,
-            cost_usd=cost_usd,
-            tenant=tenant,
-        )
-        # Legacy metrics
-        llm_requests_total.labels(target=target_name, provider=provider, status="success").inc()
-        latency_ms.labels(target=target_name, status="success").observe(duration_ms)
-        # Note: llm_cost_usd legacy metric removed, using llm_cost_usd_total instead
-        return SuccessResponse(
-            success=True,
-            data=result_data,
-            meta=MetaResponse(
-                target=target_name,
-                provider=provider,
-                model=final_model,
-                cache_hit=False,
-                idempotent_hit=False,
-                retries=retries,
-                duration_ms=duration_ms,
-                request_id=request_id,
-                trace_id=None,
-                cost_usd=cost_usd,
-                cost_estimate_usd=cost_estimate_usd,
-                cost_policy_applied=cost_policy_applied,
-                max_tokens_reduced=max_tokens_reduced if max_tokens_reduced else None,
-                original_max_tokens=original_max_tokens if max_tokens_reduced else None,
-            ),
+        return _llm_success_response(
+            response_json, normalized_response,
+            _upstream_request_id(target_config, response.headers, provider),
         )
         
     except httpx.RequestError as e:
@@ -1855,7 +4430,6 @@ async def handle_llm_proxy(
         
         duration_ms = int((time.time() - start_time) * 1000)
         error_code = ErrorCode.NETWORK_ERROR
-        upstream_status_norm = UpstreamStatus.BAD_GATEWAY.value
         _log_and_metric_llm_request(
             request_id=request_id,
             target_name=target_name,
@@ -1872,12 +4446,6 @@ async def handle_llm_proxy(
         )
         # Legacy metrics
         llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-        errors_total.labels(
-            target=target_name,
-            kind="llm",
-            error_code=error_code.value,
-            upstream_status=upstream_status_norm,
-        ).inc()
         latency_ms.labels(target=target_name, status="error").observe(duration_ms)
         return ErrorResponse(
             success=False,
@@ -1908,7 +4476,6 @@ async def handle_llm_proxy(
         
         duration_ms = int((time.time() - start_time) * 1000)
         error_code = ErrorCode.INTERNAL_ERROR
-        upstream_status_norm = UpstreamStatus.INTERNAL_SERVER_ERROR.value
         _log_and_metric_llm_request(
             request_id=request_id,
             target_name=target_name,
@@ -1925,12 +4492,6 @@ async def handle_llm_proxy(
         )
         # Legacy metrics
         llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-        errors_total.labels(
-            target=target_name,
-            kind="llm",
-            error_code=error_code.value,
-            upstream_status=upstream_status_norm,
-        ).inc()
         latency_ms.labels(target=target_name, status="error").observe(duration_ms)
         return ErrorResponse(
             success=False,
@@ -1955,9 +4516,48 @@ async def handle_llm_proxy(
         )
         
     finally:
+        if concurrency_slot:
+            concurrency_slot.release()
         await client.close()
 
 
+# `error` event data for a stream that ended before its first event
+EMPTY_STREAM_ERROR = {
+    "code": ErrorCode.UPSTREAM_STREAM_INTERRUPTED.value,
+    "message": "Stream ended before its first event",
+    "upstream_status": 502,
+}
+
+
+async def prime_llm_stream(
+    generator: AsyncIterator[str],
+) -> Tuple[Optional[Dict[str, Any]], AsyncIterator[str]]:
+    """Read the first SSE event so stream-start metadata can go into response headers.
+    
+    Returns:
+        Tuple of (meta, stream)
+        - meta: Data of the initial `meta` event (None if the stream started with an error)
+        - stream: Full event stream, including the already-read first event (an
+          EMPTY_STREAM_ERROR event if the generator produced none)
+    """
+    try:
+        first_event = await generator.__anext__()
+    except StopAsyncIteration:
+        first_event = f"event: error\ndata: {json.dumps(EMPTY_STREAM_ERROR)}\n\n"
+    
+    meta = None
+    if first_event.startswith("event: meta\n"):
+        data_line = first_event.split("\n", 2)[1]
+        meta = json.loads(data_line[len("data: "):])
+    
+    async def _stream() -> AsyncIterator[str]:
+        yield first_event
+        async for event in generator:
+            yield event
+    
+    return meta, _stream()
+
+
 async def handle_llm_stream_generator(
     target_name: str,
     messages: List[Dict[str, str]],
@@ -1972,8 +4572,23 @@ async def handle_llm_stream_generator(
     cache: Cache,
     idempotency: IdempotencyManager,
     request_id: str,
+    tenant: Optional[str] = None,
+    tier: str = "free",
+    usage_store: Optional[UsageStore] = None,
+    tags: Optional[Dict[str, str]] = None,
+    model_rate_limiter: Optional[ModelRateLimiter] = None,
+    response_format: Optional[Dict[str, Any]] = None,
+    overrides: Optional[Dict[str, int]] = None,
+    logit_bias: Optional[Dict[str, float]] = None,
+    seed: Optional[int] = None,
+    client_ip: Optional[str] = None,
+    metadata: Optional[Any] = None,
+    cost_multiplier: float = 1.0,
 ) -> AsyncIterator[str]:
-    """Handle LLM streaming request - yields SSE events."""
+    """Handle LLM streaming request - yields SSE events (metadata is echoed in meta and done).
+
+    cost_multiplier is applied as in handle_llm_proxy.
+    """
     import json
     
     start_time = time.time()
@@ -2002,6 +4617,36 @@ async def handle_llm_stream_generator(
             yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
             return
         
+        # Only whitelisted optional params reach the provider (and the cache key)
+        params, rejected_params = _allowed_params(llm_config, target_name, {
+            "temperature": temperature, "top_p": top_p, "stop": stop,
+            "response_format": response_format, "logit_bias": logit_bias, "seed": seed,
+        })
+        if rejected_params:
+            error_data = {
+                "code": ErrorCode.PARAM_NOT_ALLOWED.value,
+                "message": f"Target '{target_name}' does not accept: {', '.join(rejected_params)}",
+                "upstream_status": 400,
+                "params": rejected_params,
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
+        temperature, top_p, stop = params["temperature"], params["top_p"], params["stop"]
+        response_format, logit_bias, seed = params["response_format"], params["logit_bias"], params["seed"]
+        
+        # Kill switch: rejected before the cache unless the target serves cache hits while disabled
+        if kill_switch.is_disabled(target_name) and not target_config.get("serve_cache_when_disabled"):
+            error_data = {
+                "code": ErrorCode.TARGET_DISABLED.value,
+                "message": f"Target '{target_name}' is disabled",
+                "upstream_status": 503,
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
+        
+        # Per-request timeout/retry overrides (X-ReliAPI-* headers, already clamped)
+        target_config = apply_request_overrides(target_config, overrides)
+        
         # Apply config limits
         final_model = model or llm_config.get("default_model", "gpt-4")
         final_max_tokens = max_tokens
@@ -2075,6 +4720,45 @@ async def handle_llm_stream_generator(
             )
             return
         
+        unsupported = adapter.unsupported_params(logit_bias=logit_bias, seed=seed)
+        if unsupported:
+            error_data = {
+                "code": ErrorCode.UNSUPPORTED_PARAM.value,
+                "message": f"Provider '{provider}' does not support: {', '.join(unsupported)}",
+                "upstream_status": 400,
+                "params": unsupported,
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
+        
+        # Per-model output cap (llm.output_caps), after llm.max_tokens
+        final_max_tokens, output_cap_tokens, exceeded_cap = _apply_output_cap(
+            llm_config, final_model, final_max_tokens
+        )
+        if exceeded_cap:
+            _log_and_metric_llm_request(
+                request_id=request_id,
+                target_name=target_name,
+                provider=provider,
+                model=final_model,
+                stream=True,
+                outcome="error",
+                latency_ms=int((time.time() - start_time) * 1000),
+                cache_hit=False,
+                idempotent_hit=False,
+                error_code=ErrorCode.OUTPUT_CAP_EXCEEDED.value,
+                upstream_status=422,
+                tenant=tenant,
+            )
+            error_data = {
+                "code": ErrorCode.OUTPUT_CAP_EXCEEDED.value,
+                "message": _output_cap_message(final_model, final_max_tokens, exceeded_cap),
+                "upstream_status": 422,
+                "details": {"model": final_model, "max_tokens": final_max_tokens, "output_cap_tokens": exceeded_cap},
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
+        
         # Check streaming support
         if not adapter.supports_streaming():
             error_code_enum = ErrorCode.STREAMING_UNSUPPORTED
@@ -2109,13 +4793,47 @@ async def handle_llm_stream_generator(
         max_tokens_reduced = False
         original_max_tokens = None
         
-        cost_estimate_usd = CostEstimator.estimate_from_messages(
-            provider, final_model, messages, final_max_tokens
+        # Models without pricing: allow (no estimate), reject, or estimate from chars
+        unknown_model_policy = _unknown_model_policy(llm_config, provider, final_model)
+        if unknown_model_policy == "reject":
+            _log_and_metric_llm_request(
+                request_id=request_id,
+                target_name=target_name,
+                provider=provider,
+                model=final_model,
+                stream=True,
+                outcome="error",
+                latency_ms=int((time.time() - start_time) * 1000),
+                cache_hit=False,
+                idempotent_hit=False,
+                error_code=ErrorCode.UNKNOWN_MODEL.value,
+                upstream_status=422,
+                tenant=tenant,
+            )
+            error_data = {
+                "code": ErrorCode.UNKNOWN_MODEL.value,
+                "message": _unknown_model_message(provider, final_model),
+                "upstream_status": 422,
+                "details": {"model": final_model, "provider": provider},
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
+        cost_approximate = unknown_model_policy == "estimate_chars"
+        
+        # Including the summarization call a long history triggers
+        summary_estimate_usd = _summary_cost_estimate(
+            llm_config, provider, final_model, messages, approximate=cost_approximate
         )
+        raw_estimate_usd = _with_summary_estimate(CostEstimator.estimate_from_messages(
+            provider, final_model, messages, final_max_tokens, approximate=cost_approximate
+        ), summary_estimate_usd)
+        cost_estimate_usd = _billed(raw_estimate_usd, cost_multiplier)
         
-        # Check hard cost cap (reject if exceeded)
+        # Check hard cost cap (reject if exceeded); target caps limit provider cost,
+        # and the error reports the cap billed like every other cost
         hard_cost_cap = llm_config.get("hard_cost_cap_usd")
-        if hard_cost_cap and cost_estimate_usd and cost_estimate_usd > hard_cost_cap:
+        if hard_cost_cap and raw_estimate_usd and raw_estimate_usd > hard_cost_cap:
+            hard_cost_cap = _billed(hard_cost_cap, cost_multiplier)
             duration_ms = int((time.time() - start_time) * 1000)
             budget_events_total.labels(target=target_name, event="hard_cap", tenant=tenant or "default").inc()
             error_code = ErrorCode.BUDGET_EXCEEDED
@@ -2148,8 +4866,8 @@ async def handle_llm_stream_generator(
         
         # Check soft cost cap (throttle by reducing max_tokens)
         soft_cost_cap = llm_config.get("soft_cost_cap_usd")
-        if soft_cost_cap and cost_estimate_usd and cost_estimate_usd > soft_cost_cap:
-            reduction_factor = soft_cost_cap / cost_estimate_usd
+        if soft_cost_cap and raw_estimate_usd and raw_estimate_usd > soft_cost_cap:
+            reduction_factor = soft_cost_cap / raw_estimate_usd
             original_max_tokens = final_max_tokens
             final_max_tokens = int(final_max_tokens * reduction_factor * 0.9)  # 0.9 for safety margin
             max_tokens_reduced = True
@@ -2157,18 +4875,123 @@ async def handle_llm_stream_generator(
             budget_events_total.labels(target=target_name, event="soft_cap", tenant=tenant or "default").inc()
             
             # Re-estimate with reduced tokens
-            cost_estimate_usd = CostEstimator.estimate_from_messages(
-                provider, final_model, messages, final_max_tokens
+            cost_estimate_usd = _billed(_with_summary_estimate(CostEstimator.estimate_from_messages(
+                provider, final_model, messages, final_max_tokens, approximate=cost_approximate
+            ), summary_estimate_usd), cost_multiplier)
+        
+        # Determine API endpoint
+        api_path = adapter.api_path(final_model)
+        
+        # Completed streams are cached under the non-streaming request's key, so streamed
+        # and non-streamed calls share entries; a hit is replayed as a single chunk
+        response_cache_payload = adapter.prepare_request(
+            messages=messages,
+            model=final_model,
+            max_tokens=final_max_tokens,
+            temperature=final_temperature,
+            top_p=top_p,
+            stop=stop,
+            stream=False,
+            response_format=response_format,
+            logit_bias=logit_bias,
+            seed=seed,
+        )
+        if llm_config.get("prompt_caching"):
+            response_cache_payload = adapter.apply_prompt_caching(response_cache_payload)
+        response_cache_key = canonical_json(response_cache_payload).encode()
+        # The previous version keyed on json.dumps(sort_keys=True); accepted for one release
+        legacy_cache_key = json.dumps(response_cache_payload, sort_keys=True).encode()
+        cache_config = target_config.get("cache", {})
+        cache_skipped_nondeterministic = _skip_nondeterministic_cache(cache_config, final_temperature)
+        cached = None
+        health_reason = health_ttl_reason(target_name, target_config)
+        if cache_config.get("enabled", True) and not cache_skipped_nondeterministic:
+            cached = cache.get(
+                "POST", base_url + api_path, None, response_cache_key, None, allow_post=True, tenant=tenant,
+                serve_stale=health_reason is not None, legacy_body=legacy_cache_key,
+            )
+        if cached:
+            allowed, retry_after_s, limiting = await _admit_cache_hit(
+                model_rate_limiter, target_config, target_name, final_model, llm_config
+            )
+            if not allowed:
+                yield _model_rate_limit_event(final_model, retry_after_s, limiting)
+                return
+            _extend_on_hit(
+                cache, cache_config, cached, "POST", base_url + api_path, None, response_cache_key, None,
+                allow_post=True, tenant=tenant,
+            )
+            cached_body = cached.get("body", {})
+            cost_usd = cached.get("cost_usd")
+            duration_ms = int((time.time() - start_time) * 1000)
+            _log_and_metric_llm_request(
+                request_id=request_id,
+                target_name=target_name,
+                provider=provider,
+                model=final_model,
+                stream=True,
+                outcome="success",
+                latency_ms=duration_ms,
+                cache_hit=True,
+                idempotent_hit=False,
+                cost_usd=cost_usd,
+                tenant=tenant,
             )
+            cache_hits_total.labels(target=target_name, kind="llm", tenant=tenant or "default").inc()
+            if usage_store:
+                usage_store.record(
+                    request_id=request_id,
+                    kind="llm",
+                    target=target_name,
+                    status="success",
+                    latency_ms=duration_ms,
+                    model=final_model,
+                    cost_usd=cost_usd,
+                    raw_cost_usd=raw_cost(cost_usd, cost_multiplier),
+                    tags=tags,
+                    tenant=tenant,
+                )
+            meta_data = {
+                "target": target_name,
+                "provider": provider,
+                "model": final_model,
+                "request_id": request_id,
+                "cache_hit": True,
+                **_ttl_extended(cached, target_name, "llm", health_reason),
+                "metadata": metadata,
+            }
+            yield f"event: meta\ndata: {json.dumps(meta_data)}\n\n"
+            yield f"event: chunk\ndata: {json.dumps({'delta': cached_body.get('content', ''), 'finish_reason': None})}\n\n"
+            done_data = {
+                "finish_reason": cached_body.get("finish_reason", "stop"),
+                "usage": cached_body.get("usage"),
+                "cost_usd": cost_usd,
+                "cost_breakdown": cached.get("cost_breakdown"),
+                "cache_hit": True,
+                "metadata": metadata,
+            }
+            yield f"event: done\ndata: {json.dumps(done_data)}\n\n"
+            return
+        
+        # Maintenance mode and kill switch with serve_cache_when_disabled: the cache missed
+        if maintenance_mode.is_active(target_name) or kill_switch.is_disabled(target_name):
+            maintenance = maintenance_mode.is_active(target_name)
+            error_data = {
+                "code": (ErrorCode.MAINTENANCE_CACHE_ONLY if maintenance else ErrorCode.TARGET_DISABLED).value,
+                "message": (
+                    f"Target '{target_name}' is in maintenance mode: only cached responses are served"
+                    if maintenance else f"Target '{target_name}' is disabled"
+                ),
+                "upstream_status": 503,
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
         
-        # Handle idempotency for streaming (MVP: simple check)
+        # Handle idempotency for streaming: keyed on the non-streaming request, so streamed
+        # and non-streamed calls with the same key share one record
         if idempotency_key:
-            full_url = f"{base_url}/chat/completions"  # Simplified path
-            cache_key_bytes = json.dumps({
-                "messages": messages,
-                "model": final_model,
-                "max_tokens": final_max_tokens,
-            }, sort_keys=True).encode()
+            full_url = f"{base_url}{api_path}"
+            cache_key_bytes = response_cache_key
             
             is_new, existing_id, existing_hash = idempotency.register_request(
                 idempotency_key, "POST", full_url, None, cache_key_bytes, request_id, tenant=tenant
@@ -2177,7 +5000,13 @@ async def handle_llm_stream_generator(
             if not is_new:
                 # Check if request differs
                 current_hash = idempotency.make_request_hash("POST", full_url, None, cache_key_bytes)
-                if existing_hash != current_hash:
+                # The previous version hashed streams over messages/model/max_tokens; accepted for one release
+                legacy_hash = idempotency.make_legacy_request_hash(
+                    "POST", f"{base_url}/chat/completions", None,
+                    json.dumps({"messages": messages, "model": final_model, "max_tokens": final_max_tokens},
+                               sort_keys=True).encode(),
+                )
+                if existing_hash not in (current_hash, legacy_hash):
                     error_data = {
                         "code": "IDEMPOTENCY_CONFLICT",
                         "message": f"Idempotency key '{idempotency_key}' used with different request",
@@ -2186,41 +5015,105 @@ async def handle_llm_stream_generator(
                     yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
                     return
                 
-                # Check if result exists (completed stream)
+                # Completed stream: replay the stored response instead of calling the provider
                 existing_result = idempotency.get_result(idempotency_key, tenant=tenant)
+                if not existing_result and idempotency.is_in_progress(idempotency_key, tenant=tenant):
+                    if llm_config.get("stream_idempotency_in_progress", "reject") == "wait":
+                        existing_result = await _wait_for_idempotent_result(idempotency, idempotency_key, tenant)
+                    if not existing_result:
+                        error_data = {
+                            "code": ErrorCode.STREAM_ALREADY_IN_PROGRESS.value,
+                            "message": f"Stream already in progress for idempotency key '{idempotency_key}'",
+                            "upstream_status": 409,
+                        }
+                        yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                        return
                 if existing_result:
-                    # For MVP: return cached result as non-stream JSON
-                    # In future: could simulate SSE stream
-                    error_data = {
-                        "code": ErrorCode.STREAM_ALREADY_COMPLETED.value,
-                        "message": f"Stream already completed for idempotency key '{idempotency_key}'. Use non-streaming request to get cached result.",
-                        "upstream_status": 409,
-                    }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
-                    return
-                
-                # Check if stream is in progress
-                if idempotency.is_in_progress(idempotency_key, tenant=tenant):
-                    error_data = {
-                        "code": ErrorCode.STREAM_ALREADY_IN_PROGRESS.value,
-                        "message": f"Stream already in progress for idempotency key '{idempotency_key}'",
-                        "upstream_status": 409,
-                    }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                    cost_usd = existing_result.get("cost_usd")
+                    duration_ms = int((time.time() - start_time) * 1000)
+                    _log_and_metric_llm_request(
+                        request_id=request_id,
+                        target_name=target_name,
+                        provider=provider,
+                        model=final_model,
+                        stream=True,
+                        outcome="success",
+                        latency_ms=duration_ms,
+                        cache_hit=False,
+                        idempotent_hit=True,
+                        cost_usd=cost_usd,
+                        tenant=tenant,
+                    )
+                    if usage_store:
+                        usage_store.record(
+                            request_id=request_id,
+                            kind="llm",
+                            target=target_name,
+                            status="success",
+                            latency_ms=duration_ms,
+                            model=final_model,
+                            cost_usd=cost_usd,
+                            raw_cost_usd=raw_cost(cost_usd, cost_multiplier),
+                            tags=tags,
+                            tenant=tenant,
+                        )
+                    for event in _idempotent_stream_replay(
+                        existing_result, target_name, provider, final_model, request_id, metadata
+                    ):
+                        yield event
                     return
             
             idempotency.mark_in_progress(idempotency_key, tenant=tenant)
         
+        # Per-model RPM/TPM caps
+        allowed, model_rate_reservation, retry_after_s, limiting = await _acquire_model_rate_limit(
+            model_rate_limiter, target_name, final_model, llm_config, messages, final_max_tokens
+        )
+        if not allowed:
+            if idempotency_key:
+                idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+            yield _model_rate_limit_event(final_model, retry_after_s, limiting)
+            return
+        
+        # Long histories: older turns replaced with a summary (its cost is added to cost_usd).
+        # Only now, past every gate; the cache and idempotency keys above use the full history.
+        history_meta: Dict[str, Any] = {}
+        summary_cost_usd = None
+        if llm_config.get("summarization"):
+            messages, history_meta, summary_cost_usd = await _summarize_history(
+                messages, target_config, target_name, adapter, provider, final_model, cache,
+                tenant=tenant, request_id=request_id,
+            )
+        
+        # Regional endpoints: the stream goes to the fastest healthy region (no failover mid-stream)
+        upstream_base_url = base_url
+        region = None
+        if target_config.get("regions"):
+            best_region = region_router.rank(
+                target_name, target_config["regions"], circuit_breakers.get(target_name, target_config.get("circuit"))
+            )[0]
+            upstream_base_url, region = region_url(best_region), best_region["name"]
+        
+        # Timeout scaled to the requested generation length
+        scaled_timeout_ms = _scaled_timeout_ms(target_config, final_max_tokens, overrides)
+        
         # Send meta event
         meta_data = {
             "target": target_name,
             "provider": provider,
             "model": final_model,
             "request_id": request_id,
+            "region": region,
             "cost_estimate_usd": cost_estimate_usd,
+            "cost_approximate": cost_approximate or None,
             "cost_policy_applied": cost_policy_applied,
             "max_tokens_reduced": max_tokens_reduced if max_tokens_reduced else None,
             "original_max_tokens": original_max_tokens if max_tokens_reduced else None,
+            "output_cap_tokens": output_cap_tokens,
+            "cache_skipped_nondeterministic": cache_skipped_nondeterministic or None,
+            "timeout_ms": scaled_timeout_ms,
+            **history_meta,
+            "metadata": metadata,
         }
         yield f"event: meta\ndata: {json.dumps(meta_data)}\n\n"
         
@@ -2233,130 +5126,157 @@ async def handle_llm_stream_generator(
             top_p=top_p,
             stop=stop,
             stream=True,
+            response_format=response_format,
+            logit_bias=logit_bias,
+            seed=seed,
+            stream_usage=llm_config.get("stream_usage", True),
         )
-        
-        # Determine API endpoint
-        if provider == "openai":
-            api_path = "/chat/completions"
-        elif provider == "anthropic":
-            api_path = "/messages"
-        elif provider == "mistral":
-            api_path = "/chat/completions"
-        else:
-            api_path = "/chat/completions"
+        if llm_config.get("prompt_caching"):
+            payload = adapter.apply_prompt_caching(payload)
         
         # Create HTTP client with auth
         auth_config = target_config.get("auth", {})
-        headers = {"Content-Type": "application/json"}
+        headers = {**outbound_headers(target_config, client_ip, request_id), "Content-Type": "application/json"}
         if auth_config.get("type") == "bearer_env":
             import os
             env_var = auth_config.get("env_var")
             if env_var:
                 api_key = os.getenv(env_var)
                 if api_key:
-                    headers["Authorization"] = f"Bearer {api_key}"
+                    headers[auth_config.get("header", "Authorization")] = f"{auth_config.get('prefix', 'Bearer ')}{api_key}"
+        
+        # Create httpx client for streaming (timeout scaled to max_tokens with llm.timeout_scaling)
+        timeout_s = (scaled_timeout_ms or target_config.get("timeout_ms", 20000)) / 1000.0
+        idle_timeout_ms = target_config.get("idle_timeout_ms", 30000)
+        idle_timeout_s = idle_timeout_ms / 1000.0
+        # Until the first chunk: first_byte_timeout_ms, else the whole request timeout
+        first_byte_timeout_s = (
+            target_config["first_byte_timeout_ms"] / 1000.0 if target_config.get("first_byte_timeout_ms")
+            else timeout_s
+        )
+        usage_outcome = "error"
+        cost_usd = None
+        prompt_tokens = completion_tokens = None
+        
+        # Concurrency cap: the slot is held for the whole stream
+        try:
+            concurrency_slot = await concurrency_limiter.acquire(target_name, target_config.get("concurrency"))
+        except QueueTimeout as e:
+            if idempotency_key:
+                idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+            error_data = {
+                "code": _saturation_code(e),
+                "message": str(e),
+                "upstream_status": 503,
+                "retry_after_s": e.retry_after_s,
+            }
+            yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+            return
         
-        # Create httpx client for streaming
-        timeout_s = target_config.get("timeout_ms", 20000) / 1000.0
-        async with httpx.AsyncClient(timeout=timeout_s) as client:
+        # llm.stream_retry: keyed streams are reopened on failure ("retries"/"restarted" filled in)
+        stream_retry_config = llm_config.get("stream_retry")
+        stream_retry_stats: Dict[str, Any] = {}
+        
+        async with httpx.AsyncClient(timeout=timeout_s, auth=request_signer(target_config)) as client:
             try:
                 # Stream from provider
                 accumulated_content = ""
                 finish_reason = None
-                prompt_tokens = 0
-                completion_tokens = 0
+                stream_usage: Dict[str, int] = {}
+                
+                def open_stream() -> AsyncIterator[Dict[str, Any]]:
+                    return _iter_with_idle_timeout(
+                        adapter.stream_chat(client, upstream_base_url, api_path, payload, headers),
+                        idle_timeout_s,
+                        first_byte_timeout_s,
+                    )
+                
+                chunks = open_stream()
+                if stream_retry_config and idempotency_key:
+                    chunks = replay_safe_stream(open_stream, stream_retry_config, _stream_retryable, stream_retry_stats)
                 
-                async for chunk in adapter.stream_chat(
-                    client, base_url, api_path, payload, headers
-                ):
+                # llm.stream_cost_ceiling_usd: abort once the running cost passes the ceiling
+                cost_ceiling = llm_config.get("stream_cost_ceiling_usd")
+                ceiling_prompt_tokens = CostEstimator.estimate_prompt_tokens(messages) if cost_ceiling else 0
+                cost_ceiling_reached = False
+                
+                async for chunk in chunks:
                     stream_started = True
                     
-                    # Parse OpenAI chunk format
-                    if provider == "openai":
-                        # Check if this is a usage-only chunk (sent after [DONE])
-                        if chunk.get("_usage_only"):
-                            usage = chunk.get("usage", {})
-                            if usage:
-                                prompt_tokens = usage.get("prompt_tokens", 0)
-                                completion_tokens = usage.get("completion_tokens", 0)
-                            continue
-                        
-                        choices = chunk.get("choices", [])
-                        if choices:
-                            delta = choices[0].get("delta", {})
-                            content_delta = delta.get("content", "")
-                            if content_delta:
-                                accumulated_content += content_delta
-                                yield f"event: chunk\ndata: {json.dumps({'delta': content_delta, 'finish_reason': None})}\n\n"
-                            
-                            # Check for finish reason
-                            if choices[0].get("finish_reason"):
-                                finish_reason = choices[0]["finish_reason"]
-                        
-                        # Get usage if available in regular chunk (some providers include it)
+                    # Adapters translate provider events to OpenAI chat.completion.chunk
+                    if chunk.get("_usage_only"):
                         usage = chunk.get("usage", {})
                         if usage:
-                            prompt_tokens = usage.get("prompt_tokens", 0)
-                            completion_tokens = usage.get("completion_tokens", 0)
+                            stream_usage = _merge_stream_usage(stream_usage, usage)
+                        continue
                     
-                    # Parse Anthropic chunk format
-                    elif provider == "anthropic":
-                        # Check if this is a usage-only chunk
-                        if chunk.get("_usage_only"):
-                            usage = chunk.get("usage", {})
-                            if usage:
-                                prompt_tokens = usage.get("prompt_tokens", 0)
-                                completion_tokens = usage.get("completion_tokens", 0)
-                            continue
-                        
-                        choices = chunk.get("choices", [])
-                        if choices:
-                            delta = choices[0].get("delta", {})
-                            content_delta = delta.get("content", "")
-                            if content_delta:
-                                accumulated_content += content_delta
-                                yield f"event: chunk\ndata: {json.dumps({'delta': content_delta, 'finish_reason': None})}\n\n"
-                            
-                            # Check for finish reason
-                            if choices[0].get("finish_reason"):
-                                finish_reason = choices[0]["finish_reason"]
+                    choices = [choice for choice in chunk.get("choices", []) if choice.get("index", 0) == 0]
+                    if choices:
+                        delta = choices[0].get("delta", {})
+                        content_delta = delta.get("content", "")
+                        if content_delta:
+                            accumulated_content += content_delta
+                            yield f"event: chunk\ndata: {json.dumps({'delta': content_delta, 'finish_reason': None})}\n\n"
                         
-                        # Get usage if available
-                        usage = chunk.get("usage", {})
-                        if usage:
-                            prompt_tokens = usage.get("prompt_tokens", 0)
-                            completion_tokens = usage.get("completion_tokens", 0)
+                        # Check for finish reason
+                        if choices[0].get("finish_reason"):
+                            finish_reason = choices[0]["finish_reason"]
                     
-                    # Parse Mistral chunk format (similar to OpenAI)
-                    elif provider == "mistral":
-                        # Check if this is a usage-only chunk
-                        if chunk.get("_usage_only"):
-                            usage = chunk.get("usage", {})
-                            if usage:
-                                prompt_tokens = usage.get("prompt_tokens", 0)
-                                completion_tokens = usage.get("completion_tokens", 0)
-                            continue
-                        
-                        choices = chunk.get("choices", [])
-                        if choices:
-                            delta = choices[0].get("delta", {})
-                            content_delta = delta.get("content", "")
-                            if content_delta:
-                                accumulated_content += content_delta
-                                yield f"event: chunk\ndata: {json.dumps({'delta': content_delta, 'finish_reason': None})}\n\n"
-                            
-                            # Check for finish reason
-                            if choices[0].get("finish_reason"):
-                                finish_reason = choices[0]["finish_reason"]
-                        
-                        # Get usage if available
-                        usage = chunk.get("usage", {})
-                        if usage:
-                            prompt_tokens = usage.get("prompt_tokens", 0)
-                            completion_tokens = usage.get("completion_tokens", 0)
+                    # Get usage if available in regular chunk (some providers include it)
+                    usage = chunk.get("usage", {})
+                    if usage:
+                        stream_usage = _merge_stream_usage(stream_usage, usage)
+                    
+                    if cost_ceiling and not finish_reason:
+                        running_cost = _running_stream_cost(
+                            adapter, provider, final_model, ceiling_prompt_tokens, accumulated_content,
+                            stream_usage, cost_approximate, cost_multiplier,
+                        )
+                        if running_cost is not None and running_cost + (_billed(summary_cost_usd, cost_multiplier) or 0.0) > cost_ceiling:
+                            cost_ceiling_reached = True
+                            finish_reason = "length"
+                            break
                 
-                # Calculate final cost
-                cost_usd = adapter.get_cost_usd(final_model, prompt_tokens, completion_tokens)
+                if cost_ceiling_reached:
+                    # Closing the stream's own client drops the upstream connection, so generation stops
+                    await chunks.aclose()
+                    await client.aclose()
+                    stream_cost_ceiling_total.labels(target=target_name, model=final_model).inc()
+                    logger.warning(
+                        f"Stream {request_id} to '{target_name}' aborted at cost ceiling ${cost_ceiling:.6f}"
+                    )
+                
+                # Calculate final cost (from provider usage; local estimate for missing counts)
+                usage_source = "provider"
+                if "prompt_tokens" not in stream_usage or "completion_tokens" not in stream_usage:
+                    usage_source = "estimated"
+                    stream_usage = {**_estimate_usage(messages, [accumulated_content]), **stream_usage}
+                prompt_tokens = stream_usage.get("prompt_tokens", 0)
+                completion_tokens = stream_usage.get("completion_tokens", 0)
+                cached_prompt_tokens = stream_usage.get("cached_prompt_tokens", 0)
+                cost_breakdown = adapter.get_cost_breakdown(
+                    final_model, prompt_tokens, completion_tokens,
+                    cached_prompt_tokens=cached_prompt_tokens,
+                    cache_write_tokens=stream_usage.get("cache_write_tokens", 0),
+                )
+                if cost_breakdown is None and cost_approximate:
+                    cost_breakdown = CostEstimator.approximate_cost_breakdown_from_usage(
+                        provider, final_model, prompt_tokens, completion_tokens
+                    )
+                cost_usd = _total_cost(cost_breakdown)
+                if summary_cost_usd:
+                    cost_usd = (cost_usd or 0.0) + summary_cost_usd
+                _observe_llm_usage(target_name, final_model, prompt_tokens, completion_tokens, cost_usd)
+                cost_usd = _billed(cost_usd, cost_multiplier)
+                cost_breakdown = _billed_breakdown(cost_breakdown, cost_multiplier)
+                _observe_payload_sizes(
+                    target_name, "llm", final_model,
+                    json.dumps(payload).encode(), accumulated_content.encode(),
+                )
+                _reconcile_model_rate_limit(
+                    model_rate_limiter, model_rate_reservation, target_name, final_model, llm_config,
+                    prompt_tokens + completion_tokens,
+                )
                 
                 # Send done event
                 done_data = {
@@ -2366,44 +5286,63 @@ async def handle_llm_stream_generator(
                         "completion_tokens": completion_tokens,
                         "total_tokens": prompt_tokens + completion_tokens,
                     },
+                    "cached_prompt_tokens": cached_prompt_tokens,
+                    "usage_source": usage_source,
                     "cost_usd": cost_usd,
+                    "cost_breakdown": cost_breakdown,
+                    "cost_approximate": cost_approximate or None,
+                    # Upper bound sent at stream start (X-ReliAPI-Estimated-Cost), for comparison
+                    "cost_estimate_usd": cost_estimate_usd,
+                    **_stream_retry_meta(stream_retry_stats),
+                    "cost_ceiling_reached": cost_ceiling_reached or None,
+                    "metadata": metadata,
                 }
                 yield f"event: done\ndata: {json.dumps(done_data)}\n\n"
                 
-                # Store in cache and idempotency (final completion only)
-                cache_config = target_config.get("cache", {})
-                if cache_config.get("enabled", True):
-                    ttl = cache_ttl or cache_config.get("ttl_s", 3600)
-                    result_data = {
-                        "content": accumulated_content,
-                        "role": "assistant",
-                        "finish_reason": finish_reason or "stop",
-                        "usage": done_data["usage"],
-                    }
-                    cache.set(
-                        "POST", base_url + api_path, None, json.dumps(payload, sort_keys=True).encode(),
+                # Store in cache and idempotency (final completion only), as the same body
+                # as a non-streamed response, so either kind of request can be served from it
+                normalization = llm_config.get("normalization") or {}
+                normalized_choices = normalize_choices([{
+                    "index": 0,
+                    "content": accumulated_content,
+                    "role": "assistant",
+                    "finish_reason": finish_reason or "stop",
+                }], normalization)
+                result_data = {
+                    "content": accumulated_content,
+                    "role": "assistant",
+                    "finish_reason": normalized_choices[0]["finish_reason"],
+                    "choices": normalized_choices,
+                    "usage": done_data["usage"],
+                }
+                # Output cut at the cost ceiling is not cached (idempotent retries still replay it)
+                if cache_config.get("enabled", True) and not cache_skipped_nondeterministic and not cost_ceiling_reached:
+                    ttl = cache_ttl or _cost_weighted_ttl(cache_config, provider, final_model)
+                    # The done event is already sent; an oversized stream is only logged
+                    _cache_store_meta(lambda: cache.set(
+                        "POST", base_url + api_path, None, response_cache_key,
                         {
                             "body": result_data,
                             "cost_usd": cost_usd,
+                            "cost_breakdown": cost_breakdown,
                         },
                         ttl_s=ttl,
                         query=None,
                         allow_post=True,
                         tenant=tenant,
-                    )
+                        ttl_jitter=cache_config.get("ttl_jitter", DEFAULT_TTL_JITTER),
+                        max_ttl_s=_max_ttl(cache_config),
+                        stale_ttl_s=stale_ttl_s(cache_config, ttl),
+                    ))
                 
                 if idempotency_key:
                     idempotency_ttl = cache_ttl or cache_config.get("ttl_s", 3600) if cache_config.get("enabled", True) else 3600
                     idempotency.store_result(
                         idempotency_key,
                         {
-                            "data": {
-                                "content": accumulated_content,
-                                "role": "assistant",
-                                "finish_reason": finish_reason or "stop",
-                                "usage": done_data["usage"],
-                            },
+                            "data": result_data,
                             "cost_usd": cost_usd,
+                            "cost_breakdown": cost_breakdown,
                         },
                         ttl_s=idempotency_ttl,
                         tenant=tenant,
@@ -2411,6 +5350,7 @@ async def handle_llm_stream_generator(
                     idempotency.clear_in_progress(idempotency_key, tenant=tenant)
                 
                 # Update metrics and log
+                usage_outcome = "success"
                 duration_ms = int((time.time() - start_time) * 1000)
                 _log_and_metric_llm_request(
                     request_id=request_id,
@@ -2440,17 +5380,16 @@ async def handle_llm_stream_generator(
                         "message": f"Upstream stream interrupted: {e.response.status_code}",
                         "upstream_status": e.response.status_code,
                     }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                    yield f"event: error\ndata: {json.dumps({**error_data, **_stream_retry_meta(stream_retry_stats)})}\n\n"
                 else:
                     # Stream not started yet, can retry/fallback (simplified for MVP)
                     error_code_enum = ErrorCode.from_http_status(e.response.status_code)
-                    upstream_status_norm = UpstreamStatus.normalize(e.response.status_code)
                     error_data = {
                         "code": error_code_enum.value,
                         "message": f"Upstream returned {e.response.status_code}",
                         "upstream_status": e.response.status_code,
                     }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                    yield f"event: error\ndata: {json.dumps({**error_data, **_stream_retry_meta(stream_retry_stats)})}\n\n"
                 
                 if idempotency_key:
                     idempotency.clear_in_progress(idempotency_key, tenant=tenant)
@@ -2472,12 +5411,41 @@ async def handle_llm_stream_generator(
                 )
                 # Legacy metrics
                 llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-                errors_total.labels(
-                    target=target_name,
-                    kind="llm",
+                latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+                
+            except StreamIdleTimeout as e:
+                error_code_enum = ErrorCode.STREAM_IDLE_TIMEOUT
+                error_data = {
+                    "code": error_code_enum.value,
+                    "message": (
+                        f"No first chunk from upstream within {int(first_byte_timeout_s * 1000)}ms" if e.first_chunk
+                        else f"No chunk from upstream within {idle_timeout_ms}ms"
+                    ),
+                    "upstream_status": 504,
+                }
+                yield f"event: error\ndata: {json.dumps({**error_data, **_stream_retry_meta(stream_retry_stats)})}\n\n"
+                stream_idle_timeouts_total.labels(target=target_name, model=final_model).inc()
+                
+                if idempotency_key:
+                    idempotency.clear_in_progress(idempotency_key, tenant=tenant)
+                
+                duration_ms = int((time.time() - start_time) * 1000)
+                _log_and_metric_llm_request(
+                    request_id=request_id,
+                    target_name=target_name,
+                    provider=provider,
+                    model=final_model,
+                    stream=True,
+                    outcome="error",
+                    latency_ms=duration_ms,
+                    cache_hit=False,
+                    idempotent_hit=False,
                     error_code=error_code_enum.value,
-                    upstream_status=upstream_status_norm,
-                ).inc()
+                    upstream_status=504,
+                    tenant=tenant,
+                )
+                # Legacy metrics
+                llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
                 latency_ms.labels(target=target_name, status="error").observe(duration_ms)
                 
             except httpx.RequestError as e:
@@ -2489,16 +5457,15 @@ async def handle_llm_stream_generator(
                         "message": f"Network error during stream: {str(e)}",
                         "upstream_status": 502,
                     }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                    yield f"event: error\ndata: {json.dumps({**error_data, **_stream_retry_meta(stream_retry_stats)})}\n\n"
                 else:
                     error_code_enum = ErrorCode.NETWORK_ERROR
-                    upstream_status_norm = UpstreamStatus.BAD_GATEWAY.value
                     error_data = {
                         "code": error_code_enum.value,
                         "message": f"Network error: {str(e)}",
                         "upstream_status": 502,
                     }
-                    yield f"event: error\ndata: {json.dumps(error_data)}\n\n"
+                    yield f"event: error\ndata: {json.dumps({**error_data, **_stream_retry_meta(stream_retry_stats)})}\n\n"
                 
                 if idempotency_key:
                     idempotency.clear_in_progress(idempotency_key, tenant=tenant)
@@ -2520,12 +5487,6 @@ async def handle_llm_stream_generator(
                 )
                 # Legacy metrics
                 llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-                errors_total.labels(
-                    target=target_name,
-                    kind="llm",
-                    error_code=error_code_enum.value,
-                    upstream_status=upstream_status_norm,
-                ).inc()
                 latency_ms.labels(target=target_name, status="error").observe(duration_ms)
                 
             except Exception as e:
@@ -2558,13 +5519,26 @@ async def handle_llm_stream_generator(
                 )
                 # Legacy metrics
                 llm_requests_total.labels(target=target_name, provider=provider, status="error").inc()
-                errors_total.labels(
-                    target=target_name,
-                    kind="llm",
-                    error_code=error_code_enum.value,
-                    upstream_status=upstream_status_norm,
-                ).inc()
                 latency_ms.labels(target=target_name, status="error").observe(duration_ms)
+            
+            finally:
+                if concurrency_slot:
+                    concurrency_slot.release()
+                if usage_store:
+                    usage_store.record(
+                        request_id=request_id,
+                        kind="llm",
+                        target=target_name,
+                        status=usage_outcome,
+                        latency_ms=int((time.time() - start_time) * 1000),
+                        model=final_model,
+                        cost_usd=cost_usd,
+                        raw_cost_usd=raw_cost(cost_usd, cost_multiplier),
+                        tags=tags,
+                        tenant=tenant,
+                        prompt_tokens=prompt_tokens,
+                        completion_tokens=completion_tokens,
+                    )
     
     except Exception as e:
         # Catch-all for any errors before stream starts
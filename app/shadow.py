@@ -0,0 +1,233 @@
+"""Shadow (mirror) traffic for evaluating candidate targets.
+
+A target with a `shadow` config gets a copy of its requests sent to the
+shadow target in the background. The shadow response is compared with the
+primary response (success, status, latency, cost, content) and the result is
+logged as a structured JSON line and exported as metrics. The client only
+ever sees the primary response.
+"""
+import asyncio
+import difflib
+import json
+import logging
+import random
+from datetime import datetime
+from typing import Any, Dict, List, Optional, Set, Union
+
+from reliapi.app.schemas import ErrorResponse, SuccessResponse
+from reliapi.app.services import HTTPStreamResult, handle_http_proxy, handle_llm_proxy
+from reliapi.core.cache import Cache
+from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.key_pool import KeyPoolManager
+from reliapi.metrics.prometheus import shadow_cost_delta_usd, shadow_requests_total
+
+logger = logging.getLogger(__name__)
+
+# Only safe methods are mirrored for HTTP targets (no side effects upstream)
+SHADOW_HTTP_METHODS = {"GET", "HEAD"}
+
+# Strong references to in-flight shadow tasks (prevents garbage collection)
+_shadow_tasks: Set[asyncio.Task] = set()
+
+ProxyResult = Union[SuccessResponse, ErrorResponse]
+
+
+def select_shadow_target(target_config: Dict[str, Any]) -> Optional[Dict[str, Any]]:
+    """Return the shadow config if this request should be mirrored."""
+    shadow_config = target_config.get("shadow")
+    if not shadow_config:
+        return None
+    if random.random() >= shadow_config.get("sample_rate", 1.0):
+        return None
+    return shadow_config
+
+
+def _shadow_targets(targets: Dict[str, Dict], shadow_target: str) -> Dict[str, Dict]:
+    """Targets view for the shadow call: shadow target only, without cache or shadow."""
+    shadow_target_config = {**targets[shadow_target], "cache": {"enabled": False}}
+    shadow_target_config.pop("shadow", None)
+    return {shadow_target: shadow_target_config}
+
+
+def compare_responses(kind: str, primary: ProxyResult, shadow: ProxyResult) -> Dict[str, Any]:
+    """Build a structured comparison of primary and shadow responses.
+
+    Args:
+        kind: "http" or "llm"
+        primary: Primary response
+        shadow: Shadow response
+
+    Returns:
+        Comparison dict. `match` is None if either side failed.
+    """
+    comparison: Dict[str, Any] = {
+        "primary_success": primary.success,
+        "shadow_success": shadow.success,
+        "primary_latency_ms": primary.meta.duration_ms,
+        "shadow_latency_ms": shadow.meta.duration_ms,
+        "match": None,
+    }
+    if not shadow.success:
+        comparison["shadow_error_code"] = shadow.error.code
+
+    if kind == "llm":
+        comparison["primary_model"] = primary.meta.model
+        comparison["shadow_model"] = shadow.meta.model
+        comparison["primary_cost_usd"] = primary.meta.cost_usd
+        comparison["shadow_cost_usd"] = shadow.meta.cost_usd
+        if primary.success and shadow.success:
+            primary_content = primary.data.get("content") or ""
+            shadow_content = shadow.data.get("content") or ""
+            comparison["match"] = primary_content == shadow_content
+            comparison["similarity"] = round(
+                difflib.SequenceMatcher(None, primary_content, shadow_content).ratio(), 4
+            )
+    else:
+        if primary.success and shadow.success:
+            comparison["primary_status"] = primary.data.get("status_code")
+            comparison["shadow_status"] = shadow.data.get("status_code")
+            comparison["match"] = (
+                primary.data.get("status_code") == shadow.data.get("status_code")
+                and primary.data.get("body") == shadow.data.get("body")
+            )
+
+    return comparison
+
+
+def _record_comparison(
+    kind: str,
+    request_id: str,
+    target_name: str,
+    shadow_target: str,
+    comparison: Dict[str, Any],
+    tenant: Optional[str],
+) -> None:
+    """Log comparison as structured JSON and update metrics."""
+    log_entry = {
+        "ts": datetime.utcnow().isoformat() + "Z",
+        "event": "shadow_comparison",
+        "request_id": request_id,
+        "kind": kind,
+        "target": target_name,
+        "shadow_target": shadow_target,
+        **comparison,
+    }
+    if tenant:
+        log_entry["tenant"] = tenant
+    logger.info(json.dumps(log_entry, ensure_ascii=False))
+
+    match = comparison["match"]
+    shadow_requests_total.labels(
+        target=target_name,
+        shadow_target=shadow_target,
+        outcome="success" if comparison["shadow_success"] else "error",
+        match="n/a" if match is None else str(match).lower(),
+    ).inc()
+
+    primary_cost = comparison.get("primary_cost_usd")
+    shadow_cost = comparison.get("shadow_cost_usd")
+    if primary_cost is not None and shadow_cost is not None:
+        shadow_cost_delta_usd.labels(target=target_name, shadow_target=shadow_target).observe(
+            shadow_cost - primary_cost
+        )
+
+
+async def run_llm_shadow(
+    primary: ProxyResult,
+    shadow_config: Dict[str, Any],
+    target_name: str,
+    messages: List[Dict[str, str]],
+    max_tokens: Optional[int],
+    temperature: Optional[float],
+    top_p: Optional[float],
+    stop: Optional[List[str]],
+    targets: Dict[str, Dict],
+    cache: Cache,
+    idempotency: IdempotencyManager,
+    request_id: str,
+    tenant: Optional[str] = None,
+    key_pool_manager: Optional[KeyPoolManager] = None,
+    cost_multiplier: float = 1.0,
+) -> Optional[Dict[str, Any]]:
+    """Mirror an LLM request to the shadow target and record the comparison.
+
+    cost_multiplier matches the primary's billed cost so the cost delta compares like with like.
+    """
+    shadow_target = shadow_config["target"]
+    try:
+        shadow = await handle_llm_proxy(
+            target_name=shadow_target,
+            messages=messages,
+            model=shadow_config.get("model"),
+            max_tokens=max_tokens,
+            temperature=temperature,
+            top_p=top_p,
+            stop=stop,
+            stream=False,
+            idempotency_key=None,
+            cache_ttl=None,
+            targets=_shadow_targets(targets, shadow_target),
+            cache=cache,
+            idempotency=idempotency,
+            request_id=f"{request_id}_shadow",
+            tenant=tenant,
+            key_pool_manager=key_pool_manager,
+            cost_multiplier=cost_multiplier,
+        )
+        comparison = compare_responses("llm", primary, shadow)
+        _record_comparison("llm", request_id, target_name, shadow_target, comparison, tenant)
+        return comparison
+    except Exception as e:
+        logger.warning(f"Shadow request to '{shadow_target}' failed: {e}", exc_info=True)
+        return None
+
+
+async def run_http_shadow(
+    primary: ProxyResult,
+    shadow_config: Dict[str, Any],
+    target_name: str,
+    method: str,
+    path: str,
+    headers: Optional[Dict[str, str]],
+    query: Optional[Dict[str, Any]],
+    targets: Dict[str, Dict],
+    cache: Cache,
+    idempotency: IdempotencyManager,
+    request_id: str,
+    tenant: Optional[str] = None,
+) -> Optional[Dict[str, Any]]:
+    """Mirror a safe HTTP request to the shadow target and record the comparison."""
+    shadow_target = shadow_config["target"]
+    try:
+        shadow = await handle_http_proxy(
+            target_name=shadow_target,
+            method=method,
+            path=path,
+            headers=headers,
+            query=query,
+            body=None,
+            idempotency_key=None,
+            cache_ttl=None,
+            targets=_shadow_targets(targets, shadow_target),
+            cache=cache,
+            idempotency=idempotency,
+            request_id=f"{request_id}_shadow",
+            tenant=tenant,
+        )
+        if isinstance(shadow, HTTPStreamResult):
+            # Streamed (content_types) responses have no envelope to compare
+            await shadow.close()
+            return None
+        comparison = compare_responses("http", primary, shadow)
+        _record_comparison("http", request_id, target_name, shadow_target, comparison, tenant)
+        return comparison
+    except Exception as e:
+        logger.warning(f"Shadow request to '{shadow_target}' failed: {e}", exc_info=True)
+        return None
+
+
+def spawn_shadow(coro) -> None:
+    """Run a shadow coroutine in the background without blocking the response."""
+    task = asyncio.create_task(coro)
+    _shadow_tasks.add(task)
+    task.add_done_callback(_shadow_tasks.discard)
@@ -0,0 +1,108 @@
+"""Startup preflight: one cheap authenticated call per target.
+
+With `warmup.enabled`, each target is called once at boot (GET /models for
+LLM targets, `warmup_path` for others) so a bad API key or base URL shows
+up in the deploy logs and in GET /health instead of on the first real
+request. Targets without a warmup path are skipped.
+"""
+import asyncio
+import logging
+import time
+from typing import Any, Dict, Optional
+
+import httpx
+
+from reliapi.adapters.llm.factory import detect_provider
+from reliapi.app.services import _get_auth_from_key_pool_or_fallback
+from reliapi.core.http_client import UpstreamHTTPClient, outbound_headers
+from reliapi.core.key_pool import KeyPoolManager
+from reliapi.core.request_signing import request_signer
+from reliapi.core.retry import RetryMatrix
+
+logger = logging.getLogger(__name__)
+
+# One attempt per check: a failing check should not hold up startup
+WARMUP_RETRY_MATRIX = {
+    error_class: RetryMatrix(attempts=1) for error_class in ("429", "5xx", "net", "timeout")
+}
+
+
+def warmup_path(target_config: Dict[str, Any]) -> Optional[str]:
+    """Path called by the preflight check, or None to skip the target."""
+    if target_config.get("warmup_path"):
+        return target_config["warmup_path"]
+    return "/models" if target_config.get("llm") is not None else None
+
+
+async def check_target(
+    target_name: str,
+    target_config: Dict[str, Any],
+    key_pool_manager: Optional[KeyPoolManager] = None,
+    timeout_s: float = 5.0,
+    path: Optional[str] = None,
+) -> Dict[str, Any]:
+    """Run the preflight call for one target.
+
+    Args:
+        path: Path to call instead of the target's warmup_path
+
+    Returns:
+        {"status": "ok" | "failed" | "skipped", "path", "reachable", "status_code", "latency_ms", "error"}
+    """
+    path = path or warmup_path(target_config)
+    if not path:
+        return {"status": "skipped"}
+
+    llm_config = target_config.get("llm") or {}
+    provider = llm_config.get("provider") or detect_provider(target_config["base_url"])
+    auth, _, _ = _get_auth_from_key_pool_or_fallback(provider or target_name, key_pool_manager, target_config)
+    # Own circuit breaker: boot checks must not trip the target's shared circuit
+    client = UpstreamHTTPClient(
+        base_url=target_config["base_url"],
+        timeout_s=timeout_s,
+        retry_matrix=WARMUP_RETRY_MATRIX,
+        auth=auth,
+        default_headers=outbound_headers(target_config),
+        signer=request_signer(target_config),
+    )
+    result: Dict[str, Any] = {"path": path}
+    started = time.time()
+    try:
+        response = await client.request("GET", path)
+        result["status_code"] = response.status_code
+    except httpx.HTTPStatusError as e:
+        result["status_code"] = e.response.status_code
+    except Exception as e:
+        result["error"] = str(e) or type(e).__name__
+    finally:
+        await client.close()
+    result["latency_ms"] = int((time.time() - started) * 1000)
+    result["reachable"] = "status_code" in result
+
+    status_code = result.get("status_code")
+    if status_code is not None and 200 <= status_code < 300:
+        result["status"] = "ok"
+    else:
+        result["status"] = "failed"
+        if status_code in (401, 403):
+            result["error"] = f"Credentials rejected (HTTP {status_code})"
+        elif status_code is not None:
+            result["error"] = f"HTTP {status_code}"
+    return result
+
+
+async def run_warmup(
+    targets: Dict[str, Dict[str, Any]],
+    key_pool_manager: Optional[KeyPoolManager] = None,
+    timeout_ms: int = 5000,
+) -> Dict[str, Dict[str, Any]]:
+    """Check every target concurrently; failures are logged as warnings."""
+    names = list(targets)
+    results = await asyncio.gather(*(
+        check_target(name, targets[name], key_pool_manager, timeout_ms / 1000.0) for name in names
+    ))
+    report = dict(zip(names, results))
+    for name, result in report.items():
+        if result["status"] == "failed":
+            logger.warning(f"Warmup check failed for target '{name}' ({result['path']}): {result.get('error')}")
+    return report
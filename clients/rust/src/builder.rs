@@ -0,0 +1,399 @@
+//! Fluent, validated construction of [`LlmRequest`]s.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::types::{LlmRequest, Message, ModelConstraints, Role};
+
+/// Tag limits enforced by the server (see `app/schemas.py`).
+const MAX_TAGS: usize = 10;
+const MAX_TAG_KEY_LENGTH: usize = 64;
+const MAX_TAG_VALUE_LENGTH: usize = 128;
+
+/// logit_bias range enforced by the server.
+const MAX_LOGIT_BIAS: f64 = 100.0;
+
+/// top_logprobs ceiling enforced by the server.
+const MAX_TOP_LOGPROBS: u32 = 20;
+
+/// Serialized metadata size limit enforced by the server.
+const MAX_METADATA_BYTES: usize = 4096;
+
+/// Reasons an [`LlmRequestBuilder`] cannot produce a request.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BuildError {
+    #[error("target is required")]
+    MissingTarget,
+    #[error("at least one message is required")]
+    NoMessages,
+    #[error("unknown message role '{0}' (expected system, user or assistant)")]
+    UnknownRole(String),
+    #[error("temperature must be between 0.0 and 2.0, got {0}")]
+    InvalidTemperature(f64),
+    #[error("top_p must be between 0.0 and 1.0, got {0}")]
+    InvalidTopP(f64),
+    #[error("invalid tags: {0}")]
+    InvalidTags(String),
+    #[error("logit_bias must be between -100 and 100, got {0}")]
+    InvalidLogitBias(f64),
+    #[error("top_logprobs must be between 0 and 20, got {0}")]
+    InvalidTopLogprobs(u32),
+    #[error("logprobs is not supported with stream=true")]
+    LogprobsWithStream,
+    #[error("metadata is {0} bytes serialized (max 4096)")]
+    MetadataTooLarge(usize),
+}
+
+impl FromStr for Role {
+    type Err = BuildError;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role {
+            "system" => Ok(Role::System),
+            "user" => Ok(Role::User),
+            "assistant" => Ok(Role::Assistant),
+            other => Err(BuildError::UnknownRole(other.to_string())),
+        }
+    }
+}
+
+impl LlmRequest {
+    /// Start building a request.
+    ///
+    /// ```
+    /// use reliapi_client::LlmRequest;
+    ///
+    /// let request = LlmRequest::builder()
+    ///     .target("openai")
+    ///     .model("gpt-4o-mini")
+    ///     .system("You are terse.")
+    ///     .user("What is a circuit breaker?")
+    ///     .max_tokens(100)
+    ///     .cache(3600)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(request.messages.len(), 2);
+    /// ```
+    pub fn builder() -> LlmRequestBuilder {
+        LlmRequestBuilder::default()
+    }
+}
+
+/// Builder for [`LlmRequest`]; validation happens in [`build`](Self::build).
+#[derive(Debug, Clone, Default)]
+pub struct LlmRequestBuilder {
+    request: LlmRequest,
+    // First invalid role passed to `role_message`, reported by build()
+    invalid_role: Option<String>,
+}
+
+impl LlmRequestBuilder {
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.request.target = target.into();
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.request.model = Some(model.into());
+        self
+    }
+
+    pub fn message(mut self, message: Message) -> Self {
+        self.request.messages.push(message);
+        self
+    }
+
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.request.messages.extend(messages);
+        self
+    }
+
+    /// Add a message with a role given as a string (validated at build time).
+    pub fn role_message(mut self, role: &str, content: impl Into<String>) -> Self {
+        match role.parse::<Role>() {
+            Ok(role) => self.request.messages.push(Message::new(role, content)),
+            Err(_) => {
+                self.invalid_role.get_or_insert_with(|| role.to_string());
+            }
+        }
+        self
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.message(Message::system(content))
+    }
+
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.message(Message::user(content))
+    }
+
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.message(Message::assistant(content))
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.request.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.request.top_p = Some(top_p);
+        self
+    }
+
+    pub fn stop(mut self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.request.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Number of completions to generate (non-streaming).
+    pub fn n(mut self, n: u32) -> Self {
+        self.request.n = Some(n);
+        self
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.request.stream = stream;
+        self
+    }
+
+    /// Request JSON output (`response_format: {"type": "json_object"}`).
+    pub fn json_mode(mut self) -> Self {
+        self.request.response_format = Some(serde_json::json!({"type": "json_object"}));
+        self
+    }
+
+    /// Require the output to match a JSON Schema (retried or rejected with `SCHEMA_VIOLATION`).
+    pub fn json_schema(mut self, schema: serde_json::Value) -> Self {
+        self.request.json_schema = Some(schema);
+        self
+    }
+
+    /// Bias a token ID by -100..100 (OpenAI targets only).
+    pub fn logit_bias(mut self, token_id: u32, bias: f64) -> Self {
+        self.request
+            .logit_bias
+            .get_or_insert_with(HashMap::new)
+            .insert(token_id.to_string(), bias);
+        self
+    }
+
+    /// Sampling seed for reproducible outputs.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.request.seed = Some(seed);
+        self
+    }
+
+    /// Clean up the returned content: `none`, `trim` or `trim_fences`.
+    pub fn post_process(mut self, mode: impl Into<String>) -> Self {
+        self.request.post_process = Some(mode.into());
+        self
+    }
+
+    /// Also return the unprocessed text as `LlmData::raw_content`.
+    pub fn include_raw_content(mut self) -> Self {
+        self.request.include_raw_content = Some(true);
+        self
+    }
+
+    /// Return token log probabilities (OpenAI targets, non-streaming).
+    pub fn logprobs(mut self) -> Self {
+        self.request.logprobs = Some(true);
+        self
+    }
+
+    /// Return the `n` most likely alternatives per token (implies [`logprobs`](Self::logprobs)).
+    pub fn top_logprobs(mut self, n: u32) -> Self {
+        self.request.logprobs = Some(true);
+        self.request.top_logprobs = Some(n);
+        self
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.request.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Cache TTL in seconds.
+    pub fn cache(mut self, ttl_s: u32) -> Self {
+        self.request.cache = Some(ttl_s);
+        self
+    }
+
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.tags.get_or_insert_with(HashMap::new).insert(key.into(), value.into());
+        self
+    }
+
+    /// Arbitrary JSON echoed back in `Meta::metadata` (e.g. a job ID for callback flows).
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.request.metadata = Some(metadata);
+        self
+    }
+
+    /// Minimum context window for `model: "auto"`.
+    pub fn min_context_tokens(mut self, tokens: u32) -> Self {
+        self.request.constraints.get_or_insert_with(ModelConstraints::default).min_context_tokens = Some(tokens);
+        self
+    }
+
+    /// Required capability (e.g. `"vision"`) for `model: "auto"`.
+    pub fn capability(mut self, capability: impl Into<String>) -> Self {
+        self.request
+            .constraints
+            .get_or_insert_with(ModelConstraints::default)
+            .capabilities
+            .push(capability.into());
+        self
+    }
+
+    /// Validate and produce the serializable request.
+    pub fn build(self) -> Result<LlmRequest, BuildError> {
+        if let Some(role) = self.invalid_role {
+            return Err(BuildError::UnknownRole(role));
+        }
+        let request = self.request;
+        if request.target.trim().is_empty() {
+            return Err(BuildError::MissingTarget);
+        }
+        if request.messages.is_empty() {
+            return Err(BuildError::NoMessages);
+        }
+        if let Some(temperature) = request.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(BuildError::InvalidTemperature(temperature));
+            }
+        }
+        if let Some(top_p) = request.top_p {
+            if !(0.0..=1.0).contains(&top_p) {
+                return Err(BuildError::InvalidTopP(top_p));
+            }
+        }
+        if let Some(tags) = &request.tags {
+            validate_tags(tags)?;
+        }
+        if let Some(logit_bias) = &request.logit_bias {
+            if let Some(&bias) = logit_bias
+                .values()
+                .find(|bias| !(-MAX_LOGIT_BIAS..=MAX_LOGIT_BIAS).contains(*bias))
+            {
+                return Err(BuildError::InvalidLogitBias(bias));
+            }
+        }
+        if let Some(top_logprobs) = request.top_logprobs {
+            if top_logprobs > MAX_TOP_LOGPROBS {
+                return Err(BuildError::InvalidTopLogprobs(top_logprobs));
+            }
+        }
+        if request.logprobs == Some(true) && request.stream {
+            return Err(BuildError::LogprobsWithStream);
+        }
+        if let Some(metadata) = &request.metadata {
+            let size = metadata.to_string().len();
+            if size > MAX_METADATA_BYTES {
+                return Err(BuildError::MetadataTooLarge(size));
+            }
+        }
+        Ok(request)
+    }
+}
+
+fn validate_tags(tags: &HashMap<String, String>) -> Result<(), BuildError> {
+    if tags.len() > MAX_TAGS {
+        return Err(BuildError::InvalidTags(format!("{} tags (max {MAX_TAGS})", tags.len())));
+    }
+    for (key, value) in tags {
+        if key.is_empty() || key.chars().count() > MAX_TAG_KEY_LENGTH {
+            return Err(BuildError::InvalidTags(format!(
+                "key must be 1-{MAX_TAG_KEY_LENGTH} characters: {key:?}"
+            )));
+        }
+        if value.chars().count() > MAX_TAG_VALUE_LENGTH {
+            return Err(BuildError::InvalidTags(format!(
+                "value for {key:?} exceeds {MAX_TAG_VALUE_LENGTH} characters"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_request_in_message_order() {
+        let request = LlmRequest::builder()
+            .target("openai")
+            .model("gpt-4o-mini")
+            .system("Be brief")
+            .user("Hi")
+            .assistant("Hello")
+            .role_message("user", "Bye")
+            .max_tokens(100)
+            .cache(3600)
+            .build()
+            .unwrap();
+
+        let roles: Vec<Role> = request.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [Role::System, Role::User, Role::Assistant, Role::User]);
+        assert_eq!(request.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(request.cache, Some(3600));
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        assert_eq!(LlmRequest::builder().user("Hi").build().unwrap_err(), BuildError::MissingTarget);
+        assert_eq!(LlmRequest::builder().target("openai").build().unwrap_err(), BuildError::NoMessages);
+        assert_eq!(
+            LlmRequest::builder().target("openai").role_message("tool", "x").user("Hi").build().unwrap_err(),
+            BuildError::UnknownRole("tool".into())
+        );
+        assert_eq!(
+            LlmRequest::builder().target("openai").user("Hi").temperature(3.0).build().unwrap_err(),
+            BuildError::InvalidTemperature(3.0)
+        );
+        assert_eq!(
+            LlmRequest::builder().target("openai").user("Hi").logit_bias(50256, -150.0).build().unwrap_err(),
+            BuildError::InvalidLogitBias(-150.0)
+        );
+        assert_eq!(
+            LlmRequest::builder().target("openai").user("Hi").top_logprobs(25).build().unwrap_err(),
+            BuildError::InvalidTopLogprobs(25)
+        );
+        assert_eq!(
+            LlmRequest::builder().target("openai").user("Hi").logprobs().stream(true).build().unwrap_err(),
+            BuildError::LogprobsWithStream
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_metadata() {
+        let request = LlmRequest::builder()
+            .target("openai")
+            .user("Hi")
+            .metadata(serde_json::json!({"job_id": "j-42"}))
+            .build()
+            .unwrap();
+        assert_eq!(request.metadata, Some(serde_json::json!({"job_id": "j-42"})));
+
+        let oversized = serde_json::json!({"blob": "x".repeat(MAX_METADATA_BYTES)});
+        assert!(matches!(
+            LlmRequest::builder().target("openai").user("Hi").metadata(oversized).build(),
+            Err(BuildError::MetadataTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_tags() {
+        let builder = (0..=MAX_TAGS).fold(LlmRequest::builder().target("openai").user("Hi"), |b, i| {
+            b.tag(format!("k{i}"), "v")
+        });
+        assert!(matches!(builder.build(), Err(BuildError::InvalidTags(_))));
+    }
+}
@@ -0,0 +1,516 @@
+//! ReliAPI client and per-endpoint request builders.
+
+use std::collections::HashMap;
+use std::env;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::builder::{BuildError, LlmRequestBuilder};
+use crate::error::{Error, Result};
+use crate::stream::LlmStream;
+use crate::types::{
+    ApiErrorDetail, ErrorEnvelope, HttpData, HttpRequest, LlmData, LlmRequest, Message, Response, TokenUsage,
+};
+
+/// Hosted ReliAPI endpoint, used when `RELIAPI_URL` is not set.
+pub const DEFAULT_BASE_URL: &str = "https://reliapi.kikuai.dev";
+
+/// Client for the ReliAPI proxy endpoints.
+///
+/// Cheap to clone: the underlying connection pool is shared.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: Some(api_key.into()),
+        }
+    }
+
+    /// Build a client from `RELIAPI_URL` and `RELIAPI_API_KEY` (or `RAPIDAPI_KEY`).
+    pub fn from_env() -> Result<Self> {
+        let base_url = env::var("RELIAPI_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let api_key = env::var("RELIAPI_API_KEY")
+            .or_else(|_| env::var("RAPIDAPI_KEY"))
+            .map_err(|_| Error::Config("set RELIAPI_API_KEY or RAPIDAPI_KEY".to_string()))?;
+        Ok(Self::new(base_url, api_key))
+    }
+
+    /// Use a preconfigured `reqwest::Client` (timeouts, proxies, TLS).
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Start an LLM request (`POST /v1/proxy/llm`).
+    pub fn llm(&self, target: impl Into<String>) -> LlmCall<'_> {
+        LlmCall {
+            client: self,
+            builder: LlmRequest::builder().target(target),
+        }
+    }
+
+    /// Start an HTTP proxy request (`POST /v1/proxy/http`).
+    pub fn http(&self, target: impl Into<String>, method: impl Into<String>, path: impl Into<String>) -> HttpCall<'_> {
+        HttpCall {
+            client: self,
+            request: HttpRequest {
+                target: target.into(),
+                method: method.into().to_uppercase(),
+                path: path.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Start an embeddings request, proxied to the target's `/embeddings` endpoint.
+    pub fn embeddings(&self, target: impl Into<String>) -> EmbeddingsCall<'_> {
+        EmbeddingsCall {
+            client: self,
+            target: target.into(),
+            model: None,
+            input: Vec::new(),
+            idempotency_key: None,
+            tags: None,
+        }
+    }
+
+    /// Send a prepared LLM request.
+    pub async fn send_llm(&self, request: &LlmRequest) -> Result<Response<LlmData>> {
+        let response = self.post("/v1/proxy/llm", request).await?;
+        Self::decode(response).await
+    }
+
+    /// Send a prepared LLM request with `stream: true` and return the event stream.
+    pub async fn stream_llm(&self, request: &LlmRequest) -> Result<LlmStream> {
+        let request = LlmRequest {
+            stream: true,
+            ..request.clone()
+        };
+        let response = self.post("/v1/proxy/llm", &request).await?;
+        if !response.status().is_success() {
+            return Err(Self::decode_error(response).await);
+        }
+        Ok(LlmStream::new(response))
+    }
+
+    /// Send a prepared HTTP proxy request.
+    pub async fn send_http(&self, request: &HttpRequest) -> Result<Response<HttpData>> {
+        let response = self.post("/v1/proxy/http", request).await?;
+        Self::decode(response).await
+    }
+
+    async fn post<T: Serialize + ?Sized>(&self, path: &str, body: &T) -> Result<reqwest::Response> {
+        let mut builder = self.http.post(format!("{}{}", self.base_url, path)).json(body);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.header("X-API-Key", api_key);
+        }
+        Ok(builder.send().await?)
+    }
+
+    async fn decode<T: DeserializeOwned>(response: reqwest::Response) -> Result<Response<T>> {
+        if !response.status().is_success() {
+            return Err(Self::decode_error(response).await);
+        }
+        let bytes = response.bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    async fn decode_error(response: reqwest::Response) -> Error {
+        let status = response.status().as_u16();
+        let bytes = match response.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => return Error::Transport(e),
+        };
+        match serde_json::from_slice::<ErrorEnvelope>(&bytes) {
+            Ok(envelope) => Error::from_envelope(status, envelope.error, envelope.meta),
+            // Not a ReliAPI envelope (e.g. gateway error page)
+            Err(_) => Error::from_envelope(
+                status,
+                ApiErrorDetail {
+                    error_type: "http_error".to_string(),
+                    code: if status >= 500 { "SERVER_ERROR" } else { "CLIENT_ERROR" }.to_string(),
+                    message: String::from_utf8_lossy(&bytes).into_owned(),
+                    retryable: status >= 500 || status == 429,
+                    target: None,
+                    status_code: Some(status),
+                    source: None,
+                    retry_after_s: None,
+                    hint: None,
+                    details: None,
+                },
+                None,
+            ),
+        }
+    }
+}
+
+/// LLM request bound to a client, created by [`Client::llm`].
+///
+/// Wraps an [`LlmRequestBuilder`]; the request is validated when sent.
+#[derive(Debug)]
+pub struct LlmCall<'a> {
+    client: &'a Client,
+    builder: LlmRequestBuilder,
+}
+
+impl LlmCall<'_> {
+    fn map(mut self, f: impl FnOnce(LlmRequestBuilder) -> LlmRequestBuilder) -> Self {
+        self.builder = f(self.builder);
+        self
+    }
+
+    pub fn model(self, model: impl Into<String>) -> Self {
+        self.map(|b| b.model(model))
+    }
+
+    pub fn message(self, message: Message) -> Self {
+        self.map(|b| b.message(message))
+    }
+
+    pub fn messages(self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.map(|b| b.messages(messages))
+    }
+
+    pub fn role_message(self, role: &str, content: impl Into<String>) -> Self {
+        self.map(|b| b.role_message(role, content))
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.map(|b| b.system(content))
+    }
+
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.map(|b| b.user(content))
+    }
+
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.map(|b| b.assistant(content))
+    }
+
+    pub fn max_tokens(self, max_tokens: u32) -> Self {
+        self.map(|b| b.max_tokens(max_tokens))
+    }
+
+    pub fn temperature(self, temperature: f64) -> Self {
+        self.map(|b| b.temperature(temperature))
+    }
+
+    pub fn top_p(self, top_p: f64) -> Self {
+        self.map(|b| b.top_p(top_p))
+    }
+
+    pub fn stop(self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.map(|b| b.stop(stop))
+    }
+
+    /// Number of completions to generate (non-streaming).
+    pub fn n(self, n: u32) -> Self {
+        self.map(|b| b.n(n))
+    }
+
+    /// Request JSON output (`response_format: {"type": "json_object"}`).
+    pub fn json_mode(self) -> Self {
+        self.map(|b| b.json_mode())
+    }
+
+    /// Require the output to match a JSON Schema (retried or rejected with `SCHEMA_VIOLATION`).
+    pub fn json_schema(self, schema: serde_json::Value) -> Self {
+        self.map(|b| b.json_schema(schema))
+    }
+
+    /// Bias a token ID by -100..100 (OpenAI targets only).
+    pub fn logit_bias(self, token_id: u32, bias: f64) -> Self {
+        self.map(|b| b.logit_bias(token_id, bias))
+    }
+
+    /// Sampling seed for reproducible outputs.
+    pub fn seed(self, seed: u64) -> Self {
+        self.map(|b| b.seed(seed))
+    }
+
+    /// Clean up the returned content: `none`, `trim` or `trim_fences`.
+    pub fn post_process(self, mode: impl Into<String>) -> Self {
+        self.map(|b| b.post_process(mode))
+    }
+
+    /// Also return the unprocessed text as `LlmData::raw_content`.
+    pub fn include_raw_content(self) -> Self {
+        self.map(|b| b.include_raw_content())
+    }
+
+    /// Return token log probabilities (OpenAI targets, non-streaming).
+    pub fn logprobs(self) -> Self {
+        self.map(|b| b.logprobs())
+    }
+
+    /// Return the `n` most likely alternatives per token.
+    pub fn top_logprobs(self, n: u32) -> Self {
+        self.map(|b| b.top_logprobs(n))
+    }
+
+    pub fn idempotency_key(self, key: impl Into<String>) -> Self {
+        self.map(|b| b.idempotency_key(key))
+    }
+
+    /// Cache TTL in seconds.
+    pub fn cache(self, ttl_s: u32) -> Self {
+        self.map(|b| b.cache(ttl_s))
+    }
+
+    pub fn tag(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.map(|b| b.tag(key, value))
+    }
+
+    /// Minimum context window for `model: "auto"`.
+    pub fn min_context_tokens(self, tokens: u32) -> Self {
+        self.map(|b| b.min_context_tokens(tokens))
+    }
+
+    /// Required capability (e.g. `"vision"`) for `model: "auto"`.
+    pub fn capability(self, capability: impl Into<String>) -> Self {
+        self.map(|b| b.capability(capability))
+    }
+
+    /// Validate and return the request without sending it.
+    pub fn build(self) -> std::result::Result<LlmRequest, BuildError> {
+        self.builder.build()
+    }
+
+    pub async fn send(self) -> Result<Response<LlmData>> {
+        let client = self.client;
+        client.send_llm(&self.builder.build()?).await
+    }
+
+    /// Send with `stream: true` and return a stream of deltas.
+    pub async fn stream(self) -> Result<LlmStream> {
+        let client = self.client;
+        client.stream_llm(&self.builder.build()?).await
+    }
+}
+
+/// Builder for an HTTP proxy request, created by [`Client::http`].
+#[derive(Debug)]
+pub struct HttpCall<'a> {
+    client: &'a Client,
+    request: HttpRequest,
+}
+
+impl HttpCall<'_> {
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.headers.get_or_insert_with(HashMap::new).insert(name.into(), value.into());
+        self
+    }
+
+    pub fn query(mut self, name: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.request.query.get_or_insert_with(HashMap::new).insert(name.into(), value.into());
+        self
+    }
+
+    /// Raw request body (sent as-is upstream).
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.request.body = Some(body.into());
+        self
+    }
+
+    /// Serialize `body` as JSON.
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self> {
+        self.request.body = Some(serde_json::to_string(body)?);
+        Ok(self)
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.request.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Cache TTL in seconds (GET/HEAD only).
+    pub fn cache(mut self, ttl_s: u32) -> Self {
+        self.request.cache = Some(ttl_s);
+        self
+    }
+
+    /// Hedge a slow GET/HEAD or keyed request (`false` opts out of the target's hedging).
+    pub fn hedge(mut self, hedge: bool) -> Self {
+        self.request.hedge = Some(hedge);
+        self
+    }
+
+    /// Preview only: return at most `chars` characters of the response body.
+    pub fn max_response_chars(mut self, chars: u32) -> Self {
+        self.request.max_response_chars = Some(chars);
+        self
+    }
+
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request.tags.get_or_insert_with(HashMap::new).insert(key.into(), value.into());
+        self
+    }
+
+    /// The request that will be sent.
+    pub fn request(&self) -> &HttpRequest {
+        &self.request
+    }
+
+    pub async fn send(self) -> Result<Response<HttpData>> {
+        self.client.send_http(&self.request).await
+    }
+}
+
+/// One embedding vector.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Embedding {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// `data` of an embeddings response (OpenAI-compatible shape).
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbeddingsData {
+    pub data: Vec<Embedding>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Builder for an embeddings request, created by [`Client::embeddings`].
+#[derive(Debug)]
+pub struct EmbeddingsCall<'a> {
+    client: &'a Client,
+    target: String,
+    model: Option<String>,
+    input: Vec<String>,
+    idempotency_key: Option<String>,
+    tags: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize)]
+struct EmbeddingsBody<'b> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'b str>,
+    input: &'b [String],
+}
+
+impl EmbeddingsCall<'_> {
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.input.push(input.into());
+        self
+    }
+
+    pub fn inputs(mut self, inputs: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.input.extend(inputs.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.get_or_insert_with(HashMap::new).insert(key.into(), value.into());
+        self
+    }
+
+    pub async fn send(self) -> Result<Response<EmbeddingsData>> {
+        let body = serde_json::to_string(&EmbeddingsBody {
+            model: self.model.as_deref(),
+            input: &self.input,
+        })?;
+        let request = HttpRequest {
+            target: self.target,
+            method: "POST".to_string(),
+            path: "/embeddings".to_string(),
+            headers: Some(HashMap::from([("Content-Type".to_string(), "application/json".to_string())])),
+            body: Some(body),
+            idempotency_key: self.idempotency_key,
+            tags: self.tags,
+            ..Default::default()
+        };
+        let response = self.client.send_http(&request).await?;
+
+        // Upstream 4xx are passed through as data.status_code
+        if response.data.status_code >= 400 {
+            return Err(Error::from_envelope(
+                response.data.status_code,
+                ApiErrorDetail {
+                    error_type: "upstream_error".to_string(),
+                    code: "CLIENT_ERROR".to_string(),
+                    message: response.data.body.to_string(),
+                    retryable: false,
+                    target: response.meta.target.clone(),
+                    status_code: Some(response.data.status_code),
+                    source: Some("upstream".to_string()),
+                    retry_after_s: None,
+                    hint: None,
+                    details: None,
+                },
+                Some(response.meta),
+            ));
+        }
+        Ok(Response {
+            data: serde_json::from_value(response.data.body)?,
+            meta: response.meta,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Role;
+
+    #[test]
+    fn llm_call_collects_request() {
+        let client = Client::new("http://localhost:8000/", "key");
+        let request = client
+            .llm("openai")
+            .model("gpt-4o-mini")
+            .system("Be brief")
+            .user("Hello")
+            .max_tokens(100)
+            .cache(3600)
+            .tag("team", "growth")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "http://localhost:8000");
+        assert_eq!(request.target, "openai");
+        assert_eq!(request.messages.len(), 2);
+        assert_eq!(request.messages[0].role, Role::System);
+        assert_eq!(request.cache, Some(3600));
+        assert_eq!(request.tags.as_ref().unwrap()["team"], "growth");
+    }
+
+    #[test]
+    fn llm_call_validates_before_sending() {
+        let client = Client::new("http://localhost:8000", "key");
+        assert_eq!(client.llm("openai").build().unwrap_err(), BuildError::NoMessages);
+    }
+
+    #[test]
+    fn http_call_uppercases_method() {
+        let client = Client::new("http://localhost:8000", "key");
+        let call = client.http("my_api", "get", "/users/1").query("page", 2);
+        assert_eq!(call.request().method, "GET");
+        assert_eq!(call.request().query.as_ref().unwrap()["page"], 2);
+    }
+}
@@ -0,0 +1,199 @@
+//! Typed errors mapping the ReliAPI error-code envelope.
+
+use std::time::Duration;
+
+use crate::builder::BuildError;
+use crate::types::{ApiErrorDetail, Meta};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Normalized ReliAPI error codes (`error.code`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorCode {
+    Unauthorized,
+    BadRequest,
+    NotFound,
+    IdempotencyConflict,
+    InvalidIdempotencyKey,
+    DuplicateRequest,
+    StreamAlreadyInProgress,
+    StreamAlreadyCompleted,
+    StreamingUnsupported,
+    UnsupportedParam,
+    ParamNotAllowed,
+    TooManyTurns,
+    RateLimitReliapi,
+    ServerError,
+    ClientError,
+    NetworkError,
+    ProviderError,
+    UpstreamStreamInterrupted,
+    StreamIdleTimeout,
+    SchemaViolation,
+    EmbeddingsMismatch,
+    MaintenanceCacheOnly,
+    TargetDisabled,
+    QueueTimeout,
+    Overloaded,
+    BudgetExceeded,
+    UnknownModel,
+    OutputCapExceeded,
+    InvalidTarget,
+    UnknownProvider,
+    AdapterNotFound,
+    InternalError,
+    PluginError,
+    /// Code not known to this client version.
+    Other(String),
+}
+
+impl ErrorCode {
+    pub fn parse(code: &str) -> Self {
+        match code {
+            "UNAUTHORIZED" => Self::Unauthorized,
+            "BAD_REQUEST" => Self::BadRequest,
+            "NOT_FOUND" => Self::NotFound,
+            "IDEMPOTENCY_CONFLICT" => Self::IdempotencyConflict,
+            "INVALID_IDEMPOTENCY_KEY" => Self::InvalidIdempotencyKey,
+            "DUPLICATE_REQUEST" => Self::DuplicateRequest,
+            "STREAM_ALREADY_IN_PROGRESS" => Self::StreamAlreadyInProgress,
+            "STREAM_ALREADY_COMPLETED" => Self::StreamAlreadyCompleted,
+            "STREAMING_UNSUPPORTED" => Self::StreamingUnsupported,
+            "UNSUPPORTED_PARAM" => Self::UnsupportedParam,
+            "PARAM_NOT_ALLOWED" => Self::ParamNotAllowed,
+            "TOO_MANY_TURNS" => Self::TooManyTurns,
+            "RATE_LIMIT_RELIAPI" => Self::RateLimitReliapi,
+            "SERVER_ERROR" => Self::ServerError,
+            "CLIENT_ERROR" => Self::ClientError,
+            "NETWORK_ERROR" => Self::NetworkError,
+            "PROVIDER_ERROR" => Self::ProviderError,
+            "UPSTREAM_STREAM_INTERRUPTED" => Self::UpstreamStreamInterrupted,
+            "STREAM_IDLE_TIMEOUT" => Self::StreamIdleTimeout,
+            "SCHEMA_VIOLATION" => Self::SchemaViolation,
+            "EMBEDDINGS_MISMATCH" => Self::EmbeddingsMismatch,
+            "MAINTENANCE_CACHE_ONLY" => Self::MaintenanceCacheOnly,
+            "TARGET_DISABLED" => Self::TargetDisabled,
+            "QUEUE_TIMEOUT" => Self::QueueTimeout,
+            "OVERLOADED" => Self::Overloaded,
+            "BUDGET_EXCEEDED" => Self::BudgetExceeded,
+            "UNKNOWN_MODEL" => Self::UnknownModel,
+            "OUTPUT_CAP_EXCEEDED" => Self::OutputCapExceeded,
+            "INVALID_TARGET" => Self::InvalidTarget,
+            "UNKNOWN_PROVIDER" => Self::UnknownProvider,
+            "ADAPTER_NOT_FOUND" => Self::AdapterNotFound,
+            "INTERNAL_ERROR" => Self::InternalError,
+            "PLUGIN_ERROR" => Self::PluginError,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// Errors returned by [`Client`](crate::Client).
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// ReliAPI returned an error envelope.
+    #[error("{code:?} ({status}): {}", .detail.message)]
+    Api {
+        status: u16,
+        code: ErrorCode,
+        detail: Box<ApiErrorDetail>,
+        meta: Option<Box<Meta>>,
+    },
+
+    /// Error event received mid-stream.
+    #[error("stream error {code:?}: {message}")]
+    Stream {
+        code: ErrorCode,
+        message: String,
+        retry_after_s: Option<f64>,
+    },
+
+    /// Transport error talking to ReliAPI.
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// Response body could not be decoded.
+    #[error("decode error: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    /// Request failed validation before being sent.
+    #[error("invalid request: {0}")]
+    InvalidRequest(#[from] BuildError),
+
+    /// Missing or invalid client configuration.
+    #[error("config error: {0}")]
+    Config(String),
+}
+
+impl Error {
+    pub(crate) fn from_envelope(status: u16, detail: ApiErrorDetail, meta: Option<Meta>) -> Self {
+        Error::Api {
+            status,
+            code: ErrorCode::parse(&detail.code),
+            detail: Box::new(detail),
+            meta: meta.map(Box::new),
+        }
+    }
+
+    /// Normalized error code, if the error came from ReliAPI.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            Error::Api { code, .. } | Error::Stream { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the same request may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Api { detail, .. } => detail.retryable,
+            Error::Stream { code, .. } => matches!(
+                code,
+                ErrorCode::UpstreamStreamInterrupted
+                    | ErrorCode::StreamIdleTimeout
+                    | ErrorCode::NetworkError
+                    | ErrorCode::ServerError
+                    | ErrorCode::QueueTimeout
+            ),
+            Error::Transport(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Suggested delay before retrying (rate limit errors).
+    pub fn retry_after(&self) -> Option<Duration> {
+        let retry_after_s = match self {
+            Error::Api { detail, .. } => detail.retry_after_s,
+            Error::Stream { retry_after_s, .. } => *retry_after_s,
+            _ => None,
+        };
+        retry_after_s.map(Duration::from_secs_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorEnvelope;
+
+    #[test]
+    fn maps_error_envelope() {
+        let body = r#"{
+            "success": false,
+            "error": {"type": "rate_limit", "code": "RATE_LIMIT_RELIAPI", "message": "slow down",
+                      "retryable": true, "retry_after_s": 1.5, "status_code": 429},
+            "meta": {"duration_ms": 1, "request_id": "req_1"}
+        }"#;
+        let envelope: ErrorEnvelope = serde_json::from_str(body).unwrap();
+        let error = Error::from_envelope(429, envelope.error, envelope.meta);
+
+        assert_eq!(error.code(), Some(&ErrorCode::RateLimitReliapi));
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn unknown_codes_are_preserved() {
+        assert_eq!(ErrorCode::parse("NEW_CODE"), ErrorCode::Other("NEW_CODE".into()));
+    }
+}
@@ -0,0 +1,40 @@
+//! Typed Rust client for [ReliAPI](https://github.com/KikuAI-Lab/reliapi).
+//!
+//! Wraps the `/proxy/llm` and `/proxy/http` endpoints with typed requests,
+//! responses and errors, so callers don't hand-roll the JSON envelope.
+//!
+//! ```no_run
+//! use reliapi_client::Client;
+//!
+//! # async fn run() -> Result<(), reliapi_client::Error> {
+//! // RELIAPI_URL and RELIAPI_API_KEY (or RAPIDAPI_KEY) from the environment
+//! let client = Client::from_env()?;
+//!
+//! let response = client
+//!     .llm("openai")
+//!     .model("gpt-4o-mini")
+//!     .user("What is idempotency in API design?")
+//!     .max_tokens(100)
+//!     .cache(3600)
+//!     .send()
+//!     .await?;
+//!
+//! println!("{} (cache hit: {})", response.data.content, response.meta.cache_hit);
+//! # Ok(())
+//! # }
+//! ```
+
+mod builder;
+mod client;
+mod error;
+mod stream;
+mod types;
+
+pub use builder::{BuildError, LlmRequestBuilder};
+pub use client::{Client, Embedding, EmbeddingsCall, EmbeddingsData, HttpCall, LlmCall, DEFAULT_BASE_URL};
+pub use error::{Error, ErrorCode, Result};
+pub use stream::{LlmStream, StreamEvent};
+pub use types::{
+    ApiErrorDetail, CostBreakdown, HttpData, HttpRequest, LlmChoice, LlmData, LlmRequest, Message,
+    Meta, ModelConstraints, Response, Role, StreamDone, StreamMeta, TokenUsage,
+};
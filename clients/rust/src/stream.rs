@@ -0,0 +1,225 @@
+//! Server-Sent Events stream of LLM deltas (`stream: true`).
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use serde::Deserialize;
+
+use crate::error::{Error, ErrorCode, Result};
+use crate::types::{StreamDone, StreamMeta};
+
+/// One event from an LLM stream.
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// Request metadata, sent before the first delta.
+    Meta(StreamMeta),
+    /// Incremental content.
+    Delta {
+        content: String,
+        finish_reason: Option<String>,
+    },
+    /// Final usage and cost; the stream ends after this event.
+    Done(StreamDone),
+}
+
+#[derive(Deserialize)]
+struct ChunkData {
+    #[serde(default)]
+    delta: String,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StreamErrorData {
+    code: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    retry_after_s: Option<f64>,
+}
+
+type ByteStream = Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>;
+
+/// Stream of [`StreamEvent`]s parsed from the ReliAPI SSE response.
+///
+/// An `event: error` from the server is yielded as [`Error::Stream`] and ends
+/// the stream.
+pub struct LlmStream {
+    inner: ByteStream,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl LlmStream {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        Self {
+            inner: Box::pin(response.bytes_stream()),
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Take the next complete SSE frame (`event:`/`data:` lines ending in a blank line).
+    ///
+    /// The frame end is found on bytes and only whole frames are decoded, so a multi-byte
+    /// character or a `\r\n` split across network chunks is kept intact.
+    fn next_frame(&mut self) -> Option<String> {
+        let (end, terminator) = self.buffer.iter().enumerate().find_map(|(i, &byte)| {
+            if byte != b'\n' {
+                return None;
+            }
+            match &self.buffer[i + 1..] {
+                [b'\n', ..] => Some((i, 2)),
+                [b'\r', b'\n', ..] => Some((i, 3)),
+                _ => None,
+            }
+        })?;
+        let frame = &self.buffer[..end];
+        let frame = frame.strip_suffix(b"\r").unwrap_or(frame);
+        let frame = String::from_utf8_lossy(frame).replace("\r\n", "\n");
+        self.buffer.drain(..end + terminator);
+        Some(frame)
+    }
+}
+
+/// Parse an SSE frame into an event. Returns `None` for frames to skip.
+fn parse_frame(frame: &str) -> Option<Result<StreamEvent>> {
+    let mut event = "message";
+    let mut data = String::new();
+    for line in frame.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            event = value.trim();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(value.trim_start());
+        }
+    }
+
+    let parsed = match event {
+        "meta" => serde_json::from_str(&data).map(StreamEvent::Meta),
+        "chunk" => serde_json::from_str::<ChunkData>(&data).map(|chunk| StreamEvent::Delta {
+            content: chunk.delta,
+            finish_reason: chunk.finish_reason,
+        }),
+        "done" => serde_json::from_str(&data).map(StreamEvent::Done),
+        "error" => {
+            return Some(match serde_json::from_str::<StreamErrorData>(&data) {
+                Ok(error) => Err(Error::Stream {
+                    code: ErrorCode::parse(&error.code),
+                    message: error.message,
+                    retry_after_s: error.retry_after_s,
+                }),
+                Err(e) => Err(Error::Decode(e)),
+            });
+        }
+        // Comments, heartbeats and unknown events
+        _ => return None,
+    };
+    Some(parsed.map_err(Error::Decode))
+}
+
+impl Stream for LlmStream {
+    type Item = Result<StreamEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.finished {
+                return Poll::Ready(None);
+            }
+            while let Some(frame) = this.next_frame() {
+                if let Some(item) = parse_frame(&frame) {
+                    if matches!(item, Err(_) | Ok(StreamEvent::Done(_))) {
+                        this.finished = true;
+                    }
+                    return Poll::Ready(Some(item));
+                }
+            }
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(bytes))) => this.buffer.extend_from_slice(&bytes),
+                Poll::Ready(Some(Err(e))) => {
+                    this.finished = true;
+                    return Poll::Ready(Some(Err(Error::Transport(e))));
+                }
+                Poll::Ready(None) => {
+                    this.finished = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    fn stream_of(chunks: &[&[u8]]) -> LlmStream {
+        let chunks: Vec<reqwest::Result<Bytes>> =
+            chunks.iter().map(|chunk| Ok(Bytes::copy_from_slice(chunk))).collect();
+        LlmStream {
+            inner: Box::pin(futures_util::stream::iter(chunks)),
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn frames_split_across_chunks_are_decoded_whole() {
+        // "é" is 0xC3 0xA9; the first frame's CRLF blank line is split too
+        let frame = "event: chunk\r\ndata: {\"delta\": \"caf\u{e9}\"}\r\n\r\n".as_bytes();
+        let split = frame.iter().position(|&byte| byte == 0xC3).unwrap() + 1;
+        let crlf = frame.len() - 3;
+        let mut stream = stream_of(&[
+            &frame[..split],
+            &frame[split..crlf],
+            &frame[crlf..],
+            b"event: chunk\ndata: {\"delta\": \"!\"}\n\n",
+        ]);
+
+        let mut deltas = Vec::new();
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(StreamEvent::Delta { content, .. }) => deltas.push(content),
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        assert_eq!(deltas, ["caf\u{e9}", "!"]);
+    }
+
+    #[test]
+    fn parses_chunk_and_done_frames() {
+        let delta = parse_frame("event: chunk\ndata: {\"delta\": \"Hel\", \"finish_reason\": null}");
+        match delta {
+            Some(Ok(StreamEvent::Delta { content, .. })) => assert_eq!(content, "Hel"),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let done = parse_frame(
+            "event: done\ndata: {\"finish_reason\": \"stop\", \"usage\": {\"prompt_tokens\": 1, \
+             \"completion_tokens\": 2, \"total_tokens\": 3}, \"cost_usd\": 0.001}",
+        );
+        match done {
+            Some(Ok(StreamEvent::Done(done))) => assert_eq!(done.usage.unwrap().total_tokens, 3),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn error_frame_maps_code() {
+        let error = parse_frame("event: error\ndata: {\"code\": \"BUDGET_EXCEEDED\", \"message\": \"cap\"}");
+        match error {
+            Some(Err(e)) => assert_eq!(e.code(), Some(&ErrorCode::BudgetExceeded)),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(parse_frame(": heartbeat").is_none());
+    }
+}
@@ -0,0 +1,431 @@
+//! Request and response types mirroring the ReliAPI JSON schemas.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// LLM message role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// A single chat message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+}
+
+/// Body of `POST /proxy/llm`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LlmRequest {
+    pub target: String,
+    pub messages: Vec<Message>,
+    /// System prompt; the server prepends it ahead of any system messages in `messages`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Number of completions (non-streaming; see `LlmData::choices`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<HashMap<String, String>>,
+    /// Requirements for `model: "auto"` selection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<ModelConstraints>,
+    /// OpenAI-style response format, e.g. `{"type": "json_object"}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
+    /// JSON Schema the completion text must match (non-streaming, see `llm.output_schema`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<serde_json::Value>,
+    /// Token ID -> bias (-100..100). OpenAI targets only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<String, f64>>,
+    /// Sampling seed (part of the cache key; does not make temperature > 0 cacheable).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Return token log probabilities (OpenAI targets, non-streaming).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    /// Alternatives returned per token (0-20, requires `logprobs`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+    /// Echoed back verbatim in `Meta::metadata` (max 4 KB serialized, not part of the cache key).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Cleanup of the returned content: `none`, `trim` or `trim_fences`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_process: Option<String>,
+    /// Also return the unprocessed text as `LlmData::raw_content`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_raw_content: Option<bool>,
+}
+
+/// Capability requirements for `model: "auto"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ModelConstraints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+}
+
+/// Body of `POST /proxy/http`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HttpRequest {
+    pub target: String,
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<HashMap<String, serde_json::Value>>,
+    /// Request body as a JSON string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<u32>,
+    /// Hedge a slow GET/HEAD or keyed request; `None` uses the target's hedging config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hedge: Option<bool>,
+    /// Return at most this many characters of the body (the cached copy stays complete).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_chars: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<HashMap<String, String>>,
+    /// Echoed back verbatim in `Meta::metadata` (max 4 KB serialized, not part of the cache key).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Successful response envelope (`success: true`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response<T> {
+    pub data: T,
+    pub meta: Meta,
+}
+
+/// Response metadata.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Meta {
+    pub target: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub cache_hit: bool,
+    /// Served from a pinned (never-expiring) entry.
+    pub pinned: Option<bool>,
+    pub idempotent_hit: bool,
+    pub deduplicated: Option<bool>,
+    /// Stream aborted at `llm.stream_cost_ceiling_usd` (output cut off).
+    pub cost_ceiling_reached: Option<bool>,
+    /// `data.body` was shortened to `max_response_chars`.
+    pub truncated: Option<bool>,
+    /// Model chosen for `model: "auto"`.
+    pub auto_selected_model: Option<String>,
+    /// Input language detected for `llm.language_routing` (ISO 639-1).
+    pub detected_language: Option<String>,
+    /// Model chosen by `llm.language_routing` for the detected language.
+    pub language_selected_model: Option<String>,
+    /// Content was changed by `post_process`.
+    pub post_processed: Option<bool>,
+    /// Request log entry this response replays (`POST /replay`).
+    pub replay_of: Option<String>,
+    /// Request log entry of this request, to pass to `POST /replay`.
+    pub request_log_id: Option<String>,
+    pub retries: u32,
+    pub duration_ms: u64,
+    /// Time spent in provider calls, 0 on cache hits (`duration_ms` = upstream + queue + overhead).
+    pub upstream_ms: Option<u64>,
+    /// Time waiting on rate and concurrency limits.
+    pub queue_ms: Option<u64>,
+    /// Proxy processing time (cache, tokenization, serialization).
+    pub overhead_ms: Option<u64>,
+    pub request_id: String,
+    /// Provider's request ID (e.g. OpenAI `x-request-id`) for support tickets.
+    pub upstream_request_id: Option<String>,
+    /// Regional endpoint that served the request (targets with `regions`).
+    pub region: Option<String>,
+    /// A hedge (second identical upstream call) was sent.
+    pub hedged: Option<bool>,
+    /// Attempt whose response was used when hedged: 1 original, 2 hedge.
+    pub hedge_winner: Option<u8>,
+    /// Provider timeout computed from `max_tokens` (`llm.timeout_scaling`).
+    pub timeout_ms: Option<u64>,
+    /// Older turns were replaced with a summary (`llm.summarization`).
+    pub history_summarized: Option<bool>,
+    /// Estimated prompt tokens saved by the summary (its cost is in `cost_usd`).
+    pub history_tokens_saved: Option<u32>,
+    pub trace_id: Option<String>,
+    pub cost_usd: Option<f64>,
+    /// `cost_usd` split into input and output cost.
+    pub cost_breakdown: Option<CostBreakdown>,
+    pub cost_estimate_usd: Option<f64>,
+    /// Cost is a chars/4 estimate (model has no pricing; `on_unknown_model: estimate_chars`).
+    pub cost_approximate: Option<bool>,
+    pub cost_policy_applied: Option<String>,
+    /// Per-model output cap `max_tokens` was clamped to (`llm.output_caps`).
+    pub output_cap_tokens: Option<u32>,
+    /// Prompt tokens served from the provider's prompt cache.
+    pub cached_prompt_tokens: Option<u32>,
+    /// `"provider"` (reported usage) or `"estimated"` (local chars/4).
+    pub usage_source: Option<String>,
+    /// Effective `model`/`max_tokens`/`cache` after tenant defaults; `inherited` lists the defaulted names.
+    pub resolved_params: Option<serde_json::Value>,
+    /// The request's `metadata`, echoed back verbatim.
+    pub metadata: Option<serde_json::Value>,
+    /// Response was not cached because `temperature > 0` and the target does not set `cache_nondeterministic`.
+    pub cache_skipped_nondeterministic: Option<bool>,
+    /// TTL in seconds of the cache entry this response was stored under (after `cache.ttl_jitter`).
+    pub cache_ttl_s: Option<u64>,
+    /// Cache hits with `ttl_on_hit: extend`: seconds until the entry expires, after this hit.
+    pub cache_expires_in_s: Option<u64>,
+    /// HTTP cache hits: seconds since the response was generated upstream (`Age` header).
+    pub cache_age_s: Option<u64>,
+    /// HTTP cache hit older than the upstream `Cache-Control` max-age.
+    pub cache_stale: Option<bool>,
+    /// Cache hit past the entry's TTL, served while the target is unhealthy (`cache.health_ttl`).
+    pub cache_ttl_extended: Option<bool>,
+    /// Response was not cached because it exceeds `max_cache_value_bytes`.
+    pub cache_skipped_too_large: Option<bool>,
+    /// Batch embeddings: inputs served from the per-input cache (`cache.embeddings`).
+    pub cache_hits: Option<u32>,
+    /// Batch embeddings: inputs sent upstream; usage and cost cover only these.
+    pub cache_misses: Option<u32>,
+    /// Malformed JSON-mode output was repaired (`llm.json_repair`).
+    pub json_repaired: Option<bool>,
+    /// Retries after empty completions (`llm.empty_response`).
+    pub empty_retries: Option<u32>,
+    /// Requests with `json_schema`: the returned output matches the schema.
+    pub schema_valid: Option<bool>,
+    /// Corrective retries needed for a schema-valid output.
+    pub schema_retries: Option<u32>,
+    /// Completions requested (`n > 1` only).
+    pub requested_n: Option<u32>,
+    /// Completions returned; less than `requested_n` if the provider returned fewer.
+    pub returned_n: Option<u32>,
+    /// Model whose response the content filter blocked; `model` is the retry
+    /// (`llm.on_content_filter_model`).
+    pub content_filter_original_model: Option<String>,
+}
+
+/// Split of `cost_usd` by token type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct CostBreakdown {
+    pub input_usd: f64,
+    pub output_usd: f64,
+}
+
+/// Token usage statistics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// `data` of a successful LLM response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LlmData {
+    pub content: String,
+    pub model: String,
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Returned completions, same shape for every provider (`content` mirrors the first one).
+    #[serde(default)]
+    pub choices: Vec<LlmChoice>,
+    /// Raw provider response (`llm.normalization.include_raw`).
+    #[serde(default)]
+    pub raw: Option<serde_json::Value>,
+    /// Token log probabilities of the first choice (`logprobs: true`).
+    #[serde(default)]
+    pub logprobs: Option<serde_json::Value>,
+    /// Content before `post_process` (`include_raw_content`).
+    #[serde(default)]
+    pub raw_content: Option<String>,
+}
+
+/// One normalized completion.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LlmChoice {
+    pub index: u32,
+    pub message: Message,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Provider `logprobs` object, as returned (`logprobs: true`).
+    #[serde(default)]
+    pub logprobs: Option<serde_json::Value>,
+}
+
+/// `data` of a successful HTTP proxy response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpData {
+    pub status_code: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Upstream body (parsed JSON, or a string for non-JSON bodies).
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
+/// `error` of an error response envelope (`success: false`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiErrorDetail {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub code: String,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub status_code: Option<u16>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub retry_after_s: Option<f64>,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(default)]
+    pub details: Option<serde_json::Value>,
+}
+
+/// `meta` event at the start of an LLM stream.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StreamMeta {
+    pub target: Option<String>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub request_id: String,
+    /// Regional endpoint the stream goes to (targets with `regions`).
+    pub region: Option<String>,
+    pub cost_estimate_usd: Option<f64>,
+    pub cost_policy_applied: Option<String>,
+    /// Per-model output cap `max_tokens` was clamped to (`llm.output_caps`).
+    pub output_cap_tokens: Option<u32>,
+    /// Served from the cache: the whole completion follows as a single chunk.
+    pub cache_hit: Option<bool>,
+    /// Replay of a completed stream with the same idempotency key (a single chunk).
+    pub idempotent_hit: Option<bool>,
+    /// Provider timeout computed from `max_tokens` (`llm.timeout_scaling`).
+    pub timeout_ms: Option<u64>,
+    /// Older turns were replaced with a summary (`llm.summarization`).
+    pub history_summarized: Option<bool>,
+    /// Estimated prompt tokens saved by the summary.
+    pub history_tokens_saved: Option<u32>,
+    /// The request's `metadata`, echoed back verbatim.
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// `done` event at the end of an LLM stream.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StreamDone {
+    pub finish_reason: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub cached_prompt_tokens: Option<u32>,
+    /// `"provider"` (reported usage) or `"estimated"` (local chars/4).
+    pub usage_source: Option<String>,
+    /// Actual cost.
+    pub cost_usd: Option<f64>,
+    /// `cost_usd` split into input and output cost.
+    pub cost_breakdown: Option<CostBreakdown>,
+    /// Upper-bound estimate sent at stream start.
+    pub cost_estimate_usd: Option<f64>,
+    /// Replayed from the cache.
+    pub cache_hit: Option<bool>,
+    /// Replayed from an earlier stream with the same idempotency key.
+    pub idempotent_hit: Option<bool>,
+    /// Upstream stream attempts retried (`llm.stream_retry`).
+    pub retries: Option<u32>,
+    /// A retry restarted after partial output; the text already sent was skipped.
+    pub stream_restarted: Option<bool>,
+    /// The request's `metadata`, echoed back verbatim.
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ErrorEnvelope {
+    pub error: ApiErrorDetail,
+    #[serde(default)]
+    pub meta: Option<Meta>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn llm_request_skips_unset_fields() {
+        let request = LlmRequest {
+            target: "openai".into(),
+            messages: vec![Message::user("Hello")],
+            max_tokens: Some(100),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["messages"][0]["role"], "user");
+        assert_eq!(json["max_tokens"], 100);
+        assert!(json.get("model").is_none());
+    }
+
+    #[test]
+    fn response_deserializes_llm_envelope() {
+        let body = r#"{
+            "success": true,
+            "data": {"content": "Hi", "model": "gpt-4o-mini",
+                     "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}},
+            "meta": {"cache_hit": true, "idempotent_hit": false, "retries": 0,
+                     "duration_ms": 12, "request_id": "req_1", "cost_usd": 0.0001}
+        }"#;
+        let response: Response<LlmData> = serde_json::from_str(body).unwrap();
+        assert_eq!(response.data.content, "Hi");
+        assert_eq!(response.data.usage.unwrap().total_tokens, 4);
+        assert!(response.meta.cache_hit);
+    }
+}
@@ -85,6 +85,62 @@ class ConfigLoader:
         """Get provider key pools configuration."""
         return self.config.get("provider_key_pools")
 
+    def get_retry_budget(self) -> Optional[Dict[str, Any]]:
+        """Get global retry budget configuration."""
+        return self.config.get("retry_budget")
+
+    def get_cache_compression(self) -> Optional[Dict[str, Any]]:
+        """Get cache compression configuration."""
+        return self.config.get("cache_compression")
+
+    def get_cache_memory(self) -> Optional[Dict[str, Any]]:
+        """Get in-memory cache tier configuration."""
+        return self.config.get("cache_memory")
+
+    def get_cache_write_retry(self) -> Optional[Dict[str, Any]]:
+        """Get cache write retry configuration."""
+        return self.config.get("cache_write_retry")
+
+    def get_cache_key_version(self) -> int:
+        """Get the version mixed into cache keys (0 = unversioned)."""
+        return self.config.get("cache_key_version", 0)
+
+    def get_max_cache_value_bytes(self) -> int:
+        """Get the largest value size stored in the cache."""
+        return self.config.get("max_cache_value_bytes", 1024 * 1024)
+
+    def get_warmup(self) -> Optional[Dict[str, Any]]:
+        """Get startup warmup configuration."""
+        return self.config.get("warmup")
+
+    def get_debug_trace(self) -> Optional[Dict[str, Any]]:
+        """Get debug trace buffer configuration."""
+        return self.config.get("debug_trace")
+
+    def get_stream_tee(self) -> Optional[Dict[str, Any]]:
+        """Get stream tee (analytics sink) configuration."""
+        return self.config.get("stream_tee")
+
+    def get_usage_export(self) -> Optional[Dict[str, Any]]:
+        """Get usage export (analytics webhook) configuration."""
+        return self.config.get("usage_export")
+
+    def get_idempotency_scope(self) -> str:
+        """Get idempotency record scope ("per_key" or "global")."""
+        return self.config.get("idempotency_scope", "per_key")
+
+    def get_idempotency_keys(self) -> Dict[str, Any]:
+        """Get idempotency key validation rules."""
+        return self.config.get("idempotency_keys") or {}
+
+    def get_request_overrides(self) -> Optional[Dict[str, Any]]:
+        """Get per-request override limits configuration."""
+        return self.config.get("request_overrides")
+
+    def get_streaming(self) -> Dict[str, Any]:
+        """Get streamed response delivery configuration."""
+        return self.config.get("streaming") or {}
+
     def get_client_profiles(self) -> Optional[Dict[str, Any]]:
         """Get client profiles configuration."""
         return self.config.get("client_profiles")
@@ -1,7 +1,8 @@
 """Pydantic schemas for ReliAPI configuration validation."""
-from typing import Dict, List, Optional
+import re
+from typing import Any, Dict, List, Literal, Optional
 
-from pydantic import BaseModel, Field, field_validator
+from pydantic import BaseModel, Field, field_validator, model_validator
 
 
 class CircuitConfig(BaseModel):
@@ -9,6 +10,55 @@ class CircuitConfig(BaseModel):
     
     error_threshold: int = Field(default=5, gt=0, description="Number of failures before opening circuit")
     cooldown_s: int = Field(default=60, gt=0, description="Seconds before attempting to close circuit")
+    half_open_max_calls: int = Field(
+        default=1, gt=0, description="Probe requests allowed through at a time while half-open"
+    )
+    success_threshold: int = Field(
+        default=1, gt=0, description="Consecutive probe successes needed to close the circuit"
+    )
+    decay_half_life_s: Optional[float] = Field(
+        default=None, gt=0,
+        description="Weight failures and successes by age (halved every decay_half_life_s) instead of counting consecutive failures"
+    )
+    failure_rate_threshold: float = Field(
+        default=0.5, gt=0.0, le=1.0,
+        description="With decay_half_life_s: decayed failure rate that must also be reached to open the circuit"
+    )
+
+
+class TTLCostPolicyConfig(BaseModel):
+    """LLM cache TTL scaled by model price: ttl_s * (price / reference_usd_per_1k) ** exponent."""
+    
+    reference_usd_per_1k: float = Field(
+        default=0.01, gt=0.0, description="Price (prompt + completion per 1K tokens) that gets exactly ttl_s"
+    )
+    exponent: float = Field(
+        default=0.5, ge=-2.0, le=2.0,
+        description="> 0 caches expensive models longer, < 0 caches cheap models longer, 0 disables scaling"
+    )
+    min_ttl_s: int = Field(default=60, gt=0, description="Lower bound of the computed TTL")
+    max_ttl_s: Optional[int] = Field(default=None, gt=0, description="Upper bound of the computed TTL")
+    
+    @model_validator(mode="after")
+    def validate_bounds(self):
+        if self.max_ttl_s is not None and self.max_ttl_s < self.min_ttl_s:
+            raise ValueError("ttl_cost_policy.max_ttl_s must be at least min_ttl_s")
+        return self
+
+
+class HealthTTLConfig(BaseModel):
+    """Longer cache lifetime while the target is unhealthy: entries are kept ttl * factor, served past ttl only then."""
+    
+    factor: float = Field(default=3.0, gt=1.0, le=100.0, description="Effective TTL multiplier while a trigger is active")
+    triggers: List[Literal["circuit_open", "circuit_half_open", "elevated_errors"]] = Field(
+        default_factory=lambda: ["circuit_open", "circuit_half_open"],
+        min_length=1,
+        description=(
+            "Conditions that extend the TTL: the breaker is open or half-open, or it is closed "
+            "but counts at least min_failures recent failures (elevated_errors)"
+        )
+    )
+    min_failures: int = Field(default=2, ge=1, description="Recent breaker failures that count as elevated_errors")
 
 
 class CacheConfig(BaseModel):
@@ -16,17 +66,501 @@ class CacheConfig(BaseModel):
     
     enabled: bool = Field(default=True, description="Enable caching")
     ttl_s: int = Field(default=3600, gt=0, description="Time to live in seconds")
+    cache_nondeterministic: bool = Field(
+        default=False,
+        description="Cache LLM responses with temperature > 0 (off: sampled outputs are never served from cache)"
+    )
+    negative_statuses: List[int] = Field(
+        default_factory=list,
+        description="Upstream error statuses to cache (negative caching, e.g. [404]). Empty disables it"
+    )
+    negative_ttl_s: int = Field(default=30, gt=0, description="Time to live for cached error responses in seconds")
+    negative_max_body_bytes: int = Field(
+        default=65536,
+        gt=0,
+        description="Largest error body kept by negative caching; errors with larger bodies are not cached"
+    )
+    respect_upstream_cache_control: bool = Field(
+        default=False,
+        description=(
+            "HTTP targets: take the TTL from the upstream Cache-Control max-age/s-maxage and skip "
+            "caching on no-store/no-cache/private (a request-level cache value still wins)"
+        )
+    )
+    ttl_jitter: float = Field(
+        default=0.05,
+        ge=0,
+        le=0.5,
+        description="Spread stored TTLs randomly by +/- this fraction so entries written together expire apart (0 disables)"
+    )
+    embeddings: bool = Field(
+        default=False,
+        description=(
+            "HTTP targets: cache POST .../embeddings results per input, so a batch only sends the "
+            "inputs that miss upstream and cached results are merged back in input order"
+        )
+    )
+    ttl_on_hit: Literal["fixed", "extend"] = Field(
+        default="fixed",
+        description="extend: a hit past an entry's half-life doubles its lifetime, up to max_ttl_s after it was cached"
+    )
+    max_ttl_s: Optional[int] = Field(
+        default=None, gt=0, description="Ceiling for ttl_on_hit extend: entries expire at most this long after the write"
+    )
+    methods: List[Literal["GET", "HEAD", "POST"]] = Field(
+        default_factory=lambda: ["GET", "HEAD"],
+        description=(
+            "HTTP targets: methods whose responses are cached. Add POST for read-only endpoints (search, "
+            "GraphQL queries); POST is then cached only for requests that set their own cache TTL"
+        )
+    )
+    vary_headers: List[str] = Field(
+        default_factory=list,
+        description=(
+            "HTTP targets: request headers the upstream varies responses by (e.g., Accept-Language, X-Region); "
+            "each distinct value gets its own cache entry. Matched case-insensitively"
+        )
+    )
+    ttl_cost_policy: Optional[TTLCostPolicyConfig] = Field(
+        default=None,
+        description="LLM targets: derive the TTL of each response from its model's price instead of a flat ttl_s"
+    )
+    canonical_json_body: bool = Field(
+        default=False,
+        description=(
+            "HTTP targets: hash JSON request bodies in canonical form for cache keys and idempotency, "
+            "so key order, whitespace and number formatting do not split entries (the upstream gets the body as sent)"
+        )
+    )
+    health_ttl: Optional[HealthTTLConfig] = Field(
+        default=None,
+        description="Keep serving cached entries past their TTL while the target's circuit is open or erroring"
+    )
+
+    @model_validator(mode="after")
+    def validate_ttl_on_hit(self):
+        if self.ttl_on_hit == "extend" and (self.max_ttl_s is None or self.max_ttl_s < self.ttl_s):
+            raise ValueError("ttl_on_hit 'extend' requires max_ttl_s >= ttl_s")
+        if self.ttl_on_hit == "extend" and self.health_ttl is not None:
+            raise ValueError("health_ttl cannot be combined with ttl_on_hit 'extend'")
+        return self
+
+    @field_validator("negative_statuses")
+    @classmethod
+    def validate_negative_statuses(cls, v: List[int]) -> List[int]:
+        """Only 4xx/5xx statuses can be negatively cached."""
+        for status in v:
+            if status < 400 or status > 599:
+                raise ValueError(f"negative_statuses must be 4xx/5xx, got {status}")
+        return v
+
+
+class WarmupConfig(BaseModel):
+    """Startup preflight call per target to validate credentials."""
+    
+    enabled: bool = Field(default=False, description="Call every target once at startup (GET /models for LLM targets, warmup_path for others)")
+    fail_fast: bool = Field(default=False, description="Refuse to start if a check fails (default: log a warning and keep serving)")
+    timeout_ms: int = Field(default=5000, gt=0, le=60000, description="Timeout for each check")
+
+
+class DebugTraceConfig(BaseModel):
+    """In-memory buffer of recent request traces, read via admin-only GET /debug/recent."""
+    
+    enabled: bool = Field(default=False, description="Keep the last `size` request traces in process memory")
+    size: int = Field(default=200, ge=1, le=10000, description="Maximum traces kept (oldest dropped first)")
+    sample_rate: float = Field(default=1.0, ge=0.0, le=1.0, description="Fraction of requests traced")
+    redact: List[Literal["tenant", "key_id", "tags", "metadata", "error_message"]] = Field(
+        default_factory=lambda: ["metadata", "error_message"],
+        description="Trace fields replaced with '[redacted]' (prompts and bodies are never traced)"
+    )
+
+
+class StreamTeeConfig(BaseModel):
+    """Copy of each completed LLM stream (redacted) to an analytics sink, off the client path."""
+    
+    enabled: bool = Field(default=False, description="Queue a record of every completed LLM stream for the sink")
+    sink: Literal["file", "webhook", "usage"] = Field(
+        default="file",
+        description="file: append JSON lines to path; webhook: POST each record to url; usage: keep in Redis next to the usage records",
+    )
+    path: Optional[str] = Field(default=None, description="JSON-lines file for the file sink")
+    url: Optional[str] = Field(default=None, description="Endpoint for the webhook sink")
+    headers: Optional[Dict[str, str]] = Field(default=None, description="Extra webhook request headers (e.g., Authorization)")
+    include_messages: bool = Field(default=False, description="Also record the request messages (redacted)")
+    redact_patterns: Optional[List[str]] = Field(
+        default=None,
+        description="Regexes replaced with '[redacted]' in content and messages (default: emails and long digit runs; [] disables)"
+    )
+    max_queue: int = Field(default=1000, ge=1, description="Records waiting for the sink before new ones are dropped")
+    timeout_s: float = Field(default=5.0, gt=0, description="Webhook request timeout")
+    
+    @model_validator(mode="after")
+    def validate_sink(self):
+        if self.sink == "file" and not self.path:
+            raise ValueError("stream_tee.sink 'file' needs path")
+        if self.sink == "webhook" and not self.url:
+            raise ValueError("stream_tee.sink 'webhook' needs url")
+        for pattern in self.redact_patterns or []:
+            re.compile(pattern)
+        return self
+
+
+class UsageExportConfig(BaseModel):
+    """Per-request usage records sent in signed batches to an analytics webhook, off the request path."""
+    
+    enabled: bool = Field(default=False, description="Queue every usage record for the webhook")
+    url: Optional[str] = Field(default=None, description="Endpoint receiving POSTed batches ({\"records\": [...]})")
+    secret_env: Optional[str] = Field(
+        default=None, description="Environment variable with the HMAC-SHA256 secret signing each batch (unsigned if unset)"
+    )
+    headers: Optional[Dict[str, str]] = Field(default=None, description="Extra webhook request headers (e.g., Authorization)")
+    batch_size: int = Field(default=100, ge=1, le=10000, description="Records per batch at most")
+    flush_interval_s: float = Field(default=5.0, gt=0, description="Longest a record waits for its batch to fill")
+    attempts: int = Field(default=3, ge=1, le=10, description="Delivery attempts per batch (timeouts, 429 and 5xx are retried)")
+    backoff_ms: int = Field(default=500, ge=0, description="Delay before retry N is backoff_ms * 2^(N-1)")
+    max_queue: int = Field(default=10000, ge=1, description="Records waiting for delivery before new ones are dropped")
+    timeout_s: float = Field(default=5.0, gt=0, description="Webhook request timeout")
+    redact: List[Literal["tenant", "request_id", "tags"]] = Field(
+        default_factory=list,
+        description="Record fields replaced with '[redacted]' (tag values are always scrubbed of emails and long digit runs)"
+    )
+    close_timeout_s: float = Field(
+        default=10.0, gt=0, description="Longest shutdown waits for queued records; the rest are dropped"
+    )
+    
+    @model_validator(mode="after")
+    def validate_url(self):
+        if self.enabled and not self.url:
+            raise ValueError("usage_export needs url")
+        return self
+
+
+class MemoryCacheConfig(BaseModel):
+    """In-process cache tier in front of Redis."""
+    
+    enabled: bool = Field(default=False, description="Keep recent cache entries in process memory")
+    max_entries: int = Field(default=10000, gt=0, description="Maximum entries held in memory")
+    max_bytes: int = Field(default=64 * 1024 * 1024, gt=0, description="Maximum total size of entries held in memory")
+    eviction: Literal["lru", "cost_weighted"] = Field(
+        default="lru",
+        description="lru: evict least recently used; cost_weighted: evict lowest cost_usd per byte first"
+    )
+
+
+class CacheWriteRetryConfig(BaseModel):
+    """Background retry of Redis cache writes that hit a transient error."""
+    
+    enabled: bool = Field(default=False, description="Retry failed cache writes in the background")
+    attempts: int = Field(default=3, ge=1, le=10, description="Retries per write before it is given up")
+    backoff_ms: int = Field(default=100, ge=1, description="Delay before the first retry; doubles on each attempt")
+    max_pending: int = Field(default=1000, gt=0, description="Writes waiting for a retry at once; further failed writes are dropped")
+
+
+class CacheCompressionConfig(BaseModel):
+    """Compression of cached values in Redis (transparent to clients)."""
+    
+    enabled: bool = Field(default=False, description="Compress cached values with zlib before storing")
+    min_size_bytes: int = Field(default=1024, ge=0, description="Only compress values at least this large (serialized JSON)")
+    level: int = Field(default=6, ge=1, le=9, description="zlib compression level (1 fastest, 9 smallest)")
+
+
+class RequestOverridesConfig(BaseModel):
+    """Ceilings for per-request X-ReliAPI-Timeout-Ms / X-ReliAPI-Max-Retries overrides."""
+    
+    enabled: bool = Field(default=True, description="Honor per-request override headers")
+    max_timeout_ms: int = Field(default=120000, gt=0, description="Timeouts above this are clamped")
+    max_retries: int = Field(default=5, ge=0, description="Retry counts above this are clamped")
+
+
+class StreamingConfig(BaseModel):
+    """Streamed responses through buffering reverse proxies and load balancers."""
+    
+    disable_proxy_buffering: bool = Field(
+        default=False, description="Send X-Accel-Buffering: no on streamed responses (nginx and compatible proxies)"
+    )
+    heartbeat_interval_s: Optional[float] = Field(
+        default=None, gt=0, description="Send a ':keep-alive' SSE comment on LLM streams idle this long"
+    )
+    collapse: bool = Field(
+        default=False,
+        description="Answer every stream=true LLM request with one buffered JSON response (still streamed from the provider)",
+    )
+
+
+class IdempotencyKeyConfig(BaseModel):
+    """Validation rules for client idempotency keys (invalid keys get 400 INVALID_IDEMPOTENCY_KEY)."""
+    
+    min_length: int = Field(default=1, ge=1, description="Minimum key length (raise it to reject guessable keys)")
+    max_length: int = Field(default=256, ge=1, description="Maximum key length")
+    pattern: Optional[str] = Field(
+        default=None,
+        description="Regular expression the whole key must match (e.g., '[A-Za-z0-9_-]+')"
+    )
+    require_uuid: bool = Field(default=False, description="Only accept UUIDs (8-4-4-4-12 hex, e.g. a client-generated UUIDv4)")
+    
+    @model_validator(mode="after")
+    def validate_rules(self):
+        if self.min_length > self.max_length:
+            raise ValueError("min_length must not exceed max_length")
+        if self.pattern is not None:
+            try:
+                re.compile(self.pattern)
+            except re.error as e:
+                raise ValueError(f"Invalid pattern '{self.pattern}': {e}")
+        return self
+
+
+class ModelLimitConfig(BaseModel):
+    """Per-model rate caps matching the provider's tier limits."""
+    
+    rpm: Optional[int] = Field(default=None, gt=0, description="Maximum requests per minute")
+    tpm: Optional[int] = Field(default=None, gt=0, description="Maximum tokens per minute (estimated up front, reconciled with actual usage)")
+    on_limit: Literal["reject", "delay"] = Field(default="reject", description="Reject with 429 or delay the request when a limit is hit")
+    max_delay_ms: int = Field(default=5000, ge=0, description="Maximum time to delay a request before rejecting (on_limit: delay)")
+
+
+class OutputCapConfig(BaseModel):
+    """Hard per-model ceiling on output tokens, independent of the client's max_tokens."""
+    
+    max_tokens: int = Field(..., gt=0, description="Maximum output tokens per request (per completion with n)")
+    on_exceed: Literal["clamp", "reject"] = Field(
+        default="clamp",
+        description="Clamp a larger max_tokens to the cap, or reject it with 422 OUTPUT_CAP_EXCEEDED"
+    )
+
+
+class AutoModelConfig(BaseModel):
+    """Candidate model for `model: "auto"` cost-based selection."""
+    
+    model: str = Field(..., description="Model name")
+    target: Optional[str] = Field(default=None, description="Target serving the model (defaults to the target being configured)")
+    context_window: int = Field(..., gt=0, description="Context window in tokens (prompt + completion)")
+    capabilities: List[str] = Field(default_factory=list, description="Capabilities the model supports (e.g., vision, tools, json)")
+
+
+class LanguageRouteConfig(BaseModel):
+    """Model and/or target used for requests detected as one language."""
+    
+    model: Optional[str] = Field(default=None, description="Model for this language (default: the route target's default_model)")
+    target: Optional[str] = Field(default=None, description="Target serving the model (defaults to the target being configured)")
+    
+    @model_validator(mode="after")
+    def validate_route(self) -> "LanguageRouteConfig":
+        if not self.model and not self.target:
+            raise ValueError("language route needs a model, a target, or both")
+        return self
+
+
+class LanguageRoutingConfig(BaseModel):
+    """Routing of requests without an explicit model by the detected input language."""
+    
+    routes: Dict[str, LanguageRouteConfig] = Field(
+        ..., description="ISO 639-1 language code -> route. Format: {ja: {model: ...}, zh: {target: ..., model: ...}}"
+    )
+    min_chars: int = Field(default=10, ge=1, description="Letters needed in the user messages before a language is detected")
+    
+    @field_validator("routes")
+    @classmethod
+    def validate_languages(cls, v: Dict[str, LanguageRouteConfig]) -> Dict[str, LanguageRouteConfig]:
+        from reliapi.core.language_routing import SUPPORTED_LANGUAGES
+
+        unknown = sorted(set(v) - set(SUPPORTED_LANGUAGES))
+        if unknown:
+            raise ValueError(f"language_routing has unsupported languages {unknown} (supported: {list(SUPPORTED_LANGUAGES)})")
+        return v
+
+
+class ModelInfoConfig(BaseModel):
+    """Model metadata advertised by GET /models."""
+    
+    context_window: Optional[int] = Field(default=None, gt=0, description="Context window in tokens (prompt + completion)")
+    capabilities: List[str] = Field(default_factory=list, description="Capabilities the model supports (vision, tools, json_mode)")
+
+
+class ResponseNormalizationConfig(BaseModel):
+    """Mapping of provider-specific roles and finish reasons onto the OpenAI vocabulary."""
+    
+    role_map: Dict[str, Literal["system", "user", "assistant"]] = Field(
+        default_factory=dict,
+        description="Extra provider role -> role mappings (built-in: model, bot, chatbot -> assistant)"
+    )
+    finish_reason_map: Dict[str, str] = Field(
+        default_factory=dict,
+        description="Extra provider finish reason mappings (built-in: end_turn -> stop, max_tokens -> length, ...)"
+    )
+    include_raw: bool = Field(default=False, description="Include the raw provider response as data.raw")
+
+
+class SummarizationConfig(BaseModel):
+    """Summarization of older conversation turns once the history grows past a threshold."""
+    
+    threshold_tokens: int = Field(..., gt=0, description="Summarize when the estimated prompt exceeds this many tokens")
+    keep_recent: int = Field(default=4, ge=1, description="Latest non-system messages kept verbatim")
+    model: Optional[str] = Field(default=None, description="Summarizer model on the same target (default: the request's model)")
+    max_summary_tokens: int = Field(default=256, gt=0, description="max_tokens for the summarization call")
+    prompt: Optional[str] = Field(default=None, description="Instruction for the summarizer (default: concise summary keeping facts and decisions)")
+
+
+class TimeoutScalingConfig(BaseModel):
+    """Provider timeout that grows with the requested generation length."""
+    
+    base_ms: int = Field(default=5000, gt=0, description="Timeout for a zero-token generation")
+    per_token_ms: float = Field(default=20.0, ge=0.0, description="Added per requested max_tokens")
+    max_ms: int = Field(default=300000, gt=0, le=300000, description="Upper bound of the computed timeout")
+
+
+class StreamRetryConfig(BaseModel):
+    """Retries for streams with an idempotency key that fail mid-flight."""
+    
+    attempts: int = Field(default=2, ge=1, le=5, description="Retries after the first attempt")
+    on_partial: Literal["abort", "restart"] = Field(
+        default="abort",
+        description="After partial output: abort with an interrupted error, or restart and skip the text already sent"
+    )
+    backoff_ms: int = Field(default=200, ge=0, description="Delay before retry N is N * backoff_ms")
+
+
+class OutputSchemaConfig(BaseModel):
+    """What happens when an LLM output fails the request's json_schema."""
+    
+    on_failure: Literal["retry", "error"] = Field(
+        default="retry",
+        description="retry: ask the model again with a corrective instruction; error: return 422 SCHEMA_VIOLATION at once"
+    )
+    max_retries: int = Field(
+        default=2, ge=0, le=5, description="Corrective retries before returning 422 SCHEMA_VIOLATION"
+    )
+
+
+class EmptyResponseConfig(BaseModel):
+    """Retries for non-streaming completions that come back without content."""
+    
+    max_retries: int = Field(default=1, ge=1, le=5, description="Retries after an empty completion")
+    detect: Literal["empty", "whitespace"] = Field(
+        default="whitespace",
+        description="empty: only a missing or zero-length content; whitespace: also content that is only whitespace"
+    )
+    nudge: Optional[str] = Field(
+        default=None,
+        description="User message appended to the conversation on each retry (default: resend it unchanged)"
+    )
+    keyed_only: bool = Field(
+        default=True, description="Retry only requests with an idempotency key; other requests get the empty completion"
+    )
 
 
 class LLMConfig(BaseModel):
     """LLM-specific configuration."""
     
-    provider: Optional[str] = Field(default=None, description="Provider name (openai, anthropic, mistral)")
+    provider: Optional[str] = Field(default=None, description="Provider name (openai, anthropic, mistral, gemini)")
     default_model: Optional[str] = Field(default=None, description="Default model name")
     max_tokens: Optional[int] = Field(default=None, gt=0, description="Maximum tokens limit")
     temperature: Optional[float] = Field(default=None, ge=0.0, le=2.0, description="Temperature limit")
     soft_cost_cap_usd: Optional[float] = Field(default=None, ge=0.0, description="Soft cost cap (throttle if exceeded)")
     hard_cost_cap_usd: Optional[float] = Field(default=None, ge=0.0, description="Hard cost cap (reject if exceeded)")
+    stream_cost_ceiling_usd: Optional[float] = Field(
+        default=None,
+        gt=0.0,
+        description="Abort a stream mid-generation once its running cost passes this amount (done event has cost_ceiling_reached)"
+    )
+    max_messages: int = Field(
+        default=500,
+        gt=0,
+        description="Maximum messages in one request's messages array; longer conversations are rejected (422 TOO_MANY_TURNS)"
+    )
+    model_limits: Optional[Dict[str, ModelLimitConfig]] = Field(
+        default=None,
+        description="Per-model RPM/TPM caps. Format: {model_name: {rpm: 500, tpm: 200000}}; '*' applies to other models"
+    )
+    output_caps: Optional[Dict[str, OutputCapConfig]] = Field(
+        default=None,
+        description="Per-model output token ceilings. Format: {model_name: {max_tokens: 2000, on_exceed: clamp}}; '*' applies to other models"
+    )
+    on_content_filter_model: Optional[str] = Field(
+        default=None,
+        description="Retry a non-streaming response blocked by the content filter (finish_reason content_filter) once on this model"
+    )
+    empty_response: Optional[EmptyResponseConfig] = Field(
+        default=None,
+        description="Retry completions with empty content (not cached); off if unset"
+    )
+    output_schema: OutputSchemaConfig = Field(
+        default_factory=OutputSchemaConfig,
+        description="Handling of outputs that fail a request's json_schema (corrective retries or 422)"
+    )
+    stream_idempotency_in_progress: Literal["reject", "wait"] = Field(
+        default="reject",
+        description="Streamed request whose idempotency key is still streaming: reject (409 STREAM_ALREADY_IN_PROGRESS) or wait up to 30s and replay it"
+    )
+    allowed_params: Optional[List[str]] = Field(
+        default=None,
+        description="Optional request parameters forwarded upstream (temperature, top_p, stop, response_format, n, logit_bias, seed, logprobs, top_logprobs); default all"
+    )
+    on_unknown_param: Literal["drop", "reject", "forward"] = Field(
+        default="drop",
+        description="Request parameter outside allowed_params: drop it, reject (400 PARAM_NOT_ALLOWED) or forward it and only count it"
+    )
+    on_unknown_model: Literal["allow", "reject", "estimate_chars"] = Field(
+        default="allow",
+        description=(
+            "Behavior for models without pricing: allow (no estimate, budget caps not enforced), "
+            "reject (422 UNKNOWN_MODEL), estimate_chars (chars/4 tokens at fallback pricing, cost marked approximate)"
+        )
+    )
+    prompt_caching: bool = Field(
+        default=False,
+        description="Mark the stable conversation prefix for provider prompt caching (Anthropic cache_control; OpenAI caches automatically)"
+    )
+    stream_usage: bool = Field(
+        default=True,
+        description="Ask OpenAI for usage in the final stream chunk (stream_options.include_usage); disable for compatible servers that reject it"
+    )
+    auto_models: Optional[List[AutoModelConfig]] = Field(
+        default=None,
+        description="Candidate pool for model 'auto': the cheapest candidate meeting the request constraints is used (default_model if none match)"
+    )
+    json_repair: bool = Field(
+        default=False,
+        description="Repair almost-valid JSON (code fences, surrounding prose, trailing commas) in JSON-mode responses"
+    )
+    models: Optional[Dict[str, ModelInfoConfig]] = Field(
+        default=None,
+        description="Models listed by GET /models with their metadata. Format: {model_name: {context_window: 128000, capabilities: [vision, tools]}}"
+    )
+    live_models: bool = Field(
+        default=False,
+        description="Also list the models reported by the provider's GET /models endpoint (cached for 5 minutes)"
+    )
+    normalization: Optional[ResponseNormalizationConfig] = Field(
+        default=None,
+        description="Role/finish reason mappings and raw payload passthrough for response normalization"
+    )
+    timeout_scaling: Optional[TimeoutScalingConfig] = Field(
+        default=None,
+        description="Provider timeout base_ms + per_token_ms * max_tokens instead of the fixed timeout_ms"
+    )
+    stream_retry: Optional[StreamRetryConfig] = Field(
+        default=None,
+        description="Retry keyed streams that fail (network, idle timeout, 429/5xx) from scratch; see on_partial"
+    )
+    summarization: Optional[SummarizationConfig] = Field(
+        default=None,
+        description="Replace older turns with a summary from a cheap model when the conversation exceeds threshold_tokens"
+    )
+    language_routing: Optional[LanguageRoutingConfig] = Field(
+        default=None,
+        description="Route requests without an explicit model to a model/target chosen by the detected input language"
+    )
+    
+    @field_validator("allowed_params")
+    @classmethod
+    def validate_allowed_params(cls, v: Optional[List[str]]) -> Optional[List[str]]:
+        from reliapi.core.request_params import FORWARDABLE_PARAMS
+
+        unknown = sorted(set(v or []) - set(FORWARDABLE_PARAMS))
+        if unknown:
+            raise ValueError(f"allowed_params has unknown parameters {unknown} (known: {list(FORWARDABLE_PARAMS)})")
+        return v
     
     @field_validator("hard_cost_cap_usd")
     @classmethod
@@ -48,6 +582,123 @@ class RetryPolicyConfig(BaseModel):
     max_s: Optional[float] = Field(default=60.0, gt=0, description="Maximum delay in seconds")
 
 
+class RetryBudgetConfig(BaseModel):
+    """Retry budget: cap retries to a fraction of requests in a rolling window."""
+    
+    ratio: float = Field(default=0.1, gt=0.0, le=1.0, description="Maximum retries per request in the window (0.1 = 10%)")
+    window_s: int = Field(default=60, gt=0, le=3600, description="Rolling window in seconds")
+    min_retries: int = Field(default=10, ge=0, description="Retries always allowed per window, regardless of traffic")
+
+
+class ConcurrencyConfig(BaseModel):
+    """Per-target cap on in-flight upstream requests."""
+    
+    max_concurrent: int = Field(..., gt=0, description="Maximum in-flight upstream requests for the target")
+    max_queue_wait_ms: Optional[int] = Field(
+        default=None,
+        gt=0,
+        description="Reject with 503 QUEUE_TIMEOUT after waiting this long for a slot (wait indefinitely if unset)"
+    )
+    load_shedding: bool = Field(
+        default=False,
+        description="Reject with 503 OVERLOADED and a Retry-After hint when every slot is busy, instead of queueing"
+    )
+
+
+class HedgingConfig(BaseModel):
+    """Hedged requests: a second identical call when the first is slower than usual."""
+    
+    enabled: bool = Field(default=True, description="Hedge safe-to-repeat HTTP requests (requests may still opt out with hedge: false)")
+    delay_percentile: float = Field(default=95, gt=0, le=100, description="Hedge after this percentile of recent upstream latency")
+    initial_delay_ms: int = Field(default=200, gt=0, description="Hedge delay until min_samples latencies are known")
+    min_samples: int = Field(default=20, gt=0, description="Latency samples needed before the percentile is used")
+    min_delay_ms: int = Field(default=10, ge=0, description="Lower bound for the hedge delay")
+    max_hedge_ratio: float = Field(
+        default=0.1, gt=0, le=1, description="Cost guard: hedge at most this fraction of the target's recent requests"
+    )
+
+
+class ResponseSchemaConfig(BaseModel):
+    """JSON Schema contract for successful upstream HTTP responses."""
+    
+    json_schema: Dict[str, Any] = Field(..., description="JSON Schema (draft 2020-12) the response body must match")
+    on_violation: Literal["retry", "error"] = Field(
+        default="error",
+        description="retry: treat a mismatch like an upstream 5xx (retried); error: return 502 SCHEMA_VIOLATION"
+    )
+    
+    @field_validator("json_schema")
+    @classmethod
+    def validate_json_schema(cls, v):
+        from jsonschema import Draft202012Validator
+        from jsonschema.exceptions import SchemaError
+        
+        try:
+            Draft202012Validator.check_schema(v)
+        except SchemaError as e:
+            raise ValueError(f"Invalid json_schema: {e.message}")
+        return v
+
+
+class ResponseTransformRule(BaseModel):
+    """One rewrite of the upstream HTTP response body (applied before caching)."""
+    
+    op: Literal["rename", "drop", "default", "replace"] = Field(..., description="Transform operation")
+    field: str = Field(..., description="Dotted field path; '[]' maps over list elements (e.g., items[].id)")
+    to: Optional[str] = Field(default=None, description="New key name (rename)")
+    value: Any = Field(default=None, description="Value for missing or null fields (default)")
+    pattern: Optional[str] = Field(default=None, description="Regular expression to substitute (replace)")
+    replacement: str = Field(default="", description="Substitution for pattern matches (replace)")
+    
+    @model_validator(mode="after")
+    def validate_rule(self):
+        from reliapi.core.response_transform import parse_path
+        
+        parse_path(self.field)
+        if self.op == "rename" and (not self.to or "." in self.to or "[" in self.to):
+            raise ValueError("rename requires 'to': a plain key name")
+        if self.op == "replace":
+            if self.pattern is None:
+                raise ValueError("replace requires 'pattern'")
+            try:
+                re.compile(self.pattern)
+            except re.error as e:
+                raise ValueError(f"Invalid pattern '{self.pattern}': {e}")
+        return self
+
+
+class ContentTypeRule(BaseModel):
+    """HTTP proxy handling for requests or responses of one content type (first matching rule wins)."""
+    
+    match: str = Field(..., description="Media type to match, e.g. application/json, image/*, */* (parameters ignored)")
+    on: Literal["response", "request"] = Field(default="response", description="Match the response or the request Content-Type")
+    stream: bool = Field(
+        default=False,
+        description="Relay successful response bodies as they arrive instead of the JSON envelope (never cached)"
+    )
+    cache: bool = Field(default=True, description="false: never cache (request rules also skip the cache lookup)")
+    transform: bool = Field(default=True, description="false: skip response_transform")
+    extract: Optional[str] = Field(
+        default=None, description="Dotted path whose value replaces the JSON body, e.g. data.items ('[]' maps over lists)"
+    )
+    body: Literal["auto", "text", "base64"] = Field(
+        default="auto",
+        description="How data.body is decoded: auto (JSON, else raw text, else raw_base64), text (raw) or base64 (raw_base64)"
+    )
+    
+    @model_validator(mode="after")
+    def validate_rule(self):
+        from reliapi.core.content_types import parse_media_range
+        from reliapi.core.response_transform import parse_path
+        
+        parse_media_range(self.match)
+        if self.extract is not None:
+            parse_path(self.extract)
+        if self.stream and self.on != "response":
+            raise ValueError("stream applies to response rules only")
+        return self
+
+
 class AuthConfig(BaseModel):
     """Authentication configuration."""
     
@@ -57,17 +708,186 @@ class AuthConfig(BaseModel):
     prefix: Optional[str] = Field(default=None, description="Header prefix (e.g., 'Bearer ')")
 
 
+class SigningConfig(BaseModel):
+    """HMAC signature headers added to every upstream request (partner APIs with custom signing)."""
+    
+    secret_env: str = Field(..., description="Environment variable holding the shared HMAC secret")
+    algorithm: Literal["sha256", "sha512", "sha1"] = Field(default="sha256", description="Hash used for the HMAC")
+    signature_header: str = Field(default="X-Signature", description="Header carrying the signature")
+    timestamp_header: Optional[str] = Field(default="X-Timestamp", description="Header carrying the signing timestamp (null to send none)")
+    timestamp_format: Literal["unix", "unix_ms", "iso8601"] = Field(default="unix", description="Timestamp format")
+    string_to_sign: str = Field(
+        default="{timestamp}\n{method}\n{path}\n{body}",
+        description="Template of the signed string over {timestamp}, {method}, {path} (with query), {body}, {body_sha256}"
+    )
+    encoding: Literal["hex", "base64"] = Field(default="hex", description="Signature encoding")
+    prefix: str = Field(default="", description="Prepended to the signature value (e.g., 'sha256=')")
+    
+    @field_validator("string_to_sign")
+    @classmethod
+    def validate_string_to_sign(cls, v: str) -> str:
+        from reliapi.core.request_signing import SIGNING_PLACEHOLDERS, template_placeholders
+
+        try:
+            unknown = sorted(template_placeholders(v) - set(SIGNING_PLACEHOLDERS))
+        except ValueError as e:
+            raise ValueError(f"string_to_sign is not a valid template: {e}")
+        if unknown:
+            raise ValueError(f"string_to_sign has unknown placeholders {unknown} (known: {list(SIGNING_PLACEHOLDERS)})")
+        return v
+
+
+class ShadowConfig(BaseModel):
+    """Shadow (mirror) traffic configuration.
+    
+    A copy of each request is sent asynchronously to the shadow target and the
+    outcome is compared with the primary. Only the primary response is returned.
+    """
+    
+    target: str = Field(..., description="Target name that receives mirrored traffic")
+    sample_rate: float = Field(default=1.0, ge=0.0, le=1.0, description="Fraction of requests to mirror (0.0-1.0)")
+    model: Optional[str] = Field(default=None, description="Model for shadow LLM requests (defaults to the shadow target's default_model)")
+
+
+class DedupConfig(BaseModel):
+    """Content-hash deduplication of rapid-fire identical requests.
+    
+    Catches accidental double-submits from clients that don't send an
+    idempotency key. Identical requests arriving within the window are either
+    coalesced onto the first request's result or rejected with 409.
+    """
+    
+    enabled: bool = Field(default=False, description="Enable content-hash deduplication")
+    window_ms: int = Field(default=5000, gt=0, le=60000, description="Window in which identical requests count as duplicates")
+    action: Literal["coalesce", "reject"] = Field(default="coalesce", description="Coalesce duplicates onto the first result or reject with 409")
+    coalesce_keyed: bool = Field(
+        default=False,
+        description="Requests with an idempotency key also wait for an identical in-flight request (action coalesce), then run against its cache entry"
+    )
+
+
+class RegionConfig(BaseModel):
+    """One regional endpoint of a target."""
+    
+    name: str = Field(..., description="Region name reported in meta.region (e.g., eastus)")
+    base_url: str = Field(..., description="Base URL of the regional endpoint")
+
+
+class GrpcConfig(BaseModel):
+    """gRPC upstream: JSON requests transcoded to protobuf (see core/grpc_transcoding.py)."""
+    
+    descriptor_set: str = Field(..., description="Compiled FileDescriptorSet (protoc --include_imports --descriptor_set_out)")
+    methods: List[str] = Field(..., min_length=1, description="Callable methods as /package.Service/Method (the request path)")
+    
+    @field_validator("methods")
+    @classmethod
+    def validate_methods(cls, v: List[str]) -> List[str]:
+        for method in v:
+            if not re.fullmatch(r"/?[\w.]+\.\w+/\w+", method):
+                raise ValueError(f"Invalid gRPC method '{method}' (expected /package.Service/Method)")
+        return ["/" + method.lstrip("/") for method in v]
+
+
+class PluginConfig(BaseModel):
+    """WASM module that rewrites a target's upstream requests/responses (see core/plugins.py)."""
+    
+    path: str = Field(..., description="Path to the .wasm module")
+    name: Optional[str] = Field(default=None, description="Name used in errors and logs (default: path)")
+    max_time_ms: int = Field(default=50, gt=0, le=10000, description="Execution time limit per hook call")
+    max_memory_mb: int = Field(default=16, gt=0, le=1024, description="Linear memory limit per hook call")
+    settings: Optional[Dict[str, Any]] = Field(default=None, description="Passed read-only to the module as 'settings'")
+
+
 class TargetConfig(BaseModel):
     """Target (upstream) configuration."""
     
     base_url: str = Field(..., description="Base URL for the target")
     timeout_ms: int = Field(default=20000, gt=0, le=300000, description="Request timeout in milliseconds")
+    idle_timeout_ms: int = Field(
+        default=30000, gt=0, le=300000,
+        description="Abort a stream if no chunk arrives within this window in milliseconds (separate from timeout_ms)"
+    )
+    first_byte_timeout_ms: Optional[int] = Field(
+        default=None, gt=0, le=300000,
+        description="Abort a stream if its first chunk takes longer than this in milliseconds (default: timeout_ms)"
+    )
     circuit: Optional[CircuitConfig] = Field(default_factory=CircuitConfig, description="Circuit breaker config")
     cache: Optional[CacheConfig] = Field(default_factory=CacheConfig, description="Cache config")
     llm: Optional[LLMConfig] = Field(default=None, description="LLM-specific config (if applicable)")
     auth: Optional[AuthConfig] = Field(default=None, description="Authentication config")
+    signing: Optional[SigningConfig] = Field(default=None, description="HMAC-sign every upstream request (signature and timestamp headers)")
     fallback_targets: Optional[List[str]] = Field(default=None, description="Fallback target names (planned, not implemented)")
     retry_matrix: Optional[Dict[str, RetryPolicyConfig]] = Field(default=None, description="Retry policies by error class")
+    shadow: Optional[ShadowConfig] = Field(default=None, description="Mirror traffic to a candidate target for comparison")
+    dedup: Optional[DedupConfig] = Field(default=None, description="Deduplicate identical requests by content hash")
+    retry_budget: Optional[RetryBudgetConfig] = Field(default=None, description="Per-target retry budget (fail fast once exhausted)")
+    concurrency: Optional[ConcurrencyConfig] = Field(default=None, description="Cap in-flight upstream requests, with a bounded queue wait")
+    hedging: Optional[HedgingConfig] = Field(default=None, description="Hedge slow GET/HEAD and keyed HTTP requests")
+    regions: Optional[List[RegionConfig]] = Field(
+        default=None,
+        description="Regional endpoints; requests go to the lowest-latency healthy one and fail over to the others"
+    )
+    response_schema: Optional[ResponseSchemaConfig] = Field(default=None, description="Validate successful responses against a JSON Schema")
+    retry_on_error_codes: Optional[List[str]] = Field(
+        default=None,
+        description="Glob patterns for provider error codes in the response body that are retried like a 5xx (e.g. overloaded_error)"
+    )
+    response_transform: Optional[List[ResponseTransformRule]] = Field(
+        default=None,
+        description="Rename/drop/default/replace rules applied to HTTP response bodies before caching"
+    )
+    content_types: Optional[List[ContentTypeRule]] = Field(
+        default=None,
+        description="HTTP targets: streaming, caching, decoding and transforms per request/response content type, first match wins"
+    )
+    grpc: Optional[GrpcConfig] = Field(
+        default=None,
+        description="Call gRPC methods with JSON bodies (base_url grpc://host:port or grpcs:// for TLS)"
+    )
+    plugins: Optional[List[PluginConfig]] = Field(
+        default=None,
+        description="WASM transform_request/transform_response hooks run in order on HTTP proxy calls"
+    )
+    user_agent: Optional[str] = Field(default=None, description="User-Agent sent upstream (default: ReliAPI)")
+    default_headers: Optional[Dict[str, str]] = Field(
+        default=None,
+        description="Headers added to every upstream request (e.g., API version headers); request headers override them"
+    )
+    forward_client_ip: bool = Field(default=False, description="Send the caller's IP upstream as X-Forwarded-For")
+    serve_cache_when_disabled: bool = Field(
+        default=False,
+        description="While the target is disabled via the kill switch, still serve cache hits (misses get 503 TARGET_DISABLED)"
+    )
+    count_cache_hits_against_rate_limit: bool = Field(
+        default=False,
+        description="Count LLM cache hits against llm.model_limits rpm (off: hits skip rate limits and concurrency queues)"
+    )
+    warmup_path: Optional[str] = Field(
+        default=None,
+        description="Path for the startup warmup check (GET, default /models for LLM targets; other targets are skipped without it)"
+    )
+    upstream_request_id_header: Optional[str] = Field(
+        default=None,
+        description="Response header with the provider's request ID (default: x-request-id, request-id for Anthropic)"
+    )
+    
+    @model_validator(mode="after")
+    def validate_grpc_base_url(self):
+        if self.grpc and not self.base_url.startswith(("grpc://", "grpcs://")):
+            raise ValueError("gRPC targets need a grpc:// or grpcs:// base_url")
+        return self
+    
+    @field_validator("regions")
+    @classmethod
+    def validate_regions(cls, v: Optional[List[RegionConfig]]) -> Optional[List[RegionConfig]]:
+        if v is None:
+            return v
+        if not v:
+            raise ValueError("regions must list at least one endpoint")
+        names = [region.name for region in v]
+        if len(set(names)) != len(names):
+            raise ValueError("region names must be unique")
+        return v
 
 
 class RateLimitConfig(BaseModel):
@@ -93,6 +913,24 @@ class ProviderKeyPoolConfig(BaseModel):
     keys: List[ProviderKeyConfig] = Field(..., description="List of keys for this provider")
 
 
+class RequestDefaultsConfig(BaseModel):
+    """Parameters a tenant's requests inherit when they omit them."""
+    
+    model: Optional[str] = Field(default=None, description="Default model (LLM requests)")
+    max_tokens: Optional[int] = Field(default=None, gt=0, description="Default max_tokens (LLM requests)")
+    cache: Optional[int] = Field(default=None, ge=0, description="Default cache TTL in seconds")
+    post_process: Optional[Literal["none", "trim", "trim_fences"]] = Field(
+        default=None, description="Default content cleanup (LLM requests): trim, or trim_fences to also unwrap one code fence"
+    )
+
+
+class TenantBudgetConfig(BaseModel):
+    """Spend cap for a tenant over a calendar period (UTC)."""
+
+    limit_usd: float = Field(..., gt=0, description="Maximum recorded spend per period in USD")
+    period: Literal["day", "month"] = Field(default="month", description="Budget period; spend resets at its start")
+
+
 class TenantConfig(BaseModel):
     """Multi-tenant configuration.
     
@@ -114,6 +952,20 @@ class TenantConfig(BaseModel):
         ge=1,
         description="Rate limit in requests per minute for this tenant (minimal, in-memory counter)"
     )
+    max_messages: Optional[int] = Field(
+        default=None,
+        gt=0,
+        description="Maximum messages per LLM request for this tenant; the lower of this and the target's llm.max_messages applies"
+    )
+    budget: Optional[TenantBudgetConfig] = Field(
+        default=None,
+        description="Spend cap per day or month; requests are rejected with 402 BUDGET_EXCEEDED once it is used up"
+    )
+    cost_multiplier: float = Field(
+        default=1.0,
+        gt=0.0,
+        description="Markup applied to reported cost_usd, cost estimates and cost caps (e.g. 1.3 for +30%); usage records keep raw_cost_usd"
+    )
     cache_ttl_override: Optional[Dict[str, int]] = Field(
         default=None,
         description="Per-target cache TTL override in seconds. Format: {target_name: 300}"
@@ -122,6 +974,14 @@ class TenantConfig(BaseModel):
         default=None,
         description="Client profile name for this tenant (e.g., 'cursor_default')"
     )
+    tags: Optional[Dict[str, str]] = Field(
+        default=None,
+        description="Default cost attribution tags for this tenant's requests. Request tags override these. Format: {team: 'search'}"
+    )
+    defaults: Optional[Dict[str, RequestDefaultsConfig]] = Field(
+        default=None,
+        description="Per-target request defaults; request values override them. Format: {target_name: {model: 'gpt-4o-mini', max_tokens: 512, cache: 300, post_process: trim}}; '*' applies to every target"
+    )
 
 
 class ClientProfileConfig(BaseModel):
@@ -149,6 +1009,64 @@ class ReliAPIConfig(BaseModel):
         default=None,
         description="Provider key pools for multi-key support. If present for a provider, overrides targets[provider].auth"
     )
+    retry_budget: Optional[RetryBudgetConfig] = Field(
+        default=None,
+        description="Global retry budget shared by all targets (in addition to per-target budgets)"
+    )
+    cache_compression: Optional[CacheCompressionConfig] = Field(
+        default=None,
+        description="Compress large cached values to reduce Redis memory and network usage"
+    )
+    cache_memory: Optional[MemoryCacheConfig] = Field(
+        default=None,
+        description="In-memory cache tier with lru or cost-weighted eviction"
+    )
+    cache_write_retry: Optional[CacheWriteRetryConfig] = Field(
+        default=None,
+        description="Retry cache writes that fail on a transient Redis error, without delaying the response"
+    )
+    debug_trace: Optional[DebugTraceConfig] = Field(
+        default=None,
+        description="Ring buffer of recent request traces for live debugging (GET /debug/recent)"
+    )
+    stream_tee: Optional[StreamTeeConfig] = Field(
+        default=None,
+        description="Copy completed LLM streams (redacted) to a file or webhook for analytics"
+    )
+    usage_export: Optional[UsageExportConfig] = Field(
+        default=None,
+        description="Send per-request usage records in signed batches to an analytics webhook"
+    )
+    warmup: Optional[WarmupConfig] = Field(
+        default=None,
+        description="Validate target credentials with a cheap call at startup; results are in GET /health"
+    )
+    cache_key_version: int = Field(
+        default=0,
+        ge=0,
+        description="Mixed into every cache key; bump it on deploys that change cached formats to invalidate all entries"
+    )
+    max_cache_value_bytes: int = Field(
+        default=1024 * 1024,
+        gt=0,
+        description="Responses whose stored size exceeds this are served but not cached (meta.cache_skipped_too_large)"
+    )
+    idempotency_scope: Literal["per_key", "global"] = Field(
+        default="per_key",
+        description="Namespace idempotency records per API key (default) or share them across keys (always isolated per tenant)"
+    )
+    idempotency_keys: Optional[IdempotencyKeyConfig] = Field(
+        default=None,
+        description="Length, charset and UUID rules for client idempotency keys"
+    )
+    request_overrides: Optional[RequestOverridesConfig] = Field(
+        default=None,
+        description="Limits for per-request timeout/retry override headers (defaults apply if omitted)"
+    )
+    streaming: Optional[StreamingConfig] = Field(
+        default=None,
+        description="X-Accel-Buffering header and SSE heartbeats for streams behind buffering proxies"
+    )
     client_profiles: Optional[Dict[str, ClientProfileConfig]] = Field(
         default=None,
         description="Client profiles for different client types (e.g., cursor_default). Priority: X-Client header > tenant.profile > default"
@@ -0,0 +1,161 @@
+"""Audit log of requests rejected for budget, rate-limit or policy reasons."""
+import json
+import logging
+import time
+import uuid
+from typing import Any, Dict, List, Optional
+
+import redis
+
+from reliapi.core.errors import ErrorCode
+
+logger = logging.getLogger(__name__)
+
+# Retention default for audit records
+DEFAULT_RETENTION_S = 86400 * 90  # 90 days
+
+# Maximum records returned by one query
+DEFAULT_QUERY_LIMIT = 1000
+
+# ReliAPI error codes that are audited, by category
+AUDITED_CODES = {
+    ErrorCode.BUDGET_EXCEEDED.value: "budget",
+    ErrorCode.UNKNOWN_MODEL.value: "budget",
+    ErrorCode.OUTPUT_CAP_EXCEEDED.value: "budget",
+    ErrorCode.RATE_LIMIT_RELIAPI.value: "rate_limit",
+}
+
+
+def rejection_category(code: Optional[str]) -> Optional[str]:
+    """Audit category for a ReliAPI error code (None if not audited)."""
+    return AUDITED_CODES.get(code) if code else None
+
+
+class AuditLog:
+    """Durable record of rejected requests for compliance reviews.
+
+    Records are kept in a Redis sorted set scored by timestamp, so they
+    can be queried by time range. Records older than `retention_s` are
+    dropped on write. Unlike usage records, the audit log is global (not
+    per tenant): it is read by operators through the admin-only /audit.
+    """
+
+    def __init__(
+        self,
+        redis_url: str,
+        key_prefix: str = "reliapi",
+        retention_s: int = DEFAULT_RETENTION_S,
+    ):
+        """
+        Args:
+            redis_url: Redis connection URL
+            key_prefix: Prefix for the audit log key
+            retention_s: How long audit records are kept
+        """
+        self.key_prefix = key_prefix
+        self.retention_s = retention_s
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Audit log connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Audit log connection failed (graceful degradation): {e}", exc_info=True)
+
+    def _make_key(self) -> str:
+        return f"{self.key_prefix}:audit_log"
+
+    def record(
+        self,
+        category: str,
+        reason_code: str,
+        kind: str,
+        target: Optional[str] = None,
+        key_id: Optional[str] = None,
+        tenant: Optional[str] = None,
+        request_id: Optional[str] = None,
+        model: Optional[str] = None,
+        cost_estimate_usd: Optional[float] = None,
+        status_code: Optional[int] = None,
+        message: Optional[str] = None,
+    ) -> Optional[str]:
+        """Append an audit record.
+
+        Args:
+            category: "budget", "rate_limit" or "policy"
+            reason_code: Error code returned to the client
+            kind: "http" or "llm"
+            target: Target name
+            key_id: Hashed API key (see get_account_id)
+            tenant: Tenant name
+            request_id: Request ID (None for requests rejected before one was assigned)
+            model: LLM model name
+            cost_estimate_usd: Attempted (estimated) cost in USD
+            status_code: HTTP status returned to the client
+            message: Error message returned to the client
+
+        Returns:
+            Record ID, or None if the audit log is unavailable
+        """
+        if not self.enabled or not self.client:
+            return None
+
+        now = time.time()
+        record_id = f"audit_{uuid.uuid4().hex[:16]}"
+        record = {
+            "id": record_id,
+            "ts": now,
+            "category": category,
+            "reason_code": reason_code,
+            "kind": kind,
+            "target": target,
+            "key_id": key_id,
+            "tenant": tenant,
+            "request_id": request_id,
+            "model": model,
+            "cost_estimate_usd": cost_estimate_usd,
+            "status_code": status_code,
+            "message": message,
+        }
+        key = self._make_key()
+        try:
+            pipe = self.client.pipeline()
+            pipe.zadd(key, {json.dumps(record): now})
+            pipe.zremrangebyscore(key, "-inf", now - self.retention_s)
+            pipe.execute()
+        except Exception as e:
+            logger.warning(f"Audit record error (graceful degradation): {e}", exc_info=True)
+            return None
+        return record_id
+
+    def query(
+        self,
+        since: Optional[float] = None,
+        until: Optional[float] = None,
+        limit: int = DEFAULT_QUERY_LIMIT,
+    ) -> List[Dict[str, Any]]:
+        """Get audit records in the [since, until] time range (oldest first)."""
+        if not self.enabled or not self.client:
+            return []
+
+        try:
+            raw_records = self.client.zrangebyscore(
+                self._make_key(),
+                since if since is not None else "-inf",
+                until if until is not None else "+inf",
+                start=0,
+                num=limit,
+            )
+        except Exception as e:
+            logger.warning(f"Audit query error (graceful degradation): {e}", exc_info=True)
+            return []
+
+        records = []
+        for raw in raw_records:
+            try:
+                records.append(json.loads(raw))
+            except json.JSONDecodeError:
+                continue
+        return records
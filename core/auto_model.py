@@ -0,0 +1,72 @@
+"""Cost-based model selection for `model: "auto"`."""
+import logging
+from typing import Any, Dict, List, Optional, Tuple
+
+from reliapi.adapters.llm.factory import detect_provider
+from reliapi.core.cost_estimator import CostEstimator
+from reliapi.core.model_limits import estimate_request_tokens
+
+logger = logging.getLogger(__name__)
+
+# Model name that requests cost-based selection
+AUTO_MODEL = "auto"
+
+
+def select_auto_model(
+    target_name: str,
+    targets: Dict[str, Dict[str, Any]],
+    messages: List[Dict[str, str]],
+    max_tokens: Optional[int] = None,
+    constraints: Optional[Dict[str, Any]] = None,
+) -> Tuple[str, Optional[str], bool]:
+    """Pick the cheapest candidate in the target's `auto_models` pool that satisfies constraints.
+
+    A candidate qualifies if it has every required capability and its
+    context window fits both `min_context_tokens` and the estimated
+    request size (prompt + max_tokens). Candidates are ranked by the
+    estimated cost of this request; unpriced models are skipped.
+
+    Args:
+        target_name: Target the request was sent to (owns the candidate pool)
+        targets: All target configs
+        messages: Request messages
+        max_tokens: Requested max tokens (target max_tokens if None)
+        constraints: {"min_context_tokens": int, "capabilities": [str]}
+
+    Returns:
+        (target_name, model, matched). If no candidate matches, the original
+        target and None (its default_model) with matched=False.
+    """
+    constraints = constraints or {}
+    llm_config = targets.get(target_name, {}).get("llm") or {}
+    required_caps = set(constraints.get("capabilities") or [])
+    min_context = constraints.get("min_context_tokens") or 0
+
+    best: Optional[Tuple[float, str, str]] = None
+    for candidate in llm_config.get("auto_models") or []:
+        candidate_target = candidate.get("target") or target_name
+        candidate_config = targets.get(candidate_target)
+        if not candidate_config:
+            continue
+        candidate_llm = candidate_config.get("llm") or {}
+        model = candidate["model"]
+
+        if not required_caps.issubset(candidate.get("capabilities") or []):
+            continue
+        request_tokens = estimate_request_tokens(messages, max_tokens or candidate_llm.get("max_tokens"))
+        if candidate.get("context_window", 0) < max(min_context, request_tokens):
+            continue
+
+        provider = candidate_llm.get("provider") or detect_provider(candidate_config.get("base_url", ""))
+        cost = CostEstimator.estimate_from_messages(
+            provider, model, messages, max_tokens or candidate_llm.get("max_tokens")
+        )
+        if cost is None:
+            continue
+        if best is None or cost < best[0]:
+            best = (cost, candidate_target, model)
+
+    if best is None:
+        logger.info(f"No auto model candidate matched for target '{target_name}', using default model")
+        return target_name, None, False
+    return best[1], best[2], True
@@ -1,28 +1,96 @@
 """Universal cache implementation for HTTP requests."""
+import asyncio
+import base64
 import hashlib
 import json
 import logging
-from typing import Any, Dict, Optional
+import random
+import time
+import zlib
+from typing import Any, Dict, List, Optional, Set
 
 import redis
 
+from reliapi.core.canonical_json import SIGNIFICANT_HEADERS, request_key_hash
+from reliapi.core.memory_cache import MemoryCache
+from reliapi.metrics.prometheus import cache_write_retries_total
+
 logger = logging.getLogger(__name__)
 
+# Marker for zlib-compressed values; uncompressed values are plain JSON and never start with it
+COMPRESSED_PREFIX = "z1:"
+
+# Default +/- fraction applied to TTLs so entries written together do not expire together
+DEFAULT_TTL_JITTER = 0.05
+
+# Stored with entries written for ttl_on_hit "extend": write time and latest allowed expiry
+TTL_CEILING_FIELD = "_ttl_ceiling"
+
+# Stored with entries kept past their TTL for cache.health_ttl: end of the regular TTL
+FRESH_UNTIL_FIELD = "_fresh_until"
+
+# Redis errors a later write may not hit (cache_write_retry)
+TRANSIENT_WRITE_ERRORS = (redis.ConnectionError, redis.TimeoutError)
+
+
+class CacheValueTooLarge(Exception):
+    """A value was not cached because it exceeds max_value_bytes."""
+
+    def __init__(self, size: int, limit: int):
+        super().__init__(f"Cache value of {size} bytes exceeds max_cache_value_bytes ({limit})")
+        self.size = size
+        self.limit = limit
+
+
+def jittered_ttl(ttl_s: int, jitter: float = DEFAULT_TTL_JITTER) -> int:
+    """TTL spread uniformly over ttl_s * (1 +/- jitter), at least 1 second."""
+    if jitter <= 0:
+        return ttl_s
+    return max(1, round(ttl_s * random.uniform(1 - jitter, 1 + jitter)))
+
 
 class Cache:
     """Universal cache wrapper for HTTP requests.
     
     Supports GET/HEAD caching with ETag support.
     Cache key is based on method, URL, and significant headers.
+    With `memory` configured, an in-process tier answers before Redis and
+    keeps serving if Redis is unavailable.
     """
 
-    def __init__(self, redis_url: str, key_prefix: str = "reliapi"):
+    def __init__(
+        self,
+        redis_url: str,
+        key_prefix: str = "reliapi",
+        compression: Optional[Dict[str, Any]] = None,
+        memory: Optional[Dict[str, Any]] = None,
+        max_value_bytes: Optional[int] = None,
+        write_retry: Optional[Dict[str, Any]] = None,
+        key_version: int = 0,
+    ):
         """
         Args:
             redis_url: Redis connection URL
             key_prefix: Prefix for cache keys
+            compression: Compression config (enabled, min_size_bytes, level); off if None
+            memory: In-memory tier config (enabled, max_entries, max_bytes, eviction); off if None
+            max_value_bytes: Largest stored (encoded) value; larger ones are not cached. Unlimited if None
+            write_retry: Background write retry config (enabled, attempts, backoff_ms, max_pending); off if None
+            key_version: cache_key_version mixed into every key; bumping it orphans older entries
         """
         self.key_prefix = key_prefix
+        self.key_version = key_version
+        self.max_value_bytes = max_value_bytes
+        self.write_retry = write_retry if write_retry and write_retry.get("enabled") else None
+        self._pending_writes: Set[asyncio.Task] = set()
+        self.compression = compression if compression and compression.get("enabled") else None
+        self.memory = None
+        if memory and memory.get("enabled"):
+            self.memory = MemoryCache(
+                max_entries=memory.get("max_entries", 10000),
+                max_bytes=memory.get("max_bytes", 64 * 1024 * 1024),
+                eviction=memory.get("eviction", "lru"),
+            )
         try:
             self.client = redis.from_url(redis_url, decode_responses=True)
             self.client.ping()
@@ -30,7 +98,7 @@ class Cache:
             logger.info(f"Cache connected to Redis: {redis_url}")
         except Exception as e:
             self.client = None
-            self.enabled = False
+            self.enabled = self.memory is not None
             logger.warning(f"Cache connection failed (graceful degradation): {e}", exc_info=True)
 
     def _make_key(
@@ -41,43 +109,79 @@ class Cache:
         body: Optional[bytes] = None,
         query: Optional[Dict[str, Any]] = None,
         tenant: Optional[str] = None,
+        vary_headers: Optional[List[str]] = None,
     ) -> str:
         """Generate cache key from request parameters.
         
-        Key includes: tenant (if multi-tenant) + method + url + sorted query + significant headers + body hash
+        Key includes: tenant (if multi-tenant) + key_version (if set) + method + url + sorted query +
+        significant headers + body hash, hashed over their canonical JSON (request_key_hash)
         
         Args:
             tenant: Tenant name for multi-tenant isolation (optional)
+            vary_headers: Extra request headers the response varies by (cache.vary_headers)
+        """
+        # Significant headers only (exclude auth, trace, etc.); see core/canonical_json.py
+        cache_key_hash = request_key_hash(method, url, headers, body, query, vary_headers)
+
+        # Version 0 keeps the unversioned keys, so enabling versioning does not flush the cache
+        if self.key_version:
+            cache_key_hash = f"v{self.key_version}:{cache_key_hash}"
+
+        # Multi-tenant isolation: include tenant in cache key
+        if tenant:
+            return f"{self.key_prefix}:tenant:{tenant}:cache:{cache_key_hash}"
+        else:
+            return f"{self.key_prefix}:cache:{cache_key_hash}"
+
+    def _legacy_key(
+        self,
+        method: str,
+        url: str,
+        headers: Optional[Dict[str, str]],
+        body: Optional[bytes],
+        query: Optional[Dict[str, Any]],
+        tenant: Optional[str],
+        vary_headers: Optional[List[str]],
+    ) -> Optional[str]:
+        """Key the previous version stored this request under (json.dumps with sort_keys), if any.
+
+        Read for one release after cache keys moved to canonical JSON, so the
+        upgrade does not orphan every entry. None when the key has parts the
+        previous version did not have (a cache_key_version or varied headers).
         """
-        # Significant headers for caching (exclude auth, trace, etc.)
-        significant_headers = {}
-        if headers:
-            for h in ["Accept", "Accept-Language", "Content-Type"]:
-                if h in headers:
-                    significant_headers[h] = headers[h]
-
-        # Sort query params for consistent keys
-        # json.dumps(sort_keys=True) handles sorting recursively, so we don't need manual sorting here.
-        # We also pass dicts directly to avoid double serialization which is slow.
+        vary = {name.lower() for name in vary_headers or []}
+        if self.key_version or any(name.lower() in vary for name in headers or {}):
+            return None
+        significant_headers = {h: headers[h] for h in SIGNIFICANT_HEADERS if h in (headers or {})}
         key_data = {
             "method": method.upper(),
             "url": url,
             "query": query or {},
             "headers": significant_headers,
         }
-        
-        # For POST/PUT/PATCH with body, include body hash
         if body and method.upper() in ["POST", "PUT", "PATCH"]:
             key_data["body_hash"] = hashlib.sha256(body).hexdigest()[:16]
-
-        key_str = json.dumps(key_data, sort_keys=True)
-        cache_key_hash = hashlib.sha256(key_str.encode()).hexdigest()
-        
-        # Multi-tenant isolation: include tenant in cache key
+        cache_key_hash = hashlib.sha256(json.dumps(key_data, sort_keys=True).encode()).hexdigest()
         if tenant:
             return f"{self.key_prefix}:tenant:{tenant}:cache:{cache_key_hash}"
-        else:
-            return f"{self.key_prefix}:cache:{cache_key_hash}"
+        return f"{self.key_prefix}:cache:{cache_key_hash}"
+
+    def _encode(self, value: Dict[str, Any]) -> str:
+        """Serialize a value, compressing it if compression is on and it is large enough."""
+        serialized = json.dumps(value)
+        if not self.compression or len(serialized) < self.compression.get("min_size_bytes", 1024):
+            return serialized
+        compressed = zlib.compress(serialized.encode(), self.compression.get("level", 6))
+        encoded = COMPRESSED_PREFIX + base64.b64encode(compressed).decode("ascii")
+        # Incompressible payloads would grow after base64; keep them as plain JSON
+        return encoded if len(encoded) < len(serialized) else serialized
+
+    @staticmethod
+    def _decode(cached: str) -> Dict[str, Any]:
+        """Deserialize a stored value (compressed or plain, regardless of current config)."""
+        if cached.startswith(COMPRESSED_PREFIX):
+            cached = zlib.decompress(base64.b64decode(cached[len(COMPRESSED_PREFIX):])).decode()
+        return json.loads(cached)
 
     def get(
         self,
@@ -88,6 +192,9 @@ class Cache:
         query: Optional[Dict[str, Any]] = None,
         allow_post: bool = False,
         tenant: Optional[str] = None,
+        vary_headers: Optional[List[str]] = None,
+        serve_stale: bool = False,
+        legacy_body: Optional[bytes] = None,
     ) -> Optional[Dict[str, Any]]:
         """Get cached response if available.
         
@@ -98,8 +205,12 @@ class Cache:
             body: Request body (for POST/PUT/PATCH)
             query: Query parameters
             allow_post: Allow caching POST requests (for LLM proxy)
+            vary_headers: Extra request headers the response varies by (cache.vary_headers)
+            serve_stale: Also return entries past their regular TTL but kept for health_ttl
+            legacy_body: Body as the previous version keyed it, if it differs from body
+                (read from the legacy key on a miss; see _legacy_key)
         """
-        if not self.enabled or not self.client:
+        if not self.enabled:
             return None
 
         # Only cache GET/HEAD by default, or POST if explicitly allowed
@@ -107,18 +218,40 @@ class Cache:
             return None
 
         try:
-            key = self._make_key(method, url, headers, body, query, tenant=tenant)
+            key = self._make_key(method, url, headers, body, query, tenant=tenant, vary_headers=vary_headers)
+            if self.memory is not None:
+                cached = self.memory.get(key)
+                if cached:
+                    return self._fresh(self._decode(cached), serve_stale)
+            if not self.client:
+                return None
             cached = self.client.get(key)
             if cached:
                 # Edge case: JSON deserialization may fail if cached value is corrupted.
                 # This is handled by the try/except block below.
-                return json.loads(cached)
-        except json.JSONDecodeError as e:
+                value = self._decode(cached)
+                if self.memory is not None:
+                    # Warm the memory tier for the rest of the entry's Redis TTL
+                    ttl_s = self.client.ttl(key)
+                    if ttl_s and ttl_s > 0:
+                        self.memory.set(key, cached, ttl_s, cost_usd=value.get("cost_usd"))
+                return self._fresh(value, serve_stale)
+            legacy_key = self._legacy_key(
+                method, url, headers, body if legacy_body is None else legacy_body, query, tenant, vary_headers
+            )
+            if legacy_key:
+                cached = self.client.get(legacy_key)
+                if cached:
+                    return self._fresh(self._decode(cached), serve_stale)
+        except (json.JSONDecodeError, zlib.error, ValueError) as e:
             # Edge case: Cached value is corrupted or not valid JSON.
             # Delete the corrupted key to prevent future errors.
             logger.warning(f"Cache get: corrupted value for key {key[:50]}... (deleting): {e}", exc_info=True)
             try:
-                self.client.delete(key)
+                if self.memory is not None:
+                    self.memory.delete(key)
+                if self.client:
+                    self.client.delete(key)
             except Exception:
                 pass  # Ignore deletion errors
             return None
@@ -128,6 +261,14 @@ class Cache:
 
         return None
 
+    @staticmethod
+    def _fresh(value: Dict[str, Any], serve_stale: bool) -> Optional[Dict[str, Any]]:
+        """The entry, or None if it is past its regular TTL and stale entries are not wanted."""
+        fresh_until = value.get(FRESH_UNTIL_FIELD)
+        if fresh_until is not None and not serve_stale and time.time() >= fresh_until:
+            return None
+        return value
+
     def set(
         self,
         method: str,
@@ -139,7 +280,11 @@ class Cache:
         query: Optional[Dict[str, Any]] = None,
         allow_post: bool = False,
         tenant: Optional[str] = None,
-    ) -> None:
+        ttl_jitter: float = DEFAULT_TTL_JITTER,
+        max_ttl_s: Optional[int] = None,
+        vary_headers: Optional[List[str]] = None,
+        stale_ttl_s: int = 0,
+    ) -> Optional[int]:
         """Cache response with TTL.
         
         Args:
@@ -151,16 +296,47 @@ class Cache:
             ttl_s: Time to live in seconds
             query: Query parameters
             allow_post: Allow caching POST requests (for LLM proxy)
+            ttl_jitter: Fraction the TTL is randomly spread by (0 disables jitter)
+            max_ttl_s: Lets extend_ttl keep the entry up to this long after the write (ttl_on_hit "extend")
+            vary_headers: Extra request headers the response varies by (cache.vary_headers)
+            stale_ttl_s: Keep the entry this much longer, returned only to get(serve_stale=True)
+
+        Returns:
+            TTL applied after jitter, or None if the response was not cached
+
+        Raises:
+            CacheValueTooLarge: If the encoded value exceeds max_value_bytes
         """
-        if not self.enabled or not self.client:
-            return
+        if not self.enabled:
+            return None
 
         # Only cache GET/HEAD by default, or POST if explicitly allowed
         if method.upper() not in ["GET", "HEAD"] and not (allow_post and method.upper() == "POST"):
-            return
+            return None
 
+        ttl_s = jittered_ttl(ttl_s, ttl_jitter)
         try:
-            key = self._make_key(method, url, headers, body, query, tenant=tenant)
+            key = self._make_key(method, url, headers, body, query, tenant=tenant, vary_headers=vary_headers)
+            if max_ttl_s:
+                now = time.time()
+                value = {**value, TTL_CEILING_FIELD: {"written_at": now, "expires_by": now + max(max_ttl_s, ttl_s)}}
+            stored_ttl_s = ttl_s
+            if stale_ttl_s > 0:
+                value = {**value, FRESH_UNTIL_FIELD: time.time() + ttl_s}
+                stored_ttl_s = ttl_s + stale_ttl_s
+            encoded = self._encode(value)
+            size = len(encoded.encode())
+            if self.max_value_bytes and size > self.max_value_bytes:
+                logger.warning(
+                    f"Cache skipped oversized value for {method.upper()} {url}: "
+                    f"{size} bytes > max_cache_value_bytes {self.max_value_bytes}"
+                )
+                raise CacheValueTooLarge(size, self.max_value_bytes)
+            if self.memory is not None:
+                # cost_usd (LLM responses) drives cost-weighted eviction
+                self.memory.set(key, encoded, stored_ttl_s, cost_usd=value.get("cost_usd"))
+            if not self.client:
+                return ttl_s
             # Atomic SETEX: sets key, value, and TTL in a single operation
             # This is a single Redis command, so it's guaranteed atomic.
             #
@@ -172,15 +348,118 @@ class Cache:
             # 3. TTL expiration during write: SETEX sets both value and TTL atomically,
             #    so key will have correct TTL even if it expires during the operation.
             # 4. Memory pressure: Redis may evict keys, but this is handled by cache miss logic.
-            self.client.setex(key, ttl_s, json.dumps(value))
+            try:
+                self.client.setex(key, stored_ttl_s, encoded)
+            except TRANSIENT_WRITE_ERRORS as e:
+                if not self._schedule_write_retry(key, stored_ttl_s, encoded):
+                    raise
+                logger.warning(f"Cache set error (retrying in background): {e}")
+                return None
+            return ttl_s
+        except CacheValueTooLarge:
+            raise
         except Exception as e:
             logger.warning(f"Cache set error (graceful degradation): {e}", exc_info=True)
+            return None
+
+    def _schedule_write_retry(self, key: str, ttl_s: int, encoded: str) -> bool:
+        """Retry a failed write in the background (cache_write_retry); False if it is not retried."""
+        if not self.write_retry:
+            return False
+        try:
+            loop = asyncio.get_running_loop()
+        except RuntimeError:
+            return False
+        if len(self._pending_writes) >= self.write_retry.get("max_pending", 1000):
+            cache_write_retries_total.labels(outcome="dropped").inc()
+            return False
+        task = loop.create_task(self._retry_write(key, ttl_s, encoded, time.monotonic()))
+        self._pending_writes.add(task)
+        task.add_done_callback(self._pending_writes.discard)
+        return True
+
+    async def _retry_write(self, key: str, ttl_s: int, encoded: str, failed_at: float) -> None:
+        """SETEX with exponential backoff, keeping the expiry the first write would have had."""
+        attempts = self.write_retry.get("attempts", 3)
+        backoff_s = self.write_retry.get("backoff_ms", 100) / 1000
+        error: Optional[Exception] = None
+        for attempt in range(attempts):
+            await asyncio.sleep(backoff_s * 2 ** attempt)
+            remaining_s = ttl_s - int(time.monotonic() - failed_at)
+            if remaining_s <= 0:
+                return
+            try:
+                self.client.setex(key, remaining_s, encoded)
+            except TRANSIENT_WRITE_ERRORS as e:
+                error = e
+                continue
+            cache_write_retries_total.labels(outcome="recovered").inc()
+            return
+        cache_write_retries_total.labels(outcome="failed").inc()
+        logger.error(f"Cache write for key {key[:50]}... still failing after {attempts} retries: {error}")
+
+    def extend_ttl(
+        self,
+        method: str,
+        url: str,
+        headers: Optional[Dict[str, str]],
+        body: Optional[bytes],
+        query: Optional[Dict[str, Any]],
+        entry: Dict[str, Any],
+        allow_post: bool = False,
+        tenant: Optional[str] = None,
+        vary_headers: Optional[List[str]] = None,
+    ) -> Optional[int]:
+        """Extend a hit entry's TTL (ttl_on_hit "extend").
+
+        A hit in the second half of the entry's lifetime doubles the
+        lifetime, so entries hit at least once per half-life keep growing
+        while cold ones expire on schedule. The lifetime never passes the
+        max_ttl_s ceiling recorded at write time, so a stale answer is
+        dropped at most max_ttl_s after it was cached.
+
+        Returns:
+            Seconds until the entry expires, or None if unknown (entry written
+            without max_ttl_s, or the cache is unavailable)
+        """
+        ceiling = entry.get(TTL_CEILING_FIELD)
+        if not self.enabled or not isinstance(ceiling, dict):
+            return None
+        try:
+            key = self._make_key(method, url, headers, body, query, tenant=tenant, vary_headers=vary_headers)
+            if self.client:
+                remaining = self.client.ttl(key)
+            elif self.memory is not None:
+                remaining = self.memory.ttl(key)
+            else:
+                return None
+            if remaining is None or remaining <= 0:
+                return None
+
+            now = time.time()
+            lifetime = now - ceiling["written_at"] + remaining
+            if remaining < lifetime / 2:
+                extended = int(min(ceiling["written_at"] + 2 * lifetime, ceiling["expires_by"]) - now)
+                if extended > remaining:
+                    if self.client:
+                        self.client.expire(key, extended)
+                    if self.memory is not None:
+                        self.memory.expire(key, extended)
+                    remaining = extended
+            return int(remaining)
+        except Exception as e:
+            logger.warning(f"Cache TTL extension error (graceful degradation): {e}", exc_info=True)
+            return None
 
     def invalidate(self, pattern: str) -> None:
         """Invalidate cache entries matching pattern."""
-        if not self.enabled or not self.client:
+        if not self.enabled:
             return
 
+        if self.memory is not None:
+            self.memory.delete_prefix(f"{self.key_prefix}:cache:{pattern}")
+        if not self.client:
+            return
         try:
             keys = self.client.keys(f"{self.key_prefix}:cache:{pattern}*")
             if keys:
@@ -0,0 +1,126 @@
+"""Canonical JSON for cache keys and idempotency hashes.
+
+Every hash ReliAPI derives from structured data (cache keys, idempotency
+request hashes, LLM payload fingerprints) serializes it with
+`canonical_json`, so a client can reproduce a key byte for byte. The
+format is the JSON Canonicalization Scheme (RFC 8785):
+
+- object keys sorted by their UTF-16 code units, recursively
+- no whitespace between tokens
+- strings with the minimal JSON escapes (`\\"`, `\\\\`, `\\b`, `\\f`,
+  `\\n`, `\\r`, `\\t`, other control characters as `\\u00xx`); everything
+  else, including non-ASCII, as literal UTF-8
+- numbers in ECMAScript `Number.prototype.toString` form: integral
+  values without a fraction or exponent (`1.0` -> `1`, `-0.0` -> `0`),
+  others in shortest round-trip form (`0.1`, `1e-7`, `1.5e+21`); NaN and
+  Infinity are rejected
+
+Any RFC 8785 library (e.g. `canonicalize` for JavaScript, `jcs` for
+Python) produces the same bytes.
+"""
+import hashlib
+import json
+import math
+from typing import Any, Dict, List, Optional
+
+SIGNIFICANT_HEADERS = ("Accept", "Accept-Language", "Content-Type")
+
+
+def _number(value: float) -> str:
+    """ECMAScript Number-to-String of a finite float."""
+    if math.isnan(value) or math.isinf(value):
+        raise ValueError(f"{value} is not allowed in canonical JSON")
+    if value == 0:
+        return "0"
+    sign = "-" if value < 0 else ""
+    # repr gives the shortest round-trip digits; only the layout differs from ECMAScript
+    mantissa, _, exponent = repr(abs(value)).partition("e")
+    whole, _, fraction = mantissa.partition(".")
+    significant = (whole + fraction).lstrip("0")
+    # Decimal point position counted from the first significant digit
+    point = len(whole) + int(exponent or 0) - (len(whole + fraction) - len(significant))
+    digits = significant.rstrip("0")
+    count = len(digits)
+    if count <= point <= 21:
+        return sign + digits + "0" * (point - count)
+    if 0 < point <= 21:
+        return sign + digits[:point] + "." + digits[point:]
+    if -6 < point <= 0:
+        return sign + "0." + "0" * -point + digits
+    power = point - 1
+    fraction = "." + digits[1:] if count > 1 else ""
+    return f"{sign}{digits[0]}{fraction}e{'+' if power >= 0 else '-'}{abs(power)}"
+
+
+def _serialize(value: Any) -> str:
+    if value is None:
+        return "null"
+    if value is True:
+        return "true"
+    if value is False:
+        return "false"
+    if isinstance(value, int):
+        return str(value)
+    if isinstance(value, float):
+        return _number(value)
+    if isinstance(value, str):
+        return json.dumps(value, ensure_ascii=False)
+    if isinstance(value, dict):
+        items = sorted(((str(k), v) for k, v in value.items()), key=lambda item: item[0].encode("utf-16-be"))
+        return "{" + ",".join(f"{json.dumps(k, ensure_ascii=False)}:{_serialize(v)}" for k, v in items) + "}"
+    if isinstance(value, (list, tuple)):
+        return "[" + ",".join(_serialize(item) for item in value) + "]"
+    raise TypeError(f"{type(value).__name__} is not JSON serializable")
+
+
+def canonical_json(value: Any) -> str:
+    """RFC 8785 canonical form of a JSON-compatible value."""
+    return _serialize(value)
+
+
+def canonical_hash(value: Any) -> str:
+    """SHA-256 hex digest of the UTF-8 canonical form."""
+    return hashlib.sha256(canonical_json(value).encode("utf-8")).hexdigest()
+
+
+def canonical_body(body: Optional[bytes]) -> Optional[bytes]:
+    """Canonical form of a JSON request body; other bodies are returned unchanged."""
+    if not body:
+        return body
+    try:
+        return canonical_json(json.loads(body)).encode("utf-8")
+    except (ValueError, TypeError):
+        return body
+
+
+def request_key_hash(
+    method: str,
+    url: str,
+    headers: Optional[Dict[str, str]] = None,
+    body: Optional[bytes] = None,
+    query: Optional[Dict[str, Any]] = None,
+    vary_headers: Optional[List[str]] = None,
+) -> str:
+    """Hash part of a cache key (`<prefix>:cache:<hash>`) for a request.
+
+    Hashes the canonical form of {"method", "url", "query", "headers"}
+    plus "body_hash" (first 16 hex chars of the body's SHA-256) for
+    POST/PUT/PATCH with a body. Only the Accept, Accept-Language and
+    Content-Type headers are included. Headers in vary_headers (the
+    target's cache.vary_headers, matched case-insensitively) that the
+    request sends are added as "vary", keyed by lower-case name; requests
+    sending none of them keep the key they had without vary_headers.
+    """
+    key_data: Dict[str, Any] = {
+        "method": method.upper(),
+        "url": url,
+        "query": query or {},
+        "headers": {h: headers[h] for h in SIGNIFICANT_HEADERS if h in (headers or {})},
+    }
+    vary = {name.lower() for name in vary_headers or []}
+    varied = {name.lower(): value for name, value in (headers or {}).items() if name.lower() in vary}
+    if varied:
+        key_data["vary"] = varied
+    if body and method.upper() in ["POST", "PUT", "PATCH"]:
+        key_data["body_hash"] = hashlib.sha256(body).hexdigest()[:16]
+    return canonical_hash(key_data)
@@ -1,75 +1,236 @@
 """Circuit breaker implementation - universal for any upstream."""
 import threading
 import time
-from collections import defaultdict
-from typing import Dict
+from collections import defaultdict, deque
+from typing import Any, Deque, Dict, List, Optional, Tuple
 
 
 class CircuitBreaker:
-    """Simple circuit breaker with failure counting and TTL.
-    
+    """Circuit breaker with failure counting, TTL and a half-open probe phase.
+
     Universal implementation that works for any upstream identifier (provider, route, etc.).
-    
+
+    States:
+    - closed: requests pass; `failures_to_open` consecutive failures open the circuit.
+      With `decay_half_life_s`, failures and successes are instead weighted by age
+      (a weight halves every decay_half_life_s): the circuit opens once the decayed
+      failure score reaches `failures_to_open` and the decayed failure rate
+      failures / (failures + successes) reaches `failure_rate_threshold`, so an
+      isolated old failure no longer counts like a recent one
+    - open: requests are rejected for `open_ttl_s`
+    - half-open: after the TTL, at most `half_open_max_calls` probe requests are let
+      through at a time; `success_threshold` consecutive probe successes close the
+      circuit, any probe failure opens it again
+
+    A probe that never reports back (e.g. the request ended with a 4xx) frees its
+    slot after `open_ttl_s`, so the circuit cannot get stuck half-open.
+
     Note: Uses threading.Lock for thread-safety in async contexts where multiple
     concurrent requests may update the same upstream's failure count.
     """
 
-    def __init__(self, failures_to_open: int = 3, open_ttl_s: int = 60):
+    def __init__(
+        self,
+        failures_to_open: int = 3,
+        open_ttl_s: int = 60,
+        half_open_max_calls: int = 1,
+        success_threshold: int = 1,
+        decay_half_life_s: Optional[float] = None,
+        failure_rate_threshold: float = 0.5,
+    ):
         """
         Args:
             failures_to_open: Number of consecutive failures before opening circuit
             open_ttl_s: Time in seconds before attempting to close circuit again
+            half_open_max_calls: Probe requests allowed through at a time while half-open
+            success_threshold: Consecutive probe successes needed to close the circuit
+            decay_half_life_s: Age at which a failure or success counts half (None: consecutive failures)
+            failure_rate_threshold: Decayed failure rate also required to open (with decay_half_life_s)
         """
         self.failures_to_open = failures_to_open
         self.open_ttl_s = open_ttl_s
+        self.half_open_max_calls = half_open_max_calls
+        self.success_threshold = success_threshold
+        self.decay_half_life_s = decay_half_life_s
+        self.failure_rate_threshold = failure_rate_threshold
         self.failure_counts: Dict[str, int] = defaultdict(int)
         self.opened_at: Dict[str, float] = {}
+        # Half-open upstreams: start times of in-flight probes, consecutive probe successes
+        self.probes: Dict[str, Deque[float]] = {}
+        self.probe_successes: Dict[str, int] = {}
+        # Decayed [failure score, success score, updated at] per upstream (decay_half_life_s)
+        self.decayed: Dict[str, List[float]] = {}
         self._lock = threading.Lock()  # Thread-safe lock for async context
 
+    def _open(self, upstream: str, now: float) -> None:
+        self.opened_at[upstream] = now
+        self.probes.pop(upstream, None)
+        self.probe_successes.pop(upstream, None)
+
+    def _close(self, upstream: str) -> None:
+        self.failure_counts[upstream] = 0
+        self.decayed.pop(upstream, None)
+        self.opened_at.pop(upstream, None)
+        self.probes.pop(upstream, None)
+        self.probe_successes.pop(upstream, None)
+
+    def _decayed(self, upstream: str, now: float) -> Tuple[float, float]:
+        """(failure score, success score) decayed to now (lock must be held)."""
+        if upstream not in self.decayed:
+            return 0.0, 0.0
+        failures, successes, updated_at = self.decayed[upstream]
+        factor = 0.5 ** (max(now - updated_at, 0.0) / self.decay_half_life_s)
+        return failures * factor, successes * factor
+
+    def _record_decayed(self, upstream: str, now: float, failed: bool) -> Tuple[float, float]:
+        failures, successes = self._decayed(upstream, now)
+        if failed:
+            failures += 1
+        else:
+            successes += 1
+        self.decayed[upstream] = [failures, successes, now]
+        return failures, successes
+
+    def _state(self, upstream: str, now: float) -> str:
+        """Current state; moves an expired open circuit to half-open (lock must be held)."""
+        if upstream in self.probes:
+            probes = self.probes[upstream]
+            while probes and now - probes[0] >= self.open_ttl_s:
+                probes.popleft()
+            return "half-open"
+        if upstream in self.opened_at:
+            if now - self.opened_at[upstream] < self.open_ttl_s:
+                return "open"
+            del self.opened_at[upstream]
+            self.probes[upstream] = deque()
+            self.probe_successes[upstream] = 0
+            return "half-open"
+        return "closed"
+
     def record_success(self, upstream: str) -> None:
-        """Reset failure count on success."""
+        """Reset failure count on success; count a probe success when half-open."""
         with self._lock:
-            self.failure_counts[upstream] = 0
-            if upstream in self.opened_at:
-                del self.opened_at[upstream]
+            now = time.time()
+            if self._state(upstream, now) != "half-open":
+                self.failure_counts[upstream] = 0
+                if self.decay_half_life_s:
+                    self._record_decayed(upstream, now, failed=False)
+                return
+            if self.probes[upstream]:
+                self.probes[upstream].popleft()
+            self.probe_successes[upstream] += 1
+            if self.probe_successes[upstream] >= self.success_threshold:
+                self._close(upstream)
 
     def record_failure(self, upstream: str) -> None:
         """Record a failure and check if circuit should open."""
         with self._lock:
+            now = time.time()
+            if self._state(upstream, now) == "half-open":
+                # Failed probe: back to open for another TTL
+                self._open(upstream, now)
+                return
             self.failure_counts[upstream] += 1
-            if self.failure_counts[upstream] >= self.failures_to_open:
-                self.opened_at[upstream] = time.time()
+            if self.decay_half_life_s:
+                failures, successes = self._record_decayed(upstream, now, failed=True)
+                if (
+                    failures >= self.failures_to_open
+                    and failures / (failures + successes) >= self.failure_rate_threshold
+                ):
+                    self._open(upstream, now)
+            elif self.failure_counts[upstream] >= self.failures_to_open:
+                self._open(upstream, now)
 
-    def is_open(self, upstream: str) -> bool:
-        """Check if circuit is open for upstream."""
+    def allow_request(self, upstream: str) -> bool:
+        """Whether a request may go to the upstream; claims a probe slot when half-open."""
         with self._lock:
-            if upstream not in self.opened_at:
+            now = time.time()
+            state = self._state(upstream, now)
+            if state == "open":
                 return False
-
-            opened_time = self.opened_at[upstream]
-            if time.time() - opened_time >= self.open_ttl_s:
-                # Auto-close after TTL
-                self.failure_counts[upstream] = 0
-                del self.opened_at[upstream]
-                return False
-
+            if state == "half-open":
+                if len(self.probes[upstream]) >= self.half_open_max_calls:
+                    return False
+                self.probes[upstream].append(now)
             return True
 
+    def is_open(self, upstream: str) -> bool:
+        """Check if circuit is open for upstream (half-open counts as open once all probe slots are taken)."""
+        with self._lock:
+            state = self._state(upstream, time.time())
+            if state == "half-open":
+                return len(self.probes[upstream]) >= self.half_open_max_calls
+            return state == "open"
+
     def get_state(self, upstream: str) -> str:
         """Get circuit state: 'closed', 'open', or 'half-open'."""
         with self._lock:
-            # Check if circuit is open (inline logic to avoid deadlock)
-            if upstream in self.opened_at:
-                opened_time = self.opened_at[upstream]
-                if time.time() - opened_time >= self.open_ttl_s:
-                    # Auto-close after TTL
-                    self.failure_counts[upstream] = 0
-                    del self.opened_at[upstream]
-                else:
-                    return "open"
-            
-            if self.failure_counts[upstream] > 0:
-                return "half-open"
-            return "closed"
+            return self._state(upstream, time.time())
+
+    def snapshot(self, upstream: str) -> Dict[str, Any]:
+        """State and counters for one upstream (for /circuit)."""
+        with self._lock:
+            now = time.time()
+            state = self._state(upstream, now)
+            opened_at = self.opened_at.get(upstream)
+            failure_score = failure_rate = None
+            if self.decay_half_life_s:
+                failures, successes = self._decayed(upstream, now)
+                failure_score = round(failures, 3)
+                failure_rate = round(failures / (failures + successes), 3) if failures + successes else 0.0
+            return {
+                "state": state,
+                "failures": self.failure_counts.get(upstream, 0),
+                "failures_to_open": self.failures_to_open,
+                "failure_score": failure_score,
+                "failure_rate": failure_rate,
+                "failure_rate_threshold": self.failure_rate_threshold if self.decay_half_life_s else None,
+                "opened_at": opened_at,
+                "retry_at": opened_at + self.open_ttl_s if opened_at else None,
+                "half_open_calls": len(self.probes[upstream]) if state == "half-open" else 0,
+                "half_open_max_calls": self.half_open_max_calls,
+                "half_open_successes": self.probe_successes.get(upstream, 0),
+                "success_threshold": self.success_threshold,
+            }
+
+
+class CircuitRegistry:
+    """One circuit breaker per target, shared by all requests (in-process).
+
+    Upstream clients are created per request, so the breaker has to live
+    outside them for failures to accumulate across requests.
+    """
+
+    def __init__(self):
+        self._breakers: Dict[str, CircuitBreaker] = {}
+        self._lock = threading.Lock()
+
+    def get(self, target: str, circuit_config: Optional[Dict[str, Any]] = None) -> CircuitBreaker:
+        """Breaker for a target, created (or retuned after a config change) from `circuit` config."""
+        circuit_config = circuit_config or {}
+        settings = {
+            "failures_to_open": circuit_config.get("error_threshold", 5),
+            "open_ttl_s": circuit_config.get("cooldown_s", 60),
+            "half_open_max_calls": circuit_config.get("half_open_max_calls", 1),
+            "success_threshold": circuit_config.get("success_threshold", 1),
+            "decay_half_life_s": circuit_config.get("decay_half_life_s"),
+            "failure_rate_threshold": circuit_config.get("failure_rate_threshold", 0.5),
+        }
+        with self._lock:
+            breaker = self._breakers.get(target)
+            if breaker is None:
+                breaker = CircuitBreaker(**settings)
+                self._breakers[target] = breaker
+            else:
+                for name, value in settings.items():
+                    setattr(breaker, name, value)
+            return breaker
+
+    def reset(self) -> None:
+        """Forget all breakers."""
+        with self._lock:
+            self._breakers.clear()
 
 
+# Process-wide registry used by create_http_client
+circuit_breakers = CircuitRegistry()
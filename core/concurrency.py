@@ -0,0 +1,158 @@
+"""Per-target concurrency limits with a bounded queue wait.
+
+`concurrency.max_concurrent` caps in-flight upstream requests per target.
+Requests over the cap wait for a slot; with `max_queue_wait_ms` set they
+give up after that long (503 QUEUE_TIMEOUT), so saturation fails fast
+while admitted requests still get the full upstream `timeout_ms`. With
+`load_shedding` set they are rejected at once instead (503 OVERLOADED).
+
+Both rejections carry a Retry-After hint: the time for the slots to drain
+the queue ahead of the request, from the in-flight count and the observed
+upstream latency.
+"""
+import asyncio
+import logging
+import math
+import time
+from dataclasses import dataclass
+from typing import Any, Dict, Optional
+
+from reliapi.metrics.prometheus import load_shedding_active, load_shed_total, queue_timeouts_total
+
+logger = logging.getLogger(__name__)
+
+# Smoothing for the observed slot hold time (weight of the newest sample)
+LATENCY_EWMA_ALPHA = 0.2
+# Retry-After hint before any latency has been observed
+DEFAULT_RETRY_AFTER_S = 1.0
+
+
+class QueueTimeout(Exception):
+    """No concurrency slot became free within max_queue_wait_ms."""
+
+    def __init__(self, target: str, max_queue_wait_ms: int, retry_after_s: Optional[float] = None):
+        super().__init__(f"Target '{target}' is saturated: no slot within {max_queue_wait_ms}ms")
+        self.target = target
+        self.max_queue_wait_ms = max_queue_wait_ms
+        self.retry_after_s = retry_after_s
+
+
+class Overloaded(QueueTimeout):
+    """All slots busy on a target with load_shedding; rejected without queueing."""
+
+    def __init__(self, target: str, retry_after_s: float):
+        super().__init__(target, 0, retry_after_s)
+        self.args = (f"Target '{target}' is overloaded: all concurrency slots are busy",)
+
+
+@dataclass
+class TargetLoad:
+    """Slot usage and observed hold time for one target."""
+
+    max_concurrent: int
+    semaphore: asyncio.Semaphore
+    in_flight: int = 0
+    waiting: int = 0
+    avg_latency_s: Optional[float] = None
+
+    def observe(self, latency_s: float) -> None:
+        if self.avg_latency_s is None:
+            self.avg_latency_s = latency_s
+        else:
+            self.avg_latency_s += LATENCY_EWMA_ALPHA * (latency_s - self.avg_latency_s)
+
+    def retry_after_s(self, queued: bool = False) -> float:
+        """Seconds until the slots drain the queue up to the request (whole seconds, >= 1).
+
+        Args:
+            queued: The request is already counted in `waiting`
+        """
+        if self.avg_latency_s is None:
+            return DEFAULT_RETRY_AFTER_S
+        drain_rate = self.max_concurrent / max(self.avg_latency_s, 0.001)
+        # Every slot is busy; one slot hold drains max_concurrent queued requests
+        backlog = self.waiting + (0 if queued else 1)
+        return float(max(math.ceil(backlog / drain_rate), 1))
+
+
+@dataclass
+class ConcurrencySlot:
+    """An acquired slot; release() is idempotent."""
+
+    semaphore: asyncio.Semaphore
+    released: bool = False
+    load: Optional[TargetLoad] = None
+    target: Optional[str] = None
+    acquired_at: float = 0.0
+
+    def release(self) -> None:
+        if not self.released:
+            self.released = True
+            self.semaphore.release()
+            if self.load is not None:
+                self.load.in_flight -= 1
+                self.load.observe(time.monotonic() - self.acquired_at)
+                if self.load.in_flight < self.load.max_concurrent:
+                    load_shedding_active.labels(target=self.target).set(0)
+
+
+class ConcurrencyLimiter:
+    """Semaphores per target (in-process)."""
+
+    def __init__(self):
+        self._targets: Dict[str, TargetLoad] = {}
+
+    def _load(self, target: str, max_concurrent: int) -> TargetLoad:
+        current = self._targets.get(target)
+        if current is None or current.max_concurrent != max_concurrent:
+            # New or resized limit; slots held on a replaced semaphore release to it
+            current = TargetLoad(max_concurrent, asyncio.Semaphore(max_concurrent))
+            self._targets[target] = current
+        return current
+
+    async def acquire(self, target: str, config: Optional[Dict[str, Any]]) -> Optional[ConcurrencySlot]:
+        """Wait for a slot on the target.
+
+        Args:
+            target: Target name
+            config: Target `concurrency` config (max_concurrent, max_queue_wait_ms, load_shedding)
+
+        Returns:
+            The slot to release when the upstream call ends, or None if the target is unlimited
+
+        Raises:
+            Overloaded: If load_shedding is set and every slot is busy
+            QueueTimeout: If max_queue_wait_ms passed without a free slot
+        """
+        if not config or not config.get("max_concurrent"):
+            return None
+
+        load = self._load(target, config["max_concurrent"])
+        semaphore = load.semaphore
+        if config.get("load_shedding") and semaphore.locked():
+            retry_after_s = load.retry_after_s()
+            load_shedding_active.labels(target=target).set(1)
+            load_shed_total.labels(target=target).inc()
+            logger.warning(f"Load shed for target '{target}': all {load.max_concurrent} slots busy")
+            raise Overloaded(target, retry_after_s)
+
+        max_queue_wait_ms = config.get("max_queue_wait_ms")
+        load.waiting += 1
+        try:
+            if max_queue_wait_ms is None:
+                await semaphore.acquire()
+            else:
+                try:
+                    await asyncio.wait_for(semaphore.acquire(), timeout=max_queue_wait_ms / 1000.0)
+                except asyncio.TimeoutError:
+                    queue_timeouts_total.labels(target=target).inc()
+                    logger.warning(f"Queue timeout for target '{target}' after {max_queue_wait_ms}ms")
+                    raise QueueTimeout(target, max_queue_wait_ms, load.retry_after_s(queued=True)) from None
+        finally:
+            load.waiting -= 1
+        load.in_flight += 1
+        return ConcurrencySlot(semaphore, load=load, target=target, acquired_at=time.monotonic())
+
+
+# Process-wide limiter used by the proxy handlers
+concurrency_limiter = ConcurrencyLimiter()
@@ -0,0 +1,92 @@
+"""Per-target content-type rules for the HTTP proxy (`content_types`).
+
+One upstream often mixes JSON APIs, event streams and binary downloads.
+`content_types` is a list of rules, each matching a media type (`type/sub`,
+`type/*` or `*/*`; parameters such as charset are ignored) of the request
+or, by default, the response. The first matching rule in list order
+decides how the response is handled:
+
+- stream: relay a successful body to the client as it arrives
+- cache: false to never store it (request rules also skip the lookup)
+- transform: false to skip response_transform
+- extract: dotted path whose value replaces the JSON body
+- body: how the body is decoded into data.body (auto, text, base64)
+
+Request rules are known before the call, so they also decide the cache
+lookup; response rules only apply once the response headers arrive.
+Without a matching rule the target's regular settings apply.
+"""
+from typing import Any, Dict, List, Mapping, Optional, Tuple
+
+from reliapi.core.response_transform import parse_path
+
+
+def parse_media_range(pattern: str) -> Tuple[str, str]:
+    """(type, subtype) of a rule's `match`; either may be "*".
+
+    Raises:
+        ValueError: If the pattern is not type/subtype
+    """
+    media_type, _, subtype = pattern.split(";")[0].strip().lower().partition("/")
+    if not media_type or not subtype or (media_type == "*" and subtype != "*"):
+        raise ValueError(f"Invalid media type '{pattern}' (expected type/subtype, type/* or */*)")
+    return media_type, subtype
+
+
+def content_type(headers: Optional[Mapping[str, str]]) -> Optional[str]:
+    """Media type of a Content-Type header (lowercase, without parameters)."""
+    value = next((v for name, v in (headers or {}).items() if name.lower() == "content-type"), None)
+    if not value:
+        return None
+    return value.split(";")[0].strip().lower() or None
+
+
+def _matches(pattern: str, media: Optional[str]) -> bool:
+    if not media:
+        return False
+    media_type, subtype = parse_media_range(pattern)
+    actual_type, _, actual_subtype = media.partition("/")
+    return media_type in ("*", actual_type) and subtype in ("*", actual_subtype)
+
+
+def select_content_rule(
+    rules: Optional[List[Dict[str, Any]]],
+    request_headers: Optional[Mapping[str, str]],
+    response_headers: Optional[Mapping[str, str]] = None,
+) -> Dict[str, Any]:
+    """First rule matching the request or response Content-Type, or {} if none does.
+
+    Before the response is known (response_headers None), response rules
+    are skipped.
+    """
+    request_type = content_type(request_headers)
+    response_type = content_type(response_headers)
+    for rule in rules or []:
+        if rule.get("on", "response") == "request":
+            if _matches(rule["match"], request_type):
+                return rule
+        elif response_headers is not None and _matches(rule["match"], response_type):
+            return rule
+    return {}
+
+
+def has_stream_rule(rules: Optional[List[Dict[str, Any]]]) -> bool:
+    """Whether any rule may stream a response."""
+    return any(rule.get("stream") for rule in rules or [])
+
+
+def _extract(node: Any, segments: List[Tuple[str, bool]]) -> Any:
+    if not segments:
+        return node
+    (key, is_list), rest = segments[0], segments[1:]
+    if not isinstance(node, dict) or key not in node:
+        return None
+    child = node[key]
+    if is_list:
+        return [_extract(item, rest) for item in child] if isinstance(child, list) else None
+    return _extract(child, rest)
+
+
+def extract_field(body: Any, path: str) -> Any:
+    """Value at a dotted path (a list for `[]` segments), or None if it is missing."""
+    return _extract(body, parse_path(path))
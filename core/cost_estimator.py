@@ -11,6 +11,7 @@ class CostEstimator:
     - OpenAI: https://openai.com/pricing
     - Anthropic: https://www.anthropic.com/pricing
     - Mistral: https://mistral.ai/pricing
+    - Gemini: https://ai.google.dev/pricing
     """
     
     # Approximate pricing per 1K tokens (simplified, per-model)
@@ -35,8 +36,56 @@ class CostEstimator:
             "mistral-medium-latest": {"prompt": 0.0027, "completion": 0.0081},
             "mistral-small-latest": {"prompt": 0.0002, "completion": 0.0006},
         },
+        "gemini": {
+            "gemini-1.5-pro": {"prompt": 0.00125, "completion": 0.005},
+            "gemini-1.5-flash": {"prompt": 0.000075, "completion": 0.0003},
+            "gemini-2.0-flash": {"prompt": 0.0001, "completion": 0.0004},
+        },
+    }
+    
+    # Embedding models, per 1K input tokens
+    EMBEDDING_PRICING_PER_1K = {
+        "text-embedding-3-small": 0.00002,
+        "text-embedding-3-large": 0.00013,
+        "text-embedding-ada-002": 0.0001,
+        "mistral-embed": 0.0001,
     }
     
+    @classmethod
+    def embedding_cost(cls, model: Optional[str], prompt_tokens: int) -> Optional[float]:
+        """Cost of an embeddings call from its usage, or None if the model is unpriced."""
+        price = cls.EMBEDDING_PRICING_PER_1K.get(model or "")
+        if price is None:
+            return None
+        return (prompt_tokens / 1000.0) * price
+    
+    @classmethod
+    def is_priced(cls, provider: str, model: str) -> bool:
+        """Whether pricing is known for provider+model."""
+        return model in cls.PRICING_PER_1K.get(provider, {})
+    
+    @classmethod
+    def fallback_pricing(cls, provider: str) -> Dict[str, float]:
+        """Pricing for unpriced models: the most expensive known model of the provider.
+        
+        Deliberately pessimistic so approximate estimates still bound spend.
+        Falls back to all providers if the provider itself is unknown.
+        """
+        models = list(cls.PRICING_PER_1K.get(provider, {}).values())
+        if not models:
+            models = [p for prices in cls.PRICING_PER_1K.values() for p in prices.values()]
+        return {
+            "prompt": max(p["prompt"] for p in models),
+            "completion": max(p["completion"] for p in models),
+        }
+    
+    @classmethod
+    def _pricing(cls, provider: str, model: str, approximate: bool) -> Optional[Dict[str, float]]:
+        pricing = cls.PRICING_PER_1K.get(provider, {}).get(model)
+        if not pricing and approximate:
+            pricing = cls.fallback_pricing(provider)
+        return pricing
+    
     @classmethod
     def estimate_cost(
         cls,
@@ -44,20 +93,22 @@ class CostEstimator:
         model: str,
         prompt_tokens: int,
         max_tokens: Optional[int] = None,
+        approximate: bool = False,
     ) -> Optional[float]:
         """
         Estimate cost for LLM request.
         
         Args:
-            provider: Provider name (openai, anthropic, mistral)
+            provider: Provider name (openai, anthropic, mistral, gemini)
             model: Model name
             prompt_tokens: Estimated prompt tokens (or actual if available)
             max_tokens: Maximum completion tokens (for worst-case estimate)
+            approximate: Use fallback pricing if the model is unpriced
             
         Returns:
             Estimated cost in USD, or None if pricing unknown
         """
-        pricing = cls.PRICING_PER_1K.get(provider, {}).get(model)
+        pricing = cls._pricing(provider, model, approximate)
         if not pricing:
             return None
         
@@ -82,15 +133,50 @@ class CostEstimator:
         model: str,
         messages: list,
         max_tokens: Optional[int] = None,
+        approximate: bool = False,
     ) -> Optional[float]:
         """
         Estimate cost from messages list.
         
         Rough estimation: ~4 chars per token for English text.
         """
-        # Rough token estimation: ~4 chars per token
-        total_chars = sum(len(msg.get("content", "")) for msg in messages)
-        estimated_prompt_tokens = total_chars // 4
-        
-        return cls.estimate_cost(provider, model, estimated_prompt_tokens, max_tokens)
+        estimated_prompt_tokens = cls.estimate_prompt_tokens(messages)
+        return cls.estimate_cost(provider, model, estimated_prompt_tokens, max_tokens, approximate)
+    
+    @staticmethod
+    def estimate_tokens(text: str) -> int:
+        """Rough token count: ~4 chars per token for English text."""
+        return len(text) // 4
+    
+    @classmethod
+    def estimate_prompt_tokens(cls, messages: list) -> int:
+        """Rough prompt token count of a messages list."""
+        return cls.estimate_tokens("".join(msg.get("content", "") for msg in messages))
+    
+    @classmethod
+    def approximate_cost_from_usage(
+        cls,
+        provider: str,
+        model: str,
+        prompt_tokens: int,
+        completion_tokens: int,
+    ) -> float:
+        """Actual-usage cost for an unpriced model, using fallback pricing."""
+        breakdown = cls.approximate_cost_breakdown_from_usage(provider, model, prompt_tokens, completion_tokens)
+        return breakdown["input_usd"] + breakdown["output_usd"]
+
+    @classmethod
+    def approximate_cost_breakdown_from_usage(
+        cls,
+        provider: str,
+        model: str,
+        prompt_tokens: int,
+        completion_tokens: int,
+    ) -> Dict[str, float]:
+        """approximate_cost_from_usage split into {"input_usd", "output_usd"}."""
+        pricing = cls._pricing(provider, model, approximate=True)
+        return {
+            "input_usd": (prompt_tokens / 1000.0) * pricing["prompt"],
+            "output_usd": (completion_tokens / 1000.0) * pricing["completion"],
+        }
 
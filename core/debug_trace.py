@@ -0,0 +1,57 @@
+"""In-memory ring buffer of recent request traces for live debugging (`debug_trace`).
+
+During an incident, GET /debug/recent shows what the proxy has just been
+doing without turning on verbose logging: target, model, status,
+cache/idempotent hits, timing breakdown and retries of the last `size`
+requests (a `sample_rate` fraction of them). Traces never contain prompts
+or bodies; the fields in `redact` are replaced with REDACTED as well.
+The buffer is per process and lost on restart.
+"""
+import random
+import threading
+import time
+from collections import deque
+from typing import Any, Deque, Dict, List, Optional
+
+# Trace fields that may carry caller data, and can be redacted
+REDACTABLE_FIELDS = ("tenant", "key_id", "tags", "metadata", "error_message")
+
+DEFAULT_REDACT = ["metadata", "error_message"]
+
+REDACTED = "[redacted]"
+
+
+class DebugTraceBuffer:
+    """Keeps the last `size` sampled request traces (oldest dropped first)."""
+
+    def __init__(self, size: int = 200, sample_rate: float = 1.0, redact: Optional[List[str]] = None):
+        """
+        Args:
+            size: Maximum traces kept
+            sample_rate: Fraction of requests traced (0.0-1.0)
+            redact: Trace fields replaced with REDACTED (default DEFAULT_REDACT)
+        """
+        self.sample_rate = sample_rate
+        self.redact = set(DEFAULT_REDACT if redact is None else redact)
+        self._traces: Deque[Dict[str, Any]] = deque(maxlen=size)
+        self._lock = threading.Lock()
+
+    def record(self, **trace: Any) -> bool:
+        """Add a trace (if sampled); returns whether it was kept."""
+        if self.sample_rate < 1.0 and random.random() >= self.sample_rate:
+            return False
+        entry = {"ts": time.time(), **trace}
+        for name in self.redact:
+            if entry.get(name) is not None:
+                entry[name] = REDACTED
+        with self._lock:
+            self._traces.append(entry)
+        return True
+
+    def recent(self, limit: Optional[int] = None, target: Optional[str] = None) -> List[Dict[str, Any]]:
+        """Traces newest first, optionally only for one target."""
+        with self._lock:
+            traces = list(reversed(self._traces))
+        if target:
+            traces = [t for t in traces if t.get("target") == target]
+        return traces[:limit] if limit else traces
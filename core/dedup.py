@@ -0,0 +1,126 @@
+"""Content-hash deduplication of identical requests within a short window."""
+import hashlib
+import json
+import logging
+from typing import Any, Dict, Optional, Tuple
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+# How long the first request may stay in flight before duplicates stop waiting
+IN_FLIGHT_TTL_S = 300
+
+
+class RequestDeduplicator:
+    """Detects identical request bodies arriving within a configurable window.
+
+    Unlike idempotency keys, duplicates are identified by a hash of the
+    request content. The first request claims the hash for `window_ms`;
+    duplicates arriving in that window can wait for its result (coalesce)
+    or be rejected.
+    """
+
+    def __init__(self, redis_url: str, key_prefix: str = "reliapi"):
+        """
+        Args:
+            redis_url: Redis connection URL
+            key_prefix: Prefix for dedup keys
+        """
+        self.key_prefix = key_prefix
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Deduplicator connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Deduplicator connection failed (graceful degradation): {e}", exc_info=True)
+
+    def _make_key(self, kind: str, content_hash: str, tenant: Optional[str] = None) -> str:
+        # Multi-tenant isolation: identical bodies from different tenants are not duplicates
+        if tenant:
+            return f"{self.key_prefix}:tenant:{tenant}:dedup_{kind}:{content_hash}"
+        return f"{self.key_prefix}:dedup_{kind}:{content_hash}"
+
+    @staticmethod
+    def make_content_hash(kind: str, target: str, payload: Dict[str, Any]) -> str:
+        """Hash request content (kind, target and request fields)."""
+        key_str = json.dumps({"kind": kind, "target": target, "payload": payload}, sort_keys=True, default=str)
+        return hashlib.sha256(key_str.encode()).hexdigest()
+
+    def claim(
+        self,
+        content_hash: str,
+        request_id: str,
+        window_ms: int,
+        tenant: Optional[str] = None,
+    ) -> Tuple[bool, Optional[str]]:
+        """Claim a content hash for the dedup window.
+
+        Returns:
+            (is_first, first_request_id)
+            - is_first: True if no identical request arrived within the window
+            - first_request_id: Request ID of the first request (if duplicate)
+        """
+        if not self.enabled or not self.client:
+            return True, None
+
+        claim_key = self._make_key("claim", content_hash, tenant)
+        try:
+            # Atomic SET NX PX: only one request per window wins the claim
+            if self.client.set(claim_key, request_id, nx=True, px=window_ms):
+                self.client.setex(self._make_key("in_flight", content_hash, tenant), IN_FLIGHT_TTL_S, request_id)
+                return True, None
+            return False, self.client.get(claim_key)
+        except Exception as e:
+            logger.warning(f"Dedup claim error (graceful degradation): {e}", exc_info=True)
+            return True, None
+
+    def is_in_flight(self, content_hash: str, tenant: Optional[str] = None) -> bool:
+        """Check if the first request for this hash is still running."""
+        if not self.enabled or not self.client:
+            return False
+        try:
+            return self.client.exists(self._make_key("in_flight", content_hash, tenant)) > 0
+        except Exception as e:
+            logger.warning(f"Dedup is_in_flight error (graceful degradation): {e}", exc_info=True)
+            return False
+
+    def get_result(self, content_hash: str, tenant: Optional[str] = None) -> Optional[Dict[str, Any]]:
+        """Get the first request's result, if finished."""
+        if not self.enabled or not self.client:
+            return None
+        try:
+            result = self.client.get(self._make_key("result", content_hash, tenant))
+            return json.loads(result) if result else None
+        except Exception as e:
+            logger.warning(f"Dedup get_result error (graceful degradation): {e}", exc_info=True)
+            return None
+
+    def store_result(
+        self,
+        content_hash: str,
+        result: Dict[str, Any],
+        window_ms: int,
+        tenant: Optional[str] = None,
+    ) -> None:
+        """Store the first request's result for duplicates within the window."""
+        if not self.enabled or not self.client:
+            return
+        try:
+            self.client.set(self._make_key("result", content_hash, tenant), json.dumps(result), px=window_ms)
+        except (TypeError, ValueError) as e:
+            logger.warning(f"Dedup store_result: cannot serialize result: {e}", exc_info=True)
+        except Exception as e:
+            logger.warning(f"Dedup store_result error (graceful degradation): {e}", exc_info=True)
+
+    def finish(self, content_hash: str, tenant: Optional[str] = None) -> None:
+        """Clear the in-flight marker once the first request completes."""
+        if not self.enabled or not self.client:
+            return
+        try:
+            self.client.delete(self._make_key("in_flight", content_hash, tenant))
+        except Exception as e:
+            logger.warning(f"Dedup finish error (graceful degradation): {e}", exc_info=True)
@@ -0,0 +1,140 @@
+"""Per-input caching for batch embeddings requests.
+
+With `cache.embeddings` on, a POST to an `/embeddings` path with a JSON
+`input` list is cached per input instead of per request: inputs already
+cached are served locally, only the rest are sent upstream, and the
+results are merged back in the original order. Usage (and so cost) in the
+response covers the upstream misses only.
+"""
+import json
+import logging
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from reliapi.core.cache import Cache, CacheValueTooLarge, DEFAULT_TTL_JITTER
+from reliapi.core.canonical_json import canonical_json
+
+logger = logging.getLogger(__name__)
+
+
+class EmbeddingsMismatch(ValueError):
+    """The upstream embeddings response does not line up with the inputs sent.
+
+    The upstream call was made (and billed), so its `usage` and `model` are
+    kept for the error response.
+    """
+
+    def __init__(self, message: str, usage: Optional[Dict[str, Any]] = None, model: Optional[str] = None):
+        super().__init__(message)
+        self.usage = usage
+        self.model = model
+
+
+@dataclass
+class EmbeddingsBatch:
+    """One batch embeddings request split into cached and missing inputs."""
+
+    request: Dict[str, Any]
+    inputs: List[str]
+    # Position in `inputs` -> cached embedding item (without index)
+    cached: Dict[int, Dict[str, Any]] = field(default_factory=dict)
+    model: Optional[str] = None
+
+    @classmethod
+    def parse(cls, method: str, path: str, body: Optional[bytes]) -> Optional["EmbeddingsBatch"]:
+        """Batch for a POST .../embeddings body with string inputs, else None."""
+        if method.upper() != "POST" or not path.rstrip("/").endswith("/embeddings") or not body:
+            return None
+        try:
+            request = json.loads(body)
+        except (ValueError, UnicodeDecodeError):
+            return None
+        if not isinstance(request, dict):
+            return None
+        inputs = request.get("input")
+        if isinstance(inputs, str):
+            inputs = [inputs]
+        # Token-array inputs are passed through uncached
+        if not isinstance(inputs, list) or not inputs or not all(isinstance(text, str) for text in inputs):
+            return None
+        return cls(request=request, inputs=inputs, model=request.get("model"))
+
+    def _key(self, text: str) -> bytes:
+        # Every request parameter but the batch (model, dimensions, encoding_format, ...)
+        params = {name: value for name, value in self.request.items() if name != "input"}
+        return canonical_json({**params, "input": text}).encode()
+
+    @property
+    def misses(self) -> List[int]:
+        return [position for position in range(len(self.inputs)) if position not in self.cached]
+
+    def lookup(self, cache: Cache, url: str, tenant: Optional[str]) -> None:
+        """Fill `cached` from per-input cache entries."""
+        for position, text in enumerate(self.inputs):
+            entry = cache.get("POST", url, None, self._key(text), None, allow_post=True, tenant=tenant)
+            if entry and isinstance(entry.get("embedding"), dict):
+                self.cached[position] = entry["embedding"]
+                self.model = self.model or entry.get("model")
+
+    def upstream_body(self) -> bytes:
+        """Request body carrying only the inputs that missed the cache."""
+        return json.dumps({**self.request, "input": [self.inputs[position] for position in self.misses]}).encode()
+
+    def merge(self, body: Dict[str, Any]) -> Dict[str, Any]:
+        """Upstream response for the misses, with cached results merged back in input order.
+
+        Raises:
+            EmbeddingsMismatch: If the response has no embedding for every miss
+        """
+        misses = self.misses
+        items = body.get("data") if isinstance(body, dict) else None
+        usage = body.get("usage") if isinstance(body, dict) and isinstance(body.get("usage"), dict) else None
+        model = (body.get("model") if isinstance(body, dict) else None) or self.model
+        if not isinstance(items, list) or len(items) != len(misses):
+            raise EmbeddingsMismatch(
+                f"Upstream returned {len(items) if isinstance(items, list) else 0} embeddings for {len(misses)} inputs",
+                usage=usage, model=model,
+            )
+        by_index = {item.get("index", offset): item for offset, item in enumerate(items)}
+        if sorted(by_index) != list(range(len(misses))):
+            raise EmbeddingsMismatch("Upstream embedding indexes do not match the inputs sent", usage=usage, model=model)
+
+        fresh = {position: by_index[offset] for offset, position in enumerate(misses)}
+        return {**body, "data": self._assemble({**self.cached, **fresh})}
+
+    def cached_body(self) -> Dict[str, Any]:
+        """Response body when every input was cached (no upstream usage)."""
+        return {
+            "object": "list",
+            "data": self._assemble(self.cached),
+            "model": self.model,
+            "usage": {"prompt_tokens": 0, "total_tokens": 0},
+        }
+
+    def _assemble(self, items: Dict[int, Dict[str, Any]]) -> List[Dict[str, Any]]:
+        return [{**items[position], "index": position} for position in range(len(self.inputs))]
+
+    def store(
+        self,
+        cache: Cache,
+        url: str,
+        merged: Dict[str, Any],
+        ttl_s: int,
+        tenant: Optional[str],
+        ttl_jitter: float = DEFAULT_TTL_JITTER,
+    ) -> None:
+        """Cache each upstream result under its own input."""
+        model = merged.get("model") or self.model
+        for position in self.misses:
+            item = {name: value for name, value in merged["data"][position].items() if name != "index"}
+            try:
+                cache.set(
+                    "POST", url, None, self._key(self.inputs[position]), {"embedding": item, "model": model},
+                    ttl_s=ttl_s, allow_post=True, tenant=tenant, ttl_jitter=ttl_jitter,
+                )
+            except CacheValueTooLarge as e:
+                logger.warning(f"Embedding for input {position} not cached: {e}")
+
+    def meta(self) -> Dict[str, int]:
+        """`cache_hits`/`cache_misses` meta fields."""
+        return {"cache_hits": len(self.cached), "cache_misses": len(self.inputs) - len(self.cached)}
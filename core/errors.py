@@ -14,9 +14,14 @@ class ErrorCode(str, Enum):
     BAD_REQUEST = "BAD_REQUEST"
     NOT_FOUND = "NOT_FOUND"
     IDEMPOTENCY_CONFLICT = "IDEMPOTENCY_CONFLICT"
+    INVALID_IDEMPOTENCY_KEY = "INVALID_IDEMPOTENCY_KEY"  # Key fails the idempotency_keys rules
+    DUPLICATE_REQUEST = "DUPLICATE_REQUEST"
     STREAM_ALREADY_IN_PROGRESS = "STREAM_ALREADY_IN_PROGRESS"
     STREAM_ALREADY_COMPLETED = "STREAM_ALREADY_COMPLETED"
     STREAMING_UNSUPPORTED = "STREAMING_UNSUPPORTED"
+    UNSUPPORTED_PARAM = "UNSUPPORTED_PARAM"  # Request parameter not supported by the provider
+    PARAM_NOT_ALLOWED = "PARAM_NOT_ALLOWED"  # Request parameter outside the target's llm.allowed_params
+    TOO_MANY_TURNS = "TOO_MANY_TURNS"  # More messages than llm.max_messages (or the tenant's max_messages)
     RATE_LIMIT_RELIAPI = "RATE_LIMIT_RELIAPI"
     
     # Upstream errors (from target APIs)
@@ -25,9 +30,22 @@ class ErrorCode(str, Enum):
     NETWORK_ERROR = "NETWORK_ERROR"  # Network/timeout
     PROVIDER_ERROR = "PROVIDER_ERROR"  # Generic provider error
     UPSTREAM_STREAM_INTERRUPTED = "UPSTREAM_STREAM_INTERRUPTED"
+    STREAM_IDLE_TIMEOUT = "STREAM_IDLE_TIMEOUT"  # No chunk within idle_timeout_ms
+    SCHEMA_VIOLATION = "SCHEMA_VIOLATION"  # Response failed target response_schema
+    EMBEDDINGS_MISMATCH = "EMBEDDINGS_MISMATCH"  # Paid embeddings response did not line up with the inputs sent
+    
+    # Maintenance mode (503, served by ReliAPI)
+    MAINTENANCE_CACHE_ONLY = "MAINTENANCE_CACHE_ONLY"  # Cache miss while target is cache-only
+    TARGET_DISABLED = "TARGET_DISABLED"  # Target turned off via the admin kill switch
+    
+    # Saturation (503, served by ReliAPI)
+    QUEUE_TIMEOUT = "QUEUE_TIMEOUT"  # No concurrency slot within max_queue_wait_ms
+    OVERLOADED = "OVERLOADED"  # Every concurrency slot busy on a load_shedding target
     
     # Budget errors
     BUDGET_EXCEEDED = "BUDGET_EXCEEDED"
+    UNKNOWN_MODEL = "UNKNOWN_MODEL"  # No pricing for model (on_unknown_model: reject)
+    OUTPUT_CAP_EXCEEDED = "OUTPUT_CAP_EXCEEDED"  # max_tokens above llm.output_caps (on_exceed: reject)
     
     # Configuration errors
     INVALID_TARGET = "INVALID_TARGET"
@@ -36,6 +54,7 @@ class ErrorCode(str, Enum):
     
     # Internal errors
     INTERNAL_ERROR = "INTERNAL_ERROR"
+    PLUGIN_ERROR = "PLUGIN_ERROR"  # A target plugin failed, timed out or returned invalid output
     
     @classmethod
     def from_http_status(cls, status_code: int) -> "ErrorCode":
@@ -0,0 +1,193 @@
+"""gRPC-JSON transcoding for targets with a `grpc` section.
+
+A gRPC target is proxied through /proxy/http like any HTTP target: `path`
+names the method (`/package.Service/Method`), `body` is the request
+message as JSON, and the response body is the reply message as JSON. The
+transcoding happens in an httpx transport under UpstreamHTTPClient, so
+retries, circuit breaker, regions, caching and budgets apply unchanged.
+
+gRPC statuses are mapped to HTTP statuses (UNAVAILABLE -> 503,
+RESOURCE_EXHAUSTED -> 429, ...), so they are retried and reported like
+the equivalent HTTP errors. Error bodies are `{"error": {"code",
+"message"}}` with the gRPC status name as code. Request headers are sent
+as metadata; response metadata (initial and trailing) comes back as
+headers.
+
+The method schemas come from a compiled descriptor set (`protoc
+--include_imports --descriptor_set_out=api.pb ...`). Requires the grpcio
+and protobuf packages (`pip install grpcio protobuf`).
+"""
+import json
+import threading
+from typing import Any, Dict, List, Optional, Tuple
+
+import httpx
+
+# https://github.com/googleapis/googleapis/blob/master/google/rpc/code.proto
+GRPC_HTTP_STATUS = {
+    "OK": 200,
+    "CANCELLED": 499,
+    "UNKNOWN": 500,
+    "INVALID_ARGUMENT": 400,
+    "DEADLINE_EXCEEDED": 504,
+    "NOT_FOUND": 404,
+    "ALREADY_EXISTS": 409,
+    "PERMISSION_DENIED": 403,
+    "UNAUTHENTICATED": 401,
+    "RESOURCE_EXHAUSTED": 429,
+    "FAILED_PRECONDITION": 400,
+    "ABORTED": 409,
+    "OUT_OF_RANGE": 400,
+    "UNIMPLEMENTED": 501,
+    "INTERNAL": 500,
+    "UNAVAILABLE": 503,
+    "DATA_LOSS": 500,
+}
+
+# HTTP headers that are connection-level or set by the gRPC library itself
+_SKIPPED_HEADERS = {
+    "host", "connection", "content-length", "content-type", "accept", "accept-encoding",
+    "transfer-encoding", "te", "user-agent",
+}
+
+
+def request_metadata(headers: Dict[str, str]) -> List[Tuple[str, str]]:
+    """gRPC metadata for request headers (keys lowercased, hop-by-hop headers dropped)."""
+    return [
+        (name.lower(), value) for name, value in headers.items()
+        if name.lower() not in _SKIPPED_HEADERS and not name.lower().startswith(("grpc-", ":"))
+    ]
+
+
+def _json_response(status_code: int, body: Dict[str, Any], headers: Optional[Dict[str, str]] = None) -> httpx.Response:
+    return httpx.Response(
+        status_code,
+        headers={**(headers or {}), "content-type": "application/json"},
+        content=json.dumps(body).encode(),
+    )
+
+
+def error_response(code: str, message: str, headers: Optional[Dict[str, str]] = None) -> httpx.Response:
+    """HTTP response for a gRPC status (or a transcoding failure reported as one)."""
+    return _json_response(
+        GRPC_HTTP_STATUS.get(code, 500),
+        {"error": {"code": code, "message": message}},
+        {**(headers or {}), "grpc-status": code},
+    )
+
+
+class GrpcTranscoder:
+    """Descriptor set and channels of one gRPC target (shared across requests)."""
+
+    def __init__(self, config: Dict[str, Any]):
+        try:
+            from google.protobuf import descriptor_pb2, descriptor_pool, message_factory
+        except ImportError:
+            raise RuntimeError("gRPC targets require the grpcio and protobuf packages (pip install grpcio protobuf)")
+
+        with open(config["descriptor_set"], "rb") as f:
+            descriptor_set = descriptor_pb2.FileDescriptorSet.FromString(f.read())
+        pool = descriptor_pool.DescriptorPool()
+        for file_proto in descriptor_set.file:
+            pool.Add(file_proto)
+
+        # "/pkg.Service/Method" -> (request class, response class)
+        self.methods: Dict[str, Tuple[Any, Any]] = {}
+        for path in config["methods"]:
+            service, method = path.strip("/").split("/")
+            descriptor = pool.FindMethodByName(f"{service}.{method}")
+            self.methods[f"/{service}/{method}"] = (
+                message_factory.GetMessageClass(descriptor.input_type),
+                message_factory.GetMessageClass(descriptor.output_type),
+            )
+        self._channels: Dict[Tuple[str, str], Any] = {}
+        self._lock = threading.Lock()
+
+    def channel(self, scheme: str, authority: str):
+        """Channel to one upstream (grpcs:// uses TLS), kept for the process lifetime."""
+        import grpc
+
+        with self._lock:
+            key = (scheme, authority)
+            if key not in self._channels:
+                if scheme == "grpcs":
+                    self._channels[key] = grpc.aio.secure_channel(authority, grpc.ssl_channel_credentials())
+                else:
+                    self._channels[key] = grpc.aio.insecure_channel(authority)
+            return self._channels[key]
+
+    async def call(
+        self,
+        scheme: str,
+        authority: str,
+        path: str,
+        body: bytes,
+        headers: Dict[str, str],
+        timeout_s: Optional[float],
+    ) -> httpx.Response:
+        """Transcode a JSON request, call the method, transcode the reply."""
+        import grpc
+        from google.protobuf import json_format
+
+        if path not in self.methods:
+            return error_response("UNIMPLEMENTED", f"Method {path} is not configured for this target")
+        request_class, response_class = self.methods[path]
+        try:
+            message = json_format.Parse(body or b"{}", request_class())
+        except json_format.ParseError as e:
+            return error_response("INVALID_ARGUMENT", f"Request body does not match {request_class.DESCRIPTOR.full_name}: {e}")
+
+        rpc = self.channel(scheme, authority).unary_unary(
+            path,
+            request_serializer=request_class.SerializeToString,
+            response_deserializer=response_class.FromString,
+        )
+        call = rpc(message, metadata=request_metadata(headers), timeout=timeout_s)
+        try:
+            reply = await call
+        except grpc.aio.AioRpcError as e:
+            return error_response(e.code().name, e.details() or e.code().name, _metadata_headers(e.trailing_metadata()))
+        response_headers = _metadata_headers(await call.initial_metadata())
+        response_headers.update(_metadata_headers(await call.trailing_metadata()))
+        return _json_response(200, json_format.MessageToDict(reply), {**response_headers, "grpc-status": "OK"})
+
+
+def _metadata_headers(metadata) -> Dict[str, str]:
+    # Binary (-bin) metadata is not representable in JSON headers
+    return {key: value for key, value in metadata or () if not key.endswith("-bin")}
+
+
+_transcoders: Dict[str, GrpcTranscoder] = {}
+_transcoders_lock = threading.Lock()
+
+
+def get_transcoder(target_name: str, config: Dict[str, Any]) -> GrpcTranscoder:
+    """Transcoder for a target, built on first use."""
+    with _transcoders_lock:
+        if target_name not in _transcoders:
+            _transcoders[target_name] = GrpcTranscoder(config)
+        return _transcoders[target_name]
+
+
+class GrpcTransport(httpx.AsyncBaseTransport):
+    """httpx transport that serves requests by calling the target's gRPC methods."""
+
+    def __init__(self, target_name: str, config: Dict[str, Any]):
+        self.target_name = target_name
+        self.config = config
+
+    async def handle_async_request(self, request: httpx.Request) -> httpx.Response:
+        # Built here so a missing package or bad descriptor fails the request, not client setup
+        transcoder = get_transcoder(self.target_name, self.config)
+        timeout_s = (request.extensions.get("timeout") or {}).get("read")
+        port = request.url.port or (443 if request.url.scheme == "grpcs" else 80)
+        authority = f"{request.url.host}:{port}"
+        response = await transcoder.call(
+            request.url.scheme, authority, request.url.path, await request.aread(), dict(request.headers), timeout_s
+        )
+        response.request = request
+        return response
+
+    async def aclose(self) -> None:
+        # Channels belong to the transcoder and outlive the per-request client
+        pass
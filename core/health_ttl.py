@@ -0,0 +1,50 @@
+"""Cache TTL extension while a target is unhealthy (`cache.health_ttl`).
+
+With health_ttl configured, every cache write keeps the entry for
+ttl * factor but marks the end of its regular TTL. Lookups only return
+the extra lifetime while one of the triggers holds for the target:
+
+- circuit_open: the target's circuit breaker is open
+- circuit_half_open: the breaker is probing after a cooldown
+- elevated_errors: the breaker is closed but has min_failures recent failures
+
+So entries written before an incident keep serving through it, and the
+usual TTL applies again as soon as the target recovers.
+"""
+from typing import Any, Dict, Optional
+
+from reliapi.core.circuit_breaker import circuit_breakers
+
+_STATE_TRIGGERS = {"open": "circuit_open", "half-open": "circuit_half_open"}
+
+
+def stale_ttl_s(cache_config: Dict[str, Any], ttl_s: Optional[int]) -> int:
+    """Seconds an entry is kept past ttl_s for health_ttl (0 if not configured)."""
+    health_ttl = cache_config.get("health_ttl")
+    if not health_ttl or not ttl_s:
+        return 0
+    return int(ttl_s * (health_ttl.get("factor", 3.0) - 1))
+
+
+def health_ttl_reason(target_name: str, target_config: Dict[str, Any]) -> Optional[str]:
+    """Trigger currently extending the target's cache TTL, or None."""
+    health_ttl = target_config.get("cache", {}).get("health_ttl")
+    if not health_ttl:
+        return None
+    triggers = health_ttl.get("triggers", ["circuit_open", "circuit_half_open"])
+    breaker = circuit_breakers.get(target_name, target_config.get("circuit"))
+    snapshot = breaker.snapshot(target_config.get("base_url", "").rstrip("/"))
+    reason = _STATE_TRIGGERS.get(snapshot["state"])
+    if reason is None and snapshot["failures"] >= health_ttl.get("min_failures", 2):
+        reason = "elevated_errors"
+    return reason if reason in triggers else None
+
+
+def health_ttl_status(targets: Dict[str, Dict[str, Any]]) -> Dict[str, Dict[str, Any]]:
+    """Active TTL extension per target (for /health); empty if none applies."""
+    status = {}
+    for name, target_config in targets.items():
+        reason = health_ttl_reason(name, target_config)
+        if reason:
+            status[name] = {"reason": reason, "factor": target_config["cache"]["health_ttl"].get("factor", 3.0)}
+    return status
@@ -0,0 +1,115 @@
+"""Hedged requests for tail-latency reduction.
+
+When a hedged call has not returned after the target's hedge delay, an
+identical second request is sent and whichever finishes first wins; the
+other is cancelled. The delay is a percentile (`delay_percentile`) of the
+target's recent upstream latencies, so only the slowest calls are hedged,
+and `initial_delay_ms` is used until `min_samples` latencies are known.
+
+Only safe-to-repeat requests are hedged (GET/HEAD, or requests with an
+idempotency key). As a cost guard, at most `max_hedge_ratio` of a
+target's recent calls are hedged; past that, calls simply wait.
+"""
+import asyncio
+import math
+import threading
+from collections import deque
+from typing import Any, Awaitable, Callable, Deque, Dict, Optional, Tuple, TypeVar
+
+T = TypeVar("T")
+
+# Recent calls per target kept for the latency percentile and the hedge ratio
+WINDOW = 200
+
+
+class _TargetStats:
+    def __init__(self):
+        self.latencies_s: Deque[float] = deque(maxlen=WINDOW)
+        self.hedged: Deque[bool] = deque(maxlen=WINDOW)
+
+
+class HedgeTracker:
+    """Per-target latency samples and hedge counts (in-process)."""
+
+    def __init__(self):
+        self._stats: Dict[str, _TargetStats] = {}
+        self._lock = threading.Lock()
+
+    def _target(self, target: str) -> _TargetStats:
+        return self._stats.setdefault(target, _TargetStats())
+
+    def observe(self, target: str, latency_s: float) -> None:
+        """Record the latency of a completed upstream call."""
+        with self._lock:
+            self._target(target).latencies_s.append(latency_s)
+
+    def delay_s(self, target: str, config: Dict[str, Any]) -> float:
+        """Seconds to wait before hedging a call to target."""
+        with self._lock:
+            samples = sorted(self._target(target).latencies_s)
+        min_delay_s = config.get("min_delay_ms", 10) / 1000
+        if len(samples) < config.get("min_samples", 20):
+            return max(config.get("initial_delay_ms", 200) / 1000, min_delay_s)
+        rank = math.ceil(config.get("delay_percentile", 95) / 100 * len(samples)) - 1
+        return max(samples[max(rank, 0)], min_delay_s)
+
+    def try_hedge(self, target: str, config: Dict[str, Any]) -> bool:
+        """Claim a hedge unless it would exceed max_hedge_ratio of recent calls."""
+        with self._lock:
+            hedged = self._target(target).hedged
+            if hedged and sum(hedged) / len(hedged) >= config.get("max_hedge_ratio", 0.1):
+                return False
+            hedged[-1] = True
+            return True
+
+    def record_call(self, target: str) -> None:
+        """Count a call towards the hedge ratio (not hedged unless try_hedge claims it)."""
+        with self._lock:
+            self._target(target).hedged.append(False)
+
+    def reset(self) -> None:
+        with self._lock:
+            self._stats.clear()
+
+
+async def hedged_call(
+    call: Callable[[], Awaitable[T]],
+    delay_s: float,
+    may_hedge: Callable[[], bool],
+) -> Tuple[T, Dict[str, Any]]:
+    """Run call, start a second identical call after delay_s, and return the first to succeed.
+
+    Args:
+        call: Starts one upstream attempt
+        delay_s: Wait before hedging
+        may_hedge: Cost guard checked when the delay elapses
+
+    Returns:
+        (result, meta) with meta {"hedged": True, "hedge_winner": 1 | 2} if a hedge was sent, else {}
+
+    Raises:
+        The error of the last attempt to fail if none succeeded
+    """
+    first = asyncio.ensure_future(call())
+    done, _ = await asyncio.wait({first}, timeout=delay_s)
+    if done or not may_hedge():
+        return await first, {}
+
+    attempts = {first: 1, asyncio.ensure_future(call()): 2}
+    pending = set(attempts)
+    error: Optional[BaseException] = None
+    try:
+        while pending:
+            done, pending = await asyncio.wait(pending, return_when=asyncio.FIRST_COMPLETED)
+            for task in done:
+                if task.exception() is None:
+                    return task.result(), {"hedged": True, "hedge_winner": attempts[task]}
+                error = task.exception()
+        raise error
+    finally:
+        for task in pending:
+            task.cancel()
+
+
+# Process-wide tracker used by the HTTP proxy
+hedge_tracker = HedgeTracker()
@@ -1,11 +1,39 @@
 """Universal HTTP client with retries and circuit breaker."""
+import logging
 import time
-from typing import Any, Dict, Optional
+from typing import Any, Callable, Dict, List, Optional, Tuple
 
 import httpx
 
 from reliapi.core.circuit_breaker import CircuitBreaker
 from reliapi.core.retry import RetryEngine, RetryMatrix
+from reliapi.core.retry_budget import RetryBudgetScope
+
+logger = logging.getLogger(__name__)
+
+# User-Agent sent upstream unless the target sets user_agent
+DEFAULT_USER_AGENT = "ReliAPI"
+
+
+def outbound_headers(
+    target_config: Dict[str, Any],
+    client_ip: Optional[str] = None,
+    request_id: Optional[str] = None,
+) -> Dict[str, str]:
+    """Headers every request to the target carries (request headers override them).
+
+    Args:
+        target_config: Target config (user_agent, default_headers, forward_client_ip)
+        client_ip: Caller's IP, sent as X-Forwarded-For if forward_client_ip is on
+        request_id: ReliAPI request ID, sent as X-Request-Id
+    """
+    headers = {"User-Agent": target_config.get("user_agent") or DEFAULT_USER_AGENT}
+    headers.update(target_config.get("default_headers") or {})
+    if target_config.get("forward_client_ip") and client_ip:
+        headers["X-Forwarded-For"] = client_ip
+    if request_id:
+        headers["X-Request-Id"] = request_id
+    return headers
 
 
 class UpstreamHTTPClient:
@@ -18,6 +46,12 @@ class UpstreamHTTPClient:
         retry_matrix: Optional[Dict[str, RetryMatrix]] = None,
         circuit_breaker: Optional[CircuitBreaker] = None,
         auth: Optional[Dict[str, Any]] = None,
+        retry_budget: Optional[RetryBudgetScope] = None,
+        default_headers: Optional[Dict[str, str]] = None,
+        failover_base_urls: Optional[List[str]] = None,
+        latency_observer: Optional[Callable[[str, float], None]] = None,
+        transport: Optional[httpx.AsyncBaseTransport] = None,
+        signer: Optional[httpx.Auth] = None,
     ):
         """
         Args:
@@ -26,23 +60,39 @@ class UpstreamHTTPClient:
             retry_matrix: Retry policies by error class
             circuit_breaker: Circuit breaker instance
             auth: Authentication config (type, header, prefix, etc.)
+            retry_budget: Retry budget shared with other clients of the target
+            default_headers: Headers added to every request (request headers win)
+            failover_base_urls: Base URLs tried in order when base_url fails (network, timeout, 5xx, open circuit)
+            latency_observer: Called with (base_url, seconds) for each non-5xx response
+            transport: Custom httpx transport (e.g. gRPC transcoding) instead of HTTP
+            signer: Signs every attempt (target `signing`, see core/request_signing.py)
         """
         self.base_url = base_url.rstrip("/")
+        self.failover_base_urls = [url.rstrip("/") for url in failover_base_urls or []]
+        self.latency_observer = latency_observer
+        # Base URL that produced the last response
+        self.served_base_url: Optional[str] = None
         self.timeout_s = timeout_s
-        self.retry_engine = RetryEngine(retry_matrix)
+        self.retry_engine = RetryEngine(retry_matrix, budget=retry_budget)
         self.circuit_breaker = circuit_breaker or CircuitBreaker()
         self.auth = auth or {}
+        self.default_headers = default_headers or {}
         
         # Create HTTP client with connection pooling
         self.client = httpx.AsyncClient(
             base_url=self.base_url,
             timeout=httpx.Timeout(timeout_s, connect=5.0),
             limits=httpx.Limits(max_connections=100, max_keepalive_connections=20),
+            transport=transport,
+            auth=signer,
         )
 
     def _prepare_headers(self, headers: Optional[Dict[str, str]] = None) -> Dict[str, str]:
         """Prepare headers with authentication."""
-        result = headers.copy() if headers else {}
+        # Case-insensitive: a request header replaces the default of the same name
+        overridden = {name.lower() for name in headers or {}}
+        result = {name: value for name, value in self.default_headers.items() if name.lower() not in overridden}
+        result.update(headers or {})
         
         # Add auth header if configured
         if self.auth.get("type") == "api_key":
@@ -61,6 +111,9 @@ class UpstreamHTTPClient:
         headers: Optional[Dict[str, str]] = None,
         body: Optional[bytes] = None,
         params: Optional[Dict[str, Any]] = None,
+        response_validator: Optional[Callable[[httpx.Response], None]] = None,
+        stream_when: Optional[Callable[[httpx.Response], bool]] = None,
+        no_retry_statuses: Tuple[int, ...] = (),
     ) -> httpx.Response:
         """
         Make HTTP request with retries and circuit breaker.
@@ -71,6 +124,11 @@ class UpstreamHTTPClient:
             headers: Request headers
             body: Request body
             params: Query parameters
+            response_validator: Called on each response; raising retries the request like a failure
+            stream_when: Leave a successful response's body unread (to be streamed by the caller)
+                when this returns True for it; response_validator is then skipped
+            no_retry_statuses: Upstream statuses raised at once, neither retried nor sent to
+                a failover base URL
             
         Returns:
             HTTP response
@@ -78,24 +136,72 @@ class UpstreamHTTPClient:
         Raises:
             httpx.HTTPError: On network/HTTP errors
         """
-        upstream_id = f"{self.base_url}"
+        base_urls = [self.base_url, *self.failover_base_urls]
+        for base_url, next_url in zip(base_urls, base_urls[1:]):
+            try:
+                return await self._request_upstream(
+                    base_url, method, path, headers, body, params, response_validator, stream_when,
+                    no_retry_statuses,
+                )
+            except Exception as e:
+                if not _should_fail_over(e) or _response_status(e) in no_retry_statuses:
+                    raise
+                logger.warning(f"Upstream {base_url} failed ({e}); failing over to {next_url}")
+        return await self._request_upstream(
+            base_urls[-1], method, path, headers, body, params, response_validator, stream_when,
+            no_retry_statuses,
+        )
+
+    async def _request_upstream(
+        self,
+        base_url: str,
+        method: str,
+        path: str,
+        headers: Optional[Dict[str, str]],
+        body: Optional[bytes],
+        params: Optional[Dict[str, Any]],
+        response_validator: Optional[Callable[[httpx.Response], None]],
+        stream_when: Optional[Callable[[httpx.Response], bool]] = None,
+        no_retry_statuses: Tuple[int, ...] = (),
+    ) -> httpx.Response:
+        """One upstream (base URL) with retries and its circuit breaker."""
+        upstream_id = base_url
         
-        # Check circuit breaker
-        if self.circuit_breaker.is_open(upstream_id):
+        # Check circuit breaker (claims a probe slot when half-open)
+        if not self.circuit_breaker.allow_request(upstream_id):
             raise httpx.HTTPError("Circuit breaker is open")
 
         prepared_headers = self._prepare_headers(headers)
-        url = f"{self.base_url}{path}"
+        url = f"{base_url}{path}"
 
         async def _make_request():
             try:
-                response = await self.client.request(
-                    method=method.upper(),
-                    url=url,
-                    headers=prepared_headers,
-                    content=body,
-                    params=params,
-                )
+                started = time.monotonic()
+                request_args = {
+                    "method": method.upper(),
+                    "url": url,
+                    "headers": prepared_headers,
+                    "content": body,
+                    "params": params,
+                }
+                streaming = False
+                if stream_when is None:
+                    response = await self.client.request(**request_args)
+                else:
+                    response = await self.client.send(self.client.build_request(**request_args), stream=True)
+                    streaming = response.is_success and stream_when(response)
+                    if not streaming:
+                        await response.aread()
+                self.served_base_url = base_url
+                if self.latency_observer and response.status_code < 500:
+                    self.latency_observer(base_url, time.monotonic() - started)
+                
+                if response_validator and not streaming:
+                    try:
+                        response_validator(response)
+                    except Exception:
+                        self.circuit_breaker.record_failure(upstream_id)
+                        raise
                 
                 # Record success/failure
                 if response.is_success:
@@ -120,8 +226,15 @@ class UpstreamHTTPClient:
                 self.circuit_breaker.record_failure(upstream_id)
                 raise
 
+        def _classify(status_code: Optional[int], error: Optional[Exception]) -> str:
+            if _response_status(error) in no_retry_statuses:
+                return "no-retry"
+            return self.retry_engine._classify_error(status_code, error)
+
         # Execute with retries
-        response = await self.retry_engine.execute(_make_request)
+        response = await self.retry_engine.execute(
+            _make_request, error_classifier=_classify if no_retry_statuses else None
+        )
         return response
 
     async def close(self):
@@ -129,3 +242,19 @@ class UpstreamHTTPClient:
         await self.client.aclose()
 
 
+def _response_status(error: Optional[Exception]) -> Optional[int]:
+    """Upstream status carried by a failed request's error, if it got a response."""
+    response = getattr(error, "response", None)
+    return getattr(response, "status_code", None)
+
+
+def _should_fail_over(error: Exception) -> bool:
+    """Whether a failed upstream may be replaced by the next base URL (not for 4xx)."""
+    if isinstance(error, httpx.HTTPStatusError):
+        return error.response.status_code >= 500
+    if isinstance(error, (httpx.ConnectError, httpx.TimeoutException)):
+        return True
+    if isinstance(error, httpx.HTTPError):
+        # Open circuit
+        return str(error) == "Circuit breaker is open"
+    return getattr(error, "status_code", 0) >= 500
@@ -7,6 +7,8 @@ from typing import Any, Dict, Optional, Tuple
 
 import redis
 
+from reliapi.core.canonical_json import canonical_hash
+
 logger = logging.getLogger(__name__)
 
 
@@ -40,7 +42,29 @@ class IdempotencyManager:
         headers: Optional[Dict[str, str]] = None,
         body: Optional[bytes] = None,
     ) -> str:
-        """Generate hash of request for comparison."""
+        """Generate hash of request for comparison (SHA-256 of its canonical JSON)."""
+        key_data = {
+            "method": method.upper(),
+            "url": url,
+            "headers": headers or {},
+        }
+        if body:
+            key_data["body_hash"] = hashlib.sha256(body).hexdigest()
+        
+        return canonical_hash(key_data)
+
+    def make_legacy_request_hash(
+        self,
+        method: str,
+        url: str,
+        headers: Optional[Dict[str, str]] = None,
+        body: Optional[bytes] = None,
+    ) -> str:
+        """Request hash as computed before canonical JSON (json.dumps with sort_keys).
+
+        Accepted alongside make_request_hash for one release, so requests
+        registered by the previous version are not reported as conflicting.
+        """
         key_data = {
             "method": method.upper(),
             "url": url,
@@ -0,0 +1,75 @@
+"""Repair of almost-valid JSON emitted by LLMs in JSON mode."""
+import json
+import re
+from typing import Any, Dict, Optional
+
+# ```json ... ``` (or bare ```) around the whole output
+_CODE_FENCE = re.compile(r"^```[a-zA-Z0-9_-]*\s*\n?(.*?)\n?\s*```$", re.DOTALL)
+
+
+def is_json_mode(response_format: Optional[Dict[str, Any]]) -> bool:
+    """Whether the request asked for JSON output (OpenAI response_format)."""
+    return bool(response_format) and response_format.get("type") in ("json_object", "json_schema")
+
+
+def _is_valid(text: str) -> bool:
+    try:
+        json.loads(text)
+        return True
+    except ValueError:
+        return False
+
+
+def _strip_code_fence(text: str) -> str:
+    match = _CODE_FENCE.match(text)
+    return match.group(1).strip() if match else text
+
+
+def _extract_json_value(text: str) -> str:
+    """Drop prose before the first `{`/`[` and after the last `}`/`]`."""
+    starts = [i for i in (text.find("{"), text.find("[")) if i != -1]
+    end = max(text.rfind("}"), text.rfind("]"))
+    if not starts or end == -1:
+        return text
+    start = min(starts)
+    return text[start:end + 1] if end > start else text
+
+
+def _remove_trailing_commas(text: str) -> str:
+    """Remove commas directly before `}` or `]`, ignoring string contents."""
+    out = []
+    in_string = False
+    escaped = False
+    for i, char in enumerate(text):
+        if in_string:
+            out.append(char)
+            if escaped:
+                escaped = False
+            elif char == "\\":
+                escaped = True
+            elif char == '"':
+                in_string = False
+            continue
+        if char == '"':
+            in_string = True
+        elif char == ",":
+            rest = text[i + 1:].lstrip()
+            if rest[:1] in ("}", "]"):
+                continue
+        out.append(char)
+    return "".join(out)
+
+
+def repair_json(text: str) -> Optional[str]:
+    """Fix common LLM JSON mistakes: markdown code fences, surrounding prose, trailing commas.
+
+    Returns:
+        Repaired JSON text, or None if the text is already valid or cannot be repaired
+    """
+    if _is_valid(text):
+        return None
+
+    candidate = _strip_code_fence(text.strip())
+    candidate = _extract_json_value(candidate)
+    candidate = _remove_trailing_commas(candidate)
+    return candidate if _is_valid(candidate) else None
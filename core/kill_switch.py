@@ -0,0 +1,80 @@
+"""Per-target kill switch: stop all traffic to a target without a redeploy.
+
+Toggled via the admin endpoints. Disabled targets are rejected with 503
+TARGET_DISABLED before any upstream call (cache hits are still served if
+the target sets `serve_cache_when_disabled`). State is kept in Redis, so it
+is shared by every instance and survives restarts; without Redis it is kept
+in-process.
+"""
+import json
+import logging
+import time
+from typing import Any, Dict, Optional
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+
+class KillSwitch:
+    """Disabled-target flags."""
+
+    def __init__(self):
+        self.key_prefix = "reliapi"
+        self.client = None
+        self.enabled = False
+        self._local: Dict[str, Dict[str, Any]] = {}
+
+    def connect(self, redis_url: str, key_prefix: str = "reliapi") -> None:
+        """Share kill switch state through Redis (falls back to in-process state)."""
+        self.key_prefix = key_prefix
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Kill switch connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Kill switch Redis connection failed (in-process state only): {e}", exc_info=True)
+
+    def _key(self) -> str:
+        return f"{self.key_prefix}:disabled_targets"
+
+    def _entries(self) -> Dict[str, Dict[str, Any]]:
+        if self.enabled and self.client:
+            try:
+                return {target: json.loads(raw) for target, raw in self.client.hgetall(self._key()).items()}
+            except Exception as e:
+                logger.warning(f"Kill switch state read error (using in-process state): {e}", exc_info=True)
+        return dict(self._local)
+
+    def set(self, target: str, disabled: bool, reason: Optional[str] = None) -> None:
+        """Disable or re-enable all traffic to a target."""
+        entry = {"since": time.time(), "reason": reason}
+        if disabled:
+            self._local[target] = entry
+        else:
+            self._local.pop(target, None)
+
+        if self.enabled and self.client:
+            try:
+                if disabled:
+                    self.client.hset(self._key(), target, json.dumps(entry))
+                else:
+                    self.client.hdel(self._key(), target)
+            except Exception as e:
+                logger.warning(f"Kill switch state write error (in-process only): {e}", exc_info=True)
+        logger.warning(f"Target '{target}' {'disabled' if disabled else 'enabled'} via kill switch")
+
+    def is_disabled(self, target: str) -> bool:
+        """Whether all traffic to the target must be rejected."""
+        return target in self._entries()
+
+    def status(self) -> Dict[str, Dict[str, Any]]:
+        """Disabled targets with since/reason, for /health and the admin endpoints."""
+        return self._entries()
+
+
+# Process-wide kill switch (connected to Redis at startup)
+kill_switch = KillSwitch()
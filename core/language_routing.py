@@ -0,0 +1,126 @@
+"""Model routing by detected input language (`llm.language_routing`).
+
+Detection is deliberately lightweight: the script of the user messages
+decides most languages (Japanese kana, Korean Hangul, Chinese Han,
+Cyrillic, Arabic, ...), and a handful of common function words tells the
+Latin-script languages apart. Text too short or too ambiguous to call is
+"unknown" and keeps the target's default model.
+"""
+import logging
+import re
+from typing import Any, Dict, List, Optional, Tuple
+
+logger = logging.getLogger(__name__)
+
+# (language, inclusive code point ranges); kana and Hangul are checked before Han
+_SCRIPTS = [
+    ("ja", [(0x3040, 0x30FF), (0x31F0, 0x31FF)]),
+    ("ko", [(0xAC00, 0xD7AF), (0x1100, 0x11FF), (0x3130, 0x318F)]),
+    ("zh", [(0x4E00, 0x9FFF), (0x3400, 0x4DBF)]),
+    ("ru", [(0x0400, 0x04FF)]),
+    ("ar", [(0x0600, 0x06FF)]),
+    ("he", [(0x0590, 0x05FF)]),
+    ("hi", [(0x0900, 0x097F)]),
+    ("th", [(0x0E00, 0x0E7F)]),
+    ("el", [(0x0370, 0x03FF)]),
+]
+
+# Frequent function words of the Latin-script languages
+_STOPWORDS = {
+    "en": {"the", "and", "is", "are", "of", "to", "in", "what", "how", "you", "this", "with"},
+    "es": {"el", "la", "los", "las", "de", "que", "y", "es", "en", "por", "para", "una", "cómo"},
+    "fr": {"le", "la", "les", "des", "et", "est", "une", "que", "pour", "dans", "vous", "pas"},
+    "de": {"der", "die", "das", "und", "ist", "nicht", "ein", "eine", "mit", "ich", "sie", "wie"},
+    "pt": {"o", "os", "as", "de", "que", "e", "é", "não", "uma", "para", "com", "você"},
+    "it": {"il", "lo", "gli", "di", "che", "e", "è", "non", "una", "per", "sono", "come"},
+}
+
+SUPPORTED_LANGUAGES = tuple(lang for lang, _ in _SCRIPTS) + tuple(_STOPWORDS)
+
+DEFAULT_MIN_CHARS = 10
+
+_WORD = re.compile(r"[^\W\d_]+")
+
+
+def _script_of(char: str) -> Optional[str]:
+    code = ord(char)
+    for language, ranges in _SCRIPTS:
+        if any(low <= code <= high for low, high in ranges):
+            return language
+    return None
+
+
+def _user_text(messages: List[Dict[str, Any]]) -> str:
+    parts = []
+    for message in messages:
+        if message.get("role") != "user":
+            continue
+        content = message.get("content")
+        if isinstance(content, str):
+            parts.append(content)
+        elif isinstance(content, list):
+            parts.extend(p.get("text", "") for p in content if isinstance(p, dict))
+    return "\n".join(parts)
+
+
+def detect_language(messages: List[Dict[str, Any]], min_chars: int = DEFAULT_MIN_CHARS) -> Optional[str]:
+    """ISO 639-1 code of the user messages' language, or None if unknown.
+
+    Only letters count towards min_chars. Japanese text mixes kana with Han
+    characters, so any kana makes the Han characters count as Japanese.
+    """
+    text = _user_text(messages)
+    letters = [c for c in text if c.isalpha()]
+    if len(letters) < min_chars:
+        return None
+
+    counts: Dict[str, int] = {}
+    latin = 0
+    for char in letters:
+        language = _script_of(char)
+        if language:
+            counts[language] = counts.get(language, 0) + 1
+        elif ord(char) <= 0x024F:
+            latin += 1
+    if counts.get("ja"):
+        counts["ja"] += counts.pop("zh", 0)
+
+    if counts:
+        language, count = max(counts.items(), key=lambda item: item[1])
+        if count >= latin:
+            return language
+
+    words = _WORD.findall(text.lower())
+    hits = {lang: sum(word in stopwords for word in words) for lang, stopwords in _STOPWORDS.items()}
+    best = max(hits.values())
+    winners = [lang for lang, count in hits.items() if count == best]
+    if best == 0 or len(winners) > 1:
+        return None
+    return winners[0]
+
+
+def select_language_route(
+    target_name: str,
+    targets: Dict[str, Dict[str, Any]],
+    messages: List[Dict[str, Any]],
+) -> Tuple[str, Optional[str], Optional[str]]:
+    """(target, model, detected language) for a request without an explicit model.
+
+    Without `language_routing` on the target nothing is detected. A detected
+    language without a route keeps the target and its default model; a
+    route may switch the model, the target (using its default model), or both.
+    """
+    routing = (targets.get(target_name, {}).get("llm") or {}).get("language_routing")
+    if not routing:
+        return target_name, None, None
+
+    language = detect_language(messages, routing.get("min_chars", DEFAULT_MIN_CHARS))
+    route = (routing.get("routes") or {}).get(language) if language else None
+    if not route:
+        return target_name, None, language
+
+    route_target = route.get("target") or target_name
+    if route_target not in targets:
+        logger.warning(f"Language route '{language}' of target '{target_name}' names unknown target '{route_target}'")
+        return target_name, None, language
+    return route_target, route.get("model"), language
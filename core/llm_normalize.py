@@ -0,0 +1,66 @@
+"""Normalization of provider LLM responses to one OpenAI-style shape.
+
+Whatever the target, `data` exposes `choices[].message.{role, content}`,
+`finish_reason` and `usage.{prompt_tokens, completion_tokens}`. Provider
+roles and finish reasons are mapped onto the OpenAI vocabulary; targets
+can extend the mappings with `llm.normalization`.
+"""
+from typing import Any, Dict, List, Optional
+
+# Provider role -> OpenAI role
+DEFAULT_ROLE_MAP = {
+    "model": "assistant",    # Gemini
+    "bot": "assistant",
+    "chatbot": "assistant",  # Cohere
+}
+
+# Provider finish reason -> OpenAI finish reason
+DEFAULT_FINISH_REASON_MAP = {
+    "end_turn": "stop",          # Anthropic
+    "stop_sequence": "stop",     # Anthropic
+    "max_tokens": "length",      # Anthropic
+    "tool_use": "tool_calls",    # Anthropic
+    "model_length": "length",    # Mistral
+    "STOP": "stop",              # Gemini
+    "MAX_TOKENS": "length",      # Gemini
+    "SAFETY": "content_filter",  # Gemini
+    "RECITATION": "content_filter",  # Gemini
+}
+
+
+def normalize_role(role: Optional[str], config: Optional[Dict[str, Any]] = None) -> str:
+    """Map a provider role to system/user/assistant (default assistant)."""
+    if not role:
+        return "assistant"
+    role_map = (config or {}).get("role_map") or {}
+    key = role.lower()
+    return role_map.get(role) or role_map.get(key) or DEFAULT_ROLE_MAP.get(key, key)
+
+
+def normalize_finish_reason(reason: Optional[str], config: Optional[Dict[str, Any]] = None) -> Optional[str]:
+    """Map a provider finish reason to the OpenAI value (unknown reasons pass through)."""
+    if reason is None:
+        return None
+    finish_reason_map = (config or {}).get("finish_reason_map") or {}
+    return finish_reason_map.get(reason) or DEFAULT_FINISH_REASON_MAP.get(reason, reason)
+
+
+def normalize_choices(
+    choices: List[Dict[str, Any]],
+    config: Optional[Dict[str, Any]] = None,
+) -> List[Dict[str, Any]]:
+    """Adapter choices (index, content, role, finish_reason, logprobs) as OpenAI-style choices."""
+    normalized = []
+    for position, choice in enumerate(choices):
+        item = {
+            "index": choice.get("index", position),
+            "message": {
+                "role": normalize_role(choice.get("role"), config),
+                "content": choice.get("content") or "",
+            },
+            "finish_reason": normalize_finish_reason(choice.get("finish_reason", "stop"), config),
+        }
+        if choice.get("logprobs") is not None:
+            item["logprobs"] = choice["logprobs"]
+        normalized.append(item)
+    return normalized
@@ -0,0 +1,87 @@
+"""Maintenance mode: serve from cache only, reject cache misses.
+
+Toggled at runtime via the admin endpoint, globally or per target. State is
+kept in Redis so every instance sees the same flags; without Redis it is
+kept in-process.
+"""
+import json
+import logging
+import time
+from typing import Any, Dict, Optional
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+# Scope name for maintenance of all targets
+GLOBAL_SCOPE = "*"
+
+
+class MaintenanceMode:
+    """Global and per-target cache-only flags."""
+
+    def __init__(self):
+        self.key_prefix = "reliapi"
+        self.client = None
+        self.enabled = False
+        self._local: Dict[str, Dict[str, Any]] = {}
+
+    def connect(self, redis_url: str, key_prefix: str = "reliapi") -> None:
+        """Share maintenance state through Redis (falls back to in-process state)."""
+        self.key_prefix = key_prefix
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Maintenance mode connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Maintenance mode Redis connection failed (in-process state only): {e}", exc_info=True)
+
+    def _key(self) -> str:
+        return f"{self.key_prefix}:maintenance"
+
+    def _entries(self) -> Dict[str, Dict[str, Any]]:
+        if self.enabled and self.client:
+            try:
+                return {scope: json.loads(raw) for scope, raw in self.client.hgetall(self._key()).items()}
+            except Exception as e:
+                logger.warning(f"Maintenance state read error (using in-process state): {e}", exc_info=True)
+        return dict(self._local)
+
+    def set(self, active: bool, target: Optional[str] = None, reason: Optional[str] = None) -> None:
+        """Turn cache-only mode on or off for a target, or globally if target is None."""
+        scope = target or GLOBAL_SCOPE
+        entry = {"since": time.time(), "reason": reason}
+        if active:
+            self._local[scope] = entry
+        else:
+            self._local.pop(scope, None)
+
+        if self.enabled and self.client:
+            try:
+                if active:
+                    self.client.hset(self._key(), scope, json.dumps(entry))
+                else:
+                    self.client.hdel(self._key(), scope)
+            except Exception as e:
+                logger.warning(f"Maintenance state write error (in-process only): {e}", exc_info=True)
+        logger.warning(f"Maintenance mode {'enabled' if active else 'disabled'} for {target or 'all targets'}")
+
+    def is_active(self, target: str) -> bool:
+        """Whether requests to the target must be served from cache only."""
+        entries = self._entries()
+        return GLOBAL_SCOPE in entries or target in entries
+
+    def status(self) -> Dict[str, Any]:
+        """Maintenance state for /health and the admin endpoint."""
+        entries = self._entries()
+        return {
+            "global": entries.get(GLOBAL_SCOPE),
+            "targets": {scope: entry for scope, entry in entries.items() if scope != GLOBAL_SCOPE},
+        }
+
+
+# Process-wide maintenance flags (connected to Redis at startup)
+maintenance_mode = MaintenanceMode()
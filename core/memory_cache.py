@@ -0,0 +1,127 @@
+"""In-process cache tier in front of Redis.
+
+Entries are bounded by `max_entries` and `max_bytes`. When a write would
+exceed either cap, expired entries are dropped first, then entries are
+evicted by policy:
+
+- lru: least recently used first
+- cost_weighted: lowest `cost_usd` per byte first (cheapest to regenerate),
+  least recently used among equals; keeps the dollars saved per byte high
+"""
+import threading
+import time
+from collections import OrderedDict
+from dataclasses import dataclass
+from typing import Optional
+
+from reliapi.metrics.prometheus import memory_cache_evictions_total, memory_cache_bytes
+
+
+@dataclass
+class _Entry:
+    value: str
+    expires_at: float
+    cost_usd: float
+    size: int
+
+
+class MemoryCache:
+    """Size-bounded key -> serialized value store with TTLs (thread-safe)."""
+
+    def __init__(
+        self,
+        max_entries: int = 10000,
+        max_bytes: int = 64 * 1024 * 1024,
+        eviction: str = "lru",
+    ):
+        """
+        Args:
+            max_entries: Maximum number of entries
+            max_bytes: Maximum total size of stored values
+            eviction: "lru" or "cost_weighted"
+        """
+        self.max_entries = max_entries
+        self.max_bytes = max_bytes
+        self.eviction = eviction
+        self.total_bytes = 0
+        self._entries: "OrderedDict[str, _Entry]" = OrderedDict()  # Least recently used first
+        self._lock = threading.Lock()
+
+    def __len__(self) -> int:
+        return len(self._entries)
+
+    def _remove(self, key: str) -> None:
+        entry = self._entries.pop(key)
+        self.total_bytes -= entry.size
+
+    def _victim(self) -> str:
+        """Key to evict next under the configured policy."""
+        if self.eviction == "cost_weighted":
+            # min() keeps the first (least recently used) of equally cheap entries
+            return min(self._entries, key=lambda k: self._entries[k].cost_usd / max(self._entries[k].size, 1))
+        return next(iter(self._entries))
+
+    def _make_room(self, size: int) -> None:
+        now = time.time()
+        if len(self._entries) >= self.max_entries or self.total_bytes + size > self.max_bytes:
+            for key in [k for k, entry in self._entries.items() if entry.expires_at <= now]:
+                self._remove(key)
+        while self._entries and len(self._entries) >= self.max_entries:
+            self._remove(self._victim())
+            memory_cache_evictions_total.labels(policy=self.eviction, reason="max_entries").inc()
+        while self._entries and self.total_bytes + size > self.max_bytes:
+            self._remove(self._victim())
+            memory_cache_evictions_total.labels(policy=self.eviction, reason="max_bytes").inc()
+
+    def get(self, key: str) -> Optional[str]:
+        """Stored value, or None if missing or expired."""
+        with self._lock:
+            entry = self._entries.get(key)
+            if entry is None:
+                return None
+            if entry.expires_at <= time.time():
+                self._remove(key)
+                memory_cache_bytes.set(self.total_bytes)
+                return None
+            self._entries.move_to_end(key)
+            return entry.value
+
+    def set(self, key: str, value: str, ttl_s: float, cost_usd: Optional[float] = None) -> None:
+        """Store a value; values larger than max_bytes are not stored."""
+        size = len(value.encode())
+        with self._lock:
+            if key in self._entries:
+                self._remove(key)
+            if size > self.max_bytes:
+                return
+            self._make_room(size)
+            self._entries[key] = _Entry(value, time.time() + ttl_s, cost_usd or 0.0, size)
+            self.total_bytes += size
+            memory_cache_bytes.set(self.total_bytes)
+
+    def ttl(self, key: str) -> Optional[float]:
+        """Seconds until the entry expires, or None if missing or expired."""
+        with self._lock:
+            entry = self._entries.get(key)
+            remaining = entry.expires_at - time.time() if entry else 0
+            return remaining if remaining > 0 else None
+
+    def expire(self, key: str, ttl_s: float) -> None:
+        """Reset an existing entry's expiry to ttl_s from now."""
+        with self._lock:
+            entry = self._entries.get(key)
+            if entry is not None:
+                entry.expires_at = time.time() + ttl_s
+
+    def delete(self, key: str) -> None:
+        with self._lock:
+            if key in self._entries:
+                self._remove(key)
+                memory_cache_bytes.set(self.total_bytes)
+
+    def delete_prefix(self, prefix: str) -> None:
+        """Remove every key starting with prefix."""
+        with self._lock:
+            for key in [k for k in self._entries if k.startswith(prefix)]:
+                self._remove(key)
+            memory_cache_bytes.set(self.total_bytes)
@@ -0,0 +1,92 @@
+"""Model catalog for GET /models.
+
+A target's models come from, in order of precedence: `llm.models`,
+`llm.auto_models` served by the target, `llm.default_model`, the models
+ReliAPI has pricing for, and (with `llm.live_models`) the provider's own
+GET /models listing. Entries for the same model are merged.
+"""
+import threading
+import time
+from typing import Any, Dict, List, Optional
+
+from reliapi.core.cost_estimator import CostEstimator
+
+# Capability flags reported for every model; config names map onto them
+CAPABILITY_FLAGS = ("vision", "tools", "json_mode")
+CAPABILITY_ALIASES = {"json": "json_mode", "function_calling": "tools"}
+
+# How long a provider's live model listing is reused
+LIVE_MODELS_TTL_S = 300
+
+
+def _capability_flags(capabilities: List[str]) -> Dict[str, bool]:
+    names = {CAPABILITY_ALIASES.get(c.lower(), c.lower()) for c in capabilities}
+    return {flag: flag in names for flag in CAPABILITY_FLAGS}
+
+
+def target_models(
+    target_name: str,
+    target_config: Dict[str, Any],
+    provider: Optional[str],
+    live_models: Optional[List[str]] = None,
+) -> List[Dict[str, Any]]:
+    """Catalog entries (id, context_window, pricing, capabilities, source) for one target."""
+    llm_config = target_config.get("llm") or {}
+    entries: Dict[str, Dict[str, Any]] = {}
+
+    def add(model: str, source: str, context_window: Optional[int] = None, capabilities: Optional[List[str]] = None) -> None:
+        entry = entries.setdefault(model, {"id": model, "context_window": None, "capabilities": set(), "source": source})
+        if entry["context_window"] is None:
+            entry["context_window"] = context_window
+        entry["capabilities"].update(capabilities or [])
+
+    for model, info in (llm_config.get("models") or {}).items():
+        add(model, "config", info.get("context_window"), info.get("capabilities"))
+    for candidate in llm_config.get("auto_models") or []:
+        if candidate.get("target") in (None, target_name):
+            add(candidate["model"], "config", candidate.get("context_window"), candidate.get("capabilities"))
+    if llm_config.get("default_model"):
+        add(llm_config["default_model"], "config")
+    for model in CostEstimator.PRICING_PER_1K.get(provider or "", {}):
+        add(model, "pricing")
+    for model in live_models or []:
+        add(model, "live")
+
+    catalog = []
+    for entry in entries.values():
+        pricing = CostEstimator.PRICING_PER_1K.get(provider or "", {}).get(entry["id"])
+        catalog.append({
+            **entry,
+            "target": target_name,
+            "provider": provider,
+            "pricing": {
+                "prompt_per_1k_usd": pricing["prompt"],
+                "completion_per_1k_usd": pricing["completion"],
+            } if pricing else None,
+            "capabilities": _capability_flags(sorted(entry["capabilities"])),
+        })
+    return catalog
+
+
+class LiveModelCache:
+    """Provider model listings per target, reused for LIVE_MODELS_TTL_S (in-process)."""
+
+    def __init__(self, ttl_s: int = LIVE_MODELS_TTL_S):
+        self.ttl_s = ttl_s
+        self._entries: Dict[str, tuple] = {}
+        self._lock = threading.Lock()
+
+    def get(self, target: str) -> Optional[List[str]]:
+        with self._lock:
+            cached = self._entries.get(target)
+            if cached and time.time() - cached[0] < self.ttl_s:
+                return cached[1]
+            return None
+
+    def set(self, target: str, models: List[str]) -> None:
+        with self._lock:
+            self._entries[target] = (time.time(), models)
+
+
+# Process-wide cache used by GET /models
+live_model_cache = LiveModelCache()
@@ -0,0 +1,176 @@
+"""Per-model RPM/TPM limiter to stay under provider tier limits."""
+import asyncio
+import time
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Deque, Dict, List, Optional, Tuple
+import logging
+
+logger = logging.getLogger(__name__)
+
+# Providers enforce limits over a rolling minute
+WINDOW_SECONDS = 60.0
+
+# Wildcard key in llm.model_limits applying to models without an explicit entry
+DEFAULT_MODEL_KEY = "*"
+
+
+@dataclass
+class _UsageEntry:
+    """One request in the rolling window (tokens may be reconciled later)."""
+
+    ts: float
+    tokens: int
+
+
+@dataclass
+class ModelRateReservation:
+    """Handle for a request admitted by the limiter.
+
+    Pass to ModelRateLimiter.reconcile() once actual token usage is known.
+    """
+
+    key: str
+    entry: _UsageEntry
+    delayed_ms: int = 0
+    delayed_by: Optional[str] = None  # "rpm" or "tpm" if the request was delayed
+
+
+@dataclass
+class _ModelWindow:
+    """Rolling one-minute window of requests for a target+model."""
+
+    entries: Deque[_UsageEntry] = field(default_factory=deque)
+
+    def prune(self, now: float) -> None:
+        while self.entries and now - self.entries[0].ts >= WINDOW_SECONDS:
+            self.entries.popleft()
+
+    def used_tokens(self) -> int:
+        return sum(e.tokens for e in self.entries)
+
+    def wait_for_requests(self, now: float, rpm: int) -> float:
+        """Seconds until one more request fits under rpm."""
+        if len(self.entries) < rpm:
+            return 0.0
+        # Oldest request that must expire to free a slot
+        oldest = self.entries[len(self.entries) - rpm]
+        return max(0.0, WINDOW_SECONDS - (now - oldest.ts))
+
+    def wait_for_tokens(self, now: float, tpm: int, tokens: int) -> float:
+        """Seconds until `tokens` more fit under tpm."""
+        excess = self.used_tokens() + tokens - tpm
+        if excess <= 0:
+            return 0.0
+        for entry in self.entries:
+            excess -= entry.tokens
+            if excess <= 0:
+                return max(0.0, WINDOW_SECONDS - (now - entry.ts))
+        # Request alone exceeds tpm: can never fit
+        return float("inf")
+
+
+def resolve_model_limits(llm_config: Dict[str, Any], model: str) -> Optional[Dict[str, Any]]:
+    """Get limits for a model from llm.model_limits (exact match, then '*')."""
+    model_limits = llm_config.get("model_limits") or {}
+    return model_limits.get(model) or model_limits.get(DEFAULT_MODEL_KEY)
+
+
+def estimate_request_tokens(messages: List[Dict[str, str]], max_tokens: Optional[int]) -> int:
+    """Estimate tokens a request will consume (prompt ~4 chars/token + max completion)."""
+    prompt_tokens = sum(len(msg.get("content", "")) for msg in messages) // 4
+    return prompt_tokens + (max_tokens or 0)
+
+
+class ModelRateLimiter:
+    """Enforces per target+model requests-per-minute and tokens-per-minute.
+
+    Requests reserve their estimated tokens up front; the reservation is
+    reconciled with actual usage from the provider response so TPM tracks
+    real consumption. When a limit is hit the request is either delayed
+    (on_limit: delay, up to max_delay_ms) or rejected with retry_after.
+    """
+
+    def __init__(self):
+        self._windows: Dict[str, _ModelWindow] = {}
+        self._lock = asyncio.Lock()
+
+    @staticmethod
+    def _make_key(target: str, model: str) -> str:
+        return f"{target}:{model}"
+
+    async def acquire(
+        self,
+        target: str,
+        model: str,
+        limits: Dict[str, Any],
+        estimated_tokens: int,
+    ) -> Tuple[Optional[ModelRateReservation], Optional[float], Optional[str]]:
+        """Admit a request or report which limit blocks it.
+
+        Args:
+            target: Target name
+            model: Model name
+            limits: Model limits config (rpm, tpm, on_limit, max_delay_ms)
+            estimated_tokens: Estimated tokens for this request
+
+        Returns:
+            Tuple of (reservation, retry_after_s, limiting)
+            - reservation: Set if the request was admitted
+            - retry_after_s: Seconds until the request would fit (if rejected)
+            - limiting: "rpm" or "tpm" (if rejected)
+        """
+        key = self._make_key(target, model)
+        rpm = limits.get("rpm")
+        tpm = limits.get("tpm")
+        delay_budget_s = limits.get("max_delay_ms", 0) / 1000.0 if limits.get("on_limit") == "delay" else 0.0
+        waited_s = 0.0
+        limiting: Optional[str] = None
+
+        while True:
+            async with self._lock:
+                now = time.time()
+                window = self._windows.setdefault(key, _ModelWindow())
+                window.prune(now)
+
+                wait_rpm = window.wait_for_requests(now, rpm) if rpm else 0.0
+                wait_tpm = window.wait_for_tokens(now, tpm, estimated_tokens) if tpm else 0.0
+                wait_s = max(wait_rpm, wait_tpm)
+
+                if wait_s <= 0:
+                    entry = _UsageEntry(ts=now, tokens=estimated_tokens)
+                    window.entries.append(entry)
+                    reservation = ModelRateReservation(
+                        key=key,
+                        entry=entry,
+                        delayed_ms=int(waited_s * 1000),
+                        delayed_by=limiting,
+                    )
+                    return reservation, None, None
+
+                limiting = "rpm" if wait_rpm >= wait_tpm else "tpm"
+
+            if wait_s == float("inf"):
+                # Request alone is larger than the tpm limit
+                return None, None, limiting
+            if waited_s + wait_s > delay_budget_s:
+                return None, wait_s, limiting
+
+            await asyncio.sleep(wait_s)
+            waited_s += wait_s
+
+    def reconcile(self, reservation: Optional[ModelRateReservation], actual_tokens: Optional[int]) -> None:
+        """Replace a reservation's estimated tokens with actual usage."""
+        if reservation and actual_tokens is not None:
+            reservation.entry.tokens = actual_tokens
+
+    def get_remaining(self, target: str, model: str, limits: Dict[str, Any]) -> Dict[str, Optional[int]]:
+        """Remaining requests/tokens in the current window."""
+        window = self._windows.get(self._make_key(target, model), _ModelWindow())
+        window.prune(time.time())
+        rpm = limits.get("rpm")
+        tpm = limits.get("tpm")
+        return {
+            "rpm": max(0, rpm - len(window.entries)) if rpm else None,
+            "tpm": max(0, tpm - window.used_tokens()) if tpm else None,
+        }
@@ -0,0 +1,130 @@
+"""Pinned LLM responses: vetted answers served from cache with no TTL."""
+import hashlib
+import json
+import logging
+import time
+from typing import Any, Dict, List, Optional
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+# Request fields that identify a pinned request (None values are ignored)
+PIN_REQUEST_FIELDS = (
+    "messages", "model", "max_tokens", "temperature", "top_p", "stop",
+    "response_format", "logit_bias", "seed",
+)
+
+
+def pin_id(target: str, request: Dict[str, Any], tenant: Optional[str] = None) -> str:
+    """Stable ID of a pinned request: same target, tenant and request fields -> same ID."""
+    key_data = {
+        "target": target,
+        "tenant": tenant,
+        "request": {
+            name: request[name] for name in PIN_REQUEST_FIELDS
+            if request.get(name) is not None
+        },
+    }
+    return hashlib.sha256(json.dumps(key_data, sort_keys=True).encode()).hexdigest()[:32]
+
+
+class PinnedResponses:
+    """Permanent request -> response mappings for non-streaming LLM requests.
+
+    Pins live in one Redis hash, separate from the response cache, so they
+    never expire, are not removed by cache invalidation and are served even
+    when the target's cache is disabled. They are matched on the request as
+    the client sends it (before target defaults or cost caps are applied).
+    """
+
+    def __init__(self, redis_url: str, key_prefix: str = "reliapi"):
+        """
+        Args:
+            redis_url: Redis connection URL
+            key_prefix: Prefix for the pins key
+        """
+        self.key_prefix = key_prefix
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Pinned responses connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Pinned responses connection failed (graceful degradation): {e}", exc_info=True)
+
+    def _make_key(self) -> str:
+        return f"{self.key_prefix}:pins"
+
+    def pin(
+        self,
+        target: str,
+        request: Dict[str, Any],
+        content: str,
+        tenant: Optional[str] = None,
+        note: Optional[str] = None,
+    ) -> Optional[Dict[str, Any]]:
+        """Pin a response (replaces an existing pin for the same request).
+
+        Returns:
+            The stored entry, or None if pinning is unavailable
+        """
+        if not self.enabled or not self.client:
+            return None
+
+        entry = {
+            "id": pin_id(target, request, tenant),
+            "target": target,
+            "tenant": tenant,
+            "request": {name: request[name] for name in PIN_REQUEST_FIELDS if request.get(name) is not None},
+            "content": content,
+            "note": note,
+            "pinned_at": time.time(),
+        }
+        try:
+            self.client.hset(self._make_key(), entry["id"], json.dumps(entry))
+        except Exception as e:
+            logger.warning(f"Pin error (graceful degradation): {e}", exc_info=True)
+            return None
+        return entry
+
+    def unpin(self, entry_id: str) -> bool:
+        """Remove a pin; False if there was none."""
+        if not self.enabled or not self.client:
+            return False
+        try:
+            return bool(self.client.hdel(self._make_key(), entry_id))
+        except Exception as e:
+            logger.warning(f"Unpin error (graceful degradation): {e}", exc_info=True)
+            return False
+
+    def get(self, target: str, request: Dict[str, Any], tenant: Optional[str] = None) -> Optional[Dict[str, Any]]:
+        """Pinned entry for a request, if any."""
+        if not self.enabled or not self.client:
+            return None
+        try:
+            raw = self.client.hget(self._make_key(), pin_id(target, request, tenant))
+            return json.loads(raw) if raw else None
+        except Exception as e:
+            logger.warning(f"Pin lookup error (graceful degradation): {e}", exc_info=True)
+            return None
+
+    def list(self) -> List[Dict[str, Any]]:
+        """All pinned entries, oldest first."""
+        if not self.enabled or not self.client:
+            return []
+        try:
+            raw_entries = self.client.hgetall(self._make_key()).values()
+        except Exception as e:
+            logger.warning(f"Pin list error (graceful degradation): {e}", exc_info=True)
+            return []
+
+        entries = []
+        for raw in raw_entries:
+            try:
+                entries.append(json.loads(raw))
+            except json.JSONDecodeError:
+                continue
+        return sorted(entries, key=lambda entry: entry.get("pinned_at", 0))
@@ -0,0 +1,189 @@
+"""WASM plugins that rewrite HTTP proxy requests and responses.
+
+A target's `plugins` lists WebAssembly modules run on every upstream call,
+for logic the built-in options do not cover (custom request signing,
+bespoke rewriting). Plugins are executed with wasmtime (`pip install
+wasmtime`), each call in a fresh instance with no imports, so a module
+has no filesystem, network or clock access and keeps no state between
+calls. Every call is bounded by `max_time_ms` and `max_memory_mb`.
+
+Module ABI (JSON in, JSON out, UTF-8):
+
+- exports `memory` and `alloc(len: i32) -> i32`
+- exports `transform_request(ptr: i32, len: i32) -> i64` and/or
+  `transform_response(ptr: i32, len: i32) -> i64`
+- the return value packs the output as `(out_ptr << 32) | out_len`;
+  `out_len == 0` leaves the message unchanged
+
+transform_request receives `{"method", "path", "headers", "query", "body"}`
+(body as a string or null) right before the upstream call, after the cache
+key is computed. A request body that is not UTF-8 text fails the request
+with PluginError rather than reaching the plugin altered. transform_response receives `{"status_code", "headers",
+"body"}` (body parsed as by the proxy) after `response_transform` and
+before caching. Both return the same shape; missing keys keep their value.
+A plugin's `settings` (e.g. a signing key ID) are passed read-only as
+`settings`.
+"""
+import asyncio
+import json
+import math
+import threading
+import time
+from typing import Any, Dict, List, Optional
+
+# Epoch ticker period: time limits are enforced in these increments
+EPOCH_TICK_MS = 10
+
+
+class PluginError(Exception):
+    """A plugin could not be loaded, trapped, timed out or returned invalid output."""
+
+    def __init__(self, plugin: str, message: str):
+        super().__init__(f"Plugin '{plugin}': {message}")
+        self.plugin = plugin
+
+
+class _Runtime:
+    """Shared wasmtime engine with an epoch ticker for call time limits."""
+
+    def __init__(self):
+        self._engine = None
+        self._modules: Dict[str, Any] = {}
+        self._lock = threading.Lock()
+
+    def engine(self, plugin: str):
+        with self._lock:
+            if self._engine is None:
+                try:
+                    import wasmtime
+                except ImportError:
+                    raise PluginError(plugin, "WASM plugins require the wasmtime package (pip install wasmtime)")
+                config = wasmtime.Config()
+                config.epoch_interruption = True
+                self._engine = wasmtime.Engine(config)
+                threading.Thread(target=self._tick, args=(self._engine,), name="wasm-epoch", daemon=True).start()
+            return self._engine
+
+    @staticmethod
+    def _tick(engine) -> None:
+        while True:
+            time.sleep(EPOCH_TICK_MS / 1000)
+            engine.increment_epoch()
+
+    def module(self, name: str, path: str):
+        """Compiled module for path (compiled once per process)."""
+        engine = self.engine(name)
+        with self._lock:
+            if path not in self._modules:
+                import wasmtime
+
+                try:
+                    self._modules[path] = wasmtime.Module.from_file(engine, path)
+                except (OSError, wasmtime.WasmtimeError) as e:
+                    raise PluginError(name, f"cannot load {path}: {e}")
+            return self._modules[path]
+
+
+_runtime = _Runtime()
+
+
+class WasmPlugin:
+    """One configured plugin module."""
+
+    def __init__(self, config: Dict[str, Any]):
+        self.path = config["path"]
+        self.name = config.get("name") or self.path
+        self.max_time_ms = config.get("max_time_ms", 50)
+        self.max_memory_mb = config.get("max_memory_mb", 16)
+        self.settings = config.get("settings")
+
+    def _call(self, hook: str, payload: bytes) -> Optional[bytes]:
+        """Run hook on payload in a fresh instance (None: hook not exported or message unchanged)."""
+        module = _runtime.module(self.name, self.path)
+        import wasmtime  # Loaded: module() failed otherwise
+
+        if hook not in {export.name for export in module.exports}:
+            return None
+        store = wasmtime.Store(_runtime.engine(self.name))
+        store.set_limits(memory_size=self.max_memory_mb * 1024 * 1024)
+        store.set_epoch_deadline(max(1, math.ceil(self.max_time_ms / EPOCH_TICK_MS)))
+        try:
+            exports = wasmtime.Instance(store, module, []).exports(store)
+            memory = exports["memory"]
+            ptr = exports["alloc"](store, len(payload))
+            memory.write(store, payload, ptr)
+            packed = exports[hook](store, ptr, len(payload))
+            out_ptr, out_len = (packed >> 32) & 0xFFFFFFFF, packed & 0xFFFFFFFF
+            return bytes(memory.read(store, out_ptr, out_ptr + out_len)) if out_len else None
+        except wasmtime.Trap as e:
+            if e.trap_code == wasmtime.TrapCode.INTERRUPT:
+                raise PluginError(self.name, f"{hook} exceeded {self.max_time_ms}ms")
+            raise PluginError(self.name, f"{hook} trapped: {e}")
+        except (KeyError, wasmtime.WasmtimeError) as e:
+            raise PluginError(self.name, f"{hook} failed: {e}")
+
+    def transform(self, hook: str, message: Dict[str, Any]) -> Dict[str, Any]:
+        """Message rewritten by hook (unchanged if the module does not export it)."""
+        payload = {**message, "settings": self.settings} if self.settings is not None else message
+        output = self._call(hook, json.dumps(payload).encode())
+        if output is None:
+            return message
+        try:
+            rewritten = json.loads(output)
+        except (ValueError, UnicodeDecodeError):
+            raise PluginError(self.name, f"{hook} returned invalid JSON")
+        if not isinstance(rewritten, dict):
+            raise PluginError(self.name, f"{hook} must return a JSON object")
+        return {**message, **{key: value for key, value in rewritten.items() if key in message}}
+
+
+def load_plugins(configs: Optional[List[Dict[str, Any]]]) -> List[WasmPlugin]:
+    """Plugins for a target's `plugins` config (modules are compiled on first use)."""
+    return [WasmPlugin(config) for config in configs or []]
+
+
+async def _run(plugins: List[WasmPlugin], hook: str, message: Dict[str, Any]) -> Dict[str, Any]:
+    for plugin in plugins:
+        # CPU-bound (bounded by max_time_ms): keep it off the event loop
+        message = await asyncio.to_thread(plugin.transform, hook, message)
+    return message
+
+
+async def transform_request(
+    plugins: List[WasmPlugin],
+    method: str,
+    path: str,
+    headers: Optional[Dict[str, str]],
+    query: Optional[Dict[str, Any]],
+    body: Optional[bytes],
+) -> Dict[str, Any]:
+    """Outgoing request after every plugin's transform_request.
+
+    Returns:
+        {"method", "path", "headers", "query", "body"} with body as bytes
+
+    Raises:
+        PluginError: The body is not UTF-8, or a plugin failed
+    """
+    text = None
+    if body is not None:
+        try:
+            text = body.decode("utf-8")
+        except UnicodeDecodeError:
+            raise PluginError(plugins[0].name, "transform_request needs a UTF-8 request body, got binary")
+    message = await _run(plugins, "transform_request", {
+        "method": method,
+        "path": path,
+        "headers": headers,
+        "query": query,
+        "body": text,
+    })
+    body = message.get("body")
+    if body is not None and not isinstance(body, str):
+        body = json.dumps(body)
+    return {**message, "body": body.encode() if body is not None else None}
+
+
+async def transform_response(plugins: List[WasmPlugin], result_data: Dict[str, Any]) -> Dict[str, Any]:
+    """Response `{"status_code", "headers", "body"}` after every plugin's transform_response."""
+    return await _run(plugins, "transform_response", result_data)
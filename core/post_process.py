@@ -0,0 +1,32 @@
+"""Opt-in cleanup of LLM completion text (`post_process`).
+
+For structured-data use cases the model's answer often comes wrapped in
+whitespace or a markdown code fence. Modes:
+
+- trim: strip leading/trailing whitespace
+- trim_fences: trim, then unwrap a response that is exactly one fenced
+  block (```json ... ```); prose around or between fences is left alone
+- none: no cleanup (overrides a tenant default)
+
+Only the returned text is changed; caches keep the raw completion, so
+requests with and without post-processing share cache entries.
+"""
+import re
+from typing import Optional
+
+POST_PROCESS_MODES = ("none", "trim", "trim_fences")
+
+# One fenced block spanning the whole (trimmed) text; the info string is optional
+_FENCED = re.compile(r"\A```[\w+.-]*[ \t]*\n(.*?)\n?```\Z", re.DOTALL)
+
+
+def post_process_text(text: Optional[str], mode: Optional[str]) -> Optional[str]:
+    """Cleaned-up text for the mode (unchanged for none, or non-string content)."""
+    if not isinstance(text, str) or mode not in ("trim", "trim_fences"):
+        return text
+    text = text.strip()
+    if mode == "trim_fences":
+        match = _FENCED.match(text)
+        if match and "```" not in match.group(1):
+            text = match.group(1).strip()
+    return text
@@ -0,0 +1,76 @@
+"""Retries driven by provider error codes in the response body.
+
+Some providers report retryable failures with a status the retry engine
+does not retry (a 200 or 400 with an error body, e.g. Anthropic's
+`overloaded_error`). A target's `retry_on_error_codes` lists glob patterns
+matched against the error codes found in the body; a match is retried like
+an upstream 5xx.
+"""
+import fnmatch
+import json
+from typing import Any, Callable, Dict, List, Optional
+
+import httpx
+
+
+class RetryableErrorCode(Exception):
+    """Upstream response body carries an error code listed in retry_on_error_codes.
+
+    `status_code` is 502 so the retry engine classifies it like an upstream
+    5xx. `response` is the last response, handled as usual once retries
+    are exhausted.
+    """
+
+    def __init__(self, code: str, response: httpx.Response):
+        super().__init__(f"Retryable provider error code: {code}")
+        self.code = code
+        self.response = response
+        self.status_code = 502
+
+
+def error_codes(body: Any) -> List[str]:
+    """Error codes in a generic JSON error body (`error.code`, `error.type`, top-level `code`)."""
+    if not isinstance(body, dict):
+        return []
+    codes = []
+    error = body.get("error")
+    if isinstance(error, dict):
+        codes.extend(error.get(field) for field in ("code", "type"))
+    elif isinstance(error, str):
+        codes.append(error)
+    codes.append(body.get("code"))
+    return [str(code) for code in codes if code not in (None, "")]
+
+
+def matching_code(codes: List[str], patterns: List[str]) -> Optional[str]:
+    """First code matching any pattern (case-sensitive globs, e.g. `overloaded*`)."""
+    for code in codes:
+        if any(fnmatch.fnmatchcase(code, pattern) for pattern in patterns):
+            return code
+    return None
+
+
+def error_code_validator(
+    patterns: List[str],
+    extract: Callable[[Dict[str, Any]], List[str]] = error_codes,
+):
+    """Hook for UpstreamHTTPClient.request: raise RetryableErrorCode on a matching error body.
+
+    Args:
+        patterns: Glob patterns from the target's retry_on_error_codes
+        extract: Reads error codes from the parsed body (the LLM adapter's for LLM targets)
+    """
+
+    def _validate(response: httpx.Response) -> None:
+        # 5xx and 429 are retried by status already
+        if response.status_code >= 500 or response.status_code == 429:
+            return
+        try:
+            body = json.loads(response.content) if response.content else None
+        except (json.JSONDecodeError, UnicodeDecodeError):
+            return
+        code = matching_code(extract(body) if isinstance(body, dict) else [], patterns)
+        if code:
+            raise RetryableErrorCode(code, response)
+
+    return _validate
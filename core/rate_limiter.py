@@ -79,6 +79,50 @@ class RateLimiter:
         except Exception as e:
             logger.warning(f"Rate limit check error (graceful degradation): {e}", exc_info=True)
             return True, None  # Allow on error
+
+    def _peek(self, key: str, limit: int, window_s: int) -> Optional[tuple[int, int]]:
+        """(requests remaining, epoch seconds when the count resets) for a counter, not counting one."""
+        if not self.enabled or not self.client:
+            return None
+
+        try:
+            current = int(self.client.get(key) or 0)
+            ttl = self.client.ttl(key)
+            reset_in = ttl if isinstance(ttl, int) and ttl > 0 else window_s
+            return max(limit - current, 0), int(time.time()) + reset_in
+        except Exception as e:
+            logger.warning(f"Rate limit peek error (graceful degradation): {e}", exc_info=True)
+            return None
+
+    def peek_ip_rate_limit(
+        self,
+        ip: str,
+        limit_per_minute: int = 20,
+        prefix: str = "ip",
+    ) -> Optional[tuple[int, int]]:
+        """
+        Read the IP rate limit state without counting a request.
+
+        Returns:
+            (requests remaining, epoch seconds when the count resets), or None without Redis
+        """
+        return self._peek(f"{self.key_prefix}:ratelimit:{prefix}:{ip}", limit_per_minute, 60)
+
+    def peek_burst_protection(
+        self,
+        account_id: str,
+        limit_per_10min: int = 300,
+    ) -> Optional[tuple[int, int]]:
+        """Read the burst protection state (see check_burst_protection) without counting a request."""
+        return self._peek(f"{self.key_prefix}:burst:{account_id}", limit_per_10min, 600)
+
+    def peek_account_burst_limit(
+        self,
+        account_id: str,
+        limit_per_minute: int = 500,
+    ) -> Optional[tuple[int, int]]:
+        """Read the account burst state (see check_account_burst_limit) without counting a request."""
+        return self._peek(f"{self.key_prefix}:burst:account:{account_id}", limit_per_minute, 60)
     
     def check_account_burst_limit(
         self, 
@@ -0,0 +1,79 @@
+"""Latency-based selection between a target's regional endpoints.
+
+A target with `regions` sends each request to the fastest healthy region:
+regions are ranked by an EWMA of their recent upstream latencies, regions
+whose circuit is open go last, and regions without a sample yet go first
+so each one gets measured. If the chosen region fails (network error,
+timeout, 5xx or open circuit) the request fails over to the next one.
+
+Health is tracked by the target's circuit breaker, keyed per region URL.
+`base_url` stays the target's identity (cache keys, provider detection).
+"""
+import threading
+from typing import Any, Dict, List, Optional
+
+from reliapi.core.circuit_breaker import CircuitBreaker
+
+# Weight of the newest latency sample
+LATENCY_EWMA_ALPHA = 0.3
+
+
+def region_url(region: Dict[str, Any]) -> str:
+    return region["base_url"].rstrip("/")
+
+
+def region_name(regions: List[Dict[str, Any]], base_url: Optional[str]) -> Optional[str]:
+    """Name of the region serving base_url."""
+    for region in regions:
+        if region_url(region) == base_url:
+            return region["name"]
+    return None
+
+
+class RegionRouter:
+    """Latency EWMA per target region (in-process)."""
+
+    def __init__(self):
+        self._latency_s: Dict[str, Dict[str, float]] = {}
+        self._lock = threading.Lock()
+
+    def observe(self, target: str, base_url: str, latency_s: float) -> None:
+        """Record the latency of a response from one of the target's regions."""
+        with self._lock:
+            latencies = self._latency_s.setdefault(target, {})
+            current = latencies.get(base_url)
+            latencies[base_url] = latency_s if current is None else current + LATENCY_EWMA_ALPHA * (latency_s - current)
+
+    def latency_ms(self, target: str, base_url: str) -> Optional[float]:
+        """Latency EWMA of a region in milliseconds (None before the first response)."""
+        with self._lock:
+            latency_s = self._latency_s.get(target, {}).get(base_url)
+        return round(latency_s * 1000, 1) if latency_s is not None else None
+
+    def rank(
+        self,
+        target: str,
+        regions: List[Dict[str, Any]],
+        circuit_breaker: CircuitBreaker,
+    ) -> List[Dict[str, Any]]:
+        """Regions in the order to try them: healthy before open, then unmeasured, then fastest."""
+        with self._lock:
+            latencies = dict(self._latency_s.get(target, {}))
+
+        def _key(indexed):
+            index, region = indexed
+            url = region_url(region)
+            latency_s = latencies.get(url)
+            # Config order breaks ties
+            return (circuit_breaker.is_open(url), latency_s is not None, latency_s or 0.0, index)
+
+        return [region for _, region in sorted(enumerate(regions), key=_key)]
+
+    def reset(self) -> None:
+        """Forget all latency samples."""
+        with self._lock:
+            self._latency_s.clear()
+
+
+# Process-wide router used by create_http_client
+region_router = RegionRouter()
@@ -0,0 +1,155 @@
+"""Append-only request log for incident analysis and replay."""
+import hashlib
+import json
+import logging
+import time
+import uuid
+from typing import Any, Dict, Optional
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+# Retention defaults for request log entries
+DEFAULT_RETENTION_S = 86400 * 7  # 7 days
+DEFAULT_MAX_ENTRIES = 10000
+
+
+class RequestLog:
+    """Stores request fingerprints in Redis so calls can be replayed by entry ID.
+
+    Each entry records target, model, a hash of the prompt/body, request
+    parameters, outcome, cost and timing, plus the request payload needed
+    to replay it. Entries expire after `retention_s`; at most `max_entries`
+    are kept per tenant (oldest dropped first).
+    """
+
+    def __init__(
+        self,
+        redis_url: str,
+        key_prefix: str = "reliapi",
+        retention_s: int = DEFAULT_RETENTION_S,
+        max_entries: int = DEFAULT_MAX_ENTRIES,
+    ):
+        """
+        Args:
+            redis_url: Redis connection URL
+            key_prefix: Prefix for request log keys
+            retention_s: How long entries are kept
+            max_entries: Maximum entries kept per tenant
+        """
+        self.key_prefix = key_prefix
+        self.retention_s = retention_s
+        self.max_entries = max_entries
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Request log connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Request log connection failed (graceful degradation): {e}", exc_info=True)
+
+    def _make_key(self, kind: str, tenant: Optional[str] = None) -> str:
+        # Multi-tenant isolation: entries are only visible to their tenant
+        if tenant:
+            return f"{self.key_prefix}:tenant:{tenant}:request_log_{kind}"
+        return f"{self.key_prefix}:request_log_{kind}"
+
+    @staticmethod
+    def make_prompt_hash(payload: Dict[str, Any]) -> str:
+        """Hash the prompt (messages) or HTTP body of a request payload."""
+        prompt = payload.get("messages", payload.get("body"))
+        return hashlib.sha256(json.dumps(prompt, sort_keys=True, default=str).encode()).hexdigest()
+
+    def record(
+        self,
+        request_id: str,
+        kind: str,
+        target: str,
+        payload: Dict[str, Any],
+        status: str,
+        latency_ms: int,
+        status_code: Optional[int] = None,
+        model: Optional[str] = None,
+        cost_usd: Optional[float] = None,
+        retries: int = 0,
+        cache_hit: bool = False,
+        tenant: Optional[str] = None,
+    ) -> Optional[str]:
+        """Append a request log entry.
+
+        Args:
+            request_id: Request ID
+            kind: "http" or "llm"
+            target: Target name
+            payload: Request fields needed to replay the call
+            status: "success" or "error"
+            latency_ms: Request latency in milliseconds
+            status_code: Response status code
+            model: LLM model name (for LLM requests)
+            cost_usd: Cost in USD (for LLM requests)
+            retries: Number of retries
+            cache_hit: Whether the response came from cache
+            tenant: Tenant name for multi-tenant isolation
+
+        Returns:
+            Entry ID, or None if the log is unavailable
+        """
+        if not self.enabled or not self.client:
+            return None
+
+        entry_id = f"rlog_{uuid.uuid4().hex[:16]}"
+        entry = {
+            "id": entry_id,
+            "ts": time.time(),
+            "request_id": request_id,
+            "kind": kind,
+            "target": target,
+            "model": model,
+            "prompt_hash": self.make_prompt_hash(payload),
+            "params": {k: v for k, v in payload.items() if k not in ("messages", "body")},
+            "status": status,
+            "status_code": status_code,
+            "cost_usd": cost_usd or 0.0,
+            "latency_ms": latency_ms,
+            "retries": retries,
+            "cache_hit": cache_hit,
+            "request": payload,
+        }
+        index_key = self._make_key("index", tenant)
+        try:
+            pipe = self.client.pipeline()
+            pipe.setex(f"{self._make_key('entry', tenant)}:{entry_id}", self.retention_s, json.dumps(entry, default=str))
+            # Index keeps entry order for the max_entries cap; trimmed entries expire on their own TTL
+            pipe.lpush(index_key, entry_id)
+            pipe.ltrim(index_key, 0, self.max_entries - 1)
+            pipe.expire(index_key, self.retention_s)
+            pipe.execute()
+        except Exception as e:
+            logger.warning(f"Request log record error (graceful degradation): {e}", exc_info=True)
+            return None
+        return entry_id
+
+    def get(self, entry_id: str, tenant: Optional[str] = None) -> Optional[Dict[str, Any]]:
+        """Get a request log entry by ID (None if missing, expired or trimmed)."""
+        if not self.enabled or not self.client:
+            return None
+
+        try:
+            # Entries dropped from the index by max_entries are no longer replayable
+            index = self.client.lrange(self._make_key("index", tenant), 0, self.max_entries - 1)
+            if entry_id not in index:
+                return None
+            raw = self.client.get(f"{self._make_key('entry', tenant)}:{entry_id}")
+        except Exception as e:
+            logger.warning(f"Request log get error (graceful degradation): {e}", exc_info=True)
+            return None
+
+        if not raw:
+            return None
+        try:
+            return json.loads(raw)
+        except json.JSONDecodeError:
+            return None
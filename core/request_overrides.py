@@ -0,0 +1,105 @@
+"""Per-request timeout and retry overrides from X-ReliAPI-* headers.
+
+Clients can raise or lower the target's timeout and retry count for a
+single request. Values are clamped to server-side ceilings
+(`request_overrides` in config.yaml), so a client can never exceed what
+the operator allows.
+"""
+from typing import Any, Dict, Mapping, Optional
+
+TIMEOUT_HEADER = "X-ReliAPI-Timeout-Ms"
+MAX_RETRIES_HEADER = "X-ReliAPI-Max-Retries"
+NO_RETRY_HEADER = "X-ReliAPI-No-Retry"
+
+DEFAULT_MAX_TIMEOUT_MS = 120000
+DEFAULT_MAX_RETRIES = 5
+
+# Error classes of the retry matrix (see RetryEngine)
+RETRY_ERROR_CLASSES = ("429", "5xx", "net", "timeout")
+
+_TRUE_VALUES = ("1", "true", "yes")
+_FALSE_VALUES = ("0", "false", "no")
+
+
+class InvalidRequestOverride(ValueError):
+    """Override header has a value that cannot be parsed."""
+
+
+def _header(headers: Mapping[str, str], name: str) -> Optional[str]:
+    value = headers.get(name)
+    if value is None:
+        value = headers.get(name.lower())
+    return value.strip() if value is not None else None
+
+
+def _parse_int(name: str, value: str, minimum: int) -> int:
+    try:
+        parsed = int(value)
+    except ValueError:
+        raise InvalidRequestOverride(f"{name} must be an integer, got '{value}'")
+    if parsed < minimum:
+        raise InvalidRequestOverride(f"{name} must be >= {minimum}, got {parsed}")
+    return parsed
+
+
+def parse_request_overrides(
+    headers: Mapping[str, str],
+    limits: Optional[Dict[str, Any]] = None,
+) -> Optional[Dict[str, int]]:
+    """Read override headers and clamp them to the configured ceilings.
+
+    Args:
+        headers: Request headers
+        limits: `request_overrides` config (enabled, max_timeout_ms, max_retries)
+
+    Returns:
+        {"timeout_ms": ..., "max_retries": ...} with only the overridden keys, or None
+
+    Raises:
+        InvalidRequestOverride: If a header value is malformed
+    """
+    limits = limits or {}
+    if not limits.get("enabled", True):
+        return None
+
+    overrides: Dict[str, int] = {}
+
+    timeout = _header(headers, TIMEOUT_HEADER)
+    if timeout:
+        timeout_ms = _parse_int(TIMEOUT_HEADER, timeout, minimum=1)
+        overrides["timeout_ms"] = min(timeout_ms, limits.get("max_timeout_ms", DEFAULT_MAX_TIMEOUT_MS))
+
+    max_retries = _header(headers, MAX_RETRIES_HEADER)
+    if max_retries:
+        retries = _parse_int(MAX_RETRIES_HEADER, max_retries, minimum=0)
+        overrides["max_retries"] = min(retries, limits.get("max_retries", DEFAULT_MAX_RETRIES))
+
+    no_retry = _header(headers, NO_RETRY_HEADER)
+    if no_retry:
+        if no_retry.lower() in _TRUE_VALUES:
+            overrides["max_retries"] = 0
+        elif no_retry.lower() not in _FALSE_VALUES:
+            raise InvalidRequestOverride(f"{NO_RETRY_HEADER} must be true or false, got '{no_retry}'")
+
+    return overrides or None
+
+
+def apply_request_overrides(
+    target_config: Dict[str, Any],
+    overrides: Optional[Dict[str, int]],
+) -> Dict[str, Any]:
+    """Target config with the per-request overrides applied (original is not modified)."""
+    if not overrides:
+        return target_config
+
+    effective = dict(target_config)
+    if "timeout_ms" in overrides:
+        effective["timeout_ms"] = overrides["timeout_ms"]
+    if "max_retries" in overrides:
+        # `attempts` counts the first try as well
+        retry_matrix = target_config.get("retry_matrix") or {}
+        effective["retry_matrix"] = {
+            error_class: {**retry_matrix.get(error_class, {}), "attempts": overrides["max_retries"] + 1}
+            for error_class in {*RETRY_ERROR_CLASSES, *retry_matrix}
+        }
+    return effective
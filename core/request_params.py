@@ -0,0 +1,45 @@
+"""Per-target whitelist of request parameters forwarded upstream (`llm.allowed_params`).
+
+Operators can pin down exactly which optional request fields reach the
+provider, so clients cannot change billing or behavior with parameters
+the target was not meant to accept (n, logprobs, logit_bias, ...).
+Parameters outside the whitelist are handled per `llm.on_unknown_param`:
+dropped (default), rejected with 400 PARAM_NOT_ALLOWED, or forwarded and
+only counted, which is useful to audit traffic before enforcing a list.
+"""
+from typing import Any, Dict, List, Optional, Tuple
+
+# Optional request fields that can be whitelisted (messages, model and max_tokens always apply)
+FORWARDABLE_PARAMS = (
+    "temperature",
+    "top_p",
+    "stop",
+    "response_format",
+    "n",
+    "logit_bias",
+    "seed",
+    "logprobs",
+    "top_logprobs",
+)
+
+DEFAULT_ON_UNKNOWN_PARAM = "drop"
+
+
+def filter_params(
+    params: Dict[str, Any], allowed: Optional[List[str]], on_unknown: str = DEFAULT_ON_UNKNOWN_PARAM
+) -> Tuple[Dict[str, Any], List[str]]:
+    """(params to forward, names of the set params outside allowed).
+
+    Without a whitelist everything is forwarded. With on_unknown "drop" the
+    disallowed params are set to None; "reject" and "forward" leave them, the
+    caller rejects or forwards the request.
+    """
+    if allowed is None:
+        return params, []
+    disallowed = sorted(
+        name for name, value in params.items()
+        if value is not None and value is not False and name not in allowed
+    )
+    if on_unknown == "drop" and disallowed:
+        params = {name: None if name in disallowed else value for name, value in params.items()}
+    return params, disallowed
@@ -0,0 +1,59 @@
+"""Preview of large /proxy/http response bodies (`max_response_chars`).
+
+The cache and idempotency store keep the full body; only the body returned
+to the client is cut down. Text bodies are cut at `max_chars` characters.
+JSON bodies are measured as compact JSON and shortened structurally, so the
+preview is still valid JSON: lists and objects keep their leading items and
+keys that fit, strings inside them are cut, and the rest is left out.
+"""
+import json
+from typing import Any, Tuple
+
+# Returned by _preview when not even a shortened value fits
+_OMIT = object()
+
+
+def _size(value: Any) -> int:
+    return len(json.dumps(value, ensure_ascii=False, separators=(",", ":")))
+
+
+def _preview(value: Any, budget: int) -> Tuple[Any, bool]:
+    """(value fitting in budget chars of compact JSON, whether it was shortened); _OMIT if nothing fits."""
+    if _size(value) <= budget:
+        return value, False
+    if isinstance(value, str):
+        # Longest prefix that fits; escaped characters serialize longer than one char,
+        # so search for it instead of assuming one char per char of budget
+        low, high = 0, min(len(value), max(budget - 2, 0))
+        while low < high:
+            middle = (low + high + 1) // 2
+            if _size(value[:middle]) <= budget:
+                low = middle
+            else:
+                high = middle - 1
+        return (value[:low] if budget >= 2 else _OMIT), True
+    if isinstance(value, (list, dict)):
+        items = list(value.items()) if isinstance(value, dict) else list(enumerate(value))
+        out: Any = {} if isinstance(value, dict) else []
+        used = 2
+        for key, item in items:
+            overhead = (1 if used > 2 else 0) + (_size(key) + 1 if isinstance(value, dict) else 0)
+            shortened, _ = _preview(item, budget - used - overhead)
+            if shortened is _OMIT:
+                break
+            if isinstance(value, dict):
+                out[key] = shortened
+            else:
+                out.append(shortened)
+            used += overhead + _size(shortened)
+        return (out if budget >= 2 else _OMIT), True
+    return _OMIT, True
+
+
+def truncate_body(body: Any, max_chars: int) -> Tuple[Any, bool]:
+    """(preview of body, whether it was truncated)."""
+    if isinstance(body, (str, bytes)):
+        text = body.decode("utf-8", errors="replace") if isinstance(body, bytes) else body
+        return (text[:max_chars], True) if len(text) > max_chars else (body, False)
+    preview, truncated = _preview(body, max_chars)
+    return (None if preview is _OMIT else preview), truncated
@@ -0,0 +1,80 @@
+"""JSON Schema validation of upstream HTTP responses and LLM JSON outputs."""
+import json
+from typing import Any, Dict, Optional
+
+import httpx
+from jsonschema import Draft202012Validator
+from jsonschema.exceptions import best_match
+
+
+class SchemaViolation(Exception):
+    """Upstream response body does not match the target's response schema.
+
+    `status_code` is 502 when the violation is retryable, so the retry
+    engine classifies it like an upstream 5xx.
+    """
+
+    def __init__(self, path: str, message: str, retryable: bool = False):
+        super().__init__(f"Response schema violation at {path}: {message}")
+        self.path = path
+        self.message = message
+        self.retryable = retryable
+        self.status_code = 502 if retryable else None
+
+
+def _json_path(path) -> str:
+    """Render a jsonschema error path as `$.items[0].id`."""
+    rendered = "$"
+    for part in path:
+        rendered += f"[{part}]" if isinstance(part, int) else f".{part}"
+    return rendered
+
+
+def validate_response_body(
+    schema: Dict[str, Any],
+    body: bytes,
+    retryable: bool = False,
+) -> Optional[SchemaViolation]:
+    """Validate a JSON response body against a schema.
+
+    Returns:
+        SchemaViolation for the most relevant error, or None if the body matches
+    """
+    try:
+        instance = json.loads(body) if body else None
+    except (json.JSONDecodeError, UnicodeDecodeError) as e:
+        return SchemaViolation("$", f"response is not valid JSON ({e})", retryable)
+
+    error = best_match(Draft202012Validator(schema).iter_errors(instance))
+    if error is None:
+        return None
+    return SchemaViolation(_json_path(error.absolute_path), error.message, retryable)
+
+
+def response_validator(schema: Dict[str, Any]):
+    """Hook for UpstreamHTTPClient.request: raise a retryable SchemaViolation on 2xx mismatch."""
+
+    def _validate(response: httpx.Response) -> None:
+        if not response.is_success:
+            return
+        violation = validate_response_body(schema, response.content, retryable=True)
+        if violation:
+            raise violation
+
+    return _validate
+
+
+def validate_llm_output(schema: Dict[str, Any], content: Any) -> Optional[SchemaViolation]:
+    """Validate an LLM completion's text (a JSON document) against a client-supplied json_schema."""
+    if not isinstance(content, str):
+        return SchemaViolation("$", "completion has no text content")
+    return validate_response_body(schema, content.encode("utf-8"))
+
+
+def corrective_instruction(violation: SchemaViolation) -> str:
+    """Follow-up user message asking the model to fix a schema violation."""
+    return (
+        "Your previous reply does not match the required JSON Schema "
+        f"(at {violation.path}: {violation.message}). "
+        "Reply again with only a JSON document that matches the schema, with no other text."
+    )
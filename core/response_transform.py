@@ -0,0 +1,85 @@
+"""Per-target rewriting of upstream HTTP response bodies.
+
+`response_transform` is a list of rules applied in order to the parsed
+JSON body before it is cached and returned:
+
+- rename: move `field` to the sibling key `to`
+- drop: remove `field`
+- default: set `field` to `value` when it is missing or null
+- replace: regex-substitute `pattern` with `replacement` in a string `field`
+
+Fields are dotted paths; `[]` after a segment applies the rest of the path
+to every element of a list (e.g. `items[].id`).
+"""
+import copy
+import re
+from typing import Any, Dict, List, Optional, Tuple
+
+_MISSING = object()
+
+
+def parse_path(path: str) -> List[Tuple[str, bool]]:
+    """Split `a.items[].id` into (key, is_list) segments.
+
+    Raises:
+        ValueError: If the path is empty or malformed
+    """
+    segments = []
+    for part in path.split("."):
+        is_list = part.endswith("[]")
+        key = part[:-2] if is_list else part
+        if not key or "[" in key or "]" in key:
+            raise ValueError(f"Invalid field path '{path}'")
+        segments.append((key, is_list))
+    return segments
+
+
+def _parents(node: Any, segments: List[Tuple[str, bool]]) -> List[Dict[str, Any]]:
+    """Objects holding the last segment's key (every list element for `[]` segments)."""
+    nodes = [node]
+    for key, is_list in segments[:-1]:
+        next_nodes = []
+        for current in nodes:
+            if not isinstance(current, dict) or key not in current:
+                continue
+            child = current[key]
+            if is_list:
+                next_nodes.extend(child if isinstance(child, list) else [])
+            else:
+                next_nodes.append(child)
+        nodes = next_nodes
+    return [current for current in nodes if isinstance(current, dict)]
+
+
+def _apply_rule(body: Any, rule: Dict[str, Any]) -> None:
+    segments = parse_path(rule["field"])
+    key, is_list = segments[-1]
+    op = rule["op"]
+    for parent in _parents(body, segments):
+        value = parent.get(key, _MISSING)
+        if op == "default":
+            if value is _MISSING or value is None:
+                parent[key] = copy.deepcopy(rule.get("value"))
+        elif value is _MISSING:
+            continue
+        elif op == "drop":
+            del parent[key]
+        elif op == "rename":
+            parent[rule["to"]] = parent.pop(key)
+        elif op == "replace":
+            pattern = re.compile(rule["pattern"])
+            replacement = rule.get("replacement", "")
+            if is_list and isinstance(value, list):
+                parent[key] = [pattern.sub(replacement, v) if isinstance(v, str) else v for v in value]
+            elif isinstance(value, str):
+                parent[key] = pattern.sub(replacement, value)
+
+
+def apply_response_transform(body: Any, rules: Optional[List[Dict[str, Any]]]) -> Any:
+    """Body with the target's transform rules applied (the input is not modified)."""
+    if not rules or not isinstance(body, (dict, list)):
+        return body
+    body = copy.deepcopy(body)
+    for rule in rules:
+        _apply_rule(body, rule)
+    return body
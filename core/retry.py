@@ -2,7 +2,10 @@
 import asyncio
 import random
 import time
-from typing import Any, Callable, Dict, Optional, TypeVar
+from typing import TYPE_CHECKING, Any, Callable, Dict, Optional, TypeVar
+
+if TYPE_CHECKING:
+    from reliapi.core.retry_budget import RetryBudgetScope
 
 T = TypeVar("T")
 
@@ -61,12 +64,18 @@ class RetryMatrix:
 class RetryEngine:
     """Universal retry engine for HTTP requests."""
 
-    def __init__(self, matrix: Optional[Dict[str, RetryMatrix]] = None):
+    def __init__(
+        self,
+        matrix: Optional[Dict[str, RetryMatrix]] = None,
+        budget: Optional["RetryBudgetScope"] = None,
+    ):
         """
         Args:
             matrix: Dictionary mapping error classes to retry policies
                    Keys: "429", "5xx", "net", "timeout"
+            budget: Retry budget; when exhausted, errors are raised without retrying
         """
+        self.budget = budget
         self.matrix = matrix or {
             "429": RetryMatrix(attempts=3, backoff="exp-jitter", base_s=1.0),
             "5xx": RetryMatrix(attempts=2, backoff="exp-jitter", base_s=1.0),
@@ -115,6 +124,9 @@ class RetryEngine:
         last_error: Optional[Exception] = None
         last_status: Optional[int] = None
 
+        if self.budget:
+            self.budget.record_request()
+
         for attempt in range(1, 10):  # Max 10 attempts across all policies
             try:
                 result = await func()
@@ -136,6 +148,10 @@ class RetryEngine:
                 if not policy or attempt >= policy.attempts:
                     raise
 
+                # Fail fast instead of adding load once the retry budget is spent
+                if self.budget and not self.budget.try_acquire_retry():
+                    raise
+
                 # Extract Retry-After if available
                 retry_after = None
                 if get_retry_after:
@@ -0,0 +1,129 @@
+"""Retry budget: cap retries to a fraction of requests in a rolling window.
+
+Retries amplify load during incidents. A retry budget allows at most
+`ratio` retries per request (e.g. 0.1 = 10%) within `window_s`, with a
+`min_retries` floor so low-traffic targets can still retry. When the
+budget is spent, requests fail fast instead of retrying.
+"""
+import logging
+import time
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Deque, Dict, List, Optional, Tuple
+
+from reliapi.metrics.prometheus import retry_budget_exhausted_total, retry_budget_utilization
+
+logger = logging.getLogger(__name__)
+
+# Scope name for the budget shared by all targets
+GLOBAL_SCOPE = "global"
+
+
+@dataclass
+class _BudgetWindow:
+    """Rolling window of requests and retries for one scope."""
+
+    ratio: float
+    window_s: float
+    min_retries: int
+    requests: Deque[float] = field(default_factory=deque)
+    retries: Deque[float] = field(default_factory=deque)
+
+    def prune(self, now: float) -> None:
+        for entries in (self.requests, self.retries):
+            while entries and now - entries[0] >= self.window_s:
+                entries.popleft()
+
+    def allowance(self) -> float:
+        """Retries allowed in the current window."""
+        return max(float(self.min_retries), self.ratio * len(self.requests))
+
+    def utilization(self) -> float:
+        """Fraction of the retry allowance consumed (0.0-1.0+)."""
+        allowance = self.allowance()
+        return len(self.retries) / allowance if allowance else 1.0
+
+
+class RetryBudget:
+    """Tracks retry budgets for the global scope and per target (in-process).
+
+    Budgets are opt-in: the global budget comes from `retry_budget` at the
+    top of config.yaml, per-target budgets from `targets.<name>.retry_budget`.
+    A retry must fit every budget that applies to it.
+    """
+
+    def __init__(self):
+        self._windows: Dict[str, _BudgetWindow] = {}
+        self._global_config: Optional[Dict[str, Any]] = None
+
+    def configure(self, global_config: Optional[Dict[str, Any]]) -> None:
+        """Set (or clear) the global budget and reset all windows."""
+        self._global_config = global_config
+        self._windows.clear()
+
+    def _window(self, scope: str, config: Dict[str, Any]) -> _BudgetWindow:
+        window = self._windows.get(scope)
+        if window is None:
+            window = _BudgetWindow(
+                ratio=config.get("ratio", 0.1),
+                window_s=config.get("window_s", 60),
+                min_retries=config.get("min_retries", 10),
+            )
+            self._windows[scope] = window
+        return window
+
+    def _scopes(self, target: str, target_config: Optional[Dict[str, Any]]) -> List[Tuple[str, _BudgetWindow]]:
+        """(scope, window) for every budget that applies to the target."""
+        scopes = []
+        if self._global_config:
+            scopes.append((GLOBAL_SCOPE, self._window(GLOBAL_SCOPE, self._global_config)))
+        if target_config:
+            scopes.append((target, self._window(target, target_config)))
+        return scopes
+
+    def record_request(self, target: str, target_config: Optional[Dict[str, Any]] = None) -> None:
+        """Count a request (first attempt) towards the budgets."""
+        now = time.monotonic()
+        for scope, window in self._scopes(target, target_config):
+            window.prune(now)
+            window.requests.append(now)
+            retry_budget_utilization.labels(scope=scope).set(window.utilization())
+
+    def try_acquire_retry(self, target: str, target_config: Optional[Dict[str, Any]] = None) -> bool:
+        """Reserve one retry; False if any applicable budget is exhausted."""
+        now = time.monotonic()
+        scopes = self._scopes(target, target_config)
+        for scope, window in scopes:
+            window.prune(now)
+            if len(window.retries) + 1 > window.allowance():
+                retry_budget_exhausted_total.labels(scope=scope).inc()
+                logger.warning(f"Retry budget exhausted for {scope}: failing fast instead of retrying")
+                return False
+
+        for scope, window in scopes:
+            window.retries.append(now)
+            retry_budget_utilization.labels(scope=scope).set(window.utilization())
+        return True
+
+    def scope(self, target: str, target_config: Optional[Dict[str, Any]] = None) -> "RetryBudgetScope":
+        """Budget handle for one target's client."""
+        return RetryBudgetScope(self, target, target_config)
+
+
+@dataclass
+class RetryBudgetScope:
+    """RetryBudget bound to a target, passed to RetryEngine."""
+
+    budget: RetryBudget
+    target: str
+    target_config: Optional[Dict[str, Any]] = None
+
+    def record_request(self) -> None:
+        self.budget.record_request(self.target, self.target_config)
+
+    def try_acquire_retry(self) -> bool:
+        return self.budget.try_acquire_retry(self.target, self.target_config)
+
+
+# Process-wide retry budget (configured at startup from config.yaml)
+retry_budget = RetryBudget()
@@ -0,0 +1,51 @@
+"""SSE comment heartbeats for streams behind buffering proxies (`streaming`).
+
+Load balancers and reverse proxies close connections that stay idle, and
+some buffer a stream until enough bytes arrive. While the upstream is
+thinking, `with_heartbeats` sends an SSE comment line, which clients
+ignore by spec. Heartbeats are only sent between whole events, so the
+event stream itself is never split or altered.
+"""
+import asyncio
+from typing import AsyncIterator, Optional
+
+# SSE comment (lines starting with ":" are ignored by clients)
+HEARTBEAT = ":keep-alive\n\n"
+
+
+def is_heartbeat(event: str) -> bool:
+    """Whether an SSE event is a comment (such as a heartbeat) rather than data."""
+    return event.startswith(":")
+
+
+def with_heartbeats(events: AsyncIterator[str], interval_s: Optional[float]) -> AsyncIterator[str]:
+    """events, plus a HEARTBEAT whenever none arrived for interval_s (events as is without one)."""
+    if not interval_s:
+        return events
+    return _heartbeats(events, interval_s)
+
+
+async def _heartbeats(events: AsyncIterator[str], interval_s: float) -> AsyncIterator[str]:
+    iterator = events.__aiter__()
+    pending: Optional[asyncio.Future] = None
+    try:
+        while True:
+            if pending is None:
+                pending = asyncio.ensure_future(iterator.__anext__())
+            done, _ = await asyncio.wait({pending}, timeout=interval_s)
+            if not done:
+                yield HEARTBEAT
+                continue
+            try:
+                event = pending.result()
+            except StopAsyncIteration:
+                pending = None
+                return
+            pending = None
+            yield event
+    finally:
+        # Client went away: stop a pending read, or close the stream so its cleanup runs
+        if pending is not None and not pending.done():
+            pending.cancel()
+        elif hasattr(iterator, "aclose"):
+            await iterator.aclose()
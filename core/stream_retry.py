@@ -0,0 +1,79 @@
+"""Replay-safe retries for LLM streams (`llm.stream_retry`).
+
+A stream that fails before any text reached the client is retried from
+scratch; the client never notices. A stream that fails after partial
+output either ends with the usual interrupted error (`on_partial:
+abort`) or is restarted (`on_partial: restart`): the new attempt's text
+is matched against what was already sent, the overlap is dropped, and
+only the continuation is forwarded. If the restarted text diverges from
+what was sent (sampling is not deterministic), the original failure is
+raised rather than sending a spliced completion.
+
+The chunks are OpenAI `chat.completion.chunk` dicts (what every adapter
+yields); only choice 0 content is deduplicated.
+"""
+import asyncio
+from typing import Any, AsyncIterator, Callable, Dict
+
+
+def _with_content(chunk: Dict[str, Any], content: str) -> Dict[str, Any]:
+    """chunk with choice 0's delta content replaced."""
+    return {**chunk, "choices": [
+        {**choice, "delta": {**choice.get("delta", {}), "content": content}} if choice.get("index", 0) == 0 else choice
+        for choice in chunk["choices"]
+    ]}
+
+
+async def replay_safe_stream(
+    open_stream: Callable[[], AsyncIterator[Dict[str, Any]]],
+    config: Dict[str, Any],
+    is_retryable: Callable[[BaseException], bool],
+    stats: Dict[str, Any],
+) -> AsyncIterator[Dict[str, Any]]:
+    """Yield chunks from open_stream(), reopening it on retryable failures.
+
+    Args:
+        open_stream: Starts one upstream attempt
+        config: `llm.stream_retry` (attempts, on_partial, backoff_ms)
+        is_retryable: Whether a failure may be retried
+        stats: Updated in place with "retries" and "restarted" (partial output replayed)
+
+    Raises:
+        The last failure once retries are exhausted or not allowed, or the
+        failure that caused a restart whose output diverged
+    """
+    sent = ""  # Choice 0 text already forwarded
+    attempt = 0
+    failure = None
+    while True:
+        replayed = ""  # Choice 0 text of the current attempt
+        try:
+            async for chunk in open_stream():
+                choices = [choice for choice in chunk.get("choices") or [] if choice.get("index", 0) == 0]
+                text = choices[0].get("delta", {}).get("content") if choices else None
+                if not text:
+                    yield chunk
+                    continue
+                # Part of this attempt's text the client has already seen
+                overlap = min(max(len(sent) - len(replayed), 0), len(text))
+                if sent[len(replayed):len(replayed) + overlap] != text[:overlap]:
+                    stats["diverged"] = True
+                    raise failure
+                replayed += text
+                if overlap < len(text):
+                    sent += text[overlap:]
+                    yield _with_content(chunk, text[overlap:]) if overlap else chunk
+                elif choices[0].get("finish_reason"):
+                    yield _with_content(chunk, "")
+            return
+        except Exception as e:
+            if stats.get("diverged") or attempt >= config.get("attempts", 2) or not is_retryable(e):
+                raise
+            if sent and config.get("on_partial", "abort") != "restart":
+                raise
+            failure = e
+            attempt += 1
+            stats["retries"] = attempt
+            if sent:
+                stats["restarted"] = True
+            await asyncio.sleep(config.get("backoff_ms", 200) * attempt / 1000)
@@ -0,0 +1,145 @@
+"""Copy of completed LLM streams for analytics pipelines (`stream_tee`).
+
+Streams are delivered to the client as they arrive; the tee only keeps the
+assembled content on the side and, once the stream has finished, queues one
+record for a background worker that writes it to the sink (a JSON-lines
+file, a webhook, or Redis next to the usage records). Queueing never
+blocks: when the queue is full the record is dropped and counted. Sink
+failures are logged and counted, never raised, so the tee cannot slow
+down or break the client stream.
+
+Content is redacted with `redact_patterns` by the worker, off the client
+path, before it leaves the process; prompts are only included with
+`include_messages`.
+"""
+import asyncio
+import json
+import logging
+import re
+import time
+from typing import Any, Dict, List, Optional
+
+import httpx
+
+from reliapi.core.usage import UsageStore
+from reliapi.metrics.prometheus import stream_tee_records_total
+
+logger = logging.getLogger(__name__)
+
+# Email addresses and long digit runs (card, phone and account numbers)
+DEFAULT_REDACT_PATTERNS = [r"[\w.+-]+@[\w-]+\.[\w.-]+", r"\d[\d -]{7,}\d"]
+
+REDACTED = "[redacted]"
+
+
+class StreamTee:
+    """Queues completed stream records and writes them to a file or webhook in the background."""
+
+    def __init__(
+        self,
+        sink: str,
+        path: Optional[str] = None,
+        url: Optional[str] = None,
+        headers: Optional[Dict[str, str]] = None,
+        include_messages: bool = False,
+        redact_patterns: Optional[List[str]] = None,
+        max_queue: int = 1000,
+        timeout_s: float = 5.0,
+        usage_store: Optional[UsageStore] = None,
+    ):
+        """
+        Args:
+            sink: "file" (JSON lines appended to path), "webhook" (POST to url) or
+                "usage" (kept in Redis by usage_store, next to the usage records)
+            path: File for the "file" sink
+            url: Endpoint for the "webhook" sink
+            headers: Extra webhook request headers (e.g., Authorization)
+            include_messages: Also record the (redacted) request messages
+            redact_patterns: Regexes replaced with REDACTED (default DEFAULT_REDACT_PATTERNS)
+            max_queue: Records waiting for the sink before new ones are dropped
+            timeout_s: Webhook request timeout
+            usage_store: Store for the "usage" sink
+        """
+        self.sink = sink
+        self.path = path
+        self.url = url
+        self.headers = headers or {}
+        self.include_messages = include_messages
+        self.patterns = [
+            re.compile(p) for p in (DEFAULT_REDACT_PATTERNS if redact_patterns is None else redact_patterns)
+        ]
+        self.timeout_s = timeout_s
+        self.usage_store = usage_store
+        self._queue: asyncio.Queue = asyncio.Queue(maxsize=max_queue)
+        self._worker: Optional[asyncio.Task] = None
+
+    def redact(self, text: str) -> str:
+        for pattern in self.patterns:
+            text = pattern.sub(REDACTED, text)
+        return text
+
+    def _redacted(self, record: Dict[str, Any]) -> Dict[str, Any]:
+        redacted = {**record, "content": self.redact(record["content"])}
+        if "messages" in record:
+            redacted["messages"] = [
+                {**message, "content": self.redact(str(message.get("content", "")))}
+                for message in record["messages"]
+            ]
+        return redacted
+
+    def submit(
+        self,
+        content: str,
+        messages: Optional[List[Dict[str, Any]]] = None,
+        **fields: Any,
+    ) -> bool:
+        """Queue a completed stream's record without waiting; returns False if it was dropped.
+
+        The record is queued as is; the worker redacts it.
+        """
+        record: Dict[str, Any] = {"ts": time.time(), **fields, "content": content}
+        if self.include_messages and messages is not None:
+            record["messages"] = messages
+        try:
+            self._queue.put_nowait(record)
+        except asyncio.QueueFull:
+            stream_tee_records_total.labels(sink=self.sink, outcome="dropped").inc()
+            return False
+        if self._worker is None or self._worker.done():
+            self._worker = asyncio.create_task(self._run())
+        return True
+
+    async def _run(self) -> None:
+        async with httpx.AsyncClient(timeout=self.timeout_s) as client:
+            while True:
+                record = await self._queue.get()
+                try:
+                    await self._write(client, self._redacted(record))
+                    stream_tee_records_total.labels(sink=self.sink, outcome="written").inc()
+                except Exception as e:
+                    stream_tee_records_total.labels(sink=self.sink, outcome="failed").inc()
+                    logger.warning(f"Stream tee {self.sink} sink failed: {e}")
+                finally:
+                    self._queue.task_done()
+
+    async def _write(self, client: httpx.AsyncClient, record: Dict[str, Any]) -> None:
+        if self.sink == "webhook":
+            response = await client.post(self.url, json=record, headers=self.headers)
+            response.raise_for_status()
+        elif self.sink == "usage":
+            await asyncio.to_thread(self.usage_store.record_stream, record)
+        else:
+            line = json.dumps(record) + "\n"
+            await asyncio.to_thread(self._append, line)
+
+    def _append(self, line: str) -> None:
+        with open(self.path, "a", encoding="utf-8") as f:
+            f.write(line)
+
+    async def close(self) -> None:
+        """Write the queued records and stop the worker (at shutdown)."""
+        if self._worker is None:
+            return
+        if not self._worker.done():
+            await self._queue.join()
+        self._worker.cancel()
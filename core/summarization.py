@@ -0,0 +1,68 @@
+"""Summarization of long conversation history (`llm.summarization`).
+
+When a conversation's estimated prompt exceeds `threshold_tokens`, the
+older turns are sent to a summarizer model and replaced with a single
+system message holding the summary. System messages and the latest
+`keep_recent` messages are kept verbatim, so the model still sees its
+instructions and the current exchange word for word.
+
+The summarizer runs at temperature 0 and its result is cached (with the
+target's cache) under the older turns, so a growing conversation reuses
+one summary until more turns age out, and the main request's cache key
+stays stable between identical requests.
+"""
+from typing import Any, Dict, List, Optional, Tuple
+
+from reliapi.core.cost_estimator import CostEstimator
+
+DEFAULT_PROMPT = (
+    "Summarize the conversation below in a few sentences. Keep names, facts, numbers, "
+    "decisions and open questions; drop pleasantries. Reply with the summary only."
+)
+
+SUMMARY_PREFIX = "Summary of the earlier conversation: "
+
+
+def split_history(
+    messages: List[Dict[str, Any]],
+    config: Dict[str, Any],
+) -> Optional[Tuple[List[Dict[str, Any]], List[Dict[str, Any]]]]:
+    """(older, recent) turns if the history should be summarized, else None.
+
+    System messages are in neither list; at least two older turns are
+    needed for a summary to save anything.
+    """
+    if CostEstimator.estimate_prompt_tokens(messages) <= config["threshold_tokens"]:
+        return None
+    turns = [m for m in messages if m.get("role") != "system"]
+    keep_recent = config.get("keep_recent", 4)
+    older, recent = turns[:-keep_recent], turns[-keep_recent:]
+    if len(older) < 2:
+        return None
+    return older, recent
+
+
+def summarizer_messages(older: List[Dict[str, Any]], config: Dict[str, Any]) -> List[Dict[str, str]]:
+    """Messages for the summarization call."""
+    transcript = "\n\n".join(f"{m.get('role', 'user')}: {m.get('content') or ''}" for m in older)
+    return [
+        {"role": "system", "content": config.get("prompt") or DEFAULT_PROMPT},
+        {"role": "user", "content": transcript},
+    ]
+
+
+def replace_history(
+    messages: List[Dict[str, Any]],
+    recent: List[Dict[str, Any]],
+    summary: str,
+) -> List[Dict[str, Any]]:
+    """System messages, then the summary, then the recent turns."""
+    system = [m for m in messages if m.get("role") == "system"]
+    return [*system, {"role": "system", "content": SUMMARY_PREFIX + summary}, *recent]
+
+
+def tokens_saved(original: List[Dict[str, Any]], summarized: List[Dict[str, Any]]) -> int:
+    """Estimated prompt tokens saved by the summary (never negative)."""
+    return max(
+        CostEstimator.estimate_prompt_tokens(original) - CostEstimator.estimate_prompt_tokens(summarized), 0
+    )
@@ -0,0 +1,101 @@
+"""Per-tenant request rate and spend budget (`rate_limit_rpm`, `budget`).
+
+Each tenant (one API key) may cap its requests per minute and its spend
+per day or month. The current state is returned on every proxy response
+as X-RateLimit-Limit / -Remaining / -Reset and X-Budget-Remaining-Usd /
+X-Budget-Reset, so clients can slow down before they are rejected.
+
+Both are read from process memory: the request counter lives here, and
+spend comes from UsageStore.spend_since, which keeps a running total and
+re-reads Redis only every few seconds. Like the counter it is per
+process, so with several workers each one enforces rate_limit_rpm on its
+own and spend converges on the shared total at the next refresh.
+"""
+import time
+from datetime import datetime, timedelta, timezone
+from typing import Any, Dict, Optional, Tuple
+
+from reliapi.core.usage import UsageStore
+
+WINDOW_S = 60
+
+
+def budget_period(period: str, now: Optional[float] = None) -> Tuple[float, int]:
+    """(start, end) of the current UTC day or month, as epoch seconds."""
+    current = datetime.fromtimestamp(now if now is not None else time.time(), timezone.utc)
+    start = current.replace(hour=0, minute=0, second=0, microsecond=0)
+    if period == "day":
+        end = start + timedelta(days=1)
+    else:
+        start = start.replace(day=1)
+        end = (start + timedelta(days=32)).replace(day=1)
+    return start.timestamp(), int(end.timestamp())
+
+
+class TenantLimits:
+    """Request counters and budget lookups for tenants."""
+
+    def __init__(self, usage_store: Optional[UsageStore] = None):
+        """
+        Args:
+            usage_store: Source of recorded spend; budgets are not enforced without one
+        """
+        self.usage_store = usage_store
+        self._windows: Dict[str, Tuple[int, int]] = {}  # tenant -> (minute, requests)
+
+    def _requests(self, tenant: str, window: int) -> int:
+        current, count = self._windows.get(tenant, (window, 0))
+        return count if current == window else 0
+
+    def acquire(self, tenant: str, config: Dict[str, Any]) -> Optional[Tuple[str, float]]:
+        """Count a request against the tenant's limits.
+
+        Returns:
+            None if allowed, else ("budget" or "rate_limit", seconds until it resets).
+            A rejected request is not counted.
+        """
+        rejected = self.check(tenant, config)
+        rpm = config.get("rate_limit_rpm")
+        if rejected is None and rpm:
+            window = int(time.time() // WINDOW_S)
+            self._windows[tenant] = (window, self._requests(tenant, window) + 1)
+        return rejected
+
+    def check(self, tenant: str, config: Dict[str, Any]) -> Optional[Tuple[str, float]]:
+        """Whether a request would be admitted, like acquire() but without counting it."""
+        now = time.time()
+        budget = config.get("budget")
+        if budget:
+            remaining_usd, reset_at = self.budget_remaining(tenant, budget)
+            if remaining_usd <= 0:
+                return "budget", reset_at - now
+        rpm = config.get("rate_limit_rpm")
+        if rpm:
+            window = int(now // WINDOW_S)
+            if self._requests(tenant, window) >= rpm:
+                return "rate_limit", (window + 1) * WINDOW_S - now
+        return None
+
+    def budget_remaining(self, tenant: str, budget: Dict[str, Any]) -> Tuple[float, int]:
+        """(USD left in the current period, epoch seconds when the period ends)."""
+        since, reset_at = budget_period(budget.get("period", "month"))
+        spent = self.usage_store.spend_since(tenant, since) if self.usage_store else 0.0
+        return max(budget["limit_usd"] - spent, 0.0), reset_at
+
+    def headers(self, tenant: Optional[str], config: Optional[Dict[str, Any]]) -> Dict[str, str]:
+        """Rate-limit and budget headers for the tenant's state; empty if it has no limits."""
+        if not tenant or not config:
+            return {}
+        headers: Dict[str, str] = {}
+        rpm = config.get("rate_limit_rpm")
+        if rpm:
+            window = int(time.time() // WINDOW_S)
+            headers["X-RateLimit-Limit"] = str(rpm)
+            headers["X-RateLimit-Remaining"] = str(max(rpm - self._requests(tenant, window), 0))
+            headers["X-RateLimit-Reset"] = str((window + 1) * WINDOW_S)
+        budget = config.get("budget")
+        if budget:
+            remaining_usd, reset_at = self.budget_remaining(tenant, budget)
+            headers["X-Budget-Remaining-Usd"] = f"{remaining_usd:.6f}"
+            headers["X-Budget-Reset"] = str(reset_at)
+        return headers
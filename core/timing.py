@@ -0,0 +1,44 @@
+"""Split request duration into upstream, queue and proxy overhead time."""
+import time
+from contextlib import contextmanager
+from typing import Dict, Iterator, Optional
+
+
+class RequestTimer:
+    """Accumulates time spent in provider calls and waiting on limits.
+
+    `upstream_ms` covers provider calls (including retries), `queue_ms`
+    waits on rate/concurrency limits, and `overhead_ms` is the remainder of
+    the total duration (cache, tokenization, serialization).
+    """
+
+    def __init__(self, start_time: Optional[float] = None):
+        self.start_time = start_time if start_time is not None else time.time()
+        self.upstream_s = 0.0
+        self.queue_s = 0.0
+
+    @contextmanager
+    def upstream(self) -> Iterator[None]:
+        started = time.time()
+        try:
+            yield
+        finally:
+            self.upstream_s += time.time() - started
+
+    @contextmanager
+    def queue(self) -> Iterator[None]:
+        started = time.time()
+        try:
+            yield
+        finally:
+            self.queue_s += time.time() - started
+
+    def breakdown(self, duration_ms: int) -> Dict[str, int]:
+        """Meta fields for a request that took `duration_ms` in total."""
+        upstream_ms = min(int(self.upstream_s * 1000), duration_ms)
+        queue_ms = min(int(self.queue_s * 1000), duration_ms - upstream_ms)
+        return {
+            "upstream_ms": upstream_ms,
+            "queue_ms": queue_ms,
+            "overhead_ms": duration_ms - upstream_ms - queue_ms,
+        }
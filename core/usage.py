@@ -0,0 +1,236 @@
+"""Per-request usage records for cost attribution."""
+import json
+import logging
+import time
+from typing import Any, Dict, List, Optional, Tuple
+
+import redis
+
+logger = logging.getLogger(__name__)
+
+# Retention defaults for usage records
+DEFAULT_RETENTION_S = 86400 * 30  # 30 days
+DEFAULT_MAX_RECORDS = 100000
+
+UNTAGGED_GROUP = "(untagged)"
+
+# How long a cached spend total is served before it is re-read from Redis
+SPEND_REFRESH_S = 10.0
+
+
+class UsageStore:
+    """Stores per-request usage records (cost, latency, tags) in Redis.
+
+    Records are kept in a capped list per tenant so that cost can be
+    broken down by target, model, or arbitrary request tags.
+    """
+
+    def __init__(
+        self,
+        redis_url: str,
+        key_prefix: str = "reliapi",
+        retention_s: int = DEFAULT_RETENTION_S,
+        max_records: int = DEFAULT_MAX_RECORDS,
+    ):
+        """
+        Args:
+            redis_url: Redis connection URL
+            key_prefix: Prefix for usage keys
+            retention_s: How long usage records are kept
+            max_records: Maximum records kept per tenant (oldest dropped first)
+        """
+        self.key_prefix = key_prefix
+        self.retention_s = retention_s
+        self.max_records = max_records
+        # UsageExporter for usage_export, attached at startup
+        self.exporter = None
+        # (tenant, since) -> (total cost_usd, when it was read); see spend_since
+        self._spend: Dict[Tuple[Optional[str], float], Tuple[float, float]] = {}
+        try:
+            self.client = redis.from_url(redis_url, decode_responses=True)
+            self.client.ping()
+            self.enabled = True
+            logger.info(f"Usage store connected to Redis: {redis_url}")
+        except Exception as e:
+            self.client = None
+            self.enabled = False
+            logger.warning(f"Usage store connection failed (graceful degradation): {e}", exc_info=True)
+
+    def _make_key(self, tenant: Optional[str] = None, name: str = "usage_records") -> str:
+        """Build usage list key (tenant-isolated in multi-tenant mode)."""
+        if tenant:
+            return f"{self.key_prefix}:tenant:{tenant}:{name}"
+        return f"{self.key_prefix}:{name}"
+
+    def record(
+        self,
+        request_id: str,
+        kind: str,
+        target: Optional[str],
+        status: str,
+        latency_ms: int,
+        model: Optional[str] = None,
+        cost_usd: Optional[float] = None,
+        tags: Optional[Dict[str, str]] = None,
+        tenant: Optional[str] = None,
+        raw_cost_usd: Optional[float] = None,
+        prompt_tokens: Optional[int] = None,
+        completion_tokens: Optional[int] = None,
+    ) -> None:
+        """Append a usage record.
+
+        Args:
+            request_id: Request ID
+            kind: "http" or "llm"
+            target: Target name
+            status: "success" or "error"
+            latency_ms: Request latency in milliseconds
+            model: LLM model name (for LLM requests)
+            cost_usd: Cost in USD as billed, after the tenant's cost_multiplier (for LLM requests)
+            tags: Request tags for cost attribution
+            tenant: Tenant name for multi-tenant isolation
+            raw_cost_usd: Actual provider cost (defaults to cost_usd)
+            prompt_tokens: Prompt tokens used (for LLM requests)
+            completion_tokens: Completion tokens generated (for LLM requests)
+        """
+        if cost_usd:
+            for (spend_tenant, since), (total, read_at) in list(self._spend.items()):
+                if spend_tenant == tenant:
+                    self._spend[(spend_tenant, since)] = (total + cost_usd, read_at)
+        record = {
+            "ts": time.time(),
+            "request_id": request_id,
+            "kind": kind,
+            "target": target,
+            "model": model,
+            "status": status,
+            "latency_ms": latency_ms,
+            "cost_usd": cost_usd or 0.0,
+            "raw_cost_usd": (cost_usd if raw_cost_usd is None else raw_cost_usd) or 0.0,
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "tags": tags or {},
+        }
+        if self.exporter:
+            self.exporter.submit({**record, "tenant": tenant})
+        if not self.enabled or not self.client:
+            return
+
+        key = self._make_key(tenant)
+        try:
+            pipe = self.client.pipeline()
+            pipe.lpush(key, json.dumps(record))
+            pipe.ltrim(key, 0, self.max_records - 1)
+            pipe.expire(key, self.retention_s)
+            pipe.execute()
+        except Exception as e:
+            logger.warning(f"Usage record error (graceful degradation): {e}", exc_info=True)
+
+    def record_stream(self, record: Dict[str, Any]) -> None:
+        """Append a stream_tee record, kept like the usage records under stream_records.
+
+        Raises:
+            RuntimeError: Without Redis; errors are raised so the tee counts them
+        """
+        if not self.enabled or not self.client:
+            raise RuntimeError("usage store is not connected")
+        key = self._make_key(record.get("tenant"), "stream_records")
+        pipe = self.client.pipeline()
+        pipe.lpush(key, json.dumps(record))
+        pipe.ltrim(key, 0, self.max_records - 1)
+        pipe.expire(key, self.retention_s)
+        pipe.execute()
+
+    def spend_since(self, tenant: Optional[str], since: float) -> float:
+        """Total cost_usd recorded for a tenant since a timestamp.
+
+        Served from memory: records written by this process are added as
+        they happen, and the total is re-read from Redis (picking up other
+        workers' spend) at most every SPEND_REFRESH_S seconds.
+        """
+        cached = self._spend.get((tenant, since))
+        now = time.time()
+        if cached and (now - cached[1] < SPEND_REFRESH_S or not self.enabled):
+            return cached[0]
+        total = cached[0] if cached else 0.0
+        if self.enabled:
+            total = sum(record.get("cost_usd") or 0.0 for record in self.query(tenant, since=since))
+        # A new period replaces the tenant's previous one
+        self._spend = {key: value for key, value in self._spend.items() if key[0] != tenant}
+        self._spend[(tenant, since)] = (total, now)
+        return total
+
+    def query(
+        self,
+        tenant: Optional[str] = None,
+        since: Optional[float] = None,
+        until: Optional[float] = None,
+    ) -> List[Dict[str, Any]]:
+        """Get usage records in the [since, until] time range (newest first)."""
+        if not self.enabled or not self.client:
+            return []
+
+        try:
+            raw_records = self.client.lrange(self._make_key(tenant), 0, -1)
+        except Exception as e:
+            logger.warning(f"Usage query error (graceful degradation): {e}", exc_info=True)
+            return []
+
+        records = []
+        for raw in raw_records:
+            try:
+                record = json.loads(raw)
+            except json.JSONDecodeError:
+                continue
+            ts = record.get("ts", 0)
+            if since is not None and ts < since:
+                continue
+            if until is not None and ts > until:
+                continue
+            records.append(record)
+        return records
+
+    @staticmethod
+    def aggregate(records: List[Dict[str, Any]], group_by: Optional[str] = None) -> List[Dict[str, Any]]:
+        """Aggregate usage records by a dimension.
+
+        Args:
+            records: Usage records (from query())
+            group_by: "target", "model", "kind", "status", or "tag:<name>".
+                None aggregates everything into a single "total" group.
+
+        Returns:
+            List of groups sorted by cost (descending):
+            [{"group": str, "requests": int, "errors": int, "cost_usd": float, "raw_cost_usd": float}]
+
+        Raises:
+            ValueError: If group_by is not a supported dimension
+        """
+        tag_name = None
+        if group_by and group_by.startswith("tag:"):
+            tag_name = group_by[4:]
+            if not tag_name:
+                raise ValueError("group_by 'tag:' requires a tag name")
+        elif group_by not in (None, "target", "model", "kind", "status"):
+            raise ValueError(f"Unsupported group_by: {group_by}")
+
+        groups: Dict[str, Dict[str, Any]] = {}
+        for record in records:
+            if tag_name:
+                group = (record.get("tags") or {}).get(tag_name, UNTAGGED_GROUP)
+            elif group_by:
+                group = record.get(group_by) or "unknown"
+            else:
+                group = "total"
+
+            entry = groups.setdefault(
+                group, {"group": group, "requests": 0, "errors": 0, "cost_usd": 0.0, "raw_cost_usd": 0.0}
+            )
+            entry["requests"] += 1
+            if record.get("status") == "error":
+                entry["errors"] += 1
+            entry["cost_usd"] += record.get("cost_usd") or 0.0
+            # Records written before cost_multiplier existed were billed at cost
+            entry["raw_cost_usd"] += record.get("raw_cost_usd", record.get("cost_usd")) or 0.0
+
+        return sorted(groups.values(), key=lambda g: g["cost_usd"], reverse=True)
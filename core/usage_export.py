@@ -0,0 +1,208 @@
+"""Per-request usage records exported to an analytics webhook (`usage_export`).
+
+Every record written to the usage store is also queued here; a background
+worker collects up to batch_size records (or whatever arrived within
+flush_interval_s) and POSTs them as {"records": [...]}. Queueing never
+blocks the request: when the queue is full the record is dropped and
+counted. Timeouts, connection errors, 429 and 5xx responses are retried
+with exponential backoff; a batch that still fails is dropped and counted.
+
+Records carry no prompts or outputs. Tag values are scrubbed of emails and
+long digit runs, and the fields listed in `redact` are replaced.
+
+With secret_env set, each batch is signed: X-ReliAPI-Signature is
+"sha256=" + hex HMAC-SHA256 over "{timestamp}.{body}", with the timestamp
+sent in X-ReliAPI-Timestamp, so receivers can reject forged or replayed
+batches.
+"""
+import asyncio
+import hashlib
+import hmac
+import json
+import logging
+import os
+import re
+import time
+from typing import Any, Dict, List, Optional
+
+import httpx
+
+from reliapi.core.stream_tee import DEFAULT_REDACT_PATTERNS, REDACTED
+from reliapi.metrics.prometheus import usage_export_records_total, usage_export_retries_total
+
+logger = logging.getLogger(__name__)
+
+SIGNATURE_HEADER = "X-ReliAPI-Signature"
+TIMESTAMP_HEADER = "X-ReliAPI-Timestamp"
+
+_TAG_PATTERNS = [re.compile(p) for p in DEFAULT_REDACT_PATTERNS]
+
+
+def sign_batch(secret: bytes, body: bytes, timestamp: int) -> str:
+    """Signature header value for a batch body."""
+    message = str(timestamp).encode() + b"." + body
+    return "sha256=" + hmac.new(secret, message, hashlib.sha256).hexdigest()
+
+
+class UsageExporter:
+    """Batches usage records and POSTs them to a webhook in the background."""
+
+    def __init__(
+        self,
+        url: str,
+        secret: Optional[str] = None,
+        headers: Optional[Dict[str, str]] = None,
+        batch_size: int = 100,
+        flush_interval_s: float = 5.0,
+        attempts: int = 3,
+        backoff_ms: int = 500,
+        max_queue: int = 10000,
+        timeout_s: float = 5.0,
+        redact: Optional[List[str]] = None,
+        close_timeout_s: float = 10.0,
+    ):
+        """
+        Args:
+            url: Webhook endpoint
+            secret: HMAC secret signing each batch (unsigned if None)
+            headers: Extra webhook request headers (e.g., Authorization)
+            batch_size: Records per batch at most
+            flush_interval_s: Longest a record waits for its batch to fill
+            attempts: Delivery attempts per batch
+            backoff_ms: Delay before retry N is backoff_ms * 2^(N-1)
+            max_queue: Records waiting for delivery before new ones are dropped
+            timeout_s: Webhook request timeout
+            redact: Record fields replaced with REDACTED (tenant, request_id, tags)
+            close_timeout_s: Longest shutdown waits for queued records to be delivered
+        """
+        self.url = url
+        self.secret = secret.encode("utf-8") if secret else None
+        self.headers = headers or {}
+        self.batch_size = batch_size
+        self.flush_interval_s = flush_interval_s
+        self.attempts = attempts
+        self.backoff_ms = backoff_ms
+        self.timeout_s = timeout_s
+        self.redact = set(redact or [])
+        self.close_timeout_s = close_timeout_s
+        self._queue: asyncio.Queue = asyncio.Queue(maxsize=max_queue)
+        self._worker: Optional[asyncio.Task] = None
+        # Records queued, or taken from the queue and not yet delivered or failed
+        self._pending = 0
+
+    def _redacted(self, record: Dict[str, Any]) -> Dict[str, Any]:
+        record = dict(record)
+        tags = {}
+        for name, value in (record.get("tags") or {}).items():
+            for pattern in _TAG_PATTERNS:
+                value = pattern.sub(REDACTED, str(value))
+            tags[name] = value
+        record["tags"] = tags
+        for field in self.redact:
+            if record.get(field):
+                record[field] = REDACTED
+        return record
+
+    def submit(self, record: Dict[str, Any]) -> bool:
+        """Queue a usage record without waiting; returns False if it was dropped."""
+        try:
+            self._queue.put_nowait(self._redacted(record))
+        except asyncio.QueueFull:
+            usage_export_records_total.labels(outcome="dropped").inc()
+            return False
+        self._pending += 1
+        if self._worker is None or self._worker.done():
+            try:
+                self._worker = asyncio.get_running_loop().create_task(self._run())
+            except RuntimeError:
+                # No event loop (e.g., a sync caller): the next submit in a loop starts the worker
+                pass
+        return True
+
+    async def _next_batch(self) -> List[Dict[str, Any]]:
+        """Wait for a record, then collect more until the batch is full or flush_interval_s passes."""
+        batch = [await self._queue.get()]
+        deadline = time.monotonic() + self.flush_interval_s
+        while len(batch) < self.batch_size:
+            remaining = deadline - time.monotonic()
+            if remaining <= 0:
+                break
+            try:
+                batch.append(await asyncio.wait_for(self._queue.get(), remaining))
+            except asyncio.TimeoutError:
+                break
+        return batch
+
+    async def _run(self) -> None:
+        async with httpx.AsyncClient(timeout=self.timeout_s) as client:
+            while True:
+                batch = await self._next_batch()
+                try:
+                    delivered = await self._deliver(client, batch)
+                    usage_export_records_total.labels(outcome="delivered" if delivered else "failed").inc(len(batch))
+                finally:
+                    self._pending -= len(batch)
+                    for _ in batch:
+                        self._queue.task_done()
+
+    async def _deliver(self, client: httpx.AsyncClient, batch: List[Dict[str, Any]]) -> bool:
+        """POST one batch, retrying transient failures; False if it could not be delivered."""
+        body = json.dumps({"records": batch}).encode("utf-8")
+        for attempt in range(1, self.attempts + 1):
+            headers = {**self.headers, "Content-Type": "application/json"}
+            if self.secret:
+                timestamp = int(time.time())
+                headers[TIMESTAMP_HEADER] = str(timestamp)
+                headers[SIGNATURE_HEADER] = sign_batch(self.secret, body, timestamp)
+            try:
+                response = await client.post(self.url, content=body, headers=headers)
+                if response.is_success:
+                    return True
+                error = f"HTTP {response.status_code}"
+                retryable = response.status_code == 429 or response.status_code >= 500
+            except httpx.HTTPError as e:
+                error = str(e) or type(e).__name__
+                retryable = True
+            if not retryable or attempt == self.attempts:
+                logger.warning(f"Usage export of {len(batch)} records failed after {attempt} attempts: {error}")
+                return False
+            usage_export_retries_total.inc()
+            await asyncio.sleep(self.backoff_ms * 2 ** (attempt - 1) / 1000)
+        return False
+
+    async def close(self) -> None:
+        """Deliver the queued records (for up to close_timeout_s), drop the rest and stop the worker."""
+        if self._worker is None:
+            return
+        if not self._worker.done():
+            try:
+                await asyncio.wait_for(self._queue.join(), self.close_timeout_s)
+            except asyncio.TimeoutError:
+                pass
+        if self._pending:
+            logger.warning(f"Usage export stopped with {self._pending} records undelivered")
+            usage_export_records_total.labels(outcome="dropped").inc(self._pending)
+            self._pending = 0
+        self._worker.cancel()
+
+
+def usage_exporter(config: Dict[str, Any]) -> UsageExporter:
+    """Exporter for the `usage_export` config, with the signing secret from the environment."""
+    secret = None
+    if config.get("secret_env"):
+        secret = os.getenv(config["secret_env"])
+        if not secret:
+            logger.error(f"Usage export secret {config['secret_env']} is not set; batches are sent unsigned")
+    return UsageExporter(
+        url=config["url"],
+        secret=secret,
+        headers=config.get("headers"),
+        batch_size=config.get("batch_size", 100),
+        flush_interval_s=config.get("flush_interval_s", 5.0),
+        attempts=config.get("attempts", 3),
+        backoff_ms=config.get("backoff_ms", 500),
+        max_queue=config.get("max_queue", 10000),
+        timeout_s=config.get("timeout_s", 5.0),
+        redact=config.get("redact"),
+        close_timeout_s=config.get("close_timeout_s", 10.0),
+    )
@@ -1,289 +1,142 @@
-/**
+/*!
  * Rust example for ReliAPI
- * 
+ *
  * This example demonstrates:
  * - Basic HTTP proxy usage
  * - Basic LLM proxy usage
+ * - Streaming
  * - Error handling
- * - JSON serialization/deserialization
- * 
- * Requirements:
- *   Add to Cargo.toml:
+ *
+ * Built on the `reliapi-client` crate (clients/rust). To use it in your
+ * project, add to Cargo.toml:
  *   [dependencies]
- *   reqwest = { version = "0.11", features = ["json"] }
- *   serde = { version = "1.0", features = ["derive"] }
- *   serde_json = "1.0"
+ *   reliapi-client = { git = "https://github.com/KikuAI-Lab/reliapi" }
+ *   futures-util = "0.3"
  *   tokio = { version = "1", features = ["full"] }
- * 
- * Usage:
+ *
+ * Usage (from clients/rust):
+ *   export RELIAPI_API_KEY=your-key   # or RAPIDAPI_KEY
  *   cargo run --example rust_example
  */
 
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::env;
-
-// Configuration
-fn get_reliapi_url() -> String {
-    env::var("RELIAPI_URL").unwrap_or_else(|_| "https://reliapi.kikuai.dev".to_string())
-}
-
-fn get_api_key() -> String {
-    env::var("RAPIDAPI_KEY")
-        .or_else(|_| env::var("RELIAPI_API_KEY"))
-        .unwrap_or_else(|_| "your-api-key".to_string())
-}
-
-// Types
-#[derive(Serialize)]
-struct LLMRequest {
-    target: String,
-    messages: Vec<HashMap<String, String>>,
-    model: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    max_tokens: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    temperature: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    stream: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    idempotency_key: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    cache: Option<u32>,
-}
-
-#[derive(Serialize)]
-struct HTTPRequest {
-    target: String,
-    method: String,
-    path: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    headers: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    query: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    body: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    idempotency_key: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    cache: Option<u32>,
-}
-
-#[derive(Deserialize)]
-struct ReliAPIResponse {
-    data: serde_json::Value,
-    meta: Meta,
-}
-
-#[derive(Deserialize)]
-struct Meta {
-    request_id: String,
-    cache_hit: bool,
-    idempotent_hit: bool,
-    #[serde(default)]
-    cost_usd: Option<f64>,
-    duration_ms: u64,
-}
+use futures_util::StreamExt;
+use reliapi_client::{Client, ErrorCode, StreamEvent};
 
 // HTTP proxy example
-async fn http_proxy_example(client: &Client, url: &str, api_key: &str) {
+async fn http_proxy_example(client: &Client) {
     println!("=== HTTP Proxy Example ===");
 
-    let request = HTTPRequest {
-        target: "jsonplaceholder".to_string(),
-        method: "GET".to_string(),
-        path: "/posts/1".to_string(),
-        headers: None,
-        query: None,
-        body: None,
-        idempotency_key: Some(format!("http-{}", chrono::Utc::now().timestamp())),
-        cache: Some(300),
-    };
-
     let response = client
-        .post(&format!("{}/proxy/http", url))
-        .header("X-RapidAPI-Key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
+        .http("jsonplaceholder", "GET", "/posts/1")
+        .idempotency_key(format!("http-{}", chrono::Utc::now().timestamp()))
+        .cache(300)
         .send()
         .await;
 
     match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                let data: ReliAPIResponse = resp.json().await.unwrap();
-                println!("Success: Cache hit: {}, Request ID: {}", data.meta.cache_hit, data.meta.request_id);
-            } else {
-                let text = resp.text().await.unwrap();
-                println!("Error: {} - {}", resp.status(), text);
-            }
-        }
-        Err(e) => println!("Request error: {}", e),
+        Ok(resp) => println!("Success: Cache hit: {}, Request ID: {}", resp.meta.cache_hit, resp.meta.request_id),
+        Err(e) => println!("Error: {}", e),
     }
 }
 
 // LLM proxy example
-async fn llm_proxy_example(client: &Client, url: &str, api_key: &str) {
+async fn llm_proxy_example(client: &Client) {
     println!("\n=== LLM Proxy Example ===");
 
-    let mut messages = Vec::new();
-    let mut msg = HashMap::new();
-    msg.insert("role".to_string(), "user".to_string());
-    msg.insert("content".to_string(), "What is idempotency in API design? Explain in one sentence.".to_string());
-    messages.push(msg);
-
-    let request = LLMRequest {
-        target: "openai".to_string(),
-        messages,
-        model: "gpt-4o-mini".to_string(),
-        max_tokens: Some(100),
-        temperature: None,
-        stream: None,
-        idempotency_key: Some(format!("rust-example-{}", chrono::Utc::now().timestamp())),
-        cache: Some(3600),
-    };
-
     let response = client
-        .post(&format!("{}/proxy/llm", url))
-        .header("X-RapidAPI-Key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
+        .llm("openai")
+        .model("gpt-4o-mini")
+        .user("What is idempotency in API design? Explain in one sentence.")
+        .max_tokens(100)
+        .idempotency_key(format!("rust-example-{}", chrono::Utc::now().timestamp()))
+        .cache(3600)
         .send()
         .await;
 
     match response {
         Ok(resp) => {
-            if resp.status().is_success() {
-                let data: ReliAPIResponse = resp.json().await.unwrap();
-                
-                // Extract content from nested JSON structure
-                if let Some(choices) = data.data.get("choices").and_then(|c| c.as_array()) {
-                    if let Some(choice) = choices.get(0) {
-                        if let Some(message) = choice.get("message") {
-                            if let Some(content) = message.get("content").and_then(|c| c.as_str()) {
-                                println!("Response: {}", content);
-                            }
-                        }
-                    }
-                }
-                
-                if let Some(cost) = data.meta.cost_usd {
-                    println!("Cost: ${:.6}", cost);
-                }
-                println!("Cache hit: {}", data.meta.cache_hit);
-                println!("Request ID: {}", data.meta.request_id);
-            } else {
-                let text = resp.text().await.unwrap();
-                println!("Error: {} - {}", resp.status(), text);
+            println!("Response: {}", resp.data.content);
+            if let Some(cost) = resp.meta.cost_usd {
+                println!("Cost: ${:.6}", cost);
             }
+            println!("Cache hit: {}", resp.meta.cache_hit);
+            println!("Request ID: {}", resp.meta.request_id);
         }
-        Err(e) => println!("Request error: {}", e),
+        Err(e) => println!("Error: {}", e),
     }
 }
 
 // Caching example
-async fn caching_example(client: &Client, url: &str, api_key: &str) {
+async fn caching_example(client: &Client) {
     println!("\n=== Caching Example ===");
 
-    let mut messages = Vec::new();
-    let mut msg = HashMap::new();
-    msg.insert("role".to_string(), "user".to_string());
-    msg.insert("content".to_string(), "What is circuit breaker pattern?".to_string());
-    messages.push(msg);
+    for attempt in ["First request (will call OpenAI API)", "Second request (same question - should be cached, FREE!)"] {
+        println!("\n{}:", attempt);
+        let response = client
+            .llm("openai")
+            .model("gpt-4o-mini")
+            .user("What is circuit breaker pattern?")
+            .max_tokens(100)
+            .cache(3600)
+            .send()
+            .await;
+
+        if let Ok(resp) = response {
+            println!("Cache hit: {}, Cost: ${:.6}", resp.meta.cache_hit, resp.meta.cost_usd.unwrap_or(0.0));
+            if resp.meta.cache_hit {
+                println!("✅ Request was FREE (served from cache)!");
+            }
+        }
+    }
+}
 
-    let request = LLMRequest {
-        target: "openai".to_string(),
-        messages: messages.clone(),
-        model: "gpt-4o-mini".to_string(),
-        max_tokens: Some(100),
-        temperature: None,
-        stream: None,
-        idempotency_key: None,
-        cache: Some(3600),
-    };
+// Streaming example
+async fn streaming_example(client: &Client) {
+    println!("\n=== Streaming Example ===");
 
-    // First request
-    println!("First request (will call OpenAI API):");
-    let resp1 = client
-        .post(&format!("{}/proxy/llm", url))
-        .header("X-RapidAPI-Key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
+    let stream = client
+        .llm("openai")
+        .model("gpt-4o-mini")
+        .user("Count from 1 to 5.")
+        .max_tokens(50)
+        .stream()
         .await;
 
-    if let Ok(resp) = resp1 {
-        if resp.status().is_success() {
-            let data1: ReliAPIResponse = resp.json().await.unwrap();
-            println!("Cache hit: {}, Cost: ${:.6}", 
-                data1.meta.cache_hit, 
-                data1.meta.cost_usd.unwrap_or(0.0));
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
         }
-    }
-
-    // Second request - should be cached
-    println!("\nSecond request (same question - should be cached, FREE!):");
-    let resp2 = client
-        .post(&format!("{}/proxy/llm", url))
-        .header("X-RapidAPI-Key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
-        .send()
-        .await;
+    };
 
-    if let Ok(resp) = resp2 {
-        if resp.status().is_success() {
-            let data2: ReliAPIResponse = resp.json().await.unwrap();
-            println!("Cache hit: {}, Cost: ${:.6}", 
-                data2.meta.cache_hit, 
-                data2.meta.cost_usd.unwrap_or(0.0));
-            if data2.meta.cache_hit {
-                println!("✅ Second request was FREE (served from cache)!");
-            }
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(StreamEvent::Delta { content, .. }) => print!("{}", content),
+            Ok(StreamEvent::Done(done)) => println!("\nCost: ${:.6}", done.cost_usd.unwrap_or(0.0)),
+            Ok(StreamEvent::Meta(_)) => {}
+            Err(e) => println!("\nStream error: {}", e),
         }
     }
 }
 
 // Error handling example
-async fn error_handling_example(client: &Client, url: &str, api_key: &str) {
+async fn error_handling_example(client: &Client) {
     println!("\n=== Error Handling Example ===");
 
-    let mut messages = Vec::new();
-    let mut msg = HashMap::new();
-    msg.insert("role".to_string(), "user".to_string());
-    msg.insert("content".to_string(), "Test".to_string());
-    messages.push(msg);
-
-    let request = LLMRequest {
-        target: "openai".to_string(),
-        messages,
-        model: "gpt-4o-mini".to_string(),
-        max_tokens: Some(100000), // May exceed budget cap
-        temperature: None,
-        stream: None,
-        idempotency_key: None,
-        cache: None,
-    };
-
     let response = client
-        .post(&format!("{}/proxy/llm", url))
-        .header("X-RapidAPI-Key", api_key)
-        .header("Content-Type", "application/json")
-        .json(&request)
+        .llm("openai")
+        .model("gpt-4o-mini")
+        .user("Test")
+        .max_tokens(100000) // May exceed budget cap
         .send()
         .await;
 
     match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                println!("Success!");
-            } else {
-                let text = resp.text().await.unwrap();
-                println!("Error: {} - {}", resp.status(), text);
-            }
-        }
-        Err(e) => println!("Request error: {}", e),
+        Ok(_) => println!("Success!"),
+        Err(e) if e.code() == Some(&ErrorCode::BudgetExceeded) => println!("Budget cap hit: {}", e),
+        Err(e) if e.is_retryable() => println!("Retryable error (retry after {:?}): {}", e.retry_after(), e),
+        Err(e) => println!("Error: {}", e),
     }
 }
 
@@ -291,14 +144,19 @@ async fn error_handling_example(client: &Client, url: &str, api_key: &str) {
 async fn main() {
     println!("ReliAPI Rust Example\n");
 
-    let url = get_reliapi_url();
-    let api_key = get_api_key();
-    let client = Client::new();
+    let client = match Client::from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
 
-    http_proxy_example(&client, &url, &api_key).await;
-    llm_proxy_example(&client, &url, &api_key).await;
-    caching_example(&client, &url, &api_key).await;
-    error_handling_example(&client, &url, &api_key).await;
+    http_proxy_example(&client).await;
+    llm_proxy_example(&client).await;
+    caching_example(&client).await;
+    streaming_example(&client).await;
+    error_handling_example(&client).await;
 
     println!("\n=== Examples Completed ===");
     println!("\nBenefits of ReliAPI:");
@@ -308,17 +166,3 @@ async fn main() {
     println!("  ✓ Budget caps prevent surprise bills");
     println!("  ✓ Circuit breaker prevents cascading failures");
 }
-
-
-
-
-
-
-
-
-
-
-
-
-
-
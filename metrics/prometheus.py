@@ -36,6 +36,29 @@ cache_misses_total = Counter(
     ["target", "kind", "tenant"],
 )
 
+cache_ttl_extended_hits_total = Counter(
+    "reliapi_cache_ttl_extended_hits_total",
+    "Cache hits served past the entry's TTL while the target is unhealthy (cache.health_ttl)",
+    ["target", "kind", "reason"],
+)
+
+memory_cache_evictions_total = Counter(
+    "reliapi_memory_cache_evictions_total",
+    "Entries evicted from the in-memory cache tier",
+    ["policy", "reason"],
+)
+
+memory_cache_bytes = Gauge(
+    "reliapi_memory_cache_bytes",
+    "Bytes stored in the in-memory cache tier",
+)
+
+cache_write_retries_total = Counter(
+    "reliapi_cache_write_retries_total",
+    "Cache writes retried after a transient Redis error, by final outcome",
+    ["outcome"],  # recovered, failed, dropped
+)
+
 # Idempotency metrics
 idempotent_hits_total = Counter(
     "reliapi_idempotent_hits_total",
@@ -43,6 +66,13 @@ idempotent_hits_total = Counter(
     ["target", "kind", "tenant"],
 )
 
+# Content-hash deduplication metrics
+dedup_requests_total = Counter(
+    "reliapi_dedup_requests_total",
+    "Total duplicate requests detected by content hash",
+    ["target", "kind", "action"],  # action: "coalesced", "rejected", "awaited" (coalesce_keyed)
+)
+
 # Budget events
 budget_events_total = Counter(
     "reliapi_budget_events_total",
@@ -105,6 +135,165 @@ key_pool_status = Histogram(
     buckets=[0, 1, 2, 3],
 )
 
+# Shadow traffic metrics
+shadow_requests_total = Counter(
+    "reliapi_shadow_requests_total",
+    "Total mirrored requests sent to shadow targets",
+    ["target", "shadow_target", "outcome", "match"],  # match: "true", "false", "n/a"
+)
+
+shadow_cost_delta_usd = Histogram(
+    "reliapi_shadow_cost_delta_usd",
+    "Shadow cost minus primary cost in USD",
+    ["target", "shadow_target"],
+    buckets=[-0.1, -0.01, -0.001, 0, 0.001, 0.01, 0.1],
+)
+
+# Per-model RPM/TPM limiter metrics
+model_rate_limit_remaining = Gauge(
+    "reliapi_model_rate_limit_remaining",
+    "Remaining requests (rpm) or tokens (tpm) in the current minute per target+model",
+    ["target", "model", "limit"],  # limit: "rpm", "tpm"
+)
+
+model_rate_limit_events_total = Counter(
+    "reliapi_model_rate_limit_events_total",
+    "Total requests delayed or rejected by per-model RPM/TPM caps",
+    ["target", "model", "limit", "action"],  # action: "delayed", "rejected"
+)
+
+# Per-model usage distributions (capacity planning, output growth)
+llm_prompt_tokens = Histogram(
+    "reliapi_llm_prompt_tokens",
+    "Prompt tokens per LLM request",
+    ["target", "model"],
+    buckets=[10, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 25000, 50000, 100000],
+)
+
+llm_completion_tokens = Histogram(
+    "reliapi_llm_completion_tokens",
+    "Completion tokens per LLM request",
+    ["target", "model"],
+    buckets=[10, 50, 100, 250, 500, 1000, 2000, 4000, 8000, 16000],
+)
+
+llm_request_cost_usd = Histogram(
+    "reliapi_llm_request_cost_usd",
+    "Cost in USD per LLM request",
+    ["target", "model"],
+    buckets=[0.00001, 0.0001, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0],
+)
+
+request_size_bytes = Histogram(
+    "reliapi_request_size_bytes",
+    "Upstream request body size in bytes",
+    ["target", "kind", "model"],  # model: "n/a" for HTTP
+    buckets=[100, 1000, 10000, 100000, 1000000, 10000000],
+)
+
+response_size_bytes = Histogram(
+    "reliapi_response_size_bytes",
+    "Upstream response body size in bytes (streams: generated content)",
+    ["target", "kind", "model"],  # model: "n/a" for HTTP
+    buckets=[100, 1000, 10000, 100000, 1000000, 10000000],
+)
+
+stream_cost_ceiling_total = Counter(
+    "reliapi_stream_cost_ceiling_total",
+    "Total LLM streams aborted mid-generation at llm.stream_cost_ceiling_usd",
+    ["target", "model"],
+)
+
+stream_idle_timeouts_total = Counter(
+    "reliapi_stream_idle_timeouts_total",
+    "Total LLM streams aborted because the upstream stalled between chunks",
+    ["target", "model"],
+)
+
+json_repairs_total = Counter(
+    "reliapi_json_repairs_total",
+    "Total JSON-mode LLM responses repaired before returning (llm.json_repair)",
+    ["target", "model"],
+)
+
+language_routes_total = Counter(
+    "reliapi_language_routes_total",
+    "Total LLM requests by detected input language (llm.language_routing); routed=false keeps the default model",
+    ["target", "language", "routed"],
+)
+
+content_filter_retries_total = Counter(
+    "reliapi_content_filter_retries_total",
+    "Total LLM responses blocked by the content filter and retried on llm.on_content_filter_model",
+    ["target", "model", "fallback_model"],
+)
+
+llm_empty_retries_total = Counter(
+    "reliapi_llm_empty_retries_total",
+    "Total retries of LLM completions that came back empty (llm.empty_response)",
+    ["target", "model"],
+)
+
+llm_schema_retries_total = Counter(
+    "reliapi_llm_schema_retries_total",
+    "Total corrective retries for LLM outputs that failed the request's json_schema",
+    ["target", "model"],
+)
+
+llm_params_filtered_total = Counter(
+    "reliapi_llm_params_filtered_total",
+    "Total request parameters outside a target's llm.allowed_params",
+    ["target", "param", "action"],  # action: "drop", "reject", "forward"
+)
+
+stream_tee_records_total = Counter(
+    "reliapi_stream_tee_records_total",
+    "Completed LLM streams copied to the stream_tee sink",
+    ["sink", "outcome"],  # outcome: "written", "failed", "dropped"
+)
+
+usage_export_records_total = Counter(
+    "reliapi_usage_export_records_total",
+    "Usage records sent to the usage_export webhook",
+    ["outcome"],  # outcome: "delivered", "failed", "dropped"
+)
+
+usage_export_retries_total = Counter(
+    "reliapi_usage_export_retries_total",
+    "Usage export batch deliveries retried after a transient failure",
+)
+
+# Retry budget metrics (scope: "global" or target name)
+retry_budget_utilization = Gauge(
+    "reliapi_retry_budget_utilization",
+    "Fraction of the retry budget consumed in the current window",
+    ["scope"],
+)
+
+retry_budget_exhausted_total = Counter(
+    "reliapi_retry_budget_exhausted_total",
+    "Total retries skipped (request failed fast) because the retry budget was exhausted",
+    ["scope"],
+)
+
+queue_timeouts_total = Counter(
+    "reliapi_queue_timeouts_total",
+    "Total requests rejected because no concurrency slot was free within max_queue_wait_ms",
+    ["target"],
+)
+
+load_shed_total = Counter(
+    "reliapi_load_shed_total",
+    "Total requests rejected without queueing because every concurrency slot was busy (load_shedding)",
+    ["target"],
+)
+
+load_shedding_active = Gauge(
+    "reliapi_load_shedding_active",
+    "Whether the target is shedding load (1 from the first rejection until a slot frees up)",
+    ["target"],
+)
+
 # Rate scheduler metrics
 rate_scheduler_429_total = Counter(
     "reliapi_rate_scheduler_429_total",
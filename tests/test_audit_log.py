@@ -0,0 +1,64 @@
+"""Tests for core/audit_log.py."""
+import json
+from unittest.mock import patch
+
+import pytest
+
+from reliapi.app.routes.proxy import _audit_stream_rejection
+from reliapi.core.audit_log import AuditLog, rejection_category
+
+
+@patch('reliapi.core.audit_log.redis')
+def test_record_and_query_by_time_range(mock_redis_module, mock_redis, mock_redis_pipeline):
+    """Test rejections are stored by timestamp, pruned past retention and queried by range."""
+    mock_redis.pipeline.return_value = mock_redis_pipeline
+    mock_redis_module.from_url.return_value = mock_redis
+    log = AuditLog("redis://localhost:6379/0", retention_s=3600)
+
+    with patch('reliapi.core.audit_log.time.time', return_value=1000000.0):
+        record_id = log.record(
+            "budget", "BUDGET_EXCEEDED", "llm", target="openai", key_id="abc123",
+            tenant="acme", request_id="req_1", model="gpt-4o", cost_estimate_usd=0.12, status_code=400,
+        )
+
+    key, mapping = mock_redis_pipeline.zadd.call_args[0]
+    assert key == "reliapi:audit_log"
+    raw, score = next(iter(mapping.items()))
+    record = json.loads(raw)
+    assert score == 1000000.0
+    assert record["id"] == record_id
+    assert record["reason_code"] == "BUDGET_EXCEEDED"
+    assert record["key_id"] == "abc123"
+    assert record["cost_estimate_usd"] == 0.12
+    mock_redis_pipeline.zremrangebyscore.assert_called_once_with("reliapi:audit_log", "-inf", 1000000.0 - 3600)
+
+    mock_redis.zrangebyscore.return_value = [raw]
+    assert log.query(since=999000.0) == [record]
+    assert mock_redis.zrangebyscore.call_args[0] == ("reliapi:audit_log", 999000.0, "+inf")
+
+
+def test_audit_log_disabled_and_categories():
+    """Test the audit log degrades gracefully and only rejection codes are audited."""
+    log = AuditLog("redis://invalid:6379/0")
+    assert log.enabled is False
+    assert log.record("budget", "BUDGET_EXCEEDED", "llm") is None
+    assert log.query() == []
+
+    assert rejection_category("BUDGET_EXCEEDED") == "budget"
+    assert rejection_category("RATE_LIMIT_RELIAPI") == "rate_limit"
+    assert rejection_category("SERVER_ERROR") is None
+
+
+@pytest.mark.asyncio
+async def test_stream_ending_before_first_event_is_audited():
+    """Test a stream with no events yields one error event and is audited like a rejection."""
+    async def empty():
+        return
+        yield
+
+    with patch("reliapi.app.routes.proxy.record_rejection") as record:
+        events = [event async for event in _audit_stream_rejection(empty(), "openai", None, None, "req_1", None)]
+
+    assert len(events) == 1 and events[0].startswith("event: error\n")
+    assert record.call_args.kwargs["code"] == "UPSTREAM_STREAM_INTERRUPTED"
+    assert record.call_args.kwargs["status_code"] == 502
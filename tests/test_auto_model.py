@@ -0,0 +1,59 @@
+"""Tests for core/auto_model.py (model: "auto" selection)."""
+from reliapi.core.auto_model import select_auto_model
+
+TARGETS = {
+    "openai": {
+        "base_url": "https://api.openai.com/v1",
+        "llm": {
+            "provider": "openai",
+            "default_model": "gpt-4o-mini",
+            "max_tokens": 1024,
+            "auto_models": [
+                {"model": "gpt-4o", "context_window": 128000, "capabilities": ["vision", "tools"]},
+                {"model": "gpt-3.5-turbo", "context_window": 16385, "capabilities": ["tools"]},
+                {"model": "claude-3-haiku-20240307", "target": "anthropic", "context_window": 200000,
+                 "capabilities": ["vision"]},
+            ],
+        },
+    },
+    "anthropic": {
+        "base_url": "https://api.anthropic.com/v1",
+        "llm": {"provider": "anthropic", "default_model": "claude-3-haiku-20240307", "max_tokens": 1024},
+    },
+}
+
+MESSAGES = [{"role": "user", "content": "Summarize this in one line."}]
+
+
+def test_cheapest_candidate_without_constraints():
+    """Test the cheapest priced candidate wins, across targets."""
+    target, model, matched = select_auto_model("openai", TARGETS, MESSAGES)
+
+    assert (target, model, matched) == ("anthropic", "claude-3-haiku-20240307", True)
+
+
+def test_capability_constraint():
+    """Test candidates lacking a required capability are skipped."""
+    target, model, matched = select_auto_model(
+        "openai", TARGETS, MESSAGES, constraints={"capabilities": ["tools"]}
+    )
+
+    assert (target, model, matched) == ("openai", "gpt-3.5-turbo", True)
+
+
+def test_context_constraint():
+    """Test min_context_tokens filters out small-context candidates."""
+    _, model, _ = select_auto_model(
+        "openai", TARGETS, MESSAGES, constraints={"capabilities": ["tools"], "min_context_tokens": 100000}
+    )
+
+    assert model == "gpt-4o"
+
+
+def test_no_match_falls_back_to_default():
+    """Test no matching candidate leaves the target default model."""
+    target, model, matched = select_auto_model(
+        "openai", TARGETS, MESSAGES, constraints={"capabilities": ["audio"]}
+    )
+
+    assert (target, model, matched) == ("openai", None, False)
@@ -1,9 +1,13 @@
 """Tests for core/cache.py."""
+import asyncio
+import hashlib
 import json
 import pytest
+import redis
 from unittest.mock import Mock, patch
 
 from reliapi.core.cache import Cache
+from reliapi.core.memory_cache import MemoryCache
 
 
 @patch('reliapi.core.cache.redis')
@@ -28,13 +32,66 @@ def test_cache_ttl(mock_redis_module, mock_redis):
     mock_redis_module.from_url.return_value = mock_redis
     cache = Cache("redis://localhost:6379/0")
     
-    cache.set("GET", "https://example.com", None, None, {"data": "test"}, ttl_s=300)
+    cache.set("GET", "https://example.com", None, None, {"data": "test"}, ttl_s=300, ttl_jitter=0)
     
     # Verify TTL was set
     call_args = mock_redis.setex.call_args
     assert call_args[0][1] == 300  # TTL in seconds
 
 
+@patch('reliapi.core.cache.redis')
+def test_cache_ttl_jitter(mock_redis_module, mock_redis):
+    """Test stored TTLs are spread within +/- ttl_jitter and the applied TTL is returned."""
+    mock_redis_module.from_url.return_value = mock_redis
+    cache = Cache("redis://localhost:6379/0")
+    
+    applied = [
+        cache.set("GET", f"https://example.com/{i}", None, None, {"data": i}, ttl_s=1000, ttl_jitter=0.1)
+        for i in range(50)
+    ]
+    
+    assert [call[0][1] for call in mock_redis.setex.call_args_list] == applied
+    assert all(900 <= ttl <= 1100 for ttl in applied)
+    assert len(set(applied)) > 1
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_key_version(mock_redis_module, mock_redis):
+    """Test cache_key_version moves entries to new keys, and version 0 keeps the unversioned ones."""
+    mock_redis_module.from_url.return_value = mock_redis
+    unversioned = Cache("redis://localhost:6379/0")
+    v0 = Cache("redis://localhost:6379/0", key_version=0)
+    v2 = Cache("redis://localhost:6379/0", key_version=2)
+    v3 = Cache("redis://localhost:6379/0", key_version=3)
+
+    key = unversioned._make_key("GET", "https://example.com/items")
+    assert v0._make_key("GET", "https://example.com/items") == key
+    assert v2._make_key("GET", "https://example.com/items") == key.replace(":cache:", ":cache:v2:")
+    assert v3._make_key("GET", "https://example.com/items", tenant="acme").startswith("reliapi:tenant:acme:cache:v3:")
+
+    v2.set("GET", "https://example.com/items", None, None, {"data": "old"}, ttl_s=60)
+    mock_redis.get.side_effect = lambda k: json.dumps({"data": "old"}) if k == mock_redis.setex.call_args[0][0] else None
+    assert v2.get("GET", "https://example.com/items") == {"data": "old"}
+    assert v3.get("GET", "https://example.com/items") is None
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_reads_previous_version_key_on_miss(mock_redis_module, mock_redis):
+    """Test a miss falls back to the key the pre-canonical-JSON version wrote (key_version 0 only)."""
+    mock_redis_module.from_url.return_value = mock_redis
+    body = b'{"model": "gpt-4o-mini"}'
+    legacy_data = {"method": "POST", "url": "https://api.example.com/chat", "query": {}, "headers": {},
+                   "body_hash": hashlib.sha256(body).hexdigest()[:16]}
+    legacy_key = "reliapi:cache:" + hashlib.sha256(json.dumps(legacy_data, sort_keys=True).encode()).hexdigest()
+    mock_redis.get.side_effect = lambda k: json.dumps({"data": "old"}) if k == legacy_key else None
+
+    cache = Cache("redis://localhost:6379/0")
+    assert cache.get("POST", "https://api.example.com/chat", None, b'{"model":"gpt-4o-mini"}', None,
+                     allow_post=True, legacy_body=body) == {"data": "old"}
+    versioned = Cache("redis://localhost:6379/0", key_version=1)
+    assert versioned.get("POST", "https://api.example.com/chat", None, body, None, allow_post=True) is None
+
+
 def test_cache_disabled():
     """Test cache behavior when Redis is unavailable."""
     cache = Cache("redis://invalid:6379/0")
@@ -57,3 +114,141 @@ def test_cache_post_not_cached(mock_redis_module, mock_redis):
     # Should not call Redis
     mock_redis.setex.assert_not_called()
 
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_compression_roundtrip(mock_redis_module, mock_redis):
+    """Test large values are stored compressed and read back transparently."""
+    mock_redis_module.from_url.return_value = mock_redis
+    cache = Cache("redis://localhost:6379/0", compression={"enabled": True, "min_size_bytes": 100})
+    value = {"body": {"content": "lorem ipsum " * 200}}
+    
+    cache.set("GET", "https://example.com", None, None, value, ttl_s=60)
+    stored = mock_redis.setex.call_args[0][2]
+    assert stored.startswith("z1:")
+    assert len(stored) < len(json.dumps(value))
+    
+    mock_redis.get.return_value = stored
+    assert cache.get("GET", "https://example.com", None, None, None) == value
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_compression_threshold(mock_redis_module, mock_redis):
+    """Test values below min_size_bytes stay plain JSON."""
+    mock_redis_module.from_url.return_value = mock_redis
+    cache = Cache("redis://localhost:6379/0", compression={"enabled": True, "min_size_bytes": 1024})
+    
+    cache.set("GET", "https://example.com", None, None, {"data": "test"}, ttl_s=60)
+    assert mock_redis.setex.call_args[0][2] == json.dumps({"data": "test"})
+
+
+def test_memory_cache_cost_weighted_eviction():
+    """Test cost_weighted keeps expensive entries and lru keeps recent ones."""
+    value = "x" * 100
+    for eviction, survivor, evicted in (("cost_weighted", "expensive", "cheap"), ("lru", "cheap", "expensive")):
+        memory = MemoryCache(max_entries=2, eviction=eviction)
+        memory.set("expensive", value, ttl_s=60, cost_usd=0.05)
+        memory.set("cheap", value, ttl_s=60, cost_usd=0.0001)
+        memory.set("new", value, ttl_s=60, cost_usd=0.001)
+
+        assert len(memory) == 2
+        assert memory.get(survivor) == value
+        assert memory.get(evicted) is None
+
+    memory = MemoryCache(max_bytes=250)
+    memory.set("a", value, ttl_s=60)
+    memory.set("b", value, ttl_s=60)
+    memory.set("c", value, ttl_s=60)
+    assert memory.total_bytes == 200
+    assert memory.get("a") is None
+
+
+def test_cache_memory_tier_without_redis():
+    """Test the memory tier serves cache hits when Redis is unavailable."""
+    with patch('reliapi.core.cache.redis') as mock_redis_module:
+        mock_redis_module.from_url.side_effect = Exception("Connection failed")
+        cache = Cache("redis://invalid:6379/0", memory={"enabled": True, "eviction": "cost_weighted"})
+
+    assert cache.enabled is True
+    cache.set("GET", "https://example.com", None, None, {"data": "test", "cost_usd": 0.01}, ttl_s=60)
+    assert cache.get("GET", "https://example.com") == {"data": "test", "cost_usd": 0.01}
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_extend_ttl_on_hit(mock_redis_module, mock_redis):
+    """Test late hits double an entry's lifetime, capped at max_ttl_s after the write."""
+    mock_redis_module.from_url.return_value = mock_redis
+    cache = Cache("redis://localhost:6379/0")
+    with patch('reliapi.core.cache.time.time', return_value=1000.0):
+        cache.set("GET", "https://example.com", None, None, {"data": "test"}, ttl_s=100, ttl_jitter=0, max_ttl_s=300)
+    entry = json.loads(mock_redis.setex.call_args[0][2])
+    assert entry["data"] == "test"
+
+    def hit(now, remaining):
+        mock_redis.ttl.return_value = remaining
+        with patch('reliapi.core.cache.time.time', return_value=now):
+            return cache.extend_ttl("GET", "https://example.com", None, None, None, entry)
+
+    # Early in the lifetime: unchanged
+    assert hit(1020.0, 80) == 80
+    mock_redis.expire.assert_not_called()
+    # Past half-life: 100s lifetime becomes 200s, then 300s (the ceiling)
+    assert hit(1060.0, 40) == 140
+    assert mock_redis.expire.call_args[0][1] == 140
+    assert hit(1190.0, 10) == 110
+    # At the ceiling: never extended past written_at + max_ttl_s
+    mock_redis.expire.reset_mock()
+    assert hit(1295.0, 5) == 5
+    mock_redis.expire.assert_not_called()
+
+    # Entries written without max_ttl_s are not extended
+    assert cache.extend_ttl("GET", "https://example.com", None, None, None, {"data": "test"}) is None
+
+
+@patch('reliapi.core.cache.redis')
+def test_cache_keeps_entries_past_ttl_for_serve_stale(mock_redis_module, mock_redis):
+    """Test stale_ttl_s stores entries longer; past the TTL only serve_stale lookups get them."""
+    mock_redis_module.from_url.return_value = mock_redis
+    cache = Cache("redis://localhost:6379/0")
+    with patch('reliapi.core.cache.time.time', return_value=1000.0):
+        assert cache.set("GET", "https://example.com", None, None, {"data": "test"},
+                         ttl_s=100, ttl_jitter=0, stale_ttl_s=200) == 100
+    assert mock_redis.setex.call_args[0][1] == 300
+    mock_redis.get.return_value = mock_redis.setex.call_args[0][2]
+
+    with patch('reliapi.core.cache.time.time', return_value=1050.0):
+        assert cache.get("GET", "https://example.com")["data"] == "test"
+    with patch('reliapi.core.cache.time.time', return_value=1150.0):
+        assert cache.get("GET", "https://example.com") is None
+        assert cache.get("GET", "https://example.com", serve_stale=True)["data"] == "test"
+
+@pytest.mark.asyncio
+async def test_cache_write_retry_recovers_in_background(mock_redis):
+    """Test a transient write error is retried after the call returns, and persistent ones give up."""
+    with patch('reliapi.core.cache.redis') as mock_redis_module:
+        mock_redis_module.from_url.return_value = mock_redis
+        cache = Cache(
+            "redis://localhost:6379/0",
+            write_retry={"enabled": True, "attempts": 2, "backoff_ms": 1},
+        )
+    mock_redis.setex.side_effect = [redis.ConnectionError("blip"), None]
+
+    assert cache.set("GET", "https://example.com", None, None, {"data": 1}, ttl_s=60, ttl_jitter=0) is None
+    assert mock_redis.setex.call_count == 1
+    await asyncio.gather(*cache._pending_writes)
+
+    assert mock_redis.setex.call_count == 2
+    assert mock_redis.setex.call_args[0][1] == 60
+    assert not cache._pending_writes
+
+    mock_redis.setex.reset_mock(side_effect=True)
+    mock_redis.setex.side_effect = redis.TimeoutError("down")
+    cache.set("GET", "https://example.com/down", None, None, {"data": 2}, ttl_s=60)
+    await asyncio.gather(*cache._pending_writes)
+    assert mock_redis.setex.call_count == 3  # First write + 2 retries
+
+    # Without a running loop (or write_retry) the error is only logged
+    mock_redis.setex.reset_mock()
+    await asyncio.to_thread(cache.set, "GET", "https://example.com/sync", None, None, {"data": 3})
+    assert mock_redis.setex.call_count == 1
+    assert not cache._pending_writes
@@ -0,0 +1,43 @@
+"""Tests for canonical JSON hashing (core/canonical_json.py)."""
+import hashlib
+
+import pytest
+
+from reliapi.core.canonical_json import canonical_body, canonical_json, request_key_hash
+
+
+def test_canonical_json_format():
+    """Test sorted keys, no whitespace, literal UTF-8 and ECMAScript number formatting."""
+    value = {"b": [1, 2.0, -0.0, 0.1, 1e-7, 1e21, 0.000001], "a": {"z": None, "é": "line\n"}, "c": True}
+
+    assert canonical_json(value) == (
+        '{"a":{"z":null,"é":"line\\n"},"b":[1,2,0,0.1,1e-7,1e+21,0.000001],"c":true}'
+    )
+    # Keys sort by UTF-16 code units: an astral character sorts before U+FFFF
+    assert canonical_json({"￿": 1, "\U0001F600": 2}) == '{"\U0001F600":2,"￿":1}'
+    with pytest.raises(ValueError):
+        canonical_json({"x": float("nan")})
+
+
+def test_canonical_body_ignores_formatting():
+    """Test equivalent JSON bodies share a canonical form and non-JSON bodies pass through."""
+    assert canonical_body(b'{ "b": 1.0,\n  "a": [1, 2] }') == canonical_body(b'{"a":[1,2],"b":1}') == b'{"a":[1,2],"b":1}'
+    assert canonical_body(b"name=value&x=1") == b"name=value&x=1"
+    assert canonical_body(None) is None
+
+
+def test_request_key_hash_reproducible():
+    """Test the cache key hash is SHA-256 of the documented canonical key object."""
+    body = b'{"a":1}'
+    key_data = (
+        '{"body_hash":"' + hashlib.sha256(body).hexdigest()[:16] + '",'
+        '"headers":{"Content-Type":"application/json"},"method":"POST",'
+        '"query":{"page":2},"url":"https://api.example.com/items"}'
+    )
+
+    key_hash = request_key_hash(
+        "post", "https://api.example.com/items",
+        {"Content-Type": "application/json", "Authorization": "Bearer secret"}, body, {"page": 2},
+    )
+
+    assert key_hash == hashlib.sha256(key_data.encode()).hexdigest()
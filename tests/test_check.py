@@ -0,0 +1,145 @@
+"""Tests for admission checks without execution (POST /check, check_llm_request)."""
+from unittest.mock import Mock, patch
+
+import pytest
+from fastapi import HTTPException
+
+from reliapi.app.routes.check import _api_key_format_rejection, _free_tier_rejection
+from reliapi.app.routes.proxy import proxy_llm, turn_limit_rejection
+from reliapi.app.schemas import LLMProxyRequest
+from reliapi.app.services import check_llm_request
+from reliapi.config.schema import TenantConfig
+from reliapi.core.model_limits import ModelRateLimiter
+from reliapi.core.tenant_limits import TenantLimits
+
+MESSAGES = [{"role": "user", "content": "Hello"}]
+
+
+@pytest.fixture
+def targets():
+    """LLM target with caps."""
+    return {
+        "openai": {
+            "base_url": "https://api.openai.com/v1",
+            "llm": {
+                "provider": "openai",
+                "default_model": "gpt-4o-mini",
+                "hard_cost_cap_usd": 0.05,
+                "output_caps": {"gpt-4o": {"max_tokens": 500, "on_exceed": "reject"}},
+                "model_limits": {"gpt-4o-mini": {"rpm": 1}},
+            },
+        }
+    }
+
+
+def test_check_reports_estimate_and_first_failing_gate(targets):
+    """Test an allowed request gets its estimate, and rejected ones the gate, code and status."""
+    allowed = check_llm_request("openai", MESSAGES, None, 100, targets)
+    assert allowed["allowed"] is True
+    assert allowed["reason"] is None
+    assert allowed["model"] == "gpt-4o-mini"
+    assert allowed["cost_estimate_usd"] > 0
+
+    over_cap = check_llm_request("openai", MESSAGES, "gpt-4", 4000, targets)
+    assert over_cap["allowed"] is False
+    assert over_cap["reason"]["gate"] == "budget"
+    assert over_cap["reason"]["code"] == "BUDGET_EXCEEDED"
+    assert over_cap["cost_estimate_usd"] > 0.05
+
+    output_cap = check_llm_request("openai", MESSAGES, "gpt-4o", 1000, targets)
+    assert output_cap["reason"]["code"] == "OUTPUT_CAP_EXCEEDED"
+    assert output_cap["reason"]["status_code"] == 422
+
+    assert check_llm_request("missing", MESSAGES, None, None, targets)["reason"]["code"] == "NOT_FOUND"
+
+
+@pytest.mark.asyncio
+async def test_check_consumes_no_rate_limit(targets):
+    """Test checks leave model and tenant windows untouched, and report an exhausted RPM."""
+    limiter = ModelRateLimiter()
+    for _ in range(3):
+        assert check_llm_request("openai", MESSAGES, None, 100, targets, model_rate_limiter=limiter)["allowed"]
+
+    await limiter.acquire("openai", "gpt-4o-mini", {"rpm": 1}, 0)
+    limited = check_llm_request("openai", MESSAGES, None, 100, targets, model_rate_limiter=limiter)
+    assert limited["reason"]["gate"] == "rate_limit"
+    assert limited["reason"]["status_code"] == 429
+
+    tenant_limits = TenantLimits()
+    config = {"rate_limit_rpm": 1}
+    assert tenant_limits.check("acme", config) is None
+    assert tenant_limits.check("acme", config) is None
+    assert tenant_limits.acquire("acme", config) is None
+    assert tenant_limits.check("acme", config)[0] == "rate_limit"
+
+
+def test_turn_limit_uses_lower_of_target_and_tenant(targets):
+    """Test conversations over max_messages get 422 TOO_MANY_TURNS details; tenants only lower it."""
+    targets["openai"]["llm"]["max_messages"] = 4
+    state = Mock(targets=targets)
+    state.config_loader.get_tenant.side_effect = lambda name: TenantConfig(
+        api_key="sk-acme", max_messages={"acme": 2, "big": 10}[name],
+    ).model_dump()
+    conversation = MESSAGES * 3
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        assert turn_limit_rejection(None, "openai", MESSAGES * 4) is None
+        assert turn_limit_rejection("big", "openai", conversation) is None
+        rejected = turn_limit_rejection("acme", "openai", conversation)
+        assert turn_limit_rejection(None, "openai", MESSAGES * 5)["details"]["max_messages"] == 4
+        # Targets without the setting get the default of 500
+        assert turn_limit_rejection(None, "missing", MESSAGES * 500) is None
+
+    assert rejected["code"] == "TOO_MANY_TURNS"
+    assert rejected["details"] == {"messages": 3, "max_messages": 2}
+
+
+def test_check_peeks_free_tier_limits_and_key_format():
+    """Test free-tier limits are read without counting, and a malformed key is an auth rejection."""
+    rate_limiter = Mock()
+    rate_limiter.check_auto_ban.return_value = (False, None)
+    rate_limiter.peek_ip_rate_limit.return_value = (3, 2_000_000_000)
+    rate_limiter.peek_burst_protection.return_value = (0, 2_000_000_000)
+    rate_limiter.peek_account_burst_limit.return_value = (400, 2_000_000_000)
+    http_request = Mock()
+    http_request.client.host = "10.0.0.1"
+
+    with patch("reliapi.app.routes.check.get_app_state", return_value=Mock(rate_limiter=rate_limiter)):
+        burst = _free_tier_rejection(http_request, "sk-free-test", "free")
+        assert _free_tier_rejection(http_request, "sk-pro-test", "pro") is None
+        rate_limiter.check_auto_ban.return_value = (True, "IP banned: 5 bypass attempts")
+        banned = _free_tier_rejection(http_request, "sk-free-test", "free")
+
+    assert burst["gate"] == "rate_limit"
+    assert burst["code"] == "BURST_LIMIT_EXCEEDED"
+    assert burst["status_code"] == 429
+    assert burst["details"]["retry_after_s"] > 0
+    assert banned["gate"] == "auth"
+    assert banned["status_code"] == 403
+    rate_limiter.check_ip_rate_limit.assert_not_called()
+    rate_limiter.check_burst_protection.assert_not_called()
+
+    assert _api_key_format_rejection("sk-" + "a" * 24) is None
+    assert _api_key_format_rejection("bad key")["gate"] == "auth"
+
+
+@pytest.mark.asyncio
+async def test_turn_limit_rejects_before_rate_limits_are_counted(targets):
+    """Test a too-long conversation is rejected before the free-tier and tenant limits count it."""
+    targets["openai"]["llm"]["max_messages"] = 2
+    state = Mock(targets=targets, config_loader=None, rate_limiter=None, tenant_limits=None)
+    http_request = Mock(headers={})
+    request = LLMProxyRequest(target="openai", messages=MESSAGES * 3)
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state), \
+         patch("reliapi.app.routes.proxy.verify_api_key", return_value=(None, None, "free")), \
+         patch("reliapi.app.routes.proxy.record_check_rejection"), \
+         patch("reliapi.app.routes.proxy._check_llm_free_tier_restrictions") as free_tier, \
+         patch("reliapi.app.routes.proxy.check_tenant_limits") as tenant_limits:
+        with pytest.raises(HTTPException) as rejected:
+            await proxy_llm(request, http_request)
+
+    assert rejected.value.status_code == 422
+    assert rejected.value.detail["code"] == "TOO_MANY_TURNS"
+    free_tier.assert_not_called()
+    tenant_limits.assert_not_called()
@@ -15,7 +15,7 @@ def test_circuit_breaker_closed_to_open():
     
     # Record failures
     cb.record_failure("upstream1")
-    assert cb.get_state("upstream1") == "half-open"
+    assert cb.get_state("upstream1") == "closed"
     
     cb.record_failure("upstream1")
     cb.record_failure("upstream1")  # Third failure opens circuit
@@ -25,7 +25,7 @@ def test_circuit_breaker_closed_to_open():
 
 
 def test_circuit_breaker_open_to_closed():
-    """Test circuit breaker auto-closes after TTL."""
+    """Test circuit breaker goes half-open after TTL and a probe success closes it."""
     cb = CircuitBreaker(failures_to_open=2, open_ttl_s=1)  # Short TTL for testing
     
     # Open circuit
@@ -36,8 +36,12 @@ def test_circuit_breaker_open_to_closed():
     # Wait for TTL
     time.sleep(1.1)
     
-    # Should auto-close
+    # Half-open: one probe is let through
     assert cb.is_open("upstream1") is False
+    assert cb.get_state("upstream1") == "half-open"
+    assert cb.allow_request("upstream1") is True
+    
+    cb.record_success("upstream1")
     assert cb.get_state("upstream1") == "closed"
 
 
@@ -48,10 +52,70 @@ def test_circuit_breaker_success_resets():
     # Record failures
     cb.record_failure("upstream1")
     cb.record_failure("upstream1")
-    assert cb.get_state("upstream1") == "half-open"
+    assert cb.failure_counts["upstream1"] == 2
     
     # Success resets
     cb.record_success("upstream1")
     assert cb.get_state("upstream1") == "closed"
     assert cb.is_open("upstream1") is False
 
+
+
+def test_half_open_limits_probes_and_needs_success_threshold():
+    """Test half-open admits half_open_max_calls probes and closes after success_threshold successes."""
+    cb = CircuitBreaker(failures_to_open=1, open_ttl_s=1, half_open_max_calls=2, success_threshold=2)
+    cb.record_failure("upstream1")
+    assert cb.allow_request("upstream1") is False
+    cb.opened_at["upstream1"] -= 1.1  # Expire the open TTL
+    
+    assert cb.allow_request("upstream1") is True
+    assert cb.allow_request("upstream1") is True
+    assert cb.allow_request("upstream1") is False  # Both probe slots taken
+    assert cb.snapshot("upstream1")["half_open_calls"] == 2
+    
+    cb.record_success("upstream1")
+    snapshot = cb.snapshot("upstream1")
+    assert snapshot["state"] == "half-open"
+    assert snapshot["half_open_successes"] == 1
+    
+    cb.record_success("upstream1")
+    assert cb.get_state("upstream1") == "closed"
+
+
+def test_half_open_probe_failure_reopens():
+    """Test a failed probe opens the circuit again."""
+    cb = CircuitBreaker(failures_to_open=1, open_ttl_s=1, success_threshold=3)
+    cb.record_failure("upstream1")
+    cb.opened_at["upstream1"] -= 1.1
+    
+    assert cb.allow_request("upstream1") is True
+    cb.record_success("upstream1")
+    assert cb.allow_request("upstream1") is True
+    cb.record_failure("upstream1")
+    
+    assert cb.get_state("upstream1") == "open"
+    assert cb.allow_request("upstream1") is False
+
+
+def test_decayed_failures_ignore_old_blips():
+    """Test with decay_half_life_s old failures fade, and the failure rate must also be reached."""
+    cb = CircuitBreaker(failures_to_open=2, open_ttl_s=60, decay_half_life_s=10, failure_rate_threshold=0.5)
+    cb.record_failure("upstream1")
+    cb.decayed["upstream1"][2] -= 20  # Failure from 20s ago weighs 0.25
+    cb.record_failure("upstream1")
+    assert cb.get_state("upstream1") == "closed"
+    assert cb.snapshot("upstream1")["failure_score"] == pytest.approx(1.25, abs=0.01)
+
+    # Recent failures open it, unless successes keep the rate under the threshold
+    cb.record_failure("upstream1")
+    assert cb.get_state("upstream1") == "open"
+
+    busy = CircuitBreaker(failures_to_open=2, open_ttl_s=60, decay_half_life_s=10, failure_rate_threshold=0.5)
+    for _ in range(3):
+        busy.record_success("upstream1")
+    busy.record_failure("upstream1")
+    busy.record_failure("upstream1")
+    snapshot = busy.snapshot("upstream1")
+    assert snapshot["state"] == "closed"
+    assert snapshot["failure_rate"] == pytest.approx(0.4, abs=0.01)
+    assert CircuitBreaker().snapshot("upstream1")["failure_rate"] is None
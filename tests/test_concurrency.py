@@ -0,0 +1,71 @@
+"""Tests for core/concurrency.py."""
+from unittest.mock import patch
+
+import pytest
+
+from reliapi.core.concurrency import ConcurrencyLimiter, Overloaded, QueueTimeout
+
+
+@pytest.mark.asyncio
+async def test_queue_timeout_when_saturated():
+    """Test a request over max_concurrent gives up after max_queue_wait_ms."""
+    limiter = ConcurrencyLimiter()
+    config = {"max_concurrent": 1, "max_queue_wait_ms": 20}
+
+    slot = await limiter.acquire("openai", config)
+    with pytest.raises(QueueTimeout) as exc_info:
+        await limiter.acquire("openai", config)
+    assert exc_info.value.max_queue_wait_ms == 20
+
+    # Releasing (twice is a no-op) frees the slot for the next request
+    slot.release()
+    slot.release()
+    second = await limiter.acquire("openai", config)
+    assert second is not None
+    with pytest.raises(QueueTimeout):
+        await limiter.acquire("openai", config)
+
+
+@pytest.mark.asyncio
+async def test_unlimited_target_has_no_slot():
+    """Test targets without a concurrency config are not limited."""
+    limiter = ConcurrencyLimiter()
+    assert await limiter.acquire("openai", None) is None
+    assert await limiter.acquire("openai", {}) is None
+
+
+@pytest.mark.asyncio
+async def test_load_shedding_rejects_with_retry_after():
+    """Test a load_shedding target rejects at once with a hint from in-flight count and latency."""
+    limiter = ConcurrencyLimiter()
+    config = {"max_concurrent": 2, "load_shedding": True}
+
+    with patch("reliapi.core.concurrency.time.monotonic", side_effect=[0.0, 0.0, 4.0]):
+        first = await limiter.acquire("openai", config)
+        second = await limiter.acquire("openai", config)
+        # Observed slot hold time: 4s
+        first.release()
+    third = await limiter.acquire("openai", config)
+
+    # 2 slots draining 4s requests: one request ahead clears in 2s
+    with pytest.raises(Overloaded) as exc_info:
+        await limiter.acquire("openai", config)
+    assert exc_info.value.retry_after_s == 2.0
+    assert "overloaded" in str(exc_info.value)
+
+    second.release()
+    assert await limiter.acquire("openai", config) is not None
+    third.release()
+
+
+@pytest.mark.asyncio
+async def test_queue_timeout_carries_retry_after():
+    """Test a queue timeout without observed latency falls back to the default hint."""
+    limiter = ConcurrencyLimiter()
+    config = {"max_concurrent": 1, "max_queue_wait_ms": 10}
+
+    await limiter.acquire("anthropic", config)
+    with pytest.raises(QueueTimeout) as exc_info:
+        await limiter.acquire("anthropic", config)
+    assert not isinstance(exc_info.value, Overloaded)
+    assert exc_info.value.retry_after_s == 1.0
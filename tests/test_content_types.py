@@ -0,0 +1,138 @@
+"""Tests for per-target content-type rules (core/content_types.py)."""
+import base64
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+from pydantic import ValidationError
+
+from reliapi.app.services import HTTPStreamResult, handle_http_proxy
+from reliapi.config.schema import ContentTypeRule
+from reliapi.core.cache import Cache
+from reliapi.core.content_types import extract_field, select_content_rule
+from reliapi.core.idempotency import IdempotencyManager
+
+
+@pytest.fixture
+def mock_cache():
+    """Mock cache."""
+    cache = Mock(spec=Cache)
+    cache.enabled = True
+    cache.get.return_value = None
+    return cache
+
+
+@pytest.fixture
+def mock_idempotency():
+    """Mock idempotency manager."""
+    manager = Mock(spec=IdempotencyManager)
+    manager.enabled = True
+    return manager
+
+
+def _targets(rules):
+    return {
+        "files": {
+            "base_url": "https://files.example.com",
+            "timeout_ms": 10000,
+            "cache": {"enabled": True, "ttl_s": 300},
+            "content_types": rules,
+        }
+    }
+
+
+async def _proxy(targets, cache, idempotency, headers=None):
+    return await handle_http_proxy(
+        target_name="files", method="GET", path="/f", headers=headers, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=targets, cache=cache,
+        idempotency=idempotency, request_id="test-123",
+    )
+
+
+def test_select_content_rule_precedence():
+    """Test the first matching rule wins, parameters are ignored and response rules wait for headers."""
+    rules = [
+        {"match": "application/json", "on": "request", "cache": False},
+        {"match": "image/png", "body": "base64"},
+        {"match": "image/*", "cache": False},
+        {"match": "*/*", "transform": False},
+    ]
+    json_request = {"Content-Type": "application/json; charset=utf-8"}
+
+    assert select_content_rule(rules, json_request, {"content-type": "image/png"}) is rules[0]
+    assert select_content_rule(rules, None, {"content-type": "IMAGE/PNG"}) is rules[1]
+    assert select_content_rule(rules, None, {"content-type": "image/gif"}) is rules[2]
+    assert select_content_rule(rules, None, {}) == {}
+    assert select_content_rule(rules, None) == {}
+    assert select_content_rule(None, json_request, {"content-type": "text/plain"}) == {}
+
+
+def test_content_type_rule_validation():
+    """Test match patterns and extract paths are checked, and request rules cannot stream."""
+    assert ContentTypeRule(match="text/*", extract="data[].id").extract == "data[].id"
+    with pytest.raises(ValidationError):
+        ContentTypeRule(match="json")
+    with pytest.raises(ValidationError):
+        ContentTypeRule(match="*/json")
+    with pytest.raises(ValidationError):
+        ContentTypeRule(match="application/json", extract="a..b")
+    with pytest.raises(ValidationError):
+        ContentTypeRule(match="application/json", on="request", stream=True)
+
+
+def test_extract_field():
+    """Test extract follows dotted paths, maps over lists and returns None for missing fields."""
+    body = {"data": {"items": [{"id": 1}, {"id": 2}]}}
+
+    assert extract_field(body, "data.items[].id") == [1, 2]
+    assert extract_field(body, "data.missing") is None
+    assert extract_field("text", "data") is None
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_binary_rule_skips_cache(mock_cache, mock_idempotency):
+    """Test a matching rule decodes the body as base64 and keeps it out of the cache."""
+    targets = _targets([{"match": "image/*", "body": "base64", "cache": False}])
+    upstream = Mock(status_code=200, headers={"content-type": "image/gif"})
+    upstream.aread = AsyncMock(return_value=b"GIF89a")
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await _proxy(targets, mock_cache, mock_idempotency)
+
+    # Valid UTF-8, but the rule forces base64
+    assert result.data["body"] == {"raw_base64": base64.b64encode(b"GIF89a").decode()}
+    mock_cache.set.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_stream_rule_relays_body(mock_cache, mock_idempotency):
+    """Test a stream rule relays the upstream body and releases the client once it is consumed."""
+    targets = _targets([{"match": "text/event-stream", "stream": True}])
+
+    async def body():
+        yield b"data: a\n\n"
+        yield b"data: b\n\n"
+
+    upstream = Mock(
+        status_code=200, is_success=True, aclose=AsyncMock(), aiter_bytes=body,
+        headers={"content-type": "text/event-stream", "content-length": "18"},
+    )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await _proxy(targets, mock_cache, mock_idempotency)
+
+        assert isinstance(result, HTTPStreamResult)
+        client_cls.return_value.close.assert_not_called()
+        chunks = [chunk async for chunk in result.chunks]
+
+    assert chunks == [b"data: a\n\n", b"data: b\n\n"]
+    assert result.status_code == 200
+    assert result.headers["content-type"] == "text/event-stream"
+    assert "content-length" not in result.headers
+    assert callable(client_cls.return_value.request.call_args.kwargs["stream_when"])
+    client_cls.return_value.close.assert_awaited_once()
+    upstream.aclose.assert_awaited_once()
+    mock_cache.set.assert_not_called()
@@ -0,0 +1,75 @@
+"""Tests for CORS configuration (app/main.py _configure_cors)."""
+import pytest
+from fastapi import FastAPI
+from fastapi.testclient import TestClient
+
+from reliapi.app.main import _configure_cors
+
+
+def _client(monkeypatch, **env) -> TestClient:
+    for name in ("CORS_ORIGINS", "CORS_ALLOW_METHODS", "CORS_ALLOW_HEADERS", "CORS_ALLOW_CREDENTIALS"):
+        monkeypatch.delenv(name, raising=False)
+    for name, value in env.items():
+        monkeypatch.setenv(name, value)
+
+    app = FastAPI()
+
+    @app.post("/proxy/llm")
+    def proxy_llm():
+        return {"success": True}
+
+    _configure_cors(app)
+    return TestClient(app)
+
+
+def _preflight(client: TestClient, origin: str):
+    return client.options(
+        "/proxy/llm",
+        headers={
+            "Origin": origin,
+            "Access-Control-Request-Method": "POST",
+            "Access-Control-Request-Headers": "Content-Type,X-API-Key",
+        },
+    )
+
+
+def test_cors_disabled_by_default(monkeypatch):
+    """Test no CORS headers are sent unless CORS_ORIGINS is set."""
+    client = _client(monkeypatch)
+
+    response = client.post("/proxy/llm", headers={"Origin": "https://evil.example"})
+    assert "access-control-allow-origin" not in response.headers
+
+
+def test_cors_preflight_allowed_origin(monkeypatch):
+    """Test preflight succeeds for a configured origin with credentials."""
+    client = _client(
+        monkeypatch,
+        CORS_ORIGINS="https://app.example.com",
+        CORS_ALLOW_CREDENTIALS="true",
+    )
+
+    response = _preflight(client, "https://app.example.com")
+    assert response.status_code == 200
+    assert response.headers["access-control-allow-origin"] == "https://app.example.com"
+    assert response.headers["access-control-allow-credentials"] == "true"
+    assert "POST" in response.headers["access-control-allow-methods"]
+
+
+def test_cors_preflight_rejects_other_origin(monkeypatch):
+    """Test preflight from an unlisted origin is rejected."""
+    client = _client(monkeypatch, CORS_ORIGINS="https://app.example.com")
+
+    response = _preflight(client, "https://evil.example")
+    assert response.status_code == 400
+    assert "access-control-allow-origin" not in response.headers
+
+
+def test_cors_wildcard_never_allows_credentials(monkeypatch):
+    """Test credentials are dropped when all origins are allowed."""
+    client = _client(monkeypatch, CORS_ORIGINS="*", CORS_ALLOW_CREDENTIALS="true")
+
+    response = _preflight(client, "https://any.example")
+    assert response.status_code == 200
+    assert response.headers["access-control-allow-origin"] == "*"
+    assert "access-control-allow-credentials" not in response.headers
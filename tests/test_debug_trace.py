@@ -0,0 +1,59 @@
+"""Tests for the debug trace buffer (core/debug_trace.py) and stream traces."""
+from unittest.mock import patch
+
+import pytest
+
+from reliapi.app.dependencies import AppState
+from reliapi.app.routes.proxy import _trace_stream
+from reliapi.core.debug_trace import REDACTED, DebugTraceBuffer
+
+
+def test_buffer_keeps_newest_and_redacts():
+    """Test the buffer drops the oldest traces, lists newest first and redacts configured fields."""
+    buffer = DebugTraceBuffer(size=2, redact=["tenant", "error_message"])
+    for i in range(3):
+        buffer.record(request_id=f"req_{i}", target="openai" if i else "my_api", tenant="acme",
+                      key_id="abc", error_message=None)
+
+    traces = buffer.recent()
+    assert [t["request_id"] for t in traces] == ["req_2", "req_1"]
+    assert traces[0]["tenant"] == REDACTED
+    assert traces[0]["key_id"] == "abc"
+    assert traces[0]["error_message"] is None
+    assert buffer.recent(limit=1)[0]["request_id"] == "req_2"
+    assert buffer.recent(target="my_api") == []
+
+
+def test_buffer_sampling():
+    """Test sample_rate 0 keeps nothing and the default redacts metadata."""
+    assert DebugTraceBuffer(sample_rate=0.0).record(request_id="req_1") is False
+
+    buffer = DebugTraceBuffer()
+    buffer.record(request_id="req_1", metadata={"user": "alice@example.com"})
+    assert buffer.recent()[0]["metadata"] == REDACTED
+
+
+@pytest.mark.asyncio
+async def test_stream_trace_recorded_when_stream_ends():
+    """Test a streamed request is traced with its final status, cache hit and cost."""
+    state = AppState(debug_trace=DebugTraceBuffer(redact=[]))
+
+    async def events():
+        yield 'event: meta\ndata: {"model": "gpt-4o-mini", "provider": "openai", "cache_hit": true}\n\n'
+        yield 'event: chunk\ndata: {"delta": "Hi", "finish_reason": null}\n\n'
+        yield 'event: done\ndata: {"finish_reason": "stop", "cost_usd": 0.001, "cache_hit": true}\n\n'
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        relayed = [e async for e in _trace_stream(
+            events(), "openai", None, "sk-test", None, "req_1", {"team": "growth"}, None,
+        )]
+
+    assert len(relayed) == 3
+    trace = state.debug_trace.recent()[0]
+    assert trace["status"] == "success"
+    assert trace["model"] == "gpt-4o-mini"
+    assert trace["cache_hit"] is True
+    assert trace["streamed"] is True
+    assert trace["cost_usd"] == 0.001
+    assert trace["tags"] == {"team": "growth"}
+    assert trace["key_id"] != "sk-test"
@@ -0,0 +1,136 @@
+"""Tests for content-hash request deduplication."""
+import pytest
+from unittest.mock import AsyncMock, Mock, patch
+
+from reliapi.app.dedup import run_with_dedup
+from reliapi.app.schemas import ErrorResponse, MetaResponse, SuccessResponse
+from reliapi.core.dedup import RequestDeduplicator
+
+
+DEDUP_TARGET = {"dedup": {"enabled": True, "window_ms": 5000, "action": "coalesce"}}
+
+
+def _success(request_id="req_first"):
+    return SuccessResponse(
+        data={"status_code": 200, "body": {"ok": True}},
+        meta=MetaResponse(target="my_api", duration_ms=120, request_id=request_id),
+    )
+
+
+@pytest.fixture
+def deduplicator():
+    """Mock deduplicator where the hash is already claimed."""
+    dedup = Mock(spec=RequestDeduplicator)
+    dedup.enabled = True
+    dedup.make_content_hash.return_value = "abc"
+    dedup.claim.return_value = (False, "req_first")
+    dedup.get_result.return_value = None
+    dedup.is_in_flight.return_value = False
+    return dedup
+
+
+def test_content_hash_is_order_independent():
+    """Test identical payloads hash the same regardless of key order."""
+    h1 = RequestDeduplicator.make_content_hash("http", "my_api", {"path": "/a", "query": {"x": 1, "y": 2}})
+    h2 = RequestDeduplicator.make_content_hash("http", "my_api", {"query": {"y": 2, "x": 1}, "path": "/a"})
+    h3 = RequestDeduplicator.make_content_hash("http", "other_api", {"path": "/a", "query": {"x": 1, "y": 2}})
+    assert h1 == h2
+    assert h1 != h3
+
+
+@patch('reliapi.core.dedup.redis')
+def test_claim_window(mock_redis_module, mock_redis):
+    """Test the first request claims the hash for the window."""
+    mock_redis_module.from_url.return_value = mock_redis
+    dedup = RequestDeduplicator("redis://localhost:6379/0")
+
+    mock_redis.set.return_value = True
+    assert dedup.claim("abc", "req_1", 5000, tenant="acme") == (True, None)
+    args, kwargs = mock_redis.set.call_args
+    assert args[0] == "reliapi:tenant:acme:dedup_claim:abc"
+    assert kwargs == {"nx": True, "px": 5000}
+
+    mock_redis.set.return_value = None
+    mock_redis.get.return_value = "req_1"
+    assert dedup.claim("abc", "req_2", 5000, tenant="acme") == (False, "req_1")
+
+
+@pytest.mark.asyncio
+async def test_first_request_stores_result(deduplicator):
+    """Test the first request runs and publishes its result."""
+    deduplicator.claim.return_value = (True, None)
+    call = AsyncMock(return_value=_success())
+
+    result = await run_with_dedup(deduplicator, DEDUP_TARGET, "http", "my_api", {"path": "/a"},
+                                  "req_first", None, None, call)
+
+    assert result.meta.deduplicated is None
+    call.assert_awaited_once()
+    deduplicator.store_result.assert_called_once()
+    deduplicator.finish.assert_called_once_with("abc", tenant=None)
+
+
+@pytest.mark.asyncio
+async def test_duplicate_coalesced(deduplicator):
+    """Test a duplicate returns the first result flagged as deduplicated."""
+    deduplicator.get_result.return_value = _success().model_dump()
+    call = AsyncMock()
+
+    result = await run_with_dedup(deduplicator, DEDUP_TARGET, "http", "my_api", {"path": "/a"},
+                                  "req_dup", None, None, call)
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.deduplicated is True
+    assert result.meta.request_id == "req_dup"
+    assert result.data["body"] == {"ok": True}
+    call.assert_not_awaited()
+
+
+@pytest.mark.asyncio
+async def test_duplicate_rejected(deduplicator):
+    """Test action=reject returns 409 for duplicates."""
+    target_config = {"dedup": {"enabled": True, "action": "reject"}}
+    call = AsyncMock()
+
+    result = await run_with_dedup(deduplicator, target_config, "llm", "openai", {"messages": []},
+                                  "req_dup", None, None, call)
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.status_code == 409
+    assert result.error.code == "DUPLICATE_REQUEST"
+    assert result.error.details["first_request_id"] == "req_first"
+    call.assert_not_awaited()
+
+
+@pytest.mark.asyncio
+async def test_dedup_skipped_with_idempotency_key(deduplicator):
+    """Test requests with an idempotency key bypass dedup."""
+    call = AsyncMock(return_value=_success())
+    await run_with_dedup(deduplicator, DEDUP_TARGET, "http", "my_api", {"path": "/a"},
+                         "req_1", None, "idem-1", call)
+    call.assert_awaited_once()
+    deduplicator.claim.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_keyed_duplicate_waits_then_runs_with_coalesce_keyed(deduplicator):
+    """Test coalesce_keyed makes a keyed duplicate wait for the first request, then run its own call."""
+    target_config = {"dedup": {"enabled": True, "action": "coalesce", "coalesce_keyed": True}}
+    deduplicator.is_in_flight.side_effect = [True, True, False]
+    call = AsyncMock(return_value=_success("req_dup"))
+
+    result = await run_with_dedup(deduplicator, target_config, "llm", "openai", {"messages": []},
+                                  "req_dup", None, "idem-2", call)
+
+    # Its own result (normally a cache hit), not the coalesced result of the first request
+    assert result.meta.request_id == "req_dup"
+    assert result.meta.deduplicated is None
+    assert deduplicator.is_in_flight.call_count == 3
+    call.assert_awaited_once()
+
+    # action=reject never rejects keyed requests
+    deduplicator.claim.reset_mock()
+    reject_config = {"dedup": {"enabled": True, "action": "reject", "coalesce_keyed": True}}
+    await run_with_dedup(deduplicator, reject_config, "llm", "openai", {"messages": []},
+                         "req_3", None, "idem-3", call)
+    deduplicator.claim.assert_not_called()
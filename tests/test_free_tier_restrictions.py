@@ -1,4 +1,5 @@
 """Tests for Free tier restrictions and rate limiting."""
+import time
 import pytest
 from unittest.mock import patch
 from reliapi.core.free_tier_restrictions import FreeTierRestrictions, FREE_TIER_ALLOWED_MODELS
@@ -194,6 +195,18 @@ class TestRateLimiter:
         assert allowed is False
         assert error == "RATE_LIMIT_EXCEEDED"
     
+    def test_peek_ip_rate_limit_does_not_count(self, rate_limiter, mock_redis):
+        """Test peeking reports what is left of the minute without incrementing the counter."""
+        mock_redis.get.return_value = "15"
+        mock_redis.ttl.return_value = 42
+        mock_redis.incr.reset_mock()
+
+        remaining, reset_at = rate_limiter.peek_ip_rate_limit("192.168.1.1", limit_per_minute=20)
+
+        assert remaining == 5
+        assert reset_at - time.time() == pytest.approx(42, abs=1)
+        mock_redis.incr.assert_not_called()
+
     def test_account_burst_limit(self, rate_limiter, mock_redis):
         """Test per-account burst limiting."""
         account_id = "test-account-123"
@@ -0,0 +1,58 @@
+"""Tests for core/grpc_transcoding.py."""
+import json
+from types import SimpleNamespace
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+from pydantic import ValidationError
+
+from reliapi.config.schema import GrpcConfig, TargetConfig
+from reliapi.core.grpc_transcoding import GrpcTransport, error_response, request_metadata
+
+
+def test_grpc_status_mapping_and_metadata():
+    """Test gRPC statuses map to retryable/non-retryable HTTP statuses and headers become metadata."""
+    unavailable = error_response("UNAVAILABLE", "connection refused")
+    assert unavailable.status_code == 503
+    assert json.loads(unavailable.content) == {"error": {"code": "UNAVAILABLE", "message": "connection refused"}}
+    assert unavailable.headers["grpc-status"] == "UNAVAILABLE"
+    assert error_response("RESOURCE_EXHAUSTED", "quota").status_code == 429
+    assert error_response("INVALID_ARGUMENT", "bad").status_code == 400
+
+    metadata = request_metadata({
+        "Authorization": "Bearer t", "X-Request-Id": "r1", "Content-Type": "application/json",
+        "User-Agent": "ReliAPI", "grpc-timeout": "1S",
+    })
+    assert metadata == [("authorization", "Bearer t"), ("x-request-id", "r1")]
+
+
+def test_grpc_config_validation():
+    """Test methods are normalized to request paths and gRPC targets need a grpc(s):// base URL."""
+    config = GrpcConfig(descriptor_set="api.pb", methods=["inventory.v1.Stock/Get", "/inventory.v1.Stock/List"])
+    assert config.methods == ["/inventory.v1.Stock/Get", "/inventory.v1.Stock/List"]
+    with pytest.raises(ValidationError):
+        GrpcConfig(descriptor_set="api.pb", methods=["Get"])
+    with pytest.raises(ValidationError):
+        TargetConfig(base_url="https://inventory.internal", grpc=config)
+    assert TargetConfig(base_url="grpcs://inventory.internal:443", grpc=config).grpc == config
+
+
+@pytest.mark.asyncio
+async def test_transport_calls_method_for_request_path():
+    """Test the transport dispatches on scheme, authority and path with the read timeout."""
+    transcoder = Mock()
+    transcoder.call = AsyncMock(return_value=error_response("NOT_FOUND", "no such item"))
+    request = SimpleNamespace(
+        url=SimpleNamespace(scheme="grpc", host="inventory.internal", port=None, path="/inventory.v1.Stock/Get"),
+        headers={"X-Request-Id": "r1"},
+        extensions={"timeout": {"read": 2.5}},
+        aread=AsyncMock(return_value=b'{"sku": "A1"}'),
+    )
+
+    with patch("reliapi.core.grpc_transcoding.get_transcoder", return_value=transcoder):
+        response = await GrpcTransport("inventory", {}).handle_async_request(request)
+
+    transcoder.call.assert_awaited_once_with(
+        "grpc", "inventory.internal:80", "/inventory.v1.Stock/Get", b'{"sku": "A1"}', {"X-Request-Id": "r1"}, 2.5
+    )
+    assert response.status_code == 404
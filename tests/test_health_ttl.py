@@ -0,0 +1,51 @@
+"""Tests for core/health_ttl.py (cache.health_ttl)."""
+import pytest
+from pydantic import ValidationError
+
+from reliapi.config.schema import CacheConfig
+from reliapi.core.circuit_breaker import circuit_breakers
+from reliapi.core.health_ttl import health_ttl_reason, health_ttl_status, stale_ttl_s
+
+
+@pytest.fixture(autouse=True)
+def reset_breakers():
+    circuit_breakers.reset()
+    yield
+    circuit_breakers.reset()
+
+
+def _target(triggers=None):
+    health_ttl = {"factor": 4.0, "min_failures": 2}
+    if triggers:
+        health_ttl["triggers"] = triggers
+    cache = CacheConfig(ttl_s=100, health_ttl=health_ttl).model_dump()
+    return {"base_url": "https://api.example.com/", "circuit": {"error_threshold": 3}, "cache": cache}
+
+
+def test_trigger_follows_circuit_state():
+    """Test the extension applies while the breaker is open, and failures count only when configured."""
+    target = _target()
+    breaker = circuit_breakers.get("api", target["circuit"])
+    assert health_ttl_reason("api", target) is None
+    for _ in range(2):
+        breaker.record_failure("https://api.example.com")
+    # Closed with failures: only elevated_errors reacts to that
+    assert health_ttl_reason("api", target) is None
+    assert health_ttl_reason("api", _target(["elevated_errors"])) == "elevated_errors"
+
+    breaker.record_failure("https://api.example.com")
+    assert health_ttl_reason("api", target) == "circuit_open"
+    assert health_ttl_status({"api": target, "other": {"base_url": "https://other"}}) == {
+        "api": {"reason": "circuit_open", "factor": 4.0}
+    }
+    assert health_ttl_reason("api", {**target, "cache": {}}) is None
+
+
+def test_stale_ttl_and_config():
+    """Test entries are kept (factor - 1) * ttl longer, and ttl_on_hit extend is rejected."""
+    assert stale_ttl_s(_target()["cache"], 100) == 300
+    assert stale_ttl_s({}, 100) == 0
+    with pytest.raises(ValidationError):
+        CacheConfig(health_ttl={"factor": 1.0})
+    with pytest.raises(ValidationError):
+        CacheConfig(ttl_on_hit="extend", max_ttl_s=7200, health_ttl={})
@@ -0,0 +1,60 @@
+"""Tests for core/hedging.py."""
+import asyncio
+
+import pytest
+
+from reliapi.app.services import _hedging_config
+from reliapi.core.hedging import HedgeTracker, hedged_call
+
+
+def test_hedge_delay_and_cost_guard():
+    """Test the delay follows the latency percentile and hedges are capped by max_hedge_ratio."""
+    tracker = HedgeTracker()
+    config = {"delay_percentile": 90, "min_samples": 10, "initial_delay_ms": 300, "max_hedge_ratio": 0.2}
+    assert tracker.delay_s("api", config) == 0.3
+    for latency_ms in range(10, 110, 10):
+        tracker.observe("api", latency_ms / 1000)
+    assert tracker.delay_s("api", config) == 0.09
+
+    claimed = []
+    for _ in range(10):
+        tracker.record_call("api")
+        claimed.append(tracker.try_hedge("api", config))
+    assert claimed.count(True) == 2
+
+
+def test_only_safe_requests_are_hedged():
+    """Test hedging applies to GET/HEAD and keyed requests, and the request flag wins."""
+    target = {"hedging": {"enabled": False, "max_hedge_ratio": 0.5}}
+    assert _hedging_config(target, None, "GET", None) is None
+    assert _hedging_config(target, True, "GET", None) == target["hedging"]
+    assert _hedging_config({}, True, "POST", "order-1") == {}
+    assert _hedging_config({"hedging": {}}, None, "POST", None) is None
+    assert _hedging_config({"hedging": {}}, False, "GET", None) is None
+
+
+@pytest.mark.asyncio
+async def test_hedged_call_takes_first_success_and_cancels_loser():
+    """Test a slow first attempt is hedged, the faster hedge wins and the first is cancelled."""
+    delays = [10.0, 0.01]
+    cancelled = []
+
+    async def call():
+        delay = delays.pop(0)
+        try:
+            await asyncio.sleep(delay)
+        except asyncio.CancelledError:
+            cancelled.append(delay)
+            raise
+        return delay
+
+    result, meta = await hedged_call(call, 0.01, lambda: True)
+    await asyncio.sleep(0)
+    assert (result, meta) == (0.01, {"hedged": True, "hedge_winner": 2})
+    assert cancelled == [10.0]
+
+    # Fast calls and a spent cost guard are not hedged
+    delays[:] = [0.0]
+    assert await hedged_call(call, 1.0, lambda: True) == (0.0, {})
+    delays[:] = [0.05]
+    assert await hedged_call(call, 0.01, lambda: False) == (0.05, {})
@@ -0,0 +1,564 @@
+"""Tests for app/services.py handle_http_proxy."""
+import asyncio
+import json
+import time
+
+import httpx
+import pytest
+from unittest.mock import AsyncMock, Mock, patch
+
+from pydantic import ValidationError
+
+from reliapi.app.routes.proxy import http_cache_headers
+from reliapi.app.services import _store_http_cache, handle_http_proxy
+from reliapi.app.schemas import MetaResponse, SuccessResponse, ErrorResponse
+from reliapi.config.schema import CacheConfig, ResponseTransformRule
+from reliapi.core.cache import Cache
+from reliapi.core.http_client import UpstreamHTTPClient, outbound_headers
+from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.response_schema import validate_response_body
+
+
+@pytest.fixture
+def mock_targets():
+    """Mock targets configuration with negative caching enabled."""
+    return {
+        "my_api": {
+            "base_url": "https://api.example.com",
+            "timeout_ms": 10000,
+            "cache": {"enabled": True, "ttl_s": 300, "negative_statuses": [404, 503], "negative_ttl_s": 15},
+        }
+    }
+
+
+@pytest.fixture
+def mock_cache():
+    """Mock cache."""
+    cache = Mock(spec=Cache)
+    cache.enabled = True
+    cache.get.return_value = None
+    cache.set.return_value = None
+    return cache
+
+
+@pytest.fixture
+def mock_idempotency():
+    """Mock idempotency manager."""
+    manager = Mock(spec=IdempotencyManager)
+    manager.enabled = True
+    return manager
+
+
+def test_store_http_cache_negative_ttl(mock_targets, mock_cache):
+    """Test configured error statuses are cached with the negative TTL."""
+    cache_config = mock_targets["my_api"]["cache"]
+    result = {"status_code": 404, "headers": {}, "body": {"error": "not found"}}
+
+    _store_http_cache(mock_cache, cache_config, "GET", "https://api.example.com/x", None, None, None,
+                      result, None, None)
+
+    args, kwargs = mock_cache.set.call_args
+    assert args[4]["cached_error"] is True
+    assert kwargs["ttl_s"] == 15
+
+
+def test_store_http_cache_skips_unlisted_errors(mock_cache):
+    """Test negative caching is off by default."""
+    result = {"status_code": 404, "headers": {}, "body": {}}
+    _store_http_cache(mock_cache, {"enabled": True, "ttl_s": 300}, "GET", "https://api.example.com/x",
+                      None, None, None, result, None, None)
+    mock_cache.set.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_negative_cache_stores_error_body(mock_targets, mock_cache, mock_idempotency):
+    """Test a cached 5xx keeps the upstream error body, and bodies over the cap are not cached."""
+    upstream = Mock(status_code=503, headers={}, content=b'{"error": "overloaded"}')
+
+    async def failing_request(**kwargs):
+        raise httpx.HTTPStatusError("Server error: 503", request=Mock(), response=upstream)
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = failing_request
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/flaky", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert mock_cache.set.call_args[0][4]["body"] == {"error": "overloaded"}
+
+    mock_cache.set.reset_mock()
+    cache_config = {**mock_targets["my_api"]["cache"], "negative_max_body_bytes": 16}
+    _store_http_cache(mock_cache, cache_config, "GET", "https://api.example.com/x", None, None, None,
+                      {"status_code": 404, "headers": {}, "body": {"error": "x" * 32}}, None, None)
+    mock_cache.set.assert_not_called()
+
+
+def test_store_http_cache_respects_upstream_cache_control(mock_cache):
+    """Test max-age sets the TTL, no-store/private skip caching and a request-level TTL wins."""
+    cache_config = {"enabled": True, "ttl_s": 300, "respect_upstream_cache_control": True}
+
+    def store(cache_control, cache_ttl=None):
+        mock_cache.set.reset_mock()
+        result = {"status_code": 200, "headers": {"Cache-Control": cache_control}, "body": {}}
+        _store_http_cache(mock_cache, cache_config, "GET", "https://api.example.com/x", None, None, None,
+                          result, cache_ttl, None)
+        return mock_cache.set.call_args.kwargs["ttl_s"] if mock_cache.set.called else None
+
+    assert store("public, max-age=120") == 120
+    assert store("max-age=120, s-maxage=600") == 600
+    assert store("no-store") is None
+    assert store("private, max-age=60") is None
+    assert store("max-age=0") is None
+    assert store("must-revalidate") == 300
+    assert store("no-store", cache_ttl=30) == 30
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_negative_cache_hit(mock_targets, mock_cache, mock_idempotency):
+    """Test cached 404 is served from cache and flagged as cached_error."""
+    mock_cache.get.return_value = {"status_code": 404, "headers": {}, "body": {}, "cached_error": True}
+
+    result = await handle_http_proxy(
+        target_name="my_api", method="GET", path="/missing", headers=None, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+        idempotency=mock_idempotency, request_id="test-123",
+    )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.data["status_code"] == 404
+    assert result.meta.cache_hit is True
+    assert result.meta.cached_error is True
+    assert result.meta.upstream_ms == 0
+    assert result.meta.overhead_ms == result.meta.duration_ms
+
+
+@pytest.mark.asyncio
+async def test_keyed_http_request_not_resent_with_another_pool_key_after_504(mock_targets, mock_cache, mock_idempotency):
+    """Test a keyed request is neither retried nor switched to another pool key after a 504."""
+    mock_targets["my_api"]["cache"]["enabled"] = False
+    mock_idempotency.register_request.return_value = (True, None, None)
+    mock_idempotency.get_result.return_value = None
+    mock_idempotency.is_in_progress.return_value = False
+    timeout = Mock(status_code=504, headers={}, content=b"")
+    client = Mock(close=AsyncMock())
+    client.request = AsyncMock(
+        side_effect=httpx.HTTPStatusError("Gateway timeout: 504", request=Mock(), response=timeout)
+    )
+    key_pool = Mock()
+    key_pool.has_pool.return_value = True
+    key_pool.select_key.return_value = Mock(id="key-2", key="sk-2", provider="openai")
+
+    with patch("reliapi.app.services.create_http_client",
+               return_value=(client, Mock(id="key-1", provider="openai", qps_limit=None, status="active"), "pool")):
+        result = await handle_http_proxy(
+            target_name="my_api", method="POST", path="/orders", headers=None, query=None, body='{"sku": 1}',
+            idempotency_key="order-1", cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123", key_pool_manager=key_pool,
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.status_code == 504
+    client.request.assert_awaited_once()
+    assert client.request.call_args.kwargs["no_retry_statuses"] == (504,)
+    key_pool.select_key.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_negative_cache_hit_server_error(mock_targets, mock_cache, mock_idempotency):
+    """Test cached 5xx is returned as an error response, and counted as one."""
+    mock_cache.get.return_value = {"status_code": 503, "headers": {}, "body": {}, "cached_error": True}
+
+    with patch("reliapi.app.services.http_requests_total") as requests_total:
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/flaky", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.status_code == 503
+    assert result.meta.cached_error is True
+    requests_total.labels.assert_called_once_with(target="my_api", status="error")
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_cache_hit_age_and_headers(mock_targets, mock_cache, mock_idempotency):
+    """Test cache hits report their age (upstream Age included) and HIT/STALE against max-age."""
+    mock_cache.get.return_value = {
+        "status_code": 200,
+        "headers": {"Date": "Tue, 13 Oct 2026 10:00:00 GMT", "Age": "5", "Cache-Control": "max-age=60"},
+        "body": {"ok": True},
+        "cached_at": time.time() - 30,
+    }
+
+    result = await handle_http_proxy(
+        target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+        idempotency=mock_idempotency, request_id="test-123",
+    )
+
+    assert result.meta.cache_age_s in (35, 36)
+    assert result.meta.cache_stale is None
+    assert "cached_at" not in result.data
+    headers = http_cache_headers(result)
+    assert headers["X-ReliAPI-Cache"] == "HIT"
+    assert headers["Age"] == str(result.meta.cache_age_s)
+    assert headers["Date"] == "Tue, 13 Oct 2026 10:00:00 GMT"
+
+    mock_cache.get.return_value["cached_at"] = time.time() - 120
+    stale = await handle_http_proxy(
+        target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+        idempotency=mock_idempotency, request_id="test-124",
+    )
+    assert http_cache_headers(stale)["X-ReliAPI-Cache"] == "STALE"
+
+    live = SuccessResponse(
+        success=True,
+        data={"status_code": 200, "headers": {"date": "Wed, 14 Oct 2026 10:00:00 GMT"}, "body": {}},
+        meta=MetaResponse(cache_hit=False, retries=0, duration_ms=1, request_id="test-125"),
+    )
+    assert http_cache_headers(live) == {
+        "X-ReliAPI-Cache": "MISS", "Age": "0", "Date": "Wed, 14 Oct 2026 10:00:00 GMT",
+    }
+
+
+def test_negative_statuses_validation():
+    """Test only error statuses can be negatively cached."""
+    assert CacheConfig(negative_statuses=[404]).negative_ttl_s == 30
+    with pytest.raises(ValidationError):
+        CacheConfig(negative_statuses=[200])
+
+
+ITEMS_SCHEMA = {
+    "type": "object",
+    "required": ["items"],
+    "properties": {"items": {"type": "array", "items": {"type": "object", "required": ["id"]}}},
+}
+
+
+def test_validate_response_body_reports_path():
+    """Test violations report the JSON path of the failing value."""
+    violation = validate_response_body(ITEMS_SCHEMA, b'{"items": [{"id": 1}, {"name": "x"}]}')
+
+    assert violation.path == "$.items[1]"
+    assert validate_response_body(ITEMS_SCHEMA, b'{"items": []}') is None
+    assert validate_response_body(ITEMS_SCHEMA, b"not json").path == "$"
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_schema_violation(mock_targets, mock_cache, mock_idempotency):
+    """Test a response failing response_schema becomes a 502 SCHEMA_VIOLATION and is not cached."""
+    mock_targets["my_api"]["response_schema"] = {"json_schema": ITEMS_SCHEMA, "on_violation": "error"}
+    upstream = Mock(status_code=200, headers={"content-type": "application/json"})
+    upstream.aread = AsyncMock(return_value=b'{"items": [{"name": "x"}]}')
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "SCHEMA_VIOLATION"
+    assert result.error.status_code == 502
+    assert result.error.retryable is False
+    assert result.error.details == {"path": "$.items[0]"}
+    mock_cache.set.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_latency_breakdown(mock_targets, mock_cache, mock_idempotency):
+    """Test provider time is reported as upstream_ms and the parts add up to duration_ms."""
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=b'{"ok": true}')
+
+    async def slow_request(**kwargs):
+        await asyncio.sleep(0.05)
+        return upstream
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = slow_request
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    meta = result.meta
+    assert meta.upstream_ms >= 50
+    assert meta.queue_ms == 0
+    assert meta.upstream_ms + meta.queue_ms + meta.overhead_ms == meta.duration_ms
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_response_transform(mock_targets, mock_cache, mock_idempotency):
+    """Test response_transform rewrites the body before it is cached and returned."""
+    mock_targets["my_api"]["response_transform"] = [
+        {"op": "replace", "field": "items[].id", "pattern": "^usr_", "replacement": ""},
+        {"op": "rename", "field": "items[].fullName", "to": "name"},
+        {"op": "drop", "field": "debug"},
+        {"op": "default", "field": "next_page", "value": 1},
+    ]
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=b'{"items": [{"id": "usr_7", "fullName": "Ada"}], "debug": {"t": 3}}')
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/users", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    expected = {"items": [{"id": "7", "name": "Ada"}], "next_page": 1}
+    assert result.data["body"] == expected
+    assert mock_cache.set.call_args[0][4]["body"] == expected
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_oversized_response_not_cached(mock_targets, mock_idempotency, mock_redis):
+    """Test a response over max_cache_value_bytes is served but skipped for caching."""
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=('{"blob": "' + "x" * 500 + '"}').encode())
+
+    with patch("reliapi.core.cache.redis") as redis_module, \
+            patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        redis_module.from_url.return_value = mock_redis
+        cache = Cache("redis://localhost:6379/0", max_value_bytes=256)
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/export", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    assert result.success is True
+    assert len(result.data["body"]["blob"]) == 500
+    assert result.meta.cache_skipped_too_large is True
+    assert result.meta.cache_ttl_s is None
+    mock_redis.setex.assert_not_called()
+
+
+def test_response_transform_validation():
+    """Test transform rules are checked when the config is loaded."""
+    assert ResponseTransformRule(op="drop", field="items[].debug").field == "items[].debug"
+    with pytest.raises(ValidationError):
+        ResponseTransformRule(op="rename", field="id")
+    with pytest.raises(ValidationError):
+        ResponseTransformRule(op="replace", field="id", pattern="(")
+    with pytest.raises(ValidationError):
+        ResponseTransformRule(op="drop", field="items..id")
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_range_request_bypasses_cache(mock_targets, mock_cache, mock_idempotency):
+    """Test Range requests are forwarded, and neither read from nor written to the cache."""
+    upstream = Mock(status_code=206, headers={"Content-Range": "bytes 0-3/10", "Accept-Ranges": "bytes"})
+    upstream.aread = AsyncMock(return_value=b"\x89PNG")
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="GET", path="/files/logo.png", headers={"Range": "bytes=0-3"},
+            query=None, body=None, idempotency_key=None, cache_ttl=None, targets=mock_targets,
+            cache=mock_cache, idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    assert client_cls.return_value.request.call_args.kwargs["headers"] == {"Range": "bytes=0-3"}
+    assert result.data["status_code"] == 206
+    assert result.data["headers"]["Content-Range"] == "bytes 0-3/10"
+    assert result.data["body"] == {"raw_base64": "iVBORw=="}
+    mock_cache.get.assert_not_called()
+    mock_cache.set.assert_not_called()
+
+
+def test_request_id_from_header_or_generated():
+    """Test a valid X-Request-Id is used and sent upstream, a missing one is generated and a malformed one is a 400."""
+    from fastapi import HTTPException
+
+    from reliapi.app.dependencies import resolve_request_id
+
+    assert resolve_request_id(Mock(headers={"X-Request-Id": "order-42:retry.1"})) == "order-42:retry.1"
+    assert resolve_request_id(Mock(headers={})).startswith("req_")
+    for bad in ("", "has space", "x" * 129, "new\nline"):
+        with pytest.raises(HTTPException) as exc_info:
+            resolve_request_id(Mock(headers={"X-Request-Id": bad}))
+        assert exc_info.value.status_code == 400
+    assert outbound_headers({}, request_id="order-42")["X-Request-Id"] == "order-42"
+
+
+def test_outbound_headers_defaults_and_overrides():
+    """Test target user_agent/default_headers apply, client IP is opt-in, and request headers win."""
+    target_config = {
+        "user_agent": "acme-bot/2.0",
+        "default_headers": {"X-Api-Version": "2024-06-01"},
+        "forward_client_ip": True,
+    }
+    assert outbound_headers({})["User-Agent"] == "ReliAPI"
+    assert "X-Forwarded-For" not in outbound_headers({}, client_ip="203.0.113.7")
+
+    client = UpstreamHTTPClient(
+        base_url="https://api.example.com",
+        default_headers=outbound_headers(target_config, client_ip="203.0.113.7"),
+    )
+    assert client._prepare_headers({"x-api-version": "2025-01-01"}) == {
+        "User-Agent": "acme-bot/2.0",
+        "X-Forwarded-For": "203.0.113.7",
+        "x-api-version": "2025-01-01",
+    }
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_embeddings_partial_cache_hit(mock_targets, mock_cache, mock_idempotency):
+    """Test only uncached embedding inputs are sent upstream and results are merged in input order."""
+    mock_targets["my_api"]["cache"]["embeddings"] = True
+    entries = {}
+    mock_cache.get.side_effect = lambda method, url, headers, body, query, **kwargs: entries.get(body)
+    mock_cache.set.side_effect = lambda method, url, headers, body, value, **kwargs: entries.update({body: value})
+    call = dict(
+        target_name="my_api", method="POST", path="/v1/embeddings", headers=None, query=None,
+        idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+        idempotency=mock_idempotency, request_id="test-123",
+    )
+    first = Mock(status_code=200, headers={})
+    first.aread = AsyncMock(return_value=(
+        b'{"object": "list", "model": "emb", "usage": {"prompt_tokens": 2, "total_tokens": 2},'
+        b' "data": [{"index": 0, "embedding": [0.1]}, {"index": 1, "embedding": [0.2]}]}'
+    ))
+    second = Mock(status_code=200, headers={})
+    second.aread = AsyncMock(return_value=(
+        b'{"object": "list", "model": "emb", "usage": {"prompt_tokens": 1, "total_tokens": 1},'
+        b' "data": [{"index": 0, "embedding": [0.3]}]}'
+    ))
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(side_effect=[first, second])
+        client_cls.return_value.close = AsyncMock()
+        await handle_http_proxy(body='{"model": "emb", "input": ["a", "b"]}', **call)
+        result = await handle_http_proxy(body='{"model": "emb", "input": ["b", "c", "a"]}', **call)
+        sent = json.loads(client_cls.return_value.request.call_args.kwargs["body"])
+        cached = await handle_http_proxy(body='{"model": "emb", "input": ["c", "a"]}', **call)
+
+    assert sent == {"model": "emb", "input": ["c"]}
+    assert result.data["body"]["data"] == [
+        {"index": 0, "embedding": [0.2]}, {"index": 1, "embedding": [0.3]}, {"index": 2, "embedding": [0.1]},
+    ]
+    assert result.data["body"]["usage"]["prompt_tokens"] == 1
+    assert (result.meta.cache_hits, result.meta.cache_misses, result.meta.cache_hit) == (2, 1, False)
+
+    assert client_cls.return_value.request.call_count == 2
+    assert cached.meta.cache_hit is True
+    assert (cached.meta.cache_hits, cached.meta.cache_misses) == (2, 0)
+    assert [item["embedding"] for item in cached.data["body"]["data"]] == [[0.3], [0.1]]
+    assert cached.data["body"]["usage"]["total_tokens"] == 0
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_embeddings_mismatch_reports_cost(mock_targets, mock_cache, mock_idempotency):
+    """Test an embeddings response missing inputs is a 502 EMBEDDINGS_MISMATCH carrying the paid usage."""
+    mock_targets["my_api"]["cache"]["embeddings"] = True
+    mock_cache.get.return_value = None
+    mock_idempotency.get_result.return_value = None
+    mock_idempotency.register_request.return_value = (True, None, None)
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=(
+        b'{"object": "list", "model": "text-embedding-3-small", "usage": {"prompt_tokens": 1000, "total_tokens": 1000},'
+        b' "data": [{"index": 0, "embedding": [0.1]}]}'
+    ))
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="my_api", method="POST", path="/v1/embeddings", headers=None, query=None,
+            body='{"model": "text-embedding-3-small", "input": ["a", "b"]}', idempotency_key="emb-1",
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-123",
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "EMBEDDINGS_MISMATCH"
+    assert (result.error.status_code, result.error.retryable) == (502, False)
+    assert result.error.details["usage"]["prompt_tokens"] == 1000
+    assert result.error.details["cost_usd"] == pytest.approx(0.00002)
+    assert result.meta.cost_usd == pytest.approx(0.00002)
+    mock_cache.set.assert_not_called()
+    mock_idempotency.clear_in_progress.assert_called_once()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_vary_headers_cache_separately(mock_targets, mock_idempotency):
+    """Test cache.vary_headers gives each header value its own cache entry."""
+    mock_targets["my_api"]["cache"]["vary_headers"] = ["X-Region"]
+    with patch("reliapi.core.cache.redis") as redis_module:
+        redis_module.from_url.side_effect = Exception("Connection failed")
+        cache = Cache("redis://invalid:6379/0", memory={"enabled": True})
+
+    def upstream(region):
+        response = Mock(status_code=200, headers={})
+        response.aread = AsyncMock(return_value=json.dumps({"region": region}).encode())
+        return response
+
+    async def get(region):
+        return await handle_http_proxy(
+            target_name="my_api", method="GET", path="/prices", headers={"x-region": region}, query=None,
+            body=None, idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=cache,
+            idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(side_effect=[upstream("eu"), upstream("us")])
+        client_cls.return_value.close = AsyncMock()
+        eu, us, eu_again = await get("eu"), await get("us"), await get("eu")
+
+    assert (eu.meta.cache_hit, us.meta.cache_hit, eu_again.meta.cache_hit) == (False, False, True)
+    assert us.data["body"] == {"region": "us"}
+    assert eu_again.data["body"] == {"region": "eu"}
+    # The header is still forwarded upstream
+    assert client_cls.return_value.request.call_args.kwargs["headers"]["x-region"] == "us"
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_post_not_cached_unless_opted_in(mock_targets, mock_idempotency):
+    """Test POST is never cached by default, and cached only with cache.methods and a request TTL."""
+    with patch("reliapi.core.cache.redis") as redis_module:
+        redis_module.from_url.side_effect = Exception("Connection failed")
+        cache = Cache("redis://invalid:6379/0", memory={"enabled": True})
+
+    def upstream():
+        response = Mock(status_code=200, headers={})
+        response.aread = AsyncMock(return_value=b'{"hits": 3}')
+        return response
+
+    async def post(cache_ttl):
+        return await handle_http_proxy(
+            target_name="my_api", method="POST", path="/search", headers=None, query=None,
+            body='{"q": "shoes"}', idempotency_key=None, cache_ttl=cache_ttl, targets=mock_targets,
+            cache=cache, idempotency=mock_idempotency, request_id="test-123",
+        )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(side_effect=lambda **kwargs: upstream())
+        client_cls.return_value.close = AsyncMock()
+
+        # Default: a request TTL does not make a POST cacheable
+        assert [(await post(60)).meta.cache_hit for _ in range(2)] == [False, False]
+
+        # Opted in: only requests that set their own TTL are cached
+        mock_targets["my_api"]["cache"]["methods"] = ["GET", "HEAD", "POST"]
+        assert [(await post(None)).meta.cache_hit for _ in range(2)] == [False, False]
+        assert [(await post(60)).meta.cache_hit for _ in range(2)] == [False, True]
+
+    assert client_cls.return_value.request.await_count == 5
@@ -0,0 +1,158 @@
+"""Tests for the Idempotency-Key header (app/dependencies.py resolve_idempotency_key)."""
+import json
+from unittest.mock import Mock, patch
+
+import pytest
+from fastapi import HTTPException
+
+from reliapi.app.dependencies import resolve_idempotency_key
+from reliapi.app.schemas import SuccessResponse
+from reliapi.app.services import handle_llm_proxy
+from reliapi.core.cache import Cache
+from reliapi.core.idempotency import IdempotencyManager
+
+
+def _http_request(headers):
+    request = Mock()
+    request.headers = headers
+    return request
+
+
+def test_header_used_when_body_field_missing():
+    """Test the Idempotency-Key header is used when the body has no key."""
+    assert resolve_idempotency_key(None, _http_request({"Idempotency-Key": "idem-hdr"})) == "idem-hdr"
+    assert resolve_idempotency_key(None, _http_request({})) is None
+
+
+def _scoped_key(header_key, api_key, scope):
+    state = Mock()
+    state.config_loader.get_idempotency_scope.return_value = scope
+    state.config_loader.get_idempotency_keys.return_value = {}
+    with patch("reliapi.app.dependencies.get_app_state", return_value=state):
+        return resolve_idempotency_key(None, _http_request({"Idempotency-Key": header_key}), api_key)
+
+
+def test_per_key_scope_does_not_replay_across_api_keys():
+    """Test two API keys sending the same idempotency key get separate records in per_key mode."""
+    store = {}
+
+    def setex(key, ttl_s, value):
+        store[key] = value
+
+    manager = object.__new__(IdempotencyManager)
+    manager.key_prefix = "reliapi"
+    manager.enabled = True
+    manager.client = Mock()
+    manager.client.get.side_effect = store.get
+    manager.client.setex.side_effect = setex
+
+    key_a = _scoped_key("order-1", "sk-alice", "per_key")
+    key_b = _scoped_key("order-1", "sk-bob", "per_key")
+    manager.store_result(key_a, {"data": {"content": "alice's answer"}})
+
+    assert key_a != key_b
+    assert manager.get_result(key_b) is None
+    assert manager.get_result(key_a)["data"]["content"] == "alice's answer"
+    # global scope shares one namespace across keys
+    assert _scoped_key("order-1", "sk-alice", "global") == _scoped_key("order-1", "sk-bob", "global") == "order-1"
+
+
+@pytest.mark.parametrize("rules,key,valid", [
+    ({}, "a", True),
+    ({}, "k" * 257, False),
+    ({"min_length": 16}, "order-1", False),
+    ({"pattern": "[A-Za-z0-9_-]+"}, "order 1", False),
+    ({"pattern": "[A-Za-z0-9_-]+"}, "order_1", True),
+    ({"require_uuid": True}, "order-1", False),
+    ({"require_uuid": True}, "3f2b8c1e-9d4a-4e7b-8a2f-6c1d0e9b7a55", True),
+])
+def test_idempotency_key_rules(rules, key, valid):
+    """Test keys failing idempotency_keys rules are rejected with 400 INVALID_IDEMPOTENCY_KEY."""
+    state = Mock()
+    state.config_loader.get_idempotency_keys.return_value = rules
+    with patch("reliapi.app.dependencies.get_app_state", return_value=state):
+        if valid:
+            assert resolve_idempotency_key(key, _http_request({})) == key
+            return
+        with pytest.raises(HTTPException) as exc_info:
+            resolve_idempotency_key(key, _http_request({}))
+    assert exc_info.value.status_code == 400
+    assert exc_info.value.detail["code"] == "INVALID_IDEMPOTENCY_KEY"
+
+
+def test_body_field_takes_precedence():
+    """Test the body field wins over the header."""
+    request = _http_request({"Idempotency-Key": "idem-hdr"})
+    assert resolve_idempotency_key("idem-body", request) == "idem-body"
+
+
+@pytest.mark.asyncio
+async def test_header_key_idempotent_replay():
+    """Test a repeated request with the same Idempotency-Key header replays the stored result."""
+    idempotency_key = resolve_idempotency_key(None, _http_request({"Idempotency-Key": "idem-hdr"}))
+
+    cache = Mock(spec=Cache)
+    cache.get.return_value = None
+    idempotency = Mock(spec=IdempotencyManager)
+    idempotency.register_request.return_value = (False, "req_first", "hash-1")
+    idempotency.make_request_hash.return_value = "hash-1"
+    idempotency.get_result.return_value = {
+        "data": {"content": "Hello", "role": "assistant", "finish_reason": "stop"},
+        "cost_usd": 0.0001,
+    }
+
+    result = await handle_llm_proxy(
+        target_name="openai",
+        messages=[{"role": "user", "content": "Hello"}],
+        model=None,
+        max_tokens=None,
+        temperature=None,
+        top_p=None,
+        stop=None,
+        stream=False,
+        idempotency_key=idempotency_key,
+        cache_ttl=None,
+        targets={
+            "openai": {
+                "base_url": "https://api.openai.com/v1",
+                "llm": {"provider": "openai", "default_model": "gpt-4o-mini"},
+            }
+        },
+        cache=cache,
+        idempotency=idempotency,
+        request_id="req_second",
+        tenant=None,
+    )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.idempotent_hit is True
+    assert result.data["content"] == "Hello"
+    assert idempotency.register_request.call_args[0][0] == "idem-hdr"
+
+
+@pytest.mark.asyncio
+async def test_previous_version_hash_replays_instead_of_conflict():
+    """Test a key registered with the pre-canonical-JSON hash replays instead of returning 409."""
+    cache = Mock(spec=Cache)
+    cache.get.return_value = None
+    idempotency = Mock(spec=IdempotencyManager)
+    idempotency.register_request.return_value = (False, "req_first", "hash-old")
+    idempotency.make_request_hash.return_value = "hash-new"
+    idempotency.make_legacy_request_hash.return_value = "hash-old"
+    idempotency.get_result.return_value = {"data": {"content": "Hello"}, "cost_usd": 0.0001}
+
+    result = await handle_llm_proxy(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None, max_tokens=None,
+        temperature=None, top_p=None, stop=None, stream=False, idempotency_key="idem-upgrade", cache_ttl=None,
+        targets={"openai": {"base_url": "https://api.openai.com/v1",
+                            "llm": {"provider": "openai", "default_model": "gpt-4o-mini"}}},
+        cache=cache, idempotency=idempotency, request_id="req_second", tenant=None,
+    )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.idempotent_hit is True
+    # Hashed over the previous json.dumps(sort_keys=True) body, not the canonical one
+    legacy_body = idempotency.make_legacy_request_hash.call_args[0][3]
+    assert legacy_body == json.dumps(json.loads(legacy_body), sort_keys=True).encode()
+    assert cache.get.call_args.kwargs["legacy_body"] == legacy_body
+
@@ -0,0 +1,26 @@
+"""Tests for core/json_repair.py."""
+import json
+
+from reliapi.core.json_repair import is_json_mode, repair_json
+
+
+def test_repairs_fences_and_trailing_commas():
+    """Test markdown fences and trailing commas are fixed."""
+    text = '```json\n{"items": [1, 2,], "name": "a,}",}\n```'
+
+    repaired = repair_json(text)
+
+    assert json.loads(repaired) == {"items": [1, 2], "name": "a,}"}
+
+
+def test_extracts_json_from_prose():
+    """Test prose around the JSON value is dropped."""
+    assert json.loads(repair_json('Here you go: {"ok": true} Hope this helps!')) == {"ok": True}
+
+
+def test_valid_or_unrepairable_returns_none():
+    """Test valid JSON is left alone and hopeless output is not guessed at."""
+    assert repair_json('{"ok": true}') is None
+    assert repair_json("I cannot answer that.") is None
+    assert is_json_mode({"type": "json_object"}) is True
+    assert is_json_mode({"type": "text"}) is False
@@ -0,0 +1,97 @@
+"""Tests for core/kill_switch.py and disabled-target handling in app/services.py."""
+import os
+
+import pytest
+from unittest.mock import Mock, patch
+
+from reliapi.app.dependencies import AppState
+from reliapi.app.routes.health import health_check
+from reliapi.app.schemas import ErrorResponse, SuccessResponse
+from reliapi.app.services import handle_http_proxy
+from reliapi.core.cache import Cache
+from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.kill_switch import KillSwitch
+
+TARGETS = {
+    "my_api": {"base_url": "https://api.example.com", "timeout_ms": 10000, "cache": {"enabled": True, "ttl_s": 300}},
+}
+CACHED = {"status_code": 200, "headers": {}, "body": {"items": []}}
+
+
+def _proxy_get(cache, targets=TARGETS):
+    idempotency = Mock(spec=IdempotencyManager)
+    return handle_http_proxy(
+        target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=targets, cache=cache,
+        idempotency=idempotency, request_id="test-123",
+    )
+
+
+def test_disable_and_enable():
+    """Test targets are disabled independently and the reason is kept for /health."""
+    switch = KillSwitch()
+    switch.set("my_api", True, reason="bad deploy upstream")
+    assert switch.is_disabled("my_api") is True
+    assert switch.is_disabled("other") is False
+    assert switch.status()["my_api"]["reason"] == "bad deploy upstream"
+
+    switch.set("my_api", False)
+    assert switch.status() == {}
+
+
+@pytest.mark.asyncio
+async def test_disabled_target_rejected_before_cache():
+    """Test a disabled target returns 503 TARGET_DISABLED without reading the cache or calling upstream."""
+    cache = Mock(spec=Cache)
+    cache.get.return_value = CACHED
+    switch = KillSwitch()
+    switch.set("my_api", True)
+
+    with patch("reliapi.app.services.kill_switch", switch), \
+            patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        result = await _proxy_get(cache)
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "TARGET_DISABLED"
+    assert result.error.status_code == 503
+    cache.get.assert_not_called()
+    client_cls.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_disabled_target_serves_cache_when_configured():
+    """Test serve_cache_when_disabled serves hits and rejects misses."""
+    targets = {"my_api": {**TARGETS["my_api"], "serve_cache_when_disabled": True}}
+    cache = Mock(spec=Cache)
+    cache.get.return_value = CACHED
+    switch = KillSwitch()
+    switch.set("my_api", True)
+
+    with patch("reliapi.app.services.kill_switch", switch), \
+            patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        hit = await _proxy_get(cache, targets)
+        cache.get.return_value = None
+        miss = await _proxy_get(cache, targets)
+
+    assert isinstance(hit, SuccessResponse)
+    assert hit.meta.cache_hit is True
+    assert isinstance(miss, ErrorResponse)
+    assert miss.error.code == "TARGET_DISABLED"
+    client_cls.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_health_hides_disable_reason_without_admin_key():
+    """Test /health lists disabled targets, with the reason only for the admin key."""
+    switch = KillSwitch()
+    switch.set("my_api", True, reason="leaked key rotation")
+
+    with patch.dict(os.environ, {"RELIAPI_ADMIN_KEY": "admin-secret"}), \
+            patch("reliapi.app.routes.health.kill_switch", switch), \
+            patch("reliapi.app.routes.health.get_app_state", return_value=AppState()):
+        public = await health_check(Mock(headers={}))
+        admin = await health_check(Mock(headers={"X-Admin-Key": "admin-secret"}))
+
+    assert "reason" not in public.disabled_targets["my_api"]
+    assert public.maintenance is None
+    assert admin.disabled_targets["my_api"]["reason"] == "leaked key rotation"
@@ -0,0 +1,65 @@
+"""Tests for core/language_routing.py (llm.language_routing)."""
+from reliapi.core.language_routing import detect_language, select_language_route
+
+TARGETS = {
+    "openai": {
+        "base_url": "https://api.openai.com/v1",
+        "llm": {
+            "provider": "openai",
+            "default_model": "gpt-4o-mini",
+            "language_routing": {
+                "routes": {
+                    "ja": {"model": "gpt-4o"},
+                    "zh": {"target": "anthropic"},
+                    "ko": {"target": "missing", "model": "x"},
+                },
+                "min_chars": 10,
+            },
+        },
+    },
+    "anthropic": {
+        "base_url": "https://api.anthropic.com/v1",
+        "llm": {"provider": "anthropic", "default_model": "claude-3-haiku-20240307"},
+    },
+}
+
+
+def _user(content):
+    return [{"role": "system", "content": "You are a helpful assistant."}, {"role": "user", "content": content}]
+
+
+def test_detect_language_by_script_and_stopwords():
+    """Test scripts decide non-Latin languages, function words the Latin ones, short text is unknown."""
+    assert detect_language(_user("東京の天気はどうですか？明日は雨が降りますか")) == "ja"
+    assert detect_language(_user("请帮我总结一下这篇文章的主要内容")) == "zh"
+    assert detect_language(_user("이 문서를 한 문장으로 요약해 주세요")) == "ko"
+    assert detect_language(_user("Пожалуйста, кратко перескажи эту статью")) == "ru"
+    assert detect_language(_user("What is the capital of France and how big is it?")) == "en"
+    assert detect_language(_user("¿Cuál es la capital de Francia y por qué es famosa?")) == "es"
+    assert detect_language(_user("Wie ist das Wetter in Berlin und ist es kalt?")) == "de"
+    assert detect_language(_user("Hi")) is None
+    # Only user messages count; multimodal text parts are read
+    assert detect_language([{"role": "user", "content": [{"type": "text", "text": "この画像を説明してください"}]}]) == "ja"
+
+
+def test_route_switches_model_or_target():
+    """Test a route switches the model, the target, or keeps the default when unrouted or invalid."""
+    assert select_language_route("openai", TARGETS, _user("この記事を三行で要約してください")) == (
+        "openai", "gpt-4o", "ja"
+    )
+    assert select_language_route("openai", TARGETS, _user("请帮我总结一下这篇文章的主要内容")) == (
+        "anthropic", None, "zh"
+    )
+    assert select_language_route("openai", TARGETS, _user("What is the capital of France?")) == (
+        "openai", None, "en"
+    )
+    assert select_language_route("openai", TARGETS, _user("이 문서를 한 문장으로 요약해 주세요")) == (
+        "openai", None, "ko"
+    )
+
+
+def test_no_routing_config_detects_nothing():
+    """Test targets without language_routing are left alone."""
+    assert select_language_route("anthropic", TARGETS, _user("この記事を三行で要約してください")) == (
+        "anthropic", None, None
+    )
@@ -1,11 +1,25 @@
 """Tests for app/services.py handle_llm_proxy."""
+import asyncio
+import json
+
 import pytest
 from unittest.mock import Mock, AsyncMock, patch
 
-from reliapi.app.services import handle_llm_proxy
-from reliapi.app.schemas import SuccessResponse, ErrorResponse
+from reliapi.adapters.llm.anthropic import AnthropicAdapter
+from reliapi.app.services import (
+    StreamIdleTimeout,
+    _iter_with_idle_timeout,
+    _summary_cost_estimate,
+    collapse_llm_stream,
+    handle_llm_proxy,
+    handle_llm_stream_generator,
+    prime_llm_stream,
+)
+from reliapi.app.schemas import ErrorResponse, LLMProxyRequest, SuccessResponse
 from reliapi.core.cache import Cache
+from reliapi.core.cost_estimator import CostEstimator
 from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.model_limits import ModelRateLimiter
 
 
 @pytest.fixture
@@ -80,6 +94,462 @@ async def test_llm_proxy_budget_cap_rejection(mock_targets, mock_cache, mock_ide
         assert result.meta.cost_policy_applied == "hard_cap_rejected"
 
 
+@pytest.mark.asyncio
+async def test_llm_proxy_unknown_model_reject(mock_targets, mock_cache, mock_idempotency):
+    """Test on_unknown_model: reject returns 422 for unpriced models."""
+    mock_targets["openai"]["llm"]["on_unknown_model"] = "reject"
+
+    result = await handle_llm_proxy(
+        target_name="openai",
+        messages=[{"role": "user", "content": "Hello"}],
+        model="gpt-unpriced",
+        max_tokens=None,
+        temperature=None,
+        top_p=None,
+        stop=None,
+        stream=False,
+        idempotency_key=None,
+        cache_ttl=None,
+        targets=mock_targets,
+        cache=mock_cache,
+        idempotency=mock_idempotency,
+        request_id="test-123",
+        tenant=None,
+    )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "UNKNOWN_MODEL"
+    assert result.error.status_code == 422
+
+
+@pytest.mark.asyncio
+async def test_llm_proxy_output_cap(mock_targets, mock_cache, mock_idempotency):
+    """Test output_caps clamp max_tokens (after llm.max_tokens) or reject it per model."""
+    mock_targets["openai"]["llm"]["output_caps"] = {
+        "gpt-4o-mini": {"max_tokens": 300},
+        "*": {"max_tokens": 100, "on_exceed": "reject"},
+    }
+
+    async def call(model, max_tokens):
+        return await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=model,
+            max_tokens=max_tokens, temperature=None, top_p=None, stop=None, stream=False,
+            idempotency_key=None, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+            idempotency=mock_idempotency, request_id="test-123", tenant=None,
+        )
+
+    with patch("reliapi.app.services.CostEstimator") as mock_cost:
+        mock_cost.estimate_from_messages.return_value = 0.1  # Stop at the hard cap, after capping
+        await call("gpt-4o-mini", 5000)
+        clamped_to = mock_cost.estimate_from_messages.call_args[0][3]
+        rejected = await call("gpt-4o", 500)
+        allowed = await call("gpt-4o", 80)
+
+    assert clamped_to == 300
+    assert rejected.error.code == "OUTPUT_CAP_EXCEEDED"
+    assert rejected.error.status_code == 422
+    assert rejected.error.details == {"model": "gpt-4o", "max_tokens": 500, "output_cap_tokens": 100}
+    assert allowed.error.code == "BUDGET_EXCEEDED"
+    assert mock_cost.estimate_from_messages.call_args[0][3] == 80
+
+
+@pytest.mark.asyncio
+async def test_ttl_cost_policy_scales_cache_ttl_by_price(mock_targets, mock_cache, mock_idempotency):
+    """Test ttl_cost_policy stores pricier models longer, within bounds, and a request TTL wins."""
+    mock_targets["openai"]["llm"]["hard_cost_cap_usd"] = None
+    mock_targets["openai"]["cache"]["ttl_cost_policy"] = {
+        "reference_usd_per_1k": 0.01, "exponent": 0.5, "min_ttl_s": 300, "max_ttl_s": 7200,
+    }
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 10, "completion_tokens": 2},
+    }).encode())
+
+    async def stored_ttl(model, cache_ttl=None):
+        with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+            mock_client.return_value.request = AsyncMock(return_value=upstream)
+            mock_client.return_value.close = AsyncMock()
+            await handle_llm_proxy(
+                target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=model,
+                max_tokens=10, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+                cache_ttl=cache_ttl, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+                request_id="test-req-ttl",
+            )
+        return mock_cache.set.call_args.kwargs["ttl_s"]
+
+    assert await stored_ttl("gpt-4o") == 5091
+    assert await stored_ttl("gpt-4o-mini") == 985
+    assert await stored_ttl("gpt-4") == 7200  # 10800 clamped to max_ttl_s
+    assert await stored_ttl("gpt-unpriced") == 3600
+    assert await stored_ttl("gpt-4", cache_ttl=60) == 60
+
+
+@pytest.mark.asyncio
+async def test_llm_proxy_cost_multiplier(mock_targets, mock_cache, mock_idempotency):
+    """Test cost_multiplier scales meta cost and the estimate checked against caps."""
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 1000, "completion_tokens": 100},
+    }).encode())
+    call = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+        max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+        cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+        request_id="test-req-markup",
+    )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        billed = await handle_llm_proxy(**call, cost_multiplier=1.5)
+
+    actual = (1000 * 0.15 + 100 * 0.6) / 1_000_000
+    assert billed.meta.cost_usd == pytest.approx(actual * 1.5)
+    assert billed.meta.cost_breakdown.input_usd == pytest.approx(1000 * 0.15 / 1_000_000 * 1.5)
+
+    # Target caps apply to actual cost: 0.03 is under the 0.05 hard cap though 0.06 is billed
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client, \
+         patch("reliapi.app.services.CostEstimator") as mock_cost:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        mock_cost.estimate_from_messages.return_value = 0.03
+        admitted = await handle_llm_proxy(**call, cost_multiplier=2.0)
+        mock_cost.estimate_from_messages.return_value = 0.06
+        rejected = await handle_llm_proxy(**call, cost_multiplier=2.0)
+    assert admitted.success
+    assert rejected.error.code == "BUDGET_EXCEEDED"
+    # Reported billed: 0.12 estimate against a 0.10 cap
+    assert rejected.error.details["cost_estimate_usd"] == pytest.approx(0.12)
+    assert rejected.error.details["hard_cost_cap_usd"] == pytest.approx(0.10)
+
+
+@pytest.mark.asyncio
+async def test_content_filter_block_retried_on_fallback_model(mock_targets, mock_cache, mock_idempotency):
+    """Test a content-filtered response is retried once on on_content_filter_model, cost summed, not cached."""
+    mock_targets["openai"]["llm"]["on_content_filter_model"] = "gpt-4o"
+
+    def upstream(content, finish_reason):
+        response = Mock(status_code=200, headers={})
+        response.aread = AsyncMock(return_value=json.dumps({
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": content}, "finish_reason": finish_reason}],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 10},
+        }).encode())
+        return response
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(
+            side_effect=[upstream("", "content_filter"), upstream("Here you go", "stop")]
+        )
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key="idem-cf",
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-cf",
+        )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.data["content"] == "Here you go"
+    assert result.meta.model == "gpt-4o"
+    assert result.meta.content_filter_original_model == "gpt-4o-mini"
+    # Blocked gpt-4o-mini call plus the gpt-4o retry
+    assert result.meta.cost_usd > (100 * 0.15 + 10 * 0.6) / 1_000_000
+    assert result.meta.cost_breakdown.input_usd + result.meta.cost_breakdown.output_usd == pytest.approx(
+        result.meta.cost_usd
+    )
+    second_body = json.loads(mock_client.return_value.request.call_args_list[1].kwargs["body"])
+    assert second_body["model"] == "gpt-4o"
+    # The keyed request now replays the unblocked response
+    assert mock_idempotency.store_result.call_args[0][1]["data"]["content"] == "Here you go"
+    # The blocked output is not cached, only its replacement
+    assert [c.args[4]["body"]["content"] for c in mock_cache.set.call_args_list] == ["Here you go"]
+
+
+@pytest.mark.asyncio
+async def test_empty_completion_retried_with_nudge(mock_targets, mock_cache, mock_idempotency):
+    """Test keyed empty completions are retried with the nudge and not cached; unkeyed ones are returned."""
+    mock_targets["openai"]["llm"]["empty_response"] = {"max_retries": 2, "nudge": "Please answer."}
+
+    def upstream(content):
+        response = Mock(status_code=200, headers={})
+        response.aread = AsyncMock(return_value=json.dumps({
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": content}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 10},
+        }).encode())
+        return response
+
+    def call(idempotency_key):
+        return handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=idempotency_key,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-empty",
+        )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(side_effect=[upstream(""), upstream(" \n"), upstream("Hi!")])
+        mock_client.return_value.close = AsyncMock()
+        result = await call("idem-empty")
+
+    assert result.data["content"] == "Hi!"
+    assert result.meta.empty_retries == 2
+    retry_messages = json.loads(mock_client.return_value.request.call_args_list[1].kwargs["body"])["messages"]
+    assert retry_messages[-1] == {"role": "user", "content": "Please answer."}
+    assert mock_cache.set.call_count == 1
+    assert mock_idempotency.store_result.call_count == 1
+    assert mock_idempotency.store_result.call_args[0][1]["data"]["content"] == "Hi!"
+
+    # Still empty after every retry: nothing is stored under the key, which is released
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream(""))
+        mock_client.return_value.close = AsyncMock()
+        still_empty = await call("idem-still-empty")
+
+    assert still_empty.data["content"] == ""
+    assert still_empty.meta.empty_retries == 2
+    assert mock_idempotency.store_result.call_count == 1
+    mock_idempotency.clear_in_progress.assert_called_with("idem-still-empty", tenant=None)
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream(""))
+        mock_client.return_value.close = AsyncMock()
+        unkeyed = await call(None)
+
+    assert unkeyed.data["content"] == ""
+    assert unkeyed.meta.empty_retries is None
+    assert mock_client.return_value.request.call_count == 1
+
+
+@pytest.mark.asyncio
+async def test_json_schema_violation_retried_then_rejected(mock_targets, mock_cache, mock_idempotency):
+    """Test invalid outputs get corrective retries, only valid ones are cached/stored, exhaustion is 422."""
+    schema = {"type": "object", "required": ["city"], "properties": {"city": {"type": "string"}}}
+
+    def upstream(content):
+        response = Mock(status_code=200, headers={})
+        response.aread = AsyncMock(return_value=json.dumps({
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": content}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 10},
+        }).encode())
+        return response
+
+    def call(idempotency_key):
+        return handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Where?"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=idempotency_key,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-schema", json_schema=schema,
+        )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(
+            side_effect=[upstream('{"town": "Oslo"}'), upstream('{"city": "Oslo"}')]
+        )
+        mock_client.return_value.close = AsyncMock()
+        result = await call("idem-schema")
+
+    assert isinstance(result, SuccessResponse)
+    assert result.data["content"] == '{"city": "Oslo"}'
+    assert result.meta.schema_valid is True
+    assert result.meta.schema_retries == 1
+    retry_messages = json.loads(mock_client.return_value.request.call_args_list[1].kwargs["body"])["messages"]
+    assert retry_messages[1] == {"role": "assistant", "content": '{"town": "Oslo"}'}
+    assert "'city' is a required property" in retry_messages[2]["content"]
+    # Only the corrected output was cached, as the answer to the original request
+    assert mock_cache.set.call_count == 1
+    assert mock_cache.set.call_args[0][4]["body"]["content"] == '{"city": "Oslo"}'
+    assert json.loads(mock_cache.set.call_args[0][3])["messages"] == [{"role": "user", "content": "Where?"}]
+    # ...and stored under the idempotency key once, after the retry
+    assert mock_idempotency.store_result.call_count == 1
+    assert mock_idempotency.store_result.call_args[0][1]["data"]["content"] == '{"city": "Oslo"}'
+    mock_idempotency.clear_in_progress.assert_called_with("idem-schema", tenant=None)
+
+    mock_targets["openai"]["llm"]["output_schema"] = {"on_failure": "error", "max_retries": 2}
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream("Oslo, Norway"))
+        mock_client.return_value.close = AsyncMock()
+        rejected = await call("idem-schema-rejected")
+
+    assert isinstance(rejected, ErrorResponse)
+    assert rejected.error.code == "SCHEMA_VIOLATION"
+    assert rejected.error.status_code == 422
+    assert rejected.meta.schema_valid is False
+    assert mock_client.return_value.request.call_count == 1
+    # A duplicate replays the 422, not the invalid output
+    assert mock_idempotency.store_result.call_count == 2
+    stored = mock_idempotency.store_result.call_args[0][1]
+    assert "data" not in stored and stored["error"]["code"] == "SCHEMA_VIOLATION"
+    mock_idempotency.make_request_hash.return_value = "hash"
+    mock_idempotency.register_request.return_value = (False, "test-req-schema", "hash")
+    mock_idempotency.get_result.return_value = stored
+    replayed = await call("idem-schema-rejected")
+    assert isinstance(replayed, ErrorResponse)
+    assert replayed.error.status_code == 422
+    assert replayed.meta.idempotent_hit is True
+
+
+@pytest.mark.asyncio
+async def test_failed_schema_retry_reports_prior_attempt_cost(mock_targets, mock_cache, mock_idempotency):
+    """Test a corrective retry that errors still reports the cost of the invalid attempt before it."""
+    schema = {"type": "object", "required": ["city"], "properties": {"city": {"type": "string"}}}
+    invalid = Mock(status_code=200, headers={})
+    invalid.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Oslo"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 100, "completion_tokens": 10},
+    }).encode())
+    rejected = Mock(status_code=400, headers={})
+    rejected.aread = AsyncMock(return_value=b'{"error": {"message": "context too long"}}')
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(side_effect=[invalid, rejected])
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Where?"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-schema-error", json_schema=schema,
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.status_code == 400
+    assert result.meta.cost_usd == pytest.approx((100 * 0.15 + 10 * 0.6) / 1_000_000)
+    assert result.meta.schema_retries == 1
+
+
+@pytest.mark.asyncio
+async def test_failover_under_idempotency_key(mock_targets, mock_cache):
+    """Test a keyed failover stores the fallback's response and never resends after an ambiguous error."""
+    mock_targets["openai"]["cache"]["enabled"] = False
+    mock_targets["openai"]["fallback_targets"] = ["backup"]
+    mock_targets["backup"] = {
+        "base_url": "https://backup.example.com/v1",
+        "cache": {"enabled": False},
+        "llm": {"provider": "openai", "default_model": "gpt-4o", "max_tokens": 1024},
+    }
+    results, in_progress = {}, set()
+    idempotency = Mock(spec=IdempotencyManager)
+    idempotency.register_request.side_effect = lambda key, *a, **kw: (key not in results, None, None)
+    idempotency.make_request_hash.return_value = None
+    idempotency.get_result.side_effect = lambda key, tenant=None: results.get(key)
+    idempotency.store_result.side_effect = lambda key, value, **kw: results.update({key: value})
+    idempotency.is_in_progress.side_effect = lambda key, tenant=None: key in in_progress
+    idempotency.mark_in_progress.side_effect = lambda key, tenant=None: in_progress.add(key)
+    idempotency.clear_in_progress.side_effect = lambda key, tenant=None: in_progress.discard(key)
+    calls, no_retry = [], []
+
+    def client_for(base_url, **kwargs):
+        async def request(**request_kwargs):
+            calls.append(base_url)
+            if base_url == mock_targets["openai"]["base_url"]:
+                no_retry.append(request_kwargs.get("no_retry_statuses"))
+                response = Mock(status_code=primary_status[0], headers={})
+                response.aread = AsyncMock(return_value=b'{"error": {"message": "unavailable"}}')
+                return response
+            response = Mock(status_code=200, headers={})
+            response.aread = AsyncMock(return_value=json.dumps({
+                "choices": [{"index": 0, "message": {"role": "assistant", "content": "From backup"}, "finish_reason": "stop"}],
+                "usage": {"prompt_tokens": 10, "completion_tokens": 2},
+            }).encode())
+            return response
+
+        return Mock(request=request, close=AsyncMock(), auth=None)
+
+    async def call(key, request_id):
+        return await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=key,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=idempotency,
+            request_id=request_id, tier="pro",
+        )
+
+    primary_status = [503]
+    with patch("reliapi.app.services.UpstreamHTTPClient", side_effect=client_for):
+        first = await call("idem-fo", "req-1")
+        replay = await call("idem-fo", "req-2")
+        primary_status[0] = 504
+        ambiguous = await call("idem-504", "req-3")
+        unkeyed = await call(None, "req-4")
+
+    assert first.data["content"] == "From backup"
+    assert first.meta.fallback_target == "backup"
+    assert results["idem-fo"]["data"]["content"] == "From backup"
+    assert results["idem-fo"]["model"] == "gpt-4o"
+    assert "idem-fo" not in in_progress
+    # The replay is the backup's response, with no upstream call to either provider
+    assert replay.meta.idempotent_hit is True
+    assert replay.data["content"] == "From backup"
+    assert (replay.meta.fallback_used, replay.meta.fallback_target, replay.meta.model) == (True, "backup", "gpt-4o")
+    assert replay.meta.cost_usd == first.meta.cost_usd
+    # The primary may still complete after a 504: a keyed request is not sent to the backup
+    assert isinstance(ambiguous, ErrorResponse)
+    assert ambiguous.error.status_code == 504
+    assert "idem-504" not in results and "idem-504" not in in_progress
+    assert unkeyed.meta.fallback_target == "backup"
+    openai_url, backup_url = mock_targets["openai"]["base_url"], mock_targets["backup"]["base_url"]
+    assert calls == [openai_url, backup_url, openai_url, openai_url, backup_url]
+    # Nor is it retried on the primary (same provider, another region)
+    assert no_retry == [(504,), (504,), ()]
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("tier,fallback_targets", [("pro", []), ("free", ["backup"])])
+async def test_llm_proxy_upstream_error_response(mock_targets, mock_cache, mock_idempotency, tier, fallback_targets):
+    """Test an upstream error is returned as an upstream_error when no fallback is tried."""
+    mock_targets["openai"]["fallback_targets"] = fallback_targets
+    response = Mock(status_code=503, headers={})
+    response.aread = AsyncMock(return_value=b'{"error": {"message": "unavailable"}}')
+    client = Mock(request=AsyncMock(return_value=response), close=AsyncMock(), auth=None)
+
+    with patch("reliapi.app.services.UpstreamHTTPClient", return_value=client):
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="req-503", tier=tier,
+        )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.type == "upstream_error"
+    assert (result.error.code, result.error.status_code) == ("SERVER_ERROR", 503)
+    client.request.assert_awaited_once()
+
+
+@pytest.mark.asyncio
+async def test_llm_proxy_unknown_model_estimate_chars(mock_targets, mock_cache, mock_idempotency):
+    """Test on_unknown_model: estimate_chars enforces budget caps with an approximate estimate."""
+    mock_targets["openai"]["llm"]["on_unknown_model"] = "estimate_chars"
+    assert CostEstimator.estimate_from_messages("openai", "gpt-unpriced", [], 1024) is None
+
+    result = await handle_llm_proxy(
+        target_name="openai",
+        messages=[{"role": "user", "content": "Hello" * 1000}],
+        model="gpt-unpriced",
+        max_tokens=None,
+        temperature=None,
+        top_p=None,
+        stop=None,
+        stream=False,
+        idempotency_key=None,
+        cache_ttl=None,
+        targets=mock_targets,
+        cache=mock_cache,
+        idempotency=mock_idempotency,
+        request_id="test-123",
+        tenant=None,
+    )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "BUDGET_EXCEEDED"
+    assert result.meta.cost_approximate is True
+    assert result.meta.cost_estimate_usd > 0.05
+
+
 @pytest.mark.asyncio
 async def test_llm_proxy_target_not_found(mock_cache, mock_idempotency):
     """Test error when target is not found."""
@@ -104,3 +574,1045 @@ async def test_llm_proxy_target_not_found(mock_cache, mock_idempotency):
     assert isinstance(result, ErrorResponse)
     assert result.error.code == "NOT_FOUND"
 
+
+@pytest.mark.asyncio
+async def test_llm_proxy_model_rpm_limit(mock_targets, mock_cache, mock_idempotency):
+    """Test per-model RPM cap rejects with 429 and retry_after."""
+    mock_targets["openai"]["llm"]["model_limits"] = {"gpt-4o-mini": {"rpm": 1}}
+    limiter = ModelRateLimiter()
+    await limiter.acquire("openai", "gpt-4o-mini", {"rpm": 1}, 0)
+
+    result = await handle_llm_proxy(
+        target_name="openai",
+        messages=[{"role": "user", "content": "Hello"}],
+        model=None,
+        max_tokens=None,
+        temperature=None,
+        top_p=None,
+        stop=None,
+        stream=False,
+        idempotency_key="idem-1",
+        cache_ttl=None,
+        targets=mock_targets,
+        cache=mock_cache,
+        idempotency=mock_idempotency,
+        request_id="test-123",
+        tenant=None,
+        model_rate_limiter=limiter,
+    )
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "RATE_LIMIT_RELIAPI"
+    assert result.error.status_code == 429
+    assert result.error.retry_after_s > 0
+    mock_idempotency.clear_in_progress.assert_called_once_with("idem-1", tenant=None)
+
+
+async def _events(*events):
+    for event in events:
+        yield event
+
+
+@pytest.mark.asyncio
+async def test_prime_llm_stream_exposes_cost_estimate():
+    """Test stream-start meta (cost estimate) is read without losing events."""
+    events = [
+        'event: meta\ndata: {"request_id": "req_1", "cost_estimate_usd": 0.0042}\n\n',
+        'event: chunk\ndata: {"delta": "Hi", "finish_reason": null}\n\n',
+        'event: done\ndata: {"cost_usd": 0.001, "cost_estimate_usd": 0.0042}\n\n',
+    ]
+    meta, stream = await prime_llm_stream(_events(*events))
+
+    assert meta["cost_estimate_usd"] == 0.0042
+    assert [event async for event in stream] == events
+
+
+@pytest.mark.asyncio
+async def test_prime_llm_stream_error_first():
+    """Test streams that fail before starting have no meta."""
+    error = 'event: error\ndata: {"code": "NOT_FOUND"}\n\n'
+    meta, stream = await prime_llm_stream(_events(error))
+
+    assert meta is None
+    assert [event async for event in stream] == [error]
+
+
+@pytest.mark.asyncio
+async def test_prime_llm_stream_empty():
+    """Test a stream that ends before its first event becomes a single error event."""
+    meta, stream = await prime_llm_stream(_events())
+
+    events = [event async for event in stream]
+    assert meta is None
+    assert len(events) == 1 and events[0].startswith("event: error\n")
+    assert json.loads(events[0].split("data: ", 1)[1])["code"] == "UPSTREAM_STREAM_INTERRUPTED"
+
+
+
+@pytest.mark.asyncio
+async def test_idle_timeout_passes_steady_stream():
+    """Test chunks arriving within the idle window pass through."""
+    chunks = [{"n": 1}, {"n": 2}]
+    assert [chunk async for chunk in _iter_with_idle_timeout(_events(*chunks), 1.0)] == chunks
+
+
+@pytest.mark.asyncio
+async def test_idle_timeout_aborts_stalled_stream():
+    """Test a stream that stalls between chunks is aborted."""
+    async def stalled():
+        yield {"n": 1}
+        await asyncio.sleep(10)
+        yield {"n": 2}
+
+    received = []
+    with pytest.raises(StreamIdleTimeout) as exc_info:
+        async for chunk in _iter_with_idle_timeout(stalled(), 0.05):
+            received.append(chunk)
+    assert received == [{"n": 1}]
+    assert exc_info.value.first_chunk is False
+
+
+@pytest.mark.asyncio
+async def test_first_chunk_timeout_separate_from_idle_timeout():
+    """Test the first chunk may take longer than the idle window, and a stalled start closes the upstream."""
+    closed = []
+
+    async def slow_start(delay_s):
+        try:
+            await asyncio.sleep(delay_s)
+            yield {"n": 1}
+            yield {"n": 2}
+        finally:
+            closed.append(delay_s)
+
+    chunks = [chunk async for chunk in _iter_with_idle_timeout(slow_start(0.1), 0.05, 1.0)]
+    assert chunks == [{"n": 1}, {"n": 2}]
+
+    with pytest.raises(StreamIdleTimeout) as exc_info:
+        async for chunk in _iter_with_idle_timeout(slow_start(10), 1.0, 0.05):
+            pass
+    assert exc_info.value.first_chunk is True
+    assert closed == [0.1, 10]
+
+
+def test_observe_llm_usage_per_model():
+    """Test token and cost histograms are labeled by target and model only."""
+    from reliapi.app.services import _observe_llm_usage
+
+    with patch("reliapi.app.services.llm_prompt_tokens") as prompt_hist, \
+            patch("reliapi.app.services.llm_completion_tokens") as completion_hist, \
+            patch("reliapi.app.services.llm_request_cost_usd") as cost_hist:
+        _observe_llm_usage("openai", "gpt-4o-mini", prompt_tokens=120, completion_tokens=30, cost_usd=0.0002)
+
+    prompt_hist.labels.assert_called_once_with(target="openai", model="gpt-4o-mini")
+    prompt_hist.labels.return_value.observe.assert_called_once_with(120)
+    completion_hist.labels.return_value.observe.assert_called_once_with(30)
+    cost_hist.labels.return_value.observe.assert_called_once_with(0.0002)
+
+
+def _llm_call(targets, cache, idempotency, temperature):
+    return handle_llm_proxy(
+        target_name="openai",
+        messages=[{"role": "user", "content": "Hello"}],
+        model=None,
+        max_tokens=None,
+        temperature=temperature,
+        top_p=None,
+        stop=None,
+        stream=False,
+        idempotency_key=None,
+        cache_ttl=None,
+        targets=targets,
+        cache=cache,
+        idempotency=idempotency,
+        request_id="test-req-cache",
+        tenant=None,
+    )
+
+
+@pytest.mark.asyncio
+async def test_sampled_request_skips_cache(mock_targets, mock_cache, mock_idempotency):
+    """Test temperature > 0 bypasses the response cache by default."""
+    mock_cache.get.return_value = {"body": {"content": "cached"}, "cost_usd": 0.0001}
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "fresh"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await _llm_call(mock_targets, mock_cache, mock_idempotency, temperature=0.7)
+
+    mock_cache.get.assert_not_called()
+    mock_cache.set.assert_not_called()
+    assert isinstance(result, SuccessResponse)
+    assert result.data["content"] == "fresh"
+    assert result.meta.cache_skipped_nondeterministic is True
+
+
+@pytest.mark.asyncio
+async def test_sampled_request_cached_with_opt_in(mock_targets, mock_cache, mock_idempotency):
+    """Test cache_nondeterministic serves temperature > 0 requests from cache."""
+    mock_targets["openai"]["cache"]["cache_nondeterministic"] = True
+    mock_cache.get.return_value = {"body": {"content": "cached"}, "cost_usd": 0.0001}
+
+    result = await _llm_call(mock_targets, mock_cache, mock_idempotency, temperature=0.7)
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.cache_hit is True
+    assert result.meta.cache_skipped_nondeterministic is None
+
+
+@pytest.mark.asyncio
+async def test_upstream_request_id_captured_and_cached(mock_targets, mock_cache, mock_idempotency):
+    """Test the provider's x-request-id is exposed in meta and stored with the cached response."""
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {"x-request-id": "req_openai_123"}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "hi"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await _llm_call(mock_targets, mock_cache, mock_idempotency, temperature=0)
+
+    assert result.meta.upstream_request_id == "req_openai_123"
+    assert mock_cache.set.call_args[0][4]["upstream_request_id"] == "req_openai_123"
+
+    mock_cache.get.return_value = mock_cache.set.call_args[0][4]
+    cached = await _llm_call(mock_targets, mock_cache, mock_idempotency, temperature=0)
+    assert cached.meta.cache_hit is True
+    assert cached.meta.upstream_request_id == "req_openai_123"
+
+
+@pytest.mark.asyncio
+async def test_json_mode_output_repaired(mock_targets, mock_cache, mock_idempotency):
+    """Test fenced JSON-mode output is repaired before returning and caching when json_repair is on."""
+    mock_targets["openai"]["llm"]["json_repair"] = True
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": '```json\n{"a": 1,}\n```'}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 5, "total_tokens": 8},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "JSON please"}], model=None,
+            max_tokens=None, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-json", response_format={"type": "json_object"},
+        )
+
+    assert result.data["content"] == '{"a": 1}'
+    assert result.meta.json_repaired is True
+    assert mock_cache.set.call_args[0][4]["body"]["content"] == '{"a": 1}'
+    sent = json.loads(mock_client.return_value.request.call_args.kwargs["body"])
+    assert sent["response_format"] == {"type": "json_object"}
+
+
+@pytest.mark.asyncio
+async def test_partial_choices_served_when_provider_returns_fewer(mock_targets, mock_cache, mock_idempotency):
+    """Test n=3 with two valid choices (one failed) returns both with requested_n/returned_n."""
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [
+            {"index": 0, "message": {"role": "assistant", "content": "first"}, "finish_reason": "stop"},
+            {"index": 1, "finish_reason": "error"},
+            {"index": 2, "message": {"role": "assistant", "content": "third"}, "finish_reason": "length"},
+        ],
+        "usage": {"prompt_tokens": 10, "completion_tokens": 20, "total_tokens": 30},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Name a color"}], model=None,
+            max_tokens=50, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-n", n=3,
+        )
+
+    assert result.success is True
+    assert result.data["content"] == "first"
+    assert [choice["index"] for choice in result.data["choices"]] == [0, 2]
+    assert result.meta.requested_n == 3
+    assert result.meta.returned_n == 2
+    assert result.meta.cost_usd == pytest.approx((10 * 0.15 + 20 * 0.6) / 1_000_000)
+    sent = json.loads(mock_client.return_value.request.call_args.kwargs["body"])
+    assert sent["n"] == 3
+
+
+@pytest.mark.asyncio
+async def test_anthropic_response_normalized(mock_targets, mock_cache, mock_idempotency):
+    """Test an Anthropic Messages response comes back in the OpenAI choices shape, with raw on request."""
+    mock_targets["anthropic"] = {
+        "base_url": "https://api.anthropic.com/v1",
+        "timeout_ms": 20000,
+        "llm": {
+            "provider": "anthropic",
+            "default_model": "claude-3-haiku-20240307",
+            "normalization": {"include_raw": True},
+        },
+        "auth": {"type": "bearer_env", "env_var": "ANTHROPIC_API_KEY"},
+    }
+    raw = {
+        "content": [{"type": "text", "text": "Hello"}],
+        "stop_reason": "end_turn",
+        "usage": {"input_tokens": 4, "output_tokens": 2},
+    }
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps(raw).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="anthropic", messages=[{"role": "user", "content": "Hi"}], model=None,
+            max_tokens=16, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-normalize",
+        )
+
+    assert result.success is True
+    assert result.data["choices"] == [
+        {"index": 0, "message": {"role": "assistant", "content": "Hello"}, "finish_reason": "stop"}
+    ]
+    assert result.data["finish_reason"] == "stop"
+    assert result.data["usage"]["prompt_tokens"] == 4
+    assert result.data["usage"]["completion_tokens"] == 2
+    assert result.data["raw"] == raw
+
+
+@pytest.mark.asyncio
+async def test_seeded_request_cached_with_logit_bias_in_key(mock_targets, mock_cache, mock_idempotency):
+    """Test a seeded request is keyed on logit_bias and seed, and a seed keeps sampled requests uncached."""
+    mock_cache.get.return_value = {"body": {"content": "cached"}, "cost_usd": 0.0001}
+
+    async def call(temperature):
+        return await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=None, temperature=temperature, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-seed", logit_bias={"50256": -100}, seed=42,
+        )
+
+    result = await call(0)
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.cache_hit is True
+    cache_key_body = json.loads(mock_cache.get.call_args[0][3])
+    assert cache_key_body["logit_bias"] == {"50256": -100}
+    assert cache_key_body["seed"] == 42
+
+    mock_cache.get.reset_mock()
+    upstream = Mock(status_code=200, headers={}, aread=AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "fresh"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+    }).encode()))
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        sampled = await call(0.7)
+    mock_cache.get.assert_not_called()
+    assert sampled.meta.cache_skipped_nondeterministic is True
+
+
+@pytest.mark.asyncio
+async def test_allowed_params_drop_reject_and_forward(mock_targets, mock_cache, mock_idempotency):
+    """Test params outside llm.allowed_params are dropped, rejected or forwarded per on_unknown_param."""
+    mock_cache.get.return_value = {"body": {"content": "cached"}, "cost_usd": 0.0001}
+    mock_targets["openai"]["llm"]["allowed_params"] = ["temperature", "stop"]
+
+    async def call(**params):
+        return await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=None, temperature=0, top_p=None, stop=["\n"], stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-allowed", seed=42, logprobs=False, **params,
+        )
+
+    dropped = await call()
+    assert isinstance(dropped, SuccessResponse)
+    cache_key_body = json.loads(mock_cache.get.call_args[0][3])
+    assert cache_key_body.get("seed") is None
+    assert cache_key_body["stop"] == ["\n"]
+
+    mock_targets["openai"]["llm"]["on_unknown_param"] = "reject"
+    mock_cache.get.reset_mock()
+    rejected = await call(logit_bias={"1234": 5})
+    assert isinstance(rejected, ErrorResponse)
+    assert rejected.error.code == "PARAM_NOT_ALLOWED"
+    assert rejected.error.details["params"] == ["logit_bias", "seed"]
+    mock_cache.get.assert_not_called()
+
+    mock_targets["openai"]["llm"]["on_unknown_param"] = "forward"
+    forwarded = await call()
+    assert isinstance(forwarded, SuccessResponse)
+    assert json.loads(mock_cache.get.call_args[0][3])["seed"] == 42
+
+
+@pytest.mark.asyncio
+async def test_logit_bias_rejected_for_unsupported_provider(mock_targets, mock_cache, mock_idempotency):
+    """Test logit_bias on an Anthropic target returns UNSUPPORTED_PARAM without calling upstream."""
+    mock_targets["anthropic"] = {
+        "base_url": "https://api.anthropic.com/v1",
+        "timeout_ms": 20000,
+        "llm": {"provider": "anthropic", "default_model": "claude-3-haiku-20240307"},
+        "auth": {"type": "bearer_env", "env_var": "ANTHROPIC_API_KEY"},
+    }
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        result = await handle_llm_proxy(
+            target_name="anthropic", messages=[{"role": "user", "content": "Hi"}], model=None,
+            max_tokens=16, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-unsupported", logit_bias={"1234": 5},
+        )
+
+    mock_client.assert_not_called()
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "UNSUPPORTED_PARAM"
+    assert result.error.status_code == 400
+    assert result.error.details == {"params": ["logit_bias"], "provider": "anthropic"}
+
+
+@pytest.mark.asyncio
+async def test_logprobs_survive_cache_round_trip(mock_targets, mock_cache, mock_idempotency):
+    """Test logprobs are requested upstream, keyed in the cache and replayed unchanged from it."""
+    logprobs = {"content": [{"token": "Hi", "logprob": -0.01, "top_logprobs": [{"token": "Hi", "logprob": -0.01}]}]}
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop", "logprobs": logprobs}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1},
+    }).encode())
+    call = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+        max_tokens=16, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+        cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+        request_id="test-req-logprobs", logprobs=True, top_logprobs=1,
+    )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        first = await handle_llm_proxy(**call)
+
+    sent = json.loads(mock_client.return_value.request.call_args.kwargs["body"])
+    assert (sent["logprobs"], sent["top_logprobs"]) == (True, 1)
+    cache_key_body = json.loads(mock_cache.set.call_args[0][3])
+    assert (cache_key_body["logprobs"], cache_key_body["top_logprobs"]) == (True, 1)
+    assert first.data["logprobs"] == logprobs
+    assert first.data["choices"][0]["logprobs"] == logprobs
+
+    mock_cache.get.return_value = mock_cache.set.call_args[0][4]
+    replayed = await handle_llm_proxy(**call)
+
+    assert replayed.meta.cache_hit is True
+    assert replayed.data["logprobs"] == logprobs
+    assert replayed.data["choices"][0]["logprobs"] == logprobs
+
+
+@pytest.mark.asyncio
+async def test_cost_breakdown_reported_and_replayed_from_cache(mock_targets, mock_cache, mock_idempotency):
+    """Test cost_usd is split into input and output cost, and cache hits replay the split."""
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 1000, "completion_tokens": 100},
+    }).encode())
+    call = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+        max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+        cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+        request_id="test-req-breakdown",
+    )
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        first = await handle_llm_proxy(**call)
+
+    breakdown = first.meta.cost_breakdown
+    assert breakdown.input_usd == pytest.approx(1000 * 0.15 / 1_000_000)
+    assert breakdown.output_usd == pytest.approx(100 * 0.6 / 1_000_000)
+    assert first.meta.cost_usd == pytest.approx(breakdown.input_usd + breakdown.output_usd)
+
+    mock_cache.get.return_value = mock_cache.set.call_args[0][4]
+    replayed = await handle_llm_proxy(**call)
+
+    assert replayed.meta.cache_hit is True
+    assert replayed.meta.cost_breakdown == breakdown
+
+
+@pytest.mark.asyncio
+async def test_completed_stream_populates_cache_for_later_requests(mock_targets, mock_idempotency):
+    """Test a finished stream is cached under the non-streaming key and replayed to both kinds of request."""
+    store = {}
+    cache = Mock(spec=Cache)
+    cache.get.side_effect = lambda method, url, headers, body, query, **kw: store.get((url, body))
+    cache.set.side_effect = lambda method, url, headers, body, value, **kw: store.update({(url, body): value})
+    common = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None, max_tokens=100,
+        temperature=0, top_p=None, stop=None, idempotency_key=None, cache_ttl=None,
+        targets=mock_targets, cache=cache, idempotency=mock_idempotency,
+    )
+
+    async def fake_stream(self, client, base_url, api_path, payload, headers):
+        assert payload["stream"] is True
+        for delta in ("Hel", "lo!"):
+            yield {"choices": [{"delta": {"content": delta}, "finish_reason": None}]}
+        yield {"choices": [{"delta": {}, "finish_reason": "stop"}]}
+        yield {"_usage_only": True, "usage": {"prompt_tokens": 5, "completion_tokens": 2}}
+
+    async def stream_events(request_id):
+        events = [e async for e in handle_llm_stream_generator(request_id=request_id, **common)]
+        return [(e.split("\n")[0][len("event: "):], json.loads(e.split("\n")[1][len("data: "):])) for e in events]
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat", fake_stream):
+        first = await stream_events("test-stream-1")
+    assert [name for name, _ in first] == ["meta", "chunk", "chunk", "done"]
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat") as stream_chat, \
+            patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        non_streamed = await handle_llm_proxy(stream=False, request_id="test-stream-2", **common)
+        streamed = await stream_events("test-stream-3")
+    stream_chat.assert_not_called()
+    client_cls.return_value.request.assert_not_called()
+
+    assert non_streamed.meta.cache_hit is True
+    assert non_streamed.data["content"] == "Hello!"
+    assert non_streamed.data["choices"][0]["message"]["content"] == "Hello!"
+    assert non_streamed.data["usage"]["total_tokens"] == 7
+    assert streamed[0] == ("meta", {**streamed[0][1], "cache_hit": True})
+    assert streamed[1] == ("chunk", {"delta": "Hello!", "finish_reason": None})
+    assert streamed[2][1]["cache_hit"] is True
+    assert streamed[2][1]["finish_reason"] == "stop"
+
+
+@pytest.mark.asyncio
+async def test_cache_consulted_before_idempotency_for_keyed_requests(mock_targets, mock_idempotency):
+    """Test an identical request under a new idempotency key is served from the cache, not upstream."""
+    store = {}
+    cache = Mock(spec=Cache)
+    cache.get.side_effect = lambda method, url, headers, body, query, **kw: store.get((url, body))
+    cache.set.side_effect = lambda method, url, headers, body, value, **kw: store.update({(url, body): value})
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Once"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 5, "completion_tokens": 1},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        first, second = [
+            await handle_llm_proxy(
+                target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+                max_tokens=100, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=key,
+                cache_ttl=None, targets=mock_targets, cache=cache, idempotency=mock_idempotency,
+                request_id=f"req-{key}",
+            )
+            for key in ("idem-a", "idem-b")
+        ]
+
+    mock_client.return_value.request.assert_awaited_once()
+    assert first.meta.cache_hit is False
+    assert second.meta.cache_hit is True
+    assert second.data["content"] == "Once"
+    # The hit is answered before the idempotency layer could force an upstream call
+    assert [c.args[0] for c in mock_idempotency.register_request.call_args_list] == ["idem-a"]
+
+
+@pytest.mark.asyncio
+async def test_duplicate_streamed_idempotent_call_replays_stream(mock_targets, mock_cache):
+    """Test a repeated streamed call with the same idempotency key replays the stored stream."""
+    mock_targets["openai"]["cache"]["enabled"] = False
+    results, in_progress = {}, set()
+    idempotency = Mock(spec=IdempotencyManager)
+    idempotency.register_request.side_effect = lambda key, *args, **kw: (
+        (True, None, None) if key not in results and key not in in_progress else (False, "first", "hash")
+    )
+    idempotency.make_request_hash.return_value = "hash"
+    idempotency.get_result.side_effect = lambda key, tenant=None: results.get(key)
+    idempotency.store_result.side_effect = lambda key, value, **kw: results.update({key: value})
+    idempotency.is_in_progress.side_effect = lambda key, tenant=None: key in in_progress
+    idempotency.mark_in_progress.side_effect = lambda key, tenant=None: in_progress.add(key)
+    idempotency.clear_in_progress.side_effect = lambda key, tenant=None: in_progress.discard(key)
+
+    async def fake_stream(self, client, base_url, api_path, payload, headers):
+        for delta in ("Hel", "lo!"):
+            yield {"choices": [{"delta": {"content": delta}, "finish_reason": None}]}
+        yield {"choices": [{"delta": {}, "finish_reason": "stop"}]}
+        yield {"_usage_only": True, "usage": {"prompt_tokens": 5, "completion_tokens": 2}}
+
+    async def stream_events(request_id):
+        events = [e async for e in handle_llm_stream_generator(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, idempotency_key="idem-stream",
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=idempotency,
+            request_id=request_id,
+        )]
+        return [(e.split("\n")[0][len("event: "):], json.loads(e.split("\n")[1][len("data: "):])) for e in events]
+
+    # Still streaming: rejected by default, replayed once finished with "wait"
+    in_progress.add("idem-stream")
+    rejected = await stream_events("test-dup-0")
+    assert rejected[0][1]["code"] == "STREAM_ALREADY_IN_PROGRESS"
+    in_progress.clear()
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat", fake_stream):
+        first = await stream_events("test-dup-1")
+    assert [name for name, _ in first] == ["meta", "chunk", "chunk", "done"]
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat") as stream_chat:
+        replayed = await stream_events("test-dup-2")
+        mock_targets["openai"]["llm"]["stream_idempotency_in_progress"] = "wait"
+        stored = results.pop("idem-stream")
+        in_progress.add("idem-stream")
+        asyncio.get_running_loop().call_later(0.1, results.update, {"idem-stream": stored})
+        waited = await stream_events("test-dup-3")
+    stream_chat.assert_not_called()
+
+    assert [name for name, _ in replayed] == ["meta", "chunk", "done"]
+    assert replayed[0][1]["idempotent_hit"] is True
+    assert replayed[1][1]["delta"] == "Hello!"
+    assert replayed[2][1]["cost_usd"] == first[-1][1]["cost_usd"]
+    assert replayed[2][1]["usage"] == first[-1][1]["usage"]
+    assert waited[1:] == replayed[1:]
+
+
+@pytest.mark.asyncio
+async def test_idempotency_key_shared_by_streamed_and_buffered_calls(mock_targets, mock_cache):
+    """Test a streamed call's key replays as a buffered response, and a buffered call's as a stream."""
+    mock_targets["openai"]["cache"]["enabled"] = False
+    results, in_progress, hashes = {}, set(), {}
+    idempotency = Mock(spec=IdempotencyManager)
+
+    def register(key, method, url, headers, body, request_id, tenant=None):
+        request_hash = idempotency.make_request_hash(method, url, headers, body)
+        if key in hashes:
+            return False, "first", hashes[key]
+        hashes[key] = request_hash
+        return True, None, None
+
+    idempotency.register_request.side_effect = register
+    idempotency.make_request_hash.side_effect = lambda method, url, headers, body: f"{method} {url} {body!r}"
+    idempotency.get_result.side_effect = lambda key, tenant=None: results.get(key)
+    idempotency.store_result.side_effect = lambda key, value, **kw: results.update({key: value})
+    idempotency.is_in_progress.side_effect = lambda key, tenant=None: key in in_progress
+    idempotency.mark_in_progress.side_effect = lambda key, tenant=None: in_progress.add(key)
+    idempotency.clear_in_progress.side_effect = lambda key, tenant=None: in_progress.discard(key)
+    request = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+        max_tokens=100, temperature=0, top_p=None, stop=None, cache_ttl=None,
+        targets=mock_targets, cache=mock_cache, idempotency=idempotency,
+    )
+
+    async def fake_stream(self, client, base_url, api_path, payload, headers):
+        yield {"choices": [{"delta": {"content": "Streamed"}, "finish_reason": None}]}
+        yield {"choices": [{"delta": {}, "finish_reason": "stop"}]}
+        yield {"_usage_only": True, "usage": {"prompt_tokens": 5, "completion_tokens": 1}}
+
+    async def stream_events(key, request_id):
+        events = [e async for e in handle_llm_stream_generator(
+            **request, idempotency_key=key, request_id=request_id,
+        )]
+        return [(e.split("\n")[0][len("event: "):], json.loads(e.split("\n")[1][len("data: "):])) for e in events]
+
+    # Streamed first, then buffered
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat", fake_stream):
+        streamed = await stream_events("idem-s", "req-1")
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        buffered = await handle_llm_proxy(**request, stream=False, idempotency_key="idem-s", request_id="req-2")
+    mock_client.return_value.request.assert_not_called()
+    assert isinstance(buffered, SuccessResponse)
+    assert buffered.meta.idempotent_hit is True
+    assert buffered.data["content"] == "Streamed"
+    assert buffered.data["choices"][0]["message"]["content"] == "Streamed"
+    assert buffered.data["usage"] == streamed[-1][1]["usage"]
+    assert buffered.meta.cost_usd == streamed[-1][1]["cost_usd"]
+
+    # Buffered first, then streamed
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Buffered"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 5, "completion_tokens": 1},
+    }).encode())
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        first = await handle_llm_proxy(**request, stream=False, idempotency_key="idem-b", request_id="req-3")
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat") as stream_chat:
+        replayed = await stream_events("idem-b", "req-4")
+    stream_chat.assert_not_called()
+    assert [name for name, _ in replayed] == ["meta", "chunk", "done"]
+    assert replayed[0][1]["idempotent_hit"] is True
+    assert replayed[1][1]["delta"] == "Buffered"
+    assert replayed[2][1]["cost_usd"] == first.meta.cost_usd
+
+    # A different request under a used key is still a conflict, whichever form it takes
+    request["messages"] = [{"role": "user", "content": "Something else"}]
+    conflict = await stream_events("idem-b", "req-5")
+    assert conflict[0][1]["code"] == "IDEMPOTENCY_CONFLICT"
+
+
+@pytest.mark.asyncio
+async def test_collapse_stream_returns_one_buffered_response(mock_targets, mock_cache, mock_idempotency):
+    """Test a collapsed stream is assembled into one response with the done event's cost and usage."""
+    mock_targets["openai"]["cache"]["enabled"] = False
+
+    async def fake_stream(self, client, base_url, api_path, payload, headers):
+        for delta in ("Hel", "lo!"):
+            yield {"choices": [{"delta": {"content": delta}, "finish_reason": None}]}
+        yield {"choices": [{"delta": {}, "finish_reason": "stop"}]}
+        yield {"_usage_only": True, "usage": {"prompt_tokens": 5, "completion_tokens": 2}}
+
+    def stream():
+        return handle_llm_stream_generator(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=100, temperature=0, top_p=None, stop=None, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-collapse",
+        )
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat", fake_stream):
+        result = await collapse_llm_stream(stream(), "openai", "test-collapse")
+
+    assert isinstance(result, SuccessResponse)
+    assert result.data["content"] == "Hello!"
+    assert result.data["choices"][0]["message"]["content"] == "Hello!"
+    assert result.data["usage"]["total_tokens"] == 7
+    assert result.meta.model == "gpt-4o-mini"
+    assert result.meta.cost_usd > 0
+    assert result.meta.cost_estimate_usd is not None
+
+    # A stream error event becomes an error response with the upstream status
+    async def failed():
+        yield 'event: error\ndata: {"code": "RATE_LIMIT_RELIAPI", "message": "Slow down", "upstream_status": 429}\n\n'
+
+    rejected = await collapse_llm_stream(failed(), "openai", "test-collapse")
+    assert isinstance(rejected, ErrorResponse)
+    assert rejected.error.status_code == 429
+    assert rejected.error.code == "RATE_LIMIT_RELIAPI"
+    assert rejected.error.retryable is True
+
+
+
+@pytest.mark.asyncio
+async def test_stream_aborted_at_cost_ceiling(mock_targets, mock_cache, mock_idempotency):
+    """Test a stream whose running cost passes stream_cost_ceiling_usd is cut off and not cached."""
+    mock_targets["openai"]["llm"]["stream_cost_ceiling_usd"] = 0.0001
+    sent = []
+
+    async def fake_stream(self, client, base_url, api_path, payload, headers):
+        for i in range(1000):
+            sent.append(i)
+            yield {"choices": [{"delta": {"content": "word " * 20}, "finish_reason": None}]}
+        yield {"choices": [{"delta": {}, "finish_reason": "stop"}]}
+
+    with patch("reliapi.adapters.llm.openai.OpenAIAdapter.stream_chat", fake_stream):
+        events = [e async for e in handle_llm_stream_generator(
+            target_name="openai", messages=[{"role": "user", "content": "Write forever"}], model=None,
+            max_tokens=None, temperature=0, top_p=None, stop=None, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-ceiling",
+        )]
+
+    done = json.loads(events[-1].split("data: ", 1)[1])
+    assert events[-1].startswith("event: done")
+    assert done["cost_ceiling_reached"] is True
+    assert done["finish_reason"] == "length"
+    assert done["usage_source"] == "estimated"
+    # $0.0001 buys ~166 gpt-4o-mini output tokens (7 chunks of ~25), far short of 1000 chunks
+    assert len(sent) < 10
+    mock_cache.set.assert_not_called()
+
+
+def test_system_field_prepended_and_mapped_for_anthropic():
+    """Test the system field goes ahead of system messages and Anthropic gets them as the system param."""
+    request = LLMProxyRequest(
+        target="anthropic",
+        system="Be terse.",
+        messages=[{"role": "system", "content": "Answer in English."}, {"role": "user", "content": "Hi"}],
+    )
+
+    assert request.messages[0] == {"role": "system", "content": "Be terse."}
+    payload = AnthropicAdapter().prepare_request(request.messages, "claude-3-haiku-20240307")
+    assert payload["system"] == "Be terse.\n\nAnswer in English."
+    assert payload["messages"] == [{"role": "user", "content": "Hi"}]
+
+
+def test_openai_stream_requests_provider_usage():
+    """Test streaming OpenAI payloads ask for usage in the final chunk unless disabled."""
+    from reliapi.adapters.llm.openai import OpenAIAdapter
+
+    adapter = OpenAIAdapter()
+    messages = [{"role": "user", "content": "Hi"}]
+    assert adapter.prepare_request(messages, "gpt-4o-mini", stream=True)["stream_options"] == {"include_usage": True}
+    assert "stream_options" not in adapter.prepare_request(messages, "gpt-4o-mini", stream=True, stream_usage=False)
+    assert "stream_options" not in adapter.prepare_request(messages, "gpt-4o-mini")
+
+
+@pytest.mark.asyncio
+async def test_missing_provider_usage_estimated_locally(mock_targets, mock_cache, mock_idempotency):
+    """Test a response without usage is billed from local token estimates and marked as such."""
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "x" * 40}, "finish_reason": "stop"}],
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "y" * 80}], model=None,
+            max_tokens=16, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-usage-estimate",
+        )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.usage_source == "estimated"
+    assert result.data["usage"]["prompt_tokens"] == 20
+    assert result.data["usage"]["completion_tokens"] == 10
+    assert result.meta.cost_usd > 0
+
+
+@pytest.mark.asyncio
+async def test_cache_hits_skip_model_rate_limit_unless_counted(mock_targets, mock_cache, mock_idempotency):
+    """Test cache hits do not take RPM slots by default, and are limited with count_cache_hits_against_rate_limit."""
+    mock_targets["openai"]["llm"]["model_limits"] = {"gpt-4o-mini": {"rpm": 1}}
+    limiter = ModelRateLimiter()
+    await limiter.acquire("openai", "gpt-4o-mini", {"rpm": 1}, 0)
+    mock_cache.get.return_value = {"body": {"content": "Hi", "role": "assistant"}, "cost_usd": 0.0001}
+    call = dict(
+        target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+        max_tokens=None, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+        cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+        request_id="test-req-hit-rpm", model_rate_limiter=limiter,
+    )
+
+    for _ in range(3):
+        result = await handle_llm_proxy(**call)
+        assert isinstance(result, SuccessResponse)
+        assert result.meta.cache_hit is True
+    assert limiter.get_remaining("openai", "gpt-4o-mini", {"rpm": 1})["rpm"] == 0
+    assert len(limiter._windows["openai:gpt-4o-mini"].entries) == 1
+
+    mock_targets["openai"]["count_cache_hits_against_rate_limit"] = True
+    result = await handle_llm_proxy(**call)
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "RATE_LIMIT_RELIAPI"
+    assert result.error.retry_after_s > 0
+
+
+@pytest.mark.asyncio
+async def test_long_history_summarized_before_main_call(mock_targets, mock_cache, mock_idempotency):
+    """Test older turns are replaced with a summary and its cost is included in cost_usd."""
+    mock_targets["openai"]["llm"]["summarization"] = {
+        "threshold_tokens": 50, "keep_recent": 2, "model": "gpt-4o-mini",
+    }
+    history = [{"role": "system", "content": "Be helpful."}] + [
+        {"role": "user" if i % 2 == 0 else "assistant", "content": f"turn {i} " + "x" * 100}
+        for i in range(6)
+    ]
+
+    def _upstream(content, prompt_tokens):
+        response = Mock()
+        response.status_code = 200
+        response.headers = {}
+        response.aread = AsyncMock(return_value=json.dumps({
+            "choices": [{"message": {"role": "assistant", "content": content}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": prompt_tokens, "completion_tokens": 10, "total_tokens": prompt_tokens + 10},
+        }).encode())
+        return response
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(side_effect=[
+            _upstream("They discussed turns 0-3.", 120), _upstream("answer", 60),
+        ])
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=history, model=None, max_tokens=None, temperature=0,
+            top_p=None, stop=None, stream=False, idempotency_key=None, cache_ttl=None,
+            targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency, request_id="test-req-summary",
+        )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.history_summarized is True
+    assert result.meta.history_tokens_saved > 0
+    main_body = json.loads(mock_client.return_value.request.call_args_list[1].kwargs["body"])
+    assert [m["role"] for m in main_body["messages"]] == ["system", "system", "user", "assistant"]
+    assert main_body["messages"][1]["content"].endswith("They discussed turns 0-3.")
+    main_only = CostEstimator.PRICING_PER_1K["openai"]["gpt-4o-mini"]["prompt"] * 0.06
+    assert result.meta.cost_usd > main_only
+
+
+@pytest.mark.asyncio
+async def test_fallback_gets_unsummarized_history_and_summary_cost(mock_targets, mock_cache, mock_idempotency):
+    """Test a fallback target is sent the client's conversation, and cost_usd still includes the summary."""
+    mock_targets["openai"]["llm"]["summarization"] = {"threshold_tokens": 50, "keep_recent": 2}
+    mock_targets["openai"]["fallback_targets"] = ["backup"]
+    mock_targets["backup"] = {
+        "base_url": "https://backup.example.com/v1",
+        "cache": {"enabled": False},
+        "llm": {"provider": "openai", "default_model": "gpt-4o-mini", "max_tokens": 1024},
+    }
+    history = [
+        {"role": "user" if i % 2 == 0 else "assistant", "content": f"turn {i} " + "x" * 100} for i in range(6)
+    ]
+
+    def _upstream(status, content):
+        response = Mock(status_code=status, headers={})
+        response.aread = AsyncMock(return_value=json.dumps({
+            "choices": [{"message": {"role": "assistant", "content": content}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 10, "total_tokens": 110},
+        }).encode())
+        return response
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(side_effect=[
+            _upstream(200, "They discussed turns 0-3."), _upstream(503, "down"), _upstream(200, "answer"),
+        ])
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=history, model=None, max_tokens=None, temperature=0,
+            top_p=None, stop=None, stream=False, idempotency_key=None, cache_ttl=None,
+            targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-summary-fallback", tier="pro",
+        )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.fallback_target == "backup"
+    fallback_body = json.loads(mock_client.return_value.request.call_args_list[2].kwargs["body"])
+    assert fallback_body["messages"] == history
+    # Summary call plus the fallback's answer, 100 prompt and 10 completion tokens each
+    one_call = CostEstimator.PRICING_PER_1K["openai"]["gpt-4o-mini"]
+    one_call = (one_call["prompt"] * 100 + one_call["completion"] * 10) / 1000
+    assert result.meta.cost_usd == pytest.approx(2 * one_call)
+
+
+@pytest.mark.asyncio
+async def test_failed_summarization_reports_upstream_status(mock_targets, mock_cache, mock_idempotency):
+    """Test a summary call answering 502 with an HTML page is logged by status and the full history is sent."""
+    mock_targets["openai"]["llm"]["summarization"] = {"threshold_tokens": 50, "keep_recent": 2}
+    history = [
+        {"role": "user" if i % 2 == 0 else "assistant", "content": f"turn {i} " + "x" * 100} for i in range(6)
+    ]
+    bad_gateway = Mock(status_code=502, headers={})
+    bad_gateway.aread = AsyncMock(return_value=b"<html><body>Bad Gateway</body></html>")
+    answer = Mock(status_code=200, headers={})
+    answer.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "answer"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 200, "completion_tokens": 10, "total_tokens": 210},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client, \
+            patch("reliapi.app.services.logger") as mock_logger:
+        mock_client.return_value.request = AsyncMock(side_effect=[bad_gateway, answer])
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=history, model=None, max_tokens=None, temperature=0,
+            top_p=None, stop=None, stream=False, idempotency_key=None, cache_ttl=None,
+            targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency, request_id="test-req-summary-502",
+        )
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.history_summarized is None
+    assert "summarization failed, sending full history: status 502" in mock_logger.warning.call_args[0][0]
+    main_body = json.loads(mock_client.return_value.request.call_args_list[1].kwargs["body"])
+    assert len(main_body["messages"]) == 6
+
+
+@pytest.mark.asyncio
+async def test_summarization_runs_after_gates_and_counts_in_estimate(mock_targets, mock_cache, mock_idempotency):
+    """Test replayed or capped requests never pay for a summary, whose cost the hard cap estimate includes."""
+    mock_targets["openai"]["llm"]["summarization"] = {
+        "threshold_tokens": 50, "keep_recent": 2, "model": "gpt-4o-mini",
+    }
+    history = [{"role": "system", "content": "Be helpful."}] + [
+        {"role": "user" if i % 2 == 0 else "assistant", "content": f"turn {i} " + "x" * 100}
+        for i in range(6)
+    ]
+    call = dict(
+        target_name="openai", messages=history, model=None, max_tokens=None, temperature=0,
+        top_p=None, stop=None, stream=False, cache_ttl=None, targets=mock_targets, cache=mock_cache,
+        idempotency=mock_idempotency, request_id="test-req-summary-gates",
+    )
+
+    mock_idempotency.register_request.return_value = (False, "req-first", "hash-1")
+    mock_idempotency.make_request_hash.return_value = "hash-1"
+    mock_idempotency.get_result.return_value = {"data": {"content": "stored"}, "cost_usd": 0.001}
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock()
+        mock_client.return_value.close = AsyncMock()
+        replayed = await handle_llm_proxy(idempotency_key="idem-1", **call)
+
+        main_estimate = CostEstimator.estimate_from_messages("openai", "gpt-4o-mini", history, 1024)
+        summary_estimate = _summary_cost_estimate(mock_targets["openai"]["llm"], "openai", "gpt-4o-mini", history)
+        mock_targets["openai"]["llm"]["hard_cost_cap_usd"] = main_estimate + summary_estimate / 2
+        capped = await handle_llm_proxy(idempotency_key=None, **call)
+
+    assert replayed.meta.idempotent_hit is True
+    assert replayed.meta.history_summarized is None
+    assert isinstance(capped, ErrorResponse)
+    assert capped.error.code == "BUDGET_EXCEEDED"
+    assert capped.error.details["cost_estimate_usd"] == pytest.approx(main_estimate + summary_estimate)
+    mock_client.return_value.request.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_timeout_scales_with_max_tokens(mock_targets, mock_cache, mock_idempotency):
+    """Test llm.timeout_scaling sets the provider timeout from max_tokens and reports it in meta."""
+    mock_targets["openai"]["llm"]["timeout_scaling"] = {"base_ms": 2000, "per_token_ms": 10, "max_ms": 60000}
+    upstream = Mock()
+    upstream.status_code = 200
+    upstream.headers = {}
+    upstream.aread = AsyncMock(return_value=json.dumps({
+        "choices": [{"message": {"role": "assistant", "content": "hi"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+    }).encode())
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        mock_client.return_value.request = AsyncMock(return_value=upstream)
+        mock_client.return_value.close = AsyncMock()
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=500, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-timeout",
+        )
+        assert mock_client.call_args.kwargs["timeout_s"] == 7.0
+
+        # An explicit per-request timeout wins over the scaled one
+        overridden = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hello"}], model=None,
+            max_tokens=500, temperature=0, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=None, targets=mock_targets, cache=mock_cache, idempotency=mock_idempotency,
+            request_id="test-req-timeout-2", overrides={"timeout_ms": 3000},
+        )
+        assert mock_client.call_args.kwargs["timeout_s"] == 3.0
+
+    assert result.meta.timeout_ms == 7000
+    assert overridden.meta.timeout_ms is None
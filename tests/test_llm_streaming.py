@@ -0,0 +1,118 @@
+"""Tests for provider stream translation to OpenAI chat.completion.chunk deltas."""
+import json
+from contextlib import asynccontextmanager
+
+import pytest
+
+from reliapi.adapters.llm.anthropic import AnthropicAdapter
+from reliapi.adapters.llm.gemini import GeminiAdapter
+
+
+class _StreamResponse:
+    status_code = 200
+    request = None
+
+    def __init__(self, lines):
+        self._lines = lines
+
+    async def aiter_lines(self):
+        for line in self._lines:
+            yield line
+
+
+class _StreamClient:
+    def __init__(self, lines):
+        self.lines = lines
+        self.url = None
+
+    @asynccontextmanager
+    async def stream(self, method, url, **kwargs):
+        self.url = url
+        yield _StreamResponse(self.lines)
+
+
+async def _collect(adapter, client, api_path):
+    return [
+        chunk async for chunk in adapter.stream_chat(client, "https://api.example.com/v1", api_path, {}, {})
+    ]
+
+
+@pytest.mark.asyncio
+async def test_anthropic_stream_translated_to_openai_chunks():
+    """Test Anthropic events become role/content/finish chunks and one terminal usage chunk."""
+    events = [
+        ("message_start", {"message": {"id": "msg_1", "model": "claude-3-haiku-20240307",
+                                       "usage": {"input_tokens": 12, "output_tokens": 1}}}),
+        ("content_block_start", {"index": 0, "content_block": {"type": "text", "text": ""}}),
+        ("content_block_delta", {"index": 0, "delta": {"type": "text_delta", "text": "Hel"}}),
+        ("content_block_delta", {"index": 0, "delta": {"type": "text_delta", "text": "lo"}}),
+        ("content_block_stop", {"index": 0}),
+        ("message_delta", {"delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 5}}),
+        ("message_stop", {}),
+    ]
+    lines = [line for name, data in events for line in (f"event: {name}", f"data: {json.dumps(data)}", "")]
+
+    chunks = await _collect(AnthropicAdapter(), _StreamClient(lines), "/messages")
+
+    assert all(chunk["object"] == "chat.completion.chunk" and chunk["id"] == "msg_1" for chunk in chunks)
+    assert [chunk["choices"][0]["delta"] for chunk in chunks[:-2]] == [
+        {"role": "assistant", "content": ""}, {"content": "Hel"}, {"content": "lo"},
+    ]
+    assert chunks[-2]["choices"][0] == {"index": 0, "delta": {}, "finish_reason": "stop"}
+    assert chunks[-1]["_usage_only"] and chunks[-1]["choices"] == []
+    assert chunks[-1]["usage"]["prompt_tokens"] == 12
+    assert chunks[-1]["usage"]["completion_tokens"] == 5
+    assert chunks[-1]["usage"]["total_tokens"] == 17
+
+
+@pytest.mark.asyncio
+async def test_gemini_stream_translated_to_openai_chunks():
+    """Test Gemini candidates parts become content deltas with the last usageMetadata as usage."""
+    responses = [
+        {"responseId": "r1", "modelVersion": "gemini-1.5-flash",
+         "candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "Hel"}]}}],
+         "usageMetadata": {"promptTokenCount": 8, "candidatesTokenCount": 1}},
+        {"responseId": "r1", "modelVersion": "gemini-1.5-flash",
+         "candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "lo"}]},
+                         "finishReason": "MAX_TOKENS"}],
+         "usageMetadata": {"promptTokenCount": 8, "candidatesTokenCount": 4}},
+    ]
+    client = _StreamClient([f"data: {json.dumps(data)}" for data in responses])
+
+    chunks = await _collect(GeminiAdapter(), client, "/models/gemini-1.5-flash:generateContent")
+
+    assert client.url == "https://api.example.com/v1/models/gemini-1.5-flash:streamGenerateContent?alt=sse"
+    assert [chunk["choices"][0]["delta"] for chunk in chunks[:-2]] == [
+        {"role": "assistant", "content": ""}, {"content": "Hel"}, {"content": "lo"},
+    ]
+    assert chunks[-2]["choices"][0]["finish_reason"] == "length"
+    assert chunks[-1]["usage"] == {
+        "prompt_tokens": 8, "completion_tokens": 4, "cached_prompt_tokens": 0,
+        "cache_write_tokens": 0, "total_tokens": 12,
+    }
+
+
+def test_gemini_request_and_response_mapping():
+    """Test system/assistant messages map to systemInstruction/model and candidates parse back."""
+    adapter = GeminiAdapter()
+    payload = adapter.prepare_request(
+        messages=[
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": "Hi"},
+            {"role": "assistant", "content": "Hello"},
+            {"role": "user", "content": "Bye"},
+        ],
+        model="gemini-1.5-flash",
+        max_tokens=50,
+    )
+
+    assert payload["systemInstruction"] == {"parts": [{"text": "Be brief."}]}
+    assert [c["role"] for c in payload["contents"]] == ["user", "model", "user"]
+    assert payload["generationConfig"] == {"maxOutputTokens": 50}
+    assert adapter.api_path("gemini-1.5-flash") == "/models/gemini-1.5-flash:generateContent"
+
+    parsed = adapter.parse_response({
+        "candidates": [{"content": {"role": "model", "parts": [{"text": "Bye!"}]}, "finishReason": "STOP"}],
+    })
+    assert parsed["content"] == "Bye!"
+    assert parsed["choices"][0]["index"] == 0
@@ -0,0 +1,92 @@
+"""Tests for core/maintenance.py and cache-only serving in app/services.py."""
+import os
+
+import pytest
+from unittest.mock import Mock, patch
+
+from reliapi.app.dependencies import AppState
+from reliapi.app.routes.health import health_check
+from reliapi.app.schemas import ErrorResponse, SuccessResponse
+from reliapi.app.services import handle_http_proxy
+from reliapi.core.cache import Cache
+from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.maintenance import MaintenanceMode
+
+TARGETS = {
+    "my_api": {"base_url": "https://api.example.com", "timeout_ms": 10000, "cache": {"enabled": True, "ttl_s": 300}},
+}
+
+
+def _proxy_get(cache):
+    idempotency = Mock(spec=IdempotencyManager)
+    return handle_http_proxy(
+        target_name="my_api", method="GET", path="/items", headers=None, query=None, body=None,
+        idempotency_key=None, cache_ttl=None, targets=TARGETS, cache=cache,
+        idempotency=idempotency, request_id="test-123",
+    )
+
+
+def test_global_and_target_scopes():
+    """Test global maintenance covers every target and can be lifted per scope."""
+    mode = MaintenanceMode()
+    mode.set(True, target="my_api", reason="provider window")
+    assert mode.is_active("my_api") is True
+    assert mode.is_active("other") is False
+
+    mode.set(True)
+    assert mode.is_active("other") is True
+    assert mode.status()["targets"]["my_api"]["reason"] == "provider window"
+
+    mode.set(False)
+    mode.set(False, target="my_api")
+    assert mode.status() == {"global": None, "targets": {}}
+
+
+@pytest.mark.asyncio
+async def test_cache_miss_rejected_in_maintenance():
+    """Test a cache miss returns 503 MAINTENANCE_CACHE_ONLY without calling upstream."""
+    cache = Mock(spec=Cache)
+    cache.get.return_value = None
+    mode = MaintenanceMode()
+    mode.set(True, target="my_api")
+
+    with patch("reliapi.app.services.maintenance_mode", mode), \
+            patch("reliapi.app.services.UpstreamHTTPClient") as client_cls:
+        result = await _proxy_get(cache)
+
+    assert isinstance(result, ErrorResponse)
+    assert result.error.code == "MAINTENANCE_CACHE_ONLY"
+    assert result.error.status_code == 503
+    client_cls.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_cache_hit_served_in_maintenance():
+    """Test cached responses are still served during maintenance."""
+    cache = Mock(spec=Cache)
+    cache.get.return_value = {"status_code": 200, "headers": {}, "body": {"items": []}}
+    mode = MaintenanceMode()
+    mode.set(True)
+
+    with patch("reliapi.app.services.maintenance_mode", mode):
+        result = await _proxy_get(cache)
+
+    assert isinstance(result, SuccessResponse)
+    assert result.meta.cache_hit is True
+
+
+@pytest.mark.asyncio
+async def test_health_hides_reason_without_admin_key():
+    """Test /health lists maintenance, with the reason only for the admin key."""
+    mode = MaintenanceMode()
+    mode.set(True, target="my_api", reason="provider window")
+
+    with patch.dict(os.environ, {"RELIAPI_ADMIN_KEY": "admin-secret"}), \
+            patch("reliapi.app.routes.health.maintenance_mode", mode), \
+            patch("reliapi.app.routes.health.get_app_state", return_value=AppState()):
+        public = await health_check(Mock(headers={}))
+        admin = await health_check(Mock(headers={"X-Admin-Key": "admin-secret"}))
+
+    assert "reason" not in public.maintenance["targets"]["my_api"]
+    assert public.maintenance["targets"]["my_api"]["since"]
+    assert admin.maintenance["targets"]["my_api"]["reason"] == "provider window"
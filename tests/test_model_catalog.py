@@ -0,0 +1,39 @@
+"""Tests for core/model_catalog.py."""
+from reliapi.core.model_catalog import LiveModelCache, target_models
+
+
+def test_target_models_merges_config_pricing_and_live():
+    """Test configured metadata wins and priced/live models are listed too."""
+    target_config = {
+        "llm": {
+            "default_model": "gpt-4o-mini",
+            "models": {"gpt-4o": {"context_window": 128000, "capabilities": ["vision", "tools"]}},
+            "auto_models": [
+                {"model": "gpt-4o-mini", "context_window": 128000, "capabilities": ["json"]},
+                {"model": "claude-3-haiku-20240307", "target": "anthropic", "context_window": 200000},
+            ],
+        }
+    }
+
+    catalog = {m["id"]: m for m in target_models("openai", target_config, "openai", live_models=["o1-preview"])}
+
+    assert catalog["gpt-4o"]["context_window"] == 128000
+    assert catalog["gpt-4o"]["capabilities"] == {"vision": True, "tools": True, "json_mode": False}
+    assert catalog["gpt-4o"]["pricing"] == {"prompt_per_1k_usd": 0.005, "completion_per_1k_usd": 0.015}
+    assert catalog["gpt-4o-mini"]["capabilities"]["json_mode"] is True
+    assert catalog["gpt-3.5-turbo"]["source"] == "pricing"
+    assert catalog["o1-preview"]["source"] == "live"
+    assert catalog["o1-preview"]["pricing"] is None
+    # Auto-model candidates of other targets are not listed here
+    assert "claude-3-haiku-20240307" not in catalog
+
+
+def test_live_model_cache_expires():
+    """Test live listings are reused only within the TTL."""
+    cache = LiveModelCache(ttl_s=0)
+    cache.set("openai", ["gpt-4o"])
+    assert cache.get("openai") is None
+
+    cache = LiveModelCache(ttl_s=60)
+    cache.set("openai", ["gpt-4o"])
+    assert cache.get("openai") == ["gpt-4o"]
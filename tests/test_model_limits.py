@@ -0,0 +1,99 @@
+"""Tests for core/model_limits.py."""
+import pytest
+from unittest.mock import patch
+
+from pydantic import ValidationError
+
+from reliapi.config.schema import ModelLimitConfig
+from reliapi.core.model_limits import ModelRateLimiter, estimate_request_tokens, resolve_model_limits
+
+
+def test_resolve_model_limits_wildcard():
+    """Test exact model limits win over the '*' default."""
+    llm_config = {"model_limits": {"gpt-4o": {"rpm": 10}, "*": {"rpm": 100}}}
+    assert resolve_model_limits(llm_config, "gpt-4o") == {"rpm": 10}
+    assert resolve_model_limits(llm_config, "gpt-4o-mini") == {"rpm": 100}
+    assert resolve_model_limits({}, "gpt-4o") is None
+
+
+def test_estimate_request_tokens():
+    """Test estimate covers prompt chars and max completion."""
+    messages = [{"role": "user", "content": "x" * 400}]
+    assert estimate_request_tokens(messages, 50) == 150
+    assert estimate_request_tokens(messages, None) == 100
+
+
+@pytest.mark.asyncio
+async def test_rpm_reject_with_retry_after():
+    """Test requests over rpm are rejected with time until a slot frees."""
+    limiter = ModelRateLimiter()
+    limits = {"rpm": 2, "on_limit": "reject"}
+
+    with patch("reliapi.core.model_limits.time.time", return_value=1000.0):
+        assert (await limiter.acquire("openai", "gpt-4o", limits, 10))[0] is not None
+    with patch("reliapi.core.model_limits.time.time", return_value=1010.0):
+        assert (await limiter.acquire("openai", "gpt-4o", limits, 10))[0] is not None
+        reservation, retry_after, limiting = await limiter.acquire("openai", "gpt-4o", limits, 10)
+
+    assert reservation is None
+    assert limiting == "rpm"
+    assert retry_after == pytest.approx(50.0)
+
+    # Limits are tracked per target+model
+    with patch("reliapi.core.model_limits.time.time", return_value=1010.0):
+        assert (await limiter.acquire("openai", "gpt-4o-mini", limits, 10))[0] is not None
+
+
+@pytest.mark.asyncio
+async def test_tpm_reconciled_with_actual_usage():
+    """Test TPM uses actual token usage once reconciled."""
+    limiter = ModelRateLimiter()
+    limits = {"tpm": 1000}
+
+    reservation, _, _ = await limiter.acquire("openai", "gpt-4o", limits, 900)
+    assert limiter.get_remaining("openai", "gpt-4o", limits) == {"rpm": None, "tpm": 100}
+
+    # Over-estimated request: frees capacity for the next one
+    limiter.reconcile(reservation, 200)
+    assert limiter.get_remaining("openai", "gpt-4o", limits)["tpm"] == 800
+    assert (await limiter.acquire("openai", "gpt-4o", limits, 700))[0] is not None
+
+    reservation, retry_after, limiting = await limiter.acquire("openai", "gpt-4o", limits, 700)
+    assert reservation is None
+    assert limiting == "tpm"
+
+    # A request larger than tpm can never fit
+    assert await limiter.acquire("openai", "gpt-4o", limits, 5000) == (None, None, "tpm")
+
+
+@pytest.mark.asyncio
+async def test_delay_mode_waits_for_capacity():
+    """Test on_limit=delay queues the request until a slot frees."""
+    limiter = ModelRateLimiter()
+    limits = {"rpm": 1, "on_limit": "delay", "max_delay_ms": 2000}
+    clock = {"now": 1000.0}
+
+    async def fake_sleep(seconds):
+        clock["now"] += seconds
+
+    with patch("reliapi.core.model_limits.time.time", side_effect=lambda: clock["now"]), \
+            patch("reliapi.core.model_limits.asyncio.sleep", new=fake_sleep):
+        await limiter.acquire("openai", "gpt-4o", limits, 10)
+        clock["now"] = 1059.0
+        reservation, _, _ = await limiter.acquire("openai", "gpt-4o", limits, 10)
+
+        assert reservation is not None
+        assert reservation.delayed_by == "rpm"
+        assert reservation.delayed_ms == 1000
+
+        # Wait longer than max_delay_ms: rejected instead
+        reservation, retry_after, _ = await limiter.acquire("openai", "gpt-4o", limits, 10)
+        assert reservation is None
+        assert retry_after > 2
+
+
+def test_model_limit_config_validation():
+    """Test on_limit accepts only reject or delay."""
+    assert ModelLimitConfig(rpm=10).on_limit == "reject"
+    with pytest.raises(ValidationError):
+        ModelLimitConfig(rpm=10, on_limit="queue")
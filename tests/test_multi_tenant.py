@@ -381,6 +381,31 @@ tenants:
             assert "anthropic-backup" in tenant_b.fallback_targets["openai-primary"]
 
 
+class TestMultiTenantDefaults:
+    """Test per-tenant request defaults."""
+    
+    def test_defaults_fill_omitted_params(self):
+        """Test omitted params inherit tenant defaults and request values win."""
+        from reliapi.app.dependencies import apply_tenant_defaults
+        from reliapi.app.schemas import LLMProxyRequest
+        
+        state = Mock()
+        state.config_loader.get_tenant.return_value = TenantConfig(
+            api_key="sk-acme",
+            defaults={"*": {"cache": 300, "max_tokens": 256}, "openai": {"model": "gpt-4o-mini", "max_tokens": 512}},
+        ).model_dump(exclude_none=True)
+        request = LLMProxyRequest(target="openai", messages=[{"role": "user", "content": "Hi"}], cache=60)
+        
+        with patch("reliapi.app.dependencies.get_app_state", return_value=state):
+            resolved = apply_tenant_defaults("acme", "openai", request)
+            assert apply_tenant_defaults(None, "openai", request) is None
+        
+        assert request.model == "gpt-4o-mini"
+        assert request.max_tokens == 512
+        assert request.cache == 60
+        assert resolved == {"model": "gpt-4o-mini", "max_tokens": 512, "cache": 60, "inherited": ["model", "max_tokens"]}
+
+
 class TestMultiTenantIntegration:
     """Integration tests for multi-tenant functionality."""
     
@@ -0,0 +1,131 @@
+"""Tests for app/openai_compat.py (OpenAI <-> ReliAPI translation)."""
+import json
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+from fastapi.responses import StreamingResponse
+
+from reliapi.app.openai_compat import (
+    meta_headers,
+    to_llm_proxy_request,
+    to_openai_response,
+    to_openai_stream,
+)
+from reliapi.app.routes.openai_compat import chat_completions
+from reliapi.app.schemas import OpenAIChatCompletionRequest
+
+
+def test_openai_body_to_llm_request():
+    """Test OpenAI body fields map onto the LLM proxy request."""
+    body = OpenAIChatCompletionRequest(
+        model="gpt-4o-mini",
+        messages=[
+            {"role": "system", "content": "Be brief"},
+            {"role": "user", "content": [{"type": "text", "text": "Hi "}, {"type": "text", "text": "there"}]},
+        ],
+        max_completion_tokens=50,
+        stop="END",
+        user="u-1",  # Unsupported OpenAI field, accepted and ignored
+    )
+
+    request = to_llm_proxy_request(body, target="openai", idempotency_key="idem-1", cache_ttl=600)
+
+    assert request.target == "openai"
+    assert request.messages[1] == {"role": "user", "content": "Hi there"}
+    assert request.max_tokens == 50
+    assert request.stop == ["END"]
+    assert request.idempotency_key == "idem-1"
+    assert request.cache == 600
+
+
+def test_success_envelope_to_openai_response():
+    """Test a ReliAPI success envelope becomes a chat.completion body."""
+    status, body = to_openai_response({
+        "success": True,
+        "data": {
+            "content": "Hello",
+            "role": "assistant",
+            "finish_reason": "end_turn",
+            "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4},
+        },
+        "meta": {"request_id": "req_1", "model": "claude-3-haiku-20240307"},
+    })
+
+    assert status == 200
+    assert body["id"] == "chatcmpl-req_1"
+    assert body["object"] == "chat.completion"
+    assert body["choices"][0]["message"] == {"role": "assistant", "content": "Hello"}
+    assert body["choices"][0]["finish_reason"] == "stop"
+    assert body["usage"]["total_tokens"] == 4
+
+
+def test_error_envelope_to_openai_error():
+    """Test ReliAPI errors keep their status and code in the OpenAI error shape."""
+    status, body = to_openai_response({
+        "success": False,
+        "error": {"type": "budget_error", "code": "BUDGET_EXCEEDED", "message": "over cap", "status_code": 400},
+        "meta": {"request_id": "req_1"},
+    })
+
+    assert status == 400
+    assert body == {"error": {"message": "over cap", "type": "budget_error", "code": "BUDGET_EXCEEDED", "param": None}}
+
+
+def test_meta_headers():
+    """Test meta is exposed as X-ReliAPI-* headers."""
+    headers = meta_headers({"request_id": "req_1", "cache_hit": True, "retries": 2, "cost_usd": 0.0001, "trace_id": None})
+
+    assert headers["X-Request-ID"] == "req_1"
+    assert headers["X-ReliAPI-Cache-Hit"] == "true"
+    assert headers["X-ReliAPI-Retries"] == "2"
+    assert headers["X-ReliAPI-Cost-USD"] == "0.000100"
+
+
+async def _events(*events):
+    for event in events:
+        yield event
+
+
+@pytest.mark.asyncio
+async def test_stream_events_to_openai_chunks():
+    """Test ReliAPI stream events become chat.completion.chunk events and [DONE]."""
+    events = _events(
+        'event: meta\ndata: {"request_id": "req_1", "model": "gpt-4o-mini"}\n\n',
+        'event: chunk\ndata: {"delta": "Hi", "finish_reason": null}\n\n',
+        'event: done\ndata: {"finish_reason": "stop", "cost_usd": 0.001}\n\n',
+    )
+
+    out = [event async for event in to_openai_stream(events, "req_1", None)]
+
+    assert out[-1] == "data: [DONE]\n\n"
+    chunks = [json.loads(event[len("data: "):]) for event in out[:-1]]
+    assert chunks[0]["choices"][0]["delta"] == {"role": "assistant", "content": ""}
+    assert chunks[1]["choices"][0]["delta"] == {"content": "Hi"}
+    assert chunks[2]["choices"][0]["finish_reason"] == "stop"
+    assert all(chunk["object"] == "chat.completion.chunk" and chunk["model"] == "gpt-4o-mini" for chunk in chunks)
+
+
+@pytest.mark.asyncio
+async def test_stream_error_mid_stream():
+    """Test a mid-stream error is sent as an OpenAI error event and ends the stream."""
+    events = _events(
+        'event: meta\ndata: {"request_id": "req_1"}\n\n',
+        'event: error\ndata: {"code": "STREAM_IDLE_TIMEOUT", "message": "stalled", "upstream_status": 504}\n\n',
+    )
+
+    out = [event async for event in to_openai_stream(events, "req_1", "gpt-4o-mini")]
+
+    assert json.loads(out[-1][len("data: "):])["error"]["code"] == "STREAM_IDLE_TIMEOUT"
+
+
+@pytest.mark.asyncio
+async def test_chat_completions_empty_stream():
+    """Test a stream that ends before its first event is returned as a 502 error."""
+    body = OpenAIChatCompletionRequest(model="gpt-4o-mini", messages=[{"role": "user", "content": "Hi"}], stream=True)
+    streaming = StreamingResponse(_events(), media_type="text/event-stream", headers={"X-Request-ID": "req_1"})
+
+    with patch("reliapi.app.routes.openai_compat.proxy_llm", new=AsyncMock(return_value=streaming)):
+        response = await chat_completions(body, Mock(headers={}))
+
+    assert response.status_code == 502
+    assert json.loads(response.body)["error"]["code"] == "UPSTREAM_STREAM_INTERRUPTED"
@@ -0,0 +1,62 @@
+"""Tests for core/pinned_responses.py."""
+import json
+from unittest.mock import Mock, patch
+
+import pytest
+
+from reliapi.app.services import handle_llm_proxy
+from reliapi.core.pinned_responses import PinnedResponses, pin_id
+
+
+@patch('reliapi.core.pinned_responses.redis')
+def test_pin_get_and_unpin(mock_redis_module, mock_redis):
+    """Test pins are stored without TTL and matched on the request fields."""
+    mock_redis_module.from_url.return_value = mock_redis
+    pins = PinnedResponses("redis://localhost:6379/0")
+    request = {"messages": [{"role": "user", "content": "What is ReliAPI?"}], "model": None, "seed": None}
+
+    entry = pins.pin("openai", request, "A reliability layer.", note="FAQ")
+
+    key, field, raw = mock_redis.hset.call_args[0]
+    assert key == "reliapi:pins"
+    assert field == entry["id"] == pin_id("openai", request)
+    assert json.loads(raw)["request"] == {"messages": request["messages"]}
+    mock_redis.setex.assert_not_called()
+
+    mock_redis.hget.return_value = raw
+    assert pins.get("openai", {"messages": request["messages"]})["content"] == "A reliability layer."
+    assert pin_id("openai", request, tenant="acme") != entry["id"]
+
+    mock_redis.hdel.return_value = 1
+    assert pins.unpin(entry["id"]) is True
+    mock_redis.hdel.assert_called_once_with("reliapi:pins", entry["id"])
+
+
+@pytest.mark.asyncio
+async def test_pinned_response_served_with_cache_disabled():
+    """Test a pinned request is a cache hit even when the target's cache is off."""
+    targets = {
+        "openai": {
+            "base_url": "https://api.openai.com/v1",
+            "cache": {"enabled": False},
+            "llm": {"provider": "openai", "default_model": "gpt-4o-mini"},
+        }
+    }
+    pins = Mock(spec=PinnedResponses)
+    pins.get.return_value = {"id": "abc", "content": "Pinned answer"}
+    cache = Mock()
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as mock_client:
+        result = await handle_llm_proxy(
+            target_name="openai", messages=[{"role": "user", "content": "Hi"}], model=None,
+            max_tokens=None, temperature=0.9, top_p=None, stop=None, stream=False, idempotency_key=None,
+            cache_ttl=0, targets=targets, cache=cache, idempotency=Mock(),
+            request_id="test-req-pinned", pins=pins,
+        )
+
+    mock_client.assert_not_called()
+    cache.get.assert_not_called()
+    assert result.success is True
+    assert result.data["content"] == "Pinned answer"
+    assert result.meta.cache_hit is True
+    assert result.meta.pinned is True
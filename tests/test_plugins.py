@@ -0,0 +1,127 @@
+"""Tests for core/plugins.py and plugin hooks in handle_http_proxy."""
+import json
+import sys
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+
+from reliapi.app.services import handle_http_proxy
+from reliapi.core.cache import Cache
+from reliapi.core.errors import ErrorCode
+from reliapi.core.idempotency import IdempotencyManager
+from reliapi.core.plugins import PluginError, WasmPlugin, _Runtime, transform_request
+
+
+@pytest.fixture
+def targets():
+    return {
+        "signed_api": {
+            "base_url": "https://api.example.com",
+            "cache": {"enabled": True, "ttl_s": 300},
+            "plugins": [{"path": "/plugins/sign.wasm", "name": "sign", "settings": {"key_id": "k1"}}],
+        }
+    }
+
+
+@pytest.fixture
+def cache():
+    cache = Mock(spec=Cache)
+    cache.get.return_value = None
+    cache.set.return_value = None
+    return cache
+
+
+def _fake_call(hook, payload):
+    """Stand-in for the WASM module: signs requests, tags responses."""
+    message = json.loads(payload)
+    if hook == "transform_request":
+        headers = {**(message["headers"] or {}), "X-Signature": f"{message['settings']['key_id']}:{message['path']}"}
+        return json.dumps({"headers": headers, "settings": "ignored"}).encode()
+    return json.dumps({"body": {**message["body"], "signed": True}}).encode()
+
+
+def test_missing_runtime_is_a_plugin_error():
+    """Test plugins fail with a clear error when wasmtime is not installed."""
+    with patch.dict(sys.modules, {"wasmtime": None}):
+        with pytest.raises(PluginError, match="require the wasmtime package"):
+            _Runtime().engine("sign")
+
+
+# Real module for the ABI: transform_request returns a fixed rewrite, transform_response spins forever
+_WAT = r"""
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{\"headers\": {\"X-Plugin\": \"wasm\"}}")
+  (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+  (func (export "transform_request") (param i32 i32) (result i64) (i64.const 33))
+  (func (export "transform_response") (param i32 i32) (result i64) (loop $spin (br $spin)) (i64.const 0))
+)
+"""
+
+
+def test_wasm_module_abi_and_time_limit(tmp_path):
+    """Test a real module's output is applied, and a runaway hook is stopped at max_time_ms."""
+    pytest.importorskip("wasmtime", reason="WASM plugin runtime tests need wasmtime")
+    path = tmp_path / "rewrite.wat"
+    path.write_text(_WAT)
+    plugin = WasmPlugin({"path": str(path), "name": "rewrite", "max_time_ms": 20})
+
+    message = {"method": "GET", "path": "/orders", "headers": {"Accept": "*/*"}, "query": None, "body": None}
+    assert plugin.transform("transform_request", message) == {**message, "headers": {"X-Plugin": "wasm"}}
+    with pytest.raises(PluginError, match="exceeded 20ms"):
+        plugin.transform("transform_response", {"status_code": 200, "headers": {}, "body": None})
+
+
+@pytest.mark.asyncio
+async def test_binary_request_body_is_a_plugin_error(targets):
+    """Test a non-UTF-8 request body fails with PluginError instead of reaching the plugin."""
+    plugins = [WasmPlugin(config) for config in targets["signed_api"]["plugins"]]
+    with patch.object(WasmPlugin, "_call", side_effect=_fake_call) as call:
+        with pytest.raises(PluginError, match="Plugin 'sign': transform_request needs a UTF-8 request body"):
+            await transform_request(plugins, "POST", "/upload", None, None, b"\x89PNG\r\n\x1a\n\xff")
+    call.assert_not_called()
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_runs_plugin_hooks(targets, cache):
+    """Test transform_request rewrites the upstream call and transform_response the cached result."""
+    upstream = Mock(status_code=200, headers={})
+    upstream.aread = AsyncMock(return_value=b'{"id": 7}')
+
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls, \
+            patch.object(WasmPlugin, "_call", side_effect=_fake_call):
+        client_cls.return_value.request = AsyncMock(return_value=upstream)
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="signed_api", method="GET", path="/orders", headers={"Accept": "application/json"},
+            query=None, body=None, idempotency_key=None, cache_ttl=None, targets=targets, cache=cache,
+            idempotency=Mock(spec=IdempotencyManager), request_id="test-123",
+        )
+
+    sent = client_cls.return_value.request.call_args.kwargs
+    assert sent["headers"] == {"Accept": "application/json", "X-Signature": "k1:/orders"}
+    assert sent["path"] == "/orders"
+    assert result.data["body"] == {"id": 7, "signed": True}
+    assert cache.set.call_args[0][4]["body"] == {"id": 7, "signed": True}
+    # Cache identity is the request as received
+    assert cache.set.call_args[0][2] == {"Accept": "application/json"}
+
+
+@pytest.mark.asyncio
+async def test_http_proxy_plugin_invalid_output(targets, cache):
+    """Test a plugin returning non-JSON fails the request with PLUGIN_ERROR before the upstream call."""
+    with patch("reliapi.app.services.UpstreamHTTPClient") as client_cls, \
+            patch.object(WasmPlugin, "_call", return_value=b"not json"):
+        client_cls.return_value.request = AsyncMock()
+        client_cls.return_value.close = AsyncMock()
+        result = await handle_http_proxy(
+            target_name="signed_api", method="GET", path="/orders", headers=None, query=None, body=None,
+            idempotency_key=None, cache_ttl=None, targets=targets, cache=cache,
+            idempotency=Mock(spec=IdempotencyManager), request_id="test-123",
+        )
+
+    assert result.success is False
+    assert result.error.code == ErrorCode.PLUGIN_ERROR.value
+    assert result.error.status_code == 500
+    assert "sign" in result.error.message
+    client_cls.return_value.request.assert_not_called()
@@ -0,0 +1,39 @@
+"""Tests for core/post_process.py (post_process)."""
+from types import SimpleNamespace
+
+from reliapi.app.routes.proxy import post_process_result
+from reliapi.core.post_process import post_process_text
+
+
+def test_trim_and_unwrap_single_fence():
+    """Test trim strips whitespace and trim_fences unwraps only a whole-response fence."""
+    assert post_process_text("  {\"a\": 1}\n\n", "trim") == '{"a": 1}'
+    assert post_process_text("\n```json\n{\"a\": 1}\n```\n", "trim_fences") == '{"a": 1}'
+    assert post_process_text("```\nplain\n```", "trim_fences") == "plain"
+    assert post_process_text("```json\n{}\n```", "trim") == "```json\n{}\n```"
+    # Prose around or between fences is left alone
+    mixed = "Here you go:\n```json\n{}\n```"
+    assert post_process_text(mixed, "trim_fences") == mixed
+    two = "```\na\n```\n\n```\nb\n```"
+    assert post_process_text(two, "trim_fences") == two
+    assert post_process_text(" x ", "none") == " x "
+    assert post_process_text(None, "trim") is None
+
+
+def test_result_processed_without_touching_original():
+    """Test content and choices are cleaned on a copy, with raw_content and meta flag."""
+    raw = "```json\n{\"ok\": true}\n```"
+    data = {"content": raw, "choices": [{"index": 0, "message": {"role": "assistant", "content": raw}}]}
+    result = SimpleNamespace(success=True, data=data, meta=SimpleNamespace(post_processed=None))
+
+    post_process_result(result, "trim_fences", include_raw=True)
+
+    assert result.data["content"] == '{"ok": true}'
+    assert result.data["choices"][0]["message"]["content"] == '{"ok": true}'
+    assert result.data["raw_content"] == raw
+    assert result.meta.post_processed is True
+    assert data["content"] == raw and data["choices"][0]["message"]["content"] == raw
+
+    unchanged = SimpleNamespace(success=True, data={"content": "ok"}, meta=SimpleNamespace(post_processed=None))
+    post_process_result(unchanged, "trim")
+    assert unchanged.meta.post_processed is None and "raw_content" not in unchanged.data
@@ -0,0 +1,99 @@
+"""Tests for provider prompt-prefix caching (markers, usage parsing, cost)."""
+import pytest
+
+from reliapi.adapters.llm.anthropic import AnthropicAdapter
+from reliapi.adapters.llm.openai import OpenAIAdapter
+from reliapi.app.services import _merge_stream_usage
+
+
+def _conversation():
+    return [
+        {"role": "user", "content": "Summarize this contract: ..."},
+        {"role": "assistant", "content": "It is a lease agreement."},
+        {"role": "user", "content": "Who are the parties?"},
+    ]
+
+
+def test_anthropic_marks_prefix_before_latest_message():
+    """Test cache_control goes on the message before the latest turn."""
+    adapter = AnthropicAdapter()
+    messages = _conversation()
+    payload = adapter.prepare_request(messages=messages, model="claude-3-haiku-20240307")
+
+    cached = adapter.apply_prompt_caching(payload)
+
+    assert cached["messages"][1]["content"] == [
+        {"type": "text", "text": "It is a lease agreement.", "cache_control": {"type": "ephemeral"}}
+    ]
+    assert cached["messages"][0] == messages[0]
+    assert cached["messages"][2] == messages[2]
+    # Original request messages are untouched
+    assert messages[1]["content"] == "It is a lease agreement."
+
+
+def test_anthropic_single_message_not_marked():
+    """Test there is no stable prefix to mark on the first turn."""
+    adapter = AnthropicAdapter()
+    payload = adapter.prepare_request(messages=[{"role": "user", "content": "Hi"}], model="claude-3-haiku-20240307")
+
+    assert adapter.apply_prompt_caching(payload) == payload
+
+
+def test_anthropic_usage_includes_cache_reads_and_writes():
+    """Test Anthropic usage is normalized with cached tokens counted as prompt tokens."""
+    usage = AnthropicAdapter().parse_usage({
+        "usage": {
+            "input_tokens": 50,
+            "cache_read_input_tokens": 1000,
+            "cache_creation_input_tokens": 200,
+            "output_tokens": 30,
+        }
+    })
+
+    assert usage == {
+        "prompt_tokens": 1250,
+        "completion_tokens": 30,
+        "cached_prompt_tokens": 1000,
+        "cache_write_tokens": 200,
+    }
+
+
+def test_openai_usage_reads_cached_tokens():
+    """Test OpenAI prompt_tokens_details.cached_tokens is reported."""
+    usage = OpenAIAdapter().parse_usage({
+        "usage": {
+            "prompt_tokens": 2000,
+            "completion_tokens": 10,
+            "prompt_tokens_details": {"cached_tokens": 1536},
+        }
+    })
+
+    assert usage["prompt_tokens"] == 2000
+    assert usage["cached_prompt_tokens"] == 1536
+
+
+def test_cost_discounts_cached_tokens():
+    """Test cached prompt tokens are billed at the provider discount."""
+    openai = OpenAIAdapter()
+    full = openai.get_cost_usd("gpt-4o-mini", prompt_tokens=2000, completion_tokens=0)
+    cached = openai.get_cost_usd("gpt-4o-mini", prompt_tokens=2000, completion_tokens=0, cached_prompt_tokens=2000)
+    assert cached == pytest.approx(full * 0.5)
+
+    anthropic = AnthropicAdapter()
+    model = "claude-3-haiku-20240307"
+    full = anthropic.get_cost_usd(model, prompt_tokens=1000, completion_tokens=0)
+    assert anthropic.get_cost_usd(model, 1000, 0, cached_prompt_tokens=1000) == pytest.approx(full * 0.1)
+    assert anthropic.get_cost_usd(model, 1000, 0, cache_write_tokens=1000) == pytest.approx(full * 1.25)
+
+
+def test_merge_stream_usage_across_chunks():
+    """Test usage split across stream chunks (Anthropic message_start/message_delta) is combined."""
+    usage = _merge_stream_usage({}, {"prompt_tokens": 1200, "cached_prompt_tokens": 1000, "cache_write_tokens": 0})
+    usage = _merge_stream_usage(usage, {"completion_tokens": 42})
+
+    assert usage == {
+        "prompt_tokens": 1200,
+        "completion_tokens": 42,
+        "cached_prompt_tokens": 1000,
+        "cache_write_tokens": 0,
+    }
@@ -0,0 +1,92 @@
+"""Tests for core/regions.py and regional failover in UpstreamHTTPClient."""
+from unittest.mock import AsyncMock, Mock
+
+import httpx
+import pytest
+
+from reliapi.core.circuit_breaker import CircuitBreaker
+from reliapi.core.http_client import UpstreamHTTPClient
+from reliapi.core.regions import RegionRouter, region_name
+from reliapi.core.retry import RetryMatrix
+
+REGIONS = [
+    {"name": "eastus", "base_url": "https://eastus.example.com/v1"},
+    {"name": "westeurope", "base_url": "https://westeurope.example.com/v1/"},
+    {"name": "japaneast", "base_url": "https://japaneast.example.com/v1"},
+]
+
+
+def test_rank_prefers_fastest_healthy_region():
+    """Test unmeasured regions are tried first, then by latency EWMA, with open circuits last."""
+    router = RegionRouter()
+    breaker = CircuitBreaker(failures_to_open=1)
+
+    router.observe("azure", "https://eastus.example.com/v1", 0.4)
+    router.observe("azure", "https://westeurope.example.com/v1", 0.2)
+    assert [r["name"] for r in router.rank("azure", REGIONS, breaker)] == ["japaneast", "westeurope", "eastus"]
+
+    router.observe("azure", "https://japaneast.example.com/v1", 0.3)
+    # EWMA: 0.2 + 0.3 * (1.0 - 0.2)
+    router.observe("azure", "https://westeurope.example.com/v1", 1.0)
+    assert router.latency_ms("azure", "https://westeurope.example.com/v1") == 440.0
+    assert [r["name"] for r in router.rank("azure", REGIONS, breaker)] == ["japaneast", "eastus", "westeurope"]
+
+    breaker.record_failure("https://japaneast.example.com/v1")
+    assert [r["name"] for r in router.rank("azure", REGIONS, breaker)] == ["eastus", "westeurope", "japaneast"]
+    assert region_name(REGIONS, "https://westeurope.example.com/v1") == "westeurope"
+
+
+@pytest.mark.asyncio
+async def test_request_fails_over_to_next_region():
+    """Test a failing region hands the request to the next one, which records its latency."""
+    observed = []
+    client = UpstreamHTTPClient(
+        base_url="https://eastus.example.com/v1",
+        retry_matrix={"net": RetryMatrix(attempts=1)},
+        failover_base_urls=["https://westeurope.example.com/v1"],
+        latency_observer=lambda base_url, latency_s: observed.append(base_url),
+    )
+    success = Mock(status_code=200, is_success=True, headers={})
+
+    async def fake_request(method, url, **kwargs):
+        if url.startswith("https://eastus."):
+            raise httpx.ConnectError("Connection refused")
+        return success
+
+    client.client.request = AsyncMock(side_effect=fake_request)
+    response = await client.request("POST", "/chat/completions")
+
+    assert response is success
+    assert client.served_base_url == "https://westeurope.example.com/v1"
+    assert observed == ["https://westeurope.example.com/v1"]
+    assert client.client.request.call_args.kwargs["url"] == "https://westeurope.example.com/v1/chat/completions"
+
+    # Client errors are not failed over
+    client.client.request = AsyncMock(return_value=Mock(status_code=400, is_success=False, headers={}))
+    response = await client.request("POST", "/chat/completions")
+    assert response.status_code == 400
+    assert client.served_base_url == "https://eastus.example.com/v1"
+    await client.close()
+
+
+@pytest.mark.asyncio
+async def test_no_retry_statuses_are_neither_retried_nor_failed_over():
+    """Test a 504 listed in no_retry_statuses is raised from the first region, while others fail over."""
+    client = UpstreamHTTPClient(
+        base_url="https://eastus.example.com/v1",
+        retry_matrix={"5xx": RetryMatrix(attempts=3, backoff="linear", base_s=0.01)},
+        failover_base_urls=["https://westeurope.example.com/v1"],
+    )
+    timeout = Mock(status_code=504, is_success=False, headers={}, request=None)
+    client.client.request = AsyncMock(return_value=timeout)
+
+    with pytest.raises(httpx.HTTPStatusError) as exc_info:
+        await client.request("POST", "/chat/completions", no_retry_statuses=(504,))
+    assert exc_info.value.response is timeout
+    assert client.client.request.await_count == 1
+
+    client.client.request.reset_mock()
+    with pytest.raises(httpx.HTTPStatusError):
+        await client.request("POST", "/chat/completions")
+    assert client.client.request.call_args.kwargs["url"].startswith("https://westeurope.")
+    await client.close()
@@ -0,0 +1,80 @@
+"""Tests for POST /replay (app/routes/replay.py)."""
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+from fastapi import HTTPException
+
+from reliapi.app.dependencies import AppState
+from reliapi.app.routes.proxy import record_request_log
+from reliapi.app.routes.replay import replay
+from reliapi.app.schemas import MetaResponse, ReplayRequest, SuccessResponse
+from reliapi.core.request_log import RequestLog
+from reliapi.core.usage import UsageStore
+
+ENTRY = {
+    "id": "rlog_1",
+    "kind": "llm",
+    "target": "openai",
+    "request": {"messages": [{"role": "user", "content": "Hello"}], "model": "gpt-4o-mini", "temperature": 0.0},
+}
+
+
+def _state(entry):
+    state = AppState()
+    state.request_log = Mock(spec=RequestLog)
+    state.request_log.get.return_value = entry
+    state.usage_store = Mock(spec=UsageStore)
+    return state
+
+
+def _result():
+    return SuccessResponse(
+        success=True,
+        data={"content": "Hi"},
+        meta=MetaResponse(request_id="req_replay", duration_ms=5, cost_usd=0.001, model="gpt-4o-mini"),
+    )
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("count_budget", [False, True])
+async def test_replay_marks_response_and_budget(count_budget):
+    """Test replays are marked and only recorded in usage when count_budget is set."""
+    state = _state(ENTRY)
+    with patch("reliapi.app.routes.replay.get_app_state", return_value=state), \
+            patch("reliapi.app.routes.replay.verify_api_key", return_value=("sk-test", "acme", "pro")), \
+            patch("reliapi.app.routes.replay.handle_llm_proxy", AsyncMock(return_value=_result())) as call:
+        response = await replay(ReplayRequest(entry_id="rlog_1", count_budget=count_budget), Mock())
+
+    assert response.status_code == 200
+    state.request_log.get.assert_called_once_with("rlog_1", tenant="acme")
+    assert call.call_args.kwargs["messages"] == ENTRY["request"]["messages"]
+    assert call.call_args.kwargs["idempotency_key"] is None
+    assert state.usage_store.record.called is count_budget
+
+
+@pytest.mark.asyncio
+async def test_replay_unknown_entry():
+    """Test replaying a missing or expired entry returns 404."""
+    with patch("reliapi.app.routes.replay.get_app_state", return_value=_state(None)), \
+            patch("reliapi.app.routes.replay.verify_api_key", return_value=("sk-test", None, "pro")):
+        with pytest.raises(HTTPException) as exc_info:
+            await replay(ReplayRequest(entry_id="rlog_missing"), Mock())
+
+    assert exc_info.value.status_code == 404
+
+
+def test_request_log_id_returned_with_response():
+    """Test the logged entry ID is set in meta and returned as a header for /replay."""
+    state = _state(None)
+    state.request_log.record.return_value = "rlog_2"
+    result = _result()
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        headers = record_request_log("req_1", "llm", "openai", ENTRY["request"], result, "acme")
+
+    assert headers == {"X-ReliAPI-Request-Log-Id": "rlog_2"}
+    assert result.meta.request_log_id == "rlog_2"
+
+    state.request_log.record.return_value = None
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        assert record_request_log("req_2", "llm", "openai", ENTRY["request"], _result(), "acme") == {}
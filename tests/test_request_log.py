@@ -0,0 +1,51 @@
+"""Tests for core/request_log.py."""
+import json
+from unittest.mock import patch
+
+from reliapi.core.request_log import RequestLog
+
+
+PAYLOAD = {
+    "messages": [{"role": "user", "content": "Hello"}],
+    "model": "gpt-4o-mini",
+    "max_tokens": 50,
+    "temperature": 0.0,
+    "top_p": None,
+    "stop": None,
+}
+
+
+@patch('reliapi.core.request_log.redis')
+def test_record_fingerprint_and_get(mock_redis_module, mock_redis, mock_redis_pipeline):
+    """Test entries store a prompt hash and params per tenant and can be fetched by ID."""
+    mock_redis.pipeline.return_value = mock_redis_pipeline
+    mock_redis_module.from_url.return_value = mock_redis
+    log = RequestLog("redis://localhost:6379/0", retention_s=600, max_entries=100)
+
+    entry_id = log.record("req_1", "llm", "openai", PAYLOAD, "success", 120,
+                          status_code=200, model="gpt-4o-mini", cost_usd=0.001, tenant="acme")
+
+    key, ttl, raw = mock_redis_pipeline.setex.call_args[0]
+    assert key == f"reliapi:tenant:acme:request_log_entry:{entry_id}"
+    assert ttl == 600
+    entry = json.loads(raw)
+    assert entry["prompt_hash"] == RequestLog.make_prompt_hash(PAYLOAD)
+    assert entry["params"] == {"model": "gpt-4o-mini", "max_tokens": 50, "temperature": 0.0, "top_p": None, "stop": None}
+    assert entry["request"] == PAYLOAD
+    mock_redis_pipeline.ltrim.assert_called_once_with("reliapi:tenant:acme:request_log_index", 0, 99)
+
+    mock_redis.lrange.return_value = [entry_id]
+    mock_redis.get.return_value = raw
+    assert log.get(entry_id, tenant="acme")["request_id"] == "req_1"
+
+    # Entries trimmed from the index are not replayable
+    mock_redis.lrange.return_value = []
+    assert log.get(entry_id, tenant="acme") is None
+
+
+def test_request_log_disabled():
+    """Test request log degrades gracefully without Redis."""
+    log = RequestLog("redis://invalid:6379/0")
+    assert log.enabled is False
+    assert log.record("req_1", "http", "my_api", {"method": "GET", "path": "/"}, "success", 10) is None
+    assert log.get("rlog_missing") is None
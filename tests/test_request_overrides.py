@@ -0,0 +1,47 @@
+"""Tests for core/request_overrides.py."""
+import pytest
+
+from reliapi.core.request_overrides import (
+    InvalidRequestOverride,
+    apply_request_overrides,
+    parse_request_overrides,
+)
+
+
+def test_overrides_are_clamped_to_ceilings():
+    """Test header values above the configured maximums are clamped."""
+    headers = {"X-ReliAPI-Timeout-Ms": "600000", "X-ReliAPI-Max-Retries": "9"}
+
+    overrides = parse_request_overrides(headers, {"max_timeout_ms": 90000, "max_retries": 3})
+
+    assert overrides == {"timeout_ms": 90000, "max_retries": 3}
+    assert parse_request_overrides({}, None) is None
+    assert parse_request_overrides(headers, {"enabled": False}) is None
+
+
+def test_no_retry_wins_and_invalid_values_rejected():
+    """Test X-ReliAPI-No-Retry disables retries and malformed values raise."""
+    headers = {"X-ReliAPI-Max-Retries": "2", "X-ReliAPI-No-Retry": "true"}
+    assert parse_request_overrides(headers) == {"max_retries": 0}
+
+    with pytest.raises(InvalidRequestOverride):
+        parse_request_overrides({"X-ReliAPI-Timeout-Ms": "fast"})
+    with pytest.raises(InvalidRequestOverride):
+        parse_request_overrides({"X-ReliAPI-Max-Retries": "-1"})
+
+
+def test_apply_overrides_sets_timeout_and_attempts():
+    """Test overrides replace timeout_ms and retry attempts without mutating the target config."""
+    target_config = {
+        "base_url": "https://api.example.com",
+        "timeout_ms": 20000,
+        "retry_matrix": {"5xx": {"attempts": 3, "backoff": "linear"}},
+    }
+
+    effective = apply_request_overrides(target_config, {"timeout_ms": 60000, "max_retries": 0})
+
+    assert effective["timeout_ms"] == 60000
+    assert effective["retry_matrix"]["5xx"] == {"attempts": 1, "backoff": "linear"}
+    assert effective["retry_matrix"]["429"] == {"attempts": 1}
+    assert target_config["timeout_ms"] == 20000
+    assert target_config["retry_matrix"]["5xx"]["attempts"] == 3
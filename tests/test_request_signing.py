@@ -0,0 +1,56 @@
+"""Tests for core/request_signing.py (target signing)."""
+import hashlib
+import hmac
+from unittest.mock import Mock, patch
+
+import pytest
+from pydantic import ValidationError
+
+from reliapi.config.schema import SigningConfig
+from reliapi.core.request_signing import RequestSigner, request_signer
+
+
+def test_signature_over_canonical_request():
+    """Test the default template signs timestamp, method, path with query and body."""
+    signer = RequestSigner("s3cret", prefix="sha256=")
+
+    headers = signer.sign("post", "/v1/orders?dry_run=1", b'{"id":1}', now=1700000000.5)
+
+    expected = hmac.new(
+        b"s3cret", b'1700000000\nPOST\n/v1/orders?dry_run=1\n{"id":1}', hashlib.sha256
+    ).hexdigest()
+    assert headers == {"X-Signature": f"sha256={expected}", "X-Timestamp": "1700000000"}
+
+
+def test_auth_flow_signs_final_request_and_custom_template():
+    """Test the auth flow adds headers from the sent request; templates, formats and encodings apply."""
+    signer = RequestSigner(
+        "k", algorithm="sha512", signature_header="X-Sig", timestamp_header=None,
+        string_to_sign="{method} {path} {body_sha256}", encoding="base64",
+    )
+    request = Mock(method="GET", content=b"", headers={})
+    request.url.raw_path = b"/items?page=2"
+
+    sent = next(signer.auth_flow(request))
+
+    assert sent is request
+    assert list(request.headers) == ["X-Sig"]
+    assert request.headers["X-Sig"] == signer.sign("GET", "/items?page=2", b"")["X-Sig"]
+    assert "X-Timestamp" not in request.headers
+    assert RequestSigner("k", timestamp_format="unix_ms").sign("GET", "/", b"", now=1.25)["X-Timestamp"] == "1250"
+
+
+def test_signer_from_config():
+    """Test signers come from target config with the secret from the environment."""
+    target = {"signing": SigningConfig(secret_env="PARTNER_SECRET").model_dump()}
+
+    with patch.dict("os.environ", {"PARTNER_SECRET": "abc"}):
+        signer = request_signer(target)
+    assert signer.secret == b"abc"
+    assert signer.timestamp_header == "X-Timestamp"
+    with patch.dict("os.environ", {}, clear=True):
+        assert request_signer(target) is None
+    assert request_signer({}) is None
+
+    with pytest.raises(ValidationError):
+        SigningConfig(secret_env="X", string_to_sign="{timestamp}{nonce}")
@@ -0,0 +1,43 @@
+"""Tests for core/response_preview.py (max_response_chars)."""
+import json
+
+from reliapi.core.response_preview import truncate_body
+
+
+def test_json_preview_stays_valid_and_within_limit():
+    """Test JSON bodies keep leading items that fit and serialize within max_chars."""
+    body = {"items": [{"id": i, "name": "x" * 30, "note": None} for i in range(50)], "total": 50}
+
+    preview, truncated = truncate_body(body, 120)
+
+    assert truncated is True
+    serialized = json.dumps(preview, separators=(",", ":"))
+    assert len(serialized) <= 120
+    assert json.loads(serialized) == preview
+    assert preview["items"][0] == {"id": 0, "name": "x" * 30, "note": None}
+    assert len(preview["items"]) < 50
+    # The original (cached) body is untouched
+    assert len(body["items"]) == 50
+
+
+def test_small_and_text_bodies():
+    """Test bodies within the limit are unchanged and text bodies are cut as text."""
+    assert truncate_body({"ok": True}, 100) == ({"ok": True}, False)
+    assert truncate_body("héllo wörld", 5) == ("héllo", True)
+    assert truncate_body("short", 5) == ("short", False)
+    preview, truncated = truncate_body(["a\nb\nc\nd"], 8)
+    assert truncated and len(json.dumps(preview, ensure_ascii=False, separators=(",", ":"))) <= 8
+
+
+def test_long_escaped_string_cut_to_longest_fitting_prefix():
+    """Test a long string of escaped characters is cut to the longest prefix that fits, quickly."""
+    body = {"log": "line\n" * 200_000}
+
+    preview, truncated = truncate_body(body, 100_000)
+
+    serialized = json.dumps(preview, ensure_ascii=False, separators=(",", ":"))
+    assert truncated is True
+    assert len(serialized) <= 100_000
+    # One more character would not fit (a "\n" is two characters of JSON)
+    assert len(serialized) >= 100_000 - 2
+    assert body["log"].startswith(preview["log"])
@@ -3,6 +3,7 @@ import asyncio
 import pytest
 
 from reliapi.core.retry import RetryEngine, RetryMatrix
+from reliapi.core.retry_budget import RetryBudget
 
 
 @pytest.mark.asyncio
@@ -169,3 +170,56 @@ async def test_retry_classifies_errors_correctly():
     assert result == "success"
     assert call_count == 2  # Initial + 1 retry (attempts=2)
 
+
+
+def test_retry_budget_allowance():
+    """Test retries are capped at ratio * requests, with a min_retries floor."""
+    budget = RetryBudget()
+    config = {"ratio": 0.1, "window_s": 60, "min_retries": 1}
+
+    # Low traffic: the floor still allows one retry
+    budget.record_request("api", config)
+    assert budget.try_acquire_retry("api", config) is True
+    assert budget.try_acquire_retry("api", config) is False
+
+    # 30 requests allow 3 retries (one already spent)
+    for _ in range(29):
+        budget.record_request("api", config)
+    assert budget.try_acquire_retry("api", config) is True
+    assert budget.try_acquire_retry("api", config) is True
+    assert budget.try_acquire_retry("api", config) is False
+
+
+def test_retry_budget_global_scope():
+    """Test the global budget applies to targets without their own budget."""
+    budget = RetryBudget()
+    budget.configure({"ratio": 0.1, "min_retries": 1})
+
+    budget.record_request("a")
+    assert budget.try_acquire_retry("a") is True
+    assert budget.try_acquire_retry("b") is False
+
+    budget.configure(None)
+    assert budget.try_acquire_retry("b") is True
+
+
+@pytest.mark.asyncio
+async def test_retry_engine_fails_fast_when_budget_exhausted():
+    """Test the engine raises without retrying once the budget is spent."""
+    budget = RetryBudget()
+    matrix = {"5xx": RetryMatrix(attempts=3, backoff="exp", base_s=0.01)}
+    engine = RetryEngine(matrix, budget=budget.scope("api", {"ratio": 0.1, "min_retries": 0}))
+
+    call_count = 0
+
+    async def failing_func():
+        nonlocal call_count
+        call_count += 1
+        error = Exception("Server error")
+        error.status_code = 503
+        raise error
+
+    with pytest.raises(Exception):
+        await engine.execute(failing_func)
+
+    assert call_count == 1
@@ -394,3 +394,37 @@ async def test_retry_after_with_key_switch():
     
     print(f"Retry-After with key switch: waited {elapsed:.2f}s, switched to {selected_key_id}")
 
+
+
+@pytest.mark.asyncio
+async def test_retry_on_provider_error_code_in_200_body():
+    """Test a 200 carrying a listed provider error code is retried like a 5xx."""
+    import json
+    from unittest.mock import AsyncMock, Mock
+
+    from reliapi.adapters.llm.anthropic import AnthropicAdapter
+    from reliapi.core.http_client import UpstreamHTTPClient
+    from reliapi.core.provider_errors import RetryableErrorCode, error_code_validator
+
+    def response(body):
+        return Mock(status_code=200, is_success=True, headers={}, content=json.dumps(body).encode())
+
+    overloaded = response({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}})
+    success = response({"content": [{"type": "text", "text": "Hi"}]})
+    client = UpstreamHTTPClient(
+        base_url="https://api.anthropic.com/v1",
+        retry_matrix={"5xx": RetryMatrix(attempts=2, backoff="linear", base_s=0.01)},
+    )
+    validator = error_code_validator(["overloaded*"], AnthropicAdapter().error_codes)
+
+    client.client.request = AsyncMock(side_effect=[overloaded, success])
+    response = await client.request("POST", "/messages", response_validator=validator)
+    assert response is success
+
+    # Once attempts are exhausted the last response is handed back for normal handling
+    client.client.request = AsyncMock(side_effect=[overloaded, overloaded])
+    with pytest.raises(RetryableErrorCode) as exc_info:
+        await client.request("POST", "/messages", response_validator=validator)
+    assert exc_info.value.code == "overloaded_error"
+    assert exc_info.value.response is overloaded
+    await client.close()
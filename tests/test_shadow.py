@@ -0,0 +1,86 @@
+"""Tests for app/shadow.py."""
+import pytest
+from unittest.mock import AsyncMock, Mock, patch
+
+from reliapi.app.schemas import ErrorDetail, ErrorResponse, MetaResponse, SuccessResponse
+from reliapi.app.shadow import _shadow_targets, compare_responses, run_llm_shadow, select_shadow_target
+
+
+def _llm_success(content, cost_usd, model="gpt-4o-mini", duration_ms=100):
+    return SuccessResponse(
+        data={"content": content, "model": model},
+        meta=MetaResponse(model=model, cost_usd=cost_usd, duration_ms=duration_ms, request_id="req_1"),
+    )
+
+
+def _error():
+    return ErrorResponse(
+        error=ErrorDetail(type="upstream_error", code="SERVER_ERROR", message="boom", retryable=True),
+        meta=MetaResponse(duration_ms=50, request_id="req_1_shadow"),
+    )
+
+
+def test_compare_llm_responses():
+    """Test content match, similarity and cost are reported."""
+    primary = _llm_success("Hello world", 0.002)
+    shadow = _llm_success("Hello world!", 0.001, model="claude-3-haiku")
+
+    comparison = compare_responses("llm", primary, shadow)
+
+    assert comparison["match"] is False
+    assert 0.9 < comparison["similarity"] < 1.0
+    assert comparison["primary_cost_usd"] == 0.002
+    assert comparison["shadow_cost_usd"] == 0.001
+    assert comparison["shadow_model"] == "claude-3-haiku"
+
+
+def test_compare_with_shadow_error():
+    """Test shadow failure leaves match undetermined."""
+    comparison = compare_responses("llm", _llm_success("Hi", 0.001), _error())
+    assert comparison["match"] is None
+    assert comparison["shadow_success"] is False
+    assert comparison["shadow_error_code"] == "SERVER_ERROR"
+
+
+def test_select_shadow_target_sampling():
+    """Test sample_rate controls mirroring."""
+    assert select_shadow_target({}) is None
+    assert select_shadow_target({"shadow": {"target": "b", "sample_rate": 0.0}}) is None
+    assert select_shadow_target({"shadow": {"target": "b", "sample_rate": 1.0}})["target"] == "b"
+
+
+def test_shadow_targets_disable_cache():
+    """Test shadow calls never use or populate the cache."""
+    targets = {"candidate": {"base_url": "https://b", "cache": {"enabled": True}, "shadow": {"target": "x"}}}
+    shadow_targets = _shadow_targets(targets, "candidate")
+    assert shadow_targets["candidate"]["cache"] == {"enabled": False}
+    assert "shadow" not in shadow_targets["candidate"]
+    # Original config untouched
+    assert targets["candidate"]["cache"] == {"enabled": True}
+
+
+@pytest.mark.asyncio
+async def test_run_llm_shadow_uses_shadow_target():
+    """Test the shadow request goes to the shadow target without idempotency."""
+    shadow_result = _llm_success("Hello", 0.001, model="claude-3-haiku")
+    with patch("reliapi.app.shadow.handle_llm_proxy", new=AsyncMock(return_value=shadow_result)) as mock_proxy:
+        comparison = await run_llm_shadow(
+            primary=_llm_success("Hello", 0.002),
+            shadow_config={"target": "anthropic"},
+            target_name="openai",
+            messages=[{"role": "user", "content": "Hi"}],
+            max_tokens=None,
+            temperature=None,
+            top_p=None,
+            stop=None,
+            targets={"openai": {"base_url": "https://a"}, "anthropic": {"base_url": "https://b"}},
+            cache=Mock(),
+            idempotency=Mock(),
+            request_id="req_1",
+        )
+
+    kwargs = mock_proxy.call_args.kwargs
+    assert kwargs["target_name"] == "anthropic"
+    assert kwargs["idempotency_key"] is None
+    assert kwargs["request_id"] == "req_1_shadow"
+    assert comparison["match"] is True
@@ -0,0 +1,53 @@
+"""Tests for SSE heartbeats on streamed responses (core/sse_heartbeat.py)."""
+import asyncio
+
+import pytest
+
+from reliapi.app.openai_compat import to_openai_stream
+from reliapi.core.sse_heartbeat import HEARTBEAT, with_heartbeats
+
+
+async def _slow_events(closed):
+    try:
+        yield 'event: meta\ndata: {"model": "gpt-4o-mini"}\n\n'
+        await asyncio.sleep(0.12)
+        yield 'event: chunk\ndata: {"delta": "Hi"}\n\n'
+        yield 'event: done\ndata: {"finish_reason": "stop"}\n\n'
+    finally:
+        closed.append(True)
+
+
+@pytest.mark.asyncio
+async def test_heartbeats_fill_idle_gaps_between_events():
+    """Test heartbeats are sent only while no event arrives and events pass through unchanged."""
+    closed = []
+    events = [event async for event in with_heartbeats(_slow_events(closed), 0.05)]
+
+    assert events[0].startswith("event: meta")
+    assert events[-2:] == [
+        'event: chunk\ndata: {"delta": "Hi"}\n\n',
+        'event: done\ndata: {"finish_reason": "stop"}\n\n',
+    ]
+    assert events[1:-2] and set(events[1:-2]) == {HEARTBEAT}
+    assert closed == [True]
+
+    unchanged = _slow_events([])
+    assert with_heartbeats(unchanged, None) is unchanged
+
+
+@pytest.mark.asyncio
+async def test_heartbeats_closed_stream_and_openai_passthrough():
+    """Test closing the wrapper stops the inner stream, and OpenAI streams keep heartbeats."""
+    closed = []
+    stream = with_heartbeats(_slow_events(closed), 0.05)
+    assert (await stream.__anext__()).startswith("event: meta")
+    assert await stream.__anext__() == HEARTBEAT
+    await stream.aclose()
+    await asyncio.sleep(0)
+    assert closed == [True]
+
+    openai_events = [
+        event async for event in to_openai_stream(with_heartbeats(_slow_events([]), 0.05), "req_1", None)
+    ]
+    assert HEARTBEAT in openai_events
+    assert openai_events[-1] == "data: [DONE]\n\n"
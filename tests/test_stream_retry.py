@@ -0,0 +1,83 @@
+"""Tests for replay-safe stream retries (core/stream_retry.py)."""
+import httpx
+import pytest
+
+from reliapi.core.stream_retry import replay_safe_stream
+
+
+def _chunk(text=None, finish_reason=None):
+    delta = {"content": text} if text is not None else {}
+    return {"object": "chat.completion.chunk", "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}]}
+
+
+def _attempts(*scripts):
+    """open_stream replaying one script per attempt; an exception entry fails that attempt."""
+    calls = iter(scripts)
+
+    def open_stream():
+        async def stream():
+            for item in next(calls):
+                if isinstance(item, Exception):
+                    raise item
+                yield item
+        return stream()
+    return open_stream
+
+
+async def _text(stream):
+    return "".join([
+        chunk["choices"][0]["delta"].get("content") or "" async for chunk in stream if chunk.get("choices")
+    ])
+
+
+def _retryable(error):
+    return isinstance(error, httpx.RequestError)
+
+
+@pytest.mark.asyncio
+async def test_failure_before_output_retried_transparently():
+    """Test a stream that fails before any text is reopened from scratch."""
+    stats = {}
+    open_stream = _attempts(
+        [_chunk(""), httpx.RequestError("reset")],
+        [_chunk("Hello"), _chunk(" world", "stop")],
+    )
+
+    text = await _text(replay_safe_stream(open_stream, {"backoff_ms": 0}, _retryable, stats))
+
+    assert text == "Hello world"
+    assert stats == {"retries": 1}
+
+
+@pytest.mark.asyncio
+async def test_partial_output_restarted_and_deduplicated():
+    """Test on_partial restart skips the text already sent and forwards only the continuation."""
+    stats = {}
+    config = {"on_partial": "restart", "backoff_ms": 0}
+    open_stream = _attempts(
+        [_chunk("The quick"), httpx.RequestError("reset")],
+        [_chunk("The"), _chunk(" quick bro"), _chunk("wn fox", "stop")],
+    )
+
+    chunks = [chunk async for chunk in replay_safe_stream(open_stream, config, _retryable, stats)]
+
+    assert [c["choices"][0]["delta"].get("content") for c in chunks] == ["The quick", " bro", "wn fox"]
+    assert chunks[-1]["choices"][0]["finish_reason"] == "stop"
+    assert stats == {"retries": 1, "restarted": True}
+
+
+@pytest.mark.asyncio
+async def test_partial_output_aborts_by_default_and_divergence_raises():
+    """Test partial output is not retried with on_partial abort, and a diverging restart fails."""
+    abort = _attempts([_chunk("Partial"), httpx.RequestError("reset")])
+    with pytest.raises(httpx.RequestError):
+        await _text(replay_safe_stream(abort, {"backoff_ms": 0}, _retryable, {}))
+
+    stats = {}
+    diverging = _attempts(
+        [_chunk("Blue"), httpx.RequestError("reset")],
+        [_chunk("Green sky")],
+    )
+    with pytest.raises(httpx.RequestError):
+        await _text(replay_safe_stream(diverging, {"on_partial": "restart", "backoff_ms": 0}, _retryable, stats))
+    assert stats["diverged"] is True
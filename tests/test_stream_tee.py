@@ -0,0 +1,73 @@
+"""Tests for the stream tee (core/stream_tee.py) and teed LLM streams."""
+import json
+from unittest.mock import AsyncMock, Mock, patch
+
+import pytest
+
+from reliapi.app.dependencies import AppState
+from reliapi.app.routes.proxy import _tee_stream
+from reliapi.core.stream_tee import REDACTED, StreamTee
+
+
+async def _events():
+    yield 'event: meta\ndata: {"model": "gpt-4o-mini", "provider": "openai"}\n\n'
+    yield 'event: chunk\ndata: {"delta": "Mail bob@example.com ", "finish_reason": null}\n\n'
+    yield 'event: chunk\ndata: {"delta": "or call 555 0100 2233", "finish_reason": null}\n\n'
+    yield 'event: done\ndata: {"finish_reason": "stop", "cost_usd": 0.001, "usage": {"total_tokens": 9}}\n\n'
+
+
+@pytest.mark.asyncio
+async def test_completed_stream_written_redacted_to_file(tmp_path):
+    """Test a teed stream reaches the client unchanged and its redacted record lands in the file."""
+    path = tmp_path / "streams.jsonl"
+    state = AppState(stream_tee=StreamTee(sink="file", path=str(path), include_messages=True))
+    messages = [{"role": "user", "content": "I am alice@example.com"}]
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        relayed = [e async for e in _tee_stream(_events(), "openai", "acme", "req_1", messages, None)]
+    await state.stream_tee.close()
+
+    assert relayed == [e async for e in _events()]
+    record = json.loads(path.read_text())
+    assert record["content"] == f"Mail {REDACTED} or call {REDACTED}"
+    assert record["messages"] == [{"role": "user", "content": f"I am {REDACTED}"}]
+    assert record["model"] == "gpt-4o-mini"
+    assert record["tenant"] == "acme"
+    assert record["cost_usd"] == 0.001
+    assert record["usage"] == {"total_tokens": 9}
+
+
+@pytest.mark.asyncio
+async def test_tee_drops_when_full_and_survives_sink_failures():
+    """Test a full queue drops records, and a failing webhook or broken tee never raises."""
+    tee = StreamTee(sink="webhook", url="https://analytics.example.com/streams", max_queue=1)
+    with patch("reliapi.core.stream_tee.httpx.AsyncClient") as client_cls:
+        client = client_cls.return_value.__aenter__.return_value
+        client.post = AsyncMock(side_effect=ConnectionError("down"))
+        assert tee.submit("first", request_id="req_1") is True
+        assert tee.submit("second", request_id="req_2") is False
+        await tee.close()
+    client.post.assert_awaited_once()
+    assert "messages" not in client.post.call_args.kwargs["json"]
+
+    broken = AppState(stream_tee=Mock(submit=Mock(side_effect=RuntimeError("boom"))))
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=broken):
+        relayed = [e async for e in _tee_stream(_events(), "openai", None, "req_3", [], None)]
+    assert len(relayed) == 4
+
+
+@pytest.mark.asyncio
+async def test_worker_redacts_and_usage_sink_keeps_records():
+    """Test submit queues the raw record, and the worker redacts it before it reaches the usage sink."""
+    usage_store = Mock()
+    tee = StreamTee(sink="usage", usage_store=usage_store, include_messages=True)
+    tee.redact = Mock(side_effect=tee.redact)
+
+    assert tee.submit("Mail bob@example.com", messages=[{"role": "user", "content": "hi"}], tenant="acme")
+    tee.redact.assert_not_called()
+    await tee.close()
+
+    record = usage_store.record_stream.call_args.args[0]
+    assert record["content"] == f"Mail {REDACTED}"
+    assert record["messages"] == [{"role": "user", "content": "hi"}]
+    assert record["tenant"] == "acme"
@@ -0,0 +1,97 @@
+"""Tests for per-tenant rate limits and budgets (core/tenant_limits.py)."""
+import json
+import time
+from datetime import datetime, timezone
+from unittest.mock import Mock, patch
+
+import pytest
+
+from reliapi.app.routes.proxy import rate_limit_headers
+from reliapi.core.tenant_limits import TenantLimits, budget_period
+from reliapi.core.usage import UsageStore
+
+
+def test_rate_limit_counts_requests_per_minute():
+    """Test requests past rate_limit_rpm are rejected until the minute ends, and headers track it."""
+    limits = TenantLimits()
+    config = {"rate_limit_rpm": 2}
+
+    assert limits.acquire("acme", config) is None
+    assert limits.headers("acme", config)["X-RateLimit-Remaining"] == "1"
+    assert limits.acquire("acme", config) is None
+
+    limit, retry_after_s = limits.acquire("acme", config)
+    headers = limits.headers("acme", config)
+
+    assert limit == "rate_limit"
+    assert 0 < retry_after_s <= 60
+    assert headers["X-RateLimit-Limit"] == "2"
+    assert headers["X-RateLimit-Remaining"] == "0"
+    assert int(headers["X-RateLimit-Reset"]) % 60 == 0
+    assert int(headers["X-RateLimit-Reset"]) - time.time() == pytest.approx(retry_after_s, abs=1)
+    # Other tenants and tenants without limits are unaffected
+    assert limits.acquire("other", config) is None
+    assert limits.headers("acme", {}) == {}
+
+
+@patch('reliapi.core.usage.redis')
+def test_budget_from_cached_spend(mock_redis_module, mock_redis, mock_redis_pipeline):
+    """Test spend is read from usage records once, then kept current by new records."""
+    mock_redis.pipeline.return_value = mock_redis_pipeline
+    mock_redis_module.from_url.return_value = mock_redis
+    now = time.time()
+    mock_redis.lrange.return_value = [
+        json.dumps({"ts": now - 5, "cost_usd": 0.6}),
+        json.dumps({"ts": 0, "cost_usd": 100.0}),  # Before this period
+    ]
+    store = UsageStore("redis://localhost:6379/0")
+    limits = TenantLimits(store)
+    config = {"budget": {"limit_usd": 1.0, "period": "day"}}
+
+    assert limits.headers("acme", config)["X-Budget-Remaining-Usd"] == "0.400000"
+    assert limits.acquire("acme", config) is None
+
+    store.record("req_1", "llm", "openai", "success", 100, cost_usd=0.5, tenant="acme")
+    headers = limits.headers("acme", config)
+    limit, retry_after_s = limits.acquire("acme", config)
+
+    assert headers["X-Budget-Remaining-Usd"] == "0.000000"
+    assert headers["X-Budget-Reset"] == str(budget_period("day")[1])
+    assert limit == "budget"
+    assert 0 < retry_after_s <= 86400
+    assert mock_redis.lrange.call_count == 1
+
+
+def test_budget_period_boundaries():
+    """Test day and month periods start and end at UTC calendar boundaries."""
+    ts = datetime(2024, 12, 31, 18, 30, tzinfo=timezone.utc).timestamp()
+
+    day_start, day_end = budget_period("day", ts)
+    month_start, month_end = budget_period("month", ts)
+
+    assert day_start == datetime(2024, 12, 31, tzinfo=timezone.utc).timestamp()
+    assert day_end == int(datetime(2025, 1, 1, tzinfo=timezone.utc).timestamp())
+    assert month_start == datetime(2024, 12, 1, tzinfo=timezone.utc).timestamp()
+    assert month_end == day_end
+
+
+def test_free_tier_keys_without_rpm_get_per_ip_headers():
+    """Test a free-tier caller without rate_limit_rpm is told its per-IP limit, and a tenant rpm wins."""
+    limits = TenantLimits()
+    rate_limiter = Mock()
+    rate_limiter.peek_ip_rate_limit.return_value = (7, 1700000060)
+    state = Mock(tenant_limits=limits, rate_limiter=rate_limiter)
+    state.config_loader.get_tenant.return_value = {}
+    http_request = Mock()
+    http_request.client.host = "10.0.0.1"
+
+    with patch("reliapi.app.routes.proxy.get_app_state", return_value=state):
+        free = rate_limit_headers(None, http_request, "free")
+        paid = rate_limit_headers(None, http_request, "pro")
+        state.config_loader.get_tenant.return_value = {"rate_limit_rpm": 100}
+        tenant = rate_limit_headers("acme", http_request, "free")
+
+    assert free == {"X-RateLimit-Limit": "20", "X-RateLimit-Remaining": "7", "X-RateLimit-Reset": "1700000060"}
+    rate_limiter.peek_ip_rate_limit.assert_called_once_with("10.0.0.1", limit_per_minute=20)
+    assert paid == {}
+    assert tenant["X-RateLimit-Limit"] == "100"
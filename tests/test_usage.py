@@ -0,0 +1,118 @@
+"""Tests for core/usage.py and request tags."""
+import json
+import pytest
+from unittest.mock import Mock, patch
+
+from pydantic import ValidationError
+
+from reliapi.app.schemas import MAX_METADATA_BYTES, MAX_TAGS, LLMProxyRequest
+from reliapi.core.usage import UNTAGGED_GROUP, UsageStore
+
+
+RECORDS = [
+    {"ts": 100, "target": "openai", "model": "gpt-4o-mini", "kind": "llm", "status": "success",
+     "cost_usd": 0.02, "tags": {"team": "growth"}},
+    {"ts": 200, "target": "openai", "model": "gpt-4o", "kind": "llm", "status": "success",
+     "cost_usd": 0.10, "tags": {"team": "search"}},
+    {"ts": 300, "target": "anthropic", "model": "claude-3-haiku", "kind": "llm", "status": "error",
+     "cost_usd": 0.0, "tags": {"team": "growth"}},
+    {"ts": 400, "target": "my_api", "model": None, "kind": "http", "status": "success",
+     "cost_usd": 0.0, "tags": {}},
+]
+
+
+def test_aggregate_by_tag():
+    """Test cost breakdown by tag, with untagged bucket."""
+    groups = UsageStore.aggregate(RECORDS, "tag:team")
+    by_name = {g["group"]: g for g in groups}
+
+    assert by_name["search"]["cost_usd"] == pytest.approx(0.10)
+    assert by_name["growth"]["requests"] == 2
+    assert by_name["growth"]["errors"] == 1
+    assert by_name[UNTAGGED_GROUP]["requests"] == 1
+    # Sorted by cost descending
+    assert groups[0]["group"] == "search"
+
+
+def test_aggregate_by_target_and_total():
+    """Test built-in dimensions and ungrouped total."""
+    by_target = {g["group"]: g for g in UsageStore.aggregate(RECORDS, "target")}
+    assert by_target["openai"]["requests"] == 2
+
+    total = UsageStore.aggregate(RECORDS)
+    assert len(total) == 1
+    assert total[0]["requests"] == 4
+    assert total[0]["cost_usd"] == pytest.approx(0.12)
+
+
+def test_aggregate_raw_cost():
+    """Test raw_cost_usd is summed separately, defaulting to cost_usd for older records."""
+    records = [
+        {"ts": 100, "target": "openai", "status": "success", "cost_usd": 0.03, "raw_cost_usd": 0.02},
+        {"ts": 200, "target": "openai", "status": "success", "cost_usd": 0.01},
+    ]
+    total = UsageStore.aggregate(records)[0]
+
+    assert total["cost_usd"] == pytest.approx(0.04)
+    assert total["raw_cost_usd"] == pytest.approx(0.03)
+
+
+def test_aggregate_invalid_group_by():
+    """Test unsupported group_by is rejected."""
+    with pytest.raises(ValueError):
+        UsageStore.aggregate(RECORDS, "api_key")
+    with pytest.raises(ValueError):
+        UsageStore.aggregate(RECORDS, "tag:")
+
+
+@patch('reliapi.core.usage.redis')
+def test_record_and_query(mock_redis_module, mock_redis, mock_redis_pipeline):
+    """Test records are pushed per tenant and filtered by time range."""
+    mock_redis.pipeline.return_value = mock_redis_pipeline
+    mock_redis_module.from_url.return_value = mock_redis
+    store = UsageStore("redis://localhost:6379/0")
+
+    store.record("req_1", "llm", "openai", "success", 120, model="gpt-4o-mini",
+                 cost_usd=0.01, tags={"team": "growth"}, tenant="acme")
+    key = mock_redis_pipeline.lpush.call_args[0][0]
+    assert key == "reliapi:tenant:acme:usage_records"
+    stored = json.loads(mock_redis_pipeline.lpush.call_args[0][1])
+    assert stored["tags"] == {"team": "growth"}
+
+    mock_redis.lrange.return_value = [json.dumps(r) for r in RECORDS]
+    records = store.query(tenant="acme", since=150, until=350)
+    assert [r["ts"] for r in records] == [200, 300]
+
+
+def test_usage_store_disabled():
+    """Test usage store degrades gracefully without Redis."""
+    store = UsageStore("redis://invalid:6379/0")
+    assert store.enabled is False
+    store.record("req_1", "http", "my_api", "success", 10)
+    assert store.query() == []
+
+
+def test_request_tags_limits():
+    """Test tag count and size limits on requests."""
+    messages = [{"role": "user", "content": "Hello"}]
+    request = LLMProxyRequest(target="openai", messages=messages, tags={"team": "growth"})
+    assert request.tags == {"team": "growth"}
+
+    with pytest.raises(ValidationError):
+        LLMProxyRequest(
+            target="openai",
+            messages=messages,
+            tags={f"k{i}": "v" for i in range(MAX_TAGS + 1)},
+        )
+    with pytest.raises(ValidationError):
+        LLMProxyRequest(target="openai", messages=messages, tags={"team": "x" * 1000})
+
+
+def test_request_metadata_size_limit():
+    """Test metadata accepts any JSON up to MAX_METADATA_BYTES serialized."""
+    messages = [{"role": "user", "content": "Hello"}]
+    metadata = {"job_id": "j-42", "attempt": 2, "trace": ["a", "b"]}
+    assert LLMProxyRequest(target="openai", messages=messages, metadata=metadata).metadata == metadata
+
+    with pytest.raises(ValidationError):
+        LLMProxyRequest(target="openai", messages=messages, metadata={"blob": "x" * MAX_METADATA_BYTES})
@@ -0,0 +1,85 @@
+"""Tests for core/usage_export.py (usage_export webhook)."""
+import asyncio
+import hashlib
+import hmac
+import json
+from unittest.mock import AsyncMock, Mock, patch
+
+import httpx
+import pytest
+
+from reliapi.core.stream_tee import REDACTED
+from reliapi.core.usage import UsageStore
+from reliapi.core.usage_export import SIGNATURE_HEADER, TIMESTAMP_HEADER, UsageExporter
+
+
+@pytest.mark.asyncio
+async def test_usage_records_batched_signed_and_redacted():
+    """Test usage store records reach the webhook in one signed batch, with redacted fields."""
+    exporter = UsageExporter(
+        "https://analytics.example.com/usage", secret="s3cret", batch_size=2, redact=["tenant"],
+    )
+    with patch("reliapi.core.usage.redis") as mock_redis:
+        mock_redis.from_url.return_value.ping.side_effect = ConnectionError("no redis")
+        store = UsageStore("redis://localhost:6379/0")
+    store.exporter = exporter
+
+    with patch("reliapi.core.usage_export.httpx.AsyncClient") as client_cls:
+        client = client_cls.return_value.__aenter__.return_value
+        client.post = AsyncMock(return_value=Mock(is_success=True, status_code=200))
+        store.record("req_1", "llm", "openai", "success", 120, model="gpt-4o-mini", cost_usd=0.002,
+                     tags={"owner": "bob@example.com"}, tenant="acme", prompt_tokens=90, completion_tokens=12)
+        store.record("req_2", "http", "catalog", "error", 40, tenant="acme")
+        await exporter.close()
+
+    client.post.assert_awaited_once()
+    kwargs = client.post.call_args.kwargs
+    records = json.loads(kwargs["content"])["records"]
+    assert [r["request_id"] for r in records] == ["req_1", "req_2"]
+    assert records[0]["prompt_tokens"] == 90 and records[0]["completion_tokens"] == 12
+    assert records[0]["tags"] == {"owner": REDACTED}
+    assert records[0]["tenant"] == REDACTED
+    timestamp = kwargs["headers"][TIMESTAMP_HEADER]
+    expected = hmac.new(b"s3cret", f"{timestamp}.".encode() + kwargs["content"], hashlib.sha256).hexdigest()
+    assert kwargs["headers"][SIGNATURE_HEADER] == f"sha256={expected}"
+
+
+@pytest.mark.asyncio
+async def test_usage_export_retries_transient_failures_and_drops_when_full():
+    """Test 5xx and connection errors are retried with backoff, 4xx is not, and a full queue drops."""
+    exporter = UsageExporter("https://analytics.example.com/usage", attempts=3, backoff_ms=0, max_queue=1)
+    client = Mock()
+    client.post = AsyncMock(side_effect=[
+        httpx.ConnectError("down"), Mock(is_success=False, status_code=503), Mock(is_success=True, status_code=200),
+    ])
+    assert await exporter._deliver(client, [{"request_id": "req_1"}]) is True
+    assert client.post.await_count == 3
+
+    client.post = AsyncMock(return_value=Mock(is_success=False, status_code=400))
+    assert await exporter._deliver(client, [{"request_id": "req_2"}]) is False
+    client.post.assert_awaited_once()
+    assert SIGNATURE_HEADER not in client.post.call_args.kwargs["headers"]
+
+    with patch.object(exporter, "_run", new=AsyncMock()):
+        assert exporter.submit({"request_id": "req_3"}) is True
+        assert exporter.submit({"request_id": "req_4"}) is False
+
+
+@pytest.mark.asyncio
+async def test_close_gives_up_after_close_timeout_and_counts_the_rest_dropped():
+    """Test shutdown waits at most close_timeout_s for a stuck webhook, then drops the queued records."""
+    exporter = UsageExporter("https://analytics.example.com/usage", batch_size=1, close_timeout_s=0.05)
+
+    async def stuck(*args, **kwargs):
+        await asyncio.sleep(60)
+
+    with patch("reliapi.core.usage_export.httpx.AsyncClient") as client_cls, \
+            patch("reliapi.core.usage_export.usage_export_records_total") as records_total:
+        client_cls.return_value.__aenter__.return_value.post = AsyncMock(side_effect=stuck)
+        for n in range(3):
+            exporter.submit({"request_id": f"req_{n}"})
+        await asyncio.wait_for(exporter.close(), 1)
+
+    # One record was mid-delivery, two still queued
+    records_total.labels.assert_called_with(outcome="dropped")
+    records_total.labels.return_value.inc.assert_called_once_with(3)
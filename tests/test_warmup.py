@@ -0,0 +1,50 @@
+"""Tests for app/warmup.py."""
+from unittest.mock import AsyncMock, Mock, patch
+
+import httpx
+import pytest
+
+from reliapi.app.warmup import check_target, run_warmup
+
+
+@pytest.mark.asyncio
+async def test_warmup_reports_per_target_status():
+    """Test LLM targets are checked via /models, rejected keys fail and plain HTTP targets are skipped."""
+    targets = {
+        "openai": {"base_url": "https://api.openai.com/v1", "llm": {"provider": "openai"}},
+        "mistral": {"base_url": "https://api.mistral.ai/v1", "llm": {"provider": "mistral"}},
+        "inventory": {"base_url": "https://inventory.example.com"},
+    }
+    unauthorized = Mock(status_code=401)
+
+    async def fake_request(method, path):
+        assert (method, path) == ("GET", "/models")
+        if client_cls.call_args.kwargs["base_url"].endswith("mistral.ai/v1"):
+            raise httpx.HTTPStatusError("Unauthorized", request=Mock(), response=unauthorized)
+        return Mock(status_code=200)
+
+    with patch("reliapi.app.warmup.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(side_effect=fake_request)
+        client_cls.return_value.close = AsyncMock()
+        report = {}
+        for name, config in targets.items():
+            report.update(await run_warmup({name: config}))
+
+    assert report["openai"]["status"] == "ok"
+    assert report["mistral"]["status"] == "failed"
+    assert report["mistral"]["error"] == "Credentials rejected (HTTP 401)"
+    assert report["inventory"] == {"status": "skipped"}
+
+
+@pytest.mark.asyncio
+async def test_check_target_unreachable():
+    """Test a connection error reports the target as unreachable."""
+    with patch("reliapi.app.warmup.UpstreamHTTPClient") as client_cls:
+        client_cls.return_value.request = AsyncMock(side_effect=httpx.ConnectError("Connection refused"))
+        client_cls.return_value.close = AsyncMock()
+        result = await check_target("inventory", {"base_url": "https://inventory.example.com"}, path="/")
+
+    assert client_cls.return_value.request.call_args.args == ("GET", "/")
+    assert result["status"] == "failed"
+    assert result["reachable"] is False
+    assert result["error"] == "Connection refused"